@@ -76,6 +76,7 @@ export default withMermaid(defineConfig({
               items: [
                 { text: 'kimi 命令', link: '/zh/reference/kimi-command' },
                 { text: 'kimi info 子命令', link: '/zh/reference/kimi-info' },
+                { text: 'kimi config 子命令', link: '/zh/reference/kimi-config' },
                 { text: 'kimi acp 子命令', link: '/zh/reference/kimi-acp' },
                 { text: 'kimi mcp 子命令', link: '/zh/reference/kimi-mcp' },
                 { text: 'kimi term 子命令', link: '/zh/reference/kimi-term' },
@@ -159,6 +160,7 @@ export default withMermaid(defineConfig({
               items: [
                 { text: 'kimi Command', link: '/en/reference/kimi-command' },
                 { text: 'kimi info Subcommand', link: '/en/reference/kimi-info' },
+                { text: 'kimi config Subcommand', link: '/en/reference/kimi-config' },
                 { text: 'kimi acp Subcommand', link: '/en/reference/kimi-acp' },
                 { text: 'kimi mcp Subcommand', link: '/en/reference/kimi-mcp' },
                 { text: 'kimi term Subcommand', link: '/en/reference/kimi-term' },
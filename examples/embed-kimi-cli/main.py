@@ -0,0 +1,82 @@
+import asyncio
+import json
+import tempfile
+from pathlib import Path
+
+from kosong.tooling import CallableTool2, ToolOk, ToolReturnValue
+from pydantic import BaseModel, Field, SecretStr
+
+from kimi_cli.builder import KimiCLIBuilder
+from kimi_cli.config import Config, LLMModel, LLMProvider
+from kimi_cli.soul.observer import SoulObserver
+from kimi_cli.wire.types import TextPart, ToolCall
+
+# The scripted echo provider answers each request with the next script: first a call of the
+# custom tool, then the final answer.
+SCRIPTS = [
+    'tool_call: {"id": "call-1", "name": "Greet", "arguments": "{\\"name\\": \\"Kimi\\"}"}',
+    "text: The Greet tool says: Hello, Kimi!",
+]
+
+
+class GreetParams(BaseModel):
+    name: str = Field(description="The name of the person to greet.")
+
+
+class Greet(CallableTool2[GreetParams]):
+    name: str = "Greet"
+    description: str = "Greet a person by name."
+    params: type[GreetParams] = GreetParams
+
+    async def __call__(self, params: GreetParams) -> ToolReturnValue:
+        return ToolOk(output=f"Hello, {params.name}!")
+
+
+class ToolCallPrinter(SoulObserver):
+    def on_tool_call(self, tool_call: ToolCall) -> None:
+        print(f"[calling {tool_call.function.name}]")
+
+
+def echo_config(scripts_file: Path) -> Config:
+    return Config(
+        default_model="echo",
+        models={"echo": LLMModel(provider="echo", model="echo", max_context_size=100_000)},
+        providers={
+            "echo": LLMProvider(
+                type="_scripted_echo",
+                base_url="",
+                api_key=SecretStr(""),
+                env={"KIMI_SCRIPTED_ECHO_SCRIPTS": str(scripts_file)},
+            )
+        },
+        telemetry=False,
+    )
+
+
+async def main(work_dir: Path | None = None) -> str:
+    with tempfile.TemporaryDirectory() as tmp:
+        scripts_file = Path(tmp) / "scripts.json"
+        scripts_file.write_text(json.dumps(SCRIPTS), encoding="utf-8")
+
+        instance = await (
+            KimiCLIBuilder()
+            .work_dir(work_dir or Path.cwd())
+            .config(echo_config(scripts_file))
+            .add_tool(Greet())
+            .add_observer(ToolCallPrinter())
+            .loop_limits(max_steps_per_turn=5)
+            .ui_mode("wire")
+            .build()
+        )
+
+        answer = ""
+        async for msg in instance.run("Greet Kimi.", asyncio.Event(), merge_wire_messages=True):
+            if isinstance(msg, TextPart):
+                answer += msg.text
+        await instance.shutdown_background_tasks()
+    print(answer)
+    return answer
+
+
+if __name__ == "__main__":
+    asyncio.run(main())
@@ -1,20 +1,29 @@
 from __future__ import annotations
 
+import asyncio
+import codecs
 import contextvars
-from collections.abc import AsyncGenerator, AsyncIterator, Iterable, Mapping
+from collections.abc import (
+    AsyncGenerator,
+    AsyncIterable,
+    AsyncIterator,
+    Callable,
+    Iterable,
+    Mapping,
+)
 from dataclasses import dataclass
 from pathlib import PurePath
 from typing import TYPE_CHECKING, Literal, Protocol, runtime_checkable
 
 if TYPE_CHECKING:
-    from asyncio import StreamReader, StreamWriter
+    from asyncio import StreamWriter
 
     from asyncssh.stream import SSHReader, SSHWriter
 
     from kaos.path import KaosPath
 
     def type_check(
-        stream_reader: StreamReader,
+        stream_reader: asyncio.StreamReader,
         stream_writer: StreamWriter,
         ssh_reader: SSHReader[bytes],
         ssh_writer: SSHWriter[bytes],
@@ -28,6 +37,100 @@ if TYPE_CHECKING:
 type StrOrKaosPath = str | KaosPath
 
 
+SNIFF_BYTES = 512
+"""Number of leading bytes inspected to tell binary files from text files."""
+
+
+class BinaryFileError(ValueError):
+    """Raised when reading a file as text that looks like a binary file."""
+
+    def __init__(self, path: StrOrKaosPath) -> None:
+        super().__init__(
+            f"`{path}` appears to be a binary file (it contains NUL bytes); "
+            "use `read_bytes` to read it."
+        )
+        self.path = str(path)
+
+
+def is_binary(header: bytes | str) -> bool:
+    """Whether the leading bytes (or characters) of a file look like binary content."""
+    # NUL bytes are a strong signal of binary content.
+    if isinstance(header, str):
+        return "\x00" in header[:SNIFF_BYTES]
+    return b"\x00" in header[:SNIFF_BYTES]
+
+
+def ensure_text(path: StrOrKaosPath, header: bytes | str) -> None:
+    """Raise `BinaryFileError` if the leading bytes of the file look like binary content."""
+    if is_binary(header):
+        raise BinaryFileError(path)
+
+
+MAX_LINE_BYTES = 1024 * 1024
+"""Default number of bytes `readlines` keeps of a single line; the rest is dropped."""
+READ_CHUNK_BYTES = 64 * 1024
+"""Number of bytes read at a time when streaming the lines of a file."""
+
+
+async def split_lines(
+    chunks: AsyncIterable[bytes],
+    *,
+    encoding: str = "utf-8",
+    errors: Literal["strict", "ignore", "replace"] = "strict",
+    max_line_bytes: int | None = MAX_LINE_BYTES,
+) -> AsyncGenerator[str]:
+    """
+    Split a stream of bytes into decoded lines, the way `readlines` yields them.
+
+    Lines end with LF, with CRLF line breaks normalized to LF; only the last line may have no
+    line break. Each line is decoded on its own, so that with `errors="replace"` an invalid byte
+    sequence only affects its line. Of a line longer than `max_line_bytes`, only the first
+    `max_line_bytes` bytes are kept, followed by a `[... N bytes truncated]` marker, so that
+    memory stays bounded however long the line is. The encoding must be ASCII-compatible.
+    """
+    line = bytearray()
+    skipped = 0
+    skipped_cr = False  # whether the last dropped byte of a truncated line is `\r`
+
+    def finish(newline: bool) -> str:
+        nonlocal skipped, skipped_cr
+        if newline and skipped_cr:
+            skipped -= 1
+        if skipped:
+            # An incomplete multibyte character cut at the end of the kept bytes is dropped too
+            decoder = codecs.getincrementaldecoder(encoding)(errors)
+            text = decoder.decode(bytes(line))
+            text += f"[... {skipped + len(decoder.getstate()[0])} bytes truncated]"
+        else:
+            if newline and line.endswith(b"\r"):
+                del line[-1]
+            text = line.decode(encoding, errors)
+        line.clear()
+        skipped = 0
+        skipped_cr = False
+        return text + "\n" if newline else text
+
+    async for chunk in chunks:
+        start = 0
+        while start <= len(chunk):
+            end = chunk.find(b"\n", start)
+            piece = chunk[start:] if end == -1 else chunk[start:end]
+            if max_line_bytes is None or len(line) + len(piece) <= max_line_bytes:
+                line += piece
+            else:
+                room = max(max_line_bytes - len(line), 0)
+                line += piece[:room]
+                if len(piece) > room:
+                    skipped += len(piece) - room
+                    skipped_cr = piece.endswith(b"\r")
+            if end == -1:
+                break
+            yield finish(newline=True)
+            start = end + 1
+    if line or skipped:
+        yield finish(newline=False)
+
+
 @runtime_checkable
 class AsyncReadable(Protocol):
     """Protocol describing readable async byte streams."""
@@ -102,6 +205,108 @@ class AsyncWritable(Protocol):
         ...
 
 
+type RetentionPolicy = Literal["head", "tail", "head_tail"]
+"""Which bytes a `CappedReadable` keeps once its source outgrows the cap."""
+
+
+class CappedReadable:
+    """
+    An `AsyncReadable` that keeps at most `max_bytes` of another stream, e.g. the output of a
+    process, so that memory stays bounded however much the process writes.
+
+    The source is drained in the background as it is written, so a process never blocks on a
+    full pipe. Bytes beyond the cap are dropped as they arrive: the end with `"head"`, the
+    start with `"tail"`, and the middle with `"head_tail"`, which keeps half of the cap at each
+    end. Where bytes were dropped, a `[... N bytes truncated]` line is put instead. The tail is
+    only readable once the source reaches EOF, after which this stream reaches EOF too.
+
+    Must be created in a running event loop.
+    """
+
+    def __init__(
+        self, source: AsyncReadable, max_bytes: int, policy: RetentionPolicy = "head_tail"
+    ) -> None:
+        match policy:
+            case "head":
+                self._head_room, self._tail_max = max_bytes, 0
+            case "tail":
+                self._head_room, self._tail_max = 0, max_bytes
+            case "head_tail":
+                self._head_room, self._tail_max = max_bytes - max_bytes // 2, max_bytes // 2
+        self._source = source
+        self._tail = bytearray()
+        self._last_byte = b""
+        # Everything kept fits in the buffer, so `readline` and `readuntil` never overrun
+        self._buffer = asyncio.StreamReader(limit=max_bytes + READ_CHUNK_BYTES)
+        self.total_bytes = 0
+        """The number of bytes read from the source so far, including dropped ones."""
+        self.dropped_bytes = 0
+        """The number of bytes dropped so far."""
+        self._pump = asyncio.create_task(self._drain())
+
+    @property
+    def truncated(self) -> bool:
+        """Whether any bytes were dropped."""
+        return self.dropped_bytes > 0
+
+    async def _drain(self) -> None:
+        try:
+            while chunk := await self._source.read(READ_CHUNK_BYTES):
+                self.total_bytes += len(chunk)
+                if self._head_room > 0:
+                    head = chunk[: self._head_room]
+                    self._head_room -= len(head)
+                    self._feed(head)
+                    chunk = chunk[len(head) :]
+                if not chunk:
+                    continue
+                if self._tail_max == 0:
+                    self.dropped_bytes += len(chunk)
+                    continue
+                self._tail += chunk
+                # Drop what falls out of the tail now, so that it never outgrows the cap
+                self.dropped_bytes += max(len(self._tail) - self._tail_max, 0)
+                del self._tail[: -self._tail_max]
+        except Exception as e:
+            self._buffer.set_exception(e)
+            return
+        if self.dropped_bytes:
+            prefix = b"" if self._last_byte in (b"", b"\n") else b"\n"
+            self._feed(prefix + f"[... {self.dropped_bytes} bytes truncated]\n".encode())
+        self._feed(bytes(self._tail))
+        self._tail.clear()
+        self._buffer.feed_eof()
+
+    def _feed(self, data: bytes) -> None:
+        if data:
+            self._buffer.feed_data(data)
+            self._last_byte = data[-1:]
+
+    def __aiter__(self) -> AsyncIterator[bytes]:
+        return self._buffer.__aiter__()
+
+    def at_eof(self) -> bool:
+        return self._buffer.at_eof()
+
+    def feed_data(self, data: bytes) -> None:
+        self._buffer.feed_data(data)
+
+    def feed_eof(self) -> None:
+        self._buffer.feed_eof()
+
+    async def read(self, n: int = -1) -> bytes:
+        return await self._buffer.read(n)
+
+    async def readline(self) -> bytes:
+        return await self._buffer.readline()
+
+    async def readexactly(self, n: int) -> bytes:
+        return await self._buffer.readexactly(n)
+
+    async def readuntil(self, separator: bytes) -> bytes:
+        return await self._buffer.readuntil(separator)
+
+
 @runtime_checkable
 class KaosProcess(Protocol):
     """Process interface exposed by KAOS `exec` implementations."""
@@ -165,9 +370,21 @@ class Kaos(Protocol):
         ...
 
     def glob(
-        self, path: StrOrKaosPath, pattern: str, *, case_sensitive: bool = True
+        self,
+        path: StrOrKaosPath,
+        pattern: str,
+        *,
+        case_sensitive: bool = True,
+        on_error: Callable[[OSError], None] | None = None,
+        exclude: Callable[[KaosPath], bool] | None = None,
     ) -> AsyncGenerator[KaosPath]:
-        """Search for files/directories matching a pattern in the given path."""
+        """
+        Search for files/directories matching a pattern in the given path.
+
+        Directories that cannot be read are skipped; `on_error` is called with the error of
+        each of them, if given. Paths for which `exclude` returns true are not returned, and
+        nothing below them is.
+        """
         ...
 
     async def readbytes(self, path: StrOrKaosPath, n: int | None = None) -> bytes:
@@ -181,7 +398,12 @@ class Kaos(Protocol):
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
     ) -> str:
-        """Read the entire file contents as text."""
+        """
+        Read the entire file contents as text.
+
+        Raises:
+            BinaryFileError: When the first `SNIFF_BYTES` of the file contain NUL bytes.
+        """
         ...
 
     def readlines(
@@ -190,14 +412,30 @@ class Kaos(Protocol):
         *,
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
+        max_line_bytes: int | None = MAX_LINE_BYTES,
     ) -> AsyncGenerator[str]:
-        """Iterate over the lines of the file."""
+        """
+        Iterate over the lines of the file, as split by `split_lines`. The file is streamed, so
+        memory use is bounded by `max_line_bytes` (None for no limit).
+
+        Raises:
+            BinaryFileError: When the first `SNIFF_BYTES` of the file contain NUL bytes.
+        """
         ...
 
     async def writebytes(self, path: StrOrKaosPath, data: bytes) -> int:
         """Write bytes data to the file."""
         ...
 
+    async def writebytes_atomic(self, path: StrOrKaosPath, data: bytes) -> int:
+        """
+        Replace the content of the file with bytes data atomically: the data is written to a
+        temporary file next to it, which is then renamed over it, so readers never see a
+        partially written file, even after a crash. The permission bits of an existing file are
+        kept, and the temporary file is removed if the write fails.
+        """
+        ...
+
     async def writetext(
         self,
         path: StrOrKaosPath,
@@ -216,6 +454,10 @@ class Kaos(Protocol):
         """Create a directory at the given path."""
         ...
 
+    async def unlink(self, path: StrOrKaosPath) -> None:
+        """Remove the file at the given path."""
+        ...
+
     async def exec(self, *args: str, env: Mapping[str, str] | None = None) -> KaosProcess:
         """
         Execute a command with arguments and return the running process.
@@ -294,9 +536,16 @@ def iterdir(path: StrOrKaosPath) -> AsyncGenerator[KaosPath]:
 
 
 def glob(
-    path: StrOrKaosPath, pattern: str, *, case_sensitive: bool = True
+    path: StrOrKaosPath,
+    pattern: str,
+    *,
+    case_sensitive: bool = True,
+    on_error: Callable[[OSError], None] | None = None,
+    exclude: Callable[[KaosPath], bool] | None = None,
 ) -> AsyncGenerator[KaosPath]:
-    return get_current_kaos().glob(path, pattern, case_sensitive=case_sensitive)
+    return get_current_kaos().glob(
+        path, pattern, case_sensitive=case_sensitive, on_error=on_error, exclude=exclude
+    )
 
 
 async def readbytes(path: StrOrKaosPath, n: int | None = None) -> bytes:
@@ -317,14 +566,21 @@ def readlines(
     *,
     encoding: str = "utf-8",
     errors: Literal["strict", "ignore", "replace"] = "strict",
+    max_line_bytes: int | None = MAX_LINE_BYTES,
 ) -> AsyncGenerator[str]:
-    return get_current_kaos().readlines(path, encoding=encoding, errors=errors)
+    return get_current_kaos().readlines(
+        path, encoding=encoding, errors=errors, max_line_bytes=max_line_bytes
+    )
 
 
 async def writebytes(path: StrOrKaosPath, data: bytes) -> int:
     return await get_current_kaos().writebytes(path, data)
 
 
+async def writebytes_atomic(path: StrOrKaosPath, data: bytes) -> int:
+    return await get_current_kaos().writebytes_atomic(path, data)
+
+
 async def writetext(
     path: StrOrKaosPath,
     data: str,
@@ -342,5 +598,9 @@ async def mkdir(path: StrOrKaosPath, parents: bool = False, exist_ok: bool = Fal
     return await get_current_kaos().mkdir(path, parents=parents, exist_ok=exist_ok)
 
 
+async def unlink(path: StrOrKaosPath) -> None:
+    return await get_current_kaos().unlink(path)
+
+
 async def exec(*args: str, env: Mapping[str, str] | None = None) -> KaosProcess:
     return await get_current_kaos().exec(*args, env=env)
@@ -1,9 +1,14 @@
 from __future__ import annotations
 
 import asyncio
+import contextlib
+import fnmatch
 import os
+import re
+import secrets
+import stat
 from asyncio.subprocess import Process as AsyncioProcess
-from collections.abc import AsyncGenerator
+from collections.abc import AsyncGenerator, Callable
 from pathlib import Path, PurePath
 from typing import TYPE_CHECKING, Literal
 
@@ -19,7 +24,19 @@ from collections.abc import Mapping
 import aiofiles
 import aiofiles.os
 
-from kaos import AsyncReadable, AsyncWritable, Kaos, KaosProcess, StatResult, StrOrKaosPath
+from kaos import (
+    MAX_LINE_BYTES,
+    READ_CHUNK_BYTES,
+    SNIFF_BYTES,
+    AsyncReadable,
+    AsyncWritable,
+    Kaos,
+    KaosProcess,
+    StatResult,
+    StrOrKaosPath,
+    ensure_text,
+    split_lines,
+)
 from kaos.path import KaosPath
 
 if TYPE_CHECKING:
@@ -99,11 +116,27 @@ class LocalKaos:
             yield KaosPath.unsafe_from_local_path(local_path / entry)
 
     async def glob(
-        self, path: StrOrKaosPath, pattern: str, *, case_sensitive: bool = True
+        self,
+        path: StrOrKaosPath,
+        pattern: str,
+        *,
+        case_sensitive: bool = True,
+        on_error: Callable[[OSError], None] | None = None,
+        exclude: Callable[[KaosPath], bool] | None = None,
     ) -> AsyncGenerator[KaosPath]:
         local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
+        local_exclude = (
+            None
+            if exclude is None
+            else lambda entry: exclude(KaosPath.unsafe_from_local_path(entry))
+        )
         entries = await asyncio.to_thread(
-            lambda: list(local_path.glob(pattern, case_sensitive=case_sensitive))
+            _glob,
+            local_path,
+            pattern,
+            case_sensitive=case_sensitive,
+            on_error=on_error,
+            exclude=local_exclude,
         )
         for entry in entries:
             yield KaosPath.unsafe_from_local_path(entry)
@@ -121,6 +154,7 @@ class LocalKaos:
         errors: Literal["strict", "ignore", "replace"] = "strict",
     ) -> str:
         local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
+        ensure_text(path, await self.readbytes(path, n=SNIFF_BYTES))
         async with aiofiles.open(local_path, encoding=encoding, errors=errors) as f:
             return await f.read()
 
@@ -130,10 +164,19 @@ class LocalKaos:
         *,
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
+        max_line_bytes: int | None = MAX_LINE_BYTES,
     ) -> AsyncGenerator[str]:
         local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
-        async with aiofiles.open(local_path, encoding=encoding, errors=errors) as f:
-            async for line in f:
+        ensure_text(path, await self.readbytes(path, n=SNIFF_BYTES))
+        async with aiofiles.open(local_path, mode="rb") as f:
+
+            async def chunks() -> AsyncGenerator[bytes]:
+                while chunk := await f.read(READ_CHUNK_BYTES):
+                    yield chunk
+
+            async for line in split_lines(
+                chunks(), encoding=encoding, errors=errors, max_line_bytes=max_line_bytes
+            ):
                 yield line
 
     async def writebytes(self, path: StrOrKaosPath, data: bytes) -> int:
@@ -141,6 +184,22 @@ class LocalKaos:
         async with aiofiles.open(local_path, mode="wb") as f:
             return await f.write(data)
 
+    async def writebytes_atomic(self, path: StrOrKaosPath, data: bytes) -> int:
+        local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
+        # Write through a symlink to its target instead of replacing the link itself
+        target = Path(os.path.realpath(local_path))
+        temp_path = await asyncio.to_thread(_write_temp_file, target, data)
+        try:
+            await self._replace(temp_path, target)
+        except BaseException:
+            with contextlib.suppress(OSError):
+                await asyncio.to_thread(temp_path.unlink, missing_ok=True)
+            raise
+        return len(data)
+
+    async def _replace(self, src: Path, dst: Path) -> None:
+        await asyncio.to_thread(os.replace, src, dst)
+
     async def writetext(
         self,
         path: str | KaosPath,
@@ -154,7 +213,12 @@ class LocalKaos:
         async with aiofiles.open(
             local_path, mode=mode, encoding=encoding, errors=errors, newline=""
         ) as f:
-            return await f.write(data)
+            written = await f.write(data)
+            if mode == "a":
+                # Appends are not atomic, so at least make sure they are on disk once done
+                await f.flush()
+                await asyncio.to_thread(os.fsync, f.fileno())
+            return written
 
     async def mkdir(
         self, path: StrOrKaosPath, parents: bool = False, exist_ok: bool = False
@@ -162,6 +226,10 @@ class LocalKaos:
         local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
         await asyncio.to_thread(local_path.mkdir, parents=parents, exist_ok=exist_ok)
 
+    async def unlink(self, path: StrOrKaosPath) -> None:
+        local_path = path.unsafe_to_local_path() if isinstance(path, KaosPath) else Path(path)
+        await asyncio.to_thread(local_path.unlink)
+
     async def exec(self, *args: str, env: Mapping[str, str] | None = None) -> KaosProcess:
         if not args:
             raise ValueError("At least one argument (the program to execute) is required.")
@@ -176,5 +244,122 @@ class LocalKaos:
         return self.Process(process)
 
 
+def _write_temp_file(target: Path, data: bytes) -> Path:
+    """
+    Write `data` to a new temporary file next to `target` and flush it to disk. The file gets
+    the permission bits and, where allowed, the owner of `target` if it exists, and the default
+    permissions of new files otherwise.
+    """
+    try:
+        target_stat = target.stat()
+    except FileNotFoundError:
+        target_stat = None
+    temp_path = target.with_name(f".{target.name}.{secrets.token_hex(4)}.tmp")
+    # Created like `open` does, so that the umask applies to new files
+    flags = os.O_WRONLY | os.O_CREAT | os.O_EXCL | getattr(os, "O_BINARY", 0)
+    fd = os.open(temp_path, flags, 0o666)
+    try:
+        with os.fdopen(fd, "wb") as f:
+            f.write(data)
+            f.flush()
+            os.fsync(f.fileno())
+        if target_stat is not None:
+            os.chmod(temp_path, stat.S_IMODE(target_stat.st_mode))
+            if hasattr(os, "chown"):
+                with contextlib.suppress(OSError):
+                    os.chown(temp_path, target_stat.st_uid, target_stat.st_gid)
+    except BaseException:
+        temp_path.unlink(missing_ok=True)
+        raise
+    return temp_path
+
+
+def _glob(
+    root: Path,
+    pattern: str,
+    *,
+    case_sensitive: bool,
+    on_error: Callable[[OSError], None] | None,
+    exclude: Callable[[Path], bool] | None = None,
+) -> list[Path]:
+    """
+    Match `pattern` under `root` like `Path.glob` does, but report the directories that cannot
+    be listed to `on_error` instead of silently skipping them.
+
+    `**` matches zero or more directories without following symlinks, and a trailing `**`
+    matches everything below. A pattern ending with a separator only matches directories.
+    Entries for which `exclude` returns true are neither matched nor descended into.
+    """
+    if not pattern:
+        raise ValueError(f"Unacceptable pattern: {pattern!r}")
+    pure_pattern = PurePath(pattern)
+    if pure_pattern.anchor:
+        raise NotImplementedError("Non-relative patterns are unsupported")
+    dirs_only = pattern.endswith(("/", os.sep))
+    # Collapse repeated `**` segments, which would otherwise yield duplicates
+    parts: list[str] = []
+    for part in pure_pattern.parts:
+        if not (part == "**" and parts and parts[-1] == "**"):
+            parts.append(part)
+    flags = 0 if case_sensitive else re.IGNORECASE
+    regexes = [re.compile(fnmatch.translate(part), flags) for part in parts]
+    results: list[Path] = []
+
+    def scandir(directory: Path) -> list[os.DirEntry[str]]:
+        try:
+            with os.scandir(directory) as it:
+                if exclude is None:
+                    return list(it)
+                return [entry for entry in it if not exclude(Path(entry.path))]
+        except OSError as e:
+            if on_error is not None:
+                on_error(e)
+            return []
+
+    def is_dir(entry: os.DirEntry[str], *, follow_symlinks: bool) -> bool:
+        try:
+            return entry.is_dir(follow_symlinks=follow_symlinks)
+        except OSError:
+            return False
+
+    def add(path: Path, *, is_directory: bool) -> None:
+        if is_directory or not dirs_only:
+            results.append(path)
+
+    def select(directory: Path, index: int, entries: list[os.DirEntry[str]] | None = None) -> None:
+        part = parts[index]
+        last = index == len(parts) - 1
+        if part == "**":
+            # List each directory once, for both the next segment and the recursion
+            entries = scandir(directory)
+            if last:
+                add(directory, is_directory=True)
+            else:
+                select(directory, index + 1, entries)
+            for entry in entries:
+                if is_dir(entry, follow_symlinks=False):
+                    select(Path(entry.path), index)
+                elif last:
+                    add(Path(entry.path), is_directory=is_dir(entry, follow_symlinks=True))
+            return
+        if part == "..":
+            parent = directory / ".."
+            if last:
+                add(parent, is_directory=True)
+            else:
+                select(parent, index + 1)
+            return
+        for entry in entries if entries is not None else scandir(directory):
+            if not regexes[index].match(entry.name):
+                continue
+            if last:
+                add(Path(entry.path), is_directory=is_dir(entry, follow_symlinks=True))
+            elif is_dir(entry, follow_symlinks=True):
+                select(Path(entry.path), index + 1)
+
+    select(root, 0)
+    return results
+
+
 local_kaos = LocalKaos()
 """The default local KAOS instance."""
@@ -1,12 +1,54 @@
 from __future__ import annotations
 
-from collections.abc import AsyncGenerator
+import errno
+import os
+from collections.abc import AsyncGenerator, Callable
+from dataclasses import dataclass
 from pathlib import Path, PurePath
-from stat import S_ISDIR, S_ISREG
+from stat import S_ISDIR, S_ISLNK, S_ISREG
 from typing import Any, Literal
 
 import kaos
 
+type PathKind = Literal["file", "dir", "symlink", "other"]
+
+
+@dataclass(frozen=True, slots=True)
+class PathMetadata:
+    """
+    What a single stat tells about a path, taken once so that checking several properties of a
+    path does not stat it again. It is a snapshot and does not follow later changes.
+    """
+
+    exists: bool
+    kind: PathKind | None = None
+    """The kind of the entry, None if it does not exist. `"symlink"` only when the symlink is
+    not followed."""
+    size: int = 0
+    mtime: float = 0.0
+
+    @property
+    def is_file(self) -> bool:
+        return self.kind == "file"
+
+    @property
+    def is_dir(self) -> bool:
+        return self.kind == "dir"
+
+    @property
+    def is_symlink(self) -> bool:
+        return self.kind == "symlink"
+
+
+def _path_kind(mode: int) -> PathKind:
+    if S_ISREG(mode):
+        return "file"
+    if S_ISDIR(mode):
+        return "dir"
+    if S_ISLNK(mode):
+        return "symlink"
+    return "other"
+
 
 class KaosPath:
     """
@@ -122,37 +164,72 @@ class KaosPath:
         """Return an os.stat_result for the path."""
         return await kaos.stat(self, follow_symlinks=follow_symlinks)
 
-    async def exists(self, *, follow_symlinks: bool = True) -> bool:
-        """Return True if the path points to an existing filesystem entry."""
+    async def metadata(self, *, follow_symlinks: bool = True) -> PathMetadata:
+        """
+        Stat the path once and return what it tells. A path that cannot be stat'ed, such as a
+        dangling symlink when following symlinks, does not exist.
+        """
         try:
-            await self.stat(follow_symlinks=follow_symlinks)
-            return True
+            st = await self.stat(follow_symlinks=follow_symlinks)
         except OSError:
-            return False
+            return PathMetadata(exists=False)
+        return PathMetadata(
+            exists=True, kind=_path_kind(st.st_mode), size=st.st_size, mtime=st.st_mtime
+        )
+
+    async def exists(self, *, follow_symlinks: bool = True) -> bool:
+        """Return True if the path points to an existing filesystem entry."""
+        return (await self.metadata(follow_symlinks=follow_symlinks)).exists
 
     async def is_file(self, *, follow_symlinks: bool = True) -> bool:
         """Return True if the path points to a regular file."""
-        try:
-            st = await self.stat(follow_symlinks=follow_symlinks)
-            return S_ISREG(st.st_mode)
-        except OSError:
-            return False
+        return (await self.metadata(follow_symlinks=follow_symlinks)).is_file
 
     async def is_dir(self, *, follow_symlinks: bool = True) -> bool:
         """Return True if the path points to a directory."""
-        try:
-            st = await self.stat(follow_symlinks=follow_symlinks)
-            return S_ISDIR(st.st_mode)
-        except OSError:
-            return False
+        return (await self.metadata(follow_symlinks=follow_symlinks)).is_dir
+
+    async def is_symlink(self) -> bool:
+        """Return True if the path is a symlink, dangling or not."""
+        return (await self.metadata(follow_symlinks=False)).is_symlink
+
+    async def size(self, *, follow_symlinks: bool = True) -> int:
+        """Return the size of the entry in bytes. Raises FileNotFoundError if it does not exist."""
+        return (await self._existing_metadata(follow_symlinks)).size
+
+    async def mtime(self, *, follow_symlinks: bool = True) -> float:
+        """
+        Return the last modification time of the entry. Raises FileNotFoundError if it does not
+        exist.
+        """
+        return (await self._existing_metadata(follow_symlinks)).mtime
+
+    async def _existing_metadata(self, follow_symlinks: bool) -> PathMetadata:
+        metadata = await self.metadata(follow_symlinks=follow_symlinks)
+        if not metadata.exists:
+            raise FileNotFoundError(errno.ENOENT, os.strerror(errno.ENOENT), str(self))
+        return metadata
 
     def iterdir(self) -> AsyncGenerator[KaosPath]:
         """Return the direct children of the directory."""
         return kaos.iterdir(self)
 
-    def glob(self, pattern: str, *, case_sensitive: bool = True) -> AsyncGenerator[KaosPath]:
-        """Return all paths matching the pattern under this directory."""
-        return kaos.glob(self, pattern, case_sensitive=case_sensitive)
+    def glob(
+        self,
+        pattern: str,
+        *,
+        case_sensitive: bool = True,
+        on_error: Callable[[OSError], None] | None = None,
+        exclude: Callable[[KaosPath], bool] | None = None,
+    ) -> AsyncGenerator[KaosPath]:
+        """
+        Return all paths matching the pattern under this directory. Directories that cannot be
+        read are skipped, and reported to `on_error` if given. Paths for which `exclude` returns
+        true are skipped together with everything below them.
+        """
+        return kaos.glob(
+            self, pattern, case_sensitive=case_sensitive, on_error=on_error, exclude=exclude
+        )
 
     async def read_bytes(self, n: int | None = None) -> bytes:
         """Read the entire file contents as bytes, or the first n bytes if provided."""
@@ -172,14 +249,22 @@ class KaosPath:
         *,
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
+        max_line_bytes: int | None = kaos.MAX_LINE_BYTES,
     ) -> AsyncGenerator[str]:
-        """Iterate over the lines of the file."""
-        return kaos.readlines(self, encoding=encoding, errors=errors)
+        """
+        Iterate over the lines of the file, keeping their line breaks (CRLF is normalized to LF).
+        Lines longer than `max_line_bytes` are truncated, see `kaos.split_lines`.
+        """
+        return kaos.readlines(self, encoding=encoding, errors=errors, max_line_bytes=max_line_bytes)
 
     async def write_bytes(self, data: bytes) -> int:
         """Write bytes data to the file."""
         return await kaos.writebytes(self, data)
 
+    async def write_bytes_atomic(self, data: bytes) -> int:
+        """Replace the content of the file atomically, see `Kaos.writebytes_atomic`."""
+        return await kaos.writebytes_atomic(self, data)
+
     async def write_text(
         self,
         data: str,
@@ -215,3 +300,7 @@ class KaosPath:
     async def mkdir(self, parents: bool = False, exist_ok: bool = False) -> None:
         """Create a directory at this path."""
         return await kaos.mkdir(self, parents=parents, exist_ok=exist_ok)
+
+    async def unlink(self) -> None:
+        """Remove the file at this path."""
+        return await kaos.unlink(self)
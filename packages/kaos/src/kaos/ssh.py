@@ -1,9 +1,12 @@
 from __future__ import annotations
 
+import contextlib
+import errno
 import posixpath
+import secrets
 import shlex
 import stat
-from collections.abc import AsyncGenerator, Mapping
+from collections.abc import AsyncGenerator, Callable, Mapping
 from pathlib import PurePath, PurePosixPath
 from typing import TYPE_CHECKING, Literal
 
@@ -18,7 +21,19 @@ from asyncssh.constants import (
     FILEXFER_TYPE_SYMLINK,
 )
 
-from kaos import AsyncReadable, AsyncWritable, Kaos, KaosProcess, StatResult, StrOrKaosPath
+from kaos import (
+    MAX_LINE_BYTES,
+    READ_CHUNK_BYTES,
+    SNIFF_BYTES,
+    AsyncReadable,
+    AsyncWritable,
+    Kaos,
+    KaosProcess,
+    StatResult,
+    StrOrKaosPath,
+    ensure_text,
+    split_lines,
+)
 from kaos.path import KaosPath
 
 if TYPE_CHECKING:
@@ -210,11 +225,32 @@ class SSHKaos:
         pattern: str,
         *,
         case_sensitive: bool = True,
+        on_error: Callable[[OSError], None] | None = None,
+        exclude: Callable[[KaosPath], bool] | None = None,
     ) -> AsyncGenerator[KaosPath]:
         if not case_sensitive:
             raise ValueError("Case insensitive glob is not supported in current environment")
         real_path = await self._sftp.realpath(str(path))
-        for entry in await self._sftp.glob(f"{real_path}/{pattern}"):
+
+        def error_handler(error: Exception) -> None:
+            if on_error is None:
+                return
+            if isinstance(error, asyncssh.SFTPPermissionDenied):
+                on_error(PermissionError(errno.EACCES, error.reason))
+            elif isinstance(error, asyncssh.SFTPError):
+                on_error(OSError(errno.EIO, error.reason))
+            elif isinstance(error, OSError):
+                on_error(error)
+
+        root = PurePosixPath(real_path)
+        # SFTP glob cannot prune the walk, so excluded paths are only dropped from the results
+        for entry in await self._sftp.glob(f"{real_path}/{pattern}", error_handler=error_handler):
+            if exclude is not None:
+                entry_path = PurePosixPath(str(entry))
+                ancestors = [entry_path, *entry_path.parents]
+                below_root = [p for p in ancestors if p != root and p.is_relative_to(root)]
+                if any(exclude(KaosPath(str(p))) for p in below_root):
+                    continue
             yield KaosPath(await self._sftp.realpath(str(entry)))
 
     async def readbytes(self, path: StrOrKaosPath, n: int | None = None) -> bytes:
@@ -228,6 +264,8 @@ class SSHKaos:
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
     ) -> str:
+        async with self._sftp.open(str(path), "rb") as f:
+            ensure_text(path, await f.read(SNIFF_BYTES))
         async with self._sftp.open(str(path), "r", encoding=encoding, errors=errors) as f:
             return await f.read()
 
@@ -237,16 +275,48 @@ class SSHKaos:
         *,
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
+        max_line_bytes: int | None = MAX_LINE_BYTES,
     ) -> AsyncGenerator[str]:
-        # NOTE: readlines is not supported by SFTPClientFile
-        text = await self.readtext(path, encoding=encoding, errors=errors)
-        for line in text.splitlines():
-            yield line
+        # NOTE: readlines is not supported by SFTPClientFile, so the file is streamed in chunks
+        async with self._sftp.open(str(path), "rb") as f:
+            header = await f.read(SNIFF_BYTES)
+            ensure_text(path, header)
+
+            async def chunks() -> AsyncGenerator[bytes]:
+                yield header
+                while chunk := await f.read(READ_CHUNK_BYTES):
+                    yield chunk
+
+            async for line in split_lines(
+                chunks(), encoding=encoding, errors=errors, max_line_bytes=max_line_bytes
+            ):
+                yield line
 
     async def writebytes(self, path: StrOrKaosPath, data: bytes) -> int:
         async with self._sftp.open(str(path), "wb") as f:
             return await f.write(data)
 
+    async def writebytes_atomic(self, path: StrOrKaosPath, data: bytes) -> int:
+        # Write through a symlink to its target instead of replacing the link itself
+        target = await self._sftp.realpath(str(path))
+        try:
+            permissions = (await self._sftp.stat(target)).permissions
+        except asyncssh.SFTPNoSuchFile:
+            permissions = None
+        directory, name = posixpath.split(target)
+        temp_path = posixpath.join(directory, f".{name}.{secrets.token_hex(4)}.tmp")
+        try:
+            async with self._sftp.open(temp_path, "wb") as f:
+                await f.write(data)
+            if permissions is not None:
+                await self._sftp.chmod(temp_path, stat.S_IMODE(permissions))
+            await self._sftp.posix_rename(temp_path, target)
+        except BaseException:
+            with contextlib.suppress(asyncssh.SFTPError):
+                await self._sftp.remove(temp_path)
+            raise
+        return len(data)
+
     async def writetext(
         self,
         path: str | KaosPath,
@@ -273,6 +343,9 @@ class SSHKaos:
                 raise FileExistsError(f"{path} already exists")
             await self._sftp.mkdir(str(path))
 
+    async def unlink(self, path: StrOrKaosPath) -> None:
+        await self._sftp.remove(str(path))
+
     async def exec(self, *args: str, env: Mapping[str, str] | None = None) -> KaosProcess:
         if not args:
             raise ValueError("At least one argument (the program to execute) is required.")
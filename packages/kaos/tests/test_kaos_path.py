@@ -1,12 +1,13 @@
 from __future__ import annotations
 
 import os
+import sys
 from collections.abc import Generator
 from pathlib import Path
 
 import pytest
 
-from kaos import reset_current_kaos, set_current_kaos
+from kaos import StatResult, StrOrKaosPath, reset_current_kaos, set_current_kaos
 from kaos.local import LocalKaos
 from kaos.path import KaosPath
 
@@ -92,3 +93,60 @@ async def test_read_write_bytes(kaos_cwd: KaosPath):
     file_path = KaosPath("data.bin")
     await file_path.write_bytes(b"\x00\x01\xff")
     assert await file_path.read_bytes() == b"\x00\x01\xff"
+
+
+class StatCountingKaos(LocalKaos):
+    def __init__(self) -> None:
+        super().__init__()
+        self.stats = 0
+
+    async def stat(self, path: StrOrKaosPath, *, follow_symlinks: bool = True) -> StatResult:
+        self.stats += 1
+        return await super().stat(path, follow_symlinks=follow_symlinks)
+
+
+async def test_metadata_takes_one_stat(kaos_cwd: KaosPath):
+    file_path = KaosPath("data.txt")
+    await file_path.write_text("hello")
+    counting = StatCountingKaos()
+    token = set_current_kaos(counting)
+    try:
+        metadata = await file_path.metadata()
+    finally:
+        reset_current_kaos(token)
+
+    assert counting.stats == 1
+    assert metadata.exists and metadata.is_file and not metadata.is_dir
+    assert metadata.kind == "file"
+    assert metadata.size == 5
+    assert metadata.mtime == (await file_path.stat()).st_mtime
+    assert await file_path.size() == 5
+    assert await file_path.mtime() == metadata.mtime
+
+    dir_metadata = await KaosPath(".").metadata()
+    assert dir_metadata.kind == "dir"
+
+    missing = await KaosPath("missing").metadata()
+    assert not missing.exists and missing.kind is None
+    with pytest.raises(FileNotFoundError):
+        await KaosPath("missing").size()
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="POSIX symlinks")
+async def test_metadata_of_dangling_symlink(kaos_cwd: KaosPath):
+    os.symlink("missing-target", "dangling")
+    link = KaosPath("dangling")
+
+    assert not await link.exists()
+    assert not (await link.metadata()).exists
+    assert await link.exists(follow_symlinks=False)
+    assert await link.is_symlink()
+    assert (await link.metadata(follow_symlinks=False)).kind == "symlink"
+    assert not await link.is_file()
+    with pytest.raises(FileNotFoundError):
+        await link.mtime()
+
+    await KaosPath("target.txt").write_text("x")
+    os.symlink("target.txt", "live")
+    assert (await KaosPath("live").metadata()).kind == "file"
+    assert await KaosPath("live").is_symlink()
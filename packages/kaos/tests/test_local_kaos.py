@@ -3,12 +3,19 @@ from __future__ import annotations
 import asyncio
 import os
 import sys
+import tracemalloc
 from collections.abc import Generator
 from pathlib import Path, PurePosixPath, PureWindowsPath
 
 import pytest
 
-from kaos import reset_current_kaos, set_current_kaos
+from kaos import (
+    SNIFF_BYTES,
+    BinaryFileError,
+    CappedReadable,
+    reset_current_kaos,
+    set_current_kaos,
+)
 from kaos.local import LocalKaos
 from kaos.path import KaosPath
 
@@ -91,6 +98,54 @@ async def test_glob_includes_hidden_files(local_kaos: LocalKaos):
     assert any(".config" in p for p in deep_matched)
 
 
+@pytest.mark.skipif(
+    os.name == "nt" or os.geteuid() == 0, reason="POSIX permission bits, which root ignores"
+)
+async def test_glob_reports_unreadable_directories(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    await local_kaos.mkdir(tmp_path / "ok")
+    await local_kaos.writetext(tmp_path / "ok" / "a.py", "a")
+    await local_kaos.mkdir(tmp_path / "locked")
+    await local_kaos.writetext(tmp_path / "locked" / "b.py", "b")
+    locked = (tmp_path / "locked").unsafe_to_local_path()
+    locked.chmod(0)
+    errors: list[OSError] = []
+    try:
+        matched = [
+            str(entry.relative_to(tmp_path))
+            async for entry in local_kaos.glob(tmp_path, "**/*.py", on_error=errors.append)
+        ]
+    finally:
+        locked.chmod(0o755)
+
+    assert matched == [str(Path("ok", "a.py"))]
+    assert [(type(e), os.path.basename(e.filename)) for e in errors] == [
+        (PermissionError, "locked")
+    ]
+
+
+async def test_glob_prunes_excluded_paths(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    await local_kaos.mkdir(tmp_path / "src")
+    await local_kaos.writetext(tmp_path / "src" / "a.py", "a")
+    await local_kaos.mkdir(tmp_path / "build" / "lib", parents=True)
+    await local_kaos.writetext(tmp_path / "build" / "lib" / "b.py", "b")
+    seen: list[str] = []
+
+    def exclude(path: KaosPath) -> bool:
+        seen.append(str(path.relative_to(tmp_path)))
+        return path.name == "build"
+
+    matched = [
+        str(entry.relative_to(tmp_path))
+        async for entry in local_kaos.glob(tmp_path, "**/*.py", exclude=exclude)
+    ]
+
+    assert matched == [str(Path("src", "a.py"))]
+    assert "build" in seen
+    assert not any(p.startswith(str(Path("build", "lib"))) for p in seen)
+
+
 async def test_read_write_and_append_text(local_kaos: LocalKaos):
     tmp_path = local_kaos.getcwd()
     file_path = tmp_path / "note.txt"
@@ -144,6 +199,132 @@ async def test_read_write_bytes(local_kaos: LocalKaos):
     assert await local_kaos.readbytes(file_path) == b"\x00\x01\xff"
 
 
+async def test_writebytes_atomic_replaces_file(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    file_path = tmp_path / "data.txt"
+    await local_kaos.writebytes_atomic(file_path, b"new file")
+    assert await local_kaos.readbytes(file_path) == b"new file"
+
+    assert await local_kaos.writebytes_atomic(file_path, b"replaced") == 8
+    assert await local_kaos.readbytes(file_path) == b"replaced"
+    assert sorted(os.listdir(tmp_path.unsafe_to_local_path())) == ["data.txt"]
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="POSIX permission bits and symlinks")
+async def test_writebytes_atomic_keeps_mode_and_symlinks(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd().unsafe_to_local_path()
+    script = tmp_path / "run.sh"
+    script.write_bytes(b"echo hello")
+    script.chmod(0o755)
+    link = tmp_path / "link.sh"
+    link.symlink_to(script)
+
+    await local_kaos.writebytes_atomic(str(link), b"echo bye")
+
+    assert link.is_symlink()
+    assert script.read_bytes() == b"echo bye"
+    assert script.stat().st_mode & 0o777 == 0o755
+
+
+async def test_writebytes_atomic_cleans_up_after_a_failed_rename(tmp_path: Path):
+    class FailingReplaceKaos(LocalKaos):
+        async def _replace(self, src: Path, dst: Path) -> None:
+            assert src.read_bytes() == b"new"
+            raise OSError("rename failed")
+
+    file_path = tmp_path / "data.txt"
+    file_path.write_bytes(b"original")
+
+    with pytest.raises(OSError, match="rename failed"):
+        await FailingReplaceKaos().writebytes_atomic(str(file_path), b"new")
+
+    assert file_path.read_bytes() == b"original"
+    assert os.listdir(tmp_path) == ["data.txt"]
+
+
+async def test_unlink(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    file_path = tmp_path / "gone.txt"
+    await local_kaos.writetext(file_path, "bye")
+
+    await local_kaos.unlink(file_path)
+    assert not await file_path.exists()
+    with pytest.raises(FileNotFoundError):
+        await local_kaos.unlink(file_path)
+
+
+async def test_readtext_rejects_binary_files(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    file_path = tmp_path / "image.bin"
+    await local_kaos.writebytes(file_path, b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR")
+
+    with pytest.raises(BinaryFileError, match="use `read_bytes`") as exc_info:
+        await local_kaos.readtext(file_path, errors="replace")
+    assert exc_info.value.path == str(file_path)
+    with pytest.raises(BinaryFileError):
+        _ = [line async for line in local_kaos.readlines(file_path, errors="replace")]
+    with pytest.raises(BinaryFileError):
+        await file_path.read_text()
+
+
+async def test_readtext_only_sniffs_leading_bytes(local_kaos: LocalKaos):
+    tmp_path = local_kaos.getcwd()
+    file_path = tmp_path / "late-nul.txt"
+    content = "a" * SNIFF_BYTES + "\x00"
+    await local_kaos.writetext(file_path, content)
+
+    assert await local_kaos.readtext(file_path) == content
+    assert [line async for line in file_path.read_lines()] == [content]
+
+
+async def test_readlines_normalizes_crlf(local_kaos: LocalKaos):
+    file_path = local_kaos.getcwd() / "crlf.txt"
+    await file_path.write_bytes(b"first\r\nsecond\r\n\r\nlast\r")
+
+    assert [line async for line in file_path.read_lines()] == [
+        "first\n",
+        "second\n",
+        "\n",
+        "last\r",
+    ]
+
+
+async def test_readlines_replaces_invalid_utf8_per_line(local_kaos: LocalKaos):
+    file_path = local_kaos.getcwd() / "invalid.txt"
+    await file_path.write_bytes(b"before\nbad \xff\xfe here\nafter \xc3\xa9\n")
+
+    lines = [line async for line in file_path.read_lines(errors="replace")]
+
+    assert lines == ["before\n", "bad \ufffd\ufffd here\n", "after \u00e9\n"]
+
+
+async def test_readlines_truncates_long_lines(local_kaos: LocalKaos):
+    file_path = local_kaos.getcwd() / "long.txt"
+    await file_path.write_bytes(b"short\n" + b"x" * 100 + b"\r\nend")
+
+    lines = [line async for line in file_path.read_lines(max_line_bytes=10)]
+
+    assert lines == ["short\n", "xxxxxxxxxx[... 90 bytes truncated]\n", "end"]
+
+
+async def test_readlines_memory_is_bounded_by_line_cap(local_kaos: LocalKaos):
+    file_path = local_kaos.getcwd() / "one-line.txt"
+    size = 32 * 1024 * 1024
+    with open(file_path.unsafe_to_local_path(), "wb") as f:
+        for _ in range(size // (1024 * 1024)):
+            f.write(b"a" * 1024 * 1024)
+
+    tracemalloc.start()
+    try:
+        lines = [line async for line in file_path.read_lines(max_line_bytes=4096)]
+        _, peak = tracemalloc.get_traced_memory()
+    finally:
+        tracemalloc.stop()
+
+    assert lines == ["a" * 4096 + f"[... {size - 4096} bytes truncated]"]
+    assert peak < 4 * 1024 * 1024
+
+
 def _python_code_args(code: str) -> tuple[str, str, str]:
     return sys.executable, "-c", code
 
@@ -178,6 +359,38 @@ async def test_exec_runs_command_wait_before_read(local_kaos: LocalKaos):
     assert stderr_data.decode("utf-8").strip() == "stderr line"
 
 
+async def test_exec_capped_output_keeps_head_and_tail(local_kaos: LocalKaos):
+    size = 50 * 1024 * 1024
+    code = (
+        "import sys\n"
+        "sys.stdout.write('begin\\n')\n"
+        "for _ in range(50):\n"
+        "    sys.stdout.write('x' * (1024 * 1024))\n"
+        "sys.stdout.write('\\nend\\n')\n"
+    )
+    cap = 1024 * 1024
+
+    tracemalloc.start()
+    try:
+        process = await local_kaos.exec(*_python_code_args(code))
+        stdout = CappedReadable(process.stdout, cap)
+        lines = [line async for line in stdout]
+        _, peak = tracemalloc.get_traced_memory()
+    finally:
+        tracemalloc.stop()
+
+    assert await process.wait() == 0
+    assert stdout.at_eof()
+    assert stdout.total_bytes == size + len("begin\n\nend\n")
+    assert stdout.dropped_bytes == stdout.total_bytes - cap
+    assert lines[0] == b"begin\n"
+    assert lines[2] == f"[... {stdout.dropped_bytes} bytes truncated]\n".encode()
+    assert lines[-1] == b"end\n"
+    # The marker gets a line of its own, so the kept head ends with an added line break
+    assert sum(len(line) for line in lines) == cap + 1 + len(lines[2])
+    assert peak < 4 * cap
+
+
 async def test_exec_non_zero_exit(local_kaos: LocalKaos):
     process = await local_kaos.exec(*_python_code_args("import sys; sys.exit(7)"))
 
@@ -178,7 +178,7 @@ async def test_kaospath_roundtrip(bind_current_kaos: SSHKaos, remote_base: str):
     assert full_text == text_payload + appended
 
     lines = [line async for line in text_path.read_lines()]
-    assert lines == ["Hello SSH", "More data"]
+    assert lines == ["Hello SSH\n", "More data\n"]
 
     bytes_payload = bytes(range(32))
     bytes_written = await bytes_path.write_bytes(bytes_payload)
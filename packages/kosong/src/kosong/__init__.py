@@ -75,8 +75,15 @@ from dataclasses import dataclass
 
 from loguru import logger
 
-from kosong._generate import GenerateResult, generate
-from kosong.chat_provider import ChatProvider, ChatProviderError, StreamedMessagePart, TokenUsage
+from kosong._generate import GenerateResult, GenerationMetrics, generate
+from kosong.chat_provider import (
+    ChatProvider,
+    ChatProviderError,
+    GenerateOptions,
+    StreamedMessagePart,
+    TokenUsage,
+)
+from kosong.chat_provider.rate_limit import RateLimiter
 from kosong.message import Message, ToolCall
 from kosong.tooling import ToolResult, ToolResultFuture, Toolset
 from kosong.utils.aio import Callback
@@ -96,6 +103,8 @@ __all__ = [
     # classes and functions
     "generate",
     "GenerateResult",
+    "GenerationMetrics",
+    "GenerateOptions",
     "step",
     "StepResult",
 ]
@@ -108,18 +117,26 @@ async def step(
     history: Sequence[Message],
     *,
     on_message_part: Callback[[StreamedMessagePart], None] | None = None,
+    on_tool_call: Callable[[ToolCall], None] | None = None,
     on_tool_result: Callable[[ToolResult], None] | None = None,
+    options: GenerateOptions | None = None,
+    tool_call_preview_interval: int | None = None,
+    rate_limiter: RateLimiter | None = None,
 ) -> "StepResult":
     """
     Run one agent "step". In one step, the function generates LLM response based on the given
     context for exactly one time. All new message parts will be streamed to `on_message_part` in
-    real-time if provided. Tool calls will be handled by `toolset`. The generated message will be
-    returned in a `StepResult`. Depending on the toolset implementation, the tool calls may be
+    real-time if provided. Tool calls will be handled by `toolset`, each passed to `on_tool_call`
+    first once its arguments are complete. The generated message will be returned in a
+    `StepResult`. Depending on the toolset implementation, the tool calls may be
     handled asynchronously and the results need to be fetched with `await result.tool_results()`.
 
     The message history will NOT be modified in this function.
 
-    The token usage will be returned in the `StepResult` if available.
+    The token usage will be returned in the `StepResult` if available. `options` is forwarded to
+    `generate` to bound or tune this step's output, `tool_call_preview_interval` to stream
+    `ToolCallPreview`s of the tool calls being generated, and `rate_limiter` to pace the request
+    with the other callers of the same API.
 
     Raises:
         APIConnectionError: If the API connection fails.
@@ -141,8 +158,10 @@ async def step(
             except asyncio.CancelledError:
                 return
 
-    async def on_tool_call(tool_call: ToolCall):
+    async def handle_tool_call(tool_call: ToolCall):
         tool_calls.append(tool_call)
+        if on_tool_call:
+            on_tool_call(tool_call)
         result = toolset.handle(tool_call)
 
         if isinstance(result, ToolResult):
@@ -161,7 +180,10 @@ async def step(
             toolset.tools,
             history,
             on_message_part=on_message_part,
-            on_tool_call=on_tool_call,
+            on_tool_call=handle_tool_call,
+            options=options,
+            tool_call_preview_interval=tool_call_preview_interval,
+            rate_limiter=rate_limiter,
         )
     except (ChatProviderError, asyncio.CancelledError):
         # cancel all the futures to avoid hanging tasks
@@ -177,6 +199,7 @@ async def step(
         result.usage,
         tool_calls,
         tool_result_futures,
+        result.metrics,
     )
 
 
@@ -197,6 +220,9 @@ class StepResult:
     _tool_result_futures: dict[str, ToolResultFuture]
     """@private The futures of the results of the spawned tool calls."""
 
+    metrics: GenerationMetrics | None = None
+    """The timing of the generation of the message."""
+
     async def tool_results(self) -> list[ToolResult]:
         """All the tool results returned by corresponding tool calls."""
         if not self._tool_result_futures:
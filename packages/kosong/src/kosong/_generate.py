@@ -1,16 +1,32 @@
+import json
+import time
 from collections.abc import Sequence
+from contextlib import nullcontext
 from dataclasses import dataclass
+from typing import cast
 
+import streamingjson
 from loguru import logger
 
 from kosong.chat_provider import (
     APIEmptyResponseError,
     ChatProvider,
+    GenerateOptions,
     StreamedMessagePart,
     TokenUsage,
 )
-from kosong.message import ContentPart, Message, TextPart, ThinkPart, ToolCall
+from kosong.chat_provider.rate_limit import RateLimiter
+from kosong.message import (
+    ContentPart,
+    Message,
+    TextPart,
+    ThinkPart,
+    ToolCall,
+    ToolCallPart,
+    ToolCallPreview,
+)
 from kosong.tooling import Tool
+from kosong.utils.typing import JsonType
 from kosong.utils.aio import Callback, callback
 
 
@@ -22,6 +38,9 @@ async def generate(
     *,
     on_message_part: Callback[[StreamedMessagePart], None] | None = None,
     on_tool_call: Callback[[ToolCall], None] | None = None,
+    options: GenerateOptions | None = None,
+    tool_call_preview_interval: int | None = None,
+    rate_limiter: RateLimiter | None = None,
 ) -> "GenerateResult":
     """
     Generate one message based on the given context.
@@ -34,9 +53,15 @@ async def generate(
         history: The message history to use for generation.
         on_message_part: An optional callback to be called for each raw message part.
         on_tool_call: An optional callback to be called for each complete tool call.
+        options: Optional per-call generation options (max output tokens, stop sequences,
+            sampling). Empty options are not forwarded to the provider.
+        tool_call_preview_interval: If set, a `ToolCallPreview` of each streaming tool call is
+            passed to `on_message_part` after every this many argument fragments.
+        rate_limiter: If set, the request waits for a slot of the limiter before it is sent,
+            and holds the slot until the response has been streamed.
 
     Returns:
-        A tuple of the generated message and the token usage (if available).
+        The generated message, the token usage (if available) and the timing of the generation.
         All parts in the message are guaranteed to be complete and merged as much as possible.
 
     Raises:
@@ -48,28 +73,56 @@ async def generate(
     """
     message = Message(role="assistant", content=[])
     pending_part: StreamedMessagePart | None = None  # message part that is currently incomplete
+    assembler: ArgumentsAssembler | None = None  # arguments of the pending tool call
+
+    async def complete(part: StreamedMessagePart) -> None:
+        if isinstance(part, ToolCall) and assembler is not None:
+            part.function.arguments = assembler.finish()
+        _message_append(message, part)
+        if isinstance(part, ToolCall) and on_tool_call:
+            await callback(on_tool_call, part)
 
     logger.trace("Generating with history: {history}", history=history)
-    stream = await chat_provider.generate(system_prompt, tools, history)
-    async for part in stream:
-        logger.trace("Received part: {part}", part=part)
-        if on_message_part:
-            await callback(on_message_part, part.model_copy(deep=True))
+    limiter = rate_limiter.request() if rate_limiter is not None else nullcontext()
+    async with limiter:
+        started_at = time.monotonic()
+        first_part_at: float | None = None
+        last_part_at = started_at
+        part_count = 0
+        if options is None or options.is_empty:
+            stream = await chat_provider.generate(system_prompt, tools, history)
+        else:
+            stream = await chat_provider.generate(system_prompt, tools, history, options=options)
+        async for part in stream:
+            last_part_at = time.monotonic()
+            if first_part_at is None:
+                first_part_at = last_part_at
+            part_count += 1
+            logger.trace("Received part: {part}", part=part)
+            if on_message_part:
+                await callback(on_message_part, part.model_copy(deep=True))
+
+            if pending_part is not None and pending_part.merge_in_place(part):
+                if isinstance(part, ToolCallPart) and assembler is not None:
+                    preview = assembler.append(part.arguments_part)
+                    if preview is not None and on_message_part:
+                        await callback(on_message_part, preview)
+                continue
 
-        if pending_part is None:
-            pending_part = part
-        elif not pending_part.merge_in_place(part):  # try merge into the pending part
             # unmergeable part must push the pending part to the buffer
-            _message_append(message, pending_part)
-            if isinstance(pending_part, ToolCall) and on_tool_call:
-                await callback(on_tool_call, pending_part)
+            if pending_part is not None:
+                await complete(pending_part)
             pending_part = part
+            assembler = (
+                ArgumentsAssembler(part, preview_interval=tool_call_preview_interval)
+                if isinstance(part, ToolCall)
+                else None
+            )
 
     # end of message
+    finished_at = time.monotonic()
     if pending_part is not None:
-        _message_append(message, pending_part)
-        if isinstance(pending_part, ToolCall) and on_tool_call:
-            await callback(on_tool_call, pending_part)
+        await complete(pending_part)
 
     if not message.content and not message.tool_calls:
         raise APIEmptyResponseError("The API returned an empty response.")
@@ -88,13 +141,51 @@ async def generate(
             "during reasoning."
         )
 
+    usage = stream.usage
+    streaming_time = last_part_at - (first_part_at or last_part_at)
     return GenerateResult(
         id=stream.id,
         message=message,
-        usage=stream.usage,
+        usage=usage,
+        metrics=GenerationMetrics(
+            time_to_first_part=(first_part_at or started_at) - started_at,
+            duration=finished_at - started_at,
+            part_count=part_count,
+            output_tokens_per_second=(
+                usage.output / streaming_time
+                if usage is not None and usage.output > 0 and streaming_time >= _AT_ONCE
+                else None
+            ),
+        ),
     )
 
 
+_AT_ONCE = 0.001
+"""Parts received within this many seconds of each other are taken as received at once."""
+
+
+@dataclass(frozen=True, slots=True)
+class GenerationMetrics:
+    """
+    The timing of a generation, measured from the moment the request is sent, after waiting for
+    the rate limiter if any.
+
+    A non-streaming response arrives at once: its parts are all received together when the whole
+    message is ready, so only `duration` and `part_count` are meaningful.
+    """
+
+    time_to_first_part: float
+    """The seconds until the first part was received, 0 if there was none."""
+    duration: float
+    """The seconds until the whole response was received."""
+    part_count: int
+    """The number of parts received, before they are merged into the message."""
+    output_tokens_per_second: float | None
+    """The output tokens of the usage divided by the seconds from the first part to the last,
+    approximate since the usage includes the tokens of the first part. None if there is no usage
+    or if all the parts were received at once, within a millisecond."""
+
+
 @dataclass(frozen=True, slots=True)
 class GenerateResult:
     """The result of a generation."""
@@ -105,12 +196,104 @@ class GenerateResult:
     """The generated message."""
     usage: TokenUsage | None
     """The token usage of the generated message."""
+    metrics: GenerationMetrics
+    """The timing of the generation."""
+
+
+class ArgumentsAssembler:
+    """
+    Accumulates the argument fragments of a streaming tool call.
+
+    The final arguments are the exact concatenation of the fragments. Separately, the first
+    `PREVIEW_MAX_CHARS` characters are fed to a lenient partial-JSON parser so that the fields
+    received so far can be shown in a `ToolCallPreview` before the call is complete. Bounding the
+    parsed prefix keeps the cost of each preview constant however long the arguments grow.
+    """
+
+    PREVIEW_MAX_CHARS = 64 * 1024
+    """The number of leading argument characters that previews are parsed from."""
+
+    def __init__(self, tool_call: ToolCall, *, preview_interval: int | None = None):
+        self._tool_call = tool_call
+        self._preview_interval = preview_interval
+        self._fragments: list[str] = []
+        self._received = 0
+        self._lexer = streamingjson.Lexer()
+        self._lexed = 0
+        self._preview_arguments: dict[str, JsonType] = {}
+        self._preview_stale = False
+        if tool_call.function.arguments is not None:
+            self._add(tool_call.function.arguments)
+
+    def append(self, fragment: str | None) -> ToolCallPreview | None:
+        """
+        Add an argument fragment.
+
+        Returns:
+            A preview of the arguments if one is due after this fragment, otherwise None.
+        """
+        self._received += 1
+        if fragment is not None:
+            self._add(fragment)
+        if self._preview_interval is None or self._received % self._preview_interval != 0:
+            return None
+        return self.preview()
+
+    def preview(self) -> ToolCallPreview:
+        """
+        The fields parsed from the fragments received so far, up to `PREVIEW_MAX_CHARS`.
+        The prefix is only parsed again if it grew since the last preview.
+        """
+        if self._preview_stale:
+            self._preview_stale = False
+            try:
+                parsed = json.loads(self._lexer.complete_json(), strict=False)
+            except json.JSONDecodeError:
+                # the prefix may end inside an escape sequence; keep the previous fields
+                parsed = None
+            if isinstance(parsed, dict):
+                self._preview_arguments = cast(dict[str, JsonType], parsed)
+        return ToolCallPreview(
+            id=self._tool_call.id,
+            name=self._tool_call.function.name,
+            arguments=self._preview_arguments,
+        )
+
+    def finish(self) -> str | None:
+        """
+        The exact concatenation of the fragments, or None if the call has no arguments at all.
+        A warning is logged if it is not valid JSON.
+        """
+        if not self._fragments:
+            return None
+        arguments = "".join(self._fragments)
+        try:
+            json.loads(arguments or "{}", strict=False)
+        except json.JSONDecodeError as e:
+            logger.warning(
+                "Arguments of tool call {name} ({id}) are not valid JSON: {error}",
+                name=self._tool_call.function.name,
+                id=self._tool_call.id,
+                error=e,
+            )
+        return arguments
+
+    def _add(self, fragment: str) -> None:
+        self._fragments.append(fragment)
+        remaining = self.PREVIEW_MAX_CHARS - self._lexed
+        if remaining > 0 and fragment:
+            self._lexer.append_string(fragment[:remaining])
+            self._lexed += min(len(fragment), remaining)
+            self._preview_stale = True
 
 
 def _message_append(message: Message, part: StreamedMessagePart) -> None:
     match part:
         case ContentPart():
-            message.content.append(part)
+            # Parts separated only by a tool call, which is kept apart from the content, are
+            # merged as well, so the message does not keep a run of deltas as separate parts.
+            if not (message.content and message.content[-1].merge_in_place(part)):
+                message.content.append(part)
         case ToolCall():
             if message.tool_calls is None:
                 message.tool_calls = []
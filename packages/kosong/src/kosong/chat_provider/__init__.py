@@ -1,11 +1,12 @@
 from __future__ import annotations
 
 from collections.abc import AsyncIterator, Sequence
+from dataclasses import dataclass
 from typing import TYPE_CHECKING, Literal, Protocol, Self, runtime_checkable
 
 from pydantic import BaseModel
 
-from kosong.message import ContentPart, Message, ToolCall, ToolCallPart
+from kosong.message import ContentPart, Message, ToolCall, ToolCallPart, ToolCallPreview
 from kosong.tooling import Tool
 
 if TYPE_CHECKING:
@@ -40,10 +41,15 @@ class ChatProvider(Protocol):
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> StreamedMessage:
         """
         Generate a new message based on the given system prompt, tools, and history.
 
+        `options` overrides the provider's generation defaults for this call only. Fields a
+        provider does not support are ignored.
+
         Raises:
             APIConnectionError: If the API connection fails.
             APITimeoutError: If the API request times out.
@@ -74,7 +80,19 @@ class RetryableChatProvider(Protocol):
         ...
 
 
-type StreamedMessagePart = ContentPart | ToolCall | ToolCallPart
+@runtime_checkable
+class PartialContinuationChatProvider(Protocol):
+    """Optional interface for providers that can continue a partial assistant message."""
+
+    def supports_partial_continuation(self) -> bool:
+        """
+        Whether a trailing assistant message with `partial=True` in the history is continued
+        from where it stopped, instead of being answered with a new message.
+        """
+        ...
+
+
+type StreamedMessagePart = ContentPart | ToolCall | ToolCallPart | ToolCallPreview
 
 
 @runtime_checkable
@@ -119,6 +137,70 @@ class TokenUsage(BaseModel):
         return self.input_other + self.input_cache_read + self.input_cache_creation
 
 
+@dataclass(frozen=True, slots=True)
+class NamedToolChoice:
+    """Force the model to call the tool with the given name."""
+
+    name: str
+
+
+type ToolChoice = Literal["auto", "none", "required"] | NamedToolChoice
+"""
+Whether the model may call tools: `"auto"` lets it decide, `"none"` forbids tool calls,
+`"required"` forces at least one, and `NamedToolChoice` forces a call to a specific tool.
+"""
+
+
+@dataclass(frozen=True, slots=True, kw_only=True)
+class GenerateOptions:
+    """Per-call generation options. Unset fields fall back to the provider's defaults."""
+
+    max_output_tokens: int | None = None
+    """The maximum number of tokens to generate."""
+    stop: tuple[str, ...] = ()
+    """Sequences at which the model stops generating."""
+    temperature: float | None = None
+    top_p: float | None = None
+    tool_choice: ToolChoice = "auto"
+    """Whether the model may call tools. `"auto"` is not sent, keeping the provider's default."""
+    parallel_tool_calls: bool | None = None
+    """Whether the model may call several tools in one response. Ignored by providers without
+    support for it."""
+
+    @property
+    def is_empty(self) -> bool:
+        """Whether no option is set."""
+        return (
+            self.max_output_tokens is None
+            and not self.stop
+            and self.temperature is None
+            and self.top_p is None
+            and self.tool_choice == "auto"
+            and self.parallel_tool_calls is None
+        )
+
+    def merge(self, other: GenerateOptions | None) -> GenerateOptions:
+        """Return a copy with the fields set in `other` taking precedence."""
+        if other is None:
+            return self
+        return GenerateOptions(
+            max_output_tokens=(
+                other.max_output_tokens
+                if other.max_output_tokens is not None
+                else self.max_output_tokens
+            ),
+            stop=other.stop or self.stop,
+            temperature=other.temperature if other.temperature is not None else self.temperature,
+            top_p=other.top_p if other.top_p is not None else self.top_p,
+            tool_choice=other.tool_choice if other.tool_choice != "auto" else self.tool_choice,
+            parallel_tool_calls=(
+                other.parallel_tool_calls
+                if other.parallel_tool_calls is not None
+                else self.parallel_tool_calls
+            ),
+        )
+
+
 type ThinkingEffort = Literal["off", "low", "medium", "high", "xhigh", "max"]
 """The effort level for thinking.
 
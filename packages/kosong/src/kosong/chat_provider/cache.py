@@ -0,0 +1,282 @@
+"""
+Recording the responses of a chat provider to a cassette and replaying them later.
+
+A `CachingChatProvider` keys each request by a hash of the model, the thinking effort, the
+system prompt, the tools, the history and the generation options. In `"record"` mode it streams
+from the wrapped provider and, once a response has been streamed to the end, saves its parts, ID
+and usage as `<key>.json` in the cassette directory. In `"replay"` mode it streams the saved
+parts one by one without calling any provider, and raises `CassetteMissError` with the key when
+nothing was recorded for a request. In `"passthrough"` mode it only forwards to the wrapped
+provider.
+
+This is meant for iterating on prompts and tools in development and for replaying sessions in
+tests, without paying for the same requests over and over.
+"""
+
+import copy
+import dataclasses
+import hashlib
+import json
+from collections.abc import AsyncIterator, Sequence
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Literal
+
+from pydantic import BaseModel
+
+from kosong.chat_provider import (
+    ChatProvider,
+    ChatProviderError,
+    GenerateOptions,
+    PartialContinuationChatProvider,
+    RetryableChatProvider,
+    StreamedMessage,
+    StreamedMessagePart,
+    ThinkingEffort,
+    TokenUsage,
+)
+from kosong.message import ContentPart, Message, ToolCall, ToolCallPart, ToolCallPreview
+from kosong.tooling import Tool
+
+if TYPE_CHECKING:
+
+    def type_check(caching: "CachingChatProvider"):
+        _: ChatProvider = caching
+        _: RetryableChatProvider = caching
+
+
+type CassetteMode = Literal["record", "replay", "passthrough"]
+
+CASSETTE_VERSION = 1
+
+
+class CassetteMissError(ChatProviderError):
+    """No response was recorded in the cassette for a request being replayed."""
+
+    def __init__(self, key: str, directory: Path):
+        super().__init__(f"No response recorded for request {key} in cassette {directory}")
+        self.key = key
+        self.directory = directory
+
+
+class Recording(BaseModel):
+    """A response saved in a cassette."""
+
+    version: int = CASSETTE_VERSION
+    id: str | None = None
+    usage: TokenUsage | None = None
+    parts: list[dict[str, Any]]
+    """The streamed parts in order, each tagged with its `kind`."""
+
+
+_PART_TYPES: dict[str, type[BaseModel]] = {
+    "tool_call": ToolCall,
+    "tool_call_part": ToolCallPart,
+    "tool_call_preview": ToolCallPreview,
+}
+
+
+def _dump_part(part: StreamedMessagePart) -> dict[str, Any]:
+    if isinstance(part, ContentPart):
+        kind = "content"
+    else:
+        kind = next(kind for kind, cls in _PART_TYPES.items() if isinstance(part, cls))
+    return {"kind": kind, "part": part.model_dump(mode="json")}
+
+
+def _load_part(data: dict[str, Any]) -> StreamedMessagePart:
+    kind = data["kind"]
+    if kind == "content":
+        return ContentPart.model_validate(data["part"])  # type: ignore[reportReturnType]
+    return _PART_TYPES[kind].model_validate(data["part"])  # type: ignore[reportReturnType]
+
+
+class Cassette:
+    """A directory of recorded responses, one JSON file per request key."""
+
+    def __init__(self, directory: Path):
+        self.directory = directory
+
+    def load(self, key: str) -> Recording | None:
+        path = self.directory / f"{key}.json"
+        if not path.exists():
+            return None
+        try:
+            return Recording.model_validate_json(path.read_bytes())
+        except ValueError as e:
+            raise ChatProviderError(f"Invalid cassette file {path}: {e}") from e
+
+    def save(self, key: str, recording: Recording) -> None:
+        self.directory.mkdir(parents=True, exist_ok=True)
+        path = self.directory / f"{key}.json"
+        tmp_path = path.with_suffix(".json.tmp")
+        tmp_path.write_text(recording.model_dump_json(indent=2), encoding="utf-8")
+        tmp_path.replace(path)
+
+
+def request_key(
+    model_name: str,
+    thinking_effort: ThinkingEffort | None,
+    system_prompt: str,
+    tools: Sequence[Tool],
+    history: Sequence[Message],
+    options: GenerateOptions | None,
+) -> str:
+    """The hash a request is recorded under."""
+    request = {
+        "model": model_name,
+        "thinking_effort": thinking_effort,
+        "system_prompt": system_prompt,
+        "tools": [tool.model_dump(mode="json") for tool in tools],
+        "history": [message.model_dump(mode="json", exclude_none=True) for message in history],
+        "options": dataclasses.asdict(options or GenerateOptions()),
+    }
+    encoded = json.dumps(request, sort_keys=True, ensure_ascii=False, separators=(",", ":"))
+    return hashlib.sha256(encoded.encode("utf-8")).hexdigest()
+
+
+class CachingChatProvider:
+    """
+    Wrap a chat provider to record its responses to a cassette, or replay them from it.
+
+    In `"replay"` mode `provider` may be None, so that no real provider has to be created; the
+    model name used in the request keys must then be given as `model_name`.
+    """
+
+    def __init__(
+        self,
+        provider: ChatProvider | None,
+        cassette: Cassette,
+        *,
+        mode: CassetteMode,
+        model_name: str | None = None,
+        thinking_effort: ThinkingEffort | None = None,
+    ):
+        if provider is None and mode != "replay":
+            raise ValueError(f"A provider to wrap is required in {mode} mode")
+        if provider is None and model_name is None:
+            raise ValueError("model_name is required without a provider to wrap")
+        self._provider = provider
+        self._cassette = cassette
+        self._mode: CassetteMode = mode
+        self._model_name = model_name
+        self._thinking_effort = thinking_effort
+        self.name: str = provider.name if provider is not None else "cassette"
+
+    @property
+    def mode(self) -> CassetteMode:
+        return self._mode
+
+    @property
+    def model_name(self) -> str:
+        if self._model_name is not None:
+            return self._model_name
+        assert self._provider is not None
+        return self._provider.model_name
+
+    @property
+    def thinking_effort(self) -> ThinkingEffort | None:
+        if self._provider is not None:
+            return self._provider.thinking_effort
+        return self._thinking_effort
+
+    async def generate(
+        self,
+        system_prompt: str,
+        tools: Sequence[Tool],
+        history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
+    ) -> StreamedMessage:
+        if self._mode == "passthrough":
+            assert self._provider is not None
+            return await self._provider.generate(system_prompt, tools, history, options=options)
+
+        key = request_key(
+            self.model_name, self.thinking_effort, system_prompt, tools, history, options
+        )
+        if self._mode == "replay":
+            recording = self._cassette.load(key)
+            if recording is None:
+                raise CassetteMissError(key, self._cassette.directory)
+            return ReplayedStreamedMessage(recording)
+
+        assert self._provider is not None
+        stream = await self._provider.generate(system_prompt, tools, history, options=options)
+        return RecordingStreamedMessage(stream, self._cassette, key)
+
+    def on_retryable_error(self, error: BaseException) -> bool:
+        if not isinstance(self._provider, RetryableChatProvider):
+            return False
+        return self._provider.on_retryable_error(error)
+
+    def supports_partial_continuation(self) -> bool:
+        return (
+            isinstance(self._provider, PartialContinuationChatProvider)
+            and self._provider.supports_partial_continuation()
+        )
+
+    def with_thinking(self, effort: ThinkingEffort) -> "CachingChatProvider":
+        new_self = copy.copy(self)
+        if self._provider is not None:
+            new_self._provider = self._provider.with_thinking(effort)
+        new_self._thinking_effort = effort
+        return new_self
+
+
+class RecordingStreamedMessage:
+    """Stream the parts of a response and save them to the cassette once it ends."""
+
+    def __init__(self, wrapped: StreamedMessage, cassette: Cassette, key: str):
+        self._wrapped = wrapped
+        self._iterator = wrapped.__aiter__()
+        self._cassette = cassette
+        self._key = key
+        self._parts: list[dict[str, Any]] = []
+
+    def __aiter__(self) -> AsyncIterator[StreamedMessagePart]:
+        return self
+
+    async def __anext__(self) -> StreamedMessagePart:
+        try:
+            part = await self._iterator.__anext__()
+        except StopAsyncIteration:
+            self._cassette.save(
+                self._key, Recording(id=self.id, usage=self.usage, parts=self._parts)
+            )
+            raise
+        self._parts.append(_dump_part(part))
+        return part
+
+    @property
+    def id(self) -> str | None:
+        return self._wrapped.id
+
+    @property
+    def usage(self) -> TokenUsage | None:
+        return self._wrapped.usage
+
+
+class ReplayedStreamedMessage:
+    """Stream the parts of a recorded response one by one."""
+
+    def __init__(self, recording: Recording):
+        self._recording = recording
+        self._iter = self._to_stream()
+
+    def __aiter__(self) -> AsyncIterator[StreamedMessagePart]:
+        return self
+
+    async def __anext__(self) -> StreamedMessagePart:
+        return await self._iter.__anext__()
+
+    async def _to_stream(self) -> AsyncIterator[StreamedMessagePart]:
+        for data in self._recording.parts:
+            yield _load_part(data)
+
+    @property
+    def id(self) -> str | None:
+        return self._recording.id
+
+    @property
+    def usage(self) -> TokenUsage | None:
+        return self._recording.usage
@@ -10,6 +10,8 @@ from pydantic import BaseModel
 from kosong.chat_provider import (
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
+    PartialContinuationChatProvider,
     RetryableChatProvider,
     StreamedMessage,
     StreamedMessagePart,
@@ -115,8 +117,15 @@ class ChaosChatProvider:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "ChaosStreamedMessage":
-        base_stream = await self._provider.generate(system_prompt, tools, history)
+        if options is None:
+            base_stream = await self._provider.generate(system_prompt, tools, history)
+        else:
+            base_stream = await self._provider.generate(
+                system_prompt, tools, history, options=options
+            )
         return ChaosStreamedMessage(base_stream, self._chaos_config)
 
     def _monkey_patch_client(self):
@@ -189,6 +198,12 @@ class ChaosChatProvider:
             self._monkey_patch_client()
         return recovered
 
+    def supports_partial_continuation(self) -> bool:
+        return (
+            isinstance(self._provider, PartialContinuationChatProvider)
+            and self._provider.supports_partial_continuation()
+        )
+
     def with_thinking(self, effort: ThinkingEffort) -> "ChaosChatProvider":
         return ChaosChatProvider(self._provider.with_thinking(effort), self._chaos_config)
 
@@ -1,9 +1,20 @@
 from .echo import EchoChatProvider, EchoStreamedMessage
-from .scripted_echo import ScriptedEchoChatProvider, ScriptedEchoStreamedMessage
+from .scripted_echo import (
+    ScriptBuilder,
+    ScriptedEchoChatProvider,
+    ScriptedEchoStreamedMessage,
+    ScriptedToolCall,
+    ScriptedTurn,
+    load_scripted_turns,
+)
 
 __all__ = [
     "EchoChatProvider",
     "EchoStreamedMessage",
+    "ScriptBuilder",
     "ScriptedEchoChatProvider",
     "ScriptedEchoStreamedMessage",
+    "ScriptedToolCall",
+    "ScriptedTurn",
+    "load_scripted_turns",
 ]
@@ -7,13 +7,14 @@ from typing import TYPE_CHECKING, Self
 from kosong.chat_provider import (
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
     StreamedMessage,
     StreamedMessagePart,
     ThinkingEffort,
     TokenUsage,
 )
 from kosong.chat_provider.echo.dsl import parse_echo_script
-from kosong.message import Message
+from kosong.message import Message, TextPart, ToolCall, ToolCallPart
 from kosong.tooling import Tool
 
 if TYPE_CHECKING:
@@ -55,6 +56,10 @@ class EchoChatProvider:
     tool_call_part: {"arguments_part": "\\": \\"what time is"}
     tool_call_part: {"arguments_part": " it?\\"}"}
     ```
+
+    Stop sequences in `GenerateOptions.stop` are honored: text is cut before the first match
+    and nothing after it is streamed. So are `tool_choice="none"`, which drops the tool calls,
+    and `parallel_tool_calls=False`, which keeps only the first one.
     """
 
     name = "echo"
@@ -72,6 +77,8 @@ class EchoChatProvider:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> EchoStreamedMessage:
         if not history:
             raise ChatProviderError("EchoChatProvider requires at least one message in history.")
@@ -82,6 +89,10 @@ class EchoChatProvider:
         parts, message_id, usage = parse_echo_script(script_text)
         if not parts:
             raise ChatProviderError("EchoChatProvider DSL produced no streamable parts.")
+        if options is not None and options.stop:
+            parts = apply_stop_sequences(parts, options.stop)
+        if options is not None:
+            parts = apply_tool_call_options(parts, options)
         return EchoStreamedMessage(parts=parts, message_id=message_id, usage=usage)
 
     def with_thinking(self, effort: ThinkingEffort) -> Self:
@@ -90,6 +101,64 @@ class EchoChatProvider:
         return copy.copy(self)
 
 
+def apply_stop_sequences(
+    parts: list[StreamedMessagePart], stop: Sequence[str]
+) -> list[StreamedMessagePart]:
+    """Truncate `parts` at the first stop sequence found in the streamed text."""
+    stop = [s for s in stop if s]
+    text = ""
+    result: list[StreamedMessagePart] = []
+    for part in parts:
+        if not isinstance(part, TextPart):
+            result.append(part)
+            continue
+        candidate = text + part.text
+        # Only consider matches that end inside this part; earlier text is already streamed.
+        cut = min(
+            (
+                index
+                for seq in stop
+                if (index := candidate.find(seq, max(len(text) - len(seq) + 1, 0))) != -1
+            ),
+            default=None,
+        )
+        if cut is None:
+            result.append(part)
+            text = candidate
+            continue
+        if (kept := candidate[len(text) : max(cut, len(text))]) != "":
+            result.append(TextPart(text=kept))
+        break
+    return result
+
+
+def apply_tool_call_options(
+    parts: list[StreamedMessagePart], options: GenerateOptions
+) -> list[StreamedMessagePart]:
+    """
+    Drop the tool calls of `parts` if `options.tool_choice` is `"none"`, or all but the first
+    if `options.parallel_tool_calls` is False, as a model honoring them would.
+    """
+    if options.tool_choice == "none":
+        max_tool_calls = 0
+    elif options.parallel_tool_calls is False:
+        max_tool_calls = 1
+    else:
+        return parts
+    tool_calls = 0
+    kept = True
+    result: list[StreamedMessagePart] = []
+    for part in parts:
+        if isinstance(part, ToolCall):
+            tool_calls += 1
+            kept = tool_calls <= max_tool_calls
+        elif not isinstance(part, ToolCallPart):
+            kept = True
+        if kept:
+            result.append(part)
+    return result
+
+
 class EchoStreamedMessage(StreamedMessage):
     """Streamed message for EchoChatProvider."""
 
@@ -1,21 +1,27 @@
 from __future__ import annotations
 
+import asyncio
 import copy
 import json
 from collections import deque
 from collections.abc import AsyncIterator, Iterable, Sequence
-from typing import TYPE_CHECKING, Self
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Self
+
+from pydantic import BaseModel, Field, TypeAdapter
 
 from kosong.chat_provider import (
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
     StreamedMessage,
     StreamedMessagePart,
     ThinkingEffort,
     TokenUsage,
 )
 from kosong.chat_provider.echo.dsl import parse_echo_script
-from kosong.message import Message
+from kosong.chat_provider.echo.echo import apply_stop_sequences, apply_tool_call_options
+from kosong.message import Message, TextPart, ThinkPart, ToolCall
 from kosong.tooling import Tool
 
 if TYPE_CHECKING:
@@ -24,18 +30,129 @@ if TYPE_CHECKING:
         _: ChatProvider = scripted
 
 
+class ScriptedToolCall(BaseModel):
+    """A tool call streamed by a scripted turn."""
+
+    name: str
+    arguments: Any = None
+    """JSON arguments. Non-string values are serialized with `json.dumps`."""
+    id: str | None = None
+    """The tool call id. Defaults to `call-<turn>-<index>`."""
+
+
+class ScriptedTurn(BaseModel):
+    """A structured script entry for `ScriptedEchoChatProvider`.
+
+    Parts are streamed in order: think parts, then text parts, then tool calls.
+    """
+
+    id: str | None = None
+    think: list[str] = Field(default_factory=list[str])
+    text: list[str] = Field(default_factory=list[str])
+    tool_calls: list[ScriptedToolCall] = Field(default_factory=list[ScriptedToolCall])
+    usage: TokenUsage | None = None
+    delay: float | None = None
+    """Seconds to sleep before each streamed part. Overrides the provider-level delay."""
+
+    def to_parts(self, turn: int) -> list[StreamedMessagePart]:
+        parts: list[StreamedMessagePart] = [ThinkPart(think=think) for think in self.think]
+        parts.extend(TextPart(text=text) for text in self.text)
+        for index, call in enumerate(self.tool_calls, start=1):
+            arguments = call.arguments
+            if arguments is not None and not isinstance(arguments, str):
+                arguments = json.dumps(arguments, ensure_ascii=False)
+            parts.append(
+                ToolCall(
+                    id=call.id or f"call-{turn}-{index}",
+                    function=ToolCall.FunctionBody(name=call.name, arguments=arguments),
+                )
+            )
+        return parts
+
+
+type Script = str | ScriptedTurn
+"""An echo DSL script or a structured turn."""
+
+
+def load_scripted_turns(path: str | Path) -> list[ScriptedTurn]:
+    """Load a JSON fixture file containing a list of `ScriptedTurn` objects."""
+    data = Path(path).read_text(encoding="utf-8")
+    return TypeAdapter(list[ScriptedTurn]).validate_json(data)
+
+
+class ScriptBuilder:
+    """Fluent builder for a queue of scripted turns.
+
+    Example::
+
+        provider = (
+            ScriptBuilder()
+            .tool_call("ReadFile", {"path": "a.txt"})
+            .turn()
+            .text("Done.")
+            .build()
+        )
+    """
+
+    def __init__(self) -> None:
+        self._turns: list[ScriptedTurn] = [ScriptedTurn()]
+
+    @property
+    def _current(self) -> ScriptedTurn:
+        return self._turns[-1]
+
+    def turn(self, *, id: str | None = None, delay: float | None = None) -> Self:
+        """Start a new turn. The first turn is started implicitly."""
+        self._turns.append(ScriptedTurn(id=id, delay=delay))
+        return self
+
+    def think(self, think: str) -> Self:
+        self._current.think.append(think)
+        return self
+
+    def text(self, text: str) -> Self:
+        self._current.text.append(text)
+        return self
+
+    def tool_call(self, name: str, arguments: Any = None, *, id: str | None = None) -> Self:
+        self._current.tool_calls.append(ScriptedToolCall(name=name, arguments=arguments, id=id))
+        return self
+
+    def usage(self, input_other: int, output: int, input_cache_read: int = 0) -> Self:
+        self._current.usage = TokenUsage(
+            input_other=input_other, output=output, input_cache_read=input_cache_read
+        )
+        return self
+
+    def turns(self) -> list[ScriptedTurn]:
+        return list(self._turns)
+
+    def build(self, *, delay: float = 0.0, trace: bool = False) -> ScriptedEchoChatProvider:
+        return ScriptedEchoChatProvider(self._turns, delay=delay, trace=trace)
+
+
 class ScriptedEchoChatProvider:
     """
-    A test-only chat provider that consumes a queue of echo DSL scripts per call.
+    A test-only chat provider that consumes a queue of scripts per call.
+
+    Each script is either an echo DSL string or a structured `ScriptedTurn`.
     """
 
     name = "scripted_echo"
 
-    def __init__(self, scripts: Iterable[str], *, trace: bool = False):
-        self._scripts = deque(scripts)
+    def __init__(self, scripts: Iterable[Script], *, delay: float = 0.0, trace: bool = False):
+        self._scripts: deque[Script] = deque(scripts)
         self._turn = 0
+        self._delay = delay
         self._trace = trace
 
+    @classmethod
+    def from_json_file(
+        cls, path: str | Path, *, delay: float = 0.0, trace: bool = False
+    ) -> ScriptedEchoChatProvider:
+        """Create a provider from a JSON fixture of scripted turns."""
+        return cls(load_scripted_turns(path), delay=delay, trace=trace)
+
     @property
     def model_name(self) -> str:
         return "scripted_echo"
@@ -49,18 +166,34 @@ class ScriptedEchoChatProvider:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> ScriptedEchoStreamedMessage:
         if not self._scripts:
             raise ChatProviderError(f"ScriptedEchoChatProvider exhausted at turn {self._turn + 1}.")
-        script_text = self._scripts.popleft()
+        script = self._scripts.popleft()
         if self._trace:
-            script_json = json.dumps(script_text)
+            script_json = (
+                json.dumps(script) if isinstance(script, str) else script.model_dump_json()
+            )
             print(f"SCRIPTED_ECHO TURN {self._turn + 1}: {script_json}")
         self._turn += 1
-        parts, message_id, usage = parse_echo_script(script_text)
+        delay = self._delay
+        if isinstance(script, str):
+            parts, message_id, usage = parse_echo_script(script)
+        else:
+            parts, message_id, usage = script.to_parts(self._turn), script.id, script.usage
+            if script.delay is not None:
+                delay = script.delay
         if not parts:
-            raise ChatProviderError("ScriptedEchoChatProvider DSL produced no streamable parts.")
-        return ScriptedEchoStreamedMessage(parts=parts, message_id=message_id, usage=usage)
+            raise ChatProviderError("ScriptedEchoChatProvider script produced no streamable parts.")
+        if options is not None and options.stop:
+            parts = apply_stop_sequences(parts, options.stop)
+        if options is not None:
+            parts = apply_tool_call_options(parts, options)
+        return ScriptedEchoStreamedMessage(
+            parts=parts, message_id=message_id, usage=usage, delay=delay
+        )
 
     def with_thinking(self, effort: ThinkingEffort) -> Self:
         copied = copy.copy(self)
@@ -77,7 +210,9 @@ class ScriptedEchoStreamedMessage(StreamedMessage):
         parts: list[StreamedMessagePart],
         message_id: str | None,
         usage: TokenUsage | None,
+        delay: float = 0.0,
     ):
+        self._delay = delay
         self._iter = self._to_stream(parts)
         self._id = message_id
         self._usage = usage
@@ -92,6 +227,8 @@ class ScriptedEchoStreamedMessage(StreamedMessage):
         self, parts: list[StreamedMessagePart]
     ) -> AsyncIterator[StreamedMessagePart]:
         for part in parts:
+            if self._delay > 0:
+                await asyncio.sleep(self._delay)
             yield part
 
     @property
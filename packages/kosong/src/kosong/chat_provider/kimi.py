@@ -21,6 +21,8 @@ from typing_extensions import TypedDict
 from kosong.chat_provider import (
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
+    PartialContinuationChatProvider,
     RetryableChatProvider,
     StreamedMessagePart,
     ThinkingEffort,
@@ -30,10 +32,12 @@ from kosong.chat_provider.openai_common import (
     close_replaced_openai_client,
     convert_error,
     create_openai_client,
+    generate_options_to_openai_kwargs,
     tool_to_openai,
 )
 from kosong.message import (
     ContentPart,
+    FilePart,
     Message,
     TextPart,
     ThinkPart,
@@ -48,6 +52,7 @@ if TYPE_CHECKING:
 
     def type_check(kimi: "Kimi"):
         _: ChatProvider = kimi
+        _: PartialContinuationChatProvider = kimi
         _: RetryableChatProvider = kimi
 
 
@@ -154,6 +159,8 @@ class Kimi:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "KimiStreamedMessage":
         messages: list[ChatCompletionMessageParam] = []
         if system_prompt:
@@ -165,6 +172,7 @@ class Kimi:
             "max_tokens": 32000,
         }
         generation_kwargs.update(self._generation_kwargs)
+        generation_kwargs.update(generate_options_to_openai_kwargs(options))
 
         try:
             response = await self.client.chat.completions.create(
@@ -179,6 +187,10 @@ class Kimi:
         except (OpenAIError, httpx.HTTPError) as e:
             raise convert_error(e) from e
 
+    def supports_partial_continuation(self) -> bool:
+        # https://platform.moonshot.ai/docs/guide/use-partial-mode-feature-of-kimi-api
+        return True
+
     def on_retryable_error(self, error: BaseException) -> bool:
         old_client = self.client
         # Read api_key from the live client (not self._api_key) so that
@@ -304,6 +316,11 @@ def _guess_filename(mime_type: str) -> str:
     return f"upload{extension}"
 
 
+def _omitted_file_text(part: FilePart) -> str:
+    name = part.file.name or part.file.media_type or "attachment"
+    return f"[file {name} omitted: Kimi does not accept files]"
+
+
 def _convert_message(message: Message) -> ChatCompletionMessageParam:
     message = message.model_copy(deep=True)
     reasoning_content: str = ""
@@ -313,6 +330,9 @@ def _convert_message(message: Message) -> ChatCompletionMessageParam:
         if isinstance(part, ThinkPart):
             has_reasoning = True
             reasoning_content += part.think
+        elif isinstance(part, FilePart):
+            # The Kimi API does not accept file parts in messages, so tell the model what is missing
+            content.append(TextPart(text=_omitted_file_text(part)))
         else:
             content.append(part)
     message.content = content
@@ -4,6 +4,7 @@ from typing import TYPE_CHECKING, Self
 
 from kosong.chat_provider import (
     ChatProvider,
+    GenerateOptions,
     StreamedMessage,
     StreamedMessagePart,
     ThinkingEffort,
@@ -45,6 +46,8 @@ class MockChatProvider(ChatProvider):
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "MockStreamedMessage":
         """Always return the predefined message parts."""
         return MockStreamedMessage(self._message_parts)
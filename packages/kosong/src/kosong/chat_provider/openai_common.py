@@ -1,7 +1,7 @@
 import asyncio
 import inspect
 import re
-from collections.abc import Awaitable, Mapping
+from collections.abc import Awaitable, Mapping, Sequence
 from typing import Any, cast
 
 import httpx
@@ -15,9 +15,12 @@ from kosong.chat_provider import (
     APIStatusError,
     APITimeoutError,
     ChatProviderError,
+    GenerateOptions,
+    NamedToolChoice,
     ThinkingEffort,
     convert_httpx_error,
 )
+from kosong.message import ContentPart, FilePart
 from kosong.tooling import Tool
 
 
@@ -160,3 +163,43 @@ def tool_to_openai(tool: Tool) -> ChatCompletionToolParam:
             "parameters": tool.parameters,
         },
     }
+
+
+def content_parts_to_openai(parts: Sequence[ContentPart]) -> list[dict[str, Any]]:
+    """
+    Dump content parts in Chat Completions format. File parts become `file` parts when they
+    carry inline data or a file ID, and are skipped when they are remote URLs, which the Chat
+    Completions API cannot read.
+    """
+    dumped: list[dict[str, Any]] = []
+    for part in parts:
+        if not isinstance(part, FilePart):
+            dumped.append(part.model_dump())
+        elif part.file.url.startswith("data:"):
+            file_data = {"file_data": part.file.url, "filename": part.file.name or "file"}
+            dumped.append({"type": "file", "file": file_data})
+        elif part.file.is_file_id:
+            dumped.append({"type": "file", "file": {"file_id": part.file.url}})
+    return dumped
+
+
+def generate_options_to_openai_kwargs(options: GenerateOptions | None) -> dict[str, Any]:
+    """Convert per-call `GenerateOptions` to Chat Completions request kwargs."""
+    if options is None:
+        return {}
+    kwargs: dict[str, Any] = {}
+    if options.max_output_tokens is not None:
+        kwargs["max_tokens"] = options.max_output_tokens
+    if options.stop:
+        kwargs["stop"] = list(options.stop)
+    if options.temperature is not None:
+        kwargs["temperature"] = options.temperature
+    if options.top_p is not None:
+        kwargs["top_p"] = options.top_p
+    if isinstance(options.tool_choice, NamedToolChoice):
+        kwargs["tool_choice"] = {"type": "function", "function": {"name": options.tool_choice.name}}
+    elif options.tool_choice != "auto":
+        kwargs["tool_choice"] = options.tool_choice
+    if options.parallel_tool_calls is not None:
+        kwargs["parallel_tool_calls"] = options.parallel_tool_calls
+    return kwargs
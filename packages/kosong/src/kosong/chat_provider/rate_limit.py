@@ -0,0 +1,187 @@
+"""
+Pacing the requests to a rate-limited API across all the callers that share it.
+
+A `RateLimiter` caps the number of requests in flight and the number of requests started per
+minute. When the API answers 429 Too Many Requests, the limiter halves its concurrency limit,
+empties its token bucket and holds every caller back for a cooldown, which doubles with each
+further 429. Each run of successful requests then raises the limit by one until it is back to
+the configured value. This way one caller hitting the rate limit slows down everyone sharing the
+API, instead of each of them retrying on its own and amplifying the 429s.
+
+Pass the limiter to `kosong.generate` or `kosong.step` as `rate_limiter`.
+"""
+
+import asyncio
+import time
+from collections.abc import AsyncIterator
+from contextlib import asynccontextmanager
+from dataclasses import dataclass
+from typing import Protocol
+
+from kosong.chat_provider import APIStatusError
+
+INITIAL_COOLDOWN = 1.0
+"""The seconds callers are held back after a first 429."""
+MAX_COOLDOWN = 60.0
+"""The upper bound of the cooldown, which doubles with each consecutive 429."""
+
+
+class Clock(Protocol):
+    """The source of time of a `RateLimiter`, replaceable in tests."""
+
+    def now(self) -> float:
+        """The current time in seconds, monotonic."""
+        ...
+
+    async def sleep(self, seconds: float) -> None: ...
+
+
+class MonotonicClock:
+    def now(self) -> float:
+        return time.monotonic()
+
+    async def sleep(self, seconds: float) -> None:
+        await asyncio.sleep(seconds)
+
+
+@dataclass(frozen=True, slots=True)
+class RateLimitState:
+    """A snapshot of a `RateLimiter`."""
+
+    queued: int
+    """The number of requests waiting to be sent."""
+    in_flight: int
+    """The number of requests sent and not finished yet."""
+    concurrency_limit: int | None
+    """The current limit of requests in flight, lowered after a 429. None means no limit."""
+    cooldown_remaining: float
+    """The seconds left before requests are sent again after a 429, 0 if not cooling down."""
+
+
+class RateLimiter:
+    """
+    A concurrency limiter and token bucket that backs off adaptively on 429 responses.
+
+    The token bucket refills at `requests_per_minute / 60` tokens per second and holds at most
+    `requests_per_minute` tokens. Both limits are optional; without them, the limiter only paces
+    requests after a 429.
+    """
+
+    def __init__(
+        self,
+        *,
+        max_concurrent_requests: int | None = None,
+        requests_per_minute: int | None = None,
+        clock: Clock | None = None,
+    ) -> None:
+        if max_concurrent_requests is not None and max_concurrent_requests < 1:
+            raise ValueError("max_concurrent_requests must be at least 1")
+        if requests_per_minute is not None and requests_per_minute < 1:
+            raise ValueError("requests_per_minute must be at least 1")
+        self._clock: Clock = clock or MonotonicClock()
+        self._max_concurrent = max_concurrent_requests
+        self._limit = max_concurrent_requests
+        self._recover_to: int | None = None
+        """The limit to climb back to after a 429, None when not recovering."""
+        self._successes = 0
+        self._capacity = float(requests_per_minute) if requests_per_minute else None
+        self._tokens = self._capacity or 0.0
+        self._refilled_at = self._clock.now()
+        self._cooldown = 0.0
+        self._cooldown_until = self._refilled_at
+        self._in_flight = 0
+        self._queued = 0
+        self._waiters: list[asyncio.Event] = []
+
+    def state(self) -> RateLimitState:
+        return RateLimitState(
+            queued=self._queued,
+            in_flight=self._in_flight,
+            concurrency_limit=self._limit,
+            cooldown_remaining=max(0.0, self._cooldown_until - self._clock.now()),
+        )
+
+    def would_wait(self) -> bool:
+        """Whether a request sent now would have to wait."""
+        return self._queued > 0 or self._wait_time() is not None
+
+    @asynccontextmanager
+    async def request(self) -> AsyncIterator[None]:
+        """
+        Hold a slot for one request. A 429 raised inside the block as `APIStatusError` makes
+        the limiter back off; a block that completes helps it recover.
+        """
+        await self.acquire()
+        try:
+            yield
+        except BaseException as e:
+            self.release(e)
+            raise
+        self.release()
+
+    async def acquire(self) -> None:
+        """Wait until a request can be sent, and count it as in flight."""
+        self._queued += 1
+        try:
+            while (delay := self._wait_time()) is not None:
+                if delay > 0:
+                    await self._clock.sleep(delay)
+                    continue
+                event = asyncio.Event()
+                self._waiters.append(event)
+                await event.wait()
+        finally:
+            self._queued -= 1
+        if self._capacity is not None:
+            self._tokens -= 1
+        self._in_flight += 1
+
+    def release(self, error: BaseException | None = None) -> None:
+        """Finish a request started by `acquire`, with the error it failed with, if any."""
+        if isinstance(error, APIStatusError) and error.status_code == 429:
+            self._back_off()
+        elif error is None:
+            self._recover()
+        self._in_flight -= 1
+        waiters, self._waiters = self._waiters, []
+        for event in waiters:
+            event.set()
+
+    def _wait_time(self) -> float | None:
+        """None if a request can be sent now, else the seconds to sleep or 0 to wait for a slot."""
+        now = self._clock.now()
+        if now < self._cooldown_until:
+            return self._cooldown_until - now
+        if self._limit is not None and self._in_flight >= self._limit:
+            return 0.0
+        if self._capacity is not None:
+            rate = self._capacity / 60
+            self._tokens = min(self._capacity, self._tokens + (now - self._refilled_at) * rate)
+            self._refilled_at = now
+            if self._tokens < 1:
+                return (1 - self._tokens) / rate
+        return None
+
+    def _back_off(self) -> None:
+        current = self._limit if self._limit is not None else self._in_flight
+        if self._recover_to is None:
+            self._recover_to = current
+        self._limit = max(1, current // 2)
+        self._successes = 0
+        self._tokens = 0.0
+        self._refilled_at = self._clock.now()
+        self._cooldown = min(MAX_COOLDOWN, self._cooldown * 2) or INITIAL_COOLDOWN
+        self._cooldown_until = self._clock.now() + self._cooldown
+
+    def _recover(self) -> None:
+        self._cooldown = 0.0
+        if self._recover_to is None or self._limit is None:
+            return
+        self._successes += 1
+        if self._successes < self._limit:
+            return
+        self._successes = 0
+        self._limit += 1
+        if self._limit >= self._recover_to:
+            self._limit = self._max_concurrent
+            self._recover_to = None
@@ -6,8 +6,10 @@ except ModuleNotFoundError as exc:
         'Install with `pip install "kosong[contrib]"`.'
     ) from exc
 
+import base64
 import copy
 import json
+import mimetypes
 import re
 from collections.abc import AsyncIterator, Mapping, Sequence
 from typing import TYPE_CHECKING, Any, Literal, Self, TypedDict, Unpack, cast
@@ -40,8 +42,10 @@ from anthropic import (
 from anthropic.lib.streaming import MessageStopEvent
 from anthropic.types import (
     Base64ImageSourceParam,
+    Base64PDFSourceParam,
     CacheControlEphemeralParam,
     ContentBlockParam,
+    DocumentBlockParam,
     ImageBlockParam,
     MessageDeltaEvent,
     MessageDeltaUsage,
@@ -49,6 +53,7 @@ from anthropic.types import (
     MessageStartEvent,
     MetadataParam,
     OutputConfigParam,
+    PlainTextSourceParam,
     RawContentBlockDeltaEvent,
     RawContentBlockStartEvent,
     RawMessageStreamEvent,
@@ -60,6 +65,7 @@ from anthropic.types import (
     ToolResultBlockParam,
     ToolUseBlockParam,
     URLImageSourceParam,
+    URLPDFSourceParam,
     Usage,
 )
 from anthropic.types import (
@@ -73,6 +79,8 @@ from kosong.chat_provider import (
     APITimeoutError,
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
+    NamedToolChoice,
     StreamedMessagePart,
     ThinkingEffort,
     TokenUsage,
@@ -81,6 +89,7 @@ from kosong.chat_provider import (
 from kosong.contrib.chat_provider.common import ToolMessageConversion
 from kosong.message import (
     ContentPart,
+    FilePart,
     ImageURLPart,
     Message,
     TextPart,
@@ -281,6 +290,8 @@ class Anthropic:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "AnthropicStreamedMessage":
         # https://docs.claude.com/en/api/messages#body-messages
         # Anthropic API does not support system roles, but just a system prompt.
@@ -341,6 +352,8 @@ class Anthropic:
                         pass
         generation_kwargs: dict[str, Any] = {}
         generation_kwargs.update(self._generation_kwargs)
+        if options is not None:
+            _apply_generate_options(generation_kwargs, options)
         betas = generation_kwargs.pop("beta_features", [])
         extra_headers = {
             **{"anthropic-beta": ",".join(str(e) for e in betas)},
@@ -472,6 +485,10 @@ class Anthropic:
                 blocks.append(TextBlockParam(type="text", text=part.text))
             elif isinstance(part, ImageURLPart):
                 blocks.append(_image_url_part_to_anthropic(part))
+            elif isinstance(part, FilePart):
+                # Anthropic only reads PDF and plain text documents, other files are skipped.
+                if (document := _file_part_to_anthropic(part)) is not None:
+                    blocks.append(document)
             elif isinstance(part, ThinkPart):
                 if part.encrypted is None:
                     # missing signature, strip this thinking block.
@@ -701,6 +718,37 @@ def _image_url_part_to_anthropic(part: ImageURLPart) -> ImageBlockParam:
         )
 
 
+def _file_part_to_anthropic(part: FilePart) -> DocumentBlockParam | None:
+    """Convert a file part to a document block, or None if Anthropic cannot read the file."""
+    file = part.file
+    media_type = file.media_type
+    source: Base64PDFSourceParam | PlainTextSourceParam | URLPDFSourceParam
+    if file.url.startswith("data:"):
+        res = file.url[5:].split(";base64,", 1)
+        if len(res) != 2:
+            raise ChatProviderError(f"Invalid data URL for file: {file.name or file.url[:64]}")
+        data = res[1]
+        if media_type == "application/pdf":
+            source = Base64PDFSourceParam(type="base64", media_type="application/pdf", data=data)
+        elif media_type == "text/plain":
+            text = base64.b64decode(data).decode("utf-8", errors="replace")
+            source = PlainTextSourceParam(type="text", media_type="text/plain", data=text)
+        else:
+            return None
+    elif file.is_file_id:
+        # Files uploaded to the Anthropic Files API are only usable through the beta API.
+        return None
+    else:
+        media_type = media_type or mimetypes.guess_type(file.url)[0]
+        if media_type != "application/pdf":
+            return None
+        source = URLPDFSourceParam(type="url", url=file.url)
+    document = DocumentBlockParam(type="document", source=source)
+    if file.name:
+        document["title"] = file.name
+    return document
+
+
 def _convert_error(error: AnthropicError | httpx.HTTPError) -> ChatProviderError:
     # httpx errors may leak through the Anthropic SDK during streaming;
     # delegate to the shared converter.
@@ -721,3 +769,38 @@ def _convert_error(error: AnthropicError | httpx.HTTPError) -> ChatProviderError
     if isinstance(error, AnthropicAPIConnectionError):
         return APIConnectionError(str(error))
     return ChatProviderError(f"Anthropic error: {error}")
+
+
+def _apply_generate_options(generation_kwargs: dict[str, Any], options: GenerateOptions) -> None:
+    """Overlay per-call options onto Anthropic generation kwargs in place."""
+    thinking = generation_kwargs.get("thinking")
+    thinking_type = thinking.get("type") if isinstance(thinking, dict) else None
+    if options.max_output_tokens is not None:
+        max_tokens = options.max_output_tokens
+        # Budget-based thinking requires `max_tokens` to exceed the thinking budget.
+        if thinking_type == "enabled":
+            max_tokens += int(cast(dict[str, Any], thinking).get("budget_tokens", 0))
+        generation_kwargs["max_tokens"] = max_tokens
+    if options.stop:
+        generation_kwargs["stop_sequences"] = list(options.stop)
+    # Sampling parameters cannot be changed while extended thinking is on.
+    if thinking_type in (None, "disabled"):
+        if options.temperature is not None:
+            generation_kwargs["temperature"] = options.temperature
+        if options.top_p is not None:
+            generation_kwargs["top_p"] = options.top_p
+    forced_tool_choice: dict[str, Any] | None = None
+    if isinstance(options.tool_choice, NamedToolChoice):
+        forced_tool_choice = {"type": "tool", "name": options.tool_choice.name}
+    elif options.tool_choice == "required":
+        forced_tool_choice = {"type": "any"}
+    elif options.tool_choice == "none":
+        generation_kwargs["tool_choice"] = {"type": "none"}
+    # Nor can tool use be forced while extended thinking is on.
+    if forced_tool_choice is not None and thinking_type in (None, "disabled"):
+        configured = generation_kwargs.get("tool_choice")
+        if isinstance(configured, dict) and "disable_parallel_tool_use" in configured:
+            forced_tool_choice["disable_parallel_tool_use"] = configured[
+                "disable_parallel_tool_use"
+            ]
+        generation_kwargs["tool_choice"] = forced_tool_choice
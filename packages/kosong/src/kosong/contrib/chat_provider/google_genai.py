@@ -20,6 +20,8 @@ from google.genai import errors as genai_errors
 from google.genai.types import (
     Content,
     FunctionCall,
+    FunctionCallingConfig,
+    FunctionCallingConfigMode,
     FunctionDeclaration,
     FunctionResponse,
     FunctionResponsePart,
@@ -39,6 +41,8 @@ from kosong.chat_provider import (
     APITimeoutError,
     ChatProvider,
     ChatProviderError,
+    GenerateOptions,
+    NamedToolChoice,
     StreamedMessagePart,
     ThinkingEffort,
     TokenUsage,
@@ -47,6 +51,7 @@ from kosong.chat_provider import (
 from kosong.message import (
     AudioURLPart,
     ContentPart,
+    FilePart,
     ImageURLPart,
     Message,
     TextPart,
@@ -90,12 +95,15 @@ class GoogleGenAI:
         stream: bool = True,
         vertexai: bool | None = None,
         default_headers: dict[str, str] | None = None,
+        http_client: httpx.AsyncClient | None = None,
         **client_kwargs: Any,
     ):
         self._model = model
         self._stream = stream
         self._base_url = base_url
-        http_options = HttpOptions(base_url=base_url, headers=default_headers)
+        http_options = HttpOptions(
+            base_url=base_url, headers=default_headers, httpx_async_client=http_client
+        )
         self._client: genai_client.Client = genai.Client(
             http_options=http_options,
             api_key=api_key,
@@ -144,12 +152,39 @@ class GoogleGenAI:
         system_prompt: str,
         tools: Sequence[KosongTool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "GoogleGenAIStreamedMessage":
         contents = messages_to_google_genai_contents(history)
 
         config = GenerateContentConfig(**self._generation_kwargs)
         config.system_instruction = system_prompt
         config.tools = [tool_to_google_genai(tool) for tool in tools]
+        if options is not None:
+            if options.max_output_tokens is not None:
+                config.max_output_tokens = options.max_output_tokens
+            if options.stop:
+                config.stop_sequences = list(options.stop)
+            if options.temperature is not None:
+                config.temperature = options.temperature
+            if options.top_p is not None:
+                config.top_p = options.top_p
+            if isinstance(options.tool_choice, NamedToolChoice):
+                config.tool_config = ToolConfig(
+                    function_calling_config=FunctionCallingConfig(
+                        mode=FunctionCallingConfigMode.ANY,
+                        allowed_function_names=[options.tool_choice.name],
+                    )
+                )
+            elif options.tool_choice != "auto":
+                mode = (
+                    FunctionCallingConfigMode.NONE
+                    if options.tool_choice == "none"
+                    else FunctionCallingConfigMode.ANY
+                )
+                config.tool_config = ToolConfig(
+                    function_calling_config=FunctionCallingConfig(mode=mode)
+                )
 
         try:
             if self._stream:
@@ -447,6 +482,24 @@ def _audio_url_part_to_google_genai(part: AudioURLPart) -> Part:
         return Part.from_bytes(data=data_bytes, mime_type=mime_type)
 
 
+def _file_part_to_google_genai(part: FilePart) -> Part | None:
+    """Convert a file part to GoogleGenAI format, or None for provider file IDs."""
+    file = part.file
+
+    # Handle data URLs
+    if file.url.startswith("data:"):
+        res = file.url[5:].split(";base64,", 1)
+        if len(res) != 2:
+            raise ChatProviderError(f"Invalid data URL for file: {file.name or file.url[:64]}")
+        data_bytes = base64.b64decode(res[1])
+        return Part.from_bytes(data=data_bytes, mime_type=file.media_type or res[0])
+    if file.is_file_id:
+        # Gemini refers to uploaded files by their URI, not by a bare ID
+        return None
+    mime_type = file.media_type or mimetypes.guess_type(file.url)[0] or "application/octet-stream"
+    return Part.from_uri(file_uri=file.url, mime_type=mime_type)
+
+
 def _tool_result_to_response_and_parts(
     parts: list[ContentPart],
 ) -> tuple[dict[str, str], list[FunctionResponsePart]]:
@@ -643,6 +696,9 @@ def message_to_google_genai(message: Message) -> Content:
             parts.append(_image_url_part_to_google_genai(part))
         elif isinstance(part, AudioURLPart):
             parts.append(_audio_url_part_to_google_genai(part))
+        elif isinstance(part, FilePart):
+            if (file_part := _file_part_to_google_genai(part)) is not None:
+                parts.append(file_part)
         elif isinstance(part, ThinkPart):
             # Note: skip part.thought because it is synthetic
             continue
@@ -16,6 +16,7 @@ from typing_extensions import TypedDict
 
 from kosong.chat_provider import (
     ChatProvider,
+    GenerateOptions,
     RetryableChatProvider,
     StreamedMessagePart,
     ThinkingEffort,
@@ -23,14 +24,24 @@ from kosong.chat_provider import (
 )
 from kosong.chat_provider.openai_common import (
     close_replaced_openai_client,
+    content_parts_to_openai,
     convert_error,
     create_openai_client,
+    generate_options_to_openai_kwargs,
     reasoning_effort_to_thinking_effort,
     thinking_effort_to_reasoning_effort,
     tool_to_openai,
 )
 from kosong.contrib.chat_provider.common import ToolMessageConversion
-from kosong.message import ContentPart, Message, TextPart, ThinkPart, ToolCall, ToolCallPart
+from kosong.message import (
+    ContentPart,
+    FilePart,
+    Message,
+    TextPart,
+    ThinkPart,
+    ToolCall,
+    ToolCallPart,
+)
 from kosong.tooling import Tool
 
 if TYPE_CHECKING:
@@ -116,6 +127,8 @@ class OpenAILegacy:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "OpenAILegacyStreamedMessage":
         messages: list[ChatCompletionMessageParam] = []
         if system_prompt:
@@ -125,6 +138,7 @@ class OpenAILegacy:
 
         generation_kwargs: dict[str, Any] = {}
         generation_kwargs.update(self._generation_kwargs)
+        generation_kwargs.update(generate_options_to_openai_kwargs(options))
 
         reasoning_effort = self._reasoning_effort
         # Auto-enable reasoning_effort when the history contains ThinkPart but reasoning
@@ -215,6 +229,8 @@ class OpenAILegacy:
         else:
             message.content = content
         dumped_message = message.model_dump(exclude_none=True)
+        if any(isinstance(part, FilePart) for part in message.content):
+            dumped_message["content"] = content_parts_to_openai(message.content)
         if has_reasoning and self._reasoning_key:
             dumped_message[self._reasoning_key] = reasoning_content
         return cast(ChatCompletionMessageParam, dumped_message)
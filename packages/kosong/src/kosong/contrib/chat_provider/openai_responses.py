@@ -32,6 +32,8 @@ from openai.types.shared_params.responses_model import ResponsesModel
 
 from kosong.chat_provider import (
     ChatProvider,
+    GenerateOptions,
+    NamedToolChoice,
     RetryableChatProvider,
     StreamedMessagePart,
     ThinkingEffort,
@@ -48,6 +50,7 @@ from kosong.contrib.chat_provider.common import ToolMessageConversion
 from kosong.message import (
     AudioURLPart,
     ContentPart,
+    FilePart,
     ImageURLPart,
     Message,
     TextPart,
@@ -154,6 +157,8 @@ class OpenAIResponses:
         system_prompt: str,
         tools: Sequence[Tool],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> "OpenAIResponsesStreamedMessage":
         inputs: ResponseInputParam = []
         if system_prompt:
@@ -168,6 +173,23 @@ class OpenAIResponses:
 
         generation_kwargs: dict[str, Any] = {}
         generation_kwargs.update(self._generation_kwargs)
+        # The Responses API has no stop sequences; `options.stop` is ignored.
+        if options is not None:
+            if options.max_output_tokens is not None:
+                generation_kwargs["max_output_tokens"] = options.max_output_tokens
+            if options.temperature is not None:
+                generation_kwargs["temperature"] = options.temperature
+            if options.top_p is not None:
+                generation_kwargs["top_p"] = options.top_p
+            if isinstance(options.tool_choice, NamedToolChoice):
+                generation_kwargs["tool_choice"] = {
+                    "type": "function",
+                    "name": options.tool_choice.name,
+                }
+            elif options.tool_choice != "auto":
+                generation_kwargs["tool_choice"] = options.tool_choice
+            if options.parallel_tool_calls is not None:
+                generation_kwargs["parallel_tool_calls"] = options.parallel_tool_calls
         reasoning_effort = generation_kwargs.pop("reasoning_effort", None)
         if reasoning_effort is not None:
             generation_kwargs["reasoning"] = Reasoning(
@@ -372,6 +394,8 @@ def _content_parts_to_input_items(parts: list[ContentPart]) -> ResponseInputMess
             mapped = _map_audio_url_to_input_item(part.audio_url.url)
             if mapped is not None:
                 items.append(mapped)
+        elif isinstance(part, FilePart):
+            items.append(_map_file_part_to_input_item(part))
         else:
             # Unknown content – ignore
             continue
@@ -441,6 +465,24 @@ def _map_audio_url_to_input_item(url: str) -> ResponseInputFileParam | None:
     return None
 
 
+def _map_file_part_to_input_item(part: FilePart) -> ResponseInputFileParam:
+    """Map a file part to an input_file item, by inline data, file ID or URL."""
+    file = part.file
+    item: ResponseInputFileParam = {"type": "input_file"}
+    if file.url.startswith("data:"):
+        # Inline file data must come with a file name
+        item["file_data"] = file.url
+        item["filename"] = file.name or "file"
+        return item
+    if file.is_file_id:
+        item["file_id"] = file.url
+    else:
+        item["file_url"] = file.url
+    if file.name:
+        item["filename"] = file.name
+    return item
+
+
 def _map_audio_url_to_file_content(url: str) -> ResponseInputFileContentParam | None:
     """Map audio URL/data URI to a file content item for function_call_output."""
     if url.startswith("http://") or url.startswith("https://"):
@@ -1,5 +1,8 @@
+import uuid
 from abc import ABC
-from typing import Any, ClassVar, Literal, cast, override
+from collections.abc import Sequence
+from dataclasses import dataclass
+from typing import Any, ClassVar, Literal, Self, cast, override
 
 from pydantic import BaseModel, GetCoreSchemaHandler, field_serializer, field_validator
 from pydantic_core import core_schema
@@ -171,6 +174,47 @@ class VideoURLPart(ContentPart):
     video_url: VideoURL
 
 
+class FilePart(ContentPart):
+    """
+    A file attachment other than an image, audio or video, e.g. a PDF document.
+
+    >>> FilePart(
+    ...     file=FilePart.File(url="https://example.com/report.pdf", name="report.pdf")
+    ... ).model_dump(exclude_none=True)
+    {'type': 'file', 'file': {'url': 'https://example.com/report.pdf', 'name': 'report.pdf'}}
+    """
+
+    class File(BaseModel):
+        """File payload."""
+
+        url: str
+        """
+        The URL of the file, can be data URI scheme like `data:application/pdf;base64,...`, or
+        the ID of a file uploaded to the provider, like `file-abc123`.
+        """
+        name: str | None = None
+        """The file name, to tell the LLM what the file is."""
+        mime_type: str | None = None
+        """The MIME type of the file. Taken from the data URI when not set."""
+
+        @property
+        def is_file_id(self) -> bool:
+            """Whether `url` is the ID of a file uploaded to the provider rather than a URL."""
+            return ":" not in self.url
+
+        @property
+        def media_type(self) -> str | None:
+            """The MIME type of the file, from `mime_type` or the data URI."""
+            if self.mime_type:
+                return self.mime_type
+            if self.url.startswith("data:"):
+                return self.url[5:].split(";", 1)[0].split(",", 1)[0] or None
+            return None
+
+    type: str = "file"
+    file: File
+
+
 class ToolCall(BaseModel, MergeableMixin):
     """
     A tool call requested by the assistant.
@@ -227,6 +271,22 @@ class ToolCallPart(BaseModel, MergeableMixin):
         return True
 
 
+class ToolCallPreview(BaseModel):
+    """
+    A best-effort view of the arguments of a tool call that is still streaming, for display only.
+
+    The arguments are parsed leniently from the fragments received so far, so string values may
+    be cut short and trailing fields may be missing. Previews are never merged into the message.
+    """
+
+    id: str
+    """The ID of the tool call."""
+    name: str
+    """The name of the tool to be called."""
+    arguments: dict[str, JsonType]
+    """The fields parsed so far, empty if the arguments do not form a JSON object yet."""
+
+
 type Role = Literal[
     # for OpenAI API, this should be converted to `developer`
     # OpenAI & Kimi support system messages in the middle of the conversation.
@@ -239,6 +299,14 @@ type Role = Literal[
 ]
 """The role of a message sender."""
 
+CHARS_PER_TOKEN = 4
+"""The characters per token assumed by `Message.token_estimate`, about right for English and an
+underestimate for CJK text."""
+NON_TEXT_PART_TOKENS = 1000
+"""The tokens `Message.token_estimate` counts for each image, audio, video or file part."""
+TRUNCATION_MARKER = "…"
+"""The marker `Message.truncated` appends to the text it cuts."""
+
 
 class Message(BaseModel):
     """A message in a conversation."""
@@ -301,3 +369,327 @@ class Message(BaseModel):
     def extract_text(self, sep: str = "") -> str:
         """Extract and concatenate all text parts in the message content."""
         return sep.join(part.text for part in self.content if isinstance(part, TextPart))
+
+    def token_estimate(self) -> int:
+        """
+        Estimate the tokens of the message from the length of its text, thinking and tool calls,
+        counting `NON_TEXT_PART_TOKENS` for each other part. The estimate is a heuristic meant
+        for sizing messages, not a tokenizer.
+
+        >>> Message(role="user", content="x" * 40).token_estimate()
+        10
+        """
+        chars = sum(
+            len(call.function.name) + len(call.function.arguments or "")
+            for call in self.tool_calls or []
+        )
+        non_text_parts = 0
+        for part in self.content:
+            if isinstance(part, TextPart):
+                chars += len(part.text)
+            elif isinstance(part, ThinkPart):
+                chars += len(part.think)
+            else:
+                non_text_parts += 1
+        return chars // CHARS_PER_TOKEN + non_text_parts * NON_TEXT_PART_TOKENS
+
+    def truncated(self, max_tokens: int) -> "Message":
+        """
+        Copy the message, trimming its text and thinking from the end so that its
+        `token_estimate` is at most `max_tokens`. The part that is cut ends with
+        `TRUNCATION_MARKER`, and the text and thinking parts after it are dropped.
+
+        Tool calls and other parts are kept as they are, so the result may still exceed
+        `max_tokens` if they alone do. A thinking part that is cut loses its `encrypted`
+        signature, which no longer matches.
+
+        >>> Message(role="user", content="x" * 40).truncated(5).extract_text()
+        'xxxxxxxxxxxxxxxxxxx…'
+        """
+        message = self.model_copy(deep=True)
+        if message.token_estimate() <= max_tokens:
+            return message
+        fixed = Message(role=self.role, content=[], tool_calls=self.tool_calls).token_estimate()
+        fixed += NON_TEXT_PART_TOKENS * sum(
+            not isinstance(part, TextPart | ThinkPart) for part in self.content
+        )
+        budget = (max_tokens - fixed) * CHARS_PER_TOKEN - len(TRUNCATION_MARKER)
+        content: list[ContentPart] = []
+        cut = False
+        for part in message.content:
+            if not isinstance(part, TextPart | ThinkPart):
+                content.append(part)
+                continue
+            if cut:
+                continue
+            text = part.text if isinstance(part, TextPart) else part.think
+            if len(text) <= budget:
+                budget -= len(text)
+                content.append(part)
+                continue
+            text = text[: max(0, budget)] + TRUNCATION_MARKER
+            if isinstance(part, TextPart):
+                part.text = text
+            else:
+                part.think = text
+                part.encrypted = None
+            content.append(part)
+            cut = True
+        message.content = content
+        return message
+
+    @staticmethod
+    def system() -> "MessageBuilder":
+        """Start building a system message."""
+        return MessageBuilder("system")
+
+    @staticmethod
+    def user() -> "MessageBuilder":
+        """
+        Start building a user message.
+
+        >>> message = Message.user().text("Describe it.").image_url("https://x.com/a.png").build()
+        >>> [part.type for part in message.content]
+        ['text', 'image_url']
+        """
+        return MessageBuilder("user")
+
+    @staticmethod
+    def assistant() -> "MessageBuilder":
+        """
+        Start building an assistant message.
+
+        >>> message = Message.assistant().tool_call("add", '{"a": 2}', id="call_1").build()
+        >>> message.tool_calls[0].model_dump(exclude_none=True)
+        {'type': 'function', 'id': 'call_1', 'function': {'name': 'add', 'arguments': '{"a": 2}'}}
+        """
+        return MessageBuilder("assistant")
+
+    @staticmethod
+    def tool_result(
+        tool_call_id: str, output: "str | ContentPart | Sequence[ContentPart]"
+    ) -> "Message":
+        """
+        Build the message answering the tool call with the given ID.
+
+        >>> Message.tool_result("call_1", "5").model_dump(exclude_none=True)
+        {'role': 'tool', 'content': '5', 'tool_call_id': 'call_1'}
+        """
+        content = output if isinstance(output, str | ContentPart) else list(output)
+        return Message(role="tool", content=content, tool_call_id=tool_call_id)
+
+
+class MessageBuilder:
+    """
+    A fluent builder of `Message`, created by `Message.user()`, `Message.assistant()` and
+    `Message.system()`. Parts are added to the content in the order of the calls.
+    """
+
+    def __init__(self, role: Role) -> None:
+        self._role: Role = role
+        self._name: str | None = None
+        self._content: list[ContentPart] = []
+        self._tool_calls: list[ToolCall] = []
+        self._partial: bool | None = None
+
+    def name(self, name: str) -> Self:
+        self._name = name
+        return self
+
+    def part(self, part: ContentPart) -> Self:
+        self._content.append(part)
+        return self
+
+    def text(self, text: str) -> Self:
+        return self.part(TextPart(text=text))
+
+    def think(self, think: str, *, encrypted: str | None = None) -> Self:
+        return self.part(ThinkPart(think=think, encrypted=encrypted))
+
+    def image_url(self, url: str, *, id: str | None = None) -> Self:
+        return self.part(ImageURLPart(image_url=ImageURLPart.ImageURL(url=url, id=id)))
+
+    def audio_url(self, url: str, *, id: str | None = None) -> Self:
+        return self.part(AudioURLPart(audio_url=AudioURLPart.AudioURL(url=url, id=id)))
+
+    def video_url(self, url: str, *, id: str | None = None) -> Self:
+        return self.part(VideoURLPart(video_url=VideoURLPart.VideoURL(url=url, id=id)))
+
+    def file(self, url: str, *, name: str | None = None, mime_type: str | None = None) -> Self:
+        return self.part(FilePart(file=FilePart.File(url=url, name=name, mime_type=mime_type)))
+
+    def tool_call(self, name: str, arguments: str | None, *, id: str | None = None) -> Self:
+        """Add a tool call with JSON `arguments`. A random ID is generated if none is given."""
+        self._tool_calls.append(
+            ToolCall(
+                id=id or f"call_{uuid.uuid4().hex[:24]}",
+                function=ToolCall.FunctionBody(name=name, arguments=arguments),
+            )
+        )
+        return self
+
+    def partial(self, partial: bool = True) -> Self:
+        """Mark the message as a prefix for the model to continue."""
+        self._partial = partial
+        return self
+
+    def build(self) -> Message:
+        return Message(
+            role=self._role,
+            name=self._name,
+            content=list(self._content),
+            tool_calls=list(self._tool_calls) or None,
+            partial=self._partial,
+        )
+
+
+def merge_content_parts(parts: Sequence[ContentPart]) -> list[ContentPart]:
+    """
+    Copy the parts, merging each run of consecutive parts that can be merged, such as text
+    deltas or unsigned thinking deltas, into a single part.
+
+    >>> merge_content_parts([TextPart(text="Hel"), TextPart(text="lo"), ThinkPart(think="Hm")])
+    [TextPart(type='text', text='Hello'), ThinkPart(type='think', think='Hm', encrypted=None)]
+    """
+    merged: list[ContentPart] = []
+    for part in parts:
+        part = part.model_copy(deep=True)
+        if merged and merged[-1].merge_in_place(part):
+            continue
+        merged.append(part)
+    return merged
+
+
+type HistoryIssueKind = Literal[
+    "empty_content",
+    "consecutive_assistant",
+    "misplaced_tool_calls",
+    "missing_tool_call_id",
+    "duplicate_tool_call_id",
+    "orphan_tool_result",
+    "unanswered_tool_call",
+    "misplaced_partial",
+]
+"""The rule a message in the history breaks, see `validate_history`."""
+
+
+@dataclass(frozen=True, slots=True)
+class HistoryIssue:
+    """A problem found in a conversation history by `validate_history`."""
+
+    index: int
+    """The index of the offending message in the history."""
+    kind: HistoryIssueKind
+    message: str
+    """A human-readable description of the problem."""
+
+
+def validate_history(history: Sequence[Message]) -> list[HistoryIssue]:
+    """
+    Check a conversation history for problems that chat providers commonly reject:
+
+    - `empty_content`: a message without content, except an assistant message with tool calls.
+    - `consecutive_assistant`: an assistant message directly following another one.
+    - `misplaced_tool_calls`: tool calls on a message that is not from the assistant.
+    - `missing_tool_call_id`: a tool message without `tool_call_id`.
+    - `duplicate_tool_call_id`: a tool call ID used twice in the history.
+    - `orphan_tool_result`: a tool message that does not answer a tool call of the assistant
+      message right before it, or answers one twice.
+    - `unanswered_tool_call`: a tool call without a tool message before the next message from
+      another role, or the end of the history.
+    - `misplaced_partial`: a `partial` message that is not the last one, not from the
+      assistant, or has tool calls.
+
+    Returns:
+        list[HistoryIssue]: The issues in order of the messages, empty if the history is valid.
+
+    >>> validate_history([Message.user().text("Hi").build(), Message.assistant().build()])
+    [HistoryIssue(index=1, kind='empty_content', message='The assistant message has no content.')]
+    """
+    issues: list[HistoryIssue] = []
+    seen_call_ids: set[str] = set()
+    # Tool calls of the last assistant message that have not been answered yet, by ID
+    pending: dict[str, int] = {}
+
+    def flush_pending() -> None:
+        for call_id, index in pending.items():
+            issues.append(
+                HistoryIssue(
+                    index,
+                    "unanswered_tool_call",
+                    f"The tool call `{call_id}` has no tool result.",
+                )
+            )
+        pending.clear()
+
+    for index, message in enumerate(history):
+        if message.role != "tool":
+            flush_pending()
+
+        if not message.content and not (message.role == "assistant" and message.tool_calls):
+            issues.append(
+                HistoryIssue(index, "empty_content", f"The {message.role} message has no content.")
+            )
+        if message.role == "assistant" and index > 0 and history[index - 1].role == "assistant":
+            issues.append(
+                HistoryIssue(
+                    index,
+                    "consecutive_assistant",
+                    "The assistant message directly follows another assistant message.",
+                )
+            )
+        if message.partial and (
+            index != len(history) - 1 or message.role != "assistant" or message.tool_calls
+        ):
+            issues.append(
+                HistoryIssue(
+                    index,
+                    "misplaced_partial",
+                    "Only the last message may be partial, and it must be an assistant message "
+                    "without tool calls.",
+                )
+            )
+
+        if message.tool_calls:
+            if message.role != "assistant":
+                issues.append(
+                    HistoryIssue(
+                        index,
+                        "misplaced_tool_calls",
+                        f"The {message.role} message has tool calls.",
+                    )
+                )
+            for tool_call in message.tool_calls:
+                if tool_call.id in seen_call_ids:
+                    issues.append(
+                        HistoryIssue(
+                            index,
+                            "duplicate_tool_call_id",
+                            f"The tool call ID `{tool_call.id}` is used more than once.",
+                        )
+                    )
+                    continue
+                seen_call_ids.add(tool_call.id)
+                if message.role == "assistant":
+                    pending[tool_call.id] = index
+
+        if message.role == "tool":
+            if message.tool_call_id is None:
+                issues.append(
+                    HistoryIssue(
+                        index, "missing_tool_call_id", "The tool message has no `tool_call_id`."
+                    )
+                )
+            elif pending.pop(message.tool_call_id, None) is None:
+                issues.append(
+                    HistoryIssue(
+                        index,
+                        "orphan_tool_result",
+                        f"The tool message answers `{message.tool_call_id}`, which is not a "
+                        "pending tool call of the preceding assistant message.",
+                    )
+                )
+
+    flush_pending()
+    issues.sort(key=lambda issue: issue.index)
+    return issues
@@ -1,3 +1,4 @@
+import json
 from abc import ABC, abstractmethod
 from asyncio import Future
 from typing import Any, ClassVar, Protocol, Self, cast, override, runtime_checkable
@@ -190,7 +191,8 @@ class CallableTool(Tool, ABC):
         try:
             jsonschema.validate(arguments, self.parameters)
         except jsonschema.ValidationError as e:
-            return ToolValidateError(str(e))
+            path = ".".join(str(part) for part in e.absolute_path)
+            return ToolValidateError(f"`{path}`: {e.message}" if path else e.message)
 
         if isinstance(arguments, list):
             ret = await self.__call__(*arguments)
@@ -229,6 +231,32 @@ class _GenerateJsonSchemaNoTitles(GenerateJsonSchema):
         json_schema.pop("title", None)
 
 
+_RANGE_OPERATORS = {
+    "greater_than_equal": ">=",
+    "greater_than": ">",
+    "less_than_equal": "<=",
+    "less_than": "<",
+}
+
+
+def _describe_validation_error(error: pydantic.ValidationError) -> str:
+    """Describe each invalid argument by its name and, for a number out of range, the range."""
+    descriptions: list[str] = []
+    for e in error.errors(include_url=False):
+        name = ".".join(str(part) for part in e["loc"])
+        operator = _RANGE_OPERATORS.get(e["type"])
+        if operator is not None and e.get("ctx"):
+            limit = next(iter(e["ctx"].values()))
+            got = json.dumps(e["input"], default=str)
+            descriptions.append(f"`{name}` must be {operator} {limit}, got {got}")
+        elif name:
+            descriptions.append(f"`{name}`: {e['msg']}")
+        else:
+            # raised by a model validator, which names the parameters itself
+            descriptions.append(e["msg"].removeprefix("Value error, "))
+    return "; ".join(descriptions)
+
+
 class CallableTool2[Params: BaseModel](ABC):
     """
     The abstract base class of tools that can be called as callables, with typed parameters.
@@ -295,7 +323,7 @@ class CallableTool2[Params: BaseModel](ABC):
         try:
             params = self.params.model_validate(arguments)
         except pydantic.ValidationError as e:
-            return ToolValidateError(str(e))
+            return ToolValidateError(_describe_validation_error(e))
 
         ret = await self.__call__(params)
         if not isinstance(ret, ToolReturnValue):  # type: ignore[reportUnnecessaryIsInstance]
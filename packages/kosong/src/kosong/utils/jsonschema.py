@@ -25,9 +25,17 @@ _COMBINATOR_KEYS = (
 
 
 def deref_json_schema(schema: JsonDict) -> JsonDict:
-    """Expand local `$ref` entries in a JSON Schema without infinite recursion."""
+    """Expand local `$ref` entries in a JSON Schema without infinite recursion.
+
+    Each reference is replaced by its own copy of the target, so a definition referenced twice
+    is inlined twice and the result shares no objects between the two places. A reference to a
+    definition from inside that definition cannot be expanded: it is kept, along with the
+    definitions it needs. The `mapping` of a `discriminator` points at the removed definitions,
+    so it is dropped, keeping the `propertyName` and the `oneOf` branches.
+    """
     # Work on a deep copy so we never mutate the caller's schema.
     full_schema: JsonDict = copy.deepcopy(schema)
+    recursive_refs: set[str] = set()
 
     def resolve_pointer(root: JsonDict, pointer: str) -> JsonType:
         """Resolve a JSON Pointer (e.g. ``#/$defs/User``) inside the schema."""
@@ -43,43 +51,59 @@ def deref_json_schema(schema: JsonDict) -> JsonDict:
         except (KeyError, TypeError, ValueError):
             raise ValueError(f"Unable to resolve reference path: {pointer}") from None
 
-    def traverse(node: JsonType, root: JsonDict) -> JsonType:
-        """Recursively traverse every node to inline local references."""
+    def traverse(node: JsonType, resolving: tuple[str, ...]) -> JsonType:
+        """Recursively traverse every node to inline local references.
+
+        ``resolving`` holds the references being inlined around ``node``.
+        """
         if isinstance(node, dict):
+            ref_path = node.get("$ref")
             # Replace local ``$ref`` entries with their referenced payload.
-            if "$ref" in node and isinstance(node["$ref"], str):
-                ref_path = node["$ref"]
-                if ref_path.startswith("#"):
-                    # Resolve the local reference target.
-                    target = resolve_pointer(root, ref_path)
+            # Remote references such as http://... are left as they are.
+            if isinstance(ref_path, str) and ref_path.startswith("#"):
+                if ref_path in resolving:
+                    recursive_refs.add(ref_path)
+                else:
                     # Recursively inline the target in case it contains more refs.
-                    ref = traverse(target, root)
+                    target = resolve_pointer(full_schema, ref_path)
+                    ref = traverse(target, (*resolving, ref_path))
                     if not isinstance(ref, dict):
                         msg = "Local $ref must resolve to a JSON object"
                         raise TypeError(msg)
-                    node.pop("$ref")
-                    node.update(ref)
-                    return node
-                else:
-                    # Ignore remote references such as http://...
-                    return node
+                    siblings = {k: traverse(v, resolving) for k, v in node.items() if k != "$ref"}
+                    return {**siblings, **ref}
 
             # Traverse the remaining mapping entries.
-            return {k: traverse(v, root) for k, v in node.items()}
+            result = {k: traverse(v, resolving) for k, v in node.items()}
+            discriminator = result.get("discriminator")
+            if isinstance(discriminator, dict) and "mapping" in discriminator:
+                result["discriminator"] = {k: v for k, v in discriminator.items() if k != "mapping"}
+            return result
 
         elif isinstance(node, list):
             # Traverse list members (e.g. allOf, oneOf, items).
-            return [traverse(item, root) for item in node]
+            return [traverse(item, resolving) for item in node]
 
         else:
             return node
 
     # Remove definition buckets to keep the resolved schema minimal.
-    resolved = cast(JsonDict, traverse(full_schema, full_schema))
-
-    # Comment these lines if you want to keep the emitted definitions.
-    resolved.pop("$defs", None)
-    resolved.pop("definitions", None)
+    body = {k: v for k, v in full_schema.items() if k not in ("$defs", "definitions")}
+    resolved = cast(JsonDict, traverse(body, ()))
+
+    # Keep the definitions that recursive references still point to.
+    kept: set[str] = set()
+    while pending := recursive_refs - kept:
+        for ref_path in sorted(pending):
+            kept.add(ref_path)
+            definition = traverse(resolve_pointer(full_schema, ref_path), (ref_path,))
+            *parents, name = ref_path.lstrip("#/").split("/")
+            container = resolved
+            for part in parents:
+                child = container.setdefault(part, {})
+                assert isinstance(child, dict)
+                container = child
+            container[name] = definition
 
     return resolved
 
@@ -12,8 +12,10 @@ from kosong.tooling import Tool
 
 __all__ = [
     "ADD_TOOL",
+    "B64_PDF",
     "B64_PNG",
     "COMMON_CASES",
+    "FILES_MESSAGE",
     "MUL_TOOL",
     "capture_request",
     "make_anthropic_response",
@@ -58,6 +60,19 @@ B64_PNG = (
     "DUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=="
 )
 
+B64_PDF = "JVBERi0xLjQK"
+
+FILES_MESSAGE = (
+    Message.user()
+    .file(f"data:application/pdf;base64,{B64_PDF}", name="report.pdf")
+    .file("data:text/plain;base64,aGVsbG8=", name="notes.txt")
+    .file("https://example.com/spec.pdf")
+    .file("file-abc123", name="slides.pdf")
+    .text("Summarize these.")
+    .build()
+)
+"""A user message attaching a PDF and a text file inline, a remote PDF and an uploaded file."""
+
 ADD_TOOL = Tool(
     name="add",
     description="Add two integers.",
@@ -4,7 +4,15 @@ import json
 
 import pytest
 import respx
-from common import B64_PNG, COMMON_CASES, Case, make_anthropic_response, run_test_cases
+from common import (
+    B64_PDF,
+    B64_PNG,
+    COMMON_CASES,
+    FILES_MESSAGE,
+    Case,
+    make_anthropic_response,
+    run_test_cases,
+)
 from httpx import Response
 from inline_snapshot import snapshot
 
@@ -796,6 +804,45 @@ async def test_anthropic_metadata():
         assert body["metadata"] == snapshot({"user_id": "test-session-id"})
 
 
+async def test_anthropic_file_parts_become_documents():
+    """PDF and plain text files are sent as documents, uploaded file IDs are skipped."""
+    with respx.mock(base_url="https://api.anthropic.com") as mock:
+        mock.post("/v1/messages").mock(return_value=Response(200, json=make_anthropic_response()))
+        provider = Anthropic(
+            model="claude-sonnet-4-20250514",
+            api_key="test-key",
+            default_max_tokens=1024,
+            stream=False,
+        )
+        stream = await provider.generate("", [], [FILES_MESSAGE])
+        async for _ in stream:
+            pass
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert body["messages"][0]["content"] == snapshot(
+            [
+                {
+                    "type": "document",
+                    "source": {"type": "base64", "media_type": "application/pdf", "data": B64_PDF},
+                    "title": "report.pdf",
+                },
+                {
+                    "type": "document",
+                    "source": {"type": "text", "media_type": "text/plain", "data": "hello"},
+                    "title": "notes.txt",
+                },
+                {
+                    "type": "document",
+                    "source": {"type": "url", "url": "https://example.com/spec.pdf"},
+                },
+                {
+                    "type": "text",
+                    "text": "Summarize these.",
+                    "cache_control": {"type": "ephemeral"},
+                },
+            ]
+        )
+
+
 async def test_anthropic_metadata_omitted_when_none():
     """Metadata should not be included in the request when not provided."""
     with respx.mock(base_url="https://api.anthropic.com") as mock:
@@ -3,10 +3,12 @@
 import json
 
 import respx
-from common import COMMON_CASES, Case, make_chat_completion_response, run_test_cases
+from common import COMMON_CASES, FILES_MESSAGE, Case, make_chat_completion_response, run_test_cases
 from httpx import Response
 from inline_snapshot import snapshot
 
+from kosong import generate
+from kosong.chat_provider import GenerateOptions, NamedToolChoice
 from kosong.chat_provider.kimi import Kimi
 from kosong.message import Message, TextPart, ThinkPart, ToolCall
 from kosong.tooling import Tool
@@ -381,6 +383,44 @@ async def test_kimi_message_conversion():
         )
 
 
+async def test_kimi_replaces_file_parts_with_text():
+    with respx.mock(base_url="https://api.moonshot.ai") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = Kimi(model="kimi-k2-turbo-preview", api_key="test-key", stream=False)
+        stream = await provider.generate("", [], [FILES_MESSAGE])
+        async for _ in stream:
+            pass
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert body["messages"] == snapshot(
+            [
+                {
+                    "role": "user",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "[file report.pdf omitted: Kimi does not accept files]",
+                        },
+                        {
+                            "type": "text",
+                            "text": "[file notes.txt omitted: Kimi does not accept files]",
+                        },
+                        {
+                            "type": "text",
+                            "text": "[file attachment omitted: Kimi does not accept files]",
+                        },
+                        {
+                            "type": "text",
+                            "text": "[file slides.pdf omitted: Kimi does not accept files]",
+                        },
+                        {"type": "text", "text": "Summarize these."},
+                    ],
+                }
+            ]
+        )
+
+
 async def test_kimi_generation_kwargs():
     with respx.mock(base_url="https://api.moonshot.ai") as mock:
         mock.post("/v1/chat/completions").mock(
@@ -396,6 +436,71 @@ async def test_kimi_generation_kwargs():
         assert (body["temperature"], body["max_tokens"]) == snapshot((0.7, 2048))
 
 
+async def test_kimi_generate_options():
+    with respx.mock(base_url="https://api.moonshot.ai") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = Kimi(
+            model="kimi-k2-turbo-preview", api_key="test-key", stream=False
+        ).with_generation_kwargs(temperature=0.7)
+        await generate(
+            provider,
+            "",
+            [],
+            [Message(role="user", content="Hi")],
+            options=GenerateOptions(max_output_tokens=512, stop=("<END>",), top_p=0.9),
+        )
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert {k: body[k] for k in ("max_tokens", "stop", "temperature", "top_p")} == snapshot(
+            {"max_tokens": 512, "stop": ["<END>"], "temperature": 0.7, "top_p": 0.9}
+        )
+
+        await generate(provider, "", [], [Message(role="user", content="Hi")])
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert (body["max_tokens"], "stop" in body, "top_p" in body) == snapshot(
+            (32000, False, False)
+        )
+
+
+async def test_kimi_tool_choice():
+    with respx.mock(base_url="https://api.moonshot.ai") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = Kimi(model="kimi-k2-turbo-preview", api_key="test-key", stream=False)
+        history = [Message(role="user", content="Hi")]
+
+        tool_choices: list[object] = []
+        for tool_choice in ("auto", "none", "required", NamedToolChoice("$web_search")):
+            options = GenerateOptions(tool_choice=tool_choice)
+            await generate(provider, "", [BUILTIN_TOOL], history, options=options)
+            body = json.loads(mock.calls.last.request.content.decode())
+            tool_choices.append(body.get("tool_choice"))
+
+        assert tool_choices == snapshot(
+            [None, "none", "required", {"type": "function", "function": {"name": "$web_search"}}]
+        )
+
+
+async def test_kimi_parallel_tool_calls():
+    with respx.mock(base_url="https://api.moonshot.ai") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = Kimi(model="kimi-k2-turbo-preview", api_key="test-key", stream=False)
+        history = [Message(role="user", content="Hi")]
+
+        sent: list[object] = []
+        for parallel_tool_calls in (None, False, True):
+            options = GenerateOptions(tool_choice="none", parallel_tool_calls=parallel_tool_calls)
+            await generate(provider, "", [BUILTIN_TOOL], history, options=options)
+            body = json.loads(mock.calls.last.request.content.decode())
+            sent.append((body["tool_choice"], body.get("parallel_tool_calls")))
+
+        assert sent == snapshot([("none", None), ("none", False), ("none", True)])
+
+
 async def test_kimi_with_thinking():
     with respx.mock(base_url="https://api.moonshot.ai") as mock:
         mock.post("/v1/chat/completions").mock(
@@ -3,10 +3,18 @@
 import json
 
 import respx
-from common import COMMON_CASES, Case, make_chat_completion_response, run_test_cases
+from common import (
+    B64_PDF,
+    COMMON_CASES,
+    FILES_MESSAGE,
+    Case,
+    make_chat_completion_response,
+    run_test_cases,
+)
 from httpx import Response
 from inline_snapshot import snapshot
 
+from kosong.chat_provider import GenerateOptions
 from kosong.contrib.chat_provider.openai_legacy import OpenAILegacy
 from kosong.message import Message, TextPart, ThinkPart
 
@@ -278,6 +286,44 @@ async def test_openai_legacy_reasoning_content():
         )
 
 
+async def test_openai_legacy_file_parts():
+    """Inline files and file IDs are sent as `file` parts, remote file URLs are skipped."""
+    with respx.mock(base_url="https://api.openai.com") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = OpenAILegacy(model="gpt-4o", api_key="test-key", stream=False)
+        stream = await provider.generate("", [], [FILES_MESSAGE])
+        async for _ in stream:
+            pass
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert body["messages"] == snapshot(
+            [
+                {
+                    "role": "user",
+                    "content": [
+                        {
+                            "type": "file",
+                            "file": {
+                                "file_data": f"data:application/pdf;base64,{B64_PDF}",
+                                "filename": "report.pdf",
+                            },
+                        },
+                        {
+                            "type": "file",
+                            "file": {
+                                "file_data": "data:text/plain;base64,aGVsbG8=",
+                                "filename": "notes.txt",
+                            },
+                        },
+                        {"type": "file", "file": {"file_id": "file-abc123"}},
+                        {"type": "text", "text": "Summarize these."},
+                    ],
+                }
+            ]
+        )
+
+
 async def test_openai_legacy_empty_reasoning_content_is_round_tripped():
     with respx.mock(base_url="https://api.openai.com") as mock:
         mock.post("/v1/chat/completions").mock(
@@ -327,6 +373,26 @@ async def test_openai_legacy_generation_kwargs():
         assert (body["temperature"], body["max_tokens"]) == snapshot((0.7, 2048))
 
 
+async def test_openai_legacy_generate_options():
+    with respx.mock(base_url="https://api.openai.com") as mock:
+        mock.post("/v1/chat/completions").mock(
+            return_value=Response(200, json=make_chat_completion_response())
+        )
+        provider = OpenAILegacy(model="gpt-4.1", api_key="test-key", stream=False)
+        stream = await provider.generate(
+            "",
+            [],
+            [Message(role="user", content="Hi")],
+            options=GenerateOptions(max_output_tokens=256, stop=("\n\n",), temperature=0),
+        )
+        async for _ in stream:
+            pass
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert {k: body[k] for k in ("max_tokens", "stop", "temperature")} == snapshot(
+            {"max_tokens": 256, "stop": ["\n\n"], "temperature": 0}
+        )
+
+
 async def test_openai_legacy_with_thinking():
     with respx.mock(base_url="https://api.openai.com") as mock:
         mock.post("/v1/chat/completions").mock(
@@ -4,7 +4,7 @@ import json
 from typing import Any
 
 import respx
-from common import COMMON_CASES, Case, run_test_cases
+from common import B64_PDF, COMMON_CASES, FILES_MESSAGE, Case, run_test_cases
 from httpx import Response
 from inline_snapshot import snapshot
 
@@ -379,6 +379,33 @@ async def test_openai_responses_generation_kwargs():
         assert (body["temperature"], body["max_output_tokens"]) == snapshot((0.7, 2048))
 
 
+async def test_openai_responses_file_parts():
+    with respx.mock(base_url="https://api.openai.com") as mock:
+        mock.post("/v1/responses").mock(return_value=Response(200, json=make_response()))
+        provider = OpenAIResponses(model="gpt-4.1", api_key="test-key", stream=False)
+        stream = await provider.generate("", [], [FILES_MESSAGE])
+        async for _ in stream:
+            pass
+        body = json.loads(mock.calls.last.request.content.decode())
+        assert body["input"][0]["content"] == snapshot(
+            [
+                {
+                    "type": "input_file",
+                    "file_data": f"data:application/pdf;base64,{B64_PDF}",
+                    "filename": "report.pdf",
+                },
+                {
+                    "type": "input_file",
+                    "file_data": "data:text/plain;base64,aGVsbG8=",
+                    "filename": "notes.txt",
+                },
+                {"type": "input_file", "file_url": "https://example.com/spec.pdf"},
+                {"type": "input_file", "file_id": "file-abc123", "filename": "slides.pdf"},
+                {"type": "input_text", "text": "Summarize these."},
+            ]
+        )
+
+
 async def test_openai_responses_omits_reasoning_by_default():
     with respx.mock(base_url="https://api.openai.com") as mock:
         mock.post("/v1/responses").mock(return_value=Response(200, json=make_response()))
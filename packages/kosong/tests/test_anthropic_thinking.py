@@ -4,7 +4,11 @@ import pytest
 
 pytest.importorskip("anthropic", reason="Optional contrib dependency not installed")
 
+from typing import Any
+
+from kosong.chat_provider import GenerateOptions, NamedToolChoice, ToolChoice
 from kosong.contrib.chat_provider.anthropic import (
+    _apply_generate_options,  # pyright: ignore[reportPrivateUsage]
     _clamp_effort,  # pyright: ignore[reportPrivateUsage]
     _supports_adaptive_thinking,  # pyright: ignore[reportPrivateUsage]
     _supports_effort_param,  # pyright: ignore[reportPrivateUsage]
@@ -159,3 +163,36 @@ def test_clamp_effort(model: str, effort: str, expected: str) -> None:
 )
 def test_supports_effort_param(model: str, expected: bool) -> None:
     assert _supports_effort_param(model) is expected
+
+
+@pytest.mark.parametrize(
+    "thinking,tool_choice,expected",
+    [
+        (None, "auto", {"type": "auto", "disable_parallel_tool_use": True}),
+        (None, "none", {"type": "none"}),
+        (None, "required", {"type": "any", "disable_parallel_tool_use": True}),
+        (
+            None,
+            NamedToolChoice("Shell"),
+            {"type": "tool", "name": "Shell", "disable_parallel_tool_use": True},
+        ),
+        # Tool use cannot be forced while extended thinking is on.
+        ({"type": "enabled", "budget_tokens": 1024}, "required", {"type": "auto"}),
+        ({"type": "adaptive"}, NamedToolChoice("Shell"), {"type": "auto"}),
+        ({"type": "adaptive"}, "none", {"type": "none"}),
+    ],
+)
+def test_apply_generate_options_tool_choice(
+    thinking: dict[str, Any] | None, tool_choice: ToolChoice, expected: dict[str, Any]
+) -> None:
+    generation_kwargs: dict[str, Any] = {
+        "tool_choice": {"type": "auto"}
+        if thinking is not None
+        else {"type": "auto", "disable_parallel_tool_use": True}
+    }
+    if thinking is not None:
+        generation_kwargs["thinking"] = thinking
+
+    _apply_generate_options(generation_kwargs, GenerateOptions(tool_choice=tool_choice))
+
+    assert generation_kwargs["tool_choice"] == expected
@@ -0,0 +1,105 @@
+from pathlib import Path
+
+import pytest
+
+from kosong import generate
+from kosong.chat_provider import GenerateOptions, StreamedMessagePart, TokenUsage
+from kosong.chat_provider.cache import (
+    CachingChatProvider,
+    Cassette,
+    CassetteMissError,
+)
+from kosong.chat_provider.echo import EchoChatProvider
+from kosong.message import Message, TextPart, ThinkPart, ToolCall, ToolCallPart
+
+_SCRIPT = "\n".join(
+    [
+        "id: echo-1",
+        'usage: {"input_other": 7, "output": 3}',
+        "think: hmm",
+        "text: Hello,",
+        "text:  world!",
+        'tool_call: {"id": "call-1", "name": "search", "arguments": "{\\"q\\":"}',
+        'tool_call_part: {"arguments_part": "\\"kosong\\"}"}',
+    ]
+)
+
+
+async def _stream(provider: CachingChatProvider, history: list[Message]):
+    stream = await provider.generate("You are a test.", [], history)
+    parts: list[StreamedMessagePart] = [part async for part in stream]
+    return stream, parts
+
+
+async def test_record_then_replay_without_the_provider(tmp_path: Path):
+    cassette = Cassette(tmp_path / "cassette")
+    history = [Message(role="user", content=_SCRIPT)]
+
+    recorder = CachingChatProvider(EchoChatProvider(), cassette, mode="record")
+    recorded, recorded_parts = await _stream(recorder, history)
+    assert len(list(cassette.directory.glob("*.json"))) == 1
+
+    replayer = CachingChatProvider(None, cassette, mode="replay", model_name="echo")
+    replayed, replayed_parts = await _stream(replayer, history)
+
+    assert replayed_parts == recorded_parts
+    assert replayed_parts == [
+        ThinkPart(think="hmm"),
+        TextPart(text="Hello,"),
+        TextPart(text=" world!"),
+        ToolCall(id="call-1", function=ToolCall.FunctionBody(name="search", arguments='{"q":')),
+        ToolCallPart(arguments_part='"kosong"}'),
+    ]
+    assert replayed.id == recorded.id == "echo-1"
+    assert replayed.usage == recorded.usage == TokenUsage(input_other=7, output=3)
+
+    result = await generate(replayer, "You are a test.", [], history)
+    assert result.message.extract_text() == "Hello, world!"
+    assert result.message.tool_calls == [
+        ToolCall(
+            id="call-1", function=ToolCall.FunctionBody(name="search", arguments='{"q":"kosong"}')
+        )
+    ]
+
+
+async def test_replay_misses_name_the_request_key(tmp_path: Path):
+    cassette = Cassette(tmp_path)
+    history = [Message(role="user", content="text: hi")]
+    await _stream(CachingChatProvider(EchoChatProvider(), cassette, mode="record"), history)
+    replayer = CachingChatProvider(None, cassette, mode="replay", model_name="echo")
+
+    # A different history, option or model is a different request.
+    for provider, options in [
+        (replayer, GenerateOptions(temperature=0.5)),
+        (CachingChatProvider(None, cassette, mode="replay", model_name="other"), None),
+    ]:
+        with pytest.raises(CassetteMissError) as exc_info:
+            await provider.generate("You are a test.", [], history, options=options)
+        assert exc_info.value.key in str(exc_info.value)
+        assert not (tmp_path / f"{exc_info.value.key}.json").exists()
+
+    with pytest.raises(CassetteMissError):
+        await replayer.generate("You are a test.", [], [Message(role="user", content="text: yo")])
+
+
+async def test_passthrough_and_interrupted_streams_record_nothing(tmp_path: Path):
+    cassette = Cassette(tmp_path)
+    history = [Message(role="user", content=_SCRIPT)]
+
+    passthrough = CachingChatProvider(EchoChatProvider(), cassette, mode="passthrough")
+    _, parts = await _stream(passthrough, history)
+    assert len(parts) == 5
+
+    recorder = CachingChatProvider(EchoChatProvider(), cassette, mode="record")
+    stream = await recorder.generate("You are a test.", [], history)
+    async for _ in stream:
+        break
+
+    assert list(tmp_path.iterdir()) == []
+
+
+def test_replay_requires_a_model_name_without_a_provider(tmp_path: Path):
+    with pytest.raises(ValueError):
+        CachingChatProvider(None, Cassette(tmp_path), mode="replay")
+    with pytest.raises(ValueError):
+        CachingChatProvider(None, Cassette(tmp_path), mode="record", model_name="echo")
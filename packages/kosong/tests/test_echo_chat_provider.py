@@ -1,7 +1,12 @@
 import pytest
 
 from kosong import generate
-from kosong.chat_provider import ChatProviderError, StreamedMessagePart, TokenUsage
+from kosong.chat_provider import (
+    ChatProviderError,
+    GenerateOptions,
+    StreamedMessagePart,
+    TokenUsage,
+)
 from kosong.chat_provider.echo import EchoChatProvider
 from kosong.message import (
     AudioURLPart,
@@ -123,3 +128,58 @@ async def test_echo_chat_provider_requires_dsl_content():
 
     with pytest.raises(ChatProviderError):
         await provider.generate(system_prompt="", tools=[], history=history)
+
+
+async def test_echo_chat_provider_respects_stop_sequences():
+    dsl = "\n".join(
+        [
+            "text: Hello, wor",
+            "text: ld<END>ignored",
+            'tool_call: {"id": "call-1", "name": "search", "arguments": "{}"}',
+        ]
+    )
+    provider = EchoChatProvider()
+    result = await generate(
+        chat_provider=provider,
+        system_prompt="",
+        tools=[],
+        history=[Message(role="user", content=dsl)],
+        options=GenerateOptions(stop=("<END>",)),
+    )
+    assert result.message.content == [TextPart(text="Hello, world")]
+    assert result.message.tool_calls is None
+
+
+async def test_echo_chat_provider_respects_tool_call_options():
+    dsl = "\n".join(
+        [
+            "text: Checking",
+            'tool_call: {"id": "call-1", "name": "search", "arguments": null}',
+            'tool_call_part: {"arguments_part": "{}"}',
+            'tool_call: {"id": "call-2", "name": "fetch", "arguments": "{}"}',
+            "text: done",
+        ]
+    )
+    provider = EchoChatProvider()
+
+    async def _generate(options: GenerateOptions):
+        return await generate(
+            chat_provider=provider,
+            system_prompt="",
+            tools=[],
+            history=[Message(role="user", content=dsl)],
+            options=options,
+        )
+
+    result = await _generate(GenerateOptions(tool_choice="none"))
+    assert result.message.content == [TextPart(text="Checkingdone")]
+    assert result.message.tool_calls is None
+
+    result = await _generate(GenerateOptions(parallel_tool_calls=False))
+    assert result.message.tool_calls == [
+        ToolCall(id="call-1", function=ToolCall.FunctionBody(name="search", arguments="{}"))
+    ]
+
+    result = await _generate(GenerateOptions(parallel_tool_calls=True))
+    assert result.message.tool_calls is not None
+    assert len(result.message.tool_calls) == 2
@@ -1,12 +1,22 @@
 import asyncio
+import json
 from copy import deepcopy
 
 import pytest
 
 from kosong import generate
+from kosong._generate import ArgumentsAssembler
 from kosong.chat_provider import APIEmptyResponseError, StreamedMessagePart
+from kosong.chat_provider.echo import ScriptBuilder
 from kosong.chat_provider.mock import MockChatProvider
-from kosong.message import ImageURLPart, TextPart, ThinkPart, ToolCall, ToolCallPart
+from kosong.message import (
+    ImageURLPart,
+    TextPart,
+    ThinkPart,
+    ToolCall,
+    ToolCallPart,
+    ToolCallPreview,
+)
 
 
 def test_generate():
@@ -86,6 +96,53 @@ def test_generate_with_callbacks():
     assert output_tool_calls == message.tool_calls
 
 
+def test_generate_merges_interleaved_deltas():
+    input_parts: list[StreamedMessagePart] = [
+        ThinkPart(think="Let me"),
+        ThinkPart(think=" look."),
+        TextPart(text="Checking"),
+        TextPart(text=" the weather"),
+        ToolCall(
+            id="get_weather#1",
+            function=ToolCall.FunctionBody(name="get_weather", arguments=None),
+        ),
+        ToolCallPart(arguments_part="{}"),
+        TextPart(text=" and"),
+        TextPart(text=" the time."),
+        ToolCall(id="get_time#1", function=ToolCall.FunctionBody(name="get_time", arguments="")),
+        ThinkPart(think="Both"),
+        ThinkPart(think=" called.", encrypted="sig"),
+        ThinkPart(think="Signed thinking ends a run."),
+        TextPart(text="Done"),
+    ]
+    chat_provider = MockChatProvider(message_parts=deepcopy(input_parts))
+    output_parts: list[StreamedMessagePart] = []
+
+    result = asyncio.run(
+        generate(
+            chat_provider,
+            system_prompt="",
+            tools=[],
+            history=[],
+            on_message_part=output_parts.append,
+        )
+    )
+
+    # every delta is still streamed on its own
+    assert output_parts == input_parts
+    assert result.message.content == [
+        ThinkPart(think="Let me look."),
+        TextPart(text="Checking the weather and the time."),
+        ThinkPart(think="Both called.", encrypted="sig"),
+        ThinkPart(think="Signed thinking ends a run."),
+        TextPart(text="Done"),
+    ]
+    assert [tool_call.id for tool_call in result.message.tool_calls or []] == [
+        "get_weather#1",
+        "get_time#1",
+    ]
+
+
 def test_generate_think_only_raises_error():
     """Think-only response (no text, no tool calls) should raise APIEmptyResponseError."""
     chat_provider = MockChatProvider(
@@ -136,3 +193,120 @@ def test_generate_think_with_tool_calls_succeeds():
     result = asyncio.run(generate(chat_provider, system_prompt="", tools=[], history=[]))
     assert any(isinstance(p, ThinkPart) for p in result.message.content)
     assert result.message.tool_calls
+
+
+def test_generate_streams_tool_call_previews():
+    content = "".join(f'fn f{i}() {{\n    println!("{i}:\\t{{}}", {i});\n}}\n' for i in range(400))
+    arguments = json.dumps({"path": "src/main.rs", "content": content, "mode": "overwrite"})
+    n = len(arguments)
+    fragments = [arguments[n * i // 50 : n * (i + 1) // 50] for i in range(50)]
+    chat_provider = MockChatProvider(
+        message_parts=[
+            ToolCall(
+                id="write#1", function=ToolCall.FunctionBody(name="WriteFile", arguments=None)
+            ),
+            *(ToolCallPart(arguments_part=fragment) for fragment in fragments),
+            TextPart(text="Done."),
+        ]
+    )
+
+    previews: list[ToolCallPreview] = []
+    output_tool_calls: list[ToolCall] = []
+
+    async def on_message_part(part: StreamedMessagePart):
+        if isinstance(part, ToolCallPreview):
+            previews.append(part)
+
+    async def on_tool_call(tool_call: ToolCall):
+        output_tool_calls.append(tool_call)
+
+    message = asyncio.run(
+        generate(
+            chat_provider,
+            system_prompt="",
+            tools=[],
+            history=[],
+            on_message_part=on_message_part,
+            on_tool_call=on_tool_call,
+            tool_call_preview_interval=10,
+        )
+    ).message
+
+    assert len(previews) == 5
+    assert all(preview.id == "write#1" and preview.name == "WriteFile" for preview in previews)
+    assert all(preview.arguments["path"] == "src/main.rs" for preview in previews)
+    assert "mode" not in previews[0].arguments
+    assert previews[-1].arguments == json.loads(arguments)
+
+    assert message.tool_calls is not None
+    assert message.tool_calls[0].function.arguments == arguments
+    assert output_tool_calls == message.tool_calls
+    assert message.content == [TextPart(text="Done.")]
+
+
+def test_tool_call_previews_stop_growing_past_the_parsed_prefix():
+    content = "x" * (ArgumentsAssembler.PREVIEW_MAX_CHARS * 2)
+    arguments = json.dumps({"path": "big.txt", "content": content, "mode": "overwrite"})
+    assembler = ArgumentsAssembler(
+        ToolCall(id="write#1", function=ToolCall.FunctionBody(name="WriteFile", arguments=None)),
+        preview_interval=1,
+    )
+    previews = [
+        assembler.append(arguments[i : i + 1000]) for i in range(0, len(arguments), 1000)
+    ]
+
+    assert all(preview is not None for preview in previews)
+    last = previews[-1]
+    assert last is not None
+    assert last.arguments["path"] == "big.txt"
+    assert "mode" not in last.arguments
+    assert len(str(last.arguments["content"])) < ArgumentsAssembler.PREVIEW_MAX_CHARS
+    assert assembler.finish() == arguments
+
+
+def test_generate_without_preview_interval_streams_no_previews():
+    parts: list[StreamedMessagePart] = [
+        ToolCall(id="call#1", function=ToolCall.FunctionBody(name="bash", arguments=None)),
+        *(ToolCallPart(arguments_part=c) for c in '{"command": "ls"}'),
+    ]
+    chat_provider = MockChatProvider(message_parts=deepcopy(parts))
+    output_parts: list[StreamedMessagePart] = []
+
+    async def on_message_part(part: StreamedMessagePart):
+        output_parts.append(part)
+
+    message = asyncio.run(
+        generate(
+            chat_provider, system_prompt="", tools=[], history=[], on_message_part=on_message_part
+        )
+    ).message
+    assert output_parts == parts
+    assert message.tool_calls is not None
+    assert message.tool_calls[0].function.arguments == '{"command": "ls"}'
+
+
+async def test_generate_measures_streaming_metrics():
+    script = ScriptBuilder().text("Hello").text(", world").usage(input_other=5, output=10)
+
+    result = await generate(script.build(delay=0.02), system_prompt="", tools=[], history=[])
+
+    metrics = result.metrics
+
+    assert metrics.part_count == 2
+    assert 0.02 <= metrics.time_to_first_part < 0.5
+    assert metrics.time_to_first_part + 0.02 <= metrics.duration < 1
+    # 10 output tokens over the 0.02s or more between the two parts
+    assert metrics.output_tokens_per_second is not None
+    assert 10 / 1 < metrics.output_tokens_per_second <= 10 / 0.02
+
+
+async def test_generate_metrics_of_a_response_received_at_once():
+    script = ScriptBuilder().text("Hello").text(", world").usage(input_other=5, output=10)
+
+    result = await generate(script.build(), system_prompt="", tools=[], history=[])
+
+    metrics = result.metrics
+
+    assert metrics.part_count == 2
+    assert metrics.time_to_first_part <= metrics.duration < 0.5
+    assert metrics.output_tokens_per_second is None
@@ -1,9 +1,11 @@
 from __future__ import annotations
 
-from typing import Literal
+from enum import IntEnum
+from typing import Annotated, Literal
 
 from inline_snapshot import snapshot
-from pydantic import BaseModel, Field
+from jsonschema.validators import validator_for
+from pydantic import BaseModel, ConfigDict, Field
 
 from kosong.utils.jsonschema import deref_json_schema, ensure_property_types
 from kosong.utils.typing import JsonType
@@ -416,3 +418,82 @@ def test_nested_ref():
             "type": "object",
         }
     )
+
+
+class _Overwrite(BaseModel):
+    mode: Literal["overwrite"] = "overwrite"
+
+
+class _Append(BaseModel):
+    mode: Literal["append"]
+    separator: str = "\n"
+
+
+class _Priority(IntEnum):
+    LOW = 1
+    HIGH = 2
+
+
+class _WriteParams(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    path: str
+    write: Annotated[_Overwrite | _Append, Field(discriminator="mode")]
+    fallback: _Append | None = None
+    priority: _Priority = _Priority.LOW
+
+
+def _validator(schema: JsonSchema):
+    cls = validator_for(schema)
+    cls.check_schema(schema)
+    return cls(schema)
+
+
+def test_tagged_union_keeps_discriminator_structure():
+    resolved = ensure_property_types(deref_json_schema(_WriteParams.model_json_schema()))
+
+    assert "$defs" not in resolved
+    assert resolved["additionalProperties"] is False
+    write = resolved["properties"]["write"]
+    assert write["discriminator"] == {"propertyName": "mode"}
+    assert [branch["properties"]["mode"]["const"] for branch in write["oneOf"]] == [
+        "overwrite",
+        "append",
+    ]
+    assert resolved["properties"]["priority"]["enum"] == [1, 2]
+    assert resolved["properties"]["priority"]["type"] == "integer"
+
+    validator = _validator(resolved)
+    assert validator.is_valid({"path": "a.txt", "write": {"mode": "append"}, "priority": 2})
+    assert validator.is_valid({"path": "a.txt", "write": {"mode": "overwrite"}})
+    assert not validator.is_valid({"path": "a.txt", "write": {"mode": "truncate"}})
+    assert not validator.is_valid({"path": "a.txt", "write": {"mode": "append"}, "priority": 3})
+    assert not validator.is_valid({"path": "a.txt", "write": {"mode": "append"}, "extra": 1})
+
+
+def test_ref_used_twice_is_inlined_as_separate_copies():
+    resolved = deref_json_schema(_WriteParams.model_json_schema())
+
+    append_in_union = resolved["properties"]["write"]["oneOf"][1]
+    append_in_fallback = resolved["properties"]["fallback"]["anyOf"][0]
+    assert append_in_union == append_in_fallback
+    assert append_in_union is not append_in_fallback
+
+
+def test_recursive_ref_keeps_its_definition():
+    class Node(BaseModel):
+        name: str
+        children: list[Node] = Field(default_factory=list)
+
+    class Params(BaseModel):
+        root: Node
+
+    resolved = deref_json_schema(Params.model_json_schema())
+
+    node = resolved["properties"]["root"]
+    assert node["properties"]["children"]["items"] == {"$ref": "#/$defs/Node"}
+    assert resolved["$defs"] == {"Node": node}
+
+    validator = _validator(resolved)
+    assert validator.is_valid({"root": {"name": "a", "children": [{"name": "b"}]}})
+    assert not validator.is_valid({"root": {"name": "a", "children": [{"children": []}]}})
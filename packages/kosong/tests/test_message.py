@@ -1,13 +1,21 @@
+import doctest
+
 from inline_snapshot import snapshot
 
+import kosong.message
 from kosong.message import (
+    NON_TEXT_PART_TOKENS,
+    TRUNCATION_MARKER,
     AudioURLPart,
+    FilePart,
+    HistoryIssue,
     ImageURLPart,
     Message,
     TextPart,
     ThinkPart,
     ToolCall,
     VideoURLPart,
+    validate_history,
 )
 
 
@@ -96,6 +104,7 @@ def test_message_with_complex_content():
             ImageURLPart(image_url=ImageURLPart.ImageURL(url="https://example.com/image.png")),
             AudioURLPart(audio_url=AudioURLPart.AudioURL(url="https://example.com/audio.mp3")),
             VideoURLPart(video_url=VideoURLPart.VideoURL(url="https://example.com/video.mp4")),
+            FilePart(file=FilePart.File(url="https://example.com/doc.pdf", name="doc.pdf")),
         ],
         tool_calls=[
             ToolCall(id="123", function=ToolCall.FunctionBody(name="function", arguments="{}")),
@@ -124,6 +133,14 @@ def test_message_with_complex_content():
                     "type": "video_url",
                     "video_url": {"url": "https://example.com/video.mp4", "id": None},
                 },
+                {
+                    "type": "file",
+                    "file": {
+                        "url": "https://example.com/doc.pdf",
+                        "name": "doc.pdf",
+                        "mime_type": None,
+                    },
+                },
             ],
             "tool_calls": [
                 {
@@ -137,6 +154,20 @@ def test_message_with_complex_content():
     assert Message.model_validate(dumped) == message
 
 
+def test_file_part_url_kinds():
+    data_file = FilePart.File(url="data:application/pdf;base64,JVBERi0=")
+    assert not data_file.is_file_id
+    assert data_file.media_type == "application/pdf"
+
+    remote_file = FilePart.File(url="https://example.com/notes.txt", mime_type="text/plain")
+    assert not remote_file.is_file_id
+    assert remote_file.media_type == "text/plain"
+
+    uploaded_file = FilePart.File(url="file-abc123")
+    assert uploaded_file.is_file_id
+    assert uploaded_file.media_type is None
+
+
 def test_deserialize_from_json_plain_text():
     data = {
         "role": "user",
@@ -297,3 +328,235 @@ Hello, \n\
 world
 !\
 """)
+
+
+def test_message_module_doctests():
+    assert doctest.testmod(kosong.message).failed == 0
+
+
+def test_builder_user_message():
+    message = (
+        Message.user()
+        .text("Compare these.")
+        .image_url("https://example.com/a.png", id="img-1")
+        .audio_url("https://example.com/a.mp3")
+        .video_url("https://example.com/a.mp4")
+        .file("file-abc123", name="a.pdf", mime_type="application/pdf")
+        .build()
+    )
+    assert message == Message(
+        role="user",
+        content=[
+            TextPart(text="Compare these."),
+            ImageURLPart(
+                image_url=ImageURLPart.ImageURL(url="https://example.com/a.png", id="img-1")
+            ),
+            AudioURLPart(audio_url=AudioURLPart.AudioURL(url="https://example.com/a.mp3")),
+            VideoURLPart(video_url=VideoURLPart.VideoURL(url="https://example.com/a.mp4")),
+            FilePart(
+                file=FilePart.File(url="file-abc123", name="a.pdf", mime_type="application/pdf")
+            ),
+        ],
+    )
+
+
+def test_builder_assistant_message():
+    message = (
+        Message.assistant()
+        .think("Need the weather.")
+        .text("Let me check.")
+        .tool_call("get_weather", '{"city": "Beijing"}', id="call_1")
+        .build()
+    )
+    assert message == Message(
+        role="assistant",
+        content=[ThinkPart(think="Need the weather."), TextPart(text="Let me check.")],
+        tool_calls=[
+            ToolCall(
+                id="call_1",
+                function=ToolCall.FunctionBody(name="get_weather", arguments='{"city": "Beijing"}'),
+            )
+        ],
+    )
+
+
+def test_builder_generates_tool_call_ids():
+    message = Message.assistant().tool_call("a", None).tool_call("b", "{}").build()
+    assert message.tool_calls is not None
+    first, second = (tool_call.id for tool_call in message.tool_calls)
+    assert first and second and first != second
+
+
+def test_builder_name_and_partial():
+    message = Message.assistant().name("bot").text("The answer is").partial().build()
+    assert message.name == "bot"
+    assert message.partial is True
+    assert message.tool_calls is None
+    assert Message.system().text("Be brief.").build().role == "system"
+
+
+def test_tool_result_message():
+    assert Message.tool_result("call_1", "sunny") == Message(
+        role="tool", content="sunny", tool_call_id="call_1"
+    )
+    parts = [TextPart(text="a"), TextPart(text="b")]
+    assert Message.tool_result("call_1", parts).content == parts
+
+
+def _weather_turn(call_id: str = "call_1") -> list[Message]:
+    return [
+        Message.user().text("Weather?").build(),
+        Message.assistant().tool_call("get_weather", "{}", id=call_id).build(),
+        Message.tool_result(call_id, "sunny"),
+        Message.assistant().text("It is sunny.").build(),
+    ]
+
+
+def test_validate_history_accepts_valid_history():
+    history = [Message.system().text("Be brief.").build(), *_weather_turn()]
+    assert validate_history(history) == []
+    assert validate_history([]) == []
+
+
+def test_validate_history_empty_content():
+    history = [Message.user().build(), Message.assistant().text("Hi").build()]
+    assert validate_history(history) == snapshot(
+        [HistoryIssue(index=0, kind="empty_content", message="The user message has no content.")]
+    )
+
+
+def test_validate_history_consecutive_assistant():
+    history = [
+        Message.user().text("Hi").build(),
+        Message.assistant().text("Hello").build(),
+        Message.assistant().text("again").build(),
+    ]
+    assert [(issue.index, issue.kind) for issue in validate_history(history)] == [
+        (2, "consecutive_assistant")
+    ]
+
+
+def test_validate_history_misplaced_tool_calls():
+    history = [Message.user().text("Hi").tool_call("f", "{}", id="call_1").build()]
+    assert [(issue.index, issue.kind) for issue in validate_history(history)] == [
+        (0, "misplaced_tool_calls")
+    ]
+
+
+def test_validate_history_missing_tool_call_id():
+    history = [*_weather_turn()[:2], Message(role="tool", content="sunny")]
+    assert [(issue.index, issue.kind) for issue in validate_history(history)] == [
+        (1, "unanswered_tool_call"),
+        (2, "missing_tool_call_id"),
+    ]
+
+
+def test_validate_history_duplicate_tool_call_id():
+    history = [*_weather_turn(), *_weather_turn()]
+    assert [(issue.index, issue.kind) for issue in validate_history(history)] == [
+        (5, "duplicate_tool_call_id"),
+        (6, "orphan_tool_result"),
+    ]
+
+
+def test_validate_history_orphan_tool_result():
+    history = [Message.user().text("Hi").build(), Message.tool_result("call_1", "sunny")]
+    assert validate_history(history) == snapshot(
+        [
+            HistoryIssue(
+                index=1,
+                kind="orphan_tool_result",
+                message="The tool message answers `call_1`, which is not a pending tool call of "
+                "the preceding assistant message.",
+            )
+        ]
+    )
+
+
+def test_validate_history_unanswered_tool_call():
+    history = [
+        Message.user().text("Weather?").build(),
+        Message.assistant()
+        .tool_call("get_weather", "{}", id="call_1")
+        .tool_call("get_time", "{}", id="call_2")
+        .build(),
+        Message.tool_result("call_1", "sunny"),
+        Message.user().text("Well?").build(),
+    ]
+    assert validate_history(history) == snapshot(
+        [
+            HistoryIssue(
+                index=1,
+                kind="unanswered_tool_call",
+                message="The tool call `call_2` has no tool result.",
+            )
+        ]
+    )
+    # A tool call at the end of the history is unanswered as well
+    assert [(issue.index, issue.kind) for issue in validate_history(history[:2])] == [
+        (1, "unanswered_tool_call"),
+        (1, "unanswered_tool_call"),
+    ]
+
+
+def test_validate_history_misplaced_partial():
+    prefix = Message.assistant().text("The answer is").partial().build()
+    assert validate_history([Message.user().text("Q").build(), prefix]) == []
+
+    history = [
+        Message.user().text("Q").build(),
+        prefix,
+        Message.user().text("Go on").partial().build(),
+    ]
+    assert [(issue.index, issue.kind) for issue in validate_history(history)] == [
+        (1, "misplaced_partial"),
+        (2, "misplaced_partial"),
+    ]
+
+
+def test_message_token_estimate():
+    message = (
+        Message.assistant()
+        .think("t" * 8)
+        .text("x" * 30)
+        .image_url("https://example.com/a.png")
+        .tool_call("Read", '{"path": "a.txt"}', id="call_1")
+        .build()
+    )
+    # (8 + 30 + len("Read") + len('{"path": "a.txt"}')) // 4 text tokens, plus the image
+    assert message.token_estimate() == 59 // 4 + NON_TEXT_PART_TOKENS
+
+
+def test_message_truncated_trims_text_from_the_end():
+    message = (
+        Message.assistant()
+        .text("a" * 40)
+        .image_url("https://example.com/a.png")
+        .text("b" * 40)
+        .tool_call("Read", '{"path": "a.txt"}', id="call_1")
+        .build()
+    )
+    fixed = Message.assistant().tool_call("Read", '{"path": "a.txt"}').build().token_estimate()
+
+    truncated = message.truncated(NON_TEXT_PART_TOKENS + fixed + 15)
+
+    assert truncated.token_estimate() <= NON_TEXT_PART_TOKENS + fixed + 15
+    assert [part.type for part in truncated.content] == ["text", "image_url", "text"]
+    assert truncated.content[0] == TextPart(text="a" * 40)
+    assert truncated.content[2] == TextPart(text="b" * 19 + TRUNCATION_MARKER)
+    assert truncated.tool_calls == message.tool_calls
+    assert message.content[2] == TextPart(text="b" * 40)
+
+
+def test_message_truncated_drops_text_after_the_cut():
+    message = (
+        Message.assistant()
+        .think("t" * 40, encrypted="signature")
+        .text("x" * 40)
+        .build()
+    )
+
+    truncated = message.truncated(5)
+
+    assert truncated.content == [ThinkPart(think="t" * 19 + TRUNCATION_MARKER)]
+    assert message.truncated(20) == message
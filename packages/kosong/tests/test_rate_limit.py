@@ -0,0 +1,167 @@
+import asyncio
+from collections.abc import AsyncIterator, Sequence
+from typing import Self
+
+from kosong import generate
+from kosong.chat_provider import (
+    APIStatusError,
+    GenerateOptions,
+    StreamedMessagePart,
+    ThinkingEffort,
+    TokenUsage,
+)
+from kosong.chat_provider.rate_limit import INITIAL_COOLDOWN, RateLimiter, RateLimitState
+from kosong.message import Message, TextPart
+from kosong.tooling import Tool
+
+
+class FakeClock:
+    def __init__(self) -> None:
+        self.time = 0.0
+        self.sleeps: list[float] = []
+
+    def now(self) -> float:
+        return self.time
+
+    async def sleep(self, seconds: float) -> None:
+        self.sleeps.append(seconds)
+        self.time += seconds
+        await asyncio.sleep(0)
+
+
+class RateLimitedServer:
+    """A chat provider that answers 429 to any request sent while another one is in flight."""
+
+    name = "rate-limited"
+
+    def __init__(self) -> None:
+        self.in_flight = 0
+        self.rejected = 0
+        self.accepted = 0
+
+    @property
+    def model_name(self) -> str:
+        return "rate-limited"
+
+    @property
+    def thinking_effort(self) -> ThinkingEffort | None:
+        return None
+
+    async def generate(
+        self,
+        system_prompt: str,
+        tools: Sequence[Tool],
+        history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
+    ) -> "_Stream":
+        self.in_flight += 1
+        await asyncio.sleep(0)
+        if self.in_flight > 1:
+            self.in_flight -= 1
+            self.rejected += 1
+            raise APIStatusError(429, "Too Many Requests")
+        self.accepted += 1
+        return _Stream(self)
+
+    def with_thinking(self, effort: ThinkingEffort) -> Self:
+        return self
+
+
+class _Stream:
+    def __init__(self, server: RateLimitedServer) -> None:
+        self._server = server
+        self._parts: list[StreamedMessagePart] = [TextPart(text="ok")]
+
+    def __aiter__(self) -> AsyncIterator[StreamedMessagePart]:
+        return self
+
+    async def __anext__(self) -> StreamedMessagePart:
+        await asyncio.sleep(0)
+        if not self._parts:
+            self._server.in_flight -= 1
+            raise StopAsyncIteration
+        return self._parts.pop()
+
+    @property
+    def id(self) -> str | None:
+        return None
+
+    @property
+    def usage(self) -> TokenUsage | None:
+        return None
+
+
+async def test_rate_limiter_backs_off_until_concurrent_generates_succeed():
+    server = RateLimitedServer()
+    clock = FakeClock()
+    limiter = RateLimiter(clock=clock)
+
+    async def generate_with_retries() -> str:
+        for _ in range(10):
+            try:
+                result = await generate(server, "", [], [], rate_limiter=limiter)
+            except APIStatusError:
+                continue
+            return result.message.extract_text()
+        raise AssertionError("the request never got through")
+
+    results = await asyncio.gather(*(generate_with_retries() for _ in range(3)))
+
+    assert results == ["ok", "ok", "ok"]
+    assert server.accepted == 3
+    # The requests sent at once were rejected; the limiter then dropped to one request at a
+    # time and held the callers back for a cooldown that doubled with each consecutive 429.
+    assert server.rejected >= 2
+    assert clock.sleeps[:2] == [INITIAL_COOLDOWN, INITIAL_COOLDOWN * 2]
+    state = limiter.state()
+    assert (state.queued, state.in_flight, state.cooldown_remaining) == (0, 0, 0)
+
+
+async def test_rate_limiter_queues_requests_over_the_concurrency_limit():
+    limiter = RateLimiter(max_concurrent_requests=1, clock=FakeClock())
+    await limiter.acquire()
+    waiting = asyncio.create_task(limiter.acquire())
+    await asyncio.sleep(0)
+
+    assert limiter.would_wait()
+    assert limiter.state() == RateLimitState(
+        queued=1, in_flight=1, concurrency_limit=1, cooldown_remaining=0
+    )
+
+    limiter.release()
+    await waiting
+    assert limiter.state() == RateLimitState(
+        queued=0, in_flight=1, concurrency_limit=1, cooldown_remaining=0
+    )
+
+
+async def test_rate_limiter_spaces_requests_by_requests_per_minute():
+    clock = FakeClock()
+    limiter = RateLimiter(requests_per_minute=2, clock=clock)
+
+    for _ in range(3):
+        async with limiter.request():
+            pass
+
+    assert clock.sleeps == [30.0]
+
+
+async def test_rate_limiter_recovers_the_concurrency_limit_after_a_429():
+    clock = FakeClock()
+    limiter = RateLimiter(max_concurrent_requests=4, clock=clock)
+    await limiter.acquire()
+    limiter.release(APIStatusError(429, "Too Many Requests"))
+
+    assert limiter.state() == RateLimitState(
+        queued=0, in_flight=0, concurrency_limit=2, cooldown_remaining=INITIAL_COOLDOWN
+    )
+
+    limits: list[int | None] = []
+    for _ in range(5):
+        async with limiter.request():
+            pass
+        limits.append(limiter.state().concurrency_limit)
+
+    assert clock.sleeps == [INITIAL_COOLDOWN]
+    assert limits == [2, 3, 3, 3, 4]
@@ -1,8 +1,11 @@
+import json
+import time
+
 import pytest
 
 from kosong import generate
 from kosong.chat_provider import ChatProviderError, StreamedMessagePart, TokenUsage
-from kosong.chat_provider.echo import ScriptedEchoChatProvider
+from kosong.chat_provider.echo import ScriptBuilder, ScriptedEchoChatProvider, ScriptedTurn
 from kosong.message import (
     AudioURLPart,
     ImageURLPart,
@@ -135,3 +138,72 @@ async def test_scripted_echo_chat_provider_requires_dsl_content():
 
     with pytest.raises(ChatProviderError):
         await provider.generate(system_prompt="", tools=[], history=[])
+
+
+async def test_scripted_echo_chat_provider_structured_turns():
+    provider = (
+        ScriptBuilder()
+        .think("Need to read the file.")
+        .text("Reading.")
+        .tool_call("ReadFile", {"path": "a.txt"})
+        .usage(input_other=10, output=3)
+        .turn(id="final")
+        .text("Done.")
+        .build()
+    )
+
+    first = await generate(chat_provider=provider, system_prompt="", tools=[], history=[])
+    assert first.message.content == [
+        ThinkPart(think="Need to read the file."),
+        TextPart(text="Reading."),
+    ]
+    assert first.message.tool_calls == [
+        ToolCall(
+            id="call-1-1",
+            function=ToolCall.FunctionBody(name="ReadFile", arguments='{"path": "a.txt"}'),
+        )
+    ]
+    assert first.usage == TokenUsage(input_other=10, output=3)
+
+    second = await generate(chat_provider=provider, system_prompt="", tools=[], history=[])
+    assert second.id == "final"
+    assert second.message.content == [TextPart(text="Done.")]
+    assert second.message.tool_calls is None
+
+
+async def test_scripted_echo_chat_provider_from_json_file(tmp_path):
+    fixture = tmp_path / "script.json"
+    fixture.write_text(
+        json.dumps(
+            [
+                {
+                    "tool_calls": [{"id": "tc-1", "name": "search", "arguments": '{"q":"x"}'}],
+                    "usage": {"input_other": 1, "output": 2},
+                },
+                {"text": ["a", "b"]},
+            ]
+        )
+    )
+
+    provider = ScriptedEchoChatProvider.from_json_file(fixture)
+    stream = await provider.generate(system_prompt="", tools=[], history=[])
+    assert [part async for part in stream] == [
+        ToolCall(id="tc-1", function=ToolCall.FunctionBody(name="search", arguments='{"q":"x"}'))
+    ]
+    assert stream.usage == TokenUsage(input_other=1, output=2)
+
+    stream = await provider.generate(system_prompt="", tools=[], history=[])
+    assert [part async for part in stream] == [TextPart(text="a"), TextPart(text="b")]
+
+
+async def test_scripted_echo_chat_provider_delay_and_mixed_scripts():
+    provider = ScriptedEchoChatProvider(
+        ["text: dsl", ScriptedTurn(text=["x", "y"], delay=0.02)], delay=0.5
+    )
+    stream = await provider.generate(system_prompt="", tools=[], history=[])
+    assert [part async for part in stream] == [TextPart(text="dsl")]
+
+    stream = await provider.generate(system_prompt="", tools=[], history=[])
+    start = time.monotonic()
+    assert [part async for part in stream] == [TextPart(text="x"), TextPart(text="y")]
+    assert 0.04 <= time.monotonic() - start < 0.5
@@ -37,11 +37,15 @@ def test_step():
     toolset = SimpleToolset([PlusTool()])
 
     output_parts: list[StreamedMessagePart] = []
+    collected_tool_calls: list[ToolCall] = []
     collected_tool_results: list[ToolResult] = []
 
     def on_message_part(part: StreamedMessagePart):
         output_parts.append(part)
 
+    def on_tool_call(tool_call: ToolCall):
+        collected_tool_calls.append(tool_call)
+
     def on_tool_result(result: ToolResult):
         collected_tool_results.append(result)
 
@@ -52,6 +56,7 @@ def test_step():
             toolset=toolset,
             history=[],
             on_message_part=on_message_part,
+            on_tool_call=on_tool_call,
             on_tool_result=on_tool_result,
         )
         tool_results = await step_result.tool_results()
@@ -60,6 +65,8 @@ def test_step():
     step_result, tool_results = asyncio.run(run())
     assert step_result.message.content == [TextPart(text="Hello, world!")]
     assert step_result.tool_calls == [plus_tool_call]
+    assert step_result.metrics is not None and step_result.metrics.part_count > 0
     assert output_parts == input_parts
     assert tool_results == [ToolResult(tool_call_id="plus#123", return_value=ToolOk(output="3"))]
+    assert collected_tool_calls == [plus_tool_call]
     assert collected_tool_results == tool_results
@@ -276,6 +276,35 @@ def test_callable_tool_2():
     assert isinstance(asyncio.run(tool.call({"b": 2})), ToolValidateError)
 
 
+def test_callable_tool_2_validation_error_names_parameter_and_range():
+    class TestParams(BaseModel):
+        head_limit: int = Field(default=0, ge=0)
+        depth: int = Field(default=1, le=5)
+
+    class TestTool(CallableTool2[TestParams]):
+        name: str = "test"
+        description: str = "This is a test tool"
+        params: type[TestParams] = TestParams
+
+        @override
+        async def __call__(self, params: TestParams) -> ToolReturnValue:
+            return ToolOk(output="")
+
+    tool = TestTool()
+
+    ret = asyncio.run(tool.call({"head_limit": -1, "depth": 9}))
+    assert isinstance(ret, ToolValidateError)
+    assert ret.message == snapshot(
+        "Error validating JSON arguments: `head_limit` must be >= 0, got -1; "
+        "`depth` must be <= 5, got 9"
+    )
+    ret = asyncio.run(tool.call({"depth": "deep"}))
+    assert ret.message == snapshot(
+        "Error validating JSON arguments: `depth`: Input should be a valid integer, "
+        "unable to parse string as an integer"
+    )
+
+
 def test_simple_toolset_sub():
     class TestParams(BaseModel):
         pass
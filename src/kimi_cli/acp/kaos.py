@@ -1,12 +1,22 @@
 from __future__ import annotations
 
 import asyncio
-from collections.abc import AsyncGenerator, Iterable, Mapping
+from collections.abc import AsyncGenerator, Callable, Iterable, Mapping
 from contextlib import suppress
 from typing import Literal
 
 import acp
-from kaos import AsyncReadable, AsyncWritable, Kaos, KaosProcess, StatResult, StrOrKaosPath
+from kaos import (
+    MAX_LINE_BYTES,
+    AsyncReadable,
+    AsyncWritable,
+    Kaos,
+    KaosProcess,
+    StatResult,
+    StrOrKaosPath,
+    ensure_text,
+    split_lines,
+)
 from kaos.local import local_kaos
 from kaos.path import KaosPath
 
@@ -209,9 +219,17 @@ class ACPKaos:
         return self._fallback.iterdir(path)
 
     def glob(
-        self, path: StrOrKaosPath, pattern: str, *, case_sensitive: bool = True
+        self,
+        path: StrOrKaosPath,
+        pattern: str,
+        *,
+        case_sensitive: bool = True,
+        on_error: Callable[[OSError], None] | None = None,
+        exclude: Callable[[KaosPath], bool] | None = None,
     ) -> AsyncGenerator[KaosPath]:
-        return self._fallback.glob(path, pattern, case_sensitive=case_sensitive)
+        return self._fallback.glob(
+            path, pattern, case_sensitive=case_sensitive, on_error=on_error, exclude=exclude
+        )
 
     async def readbytes(self, path: StrOrKaosPath, n: int | None = None) -> bytes:
         return await self._fallback.readbytes(path, n=n)
@@ -227,6 +245,7 @@ class ACPKaos:
         if not self._supports_read:
             return await self._fallback.readtext(abs_path, encoding=encoding, errors=errors)
         response = await self._client.read_text_file(path=abs_path, session_id=self._session_id)
+        ensure_text(abs_path, response.content)
         return response.content
 
     async def readlines(
@@ -235,14 +254,38 @@ class ACPKaos:
         *,
         encoding: str = "utf-8",
         errors: Literal["strict", "ignore", "replace"] = "strict",
+        max_line_bytes: int | None = MAX_LINE_BYTES,
     ) -> AsyncGenerator[str]:
+        abs_path = self._abs_path(path)
+        if not self._supports_read:
+            async for line in self._fallback.readlines(
+                abs_path, encoding=encoding, errors=errors, max_line_bytes=max_line_bytes
+            ):
+                yield line
+            return
         text = await self.readtext(path, encoding=encoding, errors=errors)
-        for line in text.splitlines(keepends=True):
+
+        async def chunks() -> AsyncGenerator[bytes]:
+            yield text.encode("utf-8")
+
+        # Split like the other backends, so that line breaks and long lines are handled the same
+        async for line in split_lines(chunks(), max_line_bytes=max_line_bytes):
             yield line
 
     async def writebytes(self, path: StrOrKaosPath, data: bytes) -> int:
         return await self._fallback.writebytes(path, data)
 
+    async def writebytes_atomic(self, path: StrOrKaosPath, data: bytes) -> int:
+        # The client owns the files it can write, and saves them the way it sees fit
+        if not self._supports_write:
+            return await self._fallback.writebytes_atomic(path, data)
+        await self._client.write_text_file(
+            path=self._abs_path(path),
+            content=data.decode("utf-8"),
+            session_id=self._session_id,
+        )
+        return len(data)
+
     async def writetext(
         self,
         path: StrOrKaosPath,
@@ -283,6 +326,9 @@ class ACPKaos:
     ) -> None:
         await self._fallback.mkdir(path, parents=parents, exist_ok=exist_ok)
 
+    async def unlink(self, path: StrOrKaosPath) -> None:
+        await self._fallback.unlink(path)
+
     async def exec(self, *args: str, env: Mapping[str, str] | None = None) -> KaosProcess:
         return await self._fallback.exec(*args, env=env)
 
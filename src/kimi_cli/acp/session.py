@@ -16,7 +16,14 @@ from kimi_cli.acp.convert import (
 )
 from kimi_cli.acp.types import ACPContentBlock
 from kimi_cli.app import KimiCLI
-from kimi_cli.soul import LLMNotSet, LLMNotSupported, MaxStepsReached, RunCancelled
+from kimi_cli.soul import (
+    BudgetExceeded,
+    LLMNotSet,
+    LLMNotSupported,
+    MaxMessageSizeExceeded,
+    MaxStepsReached,
+    RunCancelled,
+)
 from kimi_cli.tools import extract_key_argument
 from kimi_cli.utils.logging import logger
 from kimi_cli.wire.file import WireFile
@@ -221,6 +228,9 @@ class ACPSession:
         except LLMNotSupported as e:
             logger.exception("LLM not supported:")
             raise acp.RequestError.internal_error({"error": str(e)}) from e
+        except MaxMessageSizeExceeded as e:
+            logger.warning("Max message size exceeded: {limit}", limit=e.limit)
+            return acp.PromptResponse(stop_reason="max_tokens")
         except APIStatusError as e:
             if e.status_code == 401 and self._is_oauth_session():
                 logger.warning("Authentication failed (401), prompting re-login")
@@ -233,6 +243,9 @@ class ACPSession:
         except MaxStepsReached as e:
             logger.warning("Max steps reached: {n_steps}", n_steps=e.n_steps)
             return acp.PromptResponse(stop_reason="max_turn_requests")
+        except BudgetExceeded as e:
+            logger.warning("Turn budget exceeded: {error}", error=e)
+            return acp.PromptResponse(stop_reason="max_tokens")
         except RunCancelled:
             logger.info("Prompt cancelled by user")
             return acp.PromptResponse(stop_reason="cancelled")
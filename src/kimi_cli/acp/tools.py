@@ -9,9 +9,9 @@ from kosong.tooling import CallableTool2, ToolReturnValue
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
 from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.tools.shell import MAX_FOREGROUND_TIMEOUT, Shell
 from kimi_cli.tools.shell import Params as ShellParams
-from kimi_cli.tools.shell import Shell
-from kimi_cli.tools.utils import ToolResultBuilder
+from kimi_cli.tools.utils import ArgumentClamps, ToolResultBuilder
 from kimi_cli.wire.types import DisplayBlock
 
 
@@ -61,6 +61,13 @@ class Terminal(CallableTool2[ShellParams]):
         self._approval = approval
 
     async def __call__(self, params: ShellParams) -> ToolReturnValue:
+        clamps = ArgumentClamps()
+        if not params.run_in_background:
+            timeout = clamps.clamp("timeout", params.timeout, MAX_FOREGROUND_TIMEOUT)
+            params = params.model_copy(update={"timeout": timeout})
+        return clamps.annotate(await self._run(params))
+
+    async def _run(self, params: ShellParams) -> ToolReturnValue:
         from kimi_cli.acp.session import get_current_acp_tool_call_id_or_none
 
         builder = ToolResultBuilder()
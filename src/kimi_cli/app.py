@@ -8,7 +8,7 @@ import time
 import warnings
 from collections.abc import AsyncGenerator, Callable
 from pathlib import Path
-from typing import TYPE_CHECKING, Any
+from typing import TYPE_CHECKING, Any, Literal
 
 import kaos
 from kaos.path import KaosPath
@@ -20,7 +20,13 @@ from kimi_cli.background.models import is_terminal_status
 from kimi_cli.cli import InputFormat, OutputFormat
 from kimi_cli.config import Config, LLMModel, LLMProvider, load_config
 from kimi_cli.constant import VERSION
-from kimi_cli.llm import augment_provider_with_env_vars, create_llm, model_display_name
+from kimi_cli.llm import (
+    LLM,
+    LLMCassette,
+    augment_provider_with_env_vars,
+    create_llm,
+    model_display_name,
+)
 from kimi_cli.session import Session
 from kimi_cli.share import get_share_dir
 from kimi_cli.soul import RunCancelled, run_soul
@@ -28,10 +34,14 @@ from kimi_cli.soul.agent import Runtime, load_agent
 from kimi_cli.soul.context import Context
 from kimi_cli.soul.kimisoul import KimiSoul
 from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.soul.turn_summary import TurnSummaryWriter
 from kimi_cli.utils.aioqueue import QueueShutDown
 from kimi_cli.utils.envvar import get_env_bool
 from kimi_cli.utils.logging import logger, open_original_stderr, redirect_stderr_to_logger
+from kimi_cli.utils.network import configure_network
 from kimi_cli.utils.path import shorten_home
+from kimi_cli.utils.redact import Redactor, collect_secrets, redact, set_redactor
+from kimi_cli.utils.session_crypto import SessionCipher, set_session_cipher
 from kimi_cli.wire import Wire, WireUISide
 from kimi_cli.wire.types import ApprovalRequest, ApprovalResponse, ContentPart, WireMessage
 
@@ -50,6 +60,12 @@ def _patch_session_id(record: dict[str, Any]) -> None:
         record["extra"].setdefault("sid", "")
 
 
+def _patch_record(record: dict[str, Any]) -> None:
+    """Inject the session ID into log records and redact secrets from the message."""
+    _patch_session_id(record)
+    record["message"] = redact(record["message"])
+
+
 def enable_logging(debug: bool = False, *, redirect_stderr: bool = True) -> None:
     # NOTE: stderr redirection is implemented by swapping the process-level fd=2 (dup2).
     # That can hide Click/Typer error output during CLI startup, so some entrypoints delay
@@ -69,11 +85,28 @@ def enable_logging(debug: bool = False, *, redirect_stderr: bool = True) -> None
         rotation="06:00",
         retention="10 days",
     )
-    logger.configure(extra={"sid": ""}, patcher=_patch_session_id)
+    logger.configure(extra={"sid": ""}, patcher=_patch_record)
     if redirect_stderr:
         redirect_stderr_to_logger()
 
 
+async def _verify_model(llm: LLM, *, strict: bool, progress: Callable[[str], None] | None) -> None:
+    """Warn about the mismatches between the model and its declared capabilities."""
+    from kimi_cli.exception import ModelVerificationError
+    from kimi_cli.model_probe import verify_model
+
+    if progress is not None:
+        progress("Verifying model...")
+    warnings = await verify_model(llm)
+    if not warnings:
+        return
+    lines = [f"Model '{llm.model_name}' does not match its config:\n"]
+    lines.extend(f"  - {warning}\n" for warning in warnings)
+    if strict:
+        raise ModelVerificationError("".join(lines).rstrip())
+    _write_original_stderr("WARNING: " + "".join(lines))
+
+
 def _write_original_stderr(text: str) -> None:
     """Write a user-facing notice to the terminal even if ``fd=2`` has been
     redirected into the logger by ``redirect_stderr_to_logger``.
@@ -131,16 +164,22 @@ class KimiCLI:
         afk: bool = False,
         runtime_afk: bool = False,
         plan_mode: bool = False,
+        dry_run: bool = False,
+        summary_file: Path | None = None,
         resumed: bool = False,
         ui_mode: str = "shell",
         # Extensions
         agent_file: Path | None = None,
         mcp_configs: list[MCPConfig] | list[dict[str, Any]] | None = None,
         skills_dirs: list[KaosPath] | None = None,
+        llm_cassette: LLMCassette | None = None,
+        verify_model: Literal["off", "warn", "strict"] | None = None,
         # Loop control
         max_steps_per_turn: int | None = None,
         max_retries_per_step: int | None = None,
         max_ralph_iterations: int | None = None,
+        ralph_success_command: str | None = None,
+        ralph_failure_limit: int | None = None,
         startup_progress: Callable[[str], None] | None = None,
         defer_mcp_loading: bool = False,
     ) -> KimiCLI:
@@ -159,17 +198,31 @@ class KimiCLI:
             runtime_afk (bool, optional): Internal invocation-only afk overlay, used by print mode
                 so it stays non-interactive without changing persisted session afk. Defaults to
                 False.
+            dry_run (bool, optional): Let tools with side effects only report what they would
+                do, see `Runtime.dry_run`. Defaults to False.
+            summary_file (Path | None, optional): JSON file to append a summary of each turn to,
+                see `Runtime.turn_summary`. Defaults to None.
             agent_file (Path | None, optional): Path to the agent file. Defaults to None.
             mcp_configs (list[MCPConfig | dict[str, Any]] | None, optional): MCP configs to load
                 MCP tools from. Defaults to None.
             skills_dirs (list[KaosPath] | None, optional): Custom skills directories that
                 override default user/project discovery. Defaults to None.
+            llm_cassette (LLMCassette | None, optional): Cassette to record the LLM responses to
+                or replay them from. Defaults to None.
+            verify_model (Literal["off", "warn", "strict"] | None, optional): Whether to probe the
+                model for the capabilities declared in its config, see `kimi_cli.model_probe`.
+                Defaults to None, which uses `Config.verify_model`.
             max_steps_per_turn (int | None, optional): Maximum number of steps in one turn.
                 Defaults to None.
             max_retries_per_step (int | None, optional): Maximum number of retries in one step.
                 Defaults to None.
             max_ralph_iterations (int | None, optional): Extra iterations after the first turn in
                 Ralph mode. Defaults to None.
+            ralph_success_command (str | None, optional): Shell command that ends Ralph mode
+                when it succeeds. Defaults to None.
+            ralph_failure_limit (int | None, optional): Number of consecutive iterations with
+                the same failed output of the success command that ends Ralph mode.
+                Defaults to None.
             startup_progress (Callable[[str], None] | None, optional): Progress callback used by
                 interactive startup UI. Defaults to None.
             defer_mcp_loading (bool, optional): Defer MCP startup until the interactive shell is
@@ -185,6 +238,8 @@ class KimiCLI:
             MCPConfigError(KimiCLIException, ValueError): When any MCP configuration is invalid.
             MCPRuntimeError(KimiCLIException, RuntimeError): When any MCP server cannot be
                 connected.
+            ModelVerificationError(KimiCLIException, ValueError): When the model does not match
+                its declared capabilities in strict `verify_model` mode.
         """
         _create_t0 = time.monotonic()
         _phase_timings_ms: dict[str, int] = {}
@@ -201,7 +256,17 @@ class KimiCLI:
             config.loop_control.max_retries_per_step = max_retries_per_step
         if max_ralph_iterations is not None:
             config.loop_control.max_ralph_iterations = max_ralph_iterations
+        if ralph_success_command is not None:
+            config.loop_control.ralph_success_command = ralph_success_command
+        if ralph_failure_limit is not None:
+            config.loop_control.ralph_failure_limit = ralph_failure_limit
         logger.info("Loaded config: {config}", config=config)
+        configure_network(config.network)
+        if config.network.accept_invalid_certs:
+            _write_original_stderr(
+                "WARNING: TLS certificate verification is disabled "
+                "(network.accept_invalid_certs = true). Do not use this on untrusted networks.\n"
+            )
 
         _phase_t = time.monotonic()
         oauth = OAuthManager(config)
@@ -229,6 +294,13 @@ class KimiCLI:
         assert provider is not None
         assert model is not None
         env_overrides = augment_provider_with_env_vars(provider, model)
+        set_redactor(
+            Redactor.from_config(
+                config.redaction,
+                collect_secrets(config, mcp_configs or [], providers=[provider]),
+            )
+        )
+        set_session_cipher(SessionCipher.load(create=True) if config.encrypt_sessions else None)
 
         # determine thinking mode
         thinking = config.default_thinking if thinking is None else thinking
@@ -246,12 +318,17 @@ class KimiCLI:
             thinking=thinking,
             session_id=session.id,
             oauth=oauth,
+            cassette=llm_cassette,
         )
         if llm is not None:
             logger.info("Using LLM provider: {provider}", provider=provider)
             logger.info("Using LLM model: {model}", model=model)
             logger.info("Thinking mode: {thinking}", thinking=thinking)
 
+        verify_model = config.verify_model if verify_model is None else verify_model
+        if llm is not None and llm.cassette is None and verify_model != "off":
+            await _verify_model(llm, strict=verify_model == "strict", progress=startup_progress)
+
         if startup_progress is not None:
             startup_progress("Scanning workspace...")
 
@@ -267,6 +344,13 @@ class KimiCLI:
         )
         runtime.ui_mode = ui_mode
         runtime.resumed = resumed
+        runtime.dry_run = dry_run
+        if summary_file is not None:
+            runtime.turn_summary = TurnSummaryWriter(summary_file)
+        if config.stream_thinking is not None:
+            runtime.stream_thinking = config.stream_thinking
+        elif ui_mode == "print":
+            runtime.stream_thinking = "off"
         runtime.notifications.recover()
         runtime.background_tasks.reconcile()
         _cleanup_stale_foreground_subagents(runtime)
@@ -302,7 +386,7 @@ class KimiCLI:
 
         if startup_progress is not None:
             startup_progress("Restoring conversation...")
-        context = Context(session.context_file)
+        context = Context(session.context_file, session_id=session.id)
         await context.restore()
 
         if context.system_prompt is not None:
@@ -398,6 +482,19 @@ class KimiCLI:
         """Get the Session instance."""
         return self._runtime.session
 
+    async def close_mcp_servers(self) -> None:
+        """Close the MCP client connections, so that stdio server processes and WebSocket
+        transports do not outlive the session and trigger firewall warnings.
+
+        Errors are logged and swallowed, since this runs on the exit and reload paths.
+        """
+        try:
+            toolset = self.soul.agent.toolset
+            if isinstance(toolset, KimiToolset):
+                await toolset.cleanup()
+        except (Exception, asyncio.CancelledError):
+            logger.warning("Error during toolset cleanup; continuing exit", exc_info=True)
+
     async def shutdown_background_tasks(self) -> None:
         """Kill active background tasks on exit, unless keep_alive_on_exit is configured.
 
@@ -416,14 +513,13 @@ class KimiCLI:
         if self._bg_refresh_task is not None and not self._bg_refresh_task.done():
             self._bg_refresh_task.cancel()
 
-        # Close MCP client connections so stdio/WebSocket transports do not
-        # outlive the CLI process and trigger firewall warnings.
+        await self.close_mcp_servers()
+
         try:
-            toolset = self.soul.agent.toolset
-            if isinstance(toolset, KimiToolset):
-                await toolset.cleanup()
+            if self._runtime.lsp is not None:
+                await self._runtime.lsp.shutdown()
         except (Exception, asyncio.CancelledError):
-            logger.warning("Error during toolset cleanup; continuing exit", exc_info=True)
+            logger.warning("Error during LSP shutdown; continuing exit", exc_info=True)
 
         bg_config = self._runtime.config.background
         if bg_config.keep_alive_on_exit:
@@ -551,6 +647,7 @@ class KimiCLI:
             LLMNotSupported: When the LLM does not have required capabilities.
             ChatProviderError: When the LLM provider returns an error.
             MaxStepsReached: When the maximum number of steps is reached.
+            BudgetExceeded: When the token or cost budget of the turn is exceeded.
             RunCancelled: When the run is cancelled by the cancel event.
         """
         async with self._env():
@@ -816,10 +913,16 @@ class KimiCLI:
             acp = ACP(self._soul)
             await acp.run()
 
-    async def run_wire_stdio(self) -> None:
-        """Run the Kimi Code CLI instance as Wire server over stdio."""
+    async def run_wire_stdio(self) -> int | None:
+        """
+        Run the Kimi Code CLI instance as Wire server over stdio.
+
+        Returns:
+            The exit code for the signal that stopped the server, or None if none did.
+        """
         from kimi_cli.wire.server import WireServer
 
         async with self._env():
             server = WireServer(self._soul)
             await server.serve()
+            return server.exit_code
@@ -40,7 +40,7 @@ from kimi_cli.config import (
 )
 from kimi_cli.constant import VERSION
 from kimi_cli.share import get_share_dir
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
 
 if TYPE_CHECKING:
@@ -452,10 +452,12 @@ def delete_tokens(ref: OAuthRef) -> None:
 
 
 async def request_device_authorization() -> DeviceAuthorization:
+    url = f"{_oauth_host().rstrip('/')}/api/oauth/device_authorization"
     async with (
         new_client_session() as session,
         session.post(
-            f"{_oauth_host().rstrip('/')}/api/oauth/device_authorization",
+            url,
+            proxy=request_proxy(url),
             data={"client_id": KIMI_CODE_CLIENT_ID},
             headers=_common_headers(),
         ) as response,
@@ -475,11 +477,13 @@ async def request_device_authorization() -> DeviceAuthorization:
 
 
 async def _request_device_token(auth: DeviceAuthorization) -> tuple[int, dict[str, Any]]:
+    url = f"{_oauth_host().rstrip('/')}/api/oauth/token"
     try:
         async with (
             new_client_session() as session,
             session.post(
-                f"{_oauth_host().rstrip('/')}/api/oauth/token",
+                url,
+                proxy=request_proxy(url),
                 data={
                     "client_id": KIMI_CODE_CLIENT_ID,
                     "device_code": auth.device_code,
@@ -502,12 +506,14 @@ async def _request_device_token(auth: DeviceAuthorization) -> tuple[int, dict[st
 
 async def refresh_token(refresh_token: str, *, max_retries: int = 3) -> OAuthToken:
     last_exc: Exception | None = None
+    url = f"{_oauth_host().rstrip('/')}/api/oauth/token"
     for attempt in range(max_retries):
         try:
             async with (
                 new_client_session() as session,
                 session.post(
-                    f"{_oauth_host().rstrip('/')}/api/oauth/token",
+                    url,
+                    proxy=request_proxy(url),
                     data={
                         "client_id": KIMI_CODE_CLIENT_ID,
                         "grant_type": "refresh_token",
@@ -1080,7 +1086,8 @@ class OAuthManager:
             return
         from kosong.chat_provider.kimi import Kimi
 
-        assert isinstance(runtime.llm.chat_provider, Kimi), "Expected Kimi chat provider"
+        if not isinstance(runtime.llm.chat_provider, Kimi):
+            return  # e.g. wrapped to record or replay a cassette
         provider = runtime.config.providers.get(provider_key)
         fallback_api_key = provider.api_key.get_secret_value() if provider else ""
         runtime.llm.chat_provider.client.api_key = access_token or fallback_api_key
@@ -9,7 +9,7 @@ from pydantic import BaseModel
 from kimi_cli.auth import KIMI_CODE_PLATFORM_ID
 from kimi_cli.config import Config, LLMModel, load_config, save_config
 from kimi_cli.llm import ModelCapability
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
 
 
@@ -274,6 +274,7 @@ async def _list_models(
     try:
         async with session.get(
             models_url,
+            proxy=request_proxy(models_url),
             headers={"Authorization": f"Bearer {api_key}"},
             raise_for_status=True,
         ) as response:
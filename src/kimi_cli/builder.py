@@ -0,0 +1,338 @@
+"""
+A builder of `KimiCLI` instances, for projects embedding Kimi Code CLI as a library.
+
+`KimiCLI.create` mirrors the command line flags one keyword argument each, which makes code
+calling it break whenever a flag is added or renamed. The builder groups the same settings into
+typed setters, reports every problem of a setup at once before anything is started, and adds
+what only embedders need: custom tools and soul observers. The `kimi` command builds its
+instances with it as well, so that both paths stay the same.
+
+Example:
+
+```python
+instance = await (
+    KimiCLIBuilder()
+    .work_dir(Path("~/project").expanduser())
+    .model("kimi-for-coding")
+    .add_tool(MyTool())
+    .build()
+)
+```
+"""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Literal, Self
+
+from kaos.path import KaosPath
+
+from kimi_cli.config import Config
+from kimi_cli.exception import InvalidToolError, KimiCLIBuilderError
+from kimi_cli.llm import LLMCassette
+from kimi_cli.session import Session
+from kimi_cli.soul.observer import SoulObserver
+from kimi_cli.soul.toolset import KimiToolset, ToolType
+
+if TYPE_CHECKING:
+    from fastmcp.mcp_config import MCPConfig
+
+    from kimi_cli.app import KimiCLI
+
+
+class KimiCLIBuilder:
+    """
+    Collects the settings of a `KimiCLI` instance and builds it.
+
+    Every setter returns the builder, so that calls can be chained. Settings left unset fall
+    back to the config, like the flags of the `kimi` command.
+    """
+
+    def __init__(self) -> None:
+        self._work_dir: KaosPath | None = None
+        self._session: Session | None = None
+        self._resumed = False
+        self._config: Config | Path | None = None
+        self._model_name: str | None = None
+        self._thinking: bool | None = None
+        self._yolo = False
+        self._afk = False
+        self._runtime_afk = False
+        self._plan_mode = False
+        self._dry_run = False
+        self._summary_file: Path | None = None
+        self._ui_mode = "shell"
+        self._agent_file: Path | None = None
+        self._mcp_configs: list[Any] = []
+        """`MCPConfig`s or their JSON dicts."""
+        self._skills_dirs: list[KaosPath] = []
+        self._llm_cassette: LLMCassette | None = None
+        self._verify_model: Literal["off", "warn", "strict"] | None = None
+        self._max_steps_per_turn: int | None = None
+        self._max_retries_per_step: int | None = None
+        self._max_ralph_iterations: int | None = None
+        self._ralph_success_command: str | None = None
+        self._ralph_failure_limit: int | None = None
+        self._startup_progress: Callable[[str], None] | None = None
+        self._defer_mcp_loading = False
+        self._tools: list[ToolType] = []
+        self._hidden_tools: list[str] = []
+        self._observers: list[SoulObserver] = []
+
+    # --- Session ---
+
+    def work_dir(self, path: KaosPath | Path) -> Self:
+        """Start a new session in the directory. Defaults to the current directory."""
+        self._work_dir = _kaos_path(path)
+        return self
+
+    def session(self, session: Session, *, resumed: bool = False) -> Self:
+        """
+        Use a session created by `Session.create`, `Session.find` or `Session.continue_`, instead
+        of starting a new one. `resumed` tells whether the session already has turns.
+        """
+        self._session = session
+        self._resumed = resumed
+        return self
+
+    # --- Configuration ---
+
+    def config(self, config: Config | Path) -> Self:
+        """The config, or the path of the config file. Defaults to the default config file."""
+        self._config = config
+        return self
+
+    def model(self, name: str) -> Self:
+        """The name of the model in the config. Defaults to `Config.default_model`."""
+        self._model_name = name
+        return self
+
+    def thinking(self, enabled: bool = True) -> Self:
+        """Whether to enable thinking. Defaults to `Config.default_thinking`."""
+        self._thinking = enabled
+        return self
+
+    def yolo(self, enabled: bool = True) -> Self:
+        """Approve all actions without confirmation."""
+        self._yolo = enabled
+        return self
+
+    def afk(self, enabled: bool = True, *, runtime_only: bool = False) -> Self:
+        """
+        Run away from keyboard: nobody answers questions or approves actions. With
+        `runtime_only`, the mode is not saved to the session.
+        """
+        if runtime_only:
+            self._runtime_afk = enabled
+        else:
+            self._afk = enabled
+        return self
+
+    def plan_mode(self, enabled: bool = True) -> Self:
+        """Start in plan mode."""
+        self._plan_mode = enabled
+        return self
+
+    def dry_run(self, enabled: bool = True) -> Self:
+        """Let tools with side effects only report what they would do."""
+        self._dry_run = enabled
+        return self
+
+    def summary_file(self, path: Path) -> Self:
+        """Append a JSON summary of each turn to the file."""
+        self._summary_file = path
+        return self
+
+    def ui_mode(self, mode: str) -> Self:
+        """The UI the instance is run with, used for telemetry and defaults. Defaults to shell."""
+        self._ui_mode = mode
+        return self
+
+    # --- Extensions ---
+
+    def agent_file(self, path: Path) -> Self:
+        """The agent spec file. Defaults to the default agent."""
+        self._agent_file = path
+        return self
+
+    def mcp_configs(self, configs: list[MCPConfig] | list[dict[str, Any]]) -> Self:
+        """Add MCP configs to load MCP tools from."""
+        self._mcp_configs.extend(configs)
+        return self
+
+    def skills_dir(self, path: KaosPath | Path) -> Self:
+        """Add a skills directory. Once one is added, the default directories are not searched."""
+        self._skills_dirs.append(_kaos_path(path))
+        return self
+
+    def llm_cassette(self, cassette: LLMCassette) -> Self:
+        """Record the LLM responses to the cassette, or replay them from it."""
+        self._llm_cassette = cassette
+        return self
+
+    def verify_model(self, mode: Literal["off", "warn", "strict"]) -> Self:
+        """Whether to probe the model for its declared capabilities. Defaults to the config."""
+        self._verify_model = mode
+        return self
+
+    def add_tool(self, tool: ToolType) -> Self:
+        """Add a tool to the agent. A tool with the name of a builtin one replaces it."""
+        self._tools.append(tool)
+        return self
+
+    def hide_tool(self, name: str) -> Self:
+        """Hide a tool of the agent from the model."""
+        self._hidden_tools.append(name)
+        return self
+
+    def add_observer(self, observer: SoulObserver) -> Self:
+        """Register an observer of the turns, steps and tool calls of the soul."""
+        self._observers.append(observer)
+        return self
+
+    # --- Loop control ---
+
+    def loop_limits(
+        self,
+        *,
+        max_steps_per_turn: int | None = None,
+        max_retries_per_step: int | None = None,
+        max_ralph_iterations: int | None = None,
+        ralph_success_command: str | None = None,
+        ralph_failure_limit: int | None = None,
+    ) -> Self:
+        """Override the limits of `Config.loop_control`. Limits left None keep the config's."""
+        if max_steps_per_turn is not None:
+            self._max_steps_per_turn = max_steps_per_turn
+        if max_retries_per_step is not None:
+            self._max_retries_per_step = max_retries_per_step
+        if max_ralph_iterations is not None:
+            self._max_ralph_iterations = max_ralph_iterations
+        if ralph_success_command is not None:
+            self._ralph_success_command = ralph_success_command
+        if ralph_failure_limit is not None:
+            self._ralph_failure_limit = ralph_failure_limit
+        return self
+
+    # --- Startup ---
+
+    def startup_progress(self, callback: Callable[[str], None]) -> Self:
+        """Report the startup phases to the callback."""
+        self._startup_progress = callback
+        return self
+
+    def defer_mcp_loading(self, enabled: bool = True) -> Self:
+        """Start the MCP servers after the instance is built rather than while building it."""
+        self._defer_mcp_loading = enabled
+        return self
+
+    # --- Building ---
+
+    def validate(self) -> list[str]:
+        """Check the settings without starting anything, and return all problems found."""
+        problems: list[str] = []
+        if self._session is not None and self._work_dir is not None:
+            problems.append("set either a session or a work_dir, not both")
+        if isinstance(self._config, Path) and not self._config.is_file():
+            problems.append(f"config file not found: {self._config}")
+        if self._model_name is not None and not self._model_name.strip():
+            problems.append("model name cannot be empty")
+        if self._agent_file is not None and not self._agent_file.is_file():
+            problems.append(f"agent file not found: {self._agent_file}")
+        for skills_dir in self._skills_dirs:
+            if not Path(str(skills_dir)).is_dir():
+                problems.append(f"skills directory not found: {skills_dir}")
+        if self._max_steps_per_turn is not None and self._max_steps_per_turn < 1:
+            problems.append("max_steps_per_turn must be at least 1")
+        if self._max_retries_per_step is not None and self._max_retries_per_step < 1:
+            problems.append("max_retries_per_step must be at least 1")
+        if self._max_ralph_iterations is not None and self._max_ralph_iterations < -1:
+            problems.append("max_ralph_iterations must be at least -1")
+        if self._ralph_failure_limit is not None and self._ralph_failure_limit < 1:
+            problems.append("ralph_failure_limit must be at least 1")
+        names: set[str] = set()
+        for tool in self._tools:
+            if not tool.name:
+                problems.append(f"tool {type(tool).__name__} has no name")
+            elif tool.name in names:
+                problems.append(f"tool {tool.name} is added more than once")
+            names.add(tool.name)
+        return problems
+
+    async def build(self) -> KimiCLI:
+        """
+        Build the instance, starting a new session unless one was set. A session started here
+        is deleted again if the build fails.
+
+        Raises:
+            KimiCLIBuilderError(KimiCLIException, ValueError): When `validate` finds problems, or
+                when tools are added or hidden but the agent's toolset is not a `KimiToolset`.
+            InvalidToolError(KimiCLIException, ValueError): When a hidden tool does not exist.
+            Any exception raised by `KimiCLI.create`.
+        """
+        if problems := self.validate():
+            raise KimiCLIBuilderError(problems)
+
+        from kimi_cli.app import KimiCLI
+
+        session = self._session
+        if session is None:
+            session = await Session.create(self._work_dir or KaosPath.cwd())
+        instance: KimiCLI | None = None
+        try:
+            instance = await KimiCLI.create(
+                session,
+                config=self._config,
+                model_name=self._model_name,
+                thinking=self._thinking,
+                yolo=self._yolo,
+                afk=self._afk,
+                runtime_afk=self._runtime_afk,
+                plan_mode=self._plan_mode,
+                dry_run=self._dry_run,
+                summary_file=self._summary_file,
+                resumed=self._resumed,
+                ui_mode=self._ui_mode,
+                agent_file=self._agent_file,
+                mcp_configs=self._mcp_configs,
+                skills_dirs=self._skills_dirs or None,
+                llm_cassette=self._llm_cassette,
+                verify_model=self._verify_model,
+                max_steps_per_turn=self._max_steps_per_turn,
+                max_retries_per_step=self._max_retries_per_step,
+                max_ralph_iterations=self._max_ralph_iterations,
+                ralph_success_command=self._ralph_success_command,
+                ralph_failure_limit=self._ralph_failure_limit,
+                startup_progress=self._startup_progress,
+                defer_mcp_loading=self._defer_mcp_loading,
+            )
+            self._add_tools(instance)
+        except BaseException:
+            if instance is not None:
+                await instance.close_mcp_servers()
+            if self._session is None:
+                # Do not leave behind a session that nothing will resume
+                await session.delete()
+            raise
+        for observer in self._observers:
+            instance.soul.add_observer(observer)
+        return instance
+
+    def _add_tools(self, instance: KimiCLI) -> None:
+        if not self._tools and not self._hidden_tools:
+            return
+        toolset = instance.soul.agent.toolset
+        if not isinstance(toolset, KimiToolset):
+            raise KimiCLIBuilderError(
+                [f"custom and hidden tools need a KimiToolset, not {type(toolset).__name__}"]
+            )
+        for tool in self._tools:
+            toolset.add(tool)
+        for name in self._hidden_tools:
+            if not toolset.hide(name):
+                raise InvalidToolError(f"Cannot hide tool {name}: the agent has no such tool")
+
+
+def _kaos_path(path: KaosPath | Path) -> KaosPath:
+    return path if isinstance(path, KaosPath) else KaosPath.unsafe_from_local_path(path)
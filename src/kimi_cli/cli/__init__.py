@@ -54,6 +54,7 @@ class ExitCode:
     SUCCESS = 0
     FAILURE = 1
     RETRYABLE = 75  # EX_TEMPFAIL from sysexits.h
+    BUDGET_EXCEEDED = 3
 
 
 InputFormat = Literal["text", "stream-json"]
@@ -140,7 +141,8 @@ def kimi(
             help=(
                 "Resume a session. "
                 "With ID: resume that session. "
-                "Without ID: interactively pick a session."
+                "With a number: resume that entry of the recent sessions list. "
+                "Without ID: interactively pick a recent session."
             ),
         ),
     ] = None,
@@ -203,6 +205,65 @@ def kimi(
             help="Start in plan mode. Default: no.",
         ),
     ] = False,
+    dry_run: Annotated[
+        bool,
+        typer.Option(
+            "--dry-run",
+            help=(
+                "Let the agent propose tool calls without executing the ones with side effects: "
+                "file edits, shell commands and commits only report what they would do. "
+                "Default: no."
+            ),
+        ),
+    ] = False,
+    summary_file: Annotated[
+        Path | None,
+        typer.Option(
+            "--summary-file",
+            dir_okay=False,
+            resolve_path=True,
+            help=(
+                "Append a JSON summary of each turn to this file: stop reason, steps, usage, "
+                "final message, changed files and shell commands. Default: none."
+            ),
+        ),
+    ] = None,
+    llm_cassette: Annotated[
+        Path | None,
+        typer.Option(
+            "--llm-cassette",
+            file_okay=False,
+            resolve_path=True,
+            help=(
+                "Record the LLM responses to this directory, or replay them from it without "
+                "calling the API, for developing prompts and tools. Default: none."
+            ),
+        ),
+    ] = None,
+    llm_cassette_mode: Annotated[
+        Literal["record", "replay"] | None,
+        typer.Option(
+            "--llm-cassette-mode",
+            help="Whether to record or replay `--llm-cassette`. Default: replay.",
+        ),
+    ] = None,
+    verify_model: Annotated[
+        bool,
+        typer.Option(
+            "--verify-model",
+            help=(
+                "Probe the model at startup and warn about capabilities its config declares but "
+                "it lacks, or omits but it uses. Default: the `verify_model` config option."
+            ),
+        ),
+    ] = False,
+    strict: Annotated[
+        bool,
+        typer.Option(
+            "--strict",
+            help="Like `--verify-model`, but refuse to start when the model does not match.",
+        ),
+    ] = False,
     afk: Annotated[
         bool,
         typer.Option(
@@ -362,11 +423,33 @@ def kimi(
             ),
         ),
     ] = None,
+    ralph_success_command: Annotated[
+        str | None,
+        typer.Option(
+            "--ralph-success-command",
+            help=(
+                "Shell command run after each iteration in Ralph mode; the loop ends when it "
+                "exits with code 0. Default: from config."
+            ),
+        ),
+    ] = None,
+    ralph_failure_limit: Annotated[
+        int | None,
+        typer.Option(
+            "--ralph-failure-limit",
+            min=1,
+            help=(
+                "Stop Ralph mode after this many consecutive iterations in which the success "
+                "command failed with the same output. Default: from config."
+            ),
+        ),
+    ] = None,
 ):
     """Kimi, your next CLI agent."""
     import asyncio
     import contextlib
     import json
+    import sys
 
     from kimi_cli.utils.proctitle import init_process_name
 
@@ -380,10 +463,12 @@ def kimi(
     from kaos.path import KaosPath
 
     from kimi_cli.agentspec import DEFAULT_AGENT_FILE, OKABE_AGENT_FILE
-    from kimi_cli.app import KimiCLI, enable_logging
+    from kimi_cli.app import enable_logging
+    from kimi_cli.builder import KimiCLIBuilder
     from kimi_cli.config import Config, load_config_from_string
     from kimi_cli.exception import ConfigError
     from kimi_cli.hooks import events as hook_events
+    from kimi_cli.llm import LLMCassette
     from kimi_cli.metadata import load_metadata, save_metadata
     from kimi_cli.session import Session
     from kimi_cli.ui.shell.startup import ShellStartupProgress
@@ -393,9 +478,13 @@ def kimi(
 
     # Don't redirect stderr during argument parsing. Our stderr redirector
     # replaces fd=2 with a pipe, which would swallow Click/Typer startup errors.
-    # Redirection is installed later, right before KimiCLI.create(), so that
+    # Redirection is installed later, right before the instance is built, so that
     # MCP server stderr noise is captured into logs from the start.
     enable_logging(debug, redirect_stderr=False)
+    if debug:
+        from kimi_cli.soul.toolset import set_tool_tracebacks
+
+        set_tool_tracebacks(True)
 
     def _emit_fatal_error(message: str) -> None:
         # Prefer writing to the original stderr fd even if we later redirect fd=2.
@@ -494,11 +583,22 @@ def kimi(
             "Final-message-only output is only supported for print UI",
             param_hint="--final-message-only",
         )
+    if llm_cassette_mode is not None and llm_cassette is None:
+        raise typer.BadParameter(
+            "Cassette mode requires `--llm-cassette`",
+            param_hint="--llm-cassette-mode",
+        )
     if _picker_mode and ui != "shell":
         raise typer.BadParameter(
             "--session without a session ID is only supported for shell UI",
             param_hint="--session",
         )
+    if _picker_mode and not sys.stdin.isatty():
+        raise typer.BadParameter(
+            "--session without a session ID needs an interactive terminal; "
+            "pass a session ID or a number from the recent sessions list instead",
+            param_hint="--session",
+        )
 
     config: Config | Path | None = None
     if config_string is not None:
@@ -607,35 +707,53 @@ def kimi(
                 if changed:
                     session.save_state()
 
-            # Redirect stderr *before* KimiCLI.create() so that MCP server
+            # Redirect stderr *before* building the instance so that MCP server
             # subprocesses (e.g. mcp-remote OAuth debug logs) write to the log
             # file instead of polluting the user's terminal.  CLI argument
             # parsing has already succeeded at this point, so Typer/Click
             # startup errors are no longer a concern.  Fatal errors from
-            # create() are still visible because _emit_fatal_error() writes to
+            # build() are still visible because _emit_fatal_error() writes to
             # the saved original stderr fd.
             redirect_stderr_to_logger()
 
-            instance = await KimiCLI.create(
-                session,
-                config=config,
-                model_name=model_name,
-                thinking=thinking,
-                yolo=yolo,
-                afk=afk,
-                runtime_afk=ui == "print",
-                plan_mode=plan,
-                resumed=resumed,
-                agent_file=agent_file,
-                mcp_configs=mcp_configs,
-                skills_dirs=skills_dirs,
-                max_steps_per_turn=max_steps_per_turn,
-                max_retries_per_step=max_retries_per_step,
-                max_ralph_iterations=max_ralph_iterations,
-                startup_progress=startup_progress.update if ui == "shell" else None,
-                defer_mcp_loading=ui == "shell" and prompt is None,
-                ui_mode=ui,
+            builder = (
+                KimiCLIBuilder()
+                .session(session, resumed=resumed)
+                .yolo(yolo)
+                .afk(afk)
+                .afk(ui == "print", runtime_only=True)
+                .plan_mode(plan)
+                .dry_run(dry_run)
+                .ui_mode(ui)
+                .mcp_configs(mcp_configs)
+                .loop_limits(
+                    max_steps_per_turn=max_steps_per_turn,
+                    max_retries_per_step=max_retries_per_step,
+                    max_ralph_iterations=max_ralph_iterations,
+                    ralph_success_command=ralph_success_command,
+                    ralph_failure_limit=ralph_failure_limit,
+                )
+                .defer_mcp_loading(ui == "shell" and prompt is None)
             )
+            if config is not None:
+                builder.config(config)
+            if model_name is not None:
+                builder.model(model_name)
+            if thinking is not None:
+                builder.thinking(thinking)
+            if summary_file is not None:
+                builder.summary_file(summary_file)
+            if llm_cassette is not None:
+                builder.llm_cassette(LLMCassette(llm_cassette, llm_cassette_mode or "replay"))
+            if verify_model or strict:
+                builder.verify_model("strict" if strict else "warn")
+            if agent_file is not None:
+                builder.agent_file(agent_file)
+            for skills_dir in skills_dirs or []:
+                builder.skills_dir(skills_dir)
+            if ui == "shell":
+                builder.startup_progress(startup_progress.update)
+            instance = await builder.build()
             startup_progress.stop()
 
             # --- SessionStart hook ---
@@ -674,8 +792,8 @@ def kimi(
                     case "wire":
                         if prompt is not None:
                             logger.warning("Wire server ignores prompt argument")
-                        await instance.run_wire_stdio()
-                        exit_code = ExitCode.SUCCESS
+                        signal_exit_code = await instance.run_wire_stdio()
+                        exit_code = signal_exit_code or ExitCode.SUCCESS
             except Reload as e:
                 preserve_background_tasks = True
                 if e.session_id is None:
@@ -709,6 +827,9 @@ def kimi(
                 if not preserve_background_tasks:
                     await instance.shutdown_background_tasks()
                     await instance.await_bg_tasks_shutdown()
+                else:
+                    # The next session starts its own MCP servers.
+                    await instance.close_mcp_servers()
 
             return session, exit_code
         finally:
@@ -748,6 +869,8 @@ def kimi(
                 work_dir_meta = metadata.new_work_dir_meta(last_session.work_dir)
             work_dir_meta.last_session_id = last_session.id
             save_metadata(metadata)
+        # Also prunes deleted and empty sessions, including this one if it was just deleted
+        await last_session.record_recent()
 
     async def _reload_loop(session_id: str | None) -> tuple[str | None, int]:
         """Run the main loop, handling Reload/SwitchToWeb/SwitchToVis.
@@ -815,17 +938,17 @@ def kimi(
         from kimi_cli.utils.datetime import format_relative_time
 
         async def _pick_session() -> str:
-            all_sessions = await Session.list(work_dir)
-            if not all_sessions:
+            recent_sessions = await Session.list_recent(work_dir, backfill=True)
+            if not recent_sessions:
                 Console().print("[yellow]No sessions found for the working directory.[/yellow]")
                 raise typer.Exit(0)
 
             choices: list[tuple[str, str]] = []
-            for s in all_sessions:
-                time_str = format_relative_time(s.updated_at)
+            for i, s in enumerate(recent_sessions, start=1):
+                time_str = format_relative_time(s.last_used_at)
                 short_id = s.id[:8]
                 name = _strip_session_id_suffix(s.title, s.id)
-                label = f"{name} ({short_id}), {time_str}"
+                label = f"{i}. {name} ({short_id}), {s.message_count} messages, {time_str}"
                 choices.append((s.id, label))
 
             try:
@@ -844,6 +967,23 @@ def kimi(
             return selection
 
         session_id = asyncio.run(_pick_session())
+    elif session_id is not None and session_id.isdigit():
+
+        async def _resolve_recent_index(target: str) -> str:
+            # A session may be named by a number itself, which takes precedence
+            if await Session.find(work_dir, target) is not None:
+                return target
+            recent_sessions = await Session.list_recent(work_dir, backfill=True)
+            index = int(target)
+            if not 1 <= index <= len(recent_sessions):
+                raise typer.BadParameter(
+                    f"No recent session number {index}; the working directory has "
+                    f"{len(recent_sessions)} recent sessions",
+                    param_hint="--session",
+                )
+            return recent_sessions[index - 1].id
+
+        session_id = asyncio.run(_resolve_recent_index(session_id))
 
     try:
         switch_target, exit_code = asyncio.run(_reload_loop(session_id))
@@ -917,11 +1057,15 @@ def login(
 
     from kimi_cli.auth.oauth import login_kimi_code
     from kimi_cli.config import load_config
+    from kimi_cli.utils.network import configure_network
+
+    config = load_config()
+    configure_network(config.network)
 
     async def _run() -> bool:
         if json:
             ok = True
-            async for event in login_kimi_code(load_config()):
+            async for event in login_kimi_code(config):
                 typer.echo(event.json)
                 if event.type == "error":
                     ok = False
@@ -931,7 +1075,7 @@ def login(
         ok = True
         status: Status | None = None
         try:
-            async for event in login_kimi_code(load_config()):
+            async for event in login_kimi_code(config):
                 if event.type == "waiting":
                     if status is None:
                         status = console.status("Waiting for user authorization...")
@@ -15,19 +15,27 @@ class LazySubcommandGroup(typer.core.TyperGroup):
 
     lazy_subcommands: dict[str, tuple[str, str, str]] = {
         "info": ("kimi_cli.cli.info", "cli", "Show version and protocol information."),
+        "config": ("kimi_cli.cli.config", "cli", "Get and set configuration values."),
         "export": ("kimi_cli.cli.export", "cli", "Export session data."),
         "mcp": ("kimi_cli.cli.mcp", "cli", "Manage MCP server configurations."),
         "plugin": ("kimi_cli.cli.plugin", "cli", "Manage plugins."),
+        "session": ("kimi_cli.cli.session", "cli", "Manage sessions."),
+        "stats": ("kimi_cli.cli.stats", "cli", "Show usage statistics across sessions."),
         "vis": ("kimi_cli.cli.vis", "cli", "Run Kimi Agent Tracing Visualizer."),
         "web": ("kimi_cli.cli.web", "cli", "Run Kimi Code CLI web interface."),
+        "wire": ("kimi_cli.cli.wire", "cli", "Wire protocol developer tools."),
     }
     lazy_command_order: tuple[str, ...] = (
         "info",
+        "config",
         "export",
         "mcp",
         "plugin",
+        "session",
+        "stats",
         "vis",
         "web",
+        "wire",
     )
 
     # Click options that support optional values.  When the flag is present
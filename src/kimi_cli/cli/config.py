@@ -0,0 +1,312 @@
+from __future__ import annotations
+
+import json
+import re
+import types
+from pathlib import Path
+from typing import TYPE_CHECKING, Annotated, Any, Union, cast, get_args, get_origin
+
+import typer
+
+if TYPE_CHECKING:
+    from tomlkit import TOMLDocument
+
+    from kimi_cli.config import Config
+
+cli = typer.Typer(help="Get and set configuration values.")
+
+SECRET_MASK = "********"
+
+_KEY_PART = re.compile(r'"([^"]*)"|([^."]+)')
+_KEY_HELP = "Dotted config key, e.g. loop_control.max_steps_per_turn."
+
+
+class ConfigKeyError(ValueError):
+    """Raised when a dotted key does not name a configuration field."""
+
+
+def split_key(key: str) -> list[str]:
+    """Split a dotted key. Segments containing dots can be quoted, e.g. `models."gpt-4.1"`."""
+    parts: list[str] = []
+    pos = 0
+    while True:
+        match = _KEY_PART.match(key, pos)
+        if match is None:
+            raise ConfigKeyError(f"Invalid config key: {key}")
+        parts.append(match.group(1) if match.group(1) is not None else match.group(2))
+        pos = match.end()
+        if pos == len(key):
+            return parts
+        if key[pos] != ".":
+            raise ConfigKeyError(f"Invalid config key: {key}")
+        pos += 1
+
+
+def _unwrap_optional(annotation: Any) -> Any:
+    if get_origin(annotation) in (Union, types.UnionType):
+        args = [arg for arg in get_args(annotation) if arg is not type(None)]
+        if len(args) == 1:
+            return args[0]
+    return annotation
+
+
+def resolve_annotation(parts: list[str]) -> Any:
+    """Return the type annotation of the config field named by the key parts."""
+    from pydantic import BaseModel
+
+    from kimi_cli.config import Config
+
+    annotation: Any = Config
+    for i, part in enumerate(parts):
+        annotation = _unwrap_optional(annotation)
+        if isinstance(annotation, type) and issubclass(annotation, BaseModel):
+            field = annotation.model_fields.get(part)
+            if field is None or field.exclude:
+                raise ConfigKeyError(f"Unknown config key: {'.'.join(parts[: i + 1])}")
+            annotation = field.annotation
+        elif get_origin(annotation) is dict:
+            annotation = get_args(annotation)[1]
+        else:
+            raise ConfigKeyError(f"Unknown config key: {'.'.join(parts[: i + 1])}")
+    return annotation
+
+
+def type_name(annotation: Any) -> str:
+    if isinstance(annotation, type):
+        return annotation.__name__
+    return str(annotation).replace("typing.", "").replace("kimi_cli.config.", "")
+
+
+def parse_value(key: str, raw: str, annotation: Any) -> Any:
+    """
+    Parse a value given on the command line for the field with the given annotation.
+
+    The value is read as a TOML value (e.g. `10`, `true`, `["a", "b"]`, `{ input = 1.0 }`), and
+    falls back to a plain string.
+
+    Raises:
+        ValueError: When the value does not match the field type.
+    """
+    import tomlkit
+    from pydantic import TypeAdapter, ValidationError
+    from tomlkit.exceptions import TOMLKitError
+
+    adapter = TypeAdapter(annotation)
+    candidates: list[Any] = []
+    try:
+        candidates.append(tomlkit.loads(f"value = {raw}").unwrap()["value"])
+    except TOMLKitError:
+        pass
+    candidates.append(raw)
+    first_error: ValidationError | None = None
+    for candidate in candidates:
+        try:
+            adapter.validate_python(candidate)
+        except ValidationError as e:
+            first_error = first_error or e
+            continue
+        return candidate
+    detail = f" ({first_error.errors()[0]['msg']})" if first_error else ""
+    raise ValueError(
+        f"Invalid value for {key}: expected {type_name(annotation)}, got {raw!r}{detail}"
+    )
+
+
+def _lookup(data: Any, parts: list[str]) -> tuple[bool, Any]:
+    for part in parts:
+        if not isinstance(data, dict) or part not in data:
+            return False, None
+        data = cast(dict[str, Any], data)[part]
+    return True, data
+
+
+def _mask_secrets(python_value: Any, json_value: Any) -> Any:
+    from pydantic import SecretStr
+
+    if isinstance(python_value, SecretStr):
+        return SECRET_MASK
+    if isinstance(python_value, dict) and isinstance(json_value, dict):
+        python_dict = cast(dict[str, Any], python_value)
+        return {
+            k: _mask_secrets(python_dict.get(k), v)
+            for k, v in cast(dict[str, Any], json_value).items()
+        }
+    return json_value
+
+
+def _flatten(data: Any, prefix: list[str], out: dict[str, Any]) -> None:
+    if isinstance(data, dict) and data:
+        for k, v in cast(dict[str, Any], data).items():
+            _flatten(v, [*prefix, k], out)
+        return
+    out[".".join(f'"{part}"' if "." in part else part for part in prefix)] = data
+
+
+def load_document(config_file: Path) -> TOMLDocument:
+    import tomlkit
+
+    if not config_file.exists():
+        return tomlkit.document()
+    return tomlkit.loads(config_file.read_text(encoding="utf-8"))
+
+
+def validate_document(document: TOMLDocument) -> Config:
+    from kimi_cli.config import Config
+
+    return Config.model_validate(document.unwrap())
+
+
+def set_in_document(document: TOMLDocument, parts: list[str], value: Any) -> None:
+    import tomlkit
+
+    container: dict[str, Any] = document
+    for i, part in enumerate(parts[:-1]):
+        child = container.get(part)
+        if child is None:
+            container[part] = tomlkit.table()
+            child = container[part]
+        elif not isinstance(child, dict):
+            raise ConfigKeyError(f"{'.'.join(parts[: i + 1])} is not a table")
+        container = cast(dict[str, Any], child)
+    container[parts[-1]] = value
+
+
+def unset_in_document(document: TOMLDocument, parts: list[str]) -> bool:
+    container: Any = document
+    for part in parts[:-1]:
+        container = container.get(part)
+        if not isinstance(container, dict):
+            return False
+    if parts[-1] not in container:
+        return False
+    del container[parts[-1]]
+    return True
+
+
+def effective_values(config: Config) -> dict[str, Any]:
+    """All effective config values, including defaults, with secrets masked."""
+    return _mask_secrets(config.model_dump(), config.model_dump(mode="json"))
+
+
+def _config_file() -> Path:
+    from kimi_cli.config import get_config_file
+
+    return get_config_file()
+
+
+def _load() -> tuple[Path, TOMLDocument, Config]:
+    from pydantic import ValidationError
+    from tomlkit.exceptions import TOMLKitError
+
+    config_file = _config_file()
+    try:
+        document = load_document(config_file)
+        config = validate_document(document)
+    except (TOMLKitError, ValidationError) as e:
+        typer.echo(f"Invalid configuration file {config_file}: {e}", err=True)
+        raise typer.Exit(code=1) from e
+    return config_file, document, config
+
+
+def _parse_key(key: str) -> tuple[list[str], Any]:
+    try:
+        parts = split_key(key)
+        return parts, resolve_annotation(parts)
+    except ConfigKeyError as e:
+        typer.echo(str(e), err=True)
+        raise typer.Exit(code=1) from e
+
+
+def _save(config_file: Path, document: TOMLDocument) -> None:
+    from pydantic import ValidationError
+
+    try:
+        validate_document(document)
+    except ValidationError as e:
+        typer.echo(f"Invalid configuration: {e}", err=True)
+        raise typer.Exit(code=1) from e
+    config_file.write_text(document.as_string(), encoding="utf-8")
+
+
+def _format(value: Any) -> str:
+    if isinstance(value, str):
+        return value
+    return json.dumps(value, ensure_ascii=False)
+
+
+@cli.command(name="get")
+def get_value(
+    key: Annotated[str, typer.Argument(help=_KEY_HELP)],
+):
+    """Print the effective value of a config key."""
+    from pydantic import SecretStr
+
+    parts, _ = _parse_key(key)
+    _, _, config = _load()
+    found, value = _lookup(config.model_dump(mode="json"), parts)
+    if not found:
+        typer.echo(f"{key} is not set.", err=True)
+        raise typer.Exit(code=1)
+    _, python_value = _lookup(config.model_dump(), parts)
+    if not isinstance(python_value, SecretStr):
+        value = _mask_secrets(python_value, value)
+    typer.echo(_format(value))
+
+
+@cli.command(name="set")
+def set_value(
+    key: Annotated[str, typer.Argument(help=_KEY_HELP)],
+    value: Annotated[str, typer.Argument(help="Value, parsed as TOML with a fallback to string.")],
+):
+    """Set a config key in the config file."""
+    parts, annotation = _parse_key(key)
+    config_file, document, _ = _load()
+    try:
+        parsed = parse_value(key, value, annotation)
+        set_in_document(document, parts, parsed)
+    except ValueError as e:
+        typer.echo(str(e), err=True)
+        raise typer.Exit(code=1) from e
+    _save(config_file, document)
+    typer.echo(f"Set {key} in {config_file}.")
+
+
+@cli.command(name="unset")
+def unset_value(
+    key: Annotated[str, typer.Argument(help=_KEY_HELP)],
+):
+    """Remove a config key from the config file, restoring its default."""
+    parts, _ = _parse_key(key)
+    config_file, document, _ = _load()
+    if not unset_in_document(document, parts):
+        typer.echo(f"{key} is not set in {config_file}.", err=True)
+        raise typer.Exit(code=1)
+    _save(config_file, document)
+    typer.echo(f"Unset {key} in {config_file}.")
+
+
+@cli.command(name="list")
+def list_values(
+    json_output: Annotated[
+        bool,
+        typer.Option("--json", help="Output values as JSON."),
+    ] = False,
+):
+    """List effective config values, including defaults. `*` marks values set in the file."""
+    config_file, document, config = _load()
+    values: dict[str, Any] = {}
+    _flatten(effective_values(config), [], values)
+    data = document.unwrap()
+    explicit = {key: _lookup(data, split_key(key))[0] for key in values}
+    if json_output:
+        typer.echo(
+            json.dumps(
+                {key: {"value": value, "set": explicit[key]} for key, value in values.items()},
+                ensure_ascii=False,
+            )
+        )
+        return
+    for key, value in values.items():
+        marker = "*" if explicit[key] else " "
+        typer.echo(f"{marker} {key} = {json.dumps(value, ensure_ascii=False)}")
+    typer.echo(f"\n* set in {config_file}")
@@ -42,7 +42,7 @@ async def _load_previous_session(work_dir: KaosPath) -> Session | None:
     return await Session.continue_(work_dir)
 
 
-def _resolve_work_dir(ctx: typer.Context) -> KaosPath:
+def resolve_work_dir(ctx: typer.Context) -> KaosPath:
     root_ctx = ctx.find_root()
     local_work_dir = root_ctx.params.get("local_work_dir")
     if local_work_dir is None:
@@ -50,7 +50,7 @@ def _resolve_work_dir(ctx: typer.Context) -> KaosPath:
     return KaosPath.unsafe_from_local_path(local_work_dir)
 
 
-def _find_session_by_id(session_id: str, *, work_dir: KaosPath | None = None) -> Path | None:
+def find_session_dir(session_id: str, *, work_dir: KaosPath | None = None) -> Path | None:
     """Find a session directory by ID, preferring the current work directory."""
     if work_dir is not None:
         session = asyncio.run(_find_session_in_work_dir(work_dir, session_id))
@@ -78,7 +78,8 @@ def _last_user_message_timestamp(session_dir: Path) -> float | None:
     if not wire_file.exists():
         return None
 
-    last_turn_begin: float | None = None
+    last_turn_begin: tuple[int, float] | None = None
+    position = 0
     try:
         with wire_file.open(encoding="utf-8") as f:
             for line in f:
@@ -91,12 +92,16 @@ def _last_user_message_timestamp(session_dir: Path) -> float | None:
                     continue
                 if isinstance(parsed, WireFileMetadata):
                     continue
-                if isinstance(parsed.to_wire_message(), TurnBegin):
-                    last_turn_begin = parsed.timestamp
+                position += 1
+                seq = parsed.seq or position
+                if isinstance(parsed.to_wire_message(), TurnBegin) and (
+                    last_turn_begin is None or seq >= last_turn_begin[0]
+                ):
+                    last_turn_begin = (seq, parsed.timestamp)
     except OSError:
         return None
 
-    return last_turn_begin
+    return last_turn_begin[1] if last_turn_begin else None
 
 
 def _session_time_range(session_dir: Path) -> tuple[float | None, float | None]:
@@ -105,8 +110,10 @@ def _session_time_range(session_dir: Path) -> tuple[float | None, float | None]:
     if not wire_file.exists():
         return None, None
 
-    first_ts: float | None = None
-    last_ts: float | None = None
+    # Ordered by `seq`; records written by older versions are numbered by their position
+    first: tuple[int, float] | None = None
+    last: tuple[int, float] | None = None
+    position = 0
     try:
         with wire_file.open(encoding="utf-8") as f:
             for line in f:
@@ -119,12 +126,15 @@ def _session_time_range(session_dir: Path) -> tuple[float | None, float | None]:
                     continue
                 if isinstance(parsed, WireFileMetadata):
                     continue
-                if first_ts is None:
-                    first_ts = parsed.timestamp
-                last_ts = parsed.timestamp
+                position += 1
+                key = (parsed.seq or position, parsed.timestamp)
+                if first is None or key[0] < first[0]:
+                    first = key
+                if last is None or key[0] >= last[0]:
+                    last = key
     except OSError:
         pass
-    return first_ts, last_ts
+    return (first[1] if first else None), (last[1] if last else None)
 
 
 def _collect_recent_log_files(session_dir: Path) -> list[Path]:
@@ -263,7 +273,7 @@ def export(
     ] = False,
 ) -> None:
     """Export a session as a ZIP archive."""
-    work_dir = _resolve_work_dir(ctx)
+    work_dir = resolve_work_dir(ctx)
 
     if session_id is None:
         session = asyncio.run(_load_previous_session(work_dir))
@@ -276,7 +286,7 @@ def export(
         session_id = session.id
         session_dir = session.dir
     else:
-        session_dir = _find_session_by_id(session_id, work_dir=work_dir)
+        session_dir = find_session_dir(session_id, work_dir=work_dir)
         if session_dir is None:
             typer.echo(f"Error: session '{session_id}' not found.", err=True)
             raise typer.Exit(code=1)
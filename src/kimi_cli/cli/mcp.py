@@ -220,27 +220,67 @@ def _has_oauth_tokens(server_url: str) -> bool:
     return asyncio.run(_check())
 
 
+def _mcp_server_transport(server: dict[str, Any]) -> str | None:
+    """The transport of a configured server, or None if it is neither stdio nor remote."""
+    if "command" in server:
+        return "stdio"
+    if "url" in server:
+        transport = server.get("transport") or "http"
+        return "http" if transport == "streamable-http" else transport
+    return None
+
+
+def _mcp_server_summary(name: str, server: dict[str, Any]) -> dict[str, Any]:
+    """A JSON-serializable summary of a configured server for `kimi mcp list --json`."""
+    transport = _mcp_server_transport(server)
+    auth_required = (
+        "url" in server and server.get("auth") == "oauth" and not _has_oauth_tokens(server["url"])
+    )
+    summary: dict[str, Any] = {"name": name, "transport": transport}
+    if "url" in server:
+        summary["url"] = server["url"]
+    if "command" in server:
+        summary["command"] = server["command"]
+        summary["args"] = server.get("args", [])
+    summary["auth_required"] = auth_required
+    if transport is None:
+        summary["status"] = "invalid"
+    elif auth_required:
+        summary["status"] = "authorization_required"
+    else:
+        summary["status"] = "ready"
+    return summary
+
+
 @cli.command("list")
-def mcp_list():
+def mcp_list(
+    json_output: Annotated[
+        bool,
+        typer.Option("--json", help="Output the servers as a JSON array."),
+    ] = False,
+):
     """List all MCP servers."""
     config_file = get_global_mcp_config_file()
     config = _load_mcp_config()
     servers: dict[str, Any] = config.get("mcpServers", {})
 
+    if json_output:
+        summaries = [_mcp_server_summary(name, server) for name, server in servers.items()]
+        typer.echo(json.dumps(summaries, ensure_ascii=False))
+        return
+
     typer.echo(f"MCP config file: {config_file}")
     if not servers:
         typer.echo("No MCP servers configured.")
         return
 
     for name, server in servers.items():
-        if "command" in server:
+        transport = _mcp_server_transport(server)
+        if transport == "stdio":
             cmd = server["command"]
             cmd_args = " ".join(server.get("args", []))
             line = f"{name} (stdio): {cmd} {cmd_args}".rstrip()
-        elif "url" in server:
-            transport = server.get("transport") or "http"
-            if transport == "streamable-http":
-                transport = "http"
+        elif transport is not None:
             line = f"{name} ({transport}): {server['url']}"
             if server.get("auth") == "oauth" and not _has_oauth_tokens(server["url"]):
                 line += " [authorization required - run: kimi mcp auth " + name + "]"
@@ -272,8 +312,8 @@ def mcp_auth(
         typer.echo(f"Authorizing with '{name}'...")
         typer.echo("A browser window will open for authorization.")
 
-        client = fastmcp.Client({"mcpServers": {name: prepare_mcp_server_config(server)}})
         try:
+            client = fastmcp.Client({"mcpServers": {name: prepare_mcp_server_config(server)}})
             async with client:
                 tools = await client.list_tools()
                 typer.echo(f"Successfully authorized with '{name}'.")
@@ -332,9 +372,8 @@ def mcp_test(
         from kimi_cli.mcp_oauth import prepare_mcp_server_config
 
         typer.echo(f"Testing connection to '{name}'...")
-        client = fastmcp.Client({"mcpServers": {name: prepare_mcp_server_config(server)}})
-
         try:
+            client = fastmcp.Client({"mcpServers": {name: prepare_mcp_server_config(server)}})
             async with client:
                 tools = await client.list_tools()
                 typer.echo(f"✓ Connected to '{name}'")
@@ -0,0 +1,53 @@
+"""Session management commands."""
+
+from __future__ import annotations
+
+import asyncio
+from pathlib import Path
+from typing import Annotated
+
+import typer
+
+cli = typer.Typer(help="Manage sessions.")
+
+
+def _session_data_files(session_dir: Path) -> list[Path]:
+    """The context and wire files of a session and its subagents, including rotated ones."""
+    return sorted(
+        path
+        for pattern in ("context*.jsonl", "wire*.jsonl")
+        for path in session_dir.rglob(pattern)
+        if path.is_file()
+    )
+
+
+@cli.command("encrypt")
+def encrypt(
+    ctx: typer.Context,
+    session_id: Annotated[str, typer.Argument(help="Session ID to encrypt.")],
+) -> None:
+    """Encrypt the context and wire files of an existing session in place."""
+    from kimi_cli.cli.export import find_session_dir, resolve_work_dir
+    from kimi_cli.exception import SessionEncryptionError
+    from kimi_cli.utils.session_crypto import SessionCipher, encrypt_session_file
+
+    session_dir = find_session_dir(session_id, work_dir=resolve_work_dir(ctx))
+    if session_dir is None:
+        typer.echo(f"Error: session '{session_id}' not found.", err=True)
+        raise typer.Exit(code=1)
+
+    try:
+        cipher = SessionCipher.load(create=True)
+    except SessionEncryptionError as exc:
+        typer.echo(f"Error: {exc}", err=True)
+        raise typer.Exit(code=1) from exc
+    assert cipher is not None
+
+    async def _encrypt_all() -> int:
+        encrypted = 0
+        for path in _session_data_files(session_dir):
+            encrypted += await encrypt_session_file(path, cipher)
+        return encrypted
+
+    encrypted = asyncio.run(_encrypt_all())
+    typer.echo(f"Encrypted {encrypted} lines of session '{session_id}' with key {cipher.key_id}.")
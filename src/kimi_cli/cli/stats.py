@@ -0,0 +1,111 @@
+"""Usage statistics aggregated across sessions."""
+
+from __future__ import annotations
+
+import json
+import time
+from pathlib import Path
+from typing import TYPE_CHECKING, Annotated
+
+import typer
+
+if TYPE_CHECKING:
+    from kimi_cli.session_stats import StatsReport
+
+cli = typer.Typer(help="Show usage statistics across sessions.")
+
+
+def _session_dirs(work_dir: Path | None) -> list[Path]:
+    """The session directories of a work directory, or of all work directories."""
+    from kaos.path import KaosPath
+
+    from kimi_cli.metadata import load_metadata
+    from kimi_cli.share import get_share_dir
+
+    if work_dir is not None:
+        meta = load_metadata().get_work_dir_meta(
+            KaosPath.unsafe_from_local_path(work_dir).canonical()
+        )
+        roots = [meta.sessions_dir] if meta is not None else []
+    else:
+        sessions_root = get_share_dir() / "sessions"
+        roots = sorted(sessions_root.iterdir()) if sessions_root.is_dir() else []
+    return [
+        session_dir
+        for root in roots
+        if root.is_dir()
+        for session_dir in sorted(root.iterdir())
+        if session_dir.is_dir()
+    ]
+
+
+def _format_report(report: StatsReport) -> list[str]:
+    stats, usage = report.stats, report.usage
+    input_tokens = usage.input_other + usage.input_cache_read + usage.input_cache_creation
+    lines = [
+        f"Sessions:  {report.sessions}",
+        f"Turns:     {stats.turns}",
+        f"Steps:     {stats.steps}",
+        f"Retries:   {stats.retries}",
+        f"Approvals: {stats.approvals_approved} approved, {stats.approvals_rejected} rejected",
+        (
+            f"Tokens:    {input_tokens:,} input ({usage.input_cache_read:,} cached), "
+            f"{usage.output:,} output"
+        ),
+        f"Cost:      ${usage.cost:.2f}"
+        + (f" (+{usage.unpriced_calls} unpriced calls)" if usage.unpriced_calls else ""),
+    ]
+    if not stats.tools:
+        return lines
+    tools = sorted(stats.tools.items(), key=lambda item: item[1].duration_s, reverse=True)
+    width = max(len("Tool"), *(len(name) for name, _ in tools))
+    lines.append("")
+    lines.append(f"{'Tool':<{width}}  {'Calls':>7}  {'Errors':>7}  {'Total':>9}  {'Average':>9}")
+    for name, tool in tools:
+        average = tool.duration_s / tool.calls if tool.calls else 0.0
+        lines.append(
+            f"{name:<{width}}  {tool.calls:>7}  {tool.errors:>7}  "
+            f"{tool.duration_s:>8.1f}s  {average:>8.1f}s"
+        )
+    return lines
+
+
+@cli.callback(invoke_without_command=True)
+def stats(
+    work_dir: Annotated[
+        Path | None,
+        typer.Option(
+            "--work-dir",
+            "-w",
+            help="Only include the sessions of this work directory. Default: all sessions.",
+        ),
+    ] = None,
+    since: Annotated[
+        str | None,
+        typer.Option(
+            "--since",
+            help="Only include the sessions active within this duration, e.g. 12h or 7d.",
+        ),
+    ] = None,
+    json_output: Annotated[
+        bool,
+        typer.Option("--json", help="Output statistics as JSON."),
+    ] = False,
+):
+    """Show tool calls, steps, turns, token usage, retries and approvals across sessions."""
+    from kimi_cli.session_stats import aggregate_session_stats
+    from kimi_cli.utils.datetime import parse_duration
+
+    since_ts: float | None = None
+    if since is not None:
+        try:
+            since_ts = time.time() - parse_duration(since)
+        except ValueError as e:
+            raise typer.BadParameter(str(e), param_hint="--since") from e
+
+    report = aggregate_session_stats(_session_dirs(work_dir), since=since_ts)
+    if json_output:
+        typer.echo(json.dumps(report.model_dump(mode="json"), ensure_ascii=False))
+        return
+    for line in _format_report(report):
+        typer.echo(line)
@@ -0,0 +1,81 @@
+"""Developer tools for the Wire protocol."""
+
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import sys
+from pathlib import Path
+from typing import TYPE_CHECKING, Annotated
+
+import typer
+
+if TYPE_CHECKING:
+    from kimi_cli.wire import WireUISide
+
+cli = typer.Typer(help="Wire protocol developer tools.")
+
+
+@cli.command("replay")
+def wire_replay(
+    file: Annotated[
+        Path,
+        typer.Argument(help="The recorded wire file to replay, e.g. a session's wire.jsonl."),
+    ],
+    speed: Annotated[
+        float,
+        typer.Option("--speed", "-s", min=0.01, help="Playback speed relative to the recording."),
+    ] = 1.0,
+    jsonrpc: Annotated[
+        bool,
+        typer.Option(
+            "--jsonrpc",
+            help="Write the messages to stdout as Wire JSON-RPC messages instead of rendering "
+            "them in the terminal.",
+        ),
+    ] = False,
+):
+    """Replay a recorded wire file as if the session were live."""
+    if not file.is_file():
+        typer.echo(f"Wire file not found: {file}", err=True)
+        raise typer.Exit(code=1)
+    asyncio.run(_replay(file, speed=speed, jsonrpc=jsonrpc))
+
+
+async def _replay(file: Path, *, speed: float, jsonrpc: bool) -> None:
+    from kimi_cli.utils.aioqueue import QueueShutDown
+    from kimi_cli.wire import Wire
+    from kimi_cli.wire.file import WireFile
+
+    wire = Wire()
+    if jsonrpc:
+        ui_task = asyncio.create_task(_write_jsonrpc(wire.ui_side(merge=True)))
+    else:
+        from kimi_cli.ui.shell.visualize import visualize
+        from kimi_cli.wire.types import StatusUpdate
+
+        ui_task = asyncio.create_task(
+            visualize(wire.ui_side(merge=False), initial_status=StatusUpdate())
+        )
+    await WireFile(file).replay_into(wire, speed=speed)
+    wire.shutdown()
+    with contextlib.suppress(QueueShutDown):
+        await ui_task
+
+
+async def _write_jsonrpc(ui_side: WireUISide) -> None:
+    from kimi_cli.utils.aioqueue import QueueShutDown
+    from kimi_cli.wire.jsonrpc import JSONRPCEventMessage, JSONRPCRequestMessage
+    from kimi_cli.wire.types import is_request
+
+    while True:
+        try:
+            msg = await ui_side.receive()
+        except QueueShutDown:
+            return
+        if is_request(msg):
+            line = JSONRPCRequestMessage(id=msg.id, params=msg).model_dump_json()
+        else:
+            line = JSONRPCEventMessage(params=msg).model_dump_json()
+        sys.stdout.write(line + "\n")
+        sys.stdout.flush()
@@ -1,6 +1,7 @@
 from __future__ import annotations
 
 import json
+import re
 from pathlib import Path
 from typing import Literal, Self
 
@@ -12,6 +13,7 @@ from pydantic import (
     SecretStr,
     ValidationError,
     field_serializer,
+    field_validator,
     model_validator,
 )
 from tomlkit.exceptions import TOMLKitError
@@ -57,6 +59,19 @@ class LLMProvider(BaseModel):
         return v.get_secret_value()
 
 
+class ModelPricing(BaseModel):
+    """Model pricing in USD per million tokens, used to estimate session cost."""
+
+    input: float = Field(ge=0)
+    """Price of uncached input tokens"""
+    output: float = Field(ge=0)
+    """Price of output tokens"""
+    cache_read: float | None = Field(default=None, ge=0)
+    """Price of cached input tokens. Defaults to the input price."""
+    cache_creation: float | None = Field(default=None, ge=0)
+    """Price of input tokens written to the cache. Defaults to the input price."""
+
+
 class LLMModel(BaseModel):
     """LLM model configuration."""
 
@@ -70,6 +85,13 @@ class LLMModel(BaseModel):
     """Model capabilities"""
     display_name: str | None = None
     """Human-readable model name (sourced from the provider's models API when available)"""
+    pricing: ModelPricing | None = None
+    """Pricing used by `/cost` to estimate spend"""
+    max_output_tokens: int | None = Field(default=None, ge=1)
+    """Maximum number of tokens to generate per step, overriding `generation.max_output_tokens`.
+    Unset uses the provider default (32000 for Kimi)."""
+    parallel_tool_calls: bool | None = None
+    """Whether the model may call several tools in one step. Unset uses the provider default."""
 
 
 class LoopControl(BaseModel):
@@ -85,6 +107,12 @@ class LoopControl(BaseModel):
     """Maximum number of retries in one step"""
     max_ralph_iterations: int = Field(default=0, ge=-1)
     """Extra iterations after the first turn in Ralph mode. Use -1 for unlimited."""
+    ralph_success_command: str | None = None
+    """Shell command run after each iteration in Ralph mode. The loop ends when it exits with
+    code 0; otherwise the end of its output is passed to the next iteration."""
+    ralph_failure_limit: int | None = Field(default=None, ge=1)
+    """Stop Ralph mode after this many consecutive iterations in which the success command
+    failed with the same output. Unlimited by default."""
     reserved_context_size: int = Field(default=50_000, ge=1000)
     """Reserved token count for LLM response generation. Auto-compaction triggers when
     either context_tokens + reserved_context_size >= max_context_size or
@@ -93,6 +121,52 @@ class LoopControl(BaseModel):
     """Context usage ratio threshold for auto-compaction. Default is 0.85 (85%).
     Auto-compaction triggers when context_tokens >= max_context_size * compaction_trigger_ratio
     or when context_tokens + reserved_context_size >= max_context_size."""
+    compaction_max_output_tokens: int = Field(default=16_000, ge=1000)
+    """Maximum number of tokens the compaction summary may generate. Default is 16000."""
+    max_message_chars: int = Field(default=200_000, ge=1000)
+    """Maximum size in characters of one assistant message, including thinking and tool call
+    arguments. A step streaming more than this is stopped and its message discarded, so that a
+    model stuck repeating itself does not flood the context. Default is 200000."""
+    max_tokens_per_turn: int | None = Field(default=None, ge=1)
+    """Maximum number of input and output tokens used by one turn, including subagents and
+    compaction. The turn stops after the step that exceeds it. Unlimited by default."""
+    max_cost_per_turn: float | None = Field(default=None, gt=0)
+    """Maximum estimated cost in USD of one turn. Only enforced for models with `pricing`
+    configured. The turn stops after the step that exceeds it. Unlimited by default."""
+    max_subagent_event_depth: int = Field(default=3, ge=1)
+    """Maximum number of `SubagentEvent` envelopes wrapped around an event forwarded from nested
+    subagents. Deeper events are flattened into the innermost allowed envelope. Default is 3."""
+    slow_phase_threshold_s: float = Field(default=120, ge=0)
+    """Seconds after which a step still generating, running tools or waiting to retry is
+    reported as slow, with a warning in the log and a `StatusUpdate`. Default is 120. Set to 0
+    to disable."""
+
+
+class GenerationConfig(BaseModel):
+    """Default generation options for agent steps. Unset fields use the provider defaults."""
+
+    max_output_tokens: int | None = Field(default=None, ge=1)
+    """Maximum number of tokens to generate per step"""
+    stop: list[str] = Field(default_factory=list)
+    """Sequences at which generation stops"""
+    temperature: float | None = Field(default=None, ge=0)
+    top_p: float | None = Field(default=None, gt=0, le=1)
+
+
+class RateLimitConfig(BaseModel):
+    """Limits on the requests sent to each model, shared by the main agent and its subagents.
+    Unset fields leave the requests unlimited until the API answers 429."""
+
+    max_concurrent_requests: int | None = Field(default=None, ge=1)
+    """Maximum number of requests in flight per model"""
+    requests_per_minute: int | None = Field(default=None, ge=1)
+    """Maximum number of requests started per minute per model"""
+
+
+class LLMConfig(BaseModel):
+    """How requests are sent to the LLM providers."""
+
+    rate_limit: RateLimitConfig = Field(default_factory=RateLimitConfig)
 
 
 class BackgroundConfig(BaseModel):
@@ -173,6 +247,10 @@ class MCPClientConfig(BaseModel):
 
     tool_call_timeout_ms: int = 60000
     """Timeout for tool calls in milliseconds."""
+    prefix_tool_names: bool = False
+    """Name every MCP tool `<server>__<tool>`, not only those whose name is already taken."""
+    max_concurrent_connections: int = Field(default=4, ge=1)
+    """How many MCP servers are connected to at a time when loading their tools."""
 
 
 class MCPConfig(BaseModel):
@@ -183,6 +261,121 @@ class MCPConfig(BaseModel):
     )
 
 
+class NetworkConfig(BaseModel):
+    """Proxy and TLS settings for outbound HTTP requests."""
+
+    http_proxy: str | None = None
+    """Proxy for `http://` URLs. Falls back to the `HTTP_PROXY` environment variable."""
+    https_proxy: str | None = None
+    """Proxy for `https://` URLs. Falls back to the `HTTPS_PROXY` environment variable."""
+    no_proxy: list[str] | None = None
+    """Hosts, domain suffixes or CIDR ranges that bypass the proxy. Falls back to `NO_PROXY`."""
+    extra_ca_bundle: str | None = None
+    """PEM file with extra CA certificates to trust, e.g. for a TLS-intercepting firewall."""
+    accept_invalid_certs: bool = False
+    """Disable TLS certificate verification. Insecure, only use for debugging."""
+
+
+class RedactionConfig(BaseModel):
+    """Redaction of secrets from tool outputs, session files and logs."""
+
+    enabled: bool = True
+    """Replace known secrets and credential-like strings with `[REDACTED:<kind>]`."""
+    patterns: dict[str, str] = Field(default_factory=dict)
+    """Extra regular expressions keyed by kind, added to (or overriding) the builtin ones."""
+    redact_wire: bool = False
+    """Also redact tool results shown in the UI. The context and session files are always
+    redacted."""
+
+    @field_validator("patterns")
+    @classmethod
+    def validate_patterns(cls, v: dict[str, str]) -> dict[str, str]:
+        for kind, pattern in v.items():
+            try:
+                re.compile(f"(?:{pattern})")
+            except re.error as e:
+                raise ValueError(f"Invalid redaction pattern for {kind!r}: {e}") from e
+        return v
+
+
+class FileJournalConfig(BaseModel):
+    """Journal of file pre-images, used to undo file changes when reverting to a checkpoint."""
+
+    enabled: bool = False
+    """Record the original content of files before `WriteFile` and `StrReplaceFile` change them."""
+    max_file_size: int = Field(default=1024 * 1024, ge=1)
+    """Files larger than this many bytes are not recorded, and cannot be restored."""
+    max_total_size: int = Field(default=50 * 1024 * 1024, ge=1)
+    """Maximum bytes of pre-images kept per session. Once reached, further files are not
+    recorded."""
+
+
+class SessionConfig(BaseModel):
+    """Lifetime of sessions."""
+
+    idle_timeout_secs: float | None = Field(default=None, gt=0)
+    """End a Wire server session that received no request and ran no turn for this many
+    seconds. None means never."""
+
+
+class ImageInputConfig(BaseModel):
+    """Limits on images attached to user input, e.g. pasted in the shell or sent over the wire."""
+
+    max_image_size: int = Field(default=5 * 1024 * 1024, ge=1)
+    """Images larger than this many bytes are downscaled, and rejected if they are still too
+    large."""
+    max_message_size: int = Field(default=20 * 1024 * 1024, ge=1)
+    """Maximum total bytes of the images in one message."""
+    max_dimension: int = Field(default=4096, ge=1)
+    """Images wider or taller than this many pixels are downscaled to fit."""
+
+
+class ToolsConfig(BaseModel):
+    """Which tools the agents may use. Applies to built-in, plugin, MCP and Wire external tools."""
+
+    enabled: list[str] | None = None
+    """If set, only the tools with these names are registered."""
+    disabled: list[str] = Field(default_factory=list)
+    """Names of tools that are never registered, even if listed in `enabled`."""
+    max_write_bytes: int = Field(default=4 * 1024 * 1024, ge=1)
+    """Maximum number of bytes one file writing or editing call may write. For edits, only the
+    growth of the file counts, so that files already over the limit can still be edited."""
+    timeout_s: float = Field(default=600, ge=0)
+    """Seconds a tool call may run before it is stopped, 0 for no limit. Time spent waiting for
+    approval does not count. Tools with a timeout of their own, such as `Agent`, are exempt."""
+    timeouts: dict[str, float] = Field(default_factory=dict)
+    """`timeout_s` of specific tools by name, which applies to exempt tools as well."""
+
+    def is_enabled(self, name: str) -> bool:
+        """Whether the tool with the given name may be registered."""
+        if name in self.disabled:
+            return False
+        return self.enabled is None or name in self.enabled
+
+
+class LSPServerConfig(BaseModel):
+    """Language server configuration."""
+
+    command: list[str] = Field(min_length=1)
+    """Command and arguments to start the language server speaking LSP over stdio."""
+    extensions: list[str] = Field(default_factory=list)
+    """File suffixes (e.g. ``.py``) handled by this server, overriding the builtin mapping."""
+    env: dict[str, str] = Field(default_factory=dict)
+    """Extra environment variables for the server process."""
+    initialization_options: dict[str, object] | None = None
+    """``initializationOptions`` sent with the ``initialize`` request."""
+
+
+class LSPConfig(BaseModel):
+    """LSP integration configuration."""
+
+    servers: dict[str, LSPServerConfig] = Field(default_factory=dict)
+    """Language servers keyed by LSP language id (e.g. ``python``, ``rust``)."""
+
+
+type StreamThinking = Literal["full", "summary", "off"]
+
+
 class Config(BaseModel):
     """Main configuration structure."""
 
@@ -225,11 +418,24 @@ class Config(BaseModel):
             "trace summary."
         ),
     )
+    stream_thinking: StreamThinking | None = Field(
+        default=None,
+        description=(
+            "How much of the model's reasoning is forwarded to the UI while streaming: 'full' "
+            "forwards all of it, 'summary' only the first characters of each step, 'off' none. "
+            "Reasoning is kept in the context either way. Defaults to 'off' in print mode and "
+            "'full' otherwise."
+        ),
+    )
     models: dict[str, LLMModel] = Field(default_factory=dict, description="List of LLM models")
     providers: dict[str, LLMProvider] = Field(
         default_factory=dict, description="List of LLM providers"
     )
     loop_control: LoopControl = Field(default_factory=LoopControl, description="Agent loop control")
+    generation: GenerationConfig = Field(
+        default_factory=GenerationConfig, description="Default generation options"
+    )
+    llm: LLMConfig = Field(default_factory=LLMConfig, description="LLM request configuration")
     background: BackgroundConfig = Field(
         default_factory=BackgroundConfig, description="Background task configuration"
     )
@@ -237,7 +443,37 @@ class Config(BaseModel):
         default_factory=NotificationConfig, description="Notification configuration"
     )
     services: Services = Field(default_factory=Services, description="Services configuration")
+    tools: ToolsConfig = Field(default_factory=ToolsConfig, description="Tool availability")
     mcp: MCPConfig = Field(default_factory=MCPConfig, description="MCP configuration")
+    lsp: LSPConfig = Field(default_factory=LSPConfig, description="LSP configuration")
+    network: NetworkConfig = Field(
+        default_factory=NetworkConfig, description="Proxy and TLS configuration"
+    )
+    redaction: RedactionConfig = Field(
+        default_factory=RedactionConfig, description="Secret redaction configuration"
+    )
+    file_journal: FileJournalConfig = Field(
+        default_factory=FileJournalConfig, description="File journal configuration"
+    )
+    session: SessionConfig = Field(
+        default_factory=SessionConfig, description="Session lifetime configuration"
+    )
+    image_input: ImageInputConfig = Field(
+        default_factory=ImageInputConfig, description="Limits on images in user input"
+    )
+    on_unsupported_media: Literal["error", "strip", "describe"] = Field(
+        default="error",
+        description=(
+            "What to do when a message has images or videos the current model cannot see: "
+            "'error' fails the turn, 'strip' replaces them with a note saying what was removed, "
+            "'describe' replaces them with a caption written by `vision_model`, or with the note "
+            "if no vision model is set."
+        ),
+    )
+    vision_model: str | None = Field(
+        default=None,
+        description="Model used to caption media in `on_unsupported_media = \"describe\"` mode",
+    )
     hooks: list[HookDef] = Field(default_factory=list, description="Hook definitions")  # pyright: ignore[reportUnknownVariableType]
     merge_all_available_skills: bool = Field(
         default=True,
@@ -258,15 +494,41 @@ class Config(BaseModel):
             "Missing paths are silently skipped."
         ),
     )
+    additional_dirs: list[str] = Field(
+        default_factory=list,
+        description=(
+            "Extra directories added to the workspace of every session, like ``--add-dir``. "
+            "Each entry may be an absolute path, ``~``-prefixed (expanded against $HOME), or "
+            "relative to the project root (the nearest ``.git`` directory above the work dir). "
+            "Missing paths are skipped with a warning."
+        ),
+    )
     telemetry: bool = Field(
         default=True,
         description="Enable anonymous telemetry to help improve kimi-cli. Set to false to disable.",
     )
+    encrypt_sessions: bool = Field(
+        default=False,
+        description=(
+            "Encrypt the context and wire files of sessions with the key in ``KIMI_SESSION_KEY`` "
+            "or the OS keychain, generating one in the keychain if there is none."
+        ),
+    )
+    verify_model: Literal["off", "warn", "strict"] = Field(
+        default="off",
+        description=(
+            "Probe the model at startup and compare it with its declared capabilities: 'warn' "
+            "prints a warning for each mismatch, 'strict' also refuses to start. Probe results "
+            "are cached for a day per provider and model."
+        ),
+    )
 
     @model_validator(mode="after")
     def validate_model(self) -> Self:
         if self.default_model and self.default_model not in self.models:
             raise ValueError(f"Default model {self.default_model} not found in models")
+        if self.vision_model and self.vision_model not in self.models:
+            raise ValueError(f"Vision model {self.vision_model} not found in models")
         for model in self.models.values():
             if model.provider not in self.providers:
                 raise ValueError(f"Provider {model.provider} not found in providers")
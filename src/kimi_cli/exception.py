@@ -41,3 +41,23 @@ class MCPRuntimeError(KimiCLIException, RuntimeError):
     """MCP runtime error."""
 
     pass
+
+
+class SessionEncryptionError(KimiCLIException, RuntimeError):
+    """An encrypted session file cannot be read, or the session key cannot be loaded."""
+
+    pass
+
+
+class ModelVerificationError(KimiCLIException, ValueError):
+    """The model does not match the capabilities declared in its config."""
+
+    pass
+
+
+class KimiCLIBuilderError(KimiCLIException, ValueError):
+    """The settings of a `KimiCLIBuilder` have problems, all listed in `problems`."""
+
+    def __init__(self, problems: list[str]):
+        super().__init__("Invalid setup:\n" + "\n".join(f"- {problem}" for problem in problems))
+        self.problems = problems
@@ -3,10 +3,13 @@ from __future__ import annotations
 import json
 import os
 from dataclasses import dataclass
+from datetime import datetime
 from pathlib import Path
 from typing import TYPE_CHECKING, Literal, cast, get_args
 
 from kosong.chat_provider import ChatProvider
+from kosong.chat_provider.cache import CachingChatProvider, Cassette
+from kosong.chat_provider.rate_limit import RateLimiter
 from pydantic import SecretStr
 
 from kimi_cli.constant import USER_AGENT
@@ -14,7 +17,7 @@ from kimi_cli.utils.logging import logger
 
 if TYPE_CHECKING:
     from kimi_cli.auth.oauth import OAuthManager
-    from kimi_cli.config import Config, LLMModel, LLMProvider
+    from kimi_cli.config import Config, LLMModel, LLMProvider, ModelPricing, RateLimitConfig
 
 type ProviderType = Literal[
     "kimi",
@@ -29,10 +32,32 @@ type ProviderType = Literal[
     "_chaos",
 ]
 
-type ModelCapability = Literal["image_in", "video_in", "thinking", "always_thinking"]
+type ModelCapability = Literal["image_in", "video_in", "file_in", "thinking", "always_thinking"]
 ALL_MODEL_CAPABILITIES: set[ModelCapability] = set(get_args(ModelCapability.__value__))
 
 
+@dataclass(frozen=True, slots=True)
+class LLMCassette:
+    """A directory to record the LLM responses to, or to replay them from."""
+
+    directory: Path
+    mode: Literal["record", "replay"]
+
+    def pinned_now(self) -> str:
+        """
+        The current time to show the model, pinned to the first session recorded to the cassette
+        so that the system prompt, and with it the request keys, match across sessions.
+        """
+        path = self.directory / "now.txt"
+        if path.is_file():
+            return path.read_text(encoding="utf-8").strip()
+        now = datetime.now().astimezone().isoformat()
+        if self.mode == "record":
+            self.directory.mkdir(parents=True, exist_ok=True)
+            path.write_text(now, encoding="utf-8")
+        return now
+
+
 @dataclass(slots=True)
 class LLM:
     chat_provider: ChatProvider
@@ -40,11 +65,17 @@ class LLM:
     capabilities: set[ModelCapability]
     model_config: LLMModel | None = None
     provider_config: LLMProvider | None = None
+    cassette: LLMCassette | None = None
+    """The cassette the responses are recorded to or replayed from, kept for derived LLMs."""
 
     @property
     def model_name(self) -> str:
         return self.chat_provider.model_name
 
+    @property
+    def pricing(self) -> ModelPricing | None:
+        return self.model_config.pricing if self.model_config is not None else None
+
 
 def model_display_name(model_name: str | None, model: LLMModel | None = None) -> str:
     if model is not None and model.display_name:
@@ -113,8 +144,20 @@ def create_llm(
     thinking: bool | None = None,
     session_id: str | None = None,
     oauth: OAuthManager | None = None,
+    cassette: LLMCassette | None = None,
 ) -> LLM | None:
-    if provider.type not in {"_echo", "_scripted_echo"} and (
+    """
+    Create the LLM of a model, or None if the model is not configured.
+
+    With a `cassette`, the responses are recorded to it or replayed from it, see
+    `kosong.chat_provider.cache`. Replaying never creates the provider itself.
+    """
+    if cassette is not None and cassette.mode == "replay":
+        # Replay without creating the real provider, which may need credentials or a network.
+        chat_provider: ChatProvider = CachingChatProvider(
+            None, Cassette(cassette.directory), mode="replay", model_name=model.model
+        )
+    elif provider.type not in {"_echo", "_scripted_echo"} and (
         not provider.base_url or not model.model
     ):
         logger.warning(
@@ -122,7 +165,54 @@ def create_llm(
             provider_type=provider.type,
         )
         return None
+    else:
+        chat_provider = _create_chat_provider(provider, model, session_id=session_id, oauth=oauth)
+
+    capabilities = derive_model_capabilities(model)
+
+    # Apply thinking if specified or if model always requires thinking
+    thinking_on = "always_thinking" in capabilities or (
+        thinking is True and "thinking" in capabilities
+    )
+    if thinking_on:
+        chat_provider = chat_provider.with_thinking("high")
+    elif thinking is False:
+        chat_provider = chat_provider.with_thinking("off")
+    # If thinking is None and model doesn't always think, leave as-is (default behavior)
+
+    # Apply Moonshot-specific ``thinking.keep`` (preserved thinking) only when
+    # the model is actually in thinking mode; otherwise the API would see a
+    # ``thinking.keep`` without an accompanying ``thinking.type`` it honors.
+    if thinking_on and provider.type == "kimi":
+        from kosong.chat_provider.kimi import Kimi
+
+        if isinstance(chat_provider, Kimi) and (
+            thinking_keep := os.getenv("KIMI_MODEL_THINKING_KEEP")
+        ):
+            chat_provider = chat_provider.with_extra_body({"thinking": {"keep": thinking_keep}})
+
+    if cassette is not None and cassette.mode == "record":
+        chat_provider = CachingChatProvider(
+            chat_provider, Cassette(cassette.directory), mode="record", model_name=model.model
+        )
+
+    return LLM(
+        chat_provider=chat_provider,
+        max_context_size=model.max_context_size,
+        capabilities=capabilities,
+        model_config=model,
+        provider_config=provider,
+        cassette=cassette,
+    )
+
 
+def _create_chat_provider(
+    provider: LLMProvider,
+    model: LLMModel,
+    *,
+    session_id: str | None,
+    oauth: OAuthManager | None,
+) -> ChatProvider:
     resolved_api_key = (
         oauth.resolve_api_key(provider.api_key, provider.oauth)
         if oauth and provider.oauth
@@ -133,11 +223,14 @@ def create_llm(
         case "kimi":
             from kosong.chat_provider.kimi import Kimi
 
+            from kimi_cli.utils.network import new_httpx_client
+
             chat_provider = Kimi(
                 model=model.model,
                 base_url=provider.base_url,
                 api_key=resolved_api_key,
                 default_headers=_kimi_default_headers(provider, oauth),
+                http_client=new_httpx_client(),
             )
 
             gen_kwargs: Kimi.GenerationKwargs = {}
@@ -155,6 +248,8 @@ def create_llm(
         case "openai_legacy":
             from kosong.contrib.chat_provider.openai_legacy import OpenAILegacy
 
+            from kimi_cli.utils.network import new_httpx_client
+
             reasoning_key = (
                 provider.reasoning_key
                 if provider.reasoning_key is not None
@@ -166,19 +261,25 @@ def create_llm(
                 api_key=resolved_api_key,
                 reasoning_key=reasoning_key,
                 default_headers=dict(provider.custom_headers) if provider.custom_headers else None,
+                http_client=new_httpx_client(),
             )
         case "openai_responses":
             from kosong.contrib.chat_provider.openai_responses import OpenAIResponses
 
+            from kimi_cli.utils.network import new_httpx_client
+
             chat_provider = OpenAIResponses(
                 model=model.model,
                 base_url=provider.base_url,
                 api_key=resolved_api_key,
                 default_headers=dict(provider.custom_headers) if provider.custom_headers else None,
+                http_client=new_httpx_client(),
             )
         case "anthropic":
             from kosong.contrib.chat_provider.anthropic import Anthropic
 
+            from kimi_cli.utils.network import new_httpx_client
+
             chat_provider = Anthropic(
                 model=model.model,
                 base_url=provider.base_url,
@@ -186,19 +287,25 @@ def create_llm(
                 default_max_tokens=50000,
                 metadata={"user_id": session_id} if session_id else None,
                 default_headers=dict(provider.custom_headers) if provider.custom_headers else None,
+                http_client=new_httpx_client(),
             )
         case "google_genai" | "gemini":
             from kosong.contrib.chat_provider.google_genai import GoogleGenAI
 
+            from kimi_cli.utils.network import new_httpx_client
+
             chat_provider = GoogleGenAI(
                 model=model.model,
                 base_url=provider.base_url,
                 api_key=resolved_api_key,
                 default_headers=dict(provider.custom_headers) if provider.custom_headers else None,
+                http_client=new_httpx_client(),
             )
         case "vertexai":
             from kosong.contrib.chat_provider.google_genai import GoogleGenAI
 
+            from kimi_cli.utils.network import new_httpx_client
+
             os.environ.update(provider.env or {})
             chat_provider = GoogleGenAI(
                 model=model.model,
@@ -206,6 +313,7 @@ def create_llm(
                 api_key=resolved_api_key,
                 vertexai=True,
                 default_headers=dict(provider.custom_headers) if provider.custom_headers else None,
+                http_client=new_httpx_client(),
             )
         case "_echo":
             from kosong.chat_provider.echo import EchoChatProvider
@@ -224,12 +332,15 @@ def create_llm(
             from kosong.chat_provider.chaos import ChaosChatProvider, ChaosConfig
             from kosong.chat_provider.kimi import Kimi
 
+            from kimi_cli.utils.network import new_httpx_client
+
             chat_provider = ChaosChatProvider(
                 provider=Kimi(
                     model=model.model,
                     base_url=provider.base_url,
                     api_key=resolved_api_key,
                     default_headers=_kimi_default_headers(provider, oauth),
+                    http_client=new_httpx_client(),
                 ),
                 chaos_config=ChaosConfig(
                     error_probability=0.8,
@@ -237,36 +348,28 @@ def create_llm(
                 ),
             )
 
-    capabilities = derive_model_capabilities(model)
+    return chat_provider
 
-    # Apply thinking if specified or if model always requires thinking
-    thinking_on = "always_thinking" in capabilities or (
-        thinking is True and "thinking" in capabilities
-    )
-    if thinking_on:
-        chat_provider = chat_provider.with_thinking("high")
-    elif thinking is False:
-        chat_provider = chat_provider.with_thinking("off")
-    # If thinking is None and model doesn't always think, leave as-is (default behavior)
 
-    # Apply Moonshot-specific ``thinking.keep`` (preserved thinking) only when
-    # the model is actually in thinking mode; otherwise the API would see a
-    # ``thinking.keep`` without an accompanying ``thinking.type`` it honors.
-    if thinking_on and provider.type == "kimi":
-        from kosong.chat_provider.kimi import Kimi
+_rate_limiters: dict[tuple[str, str], RateLimiter] = {}
 
-        if isinstance(chat_provider, Kimi) and (
-            thinking_keep := os.getenv("KIMI_MODEL_THINKING_KEEP")
-        ):
-            chat_provider = chat_provider.with_extra_body({"thinking": {"keep": thinking_keep}})
 
-    return LLM(
-        chat_provider=chat_provider,
-        max_context_size=model.max_context_size,
-        capabilities=capabilities,
-        model_config=model,
-        provider_config=provider,
-    )
+def get_rate_limiter(llm: LLM, config: RateLimitConfig) -> RateLimiter:
+    """
+    Get the rate limiter shared by all the requests to the provider and model of `llm` in this
+    process, so that the main agent and its subagents are paced together. The limiter is created
+    from `config` on first use.
+    """
+    if llm.model_config is not None:
+        key = (llm.model_config.provider, llm.model_config.model)
+    else:
+        key = (llm.chat_provider.name, llm.model_name)
+    if (limiter := _rate_limiters.get(key)) is None:
+        limiter = _rate_limiters[key] = RateLimiter(
+            max_concurrent_requests=config.max_concurrent_requests,
+            requests_per_minute=config.requests_per_minute,
+        )
+    return limiter
 
 
 def clone_llm_with_model_alias(
@@ -294,6 +397,7 @@ def clone_llm_with_model_alias(
         thinking=thinking,
         session_id=session_id,
         oauth=oauth,
+        cassette=llm.cassette if llm is not None else None,
     )
 
 
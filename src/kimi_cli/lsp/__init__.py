@@ -0,0 +1,313 @@
+"""A minimal Language Server Protocol client used by the LSP tools.
+
+Language servers are configured per language id under the ``[lsp.servers]`` config section
+and are started lazily on first use. All servers are shut down at session end.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import contextlib
+import json
+from collections.abc import Mapping
+from dataclasses import dataclass
+from pathlib import PurePosixPath
+from typing import Any, cast
+from urllib.parse import quote, unquote, urlparse
+
+import kaos
+from kaos import KaosProcess
+from kaos.path import KaosPath
+
+from kimi_cli.config import LSPConfig, LSPServerConfig
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.subprocess_env import get_noninteractive_env
+
+DEFAULT_EXTENSIONS: Mapping[str, str] = {
+    ".py": "python",
+    ".pyi": "python",
+    ".rs": "rust",
+    ".go": "go",
+    ".js": "javascript",
+    ".jsx": "javascriptreact",
+    ".ts": "typescript",
+    ".tsx": "typescriptreact",
+    ".c": "c",
+    ".h": "c",
+    ".cc": "cpp",
+    ".cpp": "cpp",
+    ".hpp": "cpp",
+    ".java": "java",
+    ".rb": "ruby",
+    ".lua": "lua",
+}
+"""Default mapping from file suffix to LSP language id."""
+
+REQUEST_TIMEOUT = 30.0
+SHUTDOWN_TIMEOUT = 5.0
+
+
+class LSPError(Exception):
+    """Raised when a language server fails or returns an error response."""
+
+
+@dataclass(frozen=True, slots=True)
+class Location:
+    """A zero-based source location returned by a language server."""
+
+    path: str
+    line: int
+    character: int
+
+
+def path_to_uri(path: KaosPath) -> str:
+    return "file://" + quote(str(path).replace("\\", "/"))
+
+
+def uri_to_path(uri: str) -> str:
+    parsed = urlparse(uri)
+    if parsed.scheme != "file":
+        return uri
+    return unquote(parsed.path)
+
+
+def parse_locations(result: Any) -> list[Location]:
+    """Normalize a `Location | Location[] | LocationLink[] | null` result."""
+    if result is None:
+        return []
+    items = cast(list[Any], result) if isinstance(result, list) else [result]
+    locations: list[Location] = []
+    for item in items:
+        if not isinstance(item, dict):
+            continue
+        item = cast(dict[str, Any], item)
+        uri = item.get("uri") or item.get("targetUri")
+        range_ = item.get("range") or item.get("targetSelectionRange") or item.get("targetRange")
+        if not isinstance(uri, str) or not isinstance(range_, dict):
+            continue
+        start = cast(dict[str, Any], range_).get("start") or {}
+        locations.append(
+            Location(
+                path=uri_to_path(uri),
+                line=int(start.get("line", 0)),
+                character=int(start.get("character", 0)),
+            )
+        )
+    return locations
+
+
+class LSPClient:
+    """JSON-RPC client for a single language server speaking over stdio."""
+
+    def __init__(self, language_id: str, config: LSPServerConfig, root: KaosPath) -> None:
+        self.language_id = language_id
+        self._config = config
+        self._root = root
+        self._process: KaosProcess | None = None
+        self._reader_task: asyncio.Task[None] | None = None
+        self._next_id = 0
+        self._pending: dict[int, asyncio.Future[Any]] = {}
+        self._opened: dict[str, tuple[int, str]] = {}
+        """Opened documents: uri -> (version, text)."""
+        self._write_lock = asyncio.Lock()
+
+    @property
+    def is_running(self) -> bool:
+        return self._process is not None and self._process.returncode is None
+
+    async def start(self) -> None:
+        env = get_noninteractive_env()
+        env.update(self._config.env)
+        self._process = await kaos.exec(*self._config.command, env=env)
+        self._reader_task = asyncio.create_task(self._read_loop())
+        root_uri = path_to_uri(self._root)
+        await self.request(
+            "initialize",
+            {
+                "processId": None,
+                "rootUri": root_uri,
+                "workspaceFolders": [{"uri": root_uri, "name": self._root.name}],
+                "capabilities": {
+                    "textDocument": {
+                        "definition": {"linkSupport": True},
+                        "references": {},
+                    }
+                },
+                "initializationOptions": self._config.initialization_options,
+            },
+        )
+        await self.notify("initialized", {})
+        logger.info(
+            "Started language server for {language_id}: {command}",
+            language_id=self.language_id,
+            command=self._config.command,
+        )
+
+    async def request(self, method: str, params: Any) -> Any:
+        self._next_id += 1
+        request_id = self._next_id
+        future: asyncio.Future[Any] = asyncio.get_running_loop().create_future()
+        self._pending[request_id] = future
+        try:
+            await self._send(
+                {"jsonrpc": "2.0", "id": request_id, "method": method, "params": params}
+            )
+            return await asyncio.wait_for(future, REQUEST_TIMEOUT)
+        finally:
+            self._pending.pop(request_id, None)
+
+    async def notify(self, method: str, params: Any) -> None:
+        await self._send({"jsonrpc": "2.0", "method": method, "params": params})
+
+    async def sync_document(self, path: KaosPath) -> str:
+        """Open the document in the server, or push its latest content. Returns the URI."""
+        uri = path_to_uri(path)
+        text = await path.read_text(errors="replace")
+        opened = self._opened.get(uri)
+        if opened is None:
+            await self.notify(
+                "textDocument/didOpen",
+                {
+                    "textDocument": {
+                        "uri": uri,
+                        "languageId": self.language_id,
+                        "version": 1,
+                        "text": text,
+                    }
+                },
+            )
+            self._opened[uri] = (1, text)
+        elif opened[1] != text:
+            version = opened[0] + 1
+            await self.notify(
+                "textDocument/didChange",
+                {
+                    "textDocument": {"uri": uri, "version": version},
+                    "contentChanges": [{"text": text}],
+                },
+            )
+            self._opened[uri] = (version, text)
+        return uri
+
+    async def shutdown(self) -> None:
+        process = self._process
+        if process is None:
+            return
+        try:
+            if process.returncode is None:
+                await asyncio.wait_for(self.request("shutdown", None), SHUTDOWN_TIMEOUT)
+                await self.notify("exit", None)
+                await asyncio.wait_for(process.wait(), SHUTDOWN_TIMEOUT)
+        except Exception:
+            logger.debug(
+                "Language server for {language_id} did not exit cleanly",
+                language_id=self.language_id,
+            )
+        finally:
+            self._process = None
+            if process.returncode is None:
+                with contextlib.suppress(Exception):
+                    await process.kill()
+            if self._reader_task is not None:
+                self._reader_task.cancel()
+                with contextlib.suppress(Exception, asyncio.CancelledError):
+                    await self._reader_task
+            self._fail_pending(LSPError("Language server was shut down"))
+
+    async def _send(self, message: dict[str, Any]) -> None:
+        if self._process is None:
+            raise LSPError(f"Language server for {self.language_id} is not running")
+        body = json.dumps(message, ensure_ascii=False).encode("utf-8")
+        async with self._write_lock:
+            self._process.stdin.write(f"Content-Length: {len(body)}\r\n\r\n".encode() + body)
+            await self._process.stdin.drain()
+
+    async def _read_loop(self) -> None:
+        assert self._process is not None
+        stdout = self._process.stdout
+        try:
+            while True:
+                header = await stdout.readuntil(b"\r\n\r\n")
+                length = 0
+                for line in header.decode("ascii", errors="replace").split("\r\n"):
+                    name, _, value = line.partition(":")
+                    if name.strip().lower() == "content-length":
+                        length = int(value.strip())
+                message = json.loads(await stdout.readexactly(length))
+                await self._dispatch(message)
+        except (asyncio.IncompleteReadError, ConnectionError):
+            pass
+        except asyncio.CancelledError:
+            raise
+        except Exception:
+            logger.exception("Language server reader for {lang} failed", lang=self.language_id)
+        finally:
+            self._fail_pending(LSPError(f"Language server for {self.language_id} exited"))
+
+    async def _dispatch(self, message: dict[str, Any]) -> None:
+        if "method" in message:
+            # Server-to-client request: reply with an empty result so the server never waits.
+            if "id" in message:
+                await self._send({"jsonrpc": "2.0", "id": message["id"], "result": None})
+            return
+        future = self._pending.get(message.get("id", -1))
+        if future is None or future.done():
+            return
+        if "error" in message:
+            error = message["error"] or {}
+            future.set_exception(LSPError(str(error.get("message", "Unknown LSP error"))))
+        else:
+            future.set_result(message.get("result"))
+
+    def _fail_pending(self, error: LSPError) -> None:
+        for future in self._pending.values():
+            if not future.done():
+                future.set_exception(error)
+        self._pending.clear()
+
+
+class LSPManager:
+    """Owns lazily-started language server clients for a session."""
+
+    def __init__(self, config: LSPConfig, root: KaosPath) -> None:
+        self._config = config
+        self._root = root
+        self._clients: dict[str, LSPClient] = {}
+        self._lock = asyncio.Lock()
+
+    def language_id_for(self, path: KaosPath) -> str | None:
+        suffix = PurePosixPath(str(path)).suffix.lower()
+        for language_id, server in self._config.servers.items():
+            if suffix in (ext.lower() for ext in server.extensions):
+                return language_id
+        return DEFAULT_EXTENSIONS.get(suffix)
+
+    def server_for(self, path: KaosPath) -> tuple[str, LSPServerConfig] | None:
+        language_id = self.language_id_for(path)
+        if language_id is None or language_id not in self._config.servers:
+            return None
+        return language_id, self._config.servers[language_id]
+
+    async def client_for(self, path: KaosPath) -> LSPClient | None:
+        """Return a running client for the file's language, or None if not configured."""
+        server = self.server_for(path)
+        if server is None:
+            return None
+        language_id, server_config = server
+        async with self._lock:
+            client = self._clients.get(language_id)
+            if client is not None and client.is_running:
+                return client
+            client = LSPClient(language_id, server_config, self._root)
+            try:
+                await client.start()
+            except Exception:
+                await client.shutdown()
+                raise
+            self._clients[language_id] = client
+            return client
+
+    async def shutdown(self) -> None:
+        clients = list(self._clients.values())
+        self._clients.clear()
+        await asyncio.gather(*(client.shutdown() for client in clients), return_exceptions=True)
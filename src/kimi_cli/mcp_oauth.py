@@ -2,7 +2,7 @@ from __future__ import annotations
 
 from contextlib import suppress
 from pathlib import Path
-from typing import TYPE_CHECKING, Any
+from typing import TYPE_CHECKING, Any, cast
 
 from kimi_cli.share import get_share_dir
 
@@ -61,7 +61,39 @@ def create_mcp_oauth(server_url: str) -> OAuth:
     return OAuth(mcp_url=server_url, token_storage=create_mcp_oauth_store())
 
 
+def expand_stdio_server_env(server_config: dict[str, Any]) -> dict[str, Any]:
+    """
+    Expand `${VAR}` and `${VAR:-default}` in the `args` and `env` of a stdio server config,
+    returning a new config.
+
+    Raises:
+        UnsetEnvVarError: When a variable without a default is not set.
+    """
+    from kimi_cli.utils.envvar import expand_env_vars
+
+    expanded = dict(server_config)
+    if isinstance(args := server_config.get("args"), list):
+        expanded["args"] = [
+            expand_env_vars(arg) if isinstance(arg, str) else arg for arg in cast(list[Any], args)
+        ]
+    if isinstance(env := server_config.get("env"), dict):
+        expanded["env"] = {
+            key: expand_env_vars(value) if isinstance(value, str) else value
+            for key, value in cast(dict[str, Any], env).items()
+        }
+    return expanded
+
+
 def prepare_mcp_server_config(server_config: dict[str, Any]) -> dict[str, Any]:
+    """
+    Turn a server config from `mcp.json` into one for `fastmcp.Client`, expanding environment
+    variables for stdio servers and creating the OAuth provider for `auth = "oauth"`.
+
+    Raises:
+        UnsetEnvVarError: When a variable without a default is not set.
+    """
+    if "command" in server_config:
+        return expand_stdio_server_env(server_config)
     if server_config.get("auth") != "oauth":
         return server_config
 
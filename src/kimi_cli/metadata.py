@@ -14,10 +14,32 @@ from kimi_cli.utils.io import atomic_json_write
 from kimi_cli.utils.logging import logger
 
 
+MAX_RECENT_SESSIONS = 20
+
+
 def get_metadata_file() -> Path:
     return get_share_dir() / "kimi.json"
 
 
+class RecentSession(BaseModel):
+    """A recently used session of a work directory, shown when picking a session to resume."""
+
+    id: str
+    """The session ID."""
+
+    created_at: float
+    """When the session was first recorded as used."""
+
+    last_used_at: float
+    """When a run of the session last ended."""
+
+    message_count: int = 0
+    """The number of messages in the session context."""
+
+    title: str = "Untitled"
+    """The title of the session, derived from its first user message unless set by the user."""
+
+
 class WorkDirMeta(BaseModel):
     """Metadata for a work directory."""
 
@@ -30,6 +52,9 @@ class WorkDirMeta(BaseModel):
     last_session_id: str | None = None
     """Last session ID of this work directory."""
 
+    recent_sessions: list[RecentSession] = Field(default_factory=list[RecentSession])
+    """Recently used sessions of this work directory, most recent first."""
+
     @property
     def sessions_dir(self) -> Path:
         """The directory to store sessions for this work directory."""
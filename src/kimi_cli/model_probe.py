@@ -0,0 +1,194 @@
+"""
+Probing a model before a session starts, to catch a config that declares capabilities the model
+does not have, or omits ones it always uses.
+
+The probe makes a minimal request, plus a thinking request and an image request when the config
+declares those capabilities, and compares what the provider accepts and returns with the declared
+capabilities. Providers expose nothing else about their models, so `video_in` and `file_in` are
+not probed. Results are cached per provider and model for a day.
+"""
+
+from __future__ import annotations
+
+import time
+from pathlib import Path
+
+from kosong import generate
+from kosong.chat_provider import (
+    APIConnectionError,
+    APITimeoutError,
+    ChatProvider,
+    ChatProviderError,
+)
+from kosong.message import ImageURLPart, Message, TextPart, ThinkPart
+from pydantic import BaseModel, Field, ValidationError
+
+from kimi_cli.llm import LLM, ModelCapability
+from kimi_cli.share import get_share_dir
+from kimi_cli.utils.io import atomic_json_write
+from kimi_cli.utils.logging import logger
+
+PROBE_CACHE_FILE_NAME = "model_probes.json"
+PROBE_CACHE_TTL_S = 24 * 60 * 60
+
+# A 1x1 transparent PNG.
+_PROBE_IMAGE_URL = (
+    "data:image/png;base64,"
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII="
+)
+
+
+class ModelProbeResult(BaseModel):
+    """The outcome of probing a model."""
+
+    probed_at: float
+    declared: list[ModelCapability]
+    """The capabilities declared in the config when the model was probed, sorted."""
+    warnings: list[str] = Field(default_factory=list)
+    """The mismatches found, empty if the model matches its config."""
+
+
+class ModelProbeCache(BaseModel):
+    version: int = 1
+    results: dict[str, ModelProbeResult] = Field(default_factory=dict)
+    """The latest result of each model, by `provider/model` and thinking effort."""
+
+
+def _cache_file() -> Path:
+    return get_share_dir() / PROBE_CACHE_FILE_NAME
+
+
+def _cache_key(llm: LLM) -> str:
+    """`provider/model`, plus `@effort` when thinking is on, which changes what is probed."""
+    if llm.model_config is not None:
+        key = f"{llm.model_config.provider}/{llm.model_config.model}"
+    else:
+        key = f"{llm.chat_provider.name}/{llm.model_name}"
+    effort = llm.chat_provider.thinking_effort
+    if effort not in (None, "off"):
+        key += f"@{effort}"
+    return key
+
+
+def _load_cache(cache_file: Path) -> ModelProbeCache:
+    if not cache_file.exists():
+        return ModelProbeCache()
+    try:
+        return ModelProbeCache.model_validate_json(cache_file.read_bytes())
+    except (OSError, ValidationError) as e:
+        logger.warning(
+            "Corrupted model probe cache, ignoring it: {path}: {error}", path=cache_file, error=e
+        )
+        return ModelProbeCache()
+
+
+async def _ask(chat_provider: ChatProvider, message: Message) -> Message:
+    result = await generate(chat_provider, "", [], [message])
+    return result.message
+
+
+def _has_thinking(message: Message) -> bool:
+    return any(isinstance(part, ThinkPart) and part.think.strip() for part in message.content)
+
+
+async def probe_model(llm: LLM) -> list[str]:
+    """
+    Probe the model of `llm` and return a warning for each mismatch with its declared
+    capabilities.
+
+    Raises:
+        ChatProviderError: When the model does not answer a minimal request at all.
+    """
+    declared = llm.capabilities
+    chat_provider = llm.chat_provider
+    warnings: list[str] = []
+
+    reply = await _ask(chat_provider, Message(role="user", content="Reply with OK."))
+    thinking_on = chat_provider.thinking_effort not in (None, "off")
+    if _has_thinking(reply):
+        if not thinking_on and "always_thinking" not in declared:
+            warnings.append(
+                "the model returned thinking content with thinking off; "
+                'add "always_thinking" to its capabilities'
+            )
+    elif "always_thinking" in declared:
+        warnings.append(
+            'config declares "always_thinking" but the model returned no thinking content'
+        )
+    elif thinking_on:
+        warnings.append(
+            'thinking is on but the model returned no thinking content; remove "thinking" from '
+            "its capabilities if it cannot think"
+        )
+
+    if "thinking" in declared and not thinking_on and "always_thinking" not in declared:
+        try:
+            reply = await _ask(
+                chat_provider.with_thinking("high"),
+                Message(role="user", content="Reply with OK."),
+            )
+        except (APIConnectionError, APITimeoutError):
+            raise
+        except ChatProviderError as e:
+            warnings.append(f'config declares "thinking" but the provider rejected thinking: {e}')
+        else:
+            if not _has_thinking(reply):
+                warnings.append(
+                    'config declares "thinking" but the model returned no thinking content '
+                    "with thinking on"
+                )
+
+    if "image_in" in declared:
+        image_message = Message(
+            role="user",
+            content=[
+                TextPart(text="Reply with OK."),
+                ImageURLPart(image_url=ImageURLPart.ImageURL(url=_PROBE_IMAGE_URL)),
+            ],
+        )
+        try:
+            await _ask(chat_provider, image_message)
+        except (APIConnectionError, APITimeoutError):
+            raise
+        except ChatProviderError as e:
+            warnings.append(
+                f'config declares "image_in" but the provider rejected an image part: {e}'
+            )
+
+    return warnings
+
+
+async def verify_model(llm: LLM, *, now: float | None = None) -> list[str]:
+    """
+    Probe the model of `llm`, or reuse the result of a probe made within the last day with the
+    same declared capabilities, and return the mismatch warnings.
+
+    A probe that fails outright is reported as a warning and is not cached.
+    """
+    now = time.time() if now is None else now
+    cache_file = _cache_file()
+    cache = _load_cache(cache_file)
+    key = _cache_key(llm)
+    declared = sorted(llm.capabilities)
+
+    cached = cache.results.get(key)
+    if (
+        cached is not None
+        and cached.declared == declared
+        and now - cached.probed_at < PROBE_CACHE_TTL_S
+    ):
+        logger.debug("Using cached model probe of {key}", key=key)
+        return cached.warnings
+
+    logger.info("Probing model {key}", key=key)
+    try:
+        warnings = await probe_model(llm)
+    except ChatProviderError as e:
+        return [f"the model did not answer a minimal request: {e}"]
+
+    cache.results[key] = ModelProbeResult(probed_at=now, declared=declared, warnings=warnings)
+    try:
+        atomic_json_write(cache.model_dump(mode="json"), cache_file)
+    except OSError as e:
+        logger.warning("Failed to save model probe cache {path}: {error}", path=cache_file, error=e)
+    return warnings
@@ -4,6 +4,7 @@ import asyncio
 import builtins
 import json
 import shutil
+import time
 import uuid
 from dataclasses import dataclass
 from pathlib import Path
@@ -11,13 +12,28 @@ from pathlib import Path
 from kaos.path import KaosPath
 from kosong.message import Message
 
-from kimi_cli.metadata import WorkDirMeta, load_metadata, save_metadata
+from kimi_cli.exception import SessionEncryptionError
+from kimi_cli.metadata import (
+    MAX_RECENT_SESSIONS,
+    RecentSession,
+    WorkDirMeta,
+    load_metadata,
+    save_metadata,
+)
 from kimi_cli.session_state import SessionState, load_session_state, save_session_state
 from kimi_cli.utils.logging import logger
+from kimi_cli.utils.session_crypto import decode_line
 from kimi_cli.utils.string import shorten
 from kimi_cli.wire.file import WireFile
 from kimi_cli.wire.types import TurnBegin
 
+RECENT_TITLE_WIDTH = 60
+
+
+def generate_title(first_message: str) -> str:
+    """Derive a one-line session title from the first user message of the session."""
+    return shorten(first_message, width=RECENT_TITLE_WIDTH) or "Untitled"
+
 
 @dataclass(slots=True, kw_only=True)
 class Session:
@@ -59,6 +75,16 @@ class Session:
         path.mkdir(parents=True, exist_ok=True)
         return path
 
+    @property
+    def snapshots_dir(self) -> Path:
+        """The absolute path of the directory of named context snapshots."""
+        return self.dir / "snapshots"
+
+    @property
+    def file_journal_dir(self) -> Path:
+        """The absolute path of the file journal directory."""
+        return self.dir / "file_journal"
+
     def is_empty(self) -> bool:
         """Whether the session has any context history or a custom title."""
         if self.state.custom_title:
@@ -71,16 +97,37 @@ class Session:
                     line = line.strip()
                     if not line:
                         continue
-                    role = json.loads(line, strict=False).get("role")
+                    role = json.loads(decode_line(line), strict=False).get("role")
                     if isinstance(role, str) and not role.startswith("_"):
                         return False
         except FileNotFoundError:
             return True
+        except SessionEncryptionError:
+            # Encrypted with a key that is not available, but not empty
+            return False
         except (OSError, ValueError, TypeError):
             logger.exception("Failed to read context file {file}:", file=self.context_file)
             return False
         return True
 
+    def message_count(self) -> int:
+        """The number of messages in the context history, not counting internal records."""
+        count = 0
+        try:
+            with self.context_file.open(encoding="utf-8") as f:
+                for line in f:
+                    line = line.strip()
+                    if not line:
+                        continue
+                    role = json.loads(decode_line(line), strict=False).get("role")
+                    if isinstance(role, str) and not role.startswith("_"):
+                        count += 1
+        except FileNotFoundError:
+            return 0
+        except (OSError, ValueError, TypeError, SessionEncryptionError):
+            logger.exception("Failed to read context file {file}:", file=self.context_file)
+        return count
+
     def save_state(self) -> None:
         """Persist the session state to disk.
 
@@ -111,20 +158,66 @@ class Session:
             self.title = self.state.custom_title
             return
 
+        if (first_message := await self._first_user_message()) is not None:
+            self.title = shorten(first_message, width=50)
+
+    async def _first_user_message(self) -> str | None:
+        """The text of the first user input recorded in the wire file, if any."""
         try:
             async for record in self.wire_file.iter_records():
                 wire_msg = record.to_wire_message()
                 if isinstance(wire_msg, TurnBegin):
-                    self.title = shorten(
-                        Message(role="user", content=wire_msg.user_input).extract_text(" "),
-                        width=50,
-                    )
-                    return
+                    return Message(role="user", content=wire_msg.user_input).extract_text(" ")
         except Exception:
             logger.exception(
                 "Failed to derive session title from wire file {file}:",
                 file=self.wire_file.path,
             )
+        return None
+
+    async def record_recent(self) -> None:
+        """
+        Record this session as the most recently used session of its work directory.
+
+        Sessions that were deleted or have no content, including this one, are pruned from the
+        recent sessions list, which keeps at most `MAX_RECENT_SESSIONS` entries.
+        """
+        if self.is_empty():
+            entry = None
+        else:
+            if self.state.custom_title:
+                title = self.state.custom_title
+            else:
+                title = generate_title(await self._first_user_message() or "")
+            now = time.time()
+            entry = RecentSession(
+                id=self.id,
+                created_at=now,
+                last_used_at=now,
+                message_count=self.message_count(),
+                title=title,
+            )
+
+        metadata = load_metadata()
+        work_dir_meta = metadata.get_work_dir_meta(self.work_dir)
+        if work_dir_meta is None:
+            work_dir_meta = metadata.new_work_dir_meta(self.work_dir)
+
+        recent: list[RecentSession] = [entry] if entry is not None else []
+        for previous in work_dir_meta.recent_sessions:
+            if previous.id == self.id:
+                if entry is not None:
+                    entry.created_at = previous.created_at
+                continue
+            session = await Session.find(self.work_dir, previous.id)
+            if session is None or session.is_empty():
+                logger.debug(
+                    "Pruning session {session_id} from recent sessions", session_id=previous.id
+                )
+                continue
+            recent.append(previous)
+        work_dir_meta.recent_sessions = recent[:MAX_RECENT_SESSIONS]
+        save_metadata(metadata)
 
     @staticmethod
     async def create(
@@ -284,6 +377,49 @@ class Session:
         all_sessions.sort(key=lambda s: s.updated_at, reverse=True)
         return all_sessions
 
+    @staticmethod
+    async def list_recent(
+        work_dir: KaosPath, n: int = MAX_RECENT_SESSIONS, *, backfill: bool = False
+    ) -> builtins.list[RecentSession]:
+        """
+        List the `n` most recently used sessions of a work directory, most recent first.
+
+        Args:
+            backfill: Fill the list up to `n` with the sessions missing from the recent sessions
+                index, e.g. those last run before it existed, most recently updated first.
+        """
+        work_dir = work_dir.canonical()
+        metadata = load_metadata()
+        work_dir_meta = metadata.get_work_dir_meta(work_dir)
+        if work_dir_meta is None:
+            logger.debug("Work directory never been used")
+            return []
+        recent = [
+            entry
+            for entry in work_dir_meta.recent_sessions
+            if (work_dir_meta.sessions_dir / entry.id / "context.jsonl").exists()
+        ][:n]
+        if not backfill or len(recent) >= n:
+            return recent
+
+        indexed = {entry.id for entry in recent}
+        for session in await Session.list(work_dir):
+            if len(recent) >= n:
+                break
+            if session.id in indexed:
+                continue
+            recent.append(
+                RecentSession(
+                    id=session.id,
+                    created_at=session.updated_at,
+                    last_used_at=session.updated_at,
+                    message_count=session.message_count(),
+                    title=session.state.custom_title
+                    or generate_title(await session._first_user_message() or ""),
+                )
+            )
+        return recent
+
     @staticmethod
     async def continue_(work_dir: KaosPath) -> Session | None:
         """Get the last session for a work directory."""
@@ -25,6 +25,17 @@ class TodoItemState(BaseModel):
     status: Literal["pending", "in_progress", "done"]
 
 
+class UsageState(BaseModel):
+    """Accumulated token usage and estimated cost stored in session state."""
+
+    input_other: int = 0
+    input_cache_read: int = 0
+    input_cache_creation: int = 0
+    output: int = 0
+    cost: float = 0.0
+    unpriced_calls: int = 0
+
+
 class SessionState(BaseModel):
     version: int = 1
     approval: ApprovalStateData = Field(default_factory=ApprovalStateData)
@@ -42,6 +53,9 @@ class SessionState(BaseModel):
     auto_archive_exempt: bool = False
     # Todo list state
     todos: list[TodoItemState] = Field(default_factory=list)  # pyright: ignore[reportUnknownVariableType]
+    # Token usage, including compaction, and the compaction share of it
+    usage: UsageState = Field(default_factory=UsageState)
+    compaction_usage: UsageState = Field(default_factory=UsageState)
 
 
 _LEGACY_METADATA_FILENAME = "metadata.json"
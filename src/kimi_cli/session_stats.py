@@ -0,0 +1,134 @@
+from __future__ import annotations
+
+import json
+from collections.abc import Iterable
+from pathlib import Path
+
+from pydantic import BaseModel, Field, ValidationError
+
+from kimi_cli.session_state import STATE_FILE_NAME, UsageState
+from kimi_cli.utils.io import atomic_json_write
+from kimi_cli.utils.logging import logger
+
+STATS_FILE_NAME = "stats.json"
+
+
+class ToolStats(BaseModel):
+    """Calls of one tool."""
+
+    calls: int = 0
+    errors: int = 0
+    duration_s: float = 0.0
+    """Total seconds spent running the tool, including waiting for approval."""
+
+
+class SessionStats(BaseModel):
+    """
+    What the agents of a session did, including its subagents. Kept apart from the session state
+    so that a corrupt stats file cannot affect loading the session.
+    """
+
+    version: int = 1
+    updated_at: float | None = None
+    """The timestamp of the last update."""
+    turns: int = 0
+    """Turns of the main agent."""
+    steps: int = 0
+    retries: int = 0
+    """LLM calls retried after a retryable error, including compaction."""
+    approvals_approved: int = 0
+    """Approval requests approved by the user, not counting auto-approved ones."""
+    approvals_rejected: int = 0
+    """Approval requests rejected or cancelled by the user."""
+    tools: dict[str, ToolStats] = Field(default_factory=dict)
+    """Calls of each tool, by tool name."""
+
+    def merge(self, other: SessionStats) -> None:
+        """Add the counters of `other` to these."""
+        if other.updated_at is not None:
+            self.updated_at = max(self.updated_at or other.updated_at, other.updated_at)
+        self.turns += other.turns
+        self.steps += other.steps
+        self.retries += other.retries
+        self.approvals_approved += other.approvals_approved
+        self.approvals_rejected += other.approvals_rejected
+        for name, tool in other.tools.items():
+            total = self.tools.setdefault(name, ToolStats())
+            total.calls += tool.calls
+            total.errors += tool.errors
+            total.duration_s += tool.duration_s
+
+
+def load_session_stats(session_dir: Path) -> SessionStats:
+    """Load the stats of a session, starting over if the file is missing or corrupt."""
+    stats_file = session_dir / STATS_FILE_NAME
+    if not stats_file.exists():
+        return SessionStats()
+    try:
+        return SessionStats.model_validate_json(stats_file.read_bytes())
+    except (OSError, ValidationError) as e:
+        logger.warning(
+            "Corrupted stats file, starting over: {path}: {error}", path=stats_file, error=e
+        )
+        return SessionStats()
+
+
+def save_session_stats(stats: SessionStats, session_dir: Path) -> None:
+    stats_file = session_dir / STATS_FILE_NAME
+    try:
+        atomic_json_write(stats.model_dump(mode="json"), stats_file)
+    except OSError as e:
+        logger.warning("Failed to save stats file {path}: {error}", path=stats_file, error=e)
+
+
+class StatsReport(BaseModel):
+    """The stats and token usage of sessions, added up."""
+
+    sessions: int = 0
+    stats: SessionStats = Field(default_factory=SessionStats)
+    usage: UsageState = Field(default_factory=UsageState)
+
+
+def aggregate_session_stats(
+    session_dirs: Iterable[Path], *, since: float | None = None
+) -> StatsReport:
+    """
+    Add up the stats and token usage of the given sessions.
+
+    Args:
+        since: Only include the sessions active at or after this timestamp. Sessions without
+            stats, written by older versions, are dated by the last change of their context.
+    """
+    report = StatsReport()
+    for session_dir in session_dirs:
+        stats = load_session_stats(session_dir)
+        if since is not None:
+            active_at = stats.updated_at or _mtime(session_dir / "context.jsonl")
+            if active_at is None or active_at < since:
+                continue
+        report.sessions += 1
+        report.stats.merge(stats)
+        usage = _load_usage(session_dir)
+        report.usage.input_other += usage.input_other
+        report.usage.input_cache_read += usage.input_cache_read
+        report.usage.input_cache_creation += usage.input_cache_creation
+        report.usage.output += usage.output
+        report.usage.cost += usage.cost
+        report.usage.unpriced_calls += usage.unpriced_calls
+    return report
+
+
+def _mtime(path: Path) -> float | None:
+    try:
+        return path.stat().st_mtime
+    except OSError:
+        return None
+
+
+def _load_usage(session_dir: Path) -> UsageState:
+    # Read without `load_session_state`, which migrates legacy files on the way.
+    try:
+        data = json.loads((session_dir / STATE_FILE_NAME).read_text(encoding="utf-8"))
+        return UsageState.model_validate(data.get("usage", {}))
+    except (OSError, ValueError, AttributeError):
+        return UsageState()
@@ -2,6 +2,8 @@
 
 from __future__ import annotations
 
+import re
+import shlex
 import sys
 from collections.abc import Callable, Iterable, Iterator, Sequence
 from dataclasses import dataclass
@@ -209,7 +211,7 @@ async def resolve_skills_roots(
     Non-existent entries are silently dropped. Duplicates collapse to one.
     """
     from kimi_cli.plugin.manager import get_plugins_dir
-    from kimi_cli.utils.path import find_project_root
+    from kimi_cli.utils.path import find_project_root, resolve_config_dir
 
     scoped: list[ScopedSkillsRoot] = []
     seen: set[str] = set()
@@ -257,7 +259,7 @@ async def resolve_skills_roots(
     if extra_skill_dirs:
         project_root = await find_project_root(work_dir)
         for raw in extra_skill_dirs:
-            resolved = _resolve_extra_skill_dir(raw, project_root)
+            resolved = resolve_config_dir(raw, project_root)
             if resolved is None:
                 continue
             try:
@@ -291,25 +293,6 @@ async def resolve_skills_roots(
     return scoped
 
 
-def _resolve_extra_skill_dir(raw: str, project_root: KaosPath) -> KaosPath | None:
-    """Resolve a single ``extra_skill_dirs`` entry to a KaosPath, or None on error.
-
-    Expands ``~``; treats non-absolute entries as relative to *project_root*.
-    """
-    if not raw:
-        return None
-    try:
-        p = Path(raw).expanduser()
-    except (RuntimeError, OSError):
-        # ``expanduser`` can raise on malformed HOME or platform-specific
-        # oddities; treat any failure as a dropped entry rather than let it
-        # kill skill discovery.
-        return None
-    if p.is_absolute():
-        return KaosPath.unsafe_from_local_path(p)
-    return KaosPath.unsafe_from_local_path(Path(str(project_root)) / p)
-
-
 def normalize_skill_name(name: str) -> str:
     """Normalize a skill name for lookup."""
     return name.casefold()
@@ -424,6 +407,45 @@ class Skill(BaseModel):
     model can tell user-scope from project-scope skills."""
 
 
+_SKILL_ARG_PLACEHOLDER = re.compile(r"\{\{\s*(args|arg:([A-Za-z0-9_-]+))\s*\}\}")
+
+
+def parse_skill_args(args: str) -> dict[str, str]:
+    """Parse ``name=value`` pairs out of slash command arguments.
+
+    Values may be quoted shell-style (``title="fix login"``). Tokens without ``=`` are ignored.
+    """
+    try:
+        tokens = shlex.split(args)
+    except ValueError:
+        tokens = args.split()
+    named: dict[str, str] = {}
+    for token in tokens:
+        key, sep, value = token.partition("=")
+        if sep and key:
+            named[key] = value
+    return named
+
+
+def render_skill_args(skill_text: str, args: str) -> str | None:
+    """Substitute ``{{args}}`` and ``{{arg:name}}`` placeholders in *skill_text*.
+
+    ``{{args}}`` expands to the raw argument string and ``{{arg:name}}`` to the value of a
+    ``name=value`` argument (empty if not given). Returns None if the text has no placeholders,
+    so callers can fall back to appending the arguments.
+    """
+    if not _SKILL_ARG_PLACEHOLDER.search(skill_text):
+        return None
+    raw = args.strip()
+    named = parse_skill_args(raw)
+
+    def _replace(match: re.Match[str]) -> str:
+        name = match.group(2)
+        return raw if name is None else named.get(name, "")
+
+    return _SKILL_ARG_PLACEHOLDER.sub(_replace, skill_text)
+
+
 async def discover_skills(
     skills_dir: KaosPath,
     *,
@@ -588,7 +610,7 @@ def parse_skill_text(
     flow = None
     if skill_type == "flow":
         try:
-            flow = _parse_flow_from_skill(content)
+            flow = _parse_flow_from_skill(content, frontmatter.get("flow_block"))
         except ValueError as exc:
             logger.error("Failed to parse flow skill {name}: {error}", name=name, error=exc)
             skill_type = "standard"
@@ -642,13 +664,42 @@ def _first_meaningful_line(content: str) -> str | None:
     return None
 
 
-def _parse_flow_from_skill(content: str) -> Flow:
-    for lang, code in _iter_fenced_codeblocks(content):
-        if lang == "mermaid":
-            return _parse_flow_block(parse_mermaid_flowchart, code)
-        if lang == "d2":
-            return _parse_flow_block(parse_d2_flowchart, code)
-    raise ValueError("Flow skills require a mermaid or d2 code block in SKILL.md.")
+def _parse_flow_from_skill(content: str, flow_block: object = None) -> Flow:
+    """Parse the flow diagram of a flow skill.
+
+    The block is chosen by the frontmatter ``flow_block`` (a 1-based index among the
+    mermaid/d2 blocks), then by a ``flow`` tag in the fence info string (e.g. an opening
+    fence of ``mermaid flow``), and finally falls back to the first mermaid/d2 block.
+    """
+    parsers: dict[str, Callable[[str], Flow]] = {
+        "mermaid": parse_mermaid_flowchart,
+        "d2": parse_d2_flowchart,
+    }
+    candidates = [
+        (lang, info, code)
+        for lang, info, code in _iter_fenced_codeblocks(content)
+        if lang in parsers
+    ]
+    if not candidates:
+        raise ValueError("Flow skills require a mermaid or d2 code block in SKILL.md.")
+
+    if flow_block is not None:
+        if isinstance(flow_block, bool) or not isinstance(flow_block, int):
+            raise ValueError(f"flow_block must be an integer, got {flow_block!r}.")
+        if not 1 <= flow_block <= len(candidates):
+            raise ValueError(
+                f"flow_block {flow_block} is out of range; "
+                f"SKILL.md has {len(candidates)} mermaid/d2 block(s)."
+            )
+        lang, _, code = candidates[flow_block - 1]
+        return _parse_flow_block(parsers[lang], code)
+
+    for lang, info, code in candidates:
+        if "flow" in info.lower().split()[1:]:
+            return _parse_flow_block(parsers[lang], code)
+
+    lang, _, code = candidates[0]
+    return _parse_flow_block(parsers[lang], code)
 
 
 def _parse_flow_block(parser: Callable[[str], Flow], code: str) -> Flow:
@@ -658,10 +709,12 @@ def _parse_flow_block(parser: Callable[[str], Flow], code: str) -> Flow:
         raise ValueError(f"Invalid flow diagram: {exc}") from exc
 
 
-def _iter_fenced_codeblocks(content: str) -> Iterator[tuple[str, str]]:
+def _iter_fenced_codeblocks(content: str) -> Iterator[tuple[str, str, str]]:
+    """Yield ``(lang, info, code)`` for each fenced code block in *content*."""
     fence = ""
     fence_char = ""
     lang = ""
+    info = ""
     buf: list[str] = []
     in_block = False
 
@@ -676,11 +729,12 @@ def _iter_fenced_codeblocks(content: str) -> Iterator[tuple[str, str]]:
             continue
 
         if _is_fence_close(stripped, fence_char, len(fence)):
-            yield lang, "\n".join(buf).strip("\n")
+            yield lang, info, "\n".join(buf).strip("\n")
             in_block = False
             fence = ""
             fence_char = ""
             lang = ""
+            info = ""
             buf = []
             continue
 
@@ -5,7 +5,9 @@ import contextlib
 from collections.abc import Callable, Coroutine
 from contextvars import ContextVar
 from dataclasses import dataclass
-from typing import TYPE_CHECKING, Any, Protocol, runtime_checkable
+from typing import TYPE_CHECKING, Any, Literal, Protocol, runtime_checkable
+
+from kosong.chat_provider import ChatProviderError
 
 from kimi_cli.hooks.engine import HookEngine
 from kimi_cli.utils.aioqueue import QueueShutDown
@@ -40,6 +42,20 @@ class LLMNotSupported(Exception):
         )
 
 
+class MaxMessageSizeExceeded(ChatProviderError):
+    """Raised when an assistant message grows beyond `loop_control.max_message_chars`."""
+
+    limit: int
+    """The maximum number of characters allowed in one assistant message."""
+
+    def __init__(self, limit: int):
+        super().__init__(
+            f"The assistant message exceeded {limit:,} characters and was discarded. "
+            "The model may be stuck repeating itself."
+        )
+        self.limit = limit
+
+
 class MaxStepsReached(Exception):
     """Raised when the maximum number of steps is reached."""
 
@@ -51,6 +67,32 @@ class MaxStepsReached(Exception):
         self.n_steps = n_steps
 
 
+class BudgetExceeded(Exception):
+    """Raised when a turn uses more tokens or costs more than the configured budget."""
+
+    kind: Literal["tokens", "cost"]
+    """Which budget was exceeded."""
+    tokens: int
+    """The number of tokens used in the turn."""
+    cost: float
+    """The estimated cost in USD of the turn."""
+    limit: float
+    """The limit that was exceeded, in tokens or USD."""
+
+    def __init__(
+        self, kind: Literal["tokens", "cost"], *, tokens: int, cost: float, limit: float
+    ) -> None:
+        if kind == "tokens":
+            detail = f"{tokens:,} tokens used, limit {int(limit):,}"
+        else:
+            detail = f"${cost:.4f} spent, limit ${limit:.4f}"
+        super().__init__(f"Turn budget exceeded: {detail}")
+        self.kind = kind
+        self.tokens = tokens
+        self.cost = cost
+        self.limit = limit
+
+
 def format_token_count(n: int) -> str:
     """Format token count as compact string, e.g. 28.5k, 128k, 1.2m."""
     suffix = ""
@@ -163,6 +205,7 @@ class Soul(Protocol):
             LLMNotSupported: When the LLM does not have required capabilities.
             ChatProviderError: When the LLM provider returns an error.
             MaxStepsReached: When the maximum number of steps is reached.
+            BudgetExceeded: When the token or cost budget of the turn is exceeded.
             asyncio.CancelledError: When the run is cancelled by user.
         """
         ...
@@ -197,6 +240,7 @@ async def run_soul(
         LLMNotSupported: When the LLM does not have required capabilities.
         ChatProviderError: When the LLM provider returns an error.
         MaxStepsReached: When the maximum number of steps is reached.
+        BudgetExceeded: When the token or cost budget of the turn is exceeded.
         RunCancelled: When the run is cancelled by the cancel event.
     """
     wire = Wire(file_backend=wire_file)
@@ -1,7 +1,7 @@
 from __future__ import annotations
 
 import asyncio
-from dataclasses import asdict, dataclass
+from dataclasses import asdict, dataclass, field
 from datetime import datetime
 from pathlib import Path
 from typing import TYPE_CHECKING, Any, Literal
@@ -10,17 +10,20 @@ import pydantic
 from jinja2 import Environment as JinjaEnvironment
 from jinja2 import FileSystemLoader, StrictUndefined, TemplateError, UndefinedError
 from kaos.path import KaosPath
+from kosong.chat_provider import GenerateOptions
 from kosong.tooling import Toolset
 
 from kimi_cli.agentspec import load_agent_spec
 from kimi_cli.approval_runtime import ApprovalRuntime
 from kimi_cli.auth.oauth import OAuthManager
 from kimi_cli.background import BackgroundTaskManager
-from kimi_cli.config import Config
+from kimi_cli.config import Config, StreamThinking
 from kimi_cli.exception import MCPConfigError, SystemPromptTemplateError
 from kimi_cli.llm import LLM
+from kimi_cli.lsp import LSPManager
 from kimi_cli.notifications import NotificationManager
 from kimi_cli.session import Session
+from kimi_cli.session_stats import load_session_stats, save_session_stats
 from kimi_cli.skill import (
     Skill,
     discover_skills_from_roots,
@@ -30,13 +33,23 @@ from kimi_cli.skill import (
 )
 from kimi_cli.soul.approval import Approval, ApprovalState
 from kimi_cli.soul.denwarenji import DenwaRenji
+from kimi_cli.soul.file_journal import FileJournal
+from kimi_cli.soul.file_tracker import FileTracker
+from kimi_cli.soul.stats import StatsRecorder
 from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.soul.turn_summary import TurnSummaryWriter
+from kimi_cli.soul.usage import UsageTotals, UsageTracker
 from kimi_cli.subagents.models import AgentTypeDefinition, ToolPolicy
 from kimi_cli.subagents.registry import LaborMarket
 from kimi_cli.subagents.store import SubagentStore
 from kimi_cli.utils.environment import Environment
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.path import find_project_root, is_within_directory, list_directory
+from kimi_cli.utils.path import (
+    find_project_root,
+    is_within_directory,
+    list_directory,
+    resolve_config_dir,
+)
 from kimi_cli.wire.root_hub import RootWireHub
 
 if TYPE_CHECKING:
@@ -196,8 +209,55 @@ class Runtime:
     resumed: bool = False
     hook_engine: Any = None
     """HookEngine instance, set by KimiCLI after soul creation."""
+    lsp: LSPManager | None = None
+    """Language servers for the LSP tools, shared with subagents."""
+    usage: UsageTracker = field(default_factory=UsageTracker)
+    """Token usage of the session, shared with subagents."""
+    stats: StatsRecorder = field(default_factory=StatsRecorder)
+    """What the agents of the session do, shared with subagents."""
+    file_journal: FileJournal | None = None
+    """Pre-images of the files changed since each checkpoint, shared with subagents. None if
+    the file journal is disabled."""
+    file_tracker: FileTracker = field(default_factory=FileTracker)
+    """The files read or written by the agents, to detect external changes. Shared with
+    subagents."""
+    dry_run: bool = False
+    """Whether tools with side effects (file edits, shell commands, commits) only report what
+    they would do instead of doing it. Read-only tools run normally."""
+    stream_thinking: StreamThinking = "full"
+    """How much of the model's reasoning the soul forwards to the wire, see
+    `Config.stream_thinking`. Think parts are added to the context regardless."""
+    turn_summary: TurnSummaryWriter | None = None
+    """Writes a summary of each turn to the `--summary-file`, shared with subagents so that
+    their tool calls are included. None if no summary file is written."""
+
+    @property
+    def generate_options(self) -> GenerateOptions | None:
+        """
+        Per-call generation defaults from the `[generation]` config and the model's
+        `max_output_tokens` and `parallel_tool_calls`, or None if unset.
+        """
+        generation = self.config.generation
+        model_config = self.llm.model_config if self.llm is not None else None
+        max_output_tokens = (
+            model_config.max_output_tokens
+            if model_config is not None and model_config.max_output_tokens is not None
+            else generation.max_output_tokens
+        )
+        options = GenerateOptions(
+            max_output_tokens=max_output_tokens,
+            stop=tuple(generation.stop),
+            temperature=generation.temperature,
+            top_p=generation.top_p,
+            parallel_tool_calls=(
+                model_config.parallel_tool_calls if model_config is not None else None
+            ),
+        )
+        return None if options.is_empty else options
 
     def __post_init__(self) -> None:
+        if self.lsp is None:
+            self.lsp = LSPManager(self.config.lsp, self.session.work_dir)
         if self.subagent_store is None:
             self.subagent_store = SubagentStore(self.session)
         if self.root_wire_hub is None:
@@ -258,11 +318,29 @@ class Runtime:
             session.state.additional_dirs = valid_dir_strs
             session.save_state()
 
+        # Add the directories of the `additional_dirs` config, which are not saved to the session
+        if config.additional_dirs:
+            project_root = await find_project_root(session.work_dir)
+            canonical_work_dir = session.work_dir.canonical()
+            for raw in config.additional_dirs:
+                resolved = resolve_config_dir(raw, project_root)
+                if resolved is None:
+                    continue
+                d = resolved.canonical()
+                if d in additional_dirs or is_within_directory(d, canonical_work_dir):
+                    continue
+                if not await d.is_dir():
+                    logger.warning(
+                        "Skipping additional_dirs entry, not a directory: {dir}", dir=raw
+                    )
+                    continue
+                additional_dirs.append(d)
+
         # Format additional dirs info for system prompt
         additional_dirs_info = ""
         if additional_dirs:
             parts: list[str] = []
-            for d in additional_dirs:
+            for i, d in enumerate(additional_dirs, start=1):
                 try:
                     dir_ls = await list_directory(d)
                 except OSError:
@@ -270,7 +348,7 @@ class Runtime:
                         "Cannot list additional directory, skipping listing: {dir}", dir=d
                     )
                     dir_ls = "[directory not readable]"
-                parts.append(f"### `{d}`\n\n```\n{dir_ls}\n```")
+                parts.append(f"### Root {i}: `{d}`\n\n```\n{dir_ls}\n```")
             additional_dirs_info = "\n\n".join(parts)
 
         # Merge invocation flags with persisted session state.
@@ -293,18 +371,37 @@ class Runtime:
             auto_approve_actions=saved_actions,
             on_change=_on_approval_change,
         )
+
+        def _on_usage_change(tracker: UsageTracker) -> None:
+            session.state.usage = tracker.session.to_state()
+            session.state.compaction_usage = tracker.compaction.to_state()
+            session.save_state()
+
+        usage = UsageTracker(
+            session=UsageTotals.from_state(session.state.usage),
+            compaction=UsageTotals.from_state(session.state.compaction_usage),
+            on_change=_on_usage_change,
+        )
+        stats = StatsRecorder(
+            stats=load_session_stats(session.dir),
+            on_change=lambda stats: save_session_stats(stats, session.dir),
+        )
         notifications = NotificationManager(
             session.context_file.parent / "notifications",
             config.notifications,
         )
 
+        now = datetime.now().astimezone().isoformat()
+        if llm is not None and llm.cassette is not None:
+            now = llm.cassette.pinned_now()
+
         return Runtime(
             config=config,
             oauth=oauth,
             llm=llm,
             session=session,
             builtin_args=BuiltinSystemPromptArgs(
-                KIMI_NOW=datetime.now().astimezone().isoformat(),
+                KIMI_NOW=now,
                 KIMI_WORK_DIR=session.work_dir,
                 KIMI_WORK_DIR_LS=ls_output,
                 KIMI_AGENTS_MD=agents_md or "",
@@ -314,7 +411,7 @@ class Runtime:
                 KIMI_SHELL=f"{environment.shell_name} (`{environment.shell_path}`)",
             ),
             denwa_renji=DenwaRenji(),
-            approval=Approval(state=approval_state),
+            approval=Approval(state=approval_state, stats=stats),
             labor_market=LaborMarket(),
             environment=environment,
             notifications=notifications,
@@ -334,6 +431,13 @@ class Runtime:
             approval_runtime=ApprovalRuntime(),
             root_wire_hub=RootWireHub(),
             role="root",
+            usage=usage,
+            stats=stats,
+            file_journal=(
+                FileJournal(session.file_journal_dir, session.work_dir, config.file_journal)
+                if config.file_journal.enabled
+                else None
+            ),
         )
 
     def copy_for_subagent(
@@ -366,6 +470,14 @@ class Runtime:
             subagent_id=agent_id,
             subagent_type=subagent_type,
             role="subagent",
+            lsp=self.lsp,
+            usage=self.usage,
+            stats=self.stats,
+            file_journal=self.file_journal,
+            file_tracker=self.file_tracker,
+            dry_run=self.dry_run,
+            stream_thinking=self.stream_thinking,
+            turn_summary=self.turn_summary,
         )
 
 
@@ -431,7 +543,7 @@ async def load_agent(
             )
         )
 
-    toolset = KimiToolset()
+    toolset = KimiToolset(runtime.config.tools, stats=runtime.stats)
     tool_deps = {
         KimiToolset: toolset,
         Runtime: runtime,
@@ -456,6 +568,9 @@ async def load_agent(
 
     plugin_tools = load_plugin_tools(get_plugins_dir(), runtime.config, approval=runtime.approval)
     for plugin_tool in plugin_tools:
+        if not toolset.is_enabled(plugin_tool.name):
+            logger.info("Skipping disabled plugin tool: {name}", name=plugin_tool.name)
+            continue
         if toolset.find(plugin_tool.name) is not None:
             logger.warning(
                 "Plugin tool '{name}' conflicts with an existing tool, skipping",
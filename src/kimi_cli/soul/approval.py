@@ -10,7 +10,8 @@ from kimi_cli.approval_runtime import (
     ApprovalSource,
     get_current_approval_source_or_none,
 )
-from kimi_cli.soul.toolset import get_current_tool_call_or_none
+from kimi_cli.soul.stats import StatsRecorder
+from kimi_cli.soul.toolset import get_current_tool_call_or_none, tool_timeout_paused
 from kimi_cli.tools.utils import ToolRejectedError
 from kimi_cli.utils.logging import logger
 from kimi_cli.wire.types import DisplayBlock
@@ -85,13 +86,16 @@ class Approval:
         *,
         state: ApprovalState | None = None,
         runtime: ApprovalRuntime | None = None,
+        stats: StatsRecorder | None = None,
     ):
         self._state = state or ApprovalState(yolo=yolo)
         self._runtime = runtime or ApprovalRuntime()
+        self._stats = stats
+        """Counts the decisions of the user, if set."""
 
     def share(self) -> Approval:
         """Create a new approval queue that shares approval state."""
-        return Approval(state=self._state, runtime=self._runtime)
+        return Approval(state=self._state, runtime=self._runtime, stats=self._stats)
 
     def set_runtime(self, runtime: ApprovalRuntime) -> None:
         self._runtime = runtime
@@ -154,6 +158,8 @@ class Approval:
         action: str,
         description: str,
         display: list[DisplayBlock] | None = None,
+        *,
+        allow_session_approval: bool = True,
     ) -> ApprovalResult:
         """
         Request approval for the given action. Intended to be called by tools.
@@ -163,6 +169,9 @@ class Approval:
             action (str): The action to request approval for.
                 This is used to identify the action for auto-approval.
             description (str): The description of the action. This is used to display to the user.
+            allow_session_approval (bool): Whether approving the action for the session covers
+                later requests. If False, every request must be confirmed on its own, and an
+                "approve for session" response only approves this request.
 
         Returns:
             ApprovalResult: Result with ``approved`` flag and optional ``feedback``.
@@ -192,7 +201,7 @@ class Approval:
             )
             return ApprovalResult(approved=True)
 
-        if action in self._state.auto_approve_actions:
+        if allow_session_approval and action in self._state.auto_approve_actions:
             from kimi_cli.telemetry import track
 
             track(
@@ -218,8 +227,11 @@ class Approval:
             source=source,
         )
         try:
-            response, feedback = await self._runtime.wait_for_response(request_id)
+            with tool_timeout_paused():
+                response, feedback = await self._runtime.wait_for_response(request_id)
         except ApprovalCancelledError:
+            if self._stats is not None:
+                self._stats.record_approval(False)
             from kimi_cli.telemetry import track
 
             track(
@@ -231,6 +243,8 @@ class Approval:
             return ApprovalResult(approved=False, feedback=record.feedback if record else "")
         from kimi_cli.telemetry import track
 
+        if self._stats is not None:
+            self._stats.record_approval(response in ("approve", "approve_for_session"))
         match response:
             case "approve":
                 track(
@@ -245,6 +259,8 @@ class Approval:
                     tool_name=tool_call.function.name,
                     approval_mode="manual",
                 )
+                if not allow_session_approval:
+                    return ApprovalResult(approved=True)
                 self._state.auto_approve_actions.add(action)
                 self._state.notify_change()
                 for pending in self._runtime.list_pending():
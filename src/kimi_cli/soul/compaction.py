@@ -4,9 +4,10 @@ from collections.abc import Sequence
 from typing import TYPE_CHECKING, NamedTuple, Protocol, runtime_checkable
 
 import kosong
-from kosong.chat_provider import TokenUsage
+from kosong.chat_provider import GenerateOptions, StreamedMessagePart, TokenUsage
 from kosong.message import Message
 from kosong.tooling.empty import EmptyToolset
+from kosong.utils.aio import Callback
 
 import kimi_cli.prompts as prompts
 from kimi_cli.llm import LLM
@@ -18,6 +19,8 @@ from kimi_cli.wire.types import ContentPart, TextPart, ThinkPart
 class CompactionResult(NamedTuple):
     messages: Sequence[Message]
     usage: TokenUsage | None
+    n_summarized: int = 0
+    """The number of messages replaced by the summary."""
 
     @property
     def estimated_token_count(self) -> int:
@@ -75,7 +78,12 @@ def should_auto_compact(
 @runtime_checkable
 class Compaction(Protocol):
     async def compact(
-        self, messages: Sequence[Message], llm: LLM, *, custom_instruction: str = ""
+        self,
+        messages: Sequence[Message],
+        llm: LLM,
+        *,
+        custom_instruction: str = "",
+        on_message_part: Callback[[StreamedMessagePart], None] | None = None,
     ) -> CompactionResult:
         """
         Compact a sequence of messages into a new sequence of messages.
@@ -84,6 +92,8 @@ class Compaction(Protocol):
             messages (Sequence[Message]): The messages to compact.
             llm (LLM): The LLM to use for compaction.
             custom_instruction: Optional user instruction to guide compaction focus.
+            on_message_part: Optional callback receiving the parts of the summary as they are
+                generated, like the one of `kosong.step`.
 
         Returns:
             CompactionResult: The compacted messages and token usage from the compaction LLM call.
@@ -100,25 +110,56 @@ if TYPE_CHECKING:
         _: Compaction = simple
 
 
+DEFAULT_COMPACTION_MAX_OUTPUT_TOKENS = 16_000
+DEFAULT_COMPACTION_MAX_MESSAGE_TOKENS = 20_000
+
+
+def compaction_divider(n_summarized: int) -> str:
+    """The divider shown where the summary of a compaction replaces the earlier history."""
+    if n_summarized <= 0:
+        # Nothing was summarized, the history was only rewritten.
+        return "---- context compacted ----"
+    noun = "message" if n_summarized == 1 else "messages"
+    return f"---- context compacted ({n_summarized} {noun} → summary) ----"
+
+
 class SimpleCompaction:
-    def __init__(self, max_preserved_messages: int = 2) -> None:
+    def __init__(
+        self,
+        max_preserved_messages: int = 2,
+        *,
+        max_output_tokens: int | None = DEFAULT_COMPACTION_MAX_OUTPUT_TOKENS,
+        max_message_tokens: int | None = DEFAULT_COMPACTION_MAX_MESSAGE_TOKENS,
+    ) -> None:
         self.max_preserved_messages = max_preserved_messages
+        # Bound the summary so a compaction cannot balloon the context it is meant to shrink.
+        self.max_output_tokens = max_output_tokens
+        # Bound each summarized message so that one huge tool output cannot overflow the
+        # compaction request itself.
+        self.max_message_tokens = max_message_tokens
 
     async def compact(
-        self, messages: Sequence[Message], llm: LLM, *, custom_instruction: str = ""
+        self,
+        messages: Sequence[Message],
+        llm: LLM,
+        *,
+        custom_instruction: str = "",
+        on_message_part: Callback[[StreamedMessagePart], None] | None = None,
     ) -> CompactionResult:
         compact_message, to_preserve = self.prepare(messages, custom_instruction=custom_instruction)
         if compact_message is None:
             return CompactionResult(messages=to_preserve, usage=None)
 
         # Call kosong.step to get the compacted context
-        # TODO: set max completion tokens
         logger.debug("Compacting context...")
         result = await kosong.step(
             chat_provider=llm.chat_provider,
             system_prompt="You are a helpful assistant that compacts conversation context.",
             toolset=EmptyToolset(),
             history=[compact_message],
+            on_message_part=on_message_part,
+            # The summary must be plain text, never a tool call to loop on
+            options=GenerateOptions(max_output_tokens=self.max_output_tokens, tool_choice="none"),
         )
         if result.usage:
             logger.debug(
@@ -136,7 +177,11 @@ class SimpleCompaction:
         content.extend(part for part in compacted_msg.content if not isinstance(part, ThinkPart))
         compacted_messages: list[Message] = [Message(role="user", content=content)]
         compacted_messages.extend(to_preserve)
-        return CompactionResult(messages=compacted_messages, usage=result.usage)
+        return CompactionResult(
+            messages=compacted_messages,
+            usage=result.usage,
+            n_summarized=len(messages) - len(to_preserve),
+        )
 
     class PrepareResult(NamedTuple):
         compact_message: Message | None
@@ -171,6 +216,8 @@ class SimpleCompaction:
         # Create input message for compaction
         compact_message = Message(role="user", content=[])
         for i, msg in enumerate(to_compact):
+            if self.max_message_tokens is not None:
+                msg = msg.truncated(self.max_message_tokens)
             compact_message.content.append(
                 TextPart(text=f"## Message {i + 1}\nRole: {msg.role}\nContent:\n")
             )
@@ -1,8 +1,12 @@
 from __future__ import annotations
 
 import asyncio
+import bisect
 import json
-from collections.abc import Sequence
+import re
+import shutil
+from collections.abc import Callable, Iterator, Sequence
+from dataclasses import dataclass
 from pathlib import Path
 from typing import Any, cast
 
@@ -11,20 +15,74 @@ import aiofiles.os
 from kosong.message import Message
 from pydantic import ValidationError
 
+from kimi_cli.exception import SessionEncryptionError
 from kimi_cli.soul.compaction import estimate_text_tokens
 from kimi_cli.soul.message import system
 from kimi_cli.utils.logging import logger
 from kimi_cli.utils.path import next_available_rotation
+from kimi_cli.utils.redact import redact_message
+from kimi_cli.utils.session_crypto import decode_line, encode_line
+
+
+TOOL_RESULT_LOST_MESSAGE = "ERROR: Tool result lost due to interruption."
+
+
+@dataclass(frozen=True, slots=True)
+class ContextMatch:
+    """A line of the history that matches a search."""
+
+    index: int
+    """The index of the message in the history."""
+    checkpoint_id: int | None
+    """The last checkpoint before the message, None if it precedes all checkpoints."""
+    role: str
+    tool_call_id: str | None
+    """The tool call the match is in, for tool call arguments and tool results."""
+    tool_name: str | None
+    """The name of the called tool, for tool call arguments."""
+    line_no: int
+    """The 1-based line number of the match in the searched text."""
+    excerpt: str
+    """The matching line with the lines around it."""
+
+
+@dataclass(frozen=True, slots=True)
+class CompactionMarker:
+    """Where the summary of a compaction begins in the history."""
+
+    id: str
+    """A short ID of the compaction, also sent in `CompactionEnd`."""
+    n_summarized: int
+    """The number of messages replaced by the summary."""
+    before_tokens: int
+    """The token count of the history before the compaction."""
+    after_tokens: int
+    """The estimated token count of the history after the compaction."""
+    position: int = 0
+    """The length of the history when the marker was written, i.e. the index of the summary."""
 
 
 class Context:
-    def __init__(self, file_backend: Path):
+    """
+    The history of a session, backed by a JSONL file.
+
+    The token count always describes the current history: `token_count` is the last usage
+    reported for a prefix of it, and the pending estimate covers the messages appended since.
+    Every operation that changes the history outside of `append_message` (restoring, reverting,
+    clearing) rebuilds both, so that compaction decisions never see a stale count.
+    """
+
+    def __init__(self, file_backend: Path, *, session_id: str | None = None):
         self._file_backend = file_backend
+        self._session_id = session_id
         self._history: list[Message] = []
         self._token_count: int = 0
         self._pending_token_estimate: int = 0
         self._next_checkpoint_id: int = 0
         """The ID of the next checkpoint, starting from 0, incremented after each checkpoint."""
+        self._checkpoint_positions: list[int] = []
+        """The length of the history at each checkpoint, indexed by checkpoint ID."""
+        self._compaction_markers: list[CompactionMarker] = []
         self._system_prompt: str | None = None
 
     async def restore(self) -> bool:
@@ -62,6 +120,7 @@ class Context:
                 )
 
         self._pending_token_estimate = estimate_text_tokens(messages_after_last_usage)
+        self.validate_and_repair()
         return True
 
     @property
@@ -70,16 +129,38 @@ class Context:
 
     @property
     def token_count(self) -> int:
+        """The token count of the history up to the last recorded usage."""
         return self._token_count
 
     @property
     def token_count_with_pending(self) -> int:
+        """The token count of the whole history, estimating the messages after the last usage."""
         return self._token_count + self._pending_token_estimate
 
     @property
     def n_checkpoints(self) -> int:
         return self._next_checkpoint_id
 
+    @property
+    def compaction_markers(self) -> Sequence[CompactionMarker]:
+        return self._compaction_markers
+
+    def checkpoint_messages(self, checkpoint_id: int) -> Sequence[Message]:
+        """
+        The messages appended after the specified checkpoint and before the next one.
+
+        Raises:
+            ValueError: When the checkpoint does not exist.
+        """
+        if not 0 <= checkpoint_id < len(self._checkpoint_positions):
+            raise ValueError(f"Checkpoint {checkpoint_id} does not exist")
+        start = self._checkpoint_positions[checkpoint_id]
+        if checkpoint_id + 1 < len(self._checkpoint_positions):
+            end = self._checkpoint_positions[checkpoint_id + 1]
+        else:
+            end = len(self._history)
+        return self._history[start:end]
+
     @property
     def system_prompt(self) -> str | None:
         return self._system_prompt
@@ -96,7 +177,7 @@ class Context:
         temporary file to avoid corruption on crash and avoid loading the entire file
         into memory.
         """
-        prompt_line = json.dumps({"role": "_system_prompt", "content": prompt}) + "\n"
+        prompt_line = encode_line(json.dumps({"role": "_system_prompt", "content": prompt})) + "\n"
 
         def _write_system_prompt_sync() -> None:
             if not self._file_backend.exists() or self._file_backend.stat().st_size == 0:
@@ -124,9 +205,11 @@ class Context:
         checkpoint_id = self._next_checkpoint_id
         self._next_checkpoint_id += 1
         logger.debug("Checkpointing, ID: {id}", id=checkpoint_id)
+        self._checkpoint_positions.append(len(self._history))
 
         async with aiofiles.open(self._file_backend, "a", encoding="utf-8") as f:
-            await f.write(json.dumps({"role": "_checkpoint", "id": checkpoint_id}) + "\n")
+            record = json.dumps({"role": "_checkpoint", "id": checkpoint_id})
+            await f.write(encode_line(record) + "\n")
         if add_user_message:
             await self.append_message(
                 Message(role="user", content=[system(f"CHECKPOINT {checkpoint_id}")])
@@ -138,6 +221,11 @@ class Context:
         After this, the specified checkpoint and all subsequent content will be
         removed from the context. File backend will be rotated.
 
+        The token count is restored along with the messages: it is replayed from the last usage
+        recorded before the checkpoint, and the messages after that usage are estimated as
+        pending. A checkpoint made after a compaction restores the compacted count, since the
+        compaction clears the file and records the estimate of the compacted history.
+
         Args:
             checkpoint_id (int): The ID of the checkpoint to revert to. 0 is the first checkpoint.
 
@@ -151,21 +239,9 @@ class Context:
             logger.error("Checkpoint {checkpoint_id} does not exist", checkpoint_id=checkpoint_id)
             raise ValueError(f"Checkpoint {checkpoint_id} does not exist")
 
-        # rotate the context file
-        rotated_file_path = await next_available_rotation(self._file_backend)
-        if rotated_file_path is None:
-            logger.error("No available rotation path found")
-            raise RuntimeError("No available rotation path found")
-        await aiofiles.os.replace(self._file_backend, rotated_file_path)
-        logger.debug(
-            "Rotated context file: {rotated_file_path}", rotated_file_path=rotated_file_path
-        )
+        rotated_file_path = await self._reset_state()
 
         # restore the context until the specified checkpoint
-        self._history.clear()
-        self._token_count = 0
-        self._next_checkpoint_id = 0
-        self._system_prompt = None
         messages_after_last_usage: list[Message] = []
         async with (
             aiofiles.open(rotated_file_path, encoding="utf-8", errors="replace") as old_file,
@@ -198,6 +274,7 @@ class Context:
                     await new_file.write(line)
 
         self._pending_token_estimate = estimate_text_tokens(messages_after_last_usage)
+        self.validate_and_repair()
 
     async def clear(self):
         """
@@ -212,13 +289,58 @@ class Context:
 
         logger.debug("Clearing context")
 
-        # rotate the context file
+        await self._reset_state()
+        self._file_backend.touch()
+
+    async def save_snapshot(self, path: Path) -> None:
+        """
+        Save the context file to `path`, with its checkpoints and token counts, to be loaded
+        later by `load_snapshot`. An existing snapshot at `path` is replaced.
+        """
+        logger.debug("Saving context snapshot: {path}", path=path)
+
+        def _save_snapshot_sync() -> None:
+            path.parent.mkdir(parents=True, exist_ok=True)
+            tmp_path = path.with_suffix(".tmp")
+            shutil.copyfile(self._file_backend, tmp_path)
+            tmp_path.replace(path)
+
+        await asyncio.to_thread(_save_snapshot_sync)
+
+    async def load_snapshot(self, path: Path) -> None:
+        """
+        Replace the context with a snapshot saved by `save_snapshot`.
+        File backend will be rotated.
+
+        Raises:
+            FileNotFoundError: When the snapshot does not exist.
+            RuntimeError: When no available rotation path is found.
+        """
+
+        logger.debug("Loading context snapshot: {path}", path=path)
+        if not path.is_file():
+            raise FileNotFoundError(f"Context snapshot not found: {path}")
+
+        await self._reset_state()
+        await asyncio.to_thread(shutil.copyfile, path, self._file_backend)
+        await self.restore()
+
+    async def _reset_state(self) -> Path:
+        """
+        Rotate the context file away and forget everything read from it: the history, the token
+        counts, the checkpoints, the compaction markers and the system prompt.
+
+        Returns:
+            Path: The path the context file was rotated to.
+
+        Raises:
+            RuntimeError: When no available rotation path is found.
+        """
         rotated_file_path = await next_available_rotation(self._file_backend)
         if rotated_file_path is None:
             logger.error("No available rotation path found")
             raise RuntimeError("No available rotation path found")
         await aiofiles.os.replace(self._file_backend, rotated_file_path)
-        self._file_backend.touch()
         logger.debug(
             "Rotated context file: {rotated_file_path}", rotated_file_path=rotated_file_path
         )
@@ -227,7 +349,36 @@ class Context:
         self._token_count = 0
         self._pending_token_estimate = 0
         self._next_checkpoint_id = 0
+        self._checkpoint_positions.clear()
+        self._compaction_markers.clear()
         self._system_prompt = None
+        return rotated_file_path
+
+    async def write_compaction_marker(
+        self, *, id: str, n_summarized: int, before_tokens: int, after_tokens: int
+    ) -> CompactionMarker:
+        """Mark the end of the history as the place where the summary of a compaction begins."""
+        marker = CompactionMarker(
+            id=id,
+            n_summarized=n_summarized,
+            before_tokens=before_tokens,
+            after_tokens=after_tokens,
+            position=len(self._history),
+        )
+        self._compaction_markers.append(marker)
+
+        async with aiofiles.open(self._file_backend, "a", encoding="utf-8") as f:
+            record = json.dumps(
+                {
+                    "role": "_compaction",
+                    "id": id,
+                    "n_summarized": n_summarized,
+                    "before_tokens": before_tokens,
+                    "after_tokens": after_tokens,
+                }
+            )
+            await f.write(encode_line(record) + "\n")
+        return marker
 
     async def append_message(self, message: Message | Sequence[Message]):
         logger.debug("Appending message(s) to context: {message}", message=message)
@@ -237,7 +388,84 @@ class Context:
 
         async with aiofiles.open(self._file_backend, "a", encoding="utf-8") as f:
             for message in messages:
-                await f.write(message.model_dump_json(exclude_none=True) + "\n")
+                line = redact_message(message).model_dump_json(exclude_none=True)
+                await f.write(encode_line(line) + "\n")
+
+    def search(
+        self,
+        pattern: re.Pattern[str],
+        *,
+        role: str | None = None,
+        context_lines: int = 2,
+        skip: Callable[[Message], bool] | None = None,
+    ) -> Iterator[ContextMatch]:
+        """
+        Search the text, tool call arguments and tool results of the history for `pattern`.
+
+        Matches are yielded newest message first, and in order within a message. The history
+        is read in place, so stopping early costs nothing for the older messages.
+
+        Args:
+            role: Only search messages of this role.
+            context_lines: The number of lines to include before and after each match.
+            skip: Messages for which this returns True are not searched.
+        """
+        for index in range(len(self._history) - 1, -1, -1):
+            message = self._history[index]
+            if (role is not None and message.role != role) or (skip and skip(message)):
+                continue
+            checkpoint_id = bisect.bisect_right(self._checkpoint_positions, index) - 1
+            texts: list[tuple[str, str | None, str | None]] = [
+                (message.extract_text("\n"), message.tool_call_id, None)
+            ]
+            for tool_call in message.tool_calls or []:
+                texts.append(
+                    (tool_call.function.arguments or "", tool_call.id, tool_call.function.name)
+                )
+            for text, tool_call_id, tool_name in texts:
+                if not pattern.search(text):
+                    continue
+                lines = text.splitlines()
+                for i, line in enumerate(lines):
+                    if not pattern.search(line):
+                        continue
+                    yield ContextMatch(
+                        index=index,
+                        checkpoint_id=checkpoint_id if checkpoint_id >= 0 else None,
+                        role=message.role,
+                        tool_call_id=tool_call_id,
+                        tool_name=tool_name,
+                        line_no=i + 1,
+                        excerpt="\n".join(lines[max(0, i - context_lines) : i + context_lines + 1]),
+                    )
+
+    def validate_and_repair(self) -> int:
+        """
+        Make sure every tool call in the history is answered exactly once, right after the
+        assistant message that made it, so that providers accept the history.
+
+        - A tool call without a result gets a synthesized error result.
+        - A tool result whose call is missing, or that answers an already answered call, is
+          dropped.
+        - Tool results separated from their assistant message, or out of order, are moved
+          right after it, in the order of the tool calls.
+
+        Repairs only apply to the in-memory history. They are deterministic, so restoring the
+        same file produces the same repaired history.
+
+        Returns:
+            int: The number of repairs made.
+        """
+        repaired, repairs = repair_tool_messages(self._history)
+        for repair in repairs:
+            logger.warning(
+                "Repaired context of session {session_id}: {repair}",
+                session_id=self._session_id or "unknown",
+                repair=repair,
+            )
+        if repairs:
+            self._history[:] = repaired
+        return len(repairs)
 
     async def update_token_count(self, token_count: int):
         logger.debug("Updating token count in context: {token_count}", token_count=token_count)
@@ -245,7 +473,8 @@ class Context:
         self._pending_token_estimate = 0
 
         async with aiofiles.open(self._file_backend, "a", encoding="utf-8") as f:
-            await f.write(json.dumps({"role": "_usage", "token_count": token_count}) + "\n")
+            record = json.dumps({"role": "_usage", "token_count": token_count})
+            await f.write(encode_line(record) + "\n")
 
     def _parse_context_line(
         self,
@@ -254,6 +483,12 @@ class Context:
         file_backend: Path,
         line_no: int,
     ) -> dict[str, Any] | None:
+        try:
+            line = decode_line(line)
+        except SessionEncryptionError as exc:
+            raise SessionEncryptionError(
+                f"Cannot read line {line_no} of {file_backend}: {exc}"
+            ) from exc
         try:
             line_json = json.loads(line, strict=False)
         except json.JSONDecodeError as exc:
@@ -323,6 +558,30 @@ class Context:
                 )
                 return False
             self._next_checkpoint_id = checkpoint_id + 1
+            self._checkpoint_positions.append(len(history))
+            return True
+        if role == "_compaction":
+            marker_id = line_json.get("id")
+            counts = [
+                line_json.get(key) for key in ("n_summarized", "before_tokens", "after_tokens")
+            ]
+            if not isinstance(marker_id, str) or not all(isinstance(c, int) for c in counts):
+                logger.warning(
+                    "Skipping invalid compaction line {line_no} in {file}",
+                    line_no=line_no,
+                    file=file_backend,
+                )
+                return False
+            n_summarized, before_tokens, after_tokens = cast(list[int], counts)
+            self._compaction_markers.append(
+                CompactionMarker(
+                    id=marker_id,
+                    n_summarized=n_summarized,
+                    before_tokens=before_tokens,
+                    after_tokens=after_tokens,
+                    position=len(history),
+                )
+            )
             return True
         try:
             message = Message.model_validate(line_json)
@@ -337,3 +596,57 @@ class Context:
         history.append(message)
         messages_after_last_usage.append(message)
         return True
+
+
+def repair_tool_messages(history: Sequence[Message]) -> tuple[list[Message], list[str]]:
+    """
+    Pair every tool call with exactly one tool result placed right after its assistant message.
+
+    Returns the repaired history and a description of each repair made.
+    """
+    repaired: list[Message] = []
+    repairs: list[str] = []
+    placed: set[int] = set()
+    for i, message in enumerate(history):
+        if message.role == "tool":
+            if i not in placed:
+                repairs.append(f"dropped orphan tool result {message.tool_call_id!r}")
+            continue
+        repaired.append(message)
+        if message.role != "assistant" or not message.tool_calls:
+            continue
+
+        # Tool results for this message can only appear before the next assistant message,
+        # since tool call IDs may be reused across steps.
+        end = next(
+            (j for j in range(i + 1, len(history)) if history[j].role == "assistant"),
+            len(history),
+        )
+        expected = i + 1
+        for tool_call in message.tool_calls:
+            index = next(
+                (
+                    j
+                    for j in range(i + 1, end)
+                    if j not in placed
+                    and history[j].role == "tool"
+                    and history[j].tool_call_id == tool_call.id
+                ),
+                None,
+            )
+            if index is None:
+                repairs.append(f"synthesized missing tool result for {tool_call.id!r}")
+                repaired.append(
+                    Message(
+                        role="tool",
+                        content=[system(TOOL_RESULT_LOST_MESSAGE)],
+                        tool_call_id=tool_call.id,
+                    )
+                )
+                continue
+            if index != expected:
+                repairs.append(f"moved tool result {tool_call.id!r} after its tool call")
+            placed.add(index)
+            repaired.append(history[index])
+            expected = index + 1
+    return repaired, repairs
@@ -6,7 +6,14 @@ from pydantic import BaseModel, Field
 class DMail(BaseModel):
     message: str = Field(description="The message to send.")
     checkpoint_id: int = Field(description="The checkpoint to send the message back to.", ge=0)
-    # TODO: allow restoring filesystem state to the checkpoint
+    restore_files: bool = Field(
+        description=(
+            "Also undo the changes made by `WriteFile` and `StrReplaceFile` to files in the "
+            "working directory since the checkpoint. The user is asked to confirm first. "
+            "Only available when the file journal is enabled."
+        ),
+        default=False,
+    )
 
 
 class DenwaRenjiError(Exception):
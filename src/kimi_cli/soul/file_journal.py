@@ -0,0 +1,197 @@
+"""
+Journal of file pre-images, used to undo the file changes made after a checkpoint.
+
+Before `WriteFile` or `StrReplaceFile` changes a file in the working directory, the original
+content (or the fact that the file did not exist) is recorded under the checkpoint the root
+agent is currently in. Each checkpoint has its own directory in the session:
+
+    file_journal/<checkpoint_id>/index.jsonl   one `JournalEntry` per recorded file
+    file_journal/<checkpoint_id>/<n>            the saved pre-image of the n-th entry
+
+Only the first change to a file within a checkpoint is recorded, so restoring the oldest entry
+of each file across the discarded checkpoints brings it back to its state at the checkpoint.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import shutil
+from pathlib import Path
+from typing import Literal
+
+from kaos.path import KaosPath
+from pydantic import BaseModel, ValidationError
+
+from kimi_cli.config import FileJournalConfig
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import is_within_directory
+
+INDEX_FILE = "index.jsonl"
+
+
+class JournalEntry(BaseModel):
+    path: str
+    """The canonical path of the file."""
+    state: Literal["saved", "absent", "skipped"]
+    """Whether the pre-image is saved, the file did not exist, or it was too large to record."""
+    blob: str | None = None
+    """The name of the file holding the pre-image, when `state` is `saved`."""
+
+
+class FileJournal:
+    def __init__(self, root: Path, work_dir: KaosPath, config: FileJournalConfig):
+        self._root = root
+        self._work_dir = work_dir.canonical()
+        self._config = config
+        self._checkpoint_id: int | None = None
+        self._recorded: set[str] = set()
+        """Paths already recorded in the current checkpoint."""
+        self._entry_count = 0
+        """Entries in the index of the current checkpoint, which also names the next blob."""
+        self._total_size: int | None = None
+        self._lock = asyncio.Lock()
+
+    def begin_checkpoint(self, checkpoint_id: int) -> None:
+        """
+        Start recording into a new checkpoint. Intended to be called by the root soul.
+
+        Checkpoint IDs restart after the context is reverted or cleared, so the journals of
+        this checkpoint and every later one belong to discarded checkpoints and are pruned.
+        """
+        self.discard(checkpoint_id)
+        self._checkpoint_id = checkpoint_id
+        self._recorded.clear()
+        self._entry_count = 0
+
+    async def record(self, path: KaosPath) -> None:
+        """
+        Record the pre-image of `path` before it is modified. Paths outside the working
+        directory, and files already recorded in the current checkpoint, are ignored.
+        """
+        if self._checkpoint_id is None:
+            return
+        path = path.canonical()
+        if not is_within_directory(path, self._work_dir):
+            return
+        key = str(path)
+        async with self._lock:
+            if key in self._recorded:
+                return
+            self._recorded.add(key)
+
+            checkpoint_dir = self._root / str(self._checkpoint_id)
+            if not await path.exists():
+                entry = JournalEntry(path=key, state="absent")
+            else:
+                size = (await path.stat()).st_size
+                used_size = await asyncio.to_thread(self._used_size)
+                if size > self._config.max_file_size or (
+                    used_size + size > self._config.max_total_size
+                ):
+                    logger.warning(
+                        "Not recording {path} in the file journal: {size} bytes exceeds the cap",
+                        path=key,
+                        size=size,
+                    )
+                    entry = JournalEntry(path=key, state="skipped")
+                else:
+                    data = await path.read_bytes()
+                    blob = str(self._entry_count)
+                    await asyncio.to_thread(_write_blob, checkpoint_dir / blob, data)
+                    self._total_size = used_size + len(data)
+                    entry = JournalEntry(path=key, state="saved", blob=blob)
+
+            await asyncio.to_thread(_append_entry, checkpoint_dir, entry)
+            self._entry_count += 1
+
+    def changed_paths(self, since: int) -> list[str]:
+        """The files recorded in checkpoint `since` and any later one, in recording order."""
+        return list(self._oldest_entries(since))
+
+    async def restore(self, since: int) -> list[str]:
+        """
+        Restore the files recorded in checkpoint `since` and any later one to their state at
+        checkpoint `since`, then discard those journals.
+
+        Returns:
+            list[str]: The paths that were restored. Files too large to be recorded are left
+                as they are.
+        """
+        restored: list[str] = []
+        for path, (checkpoint_dir, entry) in self._oldest_entries(since).items():
+            target = KaosPath(path)
+            try:
+                match entry.state:
+                    case "saved":
+                        assert entry.blob is not None
+                        data = await asyncio.to_thread((checkpoint_dir / entry.blob).read_bytes)
+                        await target.write_bytes(data)
+                    case "absent":
+                        if await target.exists():
+                            await target.unlink()
+                    case "skipped":
+                        continue
+            except OSError as e:
+                logger.warning("Failed to restore {path}: {error}", path=path, error=e)
+                continue
+            restored.append(path)
+        self.discard(since)
+        return restored
+
+    def discard(self, since: int = 0) -> None:
+        """Drop the journals of checkpoint `since` and every later one without restoring."""
+        for checkpoint_dir in self._checkpoint_dirs(since):
+            shutil.rmtree(checkpoint_dir, ignore_errors=True)
+            self._total_size = None
+
+    def _checkpoint_dirs(self, since: int) -> list[Path]:
+        if not self._root.is_dir():
+            return []
+        ids = sorted(
+            int(child.name)
+            for child in self._root.iterdir()
+            if child.is_dir() and child.name.isdigit() and int(child.name) >= since
+        )
+        return [self._root / str(checkpoint_id) for checkpoint_id in ids]
+
+    def _oldest_entries(self, since: int) -> dict[str, tuple[Path, JournalEntry]]:
+        entries: dict[str, tuple[Path, JournalEntry]] = {}
+        for checkpoint_dir in self._checkpoint_dirs(since):
+            for entry in self._read_index(checkpoint_dir):
+                entries.setdefault(entry.path, (checkpoint_dir, entry))
+        return entries
+
+    def _read_index(self, checkpoint_dir: Path) -> list[JournalEntry]:
+        index = checkpoint_dir / INDEX_FILE
+        if not index.exists():
+            return []
+        entries: list[JournalEntry] = []
+        for line in index.read_text(encoding="utf-8").splitlines():
+            if not line.strip():
+                continue
+            try:
+                entries.append(JournalEntry.model_validate_json(line))
+            except ValidationError:
+                logger.warning("Skipping invalid file journal entry in {index}", index=index)
+        return entries
+
+    def _used_size(self) -> int:
+        if self._total_size is None:
+            self._total_size = sum(
+                blob.stat().st_size
+                for checkpoint_dir in self._checkpoint_dirs(0)
+                for blob in checkpoint_dir.iterdir()
+                if blob.name != INDEX_FILE
+            )
+        return self._total_size
+
+
+def _write_blob(path: Path, data: bytes) -> None:
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_bytes(data)
+
+
+def _append_entry(checkpoint_dir: Path, entry: JournalEntry) -> None:
+    checkpoint_dir.mkdir(parents=True, exist_ok=True)
+    with (checkpoint_dir / INDEX_FILE).open("a", encoding="utf-8") as f:
+        f.write(entry.model_dump_json(exclude_none=True) + "\n")
@@ -0,0 +1,117 @@
+"""
+Tracks the state of the files the agent has read or written.
+
+File tools check the tracker before writing, so that a file the user changed in their editor
+while the agent was working is not overwritten with content based on a stale read.
+"""
+
+from __future__ import annotations
+
+import hashlib
+from collections import OrderedDict
+from dataclasses import dataclass
+
+from kaos.path import KaosPath
+
+from kimi_cli.utils.diff import format_unified_diff
+
+MAX_TRACKED_FILES = 512
+MAX_SNAPSHOT_BYTES = 256 << 10  # 256KB
+"""
+Files up to this size are read to keep their content, so that external changes can be shown as a
+diff. Larger files are tracked by mtime and size alone.
+"""
+MAX_DIFF_LINES = 40
+
+
+@dataclass(frozen=True, slots=True)
+class FileSnapshot:
+    mtime: float
+    size: int
+    digest: str | None
+    """The SHA-256 of the content, if the file is small enough to be read."""
+    text: str | None
+    """The content of the file, if it is small enough to diff against."""
+
+
+class FileTracker:
+    """The last seen state of files, keyed by canonical path and bounded in LRU order."""
+
+    def __init__(self, max_files: int = MAX_TRACKED_FILES):
+        self._max_files = max_files
+        self._snapshots: OrderedDict[str, FileSnapshot] = OrderedDict()
+
+    async def record(self, path: KaosPath) -> None:
+        """Remember the current state of `path`, right after the agent read or wrote it."""
+        path = path.canonical()
+        key = str(path)
+        snapshot = await _take_snapshot(path)
+        if snapshot is None:
+            self._snapshots.pop(key, None)
+            return
+        self._snapshots[key] = snapshot
+        self._snapshots.move_to_end(key)
+        while len(self._snapshots) > self._max_files:
+            self._snapshots.popitem(last=False)
+
+    async def check(self, path: KaosPath) -> str | None:
+        """
+        Check whether `path` was changed by someone else since the agent last saw it.
+
+        Returns:
+            str | None: A description of the external change, with a short diff when possible.
+                None if the file is unchanged, or was never read or written by the agent.
+        """
+        path = path.canonical()
+        seen = self._snapshots.get(str(path))
+        if seen is None:
+            return None
+        if not await path.exists():
+            return "The file has been deleted."
+
+        stat = await path.stat()
+        if stat.st_mtime == seen.mtime and stat.st_size == seen.size:
+            return None
+        current = await _take_snapshot(path)
+        if current is None or (current.digest is not None and current.digest == seen.digest):
+            # Touched but not changed
+            await self.record(path)
+            return None
+
+        if seen.text is None or current.text is None:
+            return (
+                "The file has been changed; it is too large to keep a copy, "
+                "so no diff is available."
+            )
+        diff_lines = format_unified_diff(
+            seen.text, current.text, str(path), include_file_header=False
+        ).splitlines()
+        if len(diff_lines) > MAX_DIFF_LINES:
+            omitted = len(diff_lines) - MAX_DIFF_LINES
+            diff_lines = diff_lines[:MAX_DIFF_LINES] + [f"... ({omitted} more lines)"]
+        diff = "\n".join(diff_lines)
+        return f"The file has been changed as follows:\n```diff\n{diff}\n```"
+
+    def has_seen(self, path: KaosPath) -> bool:
+        """Whether the agent has read or written `path` since the tracker was last cleared."""
+        return str(path.canonical()) in self._snapshots
+
+    def clear(self) -> None:
+        """Forget every file, e.g. when the context is cleared."""
+        self._snapshots.clear()
+
+
+async def _take_snapshot(path: KaosPath) -> FileSnapshot | None:
+    try:
+        stat = await path.stat()
+        if stat.st_size > MAX_SNAPSHOT_BYTES:
+            return FileSnapshot(mtime=stat.st_mtime, size=stat.st_size, digest=None, text=None)
+        data = await path.read_bytes()
+    except OSError:
+        return None
+    return FileSnapshot(
+        mtime=stat.st_mtime,
+        size=stat.st_size,
+        digest=hashlib.sha256(data).hexdigest(),
+        text=data.decode("utf-8", errors="replace"),
+    )
@@ -4,42 +4,53 @@ import asyncio
 import time
 import uuid
 from collections.abc import Awaitable, Callable, Sequence
-from dataclasses import dataclass
+from dataclasses import dataclass, replace
 from pathlib import Path
 from typing import TYPE_CHECKING, Any, Literal, cast
 
 import kosong
 import tenacity
-from kosong import StepResult
+from kosong import GenerationMetrics, StepResult
 from kosong.chat_provider import (
     APIConnectionError,
     APIEmptyResponseError,
     APIStatusError,
     APITimeoutError,
+    ChatProvider,
+    GenerateOptions,
+    PartialContinuationChatProvider,
     RetryableChatProvider,
+    StreamedMessagePart,
+    ToolChoice,
 )
-from kosong.message import Message
+from kosong.chat_provider.rate_limit import RateLimiter
+from kosong.message import Message, merge_content_parts
 from tenacity import RetryCallState, retry_if_exception, stop_after_attempt, wait_exponential_jitter
 
 from kimi_cli.approval_runtime import (
+    ApprovalRuntimeEvent,
     ApprovalSource,
     get_current_approval_source_or_none,
     reset_current_approval_source,
     set_current_approval_source,
 )
 from kimi_cli.background import build_active_task_snapshot
+from kimi_cli.config import StreamThinking
 from kimi_cli.hooks.engine import HookEngine
-from kimi_cli.llm import ModelCapability
+from kimi_cli.llm import LLM, ModelCapability, clone_llm_with_model_alias, get_rate_limiter
 from kimi_cli.notifications import (
+    NotificationEvent,
     NotificationView,
     build_notification_message,
     extract_notification_ids,
 )
-from kimi_cli.skill import Skill, read_skill_text
+from kimi_cli.skill import Skill, read_skill_text, render_skill_args
 from kimi_cli.skill.flow import Flow, FlowEdge, FlowNode, parse_choice
 from kimi_cli.soul import (
+    BudgetExceeded,
     LLMNotSet,
     LLMNotSupported,
+    MaxMessageSizeExceeded,
     MaxStepsReached,
     Soul,
     StatusSnapshot,
@@ -60,32 +71,55 @@ from kimi_cli.soul.dynamic_injection import (
 )
 from kimi_cli.soul.dynamic_injections.afk_mode import AfkModeInjectionProvider
 from kimi_cli.soul.dynamic_injections.plan_mode import PlanModeInjectionProvider
-from kimi_cli.soul.message import check_message, system, system_reminder, tool_result_to_message
+from kimi_cli.soul.media import DowngradedMedia, downgrade_media
+from kimi_cli.soul.message import (
+    InvalidUserInput,
+    check_message,
+    dmail_message,
+    prepare_user_input,
+    system,
+    system_reminder,
+    tool_result_to_message,
+)
+from kimi_cli.soul.observer import SoulObserver
+from kimi_cli.soul.ralph import SuccessCheck
 from kimi_cli.soul.slash import registry as soul_slash_registry
 from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.soul.turn_summary import failure_stop_reason
 from kimi_cli.tools.dmail import NAME as SendDMail_NAME
 from kimi_cli.tools.utils import ToolRejectedError
 from kimi_cli.utils.logging import logger
+from kimi_cli.utils.redact import redact_message, redact_tool_result
 from kimi_cli.utils.slashcmd import SlashCommand, parse_slash_command_call
 from kimi_cli.wire.file import WireFile
 from kimi_cli.wire.types import (
     CompactionBegin,
     CompactionEnd,
+    CompactionProgress,
     ContentPart,
     MCPLoadingBegin,
     MCPLoadingEnd,
+    RateLimitStatus,
     StatusUpdate,
     SteerInput,
     StepBegin,
     StepInterrupted,
+    StepMetrics,
+    StepPhase,
     StepRetry,
     TextPart,
+    ThinkPart,
+    TodoDisplayItem,
+    ToolCall,
+    ToolCallPart,
+    ToolCallPreview,
     ToolResult,
     TurnBegin,
     TurnEnd,
 )
 
 if TYPE_CHECKING:
+    from kimi_cli.tools.todo import Todo
 
     def type_check(soul: KimiSoul):
         _: Soul = soul
@@ -94,6 +128,10 @@ if TYPE_CHECKING:
 SKILL_COMMAND_PREFIX = "skill:"
 FLOW_COMMAND_PREFIX = "flow:"
 DEFAULT_MAX_FLOW_MOVES = 1000
+THINKING_SUMMARY_CHARS = 300
+"""The number of reasoning characters forwarded per step in the `summary` thinking mode."""
+TOOL_CALL_PREVIEW_INTERVAL = 10
+"""The number of argument fragments between two previews of a streaming tool call."""
 
 
 def classify_api_error(e: Exception) -> tuple[str, int | None]:
@@ -177,7 +215,10 @@ class KimiSoul:
         self._approval = agent.runtime.approval
         self._context = context
         self._loop_control = agent.runtime.config.loop_control
-        self._compaction = SimpleCompaction()  # TODO: maybe configurable and composable
+        # TODO: make the compaction strategy itself configurable and composable
+        self._compaction = SimpleCompaction(
+            max_output_tokens=self._loop_control.compaction_max_output_tokens
+        )
 
         for tool in agent.toolset.tools:
             if tool.name == SendDMail_NAME:
@@ -191,6 +232,7 @@ class KimiSoul:
         self._plan_mode: bool = self._runtime.session.state.plan_mode
         self._plan_session_id: str | None = self._runtime.session.state.plan_session_id
         self._current_turn_id: str = ""
+        self._vision_llm: LLM | None = None
         # Pre-warm slug cache so the persisted slug survives process restarts
         if self._plan_session_id is not None and self._runtime.session.state.plan_slug is not None:
             from kimi_cli.tools.plan.heroes import seed_slug_cache
@@ -209,11 +251,15 @@ class KimiSoul:
         ]
         self._hook_engine: HookEngine = HookEngine()
         self._stop_hook_active: bool = False
+        self._observers: list[SoulObserver] = []
+        self._tool_choice: ToolChoice = "auto"
+        """The tool choice of the steps of the current turn, set by `FlowRunner`."""
         if self.is_root:
             self._runtime.notifications.ack_ids("llm", extract_notification_ids(context.history))
 
         # Bind plan mode state to tools that support it
         self._bind_plan_mode_tools()
+        self._bind_context_tools()
 
         self._slash_commands = self._build_slash_commands()
         self._slash_command_map = self._index_slash_commands(self._slash_commands)
@@ -280,6 +326,28 @@ class KimiSoul:
         """Register an additional dynamic injection provider."""
         self._injection_providers.append(provider)
 
+    def add_observer(self, observer: SoulObserver) -> None:
+        """Register an observer of the turns, steps and tool calls of this soul."""
+        self._observers.append(observer)
+
+    def _observe(self, notify: Callable[[SoulObserver], None]) -> None:
+        """Call `notify` on every observer, logging the exceptions they raise."""
+        for observer in self._observers:
+            try:
+                notify(observer)
+            except Exception:
+                logger.exception(
+                    "Soul observer {observer} failed", observer=type(observer).__name__
+                )
+
+    def _send_turn_begin(self, user_input: str | list[ContentPart]) -> None:
+        wire_send(TurnBegin(user_input=user_input))
+        self._observe(lambda observer: observer.on_turn_begin(user_input))
+
+    def _send_turn_end(self, turn_end: TurnEnd) -> None:
+        wire_send(turn_end)
+        self._observe(lambda observer: observer.on_turn_end(turn_end))
+
     async def _collect_injections(self) -> list[DynamicInjection]:
         """Collect dynamic injections from all registered providers."""
         injections: list[DynamicInjection] = []
@@ -348,6 +416,18 @@ class KimiSoul:
         if isinstance(replace_tool, StrReplaceFile):
             replace_tool.bind_plan_mode(checker, path_getter)
 
+        from kimi_cli.tools.file.multi_replace import MultiStrReplaceFile
+
+        multi_replace_tool = self._agent.toolset.find("MultiStrReplaceFile")
+        if isinstance(multi_replace_tool, MultiStrReplaceFile):
+            multi_replace_tool.bind_plan_mode(checker, path_getter)
+
+        from kimi_cli.tools.file.notebook import NotebookEdit
+
+        notebook_tool = self._agent.toolset.find("NotebookEdit")
+        if isinstance(notebook_tool, NotebookEdit):
+            notebook_tool.bind_plan_mode(checker, path_getter)
+
         # ExitPlanMode has a special bind() method
         from kimi_cli.tools.plan import ExitPlanMode
 
@@ -380,6 +460,17 @@ class KimiSoul:
         if isinstance(ask_tool, AskUserQuestion):
             ask_tool.bind_afk(self._approval.is_afk)
 
+    def _bind_context_tools(self) -> None:
+        """Bind the context to tools that search it."""
+        if not isinstance(self._agent.toolset, KimiToolset):
+            return
+
+        from kimi_cli.tools.context import FindInContext
+
+        find_tool = self._agent.toolset.find("FindInContext")
+        if isinstance(find_tool, FindInContext):
+            find_tool.bind(self._context)
+
     def _ensure_plan_session_id(self) -> None:
         """Allocate a stable plan session ID on first activation."""
         if self._plan_session_id is None:
@@ -536,6 +627,38 @@ class KimiSoul:
 
     async def _checkpoint(self):
         await self._context.checkpoint(self._checkpoint_with_user_message)
+        if self.is_root and self._runtime.file_journal is not None:
+            self._runtime.file_journal.begin_checkpoint(self._context.n_checkpoints - 1)
+
+    async def revert_to_checkpoint(self, checkpoint_id: int) -> None:
+        """
+        Revert the context to the specified checkpoint, like a D-Mail without messages, and
+        checkpoint again so that the next turn starts from a checkpoint. Files are not restored.
+
+        Raises:
+            ValueError: When the checkpoint does not exist.
+        """
+        await self._context.revert_to(checkpoint_id)
+        self._last_tool_calls = []
+        await self._checkpoint()
+
+    async def load_snapshot(self, path: Path) -> None:
+        """
+        Replace the context with a snapshot saved by `Context.save_snapshot`, and checkpoint
+        so that the next turn starts from a checkpoint. Files are not restored.
+
+        The current context is checkpointed first, so that the context file rotated away by the
+        load ends at a checkpoint, like the context files rotated away by reverts.
+
+        Raises:
+            FileNotFoundError: When the snapshot does not exist.
+        """
+        if not path.is_file():
+            raise FileNotFoundError(f"Context snapshot not found: {path}")
+        await self._checkpoint()
+        await self._context.load_snapshot(path)
+        self._last_tool_calls = []
+        await self._checkpoint()
 
     def steer(self, content: str | list[ContentPart]) -> None:
         """Queue a steer message for injection into the current turn."""
@@ -563,12 +686,91 @@ class KimiSoul:
             list[ContentPart],
             [TextPart(text=content)] if isinstance(content, str) else list(content),
         )
-        message = Message(role="user", content=parts)
+        message = await self._check_capabilities(Message(role="user", content=parts))
+        await self._context.append_message(message)
+
+    async def _check_capabilities(self, message: Message) -> Message:
+        """
+        Check that the current model supports the content of `message`, downgrading the media
+        it cannot see according to `on_unsupported_media`.
+
+        Raises:
+            LLMNotSet: When the LLM is not set.
+            LLMNotSupported: When the message still needs capabilities the model lacks.
+        """
         if self._runtime.llm is None:
             raise LLMNotSet()
-        if missing_caps := check_message(message, self._runtime.llm.capabilities):
-            raise LLMNotSupported(self._runtime.llm, list(missing_caps))
-        await self._context.append_message(message)
+        llm = self._runtime.llm
+        if not (missing_caps := check_message(message, llm.capabilities)):
+            return message
+
+        mode = self._runtime.config.on_unsupported_media
+        if mode != "error":
+            vision_llm = self._get_vision_llm() if mode == "describe" else None
+            message, downgraded = await downgrade_media(
+                message, llm.capabilities, vision_llm=vision_llm
+            )
+            if downgraded:
+                self._notify_media_downgraded(downgraded)
+            missing_caps = check_message(message, llm.capabilities)
+        if missing_caps:
+            logger.warning("Message requires unsupported capabilities: {caps}", caps=missing_caps)
+            raise LLMNotSupported(llm, list(missing_caps))
+        return message
+
+    def _get_vision_llm(self) -> LLM | None:
+        config = self._runtime.config
+        if config.vision_model is None:
+            return None
+        if self._vision_llm is None:
+            try:
+                self._vision_llm = clone_llm_with_model_alias(
+                    self._runtime.llm,
+                    config,
+                    config.vision_model,
+                    session_id=self._runtime.session.id,
+                    oauth=self._runtime.oauth,
+                )
+            except Exception as e:
+                logger.warning("Failed to create the vision model: {error}", error=e)
+        return self._vision_llm
+
+    def _notify_media_downgraded(self, downgraded: list[DowngradedMedia]) -> None:
+        described = sum(media.described for media in downgraded)
+        if described == len(downgraded):
+            title = f"The model saw a description of {len(downgraded)} attachment(s)"
+        else:
+            title = f"The model could not see {len(downgraded)} attachment(s)"
+        notifications = self._runtime.notifications
+        notifications.publish(
+            NotificationEvent(
+                id=notifications.new_id(),
+                category="system",
+                type="media_downgraded",
+                source_kind="media",
+                source_id=self._runtime.session.id,
+                title=title,
+                body="\n".join(
+                    f"- {media.label()}: {'described' if media.described else 'removed'}"
+                    for media in downgraded
+                ),
+                severity="warning",
+                payload={
+                    "mode": self._runtime.config.on_unsupported_media,
+                    "media": [
+                        {
+                            "kind": media.kind,
+                            "name": media.name,
+                            "mime_type": media.mime_type,
+                            "size": media.size,
+                            "described": media.described,
+                        }
+                        for media in downgraded
+                    ],
+                },
+                targets=["wire", "shell"],
+            )
+        )
 
     @property
     def available_slash_commands(self) -> list[SlashCommand[Any]]:
@@ -587,6 +789,7 @@ class KimiSoul:
         if get_current_approval_source_or_none() is None:
             created_approval_source = ApprovalSource(kind="foreground_turn", id=uuid.uuid4().hex)
             approval_source_token = set_current_approval_source(created_approval_source)
+        approval_subscription = self._observe_approval_requests()
         try:
             # Refresh OAuth tokens on each turn to avoid idle-time expirations.
             await self._runtime.oauth.ensure_fresh(self._runtime)
@@ -596,6 +799,21 @@ class KimiSoul:
 
             set_session_id(self._runtime.session.id)
 
+            # Reject malformed or oversized images before the turn reaches the model. Images
+            # the model cannot see are left to `_turn`, which fails or downgrades them
+            # according to `on_unsupported_media`.
+            try:
+                user_input = await asyncio.to_thread(
+                    prepare_user_input, user_input, self._runtime.config.image_input
+                )
+            except InvalidUserInput as e:
+                self._send_turn_begin(user_input)
+                turn_started = True
+                wire_send(TextPart(text=str(e)))
+                self._send_turn_end(TurnEnd())
+                turn_finished = True
+                return
+
             from kimi_cli.hooks import events
 
             # --- UserPromptSubmit hook ---
@@ -618,20 +836,21 @@ class KimiSoul:
                 )
                 for result in hook_results:
                     if result.action == "block":
-                        wire_send(TurnBegin(user_input=user_input))
+                        self._send_turn_begin(user_input)
                         turn_started = True
                         wire_send(TextPart(text=result.reason or "Prompt blocked by hook."))
-                        wire_send(TurnEnd())
+                        self._send_turn_end(TurnEnd())
                         turn_finished = True
                         return
 
-            wire_send(TurnBegin(user_input=user_input))
+            self._send_turn_begin(user_input)
             turn_started = True
             from kimi_cli.telemetry import track as _track_telemetry
 
             _track_telemetry("turn_started", mode="plan" if self._plan_mode else "agent")
             user_message = Message(role="user", content=user_input)
             text_input = user_message.extract_text(" ").strip()
+            outcome: TurnOutcome | None = None
 
             if command_call := parse_slash_command_call(text_input):
                 command = self._find_slash_command(command_call.name)
@@ -643,13 +862,23 @@ class KimiSoul:
                     if isinstance(ret, Awaitable):
                         await ret
             elif self._loop_control.max_ralph_iterations != 0:
+                success_command = self._loop_control.ralph_success_command
                 runner = FlowRunner.ralph_loop(
                     user_message,
                     self._loop_control.max_ralph_iterations,
+                    success_check=(
+                        SuccessCheck(
+                            success_command,
+                            self._runtime.environment.shell_path,
+                            self._loop_control.ralph_failure_limit,
+                        )
+                        if success_command
+                        else None
+                    ),
                 )
                 await runner.run(self, "")
             else:
-                await self._turn(user_message)
+                outcome = await self._turn(user_message)
 
             # --- Stop hook (max 1 re-trigger to prevent infinite loop) ---
             if not self._stop_hook_active:
@@ -665,12 +894,12 @@ class KimiSoul:
                     if result.action == "block" and result.reason:
                         self._stop_hook_active = True
                         try:
-                            await self._turn(Message(role="user", content=result.reason))
+                            outcome = await self._turn(Message(role="user", content=result.reason))
                         finally:
                             self._stop_hook_active = False
                         break
 
-            wire_send(TurnEnd())
+            self._send_turn_end(self._turn_end(outcome))
             turn_finished = True
 
             # Auto-set title after first real turn (skip slash commands)
@@ -698,7 +927,7 @@ class KimiSoul:
                         session.state.custom_title = fresh.custom_title
         finally:
             if turn_started and not turn_finished:
-                wire_send(TurnEnd())
+                self._send_turn_end(TurnEnd())
                 from kimi_cli.telemetry import track as _track_telemetry
 
                 _track_telemetry(
@@ -713,20 +942,78 @@ class KimiSoul:
                 )
             if approval_source_token is not None:
                 reset_current_approval_source(approval_source_token)
+            if approval_subscription is not None and self._runtime.approval_runtime is not None:
+                self._runtime.approval_runtime.unsubscribe(approval_subscription)
+
+    def _observe_approval_requests(self) -> str | None:
+        """Forward the approval requests of the current turn and of its foreground subagents to
+        the observers, if any. Returns the token to unsubscribe with at the end of the turn."""
+        approval_runtime = self._runtime.approval_runtime
+        if not self._observers or approval_runtime is None:
+            return None
 
-    async def _turn(self, user_message: Message) -> TurnOutcome:
-        if self._runtime.llm is None:
-            raise LLMNotSet()
+        def _on_approval_event(event: ApprovalRuntimeEvent) -> None:
+            if event.kind == "request_created" and event.request.source.kind == "foreground_turn":
+                self._observe(lambda observer: observer.on_approval_request(event.request))
+
+        return approval_runtime.subscribe(_on_approval_event)
 
-        if missing_caps := check_message(user_message, self._runtime.llm.capabilities):
-            raise LLMNotSupported(self._runtime.llm, list(missing_caps))
+    async def _turn(self, user_message: Message) -> TurnOutcome:
+        user_message = await self._check_capabilities(user_message)
 
         self._current_turn_id = uuid.uuid4().hex
         self._last_tool_calls = []
+        turn_summary = self._runtime.turn_summary if self._runtime.role == "root" else None
+        if self._runtime.role == "root":
+            self._runtime.usage.begin_turn()
+            self._runtime.stats.record_turn()
+        if turn_summary is not None:
+            turn_summary.begin_turn()
         await self._checkpoint()  # this creates the checkpoint 0 on first run
         await self._context.append_message(user_message)
         logger.debug("Appended user message to context")
-        return await self._agent_loop()
+        if turn_summary is None:
+            return await self._agent_loop()
+
+        try:
+            outcome = await self._agent_loop()
+        except BaseException as e:
+            await turn_summary.end_turn(
+                session_id=self._runtime.session.id,
+                stop_reason=failure_stop_reason(e),
+                step_count=getattr(self, "_current_step_no", 0),
+                usage=self._runtime.usage.turn,
+                final_message=None,
+            )
+            raise
+        await turn_summary.end_turn(
+            session_id=self._runtime.session.id,
+            stop_reason=outcome.stop_reason,
+            step_count=outcome.step_count,
+            usage=self._runtime.usage.turn,
+            final_message=(
+                outcome.final_message.extract_text() if outcome.final_message is not None else None
+            ),
+        )
+        return outcome
+
+    def _turn_end(self, outcome: TurnOutcome | None) -> TurnEnd:
+        """Build the `TurnEnd` event, reporting the todos a completed turn left unfinished."""
+        if outcome is None or outcome.stop_reason != "no_tool_calls":
+            return TurnEnd()
+        unfinished = self._unfinished_todos()
+        if not unfinished:
+            return TurnEnd()
+        logger.warning("Turn ended with {n} unfinished todo item(s)", n=len(unfinished))
+        return TurnEnd(
+            todos_remaining=len(unfinished),
+            todos=[TodoDisplayItem(title=todo.title, status=todo.status) for todo in unfinished],
+        )
+
+    def _unfinished_todos(self) -> list[Todo]:
+        from kimi_cli.tools.todo import load_todos, unfinished_todos
+
+        return unfinished_todos(load_todos(self._runtime))
 
     def _build_slash_commands(self) -> list[SlashCommand[Any]]:
         commands: list[SlashCommand[Any]] = list(soul_slash_registry.list_commands())
@@ -803,8 +1090,10 @@ class KimiSoul:
                     TextPart(text=f'Failed to load skill "/{SKILL_COMMAND_PREFIX}{_skill.name}".')
                 )
                 return
-            extra = args.strip()
-            if extra:
+            rendered = render_skill_args(skill_text, args)
+            if rendered is not None:
+                skill_text = rendered
+            elif extra := args.strip():
                 skill_text = f"{skill_text}\n\nUser request:\n{extra}"
             await soul._turn(Message(role="user", content=skill_text))
 
@@ -824,6 +1113,7 @@ class KimiSoul:
                e. Step Execution     - run _step() (LLM call + tool execution).
                f. Error Handling     - BackToTheFuture (revert) or fatal exception.
                g. Outcome Resolution - steers / stop / continue.
+               h. Budget Guard       - stop when the turn exceeds its token or cost budget.
             3. Turn Resolution       - return TurnOutcome to the caller.
         """
         assert self._runtime.llm is not None
@@ -882,9 +1172,11 @@ class KimiSoul:
                 raise MaxStepsReached(self._loop_control.max_steps_per_turn)
 
             self._current_step_no = step_no
+            self._runtime.stats.record_step()
 
             # ── 2b. Step Begin ──────────────────────────────────────────────────
-            wire_send(StepBegin(n=step_no))
+            wire_send(StepBegin(n=step_no, timestamp=time.time()))
+            self._observe(lambda observer: observer.on_step_begin(step_no))
             back_to_the_future: BackToTheFuture | None = None
             step_outcome: StepOutcome | None = None
 
@@ -989,15 +1281,88 @@ class KimiSoul:
                 )
 
             if back_to_the_future is not None:
-                # Revert context to the checkpoint and inject D-Mail message.
+                # Revert context (and files, if requested) to the checkpoint and inject
+                # D-Mail message.
                 await self._context.revert_to(back_to_the_future.checkpoint_id)
+                restore_message = await self._restore_files(back_to_the_future)
                 self._last_tool_calls = []
                 await self._checkpoint()
                 await self._context.append_message(back_to_the_future.messages)
+                if restore_message is not None:
+                    await self._context.append_message(restore_message)
 
             # Consume any pending steers between steps before next iteration.
             await self._consume_pending_steers()
 
+            # ── 2h. Budget Guard ────────────────────────────────────────────────
+            if self.is_root and (exceeded := self._turn_budget_exceeded()) is not None:
+                await self._context.append_message(
+                    Message(
+                        role="user",
+                        content=[
+                            system_reminder(
+                                f"{exceeded}. The turn was stopped before finishing the task. "
+                                "If the user asks to continue, pick up where you left off."
+                            )
+                        ],
+                    )
+                )
+                raise exceeded
+
+    def _turn_budget_exceeded(self) -> BudgetExceeded | None:
+        turn = self._runtime.usage.turn
+        tokens = turn.input + turn.output
+        max_tokens = self._loop_control.max_tokens_per_turn
+        if max_tokens is not None and tokens > max_tokens:
+            return BudgetExceeded("tokens", tokens=tokens, cost=turn.cost, limit=max_tokens)
+        # Only calls on models with pricing count towards the cost.
+        max_cost = self._loop_control.max_cost_per_turn
+        if max_cost is not None and turn.cost > max_cost:
+            return BudgetExceeded("cost", tokens=tokens, cost=turn.cost, limit=max_cost)
+        return None
+
+    async def _restore_files(self, back_to_the_future: BackToTheFuture) -> Message | None:
+        """
+        Restore the files journaled since the D-Mail's checkpoint, if it asked for it, and
+        notify the user. Returns a message telling the model which files were restored.
+        """
+        journal = self._runtime.file_journal
+        if journal is None or not back_to_the_future.restore_files:
+            return None
+        checkpoint_id = back_to_the_future.checkpoint_id
+        restored = await journal.restore(checkpoint_id)
+        if not restored:
+            return None
+        logger.info(
+            "Restored {n} file(s) to checkpoint {checkpoint_id}",
+            n=len(restored),
+            checkpoint_id=checkpoint_id,
+        )
+        notifications = self._runtime.notifications
+        notifications.publish(
+            NotificationEvent(
+                id=notifications.new_id(),
+                category="system",
+                type="files_restored",
+                source_kind="file_journal",
+                source_id=str(checkpoint_id),
+                title=f"Restored {len(restored)} file(s) to checkpoint {checkpoint_id}",
+                body="\n".join(restored),
+                payload={"checkpoint_id": checkpoint_id, "paths": restored},
+                targets=["wire", "shell"],
+            )
+        )
+        return Message(
+            role="user",
+            content=[
+                system(
+                    "The following files were restored to their content at this checkpoint, "
+                    "undoing the changes your future self made to them:\n"
+                    + "\n".join(f"- {path}" for path in restored)
+                )
+            ],
+        )
+
     async def _step(self) -> StepOutcome | None:
         """Run a single step and return a stop outcome, or None to continue.
 
@@ -1018,6 +1383,8 @@ class KimiSoul:
         # already checked in `run`
         assert self._runtime.llm is not None
         chat_provider = self._runtime.llm.chat_provider
+        rate_limiter = get_rate_limiter(self._runtime.llm, self._runtime.config.llm.rate_limit)
+        rate_limit_reported = False
 
         # ═══════════════════════════════════════════════════════════════════════
         # 2e.1. NOTIFICATION DELIVERY (root role only)
@@ -1069,32 +1436,101 @@ class KimiSoul:
         # ═══════════════════════════════════════════════════════════════════════
         # 2e.3. HISTORY NORMALIZATION
         # ═══════════════════════════════════════════════════════════════════════
+        self._context.validate_and_repair()
         effective_history = normalize_history(self._context.history)
 
         # ═══════════════════════════════════════════════════════════════════════
         # 2e.4. LLM CALL WITH RETRY
         # ═══════════════════════════════════════════════════════════════════════
+        # Parts streamed by the current attempt. If the stream fails midway, they are sent back
+        # as a partial assistant message so that the provider can continue from where it stopped.
+        streamed_parts: list[StreamedMessagePart] = []
+        streamed_chars = 0
+        max_message_chars = self._loop_control.max_message_chars
+        thinking = _ThinkingForwarder(self._runtime.stream_thinking)
+        phases = _PhaseTracker(self._current_step_no, self._loop_control.slow_phase_threshold_s)
+
+        def _on_message_part(part: StreamedMessagePart) -> None:
+            nonlocal streamed_chars
+            streamed_chars += _part_size(part)
+            if streamed_chars > max_message_chars:
+                # A `ChatProviderError` makes kosong cancel the tool calls already dispatched.
+                raise MaxMessageSizeExceeded(max_message_chars)
+            thinking.forward(part)
+            streamed_parts.append(part.model_copy(deep=True))
+
+        redact_wire = self._runtime.config.redaction.redact_wire
+
+        def _on_tool_call(tool_call: ToolCall) -> None:
+            self._observe(lambda observer: observer.on_tool_call(tool_call))
+
+        def _on_tool_result(result: ToolResult) -> None:
+            wire_send(redact_tool_result(result) if redact_wire else result)
+            self._observe(lambda observer: observer.on_tool_result(result))
+
         async def _run_step_once() -> StepResult:
             """Single LLM invocation (wrapped by retry + connection recovery)."""
             # ── 2e.4.1. Toolset begin_step ────────────────────────────────────
             if isinstance(self._agent.toolset, KimiToolset):
                 self._agent.toolset.begin_step(self._last_tool_calls)
-            # ── 2e.4.2. kosong.step ───────────────────────────────────────────
+            # ── 2e.4.2. Partial continuation ──────────────────────────────────
+            nonlocal streamed_chars
+            prefix = _partial_prefix(chat_provider, streamed_parts)
+            streamed_parts.clear()
+            streamed_chars = 0
+            thinking.reset()
+            history = effective_history
+            if prefix is not None:
+                logger.info(
+                    "Continuing the interrupted response from {n} streamed part(s)",
+                    n=len(prefix.content),
+                )
+                history = [*effective_history, prefix]
+                # UIs drop the failed attempt on `StepRetry`, so replay what is being kept.
+                for part in prefix.content:
+                    _on_message_part(part.model_copy(deep=True))
+            # ── 2e.4.3. kosong.step ───────────────────────────────────────────
             # run an LLM step (may be interrupted)
-            return await kosong.step(
+            options = self._runtime.generate_options
+            if self._tool_choice != "auto":
+                options = (options or GenerateOptions()).merge(
+                    GenerateOptions(tool_choice=self._tool_choice)
+                )
+            phases.enter("generating")
+            nonlocal rate_limit_reported
+            if rate_limiter.would_wait():
+                rate_limit_reported = True
+                wire_send(StatusUpdate(rate_limit=_rate_limit_status(rate_limiter, waiting=True)))
+            result = await kosong.step(
                 chat_provider,
                 self._agent.system_prompt,
                 self._agent.toolset,
-                effective_history,
-                on_message_part=wire_send,
-                on_tool_result=wire_send,
+                history,
+                on_message_part=_on_message_part,
+                on_tool_call=_on_tool_call,
+                on_tool_result=_on_tool_result,
+                options=options,
+                tool_call_preview_interval=TOOL_CALL_PREVIEW_INTERVAL,
+                rate_limiter=rate_limiter,
+            )
+            if prefix is None:
+                return result
+            return replace(
+                result,
+                message=result.message.model_copy(
+                    update={
+                        "content": merge_content_parts([*prefix.content, *result.message.content])
+                    }
+                ),
             )
 
         max_attempts = self._loop_control.max_retries_per_step
 
         def _before_step_retry_sleep(retry_state: RetryCallState) -> None:
             self._retry_log("step", retry_state)
+            self._runtime.stats.record_retry()
             self._emit_step_retry(retry_state, max_attempts=max_attempts)
+            phases.enter("retrying", attempt=retry_state.attempt_number + 1)
 
         @tenacity.retry(
             retry=retry_if_exception(self._is_retryable_error),
@@ -1123,22 +1559,43 @@ class KimiSoul:
                 _ctx["input_tokens"] = self._context.token_count
             _step_exc._kimi_api_error_context = _ctx  # type: ignore[attr-defined]
             raise
+        finally:
+            phases.close()
+        # A step that only streamed reasoning has not sent its summary yet
+        thinking.flush()
 
         # ═══════════════════════════════════════════════════════════════════════
         # 2e.5. USAGE & STATUS UPDATE
         # ═══════════════════════════════════════════════════════════════════════
         llm_elapsed = time.monotonic() - t0
         usage = result.usage
+        metrics = result.metrics
         logger.info(
-            "LLM step completed in {elapsed:.1f}s (input={input_tokens}, output={output_tokens})",
+            "LLM step completed in {elapsed:.1f}s (input={input_tokens}, output={output_tokens}, "
+            "first part after {ttfp}, {tps} tokens/s)",
             elapsed=llm_elapsed,
             input_tokens=usage.input if usage else "?",
             output_tokens=usage.output if usage else "?",
+            ttfp=f"{metrics.time_to_first_part:.2f}s" if metrics else "?",
+            tps=(
+                f"{metrics.output_tokens_per_second:.1f}"
+                if metrics and metrics.output_tokens_per_second is not None
+                else "?"
+            ),
         )
         status_update = StatusUpdate(
             token_usage=usage, message_id=result.id, plan_mode=self._plan_mode
         )
+        if metrics is not None:
+            status_update.step_metrics = _step_metrics(metrics)
+        if rate_limit_reported:
+            status_update.rate_limit = _rate_limit_status(rate_limiter, waiting=False)
         if usage is not None:
+            self._runtime.usage.record(usage, self._runtime.llm.pricing)
+            status_update.session_usage = self._runtime.usage.session.to_token_usage()
+            status_update.session_compaction_usage = (
+                self._runtime.usage.compaction.to_token_usage()
+            )
             # mark the token count for the context before the step
             await self._context.update_token_count(usage.input)
             snap = self.status
@@ -1152,8 +1609,15 @@ class KimiSoul:
         # ═══════════════════════════════════════════════════════════════════════
         # wait for all tool results (may be interrupted)
         plan_mode_before_tools = self._plan_mode
-        results = await result.tool_results()
+        if result.tool_calls:
+            phases.enter("running_tools")
+        try:
+            results = await result.tool_results()
+        finally:
+            phases.close()
         logger.debug("Got tool results: {results}", results=results)
+        if self._runtime.turn_summary is not None:
+            self._runtime.turn_summary.record(results)
 
         # Update dedup tracking for the next step
         if isinstance(self._agent.toolset, KimiToolset):
@@ -1169,6 +1633,7 @@ class KimiSoul:
         # ═══════════════════════════════════════════════════════════════════════
         # shield the context manipulation from interruption
         await asyncio.shield(self._grow_context(result, results))
+        self._observe(lambda observer: observer.on_step_end(self._current_step_no, result, results))
 
         # ═══════════════════════════════════════════════════════════════════════
         # 2e.8. OUTCOME RESOLUTION
@@ -1194,21 +1659,8 @@ class KimiSoul:
             # raise to let the main loop take us back to the future
             raise BackToTheFuture(
                 dmail.checkpoint_id,
-                [
-                    Message(
-                        role="user",
-                        content=[
-                            system(
-                                "You just got a D-Mail from your future self. "
-                                "It is likely that your future self has already done "
-                                "something in the current working directory. Please read "
-                                "the D-Mail and decide what to do next. You MUST NEVER "
-                                "mention to the user about this information. "
-                                f"D-Mail content:\n\n{dmail.message.strip()}"
-                            )
-                        ],
-                    )
-                ],
+                [dmail_message(dmail.message)],
+                restore_files=dmail.restore_files,
             )
 
         if isinstance(self._agent.toolset, KimiToolset) and self._agent.toolset.force_stop_turn:
@@ -1225,14 +1677,10 @@ class KimiSoul:
         logger.debug("Growing context with result: {result}", result=result)
 
         assert self._runtime.llm is not None
-        tool_messages = [tool_result_to_message(tr) for tr in tool_results]
-        for tm in tool_messages:
-            if missing_caps := check_message(tm, self._runtime.llm.capabilities):
-                logger.warning(
-                    "Tool result message requires unsupported capabilities: {caps}",
-                    caps=missing_caps,
-                )
-                raise LLMNotSupported(self._runtime.llm, list(missing_caps))
+        tool_messages = [
+            await self._check_capabilities(redact_message(tool_result_to_message(tr)))
+            for tr in tool_results
+        ]
 
         await self._context.append_message(result.message)
         if result.usage is not None:
@@ -1265,12 +1713,26 @@ class KimiSoul:
         """
 
         chat_provider = self._runtime.llm.chat_provider if self._runtime.llm is not None else None
+        attempt = 0
+        stream_thinking = self._runtime.stream_thinking == "full"
+
+        def _on_summary_part(part: StreamedMessagePart) -> None:
+            # Stream the summary so that a long compaction does not look stalled
+            if isinstance(part, TextPart) and part.text:
+                wire_send(CompactionProgress(text=part.text, attempt=attempt))
+            elif isinstance(part, ThinkPart) and part.think and stream_thinking:
+                wire_send(CompactionProgress(text=part.think, think=True, attempt=attempt))
 
         async def _run_compaction_once() -> CompactionResult:
+            nonlocal attempt
             if self._runtime.llm is None:
                 raise LLMNotSet()
+            attempt += 1
             return await self._compaction.compact(
-                self._context.history, self._runtime.llm, custom_instruction=custom_instruction
+                self._context.history,
+                self._runtime.llm,
+                custom_instruction=custom_instruction,
+                on_message_part=_on_summary_part,
             )
 
         start_time = time.monotonic()
@@ -1280,6 +1742,7 @@ class KimiSoul:
             nonlocal retry_count
             retry_count = retry_state.attempt_number
             self._retry_log("compaction", retry_state)
+            self._runtime.stats.record_retry()
 
         @tenacity.retry(
             retry=retry_if_exception(self._is_retryable_error),
@@ -1333,7 +1796,7 @@ class KimiSoul:
         await self._context.clear()
         await self._context.write_system_prompt(self._agent.system_prompt)
         await self._checkpoint()
-        await self._context.append_message(compaction_result.messages)
+        compacted_messages = list(compaction_result.messages)
         estimated_token_count = compaction_result.estimated_token_count
 
         if self.is_root:
@@ -1349,9 +1812,18 @@ class KimiSoul:
                         TextPart(text=active_task_snapshot),
                     ],
                 )
-                await self._context.append_message(active_task_message)
+                compacted_messages.append(active_task_message)
                 estimated_token_count += estimate_text_tokens([active_task_message])
 
+        # Mark where the summary begins, so that UIs and exports can show the boundary.
+        marker = await self._context.write_compaction_marker(
+            id=uuid.uuid4().hex[:8],
+            n_summarized=compaction_result.n_summarized,
+            before_tokens=before_tokens,
+            after_tokens=estimated_token_count,
+        )
+        await self._context.append_message(compacted_messages)
+
         # Estimate token count so context_usage is not reported as 0%
         await self._context.update_token_count(estimated_token_count)
 
@@ -1361,7 +1833,14 @@ class KimiSoul:
         # not affected by a buggy provider.
         await self._notify_injection_providers_compacted()
 
-        wire_send(CompactionEnd())
+        wire_send(
+            CompactionEnd(
+                marker_id=marker.id,
+                n_summarized=marker.n_summarized,
+                before_tokens=marker.before_tokens,
+                after_tokens=marker.after_tokens,
+            )
+        )
 
         from kimi_cli.telemetry import track
 
@@ -1374,6 +1853,16 @@ class KimiSoul:
             retry_count=retry_count,
         )
         if compaction_result.usage is not None:
+            if self._runtime.llm is not None:
+                self._runtime.usage.record(
+                    compaction_result.usage, self._runtime.llm.pricing, compaction=True
+                )
+                wire_send(
+                    StatusUpdate(
+                        session_usage=self._runtime.usage.session.to_token_usage(),
+                        session_compaction_usage=self._runtime.usage.compaction.to_token_usage(),
+                    )
+                )
             track_kwargs["llm_input_tokens"] = compaction_result.usage.input
             track_kwargs["llm_output_tokens"] = compaction_result.usage.output
         track("compaction_finished", **track_kwargs)
@@ -1522,15 +2011,155 @@ class KimiSoul:
         )
 
 
+def _rate_limit_status(limiter: RateLimiter, *, waiting: bool) -> RateLimitStatus:
+    state = limiter.state()
+    return RateLimitStatus(
+        waiting=waiting,
+        queued=state.queued + 1 if waiting else state.queued,
+        in_flight=state.in_flight,
+        concurrency_limit=state.concurrency_limit,
+        cooldown_remaining_ms=int(state.cooldown_remaining * 1000),
+    )
+
+
+def _step_metrics(metrics: GenerationMetrics) -> StepMetrics:
+    return StepMetrics(
+        time_to_first_part_ms=int(metrics.time_to_first_part * 1000),
+        duration_ms=int(metrics.duration * 1000),
+        part_count=metrics.part_count,
+        output_tokens_per_second=metrics.output_tokens_per_second,
+    )
+
+
+def _partial_prefix(
+    chat_provider: ChatProvider, parts: Sequence[StreamedMessagePart]
+) -> Message | None:
+    """
+    Build the partial assistant message to continue from, or None if the step should be
+    regenerated from scratch.
+
+    Continuation is only attempted when the provider supports it and the interrupted attempt
+    streamed some text but no tool calls, since a half-streamed tool call cannot be resumed.
+    """
+    if not parts:
+        return None
+    if not isinstance(chat_provider, PartialContinuationChatProvider):
+        return None
+    if not chat_provider.supports_partial_continuation():
+        return None
+    content: list[ContentPart] = []
+    for part in parts:
+        if isinstance(part, ToolCall | ToolCallPart | ToolCallPreview):
+            return None
+        content.append(part)
+    content = merge_content_parts(content)
+    if not any(isinstance(part, TextPart) and part.text for part in content):
+        return None
+    return Message(role="assistant", content=content, partial=True)
+
+
+def _part_size(part: StreamedMessagePart) -> int:
+    """The number of characters a streamed part adds to the assistant message."""
+    match part:
+        case TextPart(text=text):
+            return len(text)
+        case ThinkPart(think=think):
+            return len(think)
+        case ToolCall(function=function):
+            return len(function.name) + len(function.arguments or "")
+        case ToolCallPart(arguments_part=arguments_part):
+            return len(arguments_part or "")
+        case _:
+            return 0
+
+
+class _ThinkingForwarder:
+    """
+    Forwards the parts streamed in a step to the wire, applying the `stream_thinking` mode to
+    think parts. Other parts are always forwarded.
+    """
+
+    def __init__(self, mode: StreamThinking):
+        self._mode: StreamThinking = mode
+        self._summary = ""
+        self._summary_sent = False
+
+    def reset(self) -> None:
+        """Start over for a new attempt of the step."""
+        self._summary = ""
+        self._summary_sent = False
+
+    def forward(self, part: StreamedMessagePart) -> None:
+        if not isinstance(part, ThinkPart) or self._mode == "full":
+            self.flush()
+            wire_send(part)
+            return
+        if self._mode == "off" or self._summary_sent:
+            return
+        self._summary += part.think
+        if len(self._summary) >= THINKING_SUMMARY_CHARS:
+            self.flush()
+
+    def flush(self) -> None:
+        """Send the summary collected so far, if any. Only one summary is sent per step."""
+        if self._summary_sent or not self._summary:
+            return
+        self._summary_sent = True
+        wire_send(ThinkPart(think=self._summary[:THINKING_SUMMARY_CHARS]))
+
+
+class _PhaseTracker:
+    """
+    Sends the phases of a step as `StatusUpdate`s. A phase that lasts longer than the
+    threshold is reported once more, with its elapsed time, and logged as a warning.
+    """
+
+    def __init__(self, step_no: int, threshold_s: float):
+        self._step_no = step_no
+        self._threshold_s = threshold_s
+        self._watchdog: asyncio.Task[None] | None = None
+
+    def enter(self, phase: StepPhase, *, attempt: int | None = None) -> None:
+        self.close()
+        wire_send(StatusUpdate(phase=phase, attempt=attempt))
+        if self._threshold_s > 0:
+            self._watchdog = asyncio.create_task(self._watch(phase, attempt))
+
+    def close(self) -> None:
+        """End the current phase, if any."""
+        if self._watchdog is not None:
+            self._watchdog.cancel()
+            self._watchdog = None
+
+    async def _watch(self, phase: StepPhase, attempt: int | None) -> None:
+        started = time.monotonic()
+        await asyncio.sleep(self._threshold_s)
+        elapsed = time.monotonic() - started
+        logger.warning(
+            "Step {n} is slow: {phase} for {elapsed:.1f}s",
+            n=self._step_no,
+            phase=phase,
+            elapsed=elapsed,
+        )
+        wire_send(StatusUpdate(phase=phase, attempt=attempt, elapsed_ms=int(elapsed * 1000)))
+
+
 class BackToTheFuture(Exception):
     """
     Raise when we need to revert the context to a previous checkpoint.
     The main agent loop should catch this exception and handle it.
     """
 
-    def __init__(self, checkpoint_id: int, messages: Sequence[Message]):
+    def __init__(
+        self,
+        checkpoint_id: int,
+        messages: Sequence[Message],
+        *,
+        restore_files: bool = False,
+    ):
         self.checkpoint_id = checkpoint_id
         self.messages = messages
+        self.restore_files = restore_files
 
 
 class FlowRunner:
@@ -1540,15 +2169,18 @@ class FlowRunner:
         *,
         name: str | None = None,
         max_moves: int = DEFAULT_MAX_FLOW_MOVES,
+        success_check: SuccessCheck | None = None,
     ) -> None:
         self._flow = flow
         self._name = name
         self._max_moves = max_moves
+        self._success_check = success_check
 
     @staticmethod
     def ralph_loop(
         user_message: Message,
         max_ralph_iterations: int,
+        success_check: SuccessCheck | None = None,
     ) -> FlowRunner:
         prompt_content = list(user_message.content)
         prompt_text = Message(role="user", content=prompt_content).extract_text(" ").strip()
@@ -1563,6 +2195,26 @@ class FlowRunner:
         outgoing: dict[str, list[FlowEdge]] = {"BEGIN": [], "END": []}
 
         nodes["R1"] = FlowNode(id="R1", label=prompt_content, kind="task")
+        outgoing["R1"] = []
+        outgoing["R2"] = []
+        outgoing["BEGIN"].append(FlowEdge(src="BEGIN", dst="R1", label=None))
+        outgoing["R1"].append(FlowEdge(src="R1", dst="R2", label=None))
+
+        if success_check is not None:
+            # The success command decides when to stop, so the agent is not asked to choose
+            nodes["R2"] = FlowNode(
+                id="R2",
+                label=(
+                    f"{prompt_text}. (You are running in an automated loop where the same "
+                    f"prompt is fed repeatedly until `{success_check.command}` succeeds. "
+                    "Keep working on the task until it does.)"
+                ).strip(),
+                kind="task",
+            )
+            outgoing["R2"].append(FlowEdge(src="R2", dst="R2", label=None))
+            flow = Flow(nodes=nodes, outgoing=outgoing, begin_id="BEGIN", end_id="END")
+            return FlowRunner(flow, max_moves=total_runs, success_check=success_check)
+
         nodes["R2"] = FlowNode(
             id="R2",
             label=(
@@ -1573,11 +2225,6 @@ class FlowRunner:
             ).strip(),
             kind="decision",
         )
-        outgoing["R1"] = []
-        outgoing["R2"] = []
-
-        outgoing["BEGIN"].append(FlowEdge(src="BEGIN", dst="R1", label=None))
-        outgoing["R1"].append(FlowEdge(src="R1", dst="R2", label=None))
         outgoing["R2"].append(FlowEdge(src="R2", dst="R2", label="CONTINUE"))
         outgoing["R2"].append(FlowEdge(src="R2", dst="END", label="STOP"))
 
@@ -1598,6 +2245,7 @@ class FlowRunner:
         current_id = self._flow.begin_id
         moves = 0
         total_steps = 0
+        note: str | None = None
         while True:
             node = self._flow.nodes[current_id]
             edges = self._flow.outgoing.get(current_id, [])
@@ -1618,18 +2266,37 @@ class FlowRunner:
 
             if moves >= self._max_moves:
                 raise MaxStepsReached(total_steps)
-            next_id, steps_used = await self._execute_flow_node(soul, node, edges)
+            next_id, steps_used = await self._execute_flow_node(soul, node, edges, note)
             total_steps += steps_used
             if next_id is None:
                 return
             moves += 1
             current_id = next_id
 
+            notes: list[str] = []
+            if self._success_check is not None:
+                check = await self._success_check.run()
+                if check.passed:
+                    logger.info("Agent flow stopped: success command passed.")
+                    current_id = self._flow.end_id
+                elif check.stalled:
+                    logger.warning(
+                        "Agent flow stopped: success command output did not change "
+                        "for {n} iterations.",
+                        n=self._success_check.failure_limit,
+                    )
+                    current_id = self._flow.end_id
+                notes.append(check.note)
+            if todos_note := self._todos_note(soul):
+                notes.append(todos_note)
+            note = "\n\n".join(notes) or None
+
     async def _execute_flow_node(
         self,
         soul: KimiSoul,
         node: FlowNode,
         edges: list[FlowEdge],
+        note: str | None = None,
     ) -> tuple[str | None, int]:
         if not edges:
             logger.error(
@@ -1640,9 +2307,12 @@ class FlowRunner:
 
         base_prompt = self._build_flow_prompt(node, edges)
         prompt = base_prompt
+        tool_choice: ToolChoice = "auto"
         steps_used = 0
         while True:
-            result = await self._flow_turn(soul, prompt)
+            result = await self._flow_turn(
+                soul, prompt if note is None else self._with_note(prompt, note), tool_choice
+            )
             steps_used += result.step_count
             if result.stop_reason == "tool_rejected":
                 logger.error("Agent flow stopped after tool rejection.")
@@ -1671,6 +2341,25 @@ class FlowRunner:
                 "Your last response did not include a valid choice. "
                 "Reply with one of the choices using <choice>...</choice>."
             )
+            # The work of the node is done, so the model is made to answer with the choice.
+            tool_choice = "none"
+
+    @staticmethod
+    def _todos_note(soul: KimiSoul) -> str | None:
+        unfinished = soul._unfinished_todos()  # type: ignore[reportPrivateUsage]
+        if not unfinished:
+            return None
+        lines = [
+            "The last iteration ended with these todo items unfinished:",
+            *(f"- [{todo.status}] {todo.title}" for todo in unfinished),
+            "Continue working on them, or update the todo list if they are no longer needed.",
+        ]
+        return "\n".join(lines)
+
+    @staticmethod
+    def _with_note(prompt: str | list[ContentPart], note: str) -> list[ContentPart]:
+        parts = [TextPart(text=prompt)] if isinstance(prompt, str) else list(prompt)
+        return [*parts, system(note)]
 
     @staticmethod
     def _build_flow_prompt(node: FlowNode, edges: list[FlowEdge]) -> str | list[ContentPart]:
@@ -1705,8 +2394,13 @@ class FlowRunner:
     async def _flow_turn(
         soul: KimiSoul,
         prompt: str | list[ContentPart],
+        tool_choice: ToolChoice = "auto",
     ) -> TurnOutcome:
-        wire_send(TurnBegin(user_input=prompt))
-        res = await soul._turn(Message(role="user", content=prompt))  # type: ignore[reportPrivateUsage]
-        wire_send(TurnEnd())
+        soul._send_turn_begin(prompt)  # type: ignore[reportPrivateUsage]
+        soul._tool_choice = tool_choice  # type: ignore[reportPrivateUsage]
+        try:
+            res = await soul._turn(Message(role="user", content=prompt))  # type: ignore[reportPrivateUsage]
+        finally:
+            soul._tool_choice = "auto"  # type: ignore[reportPrivateUsage]
+        soul._send_turn_end(soul._turn_end(res))  # type: ignore[reportPrivateUsage]
         return res
@@ -0,0 +1,163 @@
+"""
+Downgrading images, videos and files the current model cannot see, as set by
+`on_unsupported_media`.
+
+In `strip` mode, each unsupported part is replaced by a text placeholder saying what was
+removed. In `describe` mode, the part is instead sent to the `vision_model` in a one-shot call,
+and replaced by the caption it returns. If no vision model is configured, or captioning fails,
+the placeholder is used.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass, replace
+from typing import Literal
+from urllib.parse import unquote, urlparse
+
+import kosong
+from kosong.chat_provider import GenerateOptions
+from kosong.message import Message
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.llm import LLM, ModelCapability
+from kimi_cli.soul.message import format_size
+from kimi_cli.utils.image import parse_data_url
+from kimi_cli.utils.logging import logger
+from kimi_cli.wire.types import ContentPart, FilePart, ImageURLPart, TextPart, VideoURLPart
+
+type UnsupportedMediaMode = Literal["error", "strip", "describe"]
+type MediaKind = Literal["image", "video", "file"]
+
+CAPTION_MAX_OUTPUT_TOKENS = 1024
+CAPTION_SYSTEM_PROMPT = (
+    "You describe media for a model that cannot see it. Describe the {kind} in detail, "
+    "including any text, code, diagrams or UI it shows, with text transcribed verbatim."
+)
+
+_CAPABILITIES: dict[MediaKind, ModelCapability] = {
+    "image": "image_in",
+    "video": "video_in",
+    "file": "file_in",
+}
+
+
+@dataclass(frozen=True, slots=True)
+class DowngradedMedia:
+    kind: MediaKind
+    name: str | None
+    """The file name from the URL or the part ID, None if neither is known."""
+    mime_type: str | None
+    size: int | None
+    """The size in bytes for data URLs, None for remote URLs."""
+    described: bool
+    """Whether the part was replaced by a caption rather than a placeholder."""
+
+    def label(self) -> str:
+        """A short description of the part, e.g. ``image `cat.png` (image/png, 12.0 KB)``."""
+        return self.kind + self._details()
+
+    def placeholder(self, caption: str | None) -> str:
+        """The text part that replaces the media part."""
+        what = self.kind.capitalize() + self._details()
+        if caption is None:
+            return f"[{what} omitted: the current model does not support {self.kind} input.]"
+        return (
+            f"[{what}, described by another model because the current model does not support "
+            f"{self.kind} input:\n{caption}]"
+        )
+
+    def _details(self) -> str:
+        details = ""
+        if self.name:
+            details += f" `{self.name}`"
+        known = [d for d in (self.mime_type, self.size and format_size(self.size)) if d]
+        if known:
+            details += f" ({', '.join(known)})"
+        return details
+
+
+async def downgrade_media(
+    message: Message,
+    model_capabilities: set[ModelCapability],
+    *,
+    vision_llm: LLM | None = None,
+) -> tuple[Message, list[DowngradedMedia]]:
+    """
+    Replace the image, video and file parts of `message` that need a capability the model lacks.
+
+    Args:
+        vision_llm: The model to caption the parts with, or None to replace them by a
+            placeholder.
+
+    Returns:
+        The downgraded message, and the parts that were replaced, in order.
+    """
+    content: list[ContentPart] = []
+    downgraded: list[DowngradedMedia] = []
+    kind: MediaKind
+    for part in message.content:
+        match part:
+            case ImageURLPart() if "image_in" not in model_capabilities:
+                kind, url, part_id = "image", part.image_url.url, part.image_url.id
+            case VideoURLPart() if "video_in" not in model_capabilities:
+                kind, url, part_id = "video", part.video_url.url, part.video_url.id
+            case FilePart() if "file_in" not in model_capabilities:
+                kind, url, part_id = "file", part.file.url, None
+            case _:
+                content.append(part)
+                continue
+
+        caption = None
+        if vision_llm is not None and _CAPABILITIES[kind] in vision_llm.capabilities:
+            caption = await _caption(vision_llm, part, kind)
+        media = _inspect_url(url, part_id, kind=kind, described=caption is not None)
+        if isinstance(part, FilePart):
+            media = replace(
+                media,
+                name=part.file.name or media.name,
+                mime_type=part.file.media_type or media.mime_type,
+            )
+        content.append(TextPart(text=media.placeholder(caption)))
+        downgraded.append(media)
+
+    if not downgraded:
+        return message, []
+    return message.model_copy(update={"content": content}), downgraded
+
+
+async def _caption(llm: LLM, part: ContentPart, kind: str) -> str | None:
+    try:
+        result = await kosong.step(
+            chat_provider=llm.chat_provider,
+            system_prompt=CAPTION_SYSTEM_PROMPT.format(kind=kind),
+            toolset=EmptyToolset(),
+            history=[Message(role="user", content=[part])],
+            options=GenerateOptions(max_output_tokens=CAPTION_MAX_OUTPUT_TOKENS),
+        )
+    except Exception as e:
+        logger.warning(
+            "Failed to caption {kind} with the vision model: {error}", kind=kind, error=e
+        )
+        return None
+    return result.message.extract_text().strip() or None
+
+
+def _inspect_url(
+    url: str, part_id: str | None, *, kind: MediaKind, described: bool
+) -> DowngradedMedia:
+    mime_type: str | None = None
+    size: int | None = None
+    name = part_id
+    try:
+        parsed = parse_data_url(url)
+    except ValueError:
+        parsed = None
+    if parsed is not None:
+        mime_type, data = parsed
+        mime_type = mime_type or None
+        size = len(data)
+    elif not url.startswith("data:"):
+        name = unquote(urlparse(url).path.rsplit("/", 1)[-1]) or name
+    return DowngradedMedia(
+        kind=kind, name=name, mime_type=mime_type, size=size, described=described
+    )
@@ -5,9 +5,18 @@ from collections.abc import Sequence
 from kosong.message import Message
 from kosong.tooling.error import ToolRuntimeError
 
+from kimi_cli.config import ImageInputConfig
 from kimi_cli.llm import ModelCapability
+from kimi_cli.utils.image import (
+    downscale_image,
+    get_image_mime_type,
+    get_image_size,
+    parse_data_url,
+    to_data_url,
+)
 from kimi_cli.wire.types import (
     ContentPart,
+    FilePart,
     ImageURLPart,
     TextPart,
     ThinkPart,
@@ -32,6 +41,30 @@ def is_system_reminder_message(message: Message) -> bool:
     return isinstance(part, TextPart) and part.text.strip().startswith("<system-reminder>")
 
 
+DMAIL_PREAMBLE = (
+    "You just got a D-Mail from your future self. "
+    "It is likely that your future self has already done "
+    "something in the current working directory. Please read "
+    "the D-Mail and decide what to do next. You MUST NEVER "
+    "mention to the user about this information. "
+)
+
+
+def dmail_message(content: str) -> Message:
+    """The user message that delivers a D-Mail after reverting to its checkpoint."""
+    return Message(
+        role="user", content=[system(f"{DMAIL_PREAMBLE}D-Mail content:\n\n{content.strip()}")]
+    )
+
+
+def is_dmail_message(message: Message) -> bool:
+    """Check whether a message is an injected D-Mail user message."""
+    if message.role != "user" or len(message.content) != 1:
+        return False
+    part = message.content[0]
+    return isinstance(part, TextPart) and part.text.startswith(f"<system>{DMAIL_PREAMBLE}")
+
+
 def tool_result_to_message(tool_result: ToolResult) -> Message:
     """Convert a tool result to a message."""
     if tool_result.return_value.is_error:
@@ -55,11 +88,7 @@ def tool_result_to_message(tool_result: ToolResult) -> Message:
             # the message with "text content is empty" (see #1663).
             content.insert(0, system("Tool returned non-text content."))
 
-    return Message(
-        role="tool",
-        content=content,
-        tool_call_id=tool_result.tool_call_id,
-    )
+    return Message.tool_result(tool_result.tool_call_id, content)
 
 
 def _output_to_content_parts(
@@ -87,6 +116,93 @@ def check_message(
             capabilities_needed.add("image_in")
         elif isinstance(part, VideoURLPart):
             capabilities_needed.add("video_in")
+        elif isinstance(part, FilePart):
+            capabilities_needed.add("file_in")
         elif isinstance(part, ThinkPart):
             capabilities_needed.add("thinking")
     return capabilities_needed - model_capabilities
+
+
+class InvalidUserInput(Exception):
+    """Raised when user input cannot be sent to the model. The message says how to fix it."""
+
+
+def prepare_user_input(
+    user_input: str | list[ContentPart], config: ImageInputConfig
+) -> str | list[ContentPart]:
+    """
+    Validate and normalize the images in user input before a turn starts.
+
+    Data URL images are decoded to check their type and size, and downscaled when they exceed
+    the configured limits. Remote image URLs are left for the provider to fetch. Images the
+    model cannot see are handled by the turn according to `on_unsupported_media`.
+
+    Raises:
+        InvalidUserInput: If an image is malformed, or too large even after downscaling.
+    """
+    if isinstance(user_input, str):
+        return user_input
+    if not any(isinstance(part, ImageURLPart) for part in user_input):
+        return user_input
+
+    parts: list[ContentPart] = []
+    total_size = 0
+    n_images = 0
+    for part in user_input:
+        if not isinstance(part, ImageURLPart):
+            parts.append(part)
+            continue
+        n_images += 1
+        part, size = _normalize_image(part, n_images, config)
+        parts.append(part)
+        total_size += size
+
+    if total_size > config.max_message_size:
+        raise InvalidUserInput(
+            f"The images in this message total {format_size(total_size)}, which exceeds the "
+            f"{format_size(config.max_message_size)} limit. Send fewer or smaller images, "
+            "or raise `image_input.max_message_size` in the config."
+        )
+    return parts
+
+
+def _normalize_image(
+    part: ImageURLPart, n: int, config: ImageInputConfig
+) -> tuple[ImageURLPart, int]:
+    try:
+        parsed = parse_data_url(part.image_url.url)
+    except ValueError as e:
+        raise InvalidUserInput(f"Image {n} is not a valid data URL: {e}.") from e
+    if parsed is None:
+        return part, 0
+
+    declared_mime_type, data = parsed
+    mime_type = get_image_mime_type(data)
+    if mime_type is None:
+        raise InvalidUserInput(
+            f"Image {n} (declared as `{declared_mime_type or 'unknown'}`) could not be decoded. "
+            "Attach a PNG, JPEG, GIF or WebP image instead."
+        )
+    width, height = get_image_size(data) or (0, 0)
+    if len(data) > config.max_image_size or max(width, height) > config.max_dimension:
+        downscaled = downscale_image(
+            data, max_dimension=config.max_dimension, max_bytes=config.max_image_size
+        )
+        if downscaled is None:
+            raise InvalidUserInput(
+                f"Image {n} is {format_size(len(data))} and could not be downscaled below the "
+                f"{format_size(config.max_image_size)} limit. Attach a smaller image, "
+                "or raise `image_input.max_image_size` in the config."
+            )
+        mime_type, data = downscaled
+    elif mime_type == declared_mime_type:
+        return part, len(data)
+
+    image_url = ImageURLPart.ImageURL(url=to_data_url(mime_type, data), id=part.image_url.id)
+    return ImageURLPart(image_url=image_url), len(data)
+
+
+def format_size(size: int) -> str:
+    if size < 1024 * 1024:
+        return f"{size / 1024:.1f} KB"
+    return f"{size / 1024 / 1024:.1f} MB"
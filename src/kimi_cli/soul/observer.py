@@ -0,0 +1,100 @@
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import TYPE_CHECKING, Any, Literal
+
+if TYPE_CHECKING:
+    from kosong import StepResult
+
+    from kimi_cli.approval_runtime import ApprovalRequestRecord
+    from kimi_cli.wire.types import ContentPart, ToolCall, ToolResult, TurnEnd
+
+
+class SoulObserver:
+    """Base class for observers of a `KimiSoul`, for projects embedding it as a library.
+
+    The soul calls the observers at the points where it sends the corresponding wire messages,
+    so they see turns, steps and tool calls without parsing the wire. Override the methods you
+    need; the defaults do nothing.
+
+    Observers are called synchronously from the agent loop and must not block. The data passed
+    to them is owned by the soul and must not be modified. An exception raised by an observer
+    is logged and otherwise ignored.
+    """
+
+    def on_turn_begin(self, user_input: str | list[ContentPart]) -> None:
+        """Called when a turn begins with the user input."""
+
+    def on_turn_end(self, turn_end: TurnEnd) -> None:
+        """Called when a turn ends, including when it is interrupted."""
+
+    def on_step_begin(self, n: int) -> None:
+        """Called when step `n` of the current turn begins, 1-based."""
+
+    def on_step_end(self, n: int, result: StepResult, tool_results: list[ToolResult]) -> None:
+        """Called when step `n` has finished and its messages were added to the context."""
+
+    def on_tool_call(self, tool_call: ToolCall) -> None:
+        """Called with each complete tool call, before it runs."""
+
+    def on_tool_result(self, result: ToolResult) -> None:
+        """Called with the result of each tool call as soon as it is available."""
+
+    def on_approval_request(self, request: ApprovalRequestRecord) -> None:
+        """Called when a tool run by the current turn, or by its foreground subagents, asks for
+        approval."""
+
+
+type SoulEventKind = Literal[
+    "turn_begin",
+    "turn_end",
+    "step_begin",
+    "step_end",
+    "tool_call",
+    "tool_result",
+    "approval_request",
+]
+
+
+@dataclass(frozen=True, slots=True)
+class SoulEvent:
+    """An event recorded by `TimelineObserver`."""
+
+    kind: SoulEventKind
+    data: Any
+    """The argument of the observer method, or a tuple of them if it takes several."""
+
+
+@dataclass(slots=True)
+class TimelineObserver(SoulObserver):
+    """An observer recording every event in order, for tests."""
+
+    timeline: list[SoulEvent] = field(default_factory=list[SoulEvent])
+
+    def kinds(self) -> list[SoulEventKind]:
+        return [event.kind for event in self.timeline]
+
+    def of_kind(self, kind: SoulEventKind) -> list[Any]:
+        """The data of the recorded events of `kind`."""
+        return [event.data for event in self.timeline if event.kind == kind]
+
+    def on_turn_begin(self, user_input: str | list[ContentPart]) -> None:
+        self.timeline.append(SoulEvent("turn_begin", user_input))
+
+    def on_turn_end(self, turn_end: TurnEnd) -> None:
+        self.timeline.append(SoulEvent("turn_end", turn_end))
+
+    def on_step_begin(self, n: int) -> None:
+        self.timeline.append(SoulEvent("step_begin", n))
+
+    def on_step_end(self, n: int, result: StepResult, tool_results: list[ToolResult]) -> None:
+        self.timeline.append(SoulEvent("step_end", (n, result, tool_results)))
+
+    def on_tool_call(self, tool_call: ToolCall) -> None:
+        self.timeline.append(SoulEvent("tool_call", tool_call))
+
+    def on_tool_result(self, result: ToolResult) -> None:
+        self.timeline.append(SoulEvent("tool_result", result))
+
+    def on_approval_request(self, request: ApprovalRequestRecord) -> None:
+        self.timeline.append(SoulEvent("approval_request", request))
@@ -0,0 +1,92 @@
+"""
+The success command of Ralph mode, which decides when the loop is done.
+
+After each iteration, `loop_control.ralph_success_command` is run in the shell. The loop ends
+when it exits with code 0. Otherwise the end of its output is passed to the next iteration, and
+the loop also ends once the output has stayed the same for `ralph_failure_limit` iterations in a
+row, as the agent is then making no progress.
+"""
+
+from __future__ import annotations
+
+import asyncio
+from dataclasses import dataclass
+
+import kaos
+from kaos.path import KaosPath
+
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.subprocess_env import get_noninteractive_env
+
+SUCCESS_COMMAND_TIMEOUT = 600
+"""Seconds after which the success command is killed. A timeout counts as a failure."""
+MAX_OUTPUT_CHARS = 4000
+"""Number of characters at the end of the output that are passed to the next iteration."""
+
+
+@dataclass(frozen=True, slots=True)
+class CheckResult:
+    passed: bool
+    stalled: bool
+    """Whether the command failed with the same output for `failure_limit` iterations in a row."""
+    note: str
+    """The outcome of the command, for the prompt of the next iteration."""
+
+
+class SuccessCheck:
+    def __init__(self, command: str, shell_path: KaosPath, failure_limit: int | None = None):
+        self.command = command
+        self._shell_path = shell_path
+        self.failure_limit = failure_limit
+        self._last_output: str | None = None
+        self._repeats = 0
+
+    async def run(self) -> CheckResult:
+        """Run the command and compare its output with the previous failed runs."""
+        try:
+            exit_code, output = await self._exec()
+            status = f"failed with exit code {exit_code}"
+        except TimeoutError:
+            exit_code, output = None, ""
+            status = f"timed out after {SUCCESS_COMMAND_TIMEOUT}s"
+        except Exception as e:
+            logger.warning("Failed to run Ralph success command: {error}", error=e)
+            exit_code, output = None, ""
+            status = f"could not be run: {e}"
+
+        if exit_code == 0:
+            return CheckResult(
+                passed=True,
+                stalled=False,
+                note=f"The success command `{self.command}` passed.",
+            )
+
+        if output == self._last_output:
+            self._repeats += 1
+        else:
+            self._last_output = output
+            self._repeats = 1
+        stalled = self.failure_limit is not None and self._repeats >= self.failure_limit
+
+        note = f"The success command `{self.command}` {status}."
+        if output.strip():
+            if len(output) > MAX_OUTPUT_CHARS:
+                output = "[...]\n" + output[-MAX_OUTPUT_CHARS:]
+            note += f" Its output was:\n{output.rstrip()}"
+        return CheckResult(passed=False, stalled=stalled, note=note)
+
+    async def _exec(self) -> tuple[int, str]:
+        process = await kaos.exec(
+            str(self._shell_path), "-c", self.command, env=get_noninteractive_env()
+        )
+        process.stdin.close()
+        try:
+            stdout, stderr = await asyncio.wait_for(
+                asyncio.gather(process.stdout.read(), process.stderr.read()),
+                SUCCESS_COMMAND_TIMEOUT,
+            )
+            code = await process.wait()
+        except (asyncio.CancelledError, TimeoutError):
+            await process.kill()
+            raise
+        return code, (stdout + stderr).decode("utf-8", errors="replace")
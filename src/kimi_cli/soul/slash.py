@@ -1,9 +1,11 @@
 from __future__ import annotations
 
+import re
 import tempfile
-from collections.abc import Awaitable, Callable
+from collections.abc import Awaitable, Callable, Sequence
+from datetime import datetime
 from pathlib import Path
-from typing import TYPE_CHECKING
+from typing import TYPE_CHECKING, Any
 
 from kaos.path import KaosPath
 from kosong.message import Message
@@ -15,9 +17,11 @@ from kimi_cli.soul.agent import load_agents_md
 from kimi_cli.soul.context import Context
 from kimi_cli.soul.dynamic_injections.afk_mode import AFK_DISABLED_REMINDER
 from kimi_cli.soul.message import system, system_reminder
+from kimi_cli.soul.usage import UsageTotals
 from kimi_cli.utils.export import is_sensitive_file
 from kimi_cli.utils.path import sanitize_cli_path, shorten_home
-from kimi_cli.utils.slashcmd import SlashCommandRegistry
+from kimi_cli.utils.slashcmd import SlashCommand, SlashCommandRegistry
+from kimi_cli.utils.string import shorten
 from kimi_cli.wire.types import StatusUpdate, TextPart
 
 if TYPE_CHECKING:
@@ -34,6 +38,36 @@ Raises:
 registry = SlashCommandRegistry[SoulSlashCmdFunc]()
 
 
+@registry.command(aliases=["h", "?"])
+def help(soul: KimiSoul, args: str):
+    """List available slash commands and skills"""
+    from kimi_cli.soul.kimisoul import FLOW_COMMAND_PREFIX, SKILL_COMMAND_PREFIX
+
+    builtins: list[SlashCommand[Any]] = []
+    skills: list[SlashCommand[Any]] = []
+    flows: list[SlashCommand[Any]] = []
+    for cmd in soul.available_slash_commands:
+        if cmd.name.startswith(SKILL_COMMAND_PREFIX):
+            skills.append(cmd)
+        elif cmd.name.startswith(FLOW_COMMAND_PREFIX):
+            flows.append(cmd)
+        else:
+            builtins.append(cmd)
+
+    sections: list[str] = []
+    for title, commands in (("Slash commands", builtins), ("Skills", skills), ("Flows", flows)):
+        if not commands:
+            continue
+        lines = [f"{title}:"]
+        for cmd in sorted(commands, key=lambda c: c.name):
+            line = f"  {cmd.slash_name()}"
+            if cmd.description:
+                line += f": {cmd.description}"
+            lines.append(line)
+        sections.append("\n".join(lines))
+    wire_send(TextPart(text="\n\n".join(sections)))
+
+
 @registry.command
 async def init(soul: KimiSoul, args: str):
     """Analyze the codebase and generate an `AGENTS.md` file"""
@@ -82,6 +116,7 @@ async def clear(soul: KimiSoul, args: str):
     """Clear the context"""
     logger.info("Running `/clear`")
     await soul.context.clear()
+    soul.runtime.file_tracker.clear()
     await soul.context.write_system_prompt(soul.agent.system_prompt)
     wire_send(TextPart(text="The context has been cleared."))
     snap = soul.status
@@ -94,6 +129,183 @@ async def clear(soul: KimiSoul, args: str):
     )
 
 
+@registry.command
+def checkpoints(soul: KimiSoul, args: str):
+    """List the checkpoints of the context with a preview of each"""
+    context = soul.context
+    if context.n_checkpoints == 0:
+        wire_send(TextPart(text="The context has no checkpoints."))
+        return
+    lines = ["Checkpoints:"]
+    for checkpoint_id in range(context.n_checkpoints):
+        try:
+            messages = context.checkpoint_messages(checkpoint_id)
+        except ValueError:
+            continue
+        lines.append(f"  {checkpoint_id}: {_checkpoint_preview(messages)}")
+    lines.append("Run /revert <id> to drop a checkpoint and everything after it.")
+    wire_send(TextPart(text="\n".join(lines)))
+
+
+def _checkpoint_preview(messages: Sequence[Message]) -> str:
+    for message in messages:
+        text = message.extract_text(" ").strip()
+        if text.startswith("<system>CHECKPOINT "):
+            continue
+        if text:
+            return f"{message.role}: {shorten(text, width=60)}"
+        if message.tool_calls:
+            names = ", ".join(call.function.name for call in message.tool_calls)
+            return f"{message.role}: calls {names}"
+    return "(empty)"
+
+
+@registry.command
+async def revert(soul: KimiSoul, args: str):
+    """Revert the context to a checkpoint listed by /checkpoints, e.g. /revert 3"""
+    try:
+        checkpoint_id = int(args.strip())
+    except ValueError:
+        wire_send(TextPart(text="Usage: /revert <checkpoint id>, see /checkpoints"))
+        return
+    if not 0 <= checkpoint_id < soul.context.n_checkpoints:
+        wire_send(
+            TextPart(
+                text=f"Checkpoint {checkpoint_id} does not exist. "
+                "Run /checkpoints to list the checkpoints."
+            )
+        )
+        return
+
+    logger.info("Running `/revert {checkpoint_id}`", checkpoint_id=checkpoint_id)
+    await soul.revert_to_checkpoint(checkpoint_id)
+    wire_send(
+        TextPart(
+            text=f"Reverted the context to checkpoint {checkpoint_id}. "
+            "Files changed since then were not restored."
+        )
+    )
+    snap = soul.status
+    wire_send(
+        StatusUpdate(
+            context_usage=snap.context_usage,
+            context_tokens=snap.context_tokens,
+            max_context_tokens=snap.max_context_tokens,
+        )
+    )
+
+
+_SNAPSHOT_NAME = re.compile(r"[A-Za-z0-9_-][A-Za-z0-9._-]*")
+
+
+def _snapshot_path(soul: KimiSoul, name: str) -> Path | None:
+    if not _SNAPSHOT_NAME.fullmatch(name):
+        wire_send(
+            TextPart(
+                text=f"Invalid snapshot name: {name!r}. "
+                "Use letters, digits, dots, dashes and underscores."
+            )
+        )
+        return None
+    return soul.runtime.session.snapshots_dir / f"{name}.jsonl"
+
+
+@registry.command
+async def save(soul: KimiSoul, args: str):
+    """Save the context as a named snapshot, or list the snapshots, e.g. /save baseline"""
+    name = args.strip()
+    if not name:
+        snapshots_dir = soul.runtime.session.snapshots_dir
+        paths = sorted(snapshots_dir.glob("*.jsonl")) if snapshots_dir.is_dir() else []
+        if not paths:
+            wire_send(TextPart(text="No snapshots saved. Run /save <name> to save one."))
+            return
+        lines = ["Snapshots:"]
+        for path in paths:
+            saved_at = datetime.fromtimestamp(path.stat().st_mtime).strftime("%Y-%m-%d %H:%M")
+            lines.append(f"  {path.stem} (saved {saved_at})")
+        lines.append("Run /load <name> to replace the context with a snapshot.")
+        wire_send(TextPart(text="\n".join(lines)))
+        return
+
+    path = _snapshot_path(soul, name)
+    if path is None:
+        return
+    logger.info("Running `/save {name}`", name=name)
+    await soul.context.save_snapshot(path)
+    wire_send(TextPart(text=f"Saved the context as snapshot {name}."))
+
+
+@registry.command
+async def load(soul: KimiSoul, args: str):
+    """Replace the context with a snapshot saved by /save, e.g. /load baseline"""
+    name = args.strip()
+    if not name:
+        wire_send(TextPart(text="Usage: /load <name>, run /save to list the snapshots"))
+        return
+    path = _snapshot_path(soul, name)
+    if path is None:
+        return
+    if not path.is_file():
+        wire_send(
+            TextPart(text=f"Snapshot {name} does not exist. Run /save to list the snapshots.")
+        )
+        return
+
+    logger.info("Running `/load {name}`", name=name)
+    await soul.load_snapshot(path)
+    wire_send(
+        TextPart(
+            text=f"Loaded snapshot {name} into the context. "
+            "Files changed since it was saved were not restored."
+        )
+    )
+    snap = soul.status
+    wire_send(
+        StatusUpdate(
+            context_usage=snap.context_usage,
+            context_tokens=snap.context_tokens,
+            max_context_tokens=snap.max_context_tokens,
+        )
+    )
+
+
+@registry.command
+def cost(soul: KimiSoul, args: str):
+    """Show token usage and estimated cost of the current turn and the session"""
+    usage = soul.runtime.usage
+    wire_send(
+        TextPart(
+            text="\n\n".join(
+                [
+                    _format_usage_totals("Current turn", usage.turn),
+                    _format_usage_totals("Session", usage.session),
+                ]
+            )
+        )
+    )
+
+
+def _format_usage_totals(title: str, totals: UsageTotals) -> str:
+    if totals.unpriced_calls == 0:
+        cost = f"${totals.cost:.4f}"
+    elif totals.cost > 0:
+        cost = (
+            f"${totals.cost:.4f} (excludes {totals.unpriced_calls} call(s) "
+            "on models without pricing)"
+        )
+    else:
+        cost = "unknown (no pricing configured for the model)"
+    return "\n".join(
+        [
+            f"{title}:",
+            f"  Input tokens: {totals.input:,} ({totals.input_cache_read:,} cached)",
+            f"  Output tokens: {totals.output:,}",
+            f"  Estimated cost: {cost}",
+        ]
+    )
+
+
 @registry.command
 async def yolo(soul: KimiSoul, args: str):
     """Toggle YOLO mode (auto-approve all actions)"""
@@ -156,6 +368,23 @@ async def afk(soul: KimiSoul, args: str):
         )
 
 
+@registry.command
+def dryrun(soul: KimiSoul, args: str):
+    """Toggle dry-run mode (file edits and commands only report what they would do)"""
+    soul.runtime.dry_run = not soul.runtime.dry_run
+    if soul.runtime.dry_run:
+        wire_send(
+            TextPart(
+                text=(
+                    "Dry-run mode enabled. File edits only show their diffs, and commands are "
+                    "not run."
+                )
+            )
+        )
+    else:
+        wire_send(TextPart(text="Dry-run mode disabled. Tools will make changes again."))
+
+
 @registry.command
 async def plan(soul: KimiSoul, args: str):
     """Toggle plan mode. Usage: /plan [on|off|view|clear]"""
@@ -285,6 +514,7 @@ async def export(soul: KimiSoul, args: str):
         token_count=soul.context.token_count,
         args=args,
         default_dir=Path(str(session.work_dir)),
+        compaction_markers=soul.context.compaction_markers,
     )
     if isinstance(result, str):
         wire_send(TextPart(text=result))
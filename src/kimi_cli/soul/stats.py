@@ -0,0 +1,47 @@
+from __future__ import annotations
+
+import time
+from collections.abc import Callable
+from dataclasses import dataclass, field
+
+from kimi_cli.session_stats import SessionStats, ToolStats
+
+
+@dataclass(slots=True, kw_only=True)
+class StatsRecorder:
+    """Counts what the agents of a session do, shared by the main agent and its subagents."""
+
+    stats: SessionStats = field(default_factory=SessionStats)
+    on_change: Callable[[SessionStats], None] | None = None
+    """Called after each update, e.g. to persist the stats."""
+
+    def record_turn(self) -> None:
+        self.stats.turns += 1
+        self._changed()
+
+    def record_step(self) -> None:
+        self.stats.steps += 1
+        self._changed()
+
+    def record_retry(self) -> None:
+        self.stats.retries += 1
+        self._changed()
+
+    def record_approval(self, approved: bool) -> None:
+        if approved:
+            self.stats.approvals_approved += 1
+        else:
+            self.stats.approvals_rejected += 1
+        self._changed()
+
+    def record_tool_call(self, tool_name: str, duration_s: float, *, error: bool) -> None:
+        tool = self.stats.tools.setdefault(tool_name, ToolStats())
+        tool.calls += 1
+        tool.errors += int(error)
+        tool.duration_s += duration_s
+        self._changed()
+
+    def _changed(self) -> None:
+        self.stats.updated_at = time.time()
+        if self.on_change is not None:
+            self.on_change(self.stats)
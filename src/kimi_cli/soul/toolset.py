@@ -2,10 +2,14 @@ from __future__ import annotations
 
 import asyncio
 import contextlib
+import hashlib
 import importlib
 import inspect
 import json
+import re
 import time
+import traceback
+from collections.abc import Iterator
 from contextvars import ContextVar
 from dataclasses import dataclass
 from datetime import timedelta
@@ -30,9 +34,11 @@ from kosong.tooling.mcp import convert_mcp_content
 from kosong.utils.typing import JsonType
 
 from kimi_cli import logger
+from kimi_cli.config import ToolsConfig
 from kimi_cli.exception import InvalidToolError, MCPRuntimeError
 from kimi_cli.hooks.engine import HookEngine
-from kimi_cli.tools import SkipThisTool
+from kimi_cli.tools import ExemptFromTimeout, SkipThisTool
+from kimi_cli.tools.utils import dry_run_result
 from kimi_cli.wire.types import (
     AudioURLPart,
     ContentPart,
@@ -44,6 +50,8 @@ from kimi_cli.wire.types import (
     ToolCallRequest,
     ToolResult,
     ToolReturnValue,
+    ToolSchema,
+    ToolSource,
     VideoURLPart,
 )
 
@@ -55,11 +63,18 @@ if TYPE_CHECKING:
     from fastmcp.mcp_config import MCPConfig
 
     from kimi_cli.soul.agent import Runtime
+    from kimi_cli.soul.stats import StatsRecorder
 
 current_tool_call = ContextVar[ToolCall | None]("current_tool_call", default=None)
 
+_current_tool_timeout = ContextVar[asyncio.Timeout | None]("_current_tool_timeout", default=None)
+
 _current_session_id: ContextVar[str] = ContextVar("_current_session_id", default="")
 
+_tool_tracebacks = False
+"""Whether the result of a tool that raises includes the traceback, enabled by `--debug`."""
+_MAX_TRACEBACK_CHARS = 4000
+
 
 def set_session_id(sid: str) -> None:
     _current_session_id.set(sid)
@@ -73,6 +88,30 @@ def _get_session_id() -> str:
     return _current_session_id.get()
 
 
+def set_tool_tracebacks(enabled: bool) -> None:
+    """Include a truncated traceback in the error result of a tool that raises, or not."""
+    global _tool_tracebacks
+    _tool_tracebacks = enabled
+
+
+def _tool_exception_message(e: Exception) -> str:
+    """The message of the error result of a tool that raised `e`."""
+    message = str(e)
+    if not _tool_tracebacks:
+        return message
+    formatted = "".join(traceback.format_exception(e)).rstrip()
+    if len(formatted) > _MAX_TRACEBACK_CHARS:
+        # the innermost frames, at the end, are the most useful
+        formatted = "[truncated]\n..." + formatted[-_MAX_TRACEBACK_CHARS:]
+    return f"{message}\n\n{formatted}"
+
+
+def _tool_deadline() -> float | None:
+    """The event loop time at which the timeout of the current tool call runs out, if any."""
+    timeout = _current_tool_timeout.get()
+    return timeout.when() if timeout is not None else None
+
+
 def get_current_tool_call_or_none() -> ToolCall | None:
     """
     Get the current tool call or None.
@@ -81,6 +120,26 @@ def get_current_tool_call_or_none() -> ToolCall | None:
     return current_tool_call.get()
 
 
+@contextlib.contextmanager
+def tool_timeout_paused() -> Iterator[None]:
+    """
+    Keep the timeout of the current tool call from running out, e.g. while it waits for the user
+    to approve it.
+    """
+    timeout = _current_tool_timeout.get()
+    if timeout is None or (deadline := timeout.when()) is None:
+        yield
+        return
+    loop = asyncio.get_running_loop()
+    remaining = deadline - loop.time()
+    timeout.reschedule(None)
+    try:
+        yield
+    finally:
+        if not timeout.expired():
+            timeout.reschedule(loop.time() + remaining)
+
+
 type ToolType = CallableTool | CallableTool2[Any]
 type ToolCallKey = tuple[str, str]
 
@@ -133,6 +192,11 @@ _REPEAT_REMINDER_2_START = 5
 _REPEAT_REMINDER_3_START = 8
 _REPEAT_FORCE_STOP_STREAK = 12
 
+_TOOL_NAME_INVALID_CHARS = re.compile(r"[^A-Za-z0-9_-]")
+"""Characters of an MCP server name that cannot appear in the prefixed name of its tools."""
+_MAX_TOOL_NAME_LENGTH = 64
+"""The longest tool name most providers accept, which prefixed MCP tool names are cut to."""
+
 type RepeatAction = Literal["none", "r1", "r2", "r3", "stop"]
 
 
@@ -205,7 +269,12 @@ def _append_reminder_to_return_value(
 
 
 class KimiToolset:
-    def __init__(self) -> None:
+    def __init__(
+        self, tools_config: ToolsConfig | None = None, *, stats: StatsRecorder | None = None
+    ) -> None:
+        self._tools_config = tools_config if tools_config is not None else ToolsConfig()
+        self._stats = stats
+        """Counts the calls of each tool and the time spent in them, if set."""
         self._tool_dict: dict[str, ToolType] = {}
         self._hidden_tools: set[str] = set()
         self._mcp_servers: dict[str, MCPServerInfo] = {}
@@ -230,6 +299,10 @@ class KimiToolset:
     def add(self, tool: ToolType) -> None:
         self._tool_dict[tool.name] = tool
 
+    def is_enabled(self, tool_name: str) -> bool:
+        """Whether the `[tools]` config allows registering the tool with the given name."""
+        return self._tools_config.is_enabled(tool_name)
+
     def hide(self, tool_name: str) -> bool:
         """Hide a tool from the LLM tool list. Returns True if the tool exists."""
         if tool_name in self._tool_dict:
@@ -260,6 +333,26 @@ class KimiToolset:
             tool.base for tool in self._tool_dict.values() if tool.name not in self._hidden_tools
         ]
 
+    def tool_schemas(self) -> list[ToolSchema]:
+        """The tools offered to the model, as `tools` does, each marked with its source."""
+        mcp_servers = {
+            tool.name: server
+            for server, info in self._mcp_servers.items()
+            for tool in info.tools
+        }
+        schemas: list[ToolSchema] = []
+        for tool in self.tools:
+            server = mcp_servers.get(tool.name)
+            source: ToolSource
+            if server is not None:
+                source = "mcp"
+            elif isinstance(self._tool_dict[tool.name], WireExternalTool):
+                source = "external"
+            else:
+                source = "builtin"
+            schemas.append(ToolSchema(**tool.model_dump(), source=source, mcp_server=server))
+        return schemas
+
     def begin_step(self, previous_calls: list[tuple[str, str]]) -> None:
         """Called before each step to set up deduplication state."""
         self._previous_step_calls = [
@@ -406,7 +499,7 @@ class KimiToolset:
                 # --- Execute tool ---
                 t0 = time.monotonic()
                 try:
-                    ret = await tool.call(arguments)
+                    ret = await self._call_with_timeout(tool, arguments)
                 except Exception as e:
                     tool_elapsed = time.monotonic() - t0
                     logger.exception(
@@ -414,6 +507,8 @@ class KimiToolset:
                         tool_name=tool_name,
                         call_id=tool_call.id,
                     )
+                    if self._stats is not None:
+                        self._stats.record_tool_call(tool_name, tool_elapsed, error=True)
                     # --- PostToolUseFailure (fire-and-forget) ---
                     _hook_task = asyncio.create_task(
                         self._hook_engine.trigger(
@@ -444,7 +539,7 @@ class KimiToolset:
                     )
                     return ToolResult(
                         tool_call_id=tool_call.id,
-                        return_value=ToolRuntimeError(str(e)),
+                        return_value=ToolRuntimeError(_tool_exception_message(e)),
                     )
 
                 tool_elapsed = time.monotonic() - t0
@@ -454,6 +549,10 @@ class KimiToolset:
                     elapsed=tool_elapsed,
                     call_id=tool_call.id,
                 )
+                if self._stats is not None:
+                    self._stats.record_tool_call(
+                        tool_name, tool_elapsed, error=isinstance(ret, ToolError)
+                    )
                 from kimi_cli.telemetry import track as _track_tool_call
 
                 if isinstance(ret, ToolError):
@@ -513,12 +612,42 @@ class KimiToolset:
         finally:
             current_tool_call.reset(token)
 
+    async def _call_with_timeout(self, tool: ToolType, arguments: JsonType) -> ToolReturnValue:
+        """Call the tool, stopping it with a `Timeout` error if it runs past its timeout."""
+        timeout_s = self._tools_config.timeouts.get(tool.name)
+        if timeout_s is None and not isinstance(tool, ExemptFromTimeout):
+            timeout_s = self._tools_config.timeout_s
+        timeout = asyncio.timeout(timeout_s or None)
+        token = _current_tool_timeout.set(timeout)
+        try:
+            async with timeout:
+                return await tool.call(arguments)
+        except TimeoutError:
+            if not timeout.expired():
+                raise
+        finally:
+            _current_tool_timeout.reset(token)
+        logger.warning(
+            "Tool {tool_name} timed out after {timeout_s}s",
+            tool_name=tool.name,
+            timeout_s=timeout_s,
+        )
+        return ToolError(
+            message=(
+                f"`{tool.name}` did not finish within {timeout_s:g} seconds and was stopped. "
+                "Its work may be incomplete."
+            ),
+            brief="Timeout",
+        )
+
     def register_external_tool(
         self,
         name: str,
         description: str,
         parameters: dict[str, Any],
     ) -> tuple[bool, str | None]:
+        if not self.is_enabled(name):
+            return False, "tool is disabled in the config"
         if name in self._tool_dict:
             existing = self._tool_dict[name]
             if not isinstance(existing, WireExternalTool):
@@ -598,6 +727,9 @@ class KimiToolset:
             except SkipThisTool:
                 logger.info("Skipping tool: {tool_path}", tool_path=tool_path)
                 continue
+            if tool and not self.is_enabled(tool.name):
+                logger.info("Skipping disabled tool: {tool_path}", tool_path=tool_path)
+                continue
             if tool:
                 self.add(tool)
                 good_tools.append(tool_path)
@@ -653,10 +785,16 @@ class KimiToolset:
                 connected.
         """
         import fastmcp
-        from fastmcp.mcp_config import MCPConfig, RemoteMCPServer
+        from fastmcp.mcp_config import MCPConfig, RemoteMCPServer, StdioMCPServer
 
-        from kimi_cli.mcp_oauth import create_mcp_oauth, has_mcp_oauth_tokens
+        from kimi_cli.mcp_oauth import (
+            create_mcp_oauth,
+            expand_stdio_server_env,
+            has_mcp_oauth_tokens,
+        )
         from kimi_cli.ui.shell.prompt import toast
+        from kimi_cli.utils.envvar import UnsetEnvVarError
+        from kimi_cli.utils.network import new_mcp_httpx_client
 
         async def _check_oauth_tokens(server_url: str) -> bool:
             """Check if OAuth tokens exist for the server."""
@@ -682,23 +820,57 @@ class KimiToolset:
                 status="unauthorized", client=None, tools=[]
             )
 
+        # Servers whose config cannot be used fail without being connected to
+        config_errors: dict[str, Exception] = {}
+
+        def _expand_stdio_env(server_config: StdioMCPServer) -> StdioMCPServer:
+            """Expand `${VAR}` and `${VAR:-default}` in the `env` and `args` of a stdio server."""
+            return server_config.model_copy(
+                update=expand_stdio_server_env(
+                    {"args": server_config.args, "env": server_config.env}
+                )
+            )
+
+        # Servers are connected to concurrently, a few at a time, so that a slow server does not
+        # hold up the others
+        connection_slots = asyncio.Semaphore(runtime.config.mcp.client.max_concurrent_connections)
+
         async def _connect_server(
             server_name: str, server_info: MCPServerInfo
         ) -> tuple[str, Exception | None]:
             if server_info.status != "pending":
                 return server_name, None
 
-            server_info.status = "connecting"
             try:
                 assert server_info.client is not None
-                async with server_info.client as client:
-                    for tool in await client.list_tools():
-                        server_info.tools.append(
-                            MCPTool(server_name, tool, client, runtime=runtime)
+                async with connection_slots:
+                    server_info.status = "connecting"
+                    async with server_info.client as client:
+                        mcp_tools = await client.list_tools()
+
+                # Names are picked and registered without awaiting, so that servers connecting
+                # concurrently see each other's tools
+                for tool in mcp_tools:
+                    name = self._mcp_tool_name(server_name, tool.name, runtime)
+                    if name is None:
+                        continue
+                    if not self._is_mcp_tool_enabled(tool.name, name):
+                        logger.info(
+                            "Skipping disabled MCP tool: {server_name}/{tool_name}",
+                            server_name=server_name,
+                            tool_name=tool.name,
                         )
-
-                for tool in server_info.tools:
-                    self.add(tool)
+                        continue
+                    mcp_tool = MCPTool(
+                        server_name,
+                        tool,
+                        client,
+                        runtime=runtime,
+                        name=name,
+                        max_retries=server_info.max_retries,
+                    )
+                    server_info.tools.append(mcp_tool)
+                    self.add(mcp_tool)
 
                 server_info.status = "connected"
                 logger.info("Connected MCP server: {server_name}", server_name=server_name)
@@ -720,7 +892,10 @@ class KimiToolset:
                 if server_info.status == "pending"
             ]
             results = await asyncio.gather(*tasks) if tasks else []
-            failed_servers = {name: error for name, error in results if error is not None}
+            failed_servers = {
+                **config_errors,
+                **{name: error for name, error in results if error is not None},
+            }
 
             for mcp_config in mcp_configs:
                 # Skip empty MCP configs (no servers defined)
@@ -758,9 +933,32 @@ class KimiToolset:
                         continue
                     server_config = server_config.model_copy(update={"auth": auth})
 
-                client = fastmcp.Client(MCPConfig(mcpServers={server_name: server_config}))
+                if isinstance(server_config, RemoteMCPServer):
+                    # Route HTTP transports through the `[network]` proxy and TLS settings.
+                    transport = server_config.to_transport()
+                    transport.httpx_client_factory = new_mcp_httpx_client
+                    client = fastmcp.Client(transport)
+                else:
+                    if isinstance(server_config, StdioMCPServer):
+                        try:
+                            server_config = _expand_stdio_env(server_config)
+                        except UnsetEnvVarError as e:
+                            logger.error(
+                                "Failed to connect MCP server: {server_name}, error: {error}",
+                                server_name=server_name,
+                                error=e,
+                            )
+                            config_errors[server_name] = e
+                            self._mcp_servers[server_name] = MCPServerInfo(
+                                status="failed", client=None, tools=[]
+                            )
+                            continue
+                    client = fastmcp.Client(MCPConfig(mcpServers={server_name: server_config}))
                 self._mcp_servers[server_name] = MCPServerInfo(
-                    status="pending", client=client, tools=[]
+                    status="pending",
+                    client=client,
+                    tools=[],
+                    max_retries=_mcp_max_retries(server_name, server_config),
                 )
 
         if in_background:
@@ -768,6 +966,49 @@ class KimiToolset:
         else:
             await _connect()
 
+    def _mcp_tool_name(self, server_name: str, tool_name: str, runtime: Runtime) -> str | None:
+        """
+        The name to register an MCP tool under: its own name, or `<server>__<tool>` if that is
+        taken by a tool registered earlier or if `mcp.client.prefix_tool_names` is set. None if
+        the prefixed name is taken as well. Prefixed names longer than `_MAX_TOOL_NAME_LENGTH`
+        are cut to it, ending with a hash of the full name.
+        """
+        prefixed = f"{_TOOL_NAME_INVALID_CHARS.sub('_', server_name)}__{tool_name}"
+        if len(prefixed) > _MAX_TOOL_NAME_LENGTH:
+            # Keep the cut names of different tools apart with a hash of the full name
+            digest = hashlib.sha256(prefixed.encode()).hexdigest()[:8]
+            prefixed = f"{prefixed[: _MAX_TOOL_NAME_LENGTH - len(digest) - 1]}_{digest}"
+        if not runtime.config.mcp.client.prefix_tool_names:
+            if tool_name not in self._tool_dict:
+                return tool_name
+            logger.info(
+                "MCP tool name {tool_name} of server {server_name} is already taken, "
+                "registering it as {prefixed}",
+                tool_name=tool_name,
+                server_name=server_name,
+                prefixed=prefixed,
+            )
+        if prefixed in self._tool_dict:
+            logger.warning(
+                "Skipping MCP tool {server_name}/{tool_name}: {prefixed} is already taken",
+                server_name=server_name,
+                tool_name=tool_name,
+                prefixed=prefixed,
+            )
+            return None
+        return prefixed
+
+    def _is_mcp_tool_enabled(self, tool_name: str, registered_name: str) -> bool:
+        """
+        Whether the `[tools]` config allows registering an MCP tool, checking both the name given
+        by its server and the name it is registered under: it is skipped if either is disabled,
+        and allowed if either is enabled.
+        """
+        names = {tool_name, registered_name}
+        if any(name in self._tools_config.disabled for name in names):
+            return False
+        return any(self.is_enabled(name) for name in names)
+
     def has_pending_mcp_tools(self) -> bool:
         """Return True if the background MCP tool-loading task is still running."""
         return self._mcp_loading_task is not None and not self._mcp_loading_task.done()
@@ -784,18 +1025,40 @@ class KimiToolset:
                 self._mcp_loading_task = None
 
     async def cleanup(self) -> None:
-        """Cleanup any resources held by the toolset."""
+        """
+        Cleanup any resources held by the toolset: stop loading MCP tools, and close all MCP
+        clients concurrently, so that the processes of stdio servers exit with the session. A
+        client that does not close within `MCP_CLOSE_TIMEOUT_S` is given up on.
+        """
         self._deferred_mcp_load = None
         if self._mcp_loading_task:
             self._mcp_loading_task.cancel()
             with contextlib.suppress(Exception, asyncio.CancelledError):
                 await self._mcp_loading_task
-        for server_info in self._mcp_servers.values():
-            if server_info.client is not None:
-                try:
-                    await server_info.client.close()
-                except Exception:
-                    logger.warning("Failed to close MCP client", exc_info=True)
+        await asyncio.gather(
+            *(
+                _close_mcp_client(server_name, server_info.client)
+                for server_name, server_info in self._mcp_servers.items()
+                if server_info.client is not None
+            )
+        )
+
+
+MCP_CLOSE_TIMEOUT_S = 5.0
+"""How long closing an MCP client may take before cleanup gives up on it."""
+
+
+async def _close_mcp_client(server_name: str, client: fastmcp.Client[Any]) -> None:
+    try:
+        await asyncio.wait_for(client.close(), MCP_CLOSE_TIMEOUT_S)
+    except TimeoutError:
+        logger.warning(
+            "MCP server {server_name} did not close within {timeout}s",
+            server_name=server_name,
+            timeout=MCP_CLOSE_TIMEOUT_S,
+        )
+    except Exception:
+        logger.exception("Failed to close MCP server {server_name}:", server_name=server_name)
 
 
 @dataclass(slots=True)
@@ -803,9 +1066,37 @@ class MCPServerInfo:
     status: Literal["pending", "connecting", "connected", "failed", "unauthorized"]
     client: fastmcp.Client[Any] | None
     tools: list[MCPTool[Any]]
+    max_retries: int = 0
+    """How many times a tool call of the server that timed out is retried."""
+
+
+_MCP_RETRY_DELAY_S = 1.0
+"""The delay before the first retry of a timed out MCP tool call, doubled for each retry."""
+_MCP_MAX_RETRY_DELAY_S = 10.0
 
 
-class MCPTool[T: ClientTransport](CallableTool):
+def _mcp_max_retries(server_name: str, server_config: Any) -> int:
+    """The `max_retries` of a server in the MCP config, which is not a field known to fastmcp."""
+    value = getattr(server_config, "max_retries", None)
+    if value is None:
+        return 0
+    if isinstance(value, bool) or not isinstance(value, int) or value < 0:
+        logger.warning(
+            "Ignoring invalid max_retries of MCP server {server_name}: {value}",
+            server_name=server_name,
+            value=value,
+        )
+        return 0
+    return value
+
+
+def _is_mcp_timeout(e: Exception) -> bool:
+    # fastmcp raises `RuntimeError` on timeout and we cannot tell it from other errors
+    exc_msg = str(e).lower()
+    return "timeout" in exc_msg or "timed out" in exc_msg
+
+
+class MCPTool[T: ClientTransport](CallableTool, ExemptFromTimeout):
     def __init__(
         self,
         server_name: str,
@@ -813,37 +1104,76 @@ class MCPTool[T: ClientTransport](CallableTool):
         client: fastmcp.Client[T],
         *,
         runtime: Runtime,
+        name: str | None = None,
+        max_retries: int = 0,
         **kwargs: Any,
     ):
+        """
+        `name` is the name to register the tool under, if not the name given by the server.
+        A call that times out is retried up to `max_retries` times, with backoff.
+        """
+        name = name or mcp_tool.name
+        alias = (
+            f"It is named `{name}` here, after the server, to tell it apart from other tools "
+            f"named `{mcp_tool.name}`.\n\n"
+            if name != mcp_tool.name
+            else ""
+        )
         super().__init__(
-            name=mcp_tool.name,
+            name=name,
             description=(
                 f"This is an MCP (Model Context Protocol) tool from MCP server `{server_name}`.\n\n"
-                f"{mcp_tool.description or 'No description provided.'}"
+                f"{alias}{mcp_tool.description or 'No description provided.'}"
             ),
             parameters=mcp_tool.inputSchema,
             **kwargs,
         )
+        self._server_name = server_name
         self._mcp_tool = mcp_tool
         self._client = client
         self._runtime = runtime
         self._timeout = timedelta(milliseconds=runtime.config.mcp.client.tool_call_timeout_ms)
-        self._action_name = f"mcp:{mcp_tool.name}"
+        self._max_retries = max_retries
+        self._action_name = f"mcp:{name}"
 
     async def __call__(self, *args: Any, **kwargs: Any) -> ToolReturnValue:
+        if self._runtime.dry_run:
+            # What an MCP tool does is unknown, so it is never called in dry-run mode
+            return dry_run_result(
+                f"Call MCP tool `{self._mcp_tool.name}` of MCP server `{self._server_name}` "
+                f"with arguments {json.dumps(kwargs, ensure_ascii=False)}"
+            )
         description = f"Call MCP tool `{self._mcp_tool.name}`."
         result = await self._runtime.approval.request(self.name, self._action_name, description)
         if not result:
             return result.rejection_error()
 
+        retries = 0
         try:
             async with self._client as client:
-                result = await client.call_tool(
-                    self._mcp_tool.name,
-                    kwargs,
-                    timeout=self._timeout,
-                    raise_on_error=False,
-                )
+                while True:
+                    try:
+                        result = await client.call_tool(
+                            self._mcp_tool.name,
+                            kwargs,
+                            timeout=self._timeout,
+                            raise_on_error=False,
+                        )
+                        break
+                    except Exception as e:
+                        delay = min(_MCP_RETRY_DELAY_S * 2**retries, _MCP_MAX_RETRY_DELAY_S)
+                        if not _is_mcp_timeout(e) or not self._can_retry(retries, delay):
+                            raise
+                        retries += 1
+                        logger.warning(
+                            "MCP tool call timed out, retrying in {delay}s ({retry}/{max}): "
+                            "{tool_name}",
+                            delay=delay,
+                            retry=retries,
+                            max=self._max_retries,
+                            tool_name=self._mcp_tool.name,
+                        )
+                        await asyncio.sleep(delay)
                 if result.is_error:
                     logger.warning(
                         "MCP tool returned error: {tool_name}: {content}",
@@ -852,17 +1182,18 @@ class MCPTool[T: ClientTransport](CallableTool):
                     )
                 return convert_mcp_tool_result(result)
         except Exception as e:
-            # fastmcp raises `RuntimeError` on timeout and we cannot tell it from other errors
-            exc_msg = str(e).lower()
-            if "timeout" in exc_msg or "timed out" in exc_msg:
+            if _is_mcp_timeout(e):
                 logger.warning(
                     "MCP tool call timed out: {tool_name}: {error}",
                     tool_name=self._mcp_tool.name,
                     error=e,
                 )
+                retried = (
+                    f" after {retries} of {self._max_retries} retries" if self._max_retries else ""
+                )
                 return ToolError(
                     message=(
-                        f"Timeout while calling MCP tool `{self._mcp_tool.name}`. "
+                        f"Timeout while calling MCP tool `{self._mcp_tool.name}`{retried}. "
                         "You may explain to the user that the timeout config is set too low."
                     ),
                     brief="Timeout",
@@ -874,6 +1205,19 @@ class MCPTool[T: ClientTransport](CallableTool):
             )
             raise
 
+    def _can_retry(self, retries: int, delay: float) -> bool:
+        """
+        Whether to retry after `retries` retries, waiting `delay` seconds first. A retry must
+        have time to run in full before the timeout of the whole tool call, if any, runs out.
+        """
+        if retries >= self._max_retries:
+            return False
+        deadline = _tool_deadline()
+        if deadline is None:
+            return True
+        loop = asyncio.get_running_loop()
+        return loop.time() + delay + self._timeout.total_seconds() <= deadline
+
 
 class WireExternalTool(CallableTool):
     def __init__(self, *, name: str, description: str, parameters: dict[str, Any]) -> None:
@@ -0,0 +1,168 @@
+"""
+Machine-readable summaries of the turns of the main agent, written for CI with `--summary-file`.
+
+At the end of every turn, including each iteration in Ralph mode, a `TurnSummary` is appended
+to the JSON document at the summary file, which is rewritten atomically:
+
+    {"turns": [{"session_id": "...", "turn": 1, "stop_reason": "no_tool_calls", ...}, ...]}
+
+File tools and `Shell` report what they changed or ran in the `extras` of their results, under
+`FILE_CHANGES_EXTRA` and `SHELL_COMMAND_EXTRA`. These are collected from the tool results of
+the turn, including those of subagents.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+from typing import Literal
+
+from kosong.tooling import ToolResult
+from pydantic import BaseModel, Field, ValidationError
+
+from kimi_cli.session_state import UsageState
+from kimi_cli.soul import BudgetExceeded, MaxStepsReached
+from kimi_cli.soul.usage import UsageTotals
+from kimi_cli.tools.utils import ToolRejectedError
+from kimi_cli.utils.io import atomic_json_write
+from kimi_cli.utils.logging import logger
+
+FILE_CHANGES_EXTRA = "file_changes"
+"""`extras` key of the files a tool changed, as a `{path: FileChange}` mapping."""
+SHELL_COMMAND_EXTRA = "shell_command"
+"""`extras` key of the command a tool ran, as `{"command": str, "exit_code": int | None}`."""
+
+type FileChange = Literal["created", "modified", "deleted"]
+
+
+class FileChanges(BaseModel):
+    created: list[str] = Field(default_factory=list)
+    modified: list[str] = Field(default_factory=list)
+    deleted: list[str] = Field(default_factory=list)
+
+
+class ShellCommand(BaseModel):
+    command: str
+    exit_code: int | None
+    """None if the command did not exit by itself, e.g. it was killed by the timeout."""
+
+
+class TurnSummary(BaseModel):
+    session_id: str
+    turn: int
+    """The 1-based index of the turn among the turns of the session in the summary file."""
+    stop_reason: str
+    """Why the turn stopped: a `TurnStopReason`, or `max_steps_reached`, `budget_exceeded`,
+    `cancelled` or `error` if it did not finish."""
+    step_count: int
+    usage: UsageState
+    final_message: str | None
+    """The text of the last assistant message, None if the turn did not finish."""
+    files: FileChanges = Field(default_factory=FileChanges)
+    shell_commands: list[ShellCommand] = Field(default_factory=list)
+
+
+class SummaryDocument(BaseModel):
+    turns: list[TurnSummary] = Field(default_factory=list)
+
+
+def failure_stop_reason(error: BaseException) -> str:
+    """The stop reason of a turn that ended with `error`."""
+    match error:
+        case MaxStepsReached():
+            return "max_steps_reached"
+        case BudgetExceeded():
+            return "budget_exceeded"
+        case asyncio.CancelledError():
+            return "cancelled"
+        case _:
+            return "error"
+
+
+class TurnSummaryWriter:
+    """Collects what the agents did during a turn and appends it to the summary file."""
+
+    def __init__(self, path: Path):
+        self._path = path
+        self._files: dict[str, FileChange] = {}
+        self._shell_commands: list[ShellCommand] = []
+
+    def begin_turn(self) -> None:
+        self._files = {}
+        self._shell_commands = []
+
+    def record(self, results: list[ToolResult]) -> None:
+        """Collect the file changes and shell commands reported by tool results."""
+        for result in results:
+            return_value = result.return_value
+            if isinstance(return_value, ToolRejectedError) or not return_value.extras:
+                continue
+            changes = return_value.extras.get(FILE_CHANGES_EXTRA)
+            if isinstance(changes, dict):
+                for path, change in changes.items():
+                    if change in ("created", "modified", "deleted"):
+                        self._record_file_change(path, change)
+            command = return_value.extras.get(SHELL_COMMAND_EXTRA)
+            if isinstance(command, dict):
+                try:
+                    self._shell_commands.append(ShellCommand.model_validate(command))
+                except ValidationError:
+                    logger.warning(
+                        "Invalid shell command in tool result: {command}", command=command
+                    )
+
+    def _record_file_change(self, path: str, change: FileChange) -> None:
+        # Combine the changes of the turn into one, as seen from before the turn
+        match self._files.get(path), change:
+            case "created", "modified":
+                return
+            case "created", "deleted":
+                del self._files[path]
+            case "deleted", "created":
+                self._files[path] = "modified"
+            case _:
+                self._files[path] = change
+
+    async def end_turn(
+        self,
+        *,
+        session_id: str,
+        stop_reason: str,
+        step_count: int,
+        usage: UsageTotals,
+        final_message: str | None,
+    ) -> None:
+        """Append the summary of the turn to the summary file. Errors are logged, not raised."""
+        files = FileChanges()
+        for path, change in sorted(self._files.items()):
+            getattr(files, change).append(path)
+        try:
+            document = await asyncio.to_thread(self._load)
+            turn = 1 + sum(1 for summary in document.turns if summary.session_id == session_id)
+            document.turns.append(
+                TurnSummary(
+                    session_id=session_id,
+                    turn=turn,
+                    stop_reason=stop_reason,
+                    step_count=step_count,
+                    usage=usage.to_state(),
+                    final_message=final_message,
+                    files=files,
+                    shell_commands=self._shell_commands,
+                )
+            )
+            await asyncio.to_thread(atomic_json_write, document.model_dump(mode="json"), self._path)
+        except Exception:
+            logger.exception("Failed to write turn summary to {path}:", path=self._path)
+        finally:
+            self.begin_turn()
+
+    def _load(self) -> SummaryDocument:
+        try:
+            return SummaryDocument.model_validate(json.loads(self._path.read_text("utf-8")))
+        except FileNotFoundError:
+            return SummaryDocument()
+        except (ValueError, ValidationError):
+            logger.warning("Summary file {path} is invalid, starting it over", path=self._path)
+            return SummaryDocument()
@@ -0,0 +1,96 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from dataclasses import asdict, dataclass, field
+from typing import TYPE_CHECKING
+
+from kosong.chat_provider import TokenUsage
+
+from kimi_cli.session_state import UsageState
+
+if TYPE_CHECKING:
+    from kimi_cli.config import ModelPricing
+
+
+def estimate_cost(usage: TokenUsage, pricing: ModelPricing) -> float:
+    """Estimate the cost in USD of the given token usage."""
+    cache_read = pricing.cache_read if pricing.cache_read is not None else pricing.input
+    cache_creation = (
+        pricing.cache_creation if pricing.cache_creation is not None else pricing.input
+    )
+    return (
+        usage.input_other * pricing.input
+        + usage.input_cache_read * cache_read
+        + usage.input_cache_creation * cache_creation
+        + usage.output * pricing.output
+    ) / 1_000_000
+
+
+@dataclass(slots=True)
+class UsageTotals:
+    """Accumulated token usage and estimated cost."""
+
+    input_other: int = 0
+    input_cache_read: int = 0
+    input_cache_creation: int = 0
+    output: int = 0
+    cost: float = 0.0
+    """Estimated cost in USD of the LLM calls made on models with pricing."""
+    unpriced_calls: int = 0
+    """Number of LLM calls made on models without pricing, which are not included in `cost`."""
+
+    @property
+    def input(self) -> int:
+        return self.input_other + self.input_cache_read + self.input_cache_creation
+
+    def add(self, usage: TokenUsage, pricing: ModelPricing | None) -> None:
+        self.input_other += usage.input_other
+        self.input_cache_read += usage.input_cache_read
+        self.input_cache_creation += usage.input_cache_creation
+        self.output += usage.output
+        if pricing is None:
+            self.unpriced_calls += 1
+        else:
+            self.cost += estimate_cost(usage, pricing)
+
+    def to_token_usage(self) -> TokenUsage:
+        return TokenUsage(
+            input_other=self.input_other,
+            output=self.output,
+            input_cache_read=self.input_cache_read,
+            input_cache_creation=self.input_cache_creation,
+        )
+
+    def to_state(self) -> UsageState:
+        return UsageState.model_validate(asdict(self))
+
+    @staticmethod
+    def from_state(state: UsageState) -> UsageTotals:
+        return UsageTotals(**state.model_dump())
+
+
+@dataclass(slots=True, kw_only=True)
+class UsageTracker:
+    """Token usage of a session, shared by the main agent and its subagents."""
+
+    session: UsageTotals = field(default_factory=UsageTotals)
+    """Usage of all LLM calls in the session, including compaction."""
+    compaction: UsageTotals = field(default_factory=UsageTotals)
+    """Usage of the compaction LLM calls, also included in `session`."""
+    turn: UsageTotals = field(default_factory=UsageTotals)
+    """Usage of the current, or last finished, turn of the main agent."""
+    on_change: Callable[[UsageTracker], None] | None = None
+    """Called after each recorded LLM call, e.g. to persist the session totals."""
+
+    def begin_turn(self) -> None:
+        self.turn = UsageTotals()
+
+    def record(
+        self, usage: TokenUsage, pricing: ModelPricing | None, *, compaction: bool = False
+    ) -> None:
+        self.session.add(usage, pricing)
+        self.turn.add(usage, pricing)
+        if compaction:
+            self.compaction.add(usage, pricing)
+        if self.on_change is not None:
+            self.on_change(self)
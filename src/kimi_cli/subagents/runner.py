@@ -17,6 +17,7 @@ from kimi_cli.approval_runtime import (
 from kimi_cli.soul import MaxStepsReached, RunCancelled, UILoopFn, get_wire_or_none, run_soul
 from kimi_cli.soul.kimisoul import KimiSoul
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
+from kimi_cli.soul.usage import UsageTotals
 from kimi_cli.subagents.builder import SubagentBuilder
 from kimi_cli.subagents.core import SubagentRunSpec, prepare_soul
 from kimi_cli.subagents.models import AgentInstanceRecord, AgentLaunchSpec
@@ -30,6 +31,8 @@ from kimi_cli.wire.types import (
     ApprovalResponse,
     HookRequest,
     QuestionRequest,
+    StatusUpdate,
+    SubagentEnd,
     SubagentEvent,
     ToolCallRequest,
 )
@@ -173,6 +176,23 @@ async def run_with_summary_continuation(
     return final_response, None
 
 
+def limit_subagent_event_depth(event: SubagentEvent, max_depth: int) -> SubagentEvent:
+    """
+    Flatten the `SubagentEvent` envelopes nested below the `max_depth`-th one into it, so that
+    deeply nested subagents do not produce an ever deeper envelope chain.
+    """
+    if not isinstance(event.event, SubagentEvent):
+        return event
+    if max_depth > 1:
+        inner = limit_subagent_event_depth(event.event, max_depth - 1)
+        return event.model_copy(update={"event": inner})
+    depth, inner = event.depth, event.event
+    while isinstance(inner, SubagentEvent):
+        depth += inner.depth
+        inner = inner.event
+    return event.model_copy(update={"depth": depth, "event": inner})
+
+
 # ---------------------------------------------------------------------------
 # Foreground runner
 # ---------------------------------------------------------------------------
@@ -241,16 +261,36 @@ class ForegroundSubagentRunner:
         )
         approval_source: ApprovalSource | None = None
         approval_source_token = None
+        tool_call = get_current_tool_call_or_none()
+        parent_tool_call_id = tool_call.id if tool_call is not None else None
+        usage = UsageTotals()
+
+        def _send_end(status: SubagentEnd.Status, final_message: str | None = None) -> None:
+            super_wire = get_wire_or_none()
+            if super_wire is None or parent_tool_call_id is None:
+                return
+            super_wire.soul_side.send(
+                SubagentEnd(
+                    parent_tool_call_id=parent_tool_call_id,
+                    agent_id=agent_id,
+                    subagent_type=actual_type,
+                    status=status,
+                    final_message=final_message,
+                    usage=usage.to_token_usage(),
+                )
+            )
+
         try:
             # Propagate hook engine from parent runtime to subagent soul
             if self._runtime.hook_engine is not None:
                 soul.set_hook_engine(self._runtime.hook_engine)
-            tool_call = get_current_tool_call_or_none()
             ui_loop_fn = self._make_ui_loop_fn(
-                parent_tool_call_id=tool_call.id if tool_call is not None else None,
+                parent_tool_call_id=parent_tool_call_id,
                 agent_id=agent_id,
                 subagent_type=actual_type,
                 output_writer=output_writer,
+                usage=usage,
+                max_event_depth=self._runtime.config.loop_control.max_subagent_event_depth,
             )
 
             # Use a single stable ApprovalSource for the entire run (including summary
@@ -289,6 +329,7 @@ class ForegroundSubagentRunner:
             if failure is not None:
                 self._store.update_instance(agent_id, status="failed")
                 output_writer.stage(f"failed: {failure.brief}")
+                _send_end("failed")
                 return ToolError(message=failure.message, brief=failure.brief)
             output_writer.stage("run_soul_finished")
 
@@ -311,14 +352,17 @@ class ForegroundSubagentRunner:
         except asyncio.CancelledError:
             self._store.update_instance(agent_id, status="killed")
             output_writer.stage("cancelled")
+            _send_end("cancelled")
             raise
         except RunCancelled as exc:
             self._store.update_instance(agent_id, status="killed")
             output_writer.stage("cancelled")
+            _send_end("cancelled")
             raise RunCancelled("Subagent run was cancelled.") from exc
         except Exception:
             self._store.update_instance(agent_id, status="failed")
             output_writer.stage("failed_exception")
+            _send_end("failed")
             raise
         finally:
             if approval_source_token is not None:
@@ -331,12 +375,14 @@ class ForegroundSubagentRunner:
         if final_response is None:
             self._store.update_instance(agent_id, status="failed")
             output_writer.stage("failed: empty output")
+            _send_end("failed")
             return ToolError(
                 message="Agent completed but produced no output.",
                 brief="Empty agent output",
             )
         self._store.update_instance(agent_id, status="idle")
         output_writer.summary(final_response)
+        _send_end("completed", final_response)
         lines = [
             f"agent_id: {agent_id}",
             "resumed: true" if resumed else "resumed: false",
@@ -397,6 +443,8 @@ class ForegroundSubagentRunner:
         agent_id: str,
         subagent_type: str,
         output_writer: SubagentOutputWriter,
+        usage: UsageTotals,
+        max_event_depth: int,
     ):
         super_wire = get_wire_or_none()
 
@@ -406,6 +454,12 @@ class ForegroundSubagentRunner:
                 msg = await wire_ui.receive()
                 # Always write to output file regardless of wire availability.
                 output_writer.write_wire_message(msg)
+                # Nested subagents report their usage once, in the `SubagentEnd` sent by their
+                # runner; their own steps only reach this wire wrapped in `SubagentEvent`.
+                if isinstance(msg, StatusUpdate) and msg.token_usage is not None:
+                    usage.add(msg.token_usage, None)
+                elif isinstance(msg, SubagentEnd) and msg.usage is not None:
+                    usage.add(msg.usage, None)
                 if super_wire is None or parent_tool_call_id is None:
                     continue
                 if isinstance(
@@ -416,13 +470,14 @@ class ForegroundSubagentRunner:
                     continue
                 if isinstance(msg, HookRequest):
                     continue
-                super_wire.soul_side.send(
-                    SubagentEvent(
-                        parent_tool_call_id=parent_tool_call_id,
-                        agent_id=agent_id,
-                        subagent_type=subagent_type,
-                        event=msg,
-                    )
+                event = SubagentEvent(
+                    parent_tool_call_id=parent_tool_call_id,
+                    agent_id=agent_id,
+                    subagent_type=subagent_type,
+                    event=msg,
                 )
+                if isinstance(msg, SubagentEvent):
+                    event = limit_subagent_event_depth(event, max_event_depth)
+                super_wire.soul_side.send(event)
 
         return _ui_loop_fn
@@ -200,21 +200,24 @@ class AsyncTransport:
 
     async def _send_http(self, payload: dict[str, Any]) -> None:
         """Attempt HTTP POST with 401 anonymous fallback."""
-        from kimi_cli.utils.aiohttp import new_client_session
+        from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 
         token = self._get_access_token() if self._get_access_token else None
         headers = {"Content-Type": "application/json"}
         if token:
             headers["Authorization"] = f"Bearer {token}"
+        proxy = request_proxy(self._endpoint)
 
         async with new_client_session(timeout=SEND_TIMEOUT) as session:
             try:
-                async with session.post(self._endpoint, json=payload, headers=headers) as resp:
+                async with session.post(
+                    self._endpoint, proxy=proxy, json=payload, headers=headers
+                ) as resp:
                     if resp.status == 401 and token:
                         # Auth failed — retry without token (anonymous)
                         headers.pop("Authorization", None)
                         async with session.post(
-                            self._endpoint, json=payload, headers=headers
+                            self._endpoint, proxy=proxy, json=payload, headers=headers
                         ) as retry_resp:
                             if retry_resp.status >= 500 or retry_resp.status == 429:
                                 raise _TransientError(f"HTTP {retry_resp.status}")
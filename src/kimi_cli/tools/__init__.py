@@ -14,6 +14,13 @@ class SkipThisTool(Exception):
     pass
 
 
+class ExemptFromTimeout:
+    """
+    Mixin for tools that are not stopped after `tools.timeout_s`, because they have a timeout of
+    their own or wait for the user.
+    """
+
+
 def extract_key_argument(json_content: str | streamingjson.Lexer, tool_name: str) -> str | None:
     if isinstance(json_content, streamingjson.Lexer):
         json_str = json_content.complete_json()
@@ -67,7 +74,7 @@ def extract_key_argument(json_content: str | streamingjson.Lexer, tool_name: str
             if not isinstance(curr_args, dict) or not curr_args.get("pattern"):
                 return None
             key_argument = str(curr_args["pattern"])
-        case "Grep":
+        case "Grep" | "FindInContext":
             if not isinstance(curr_args, dict) or not curr_args.get("pattern"):
                 return None
             key_argument = str(curr_args["pattern"])
@@ -79,6 +86,43 @@ def extract_key_argument(json_content: str | streamingjson.Lexer, tool_name: str
             if not isinstance(curr_args, dict) or not curr_args.get("path"):
                 return None
             key_argument = _normalize_path(str(curr_args["path"]))
+        case "MultiStrReplaceFile":
+            files = curr_args.get("files") if isinstance(curr_args, dict) else None
+            if not isinstance(files, list):
+                return None
+            file_paths = [
+                _normalize_path(str(file["path"]))
+                for file in files
+                if isinstance(file, dict) and file.get("path")
+            ]
+            if not file_paths:
+                return None
+            key_argument = file_paths[0]
+            if len(file_paths) > 1:
+                key_argument += f" (+{len(file_paths) - 1} more)"
+        case "NotebookRead" | "ReadTable" | "ReadPdf":
+            if not isinstance(curr_args, dict) or not curr_args.get("path"):
+                return None
+            key_argument = _normalize_path(str(curr_args["path"]))
+        case "NotebookEdit":
+            if not isinstance(curr_args, dict) or not curr_args.get("path"):
+                return None
+            key_argument = _normalize_path(str(curr_args["path"]))
+            if "cell_index" in curr_args:
+                key_argument += f"#{curr_args['cell_index']}"
+        case "GoToDefinition" | "FindReferences":
+            if not isinstance(curr_args, dict) or not curr_args.get("path"):
+                return None
+            key_argument = _normalize_path(str(curr_args["path"]))
+            if curr_args.get("line"):
+                key_argument += f":{curr_args['line']}"
+        case "Git":
+            if not isinstance(curr_args, dict) or not curr_args.get("op"):
+                return None
+            key_argument = str(curr_args["op"])
+            paths = curr_args.get("paths")
+            if curr_args["op"] == "diff" and isinstance(paths, list) and paths:
+                key_argument += " " + " ".join(str(path) for path in paths)
         case "SearchWeb":
             if not isinstance(curr_args, dict) or not curr_args.get("query"):
                 return None
@@ -9,6 +9,7 @@ from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
 from kimi_cli.subagents.models import AgentLaunchSpec, AgentTypeDefinition
 from kimi_cli.subagents.runner import ForegroundRunRequest, ForegroundSubagentRunner
+from kimi_cli.tools import ExemptFromTimeout
 from kimi_cli.tools.utils import load_desc
 from kimi_cli.utils.logging import logger
 
@@ -62,7 +63,7 @@ class Params(BaseModel):
         return self.timeout
 
 
-class AgentTool(CallableTool2[Params]):
+class AgentTool(CallableTool2[Params], ExemptFromTimeout):
     name: str = NAME
     params: type[Params] = Params
 
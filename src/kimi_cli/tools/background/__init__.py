@@ -8,8 +8,9 @@ from pydantic import BaseModel, Field
 from kimi_cli.background import TaskView, format_task, format_task_list, list_task_views
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
+from kimi_cli.tools import ExemptFromTimeout
 from kimi_cli.tools.display import BackgroundTaskDisplayBlock
-from kimi_cli.tools.utils import load_desc
+from kimi_cli.tools.utils import dry_run_result, load_desc
 
 TASK_OUTPUT_PREVIEW_BYTES = 32 << 10
 TASK_OUTPUT_READ_HINT_LINES = 300
@@ -179,7 +180,7 @@ class TaskList(CallableTool2[TaskListParams]):
         )
 
 
-class TaskOutput(CallableTool2[TaskOutputParams]):
+class TaskOutput(CallableTool2[TaskOutputParams], ExemptFromTimeout):
     name: str = "TaskOutput"
     description: str = load_desc(Path(__file__).parent / "output.md")
     params: type[TaskOutputParams] = TaskOutputParams
@@ -297,6 +298,12 @@ class TaskStop(CallableTool2[TaskStopParams]):
         if view is None:
             return ToolError(message=f"Task not found: {params.task_id}", brief="Task not found")
 
+        if self._runtime.dry_run:
+            return dry_run_result(
+                f"Stop background task `{params.task_id}`",
+                [_task_display(self._runtime, params.task_id)],
+            )
+
         result = await self._approval.request(
             self.name,
             "stop background task",
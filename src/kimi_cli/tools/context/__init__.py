@@ -0,0 +1,95 @@
+import re
+from pathlib import Path
+from typing import Literal, override
+
+from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
+from pydantic import BaseModel, Field
+
+from kimi_cli.soul.context import Context, ContextMatch
+from kimi_cli.soul.message import is_dmail_message
+from kimi_cli.tools.utils import load_desc
+
+MAX_OUTPUT_BYTES = 16_000
+CONTEXT_LINES = 2
+
+
+class Params(BaseModel):
+    pattern: str = Field(description="The regular expression to search for.")
+    role: Literal["user", "assistant", "tool"] | None = Field(
+        default=None,
+        description="Only search messages of this role. Defaults to all roles.",
+    )
+    limit: int = Field(
+        default=20,
+        ge=1,
+        le=100,
+        description="Maximum number of matches to return.",
+    )
+
+
+class FindInContext(CallableTool2[Params]):
+    name: str = "FindInContext"
+    description: str = load_desc(Path(__file__).parent / "find.md")
+    params: type[Params] = Params
+
+    def __init__(self) -> None:
+        super().__init__()
+        self._context: Context | None = None
+
+    def bind(self, context: Context) -> None:
+        """Late-bind the context of the soul after KimiSoul is constructed."""
+        self._context = context
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        if self._context is None:
+            return ToolError(
+                message="FindInContext is not properly initialized.",
+                brief="Not initialized",
+            )
+        try:
+            pattern = re.compile(params.pattern)
+        except re.error as e:
+            return ToolError(
+                message=f"Invalid regular expression `{params.pattern}`: {e}",
+                brief="Invalid pattern",
+            )
+
+        entries: list[str] = []
+        n_bytes = 0
+        over_budget = False
+        for match in self._context.search(
+            pattern, role=params.role, context_lines=CONTEXT_LINES, skip=is_dmail_message
+        ):
+            if len(entries) >= params.limit:
+                break
+            entry = _format_match(match)
+            n_bytes += len(entry.encode("utf-8"))
+            if entries and n_bytes > MAX_OUTPUT_BYTES:
+                over_budget = True
+                break
+            entries.append(entry)
+
+        if not entries:
+            return ToolOk(output="", message="No matches found.", brief="No matches")
+        message = f"Found {len(entries)} match(es), newest first."
+        if over_budget:
+            message += " More matches were left out to fit in the output; narrow the pattern."
+        return ToolOk(
+            output="\n\n".join(entries),
+            message=message,
+            brief=f"{len(entries)} match(es)",
+        )
+
+
+def _format_match(match: ContextMatch) -> str:
+    where = f"message {match.index}"
+    if match.checkpoint_id is not None:
+        where += f", checkpoint {match.checkpoint_id}"
+    if match.tool_name is not None:
+        where += f", call to {match.tool_name} ({match.tool_call_id})"
+    elif match.tool_call_id is not None:
+        where += f", result of {match.tool_call_id}"
+    else:
+        where += f", {match.role}"
+    return f"[{where}] line {match.line_no}:\n{match.excerpt}"
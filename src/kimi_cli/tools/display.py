@@ -1,7 +1,28 @@
-from typing import Literal
+from typing import Any, Literal
 
 from kosong.tooling import DisplayBlock
-from pydantic import BaseModel
+from pydantic import BaseModel, Field, SerializerFunctionWrapHandler, model_serializer
+
+
+class DiffWordChange(BaseModel):
+    """The changed parts of a line changed in place, as character ranges `[start, end)`."""
+
+    old_line: int
+    """Line number of the line in the old text, 1-based."""
+    new_line: int
+    """Line number of the line in the new text, 1-based."""
+    old_ranges: list[tuple[int, int]]
+    new_ranges: list[tuple[int, int]]
+
+
+class DiffHunk(BaseModel):
+    """A changed region of a file, with its context lines."""
+
+    old_text: str
+    new_text: str
+    old_start: int = 1
+    new_start: int = 1
+    word_changes: list[DiffWordChange] = Field(default_factory=list[DiffWordChange])
 
 
 class DiffDisplayBlock(DisplayBlock):
@@ -14,6 +35,31 @@ class DiffDisplayBlock(DisplayBlock):
     old_start: int = 1
     new_start: int = 1
     is_summary: bool = False
+    word_changes: list[DiffWordChange] = Field(default_factory=list[DiffWordChange])
+    """The lines of the block changed in place, with their changed parts."""
+    truncated: bool = False
+    """
+    Whether the file was too large to send its changes as text: `old_text` and `new_text` hold
+    the line counts as for `is_summary`, and `hunks` the changed regions.
+    """
+    hunks: list[DiffHunk] | None = None
+    """The changed regions of a `truncated` diff, up to a size limit."""
+    omitted_hunks: int = 0
+    """The number of changed regions of a `truncated` diff left out past the size limit."""
+
+    @model_serializer(mode="wrap")
+    def _serialize(self, handler: SerializerFunctionWrapHandler) -> dict[str, Any]:
+        # Fields added in Wire 1.11 are only sent when set, so older clients see the same blocks
+        data = handler(self)
+        if not self.word_changes:
+            data.pop("word_changes", None)
+        if not self.truncated:
+            data.pop("truncated", None)
+        if self.hunks is None:
+            data.pop("hunks", None)
+        if not self.omitted_hunks:
+            data.pop("omitted_hunks", None)
+        return data
 
 
 class TodoDisplayItem(BaseModel):
@@ -28,12 +74,37 @@ class TodoDisplayBlock(DisplayBlock):
     items: list[TodoDisplayItem]
 
 
+type ShellRiskIndicator = Literal["write", "network", "privilege_escalation"]
+
+
+class ShellCommandInfo(BaseModel):
+    """One simple command of a shell command line."""
+
+    text: str
+    """The source text of the command."""
+    programs: list[str]
+    """The programs it invokes, wrappers like `sudo` first. Empty if it only sets variables."""
+    indicators: list[ShellRiskIndicator] = Field(default_factory=list[ShellRiskIndicator])
+
+
+class ShellCommandAnalysis(BaseModel):
+    """A summary of a shell command line, parsed to help review it before approval."""
+
+    commands: list[ShellCommandInfo]
+    """The simple commands, including those in subshells, command substitutions and `sh -c`."""
+    indicators: list[ShellRiskIndicator] = Field(default_factory=list[ShellRiskIndicator])
+    """The indicators of all the commands."""
+    outside_redirects: list[str] = Field(default_factory=list[str])
+    """The targets of output redirections that are not inside the workspace."""
+
+
 class ShellDisplayBlock(DisplayBlock):
     """Display block describing a shell command."""
 
     type: str = "shell"
     language: str
     command: str
+    analysis: ShellCommandAnalysis | None = None
 
 
 class BackgroundTaskDisplayBlock(DisplayBlock):
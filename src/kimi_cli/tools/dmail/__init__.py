@@ -3,10 +3,12 @@ from typing import override
 
 from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
 
+from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.denwarenji import DenwaRenji, DenwaRenjiError, DMail
 from kimi_cli.tools.utils import load_desc
 
 NAME = "SendDMail"
+RESTORE_FILES_ACTION = "restore files to checkpoint"
 
 
 class SendDMail(CallableTool2[DMail]):
@@ -14,12 +16,36 @@ class SendDMail(CallableTool2[DMail]):
     description: str = load_desc(Path(__file__).parent / "dmail.md")
     params: type[DMail] = DMail
 
-    def __init__(self, denwa_renji: DenwaRenji) -> None:
+    def __init__(self, denwa_renji: DenwaRenji, runtime: Runtime) -> None:
         super().__init__()
         self._denwa_renji = denwa_renji
+        self._runtime = runtime
 
     @override
     async def __call__(self, params: DMail) -> ToolReturnValue:
+        if params.restore_files:
+            # The journal follows the checkpoints of the root agent only.
+            journal = self._runtime.file_journal
+            if journal is None or self._runtime.role != "root":
+                return ToolError(
+                    output="",
+                    message=(
+                        "Failed to send D-Mail. Error: files cannot be restored because the "
+                        "file journal is not enabled. Send the D-Mail without `restore_files`."
+                    ),
+                    brief="Failed to send D-Mail",
+                )
+            paths = journal.changed_paths(params.checkpoint_id)
+            if paths:
+                result = await self._runtime.approval.request(
+                    self.name,
+                    RESTORE_FILES_ACTION,
+                    f"Restore {len(paths)} file(s) to checkpoint {params.checkpoint_id}:\n"
+                    + "\n".join(f"- {path}" for path in paths),
+                )
+                if not result:
+                    # Declining the restore does not cancel the D-Mail itself.
+                    params = params.model_copy(update={"restore_files": False})
         try:
             self._denwa_renji.send_dmail(params)
         except DenwaRenjiError as e:
@@ -0,0 +1,96 @@
+from pathlib import Path
+from typing import override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolReturnValue
+from kosong.utils.typing import JsonType
+from pydantic import BaseModel
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.git import read_git_status, run_git
+from kimi_cli.tools.utils import ToolResultBuilder, load_desc
+from kimi_cli.utils.logging import logger
+
+
+class Params(BaseModel):
+    pass
+
+
+class GetEnvironment(CallableTool2[Params]):
+    name: str = "GetEnvironment"
+    description: str = load_desc(Path(__file__).parent / "description.md")
+    params: type[Params] = Params
+
+    def __init__(self, runtime: Runtime):
+        super().__init__()
+        self._runtime = runtime
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        runtime = self._runtime
+        environment = runtime.environment
+        work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        git_line, git_json = await _git_summary(work_dir)
+
+        lines = [f"working directory: {work_dir}"]
+        if runtime.additional_dirs:
+            lines.append(
+                "additional directories: " + ", ".join(str(d) for d in runtime.additional_dirs)
+            )
+        lines.append(f"os: {environment.os_kind} {environment.os_version} ({environment.os_arch})")
+        lines.append(f"shell: {environment.shell_name} ({environment.shell_path})")
+        lines.append(f"git: {git_line}")
+        skills = sorted(runtime.skills.values(), key=lambda skill: skill.name)
+        if skills:
+            lines.append("skills:")
+            for skill in skills:
+                summary = skill.description.strip().splitlines()
+                lines.append(f"  {skill.name}: {summary[0]}" if summary else f"  {skill.name}")
+        else:
+            lines.append("skills: none")
+
+        builder = ToolResultBuilder()
+        builder.write("\n".join(lines) + "\n")
+        builder.extras(
+            environment={
+                "work_dir": str(work_dir),
+                "additional_dirs": [str(d) for d in runtime.additional_dirs],
+                "os_kind": environment.os_kind,
+                "os_arch": environment.os_arch,
+                "os_version": environment.os_version,
+                "shell_name": environment.shell_name,
+                "shell_path": str(environment.shell_path),
+                "git": git_json,
+                "skills": [skill.name for skill in skills],
+            }
+        )
+        return builder.ok(brief=f"{environment.os_kind}, {environment.shell_name}")
+
+
+async def _git_summary(work_dir: KaosPath) -> tuple[str, JsonType]:
+    """Summarize the git state of `work_dir` in one line, and as JSON (None outside a repo)."""
+    try:
+        code, out, _ = await run_git(work_dir, "rev-parse", "--is-inside-work-tree")
+        if code != 0 or out.strip() != "true":
+            return "not a git repository", None
+        status = await read_git_status(work_dir)
+    except Exception as e:
+        # git may be missing, slow or broken; the rest of the environment is still useful.
+        logger.debug("Failed to read git status of {work_dir}: {error}", work_dir=work_dir, error=e)
+        return f"unavailable ({str(e) or type(e).__name__})", None
+
+    summary = f"on branch {status.branch}" if status.branch else "detached HEAD"
+    if status.upstream:
+        summary += f", tracking {status.upstream} (ahead {status.ahead}, behind {status.behind})"
+    counts = [
+        f"{len(entries)} {title}"
+        for title, entries in (
+            ("staged", status.staged),
+            ("unstaged", status.unstaged),
+            ("untracked", status.untracked),
+            ("conflicted", status.conflicted),
+        )
+        if entries
+    ]
+    summary += "; " + (", ".join(counts) if counts else "working tree clean")
+    return summary, status.to_json()
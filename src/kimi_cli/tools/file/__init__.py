@@ -15,9 +15,13 @@ class FileActions(StrEnum):
 
 from .glob import Glob  # noqa: E402
 from .grep_local import Grep  # noqa: E402
+from .multi_replace import MultiStrReplaceFile  # noqa: E402
+from .notebook import NotebookEdit, NotebookRead  # noqa: E402
+from .pdf import ReadPdf  # noqa: E402
 from .read import ReadFile  # noqa: E402
 from .read_media import ReadMediaFile  # noqa: E402
 from .replace import StrReplaceFile  # noqa: E402
+from .table import ReadTable  # noqa: E402
 from .write import WriteFile  # noqa: E402
 
 __all__ = (
@@ -27,4 +31,9 @@ __all__ = (
     "Grep",
     "WriteFile",
     "StrReplaceFile",
+    "NotebookRead",
+    "NotebookEdit",
+    "ReadTable",
+    "ReadPdf",
+    "MultiStrReplaceFile",
 )
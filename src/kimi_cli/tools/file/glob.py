@@ -1,23 +1,30 @@
 """Glob tool implementation."""
 
-from pathlib import Path
+import asyncio
+from pathlib import Path, PurePath
 from typing import override
 
+import kaos
 from kaos.path import KaosPath
 from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
 from pydantic import BaseModel, Field
 
 from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.file.utils import VCS_DIRS, unreadable_entries_message
 from kimi_cli.tools.utils import load_desc
 from kimi_cli.utils.logging import logger
 from kimi_cli.utils.path import (
+    format_workspace_roots,
     is_within_directory,
     is_within_workspace,
     kaos_path_from_user_input,
     list_directory,
+    outside_workspace_hint,
+    resolve_workspace_root,
 )
 
 MAX_MATCHES = 1000
+GIT_LS_FILES_TIMEOUT = 10  # seconds
 GLOB_DESC_PATH = Path(__file__).parent / "glob.md"
 WINDOWS_PATH_HINT = (
     "On Windows, the `directory` parameter accepts both Windows native paths "
@@ -46,10 +53,64 @@ class Params(BaseModel):
         ),
         default=None,
     )
+    root: int | str | None = Field(
+        description=(
+            "The workspace root to search in, given by its index (0 is the working directory, "
+            "the additional directories follow in the order they are listed) or by its path. "
+            "When set, `directory` may be relative to this root. Defaults to the working "
+            "directory."
+        ),
+        default=None,
+    )
     include_dirs: bool = Field(
         description="Whether to include directories in results.",
         default=True,
     )
+    include_ignored: bool = Field(
+        description=(
+            "Include paths that are ignored by `.gitignore`, `.git/info/exclude`, and the "
+            "global git excludes file. Useful for looking into gitignored artifacts such as "
+            "build outputs or `node_modules`. VCS directories like `.git` are always skipped. "
+            "Defaults to false."
+        ),
+        default=False,
+    )
+
+
+async def _git_ignored_paths(directory: KaosPath) -> set[PurePath]:
+    """
+    Return the untracked paths under `directory` (relative to it) that git ignores. A directory
+    that is ignored as a whole is listed once, without its contents, so that git does not walk
+    it either.
+
+    Returns an empty set when `directory` is not inside a git work tree or git is unavailable,
+    so that the search falls back to full traversal.
+    """
+    proc = None
+    try:
+        proc = await kaos.exec(
+            "git",
+            "-C",
+            str(directory),
+            "ls-files",
+            "-z",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        )
+        proc.stdin.close()
+        stdout = await asyncio.wait_for(proc.stdout.read(-1), timeout=GIT_LS_FILES_TIMEOUT)
+        # 128: not a git work tree or another error.
+        if await proc.wait() != 0:
+            return set()
+        return {PurePath(p) for p in stdout.decode("utf-8", errors="replace").split("\0") if p}
+    except Exception as e:
+        logger.debug("git ls-files failed in {directory}: {error}", directory=directory, error=e)
+        if proc is not None and proc.returncode is None:
+            await proc.kill()
+            await proc.wait()
+        return set()
 
 
 class Glob(CallableTool2[Params]):
@@ -97,6 +158,7 @@ class Glob(CallableTool2[Params]):
                 f"`{directory}` is outside the workspace. "
                 "You can only search within the working directory, "
                 "additional directories, and skills directories."
+                + outside_workspace_hint(self._work_dir, self._additional_dirs)
             ),
             brief="Directory outside workspace",
         )
@@ -109,15 +171,33 @@ class Glob(CallableTool2[Params]):
             if pattern_error:
                 return pattern_error
 
+            base_dir = self._work_dir
+            if params.root is not None:
+                root_dir = resolve_workspace_root(
+                    params.root, self._work_dir, self._additional_dirs
+                )
+                if root_dir is None:
+                    return ToolError(
+                        message=(
+                            f"`{params.root}` is not a workspace root. The workspace roots are "
+                            f"{format_workspace_roots(self._work_dir, self._additional_dirs)}."
+                        ),
+                        brief="Invalid root",
+                    )
+                base_dir = root_dir
+
             dir_path = (
-                kaos_path_from_user_input(params.directory) if params.directory else self._work_dir
+                kaos_path_from_user_input(params.directory) if params.directory else base_dir
             )
+            if params.root is not None and not dir_path.is_absolute():
+                dir_path = base_dir / dir_path
 
             if not dir_path.is_absolute():
                 return ToolError(
                     message=(
                         f"`{params.directory}` is not an absolute path. "
                         "You must provide an absolute path to search."
+                        + outside_workspace_hint(self._work_dir, self._additional_dirs)
                     ),
                     brief="Invalid directory",
                 )
@@ -127,45 +207,84 @@ class Glob(CallableTool2[Params]):
             if dir_error:
                 return dir_error
 
-            if not await dir_path.exists():
+            dir_metadata = await dir_path.metadata()
+            if not dir_metadata.exists:
                 return ToolError(
                     message=f"`{params.directory}` does not exist.",
                     brief="Directory not found",
                 )
-            if not await dir_path.is_dir():
+            if not dir_metadata.is_dir:
                 return ToolError(
                     message=f"`{params.directory}` is not a directory.",
                     brief="Invalid directory",
                 )
 
-            # Perform the glob search - users can use ** directly in pattern
+            # Skip VCS metadata and, unless requested otherwise, gitignored paths, without
+            # walking into them
+            ignored = set() if params.include_ignored else await _git_ignored_paths(dir_path)
+
+            def exclude(path: KaosPath) -> bool:
+                return path.name in VCS_DIRS or PurePath(str(path.relative_to(dir_path))) in ignored
+
+            # Perform the glob search - users can use ** directly in pattern. Directories that
+            # cannot be read are skipped and reported, unless it is the searched directory itself
             matches: list[KaosPath] = []
-            async for match in dir_path.glob(params.pattern):
+            errors: list[OSError] = []
+            async for match in dir_path.glob(
+                params.pattern, on_error=errors.append, exclude=exclude
+            ):
                 matches.append(match)
+            for error in errors:
+                if error.filename is not None and KaosPath(str(error.filename)) == dir_path:
+                    return ToolError(
+                        message=f"Failed to read `{params.directory}`. Error: {error}",
+                        brief="Directory unreadable",
+                    )
 
             # Filter out directories if not requested
             if not params.include_dirs:
-                matches = [p for p in matches if await p.is_file()]
+                matches = [p for p in matches if (await p.metadata()).is_file]
 
             # Sort for consistent output
             matches.sort()
 
-            # Limit matches
+            # Limit matches, but keep the total so the model knows what it is missing
+            total = len(matches)
             message = (
-                f"Found {len(matches)} matches for pattern `{params.pattern}`."
-                if len(matches) > 0
+                f"Found {total} matches for pattern `{params.pattern}`."
+                if total > 0
                 else f"No matches found for pattern `{params.pattern}`."
             )
-            if len(matches) > MAX_MATCHES:
+            brief = f"{total} matches"
+            if total > MAX_MATCHES:
                 matches = matches[:MAX_MATCHES]
-                message += (
-                    f" Only the first {MAX_MATCHES} matches are returned. "
+                brief = f"showing {MAX_MATCHES} of {total} matches"
+                message = (
+                    f"Found {total} matches for pattern `{params.pattern}`, {brief}. "
                     "You may want to use a more specific pattern."
                 )
 
+            if errors:
+                unreadable: list[str] = []
+                for error in errors:
+                    if error.filename is None:
+                        unreadable.append(error.strerror or str(error))
+                        continue
+                    error_path = KaosPath(str(error.filename))
+                    unreadable.append(
+                        str(error_path.relative_to(dir_path))
+                        if is_within_directory(error_path, dir_path)
+                        else str(error_path)
+                    )
+                message += " " + unreadable_entries_message(
+                    unreadable,
+                    permission_errors=all(isinstance(e, PermissionError) for e in errors),
+                )
+
             return ToolOk(
                 output="\n".join(str(p.relative_to(dir_path)) for p in matches),
                 message=message,
+                brief=brief,
             )
 
         except Exception as e:
@@ -16,15 +16,22 @@ from pathlib import Path
 from typing import override
 
 import aiohttp
+from kaos import SNIFF_BYTES, is_binary
 from kosong.tooling import CallableTool2, ToolError, ToolReturnValue
 from pydantic import BaseModel, Field
 
 import kimi_cli
 from kimi_cli.share import get_share_dir
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.file.utils import VCS_DIRS, unreadable_entries_message
 from kimi_cli.tools.utils import ToolResultBuilder, load_desc
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.path import normalize_user_path
+from kimi_cli.utils.path import (
+    format_workspace_roots,
+    kaos_path_from_user_input,
+    resolve_workspace_root,
+)
 from kimi_cli.utils.sensitive import is_sensitive_file, sensitive_file_warning
 
 
@@ -39,6 +46,14 @@ class Params(BaseModel):
         ),
         default=".",
     )
+    root: int | str | None = Field(
+        description=(
+            "The workspace root to search in, given by its index (0 is the working directory, "
+            "the additional directories follow in the order they are listed) or by its path. "
+            "When set, `path` may be relative to this root. Defaults to the working directory."
+        ),
+        default=None,
+    )
     glob: str | None = Field(
         description=(
             "Glob pattern to filter files (e.g. `*.js`, `*.{ts,tsx}`). No filter by default."
@@ -135,6 +150,15 @@ class Params(BaseModel):
         ),
         default=False,
     )
+    binary: bool = Field(
+        description=(
+            "Search binary files as if they were text (the `--text` option). By default, "
+            "files with a NUL byte near the start are treated as binary: they are skipped in "
+            "`content` mode and marked `(binary file matches)` in `files_with_matches` mode. "
+            "Defaults to false."
+        ),
+        default=False,
+    )
 
 
 RG_VERSION = "15.0.0"
@@ -213,7 +237,7 @@ async def _download_and_install_rg(bin_name: str) -> Path:
             tar_path = Path(tmpdir) / filename
 
             try:
-                async with session.get(url) as resp:
+                async with session.get(url, proxy=request_proxy(url)) as resp:
                     resp.raise_for_status()
                     with open(tar_path, "wb") as fh:
                         async for chunk in resp.content.iter_chunked(1024 * 64):
@@ -279,7 +303,10 @@ def _build_rg_args(rg_path: str, params: Params, *, single_threaded: bool = Fals
     args.append("--hidden")
     if params.include_ignored:
         args.append("--no-ignore")
-    for vcs_dir in (".git", ".svn", ".hg", ".bzr", ".jj", ".sl"):
+    # Search binary files to the end instead of silently quitting at the first NUL byte,
+    # so that their matches can be reported (or their lines printed with `--text`).
+    args.append("--text" if params.binary else "--binary")
+    for vcs_dir in VCS_DIRS:
         args.extend(["--glob", f"!{vcs_dir}"])
 
     if single_threaded:
@@ -317,7 +344,7 @@ def _build_rg_args(rg_path: str, params: Params, *, single_threaded: bool = Fals
     # Separate pattern from flags to avoid ambiguity (e.g. pattern starting with -)
     args.append("--")
     args.append(params.pattern)
-    args.append(os.path.expanduser(normalize_user_path(params.path)))
+    args.append(str(kaos_path_from_user_input(params.path)))
 
     return args
 
@@ -374,6 +401,30 @@ def _is_eagain(stderr: str) -> bool:
     return "os error 11" in stderr or "Resource temporarily unavailable" in stderr
 
 
+_RG_ENTRY_ERROR_RE = re.compile(r"^rg: (.+): ([^:]+) \(os error (\d+)\)$")
+"""An I/O error ripgrep reports for a single path, e.g. a directory it may not read."""
+
+_PERMISSION_ERRNOS = {1, 5, 13}
+"""EPERM, EACCES, and Windows' ERROR_ACCESS_DENIED."""
+
+
+def _parse_rg_entry_errors(stderr: str) -> tuple[list[tuple[str, int]], list[str]]:
+    """
+    Split ripgrep's stderr into per-path I/O errors, as `(path, errno)`, and any other lines.
+    """
+    entry_errors: list[tuple[str, int]] = []
+    other_lines: list[str] = []
+    for line in stderr.splitlines():
+        if not line.strip():
+            continue
+        m = _RG_ENTRY_ERROR_RE.match(line)
+        if m:
+            entry_errors.append((m.group(1), int(m.group(3))))
+        else:
+            other_lines.append(line)
+    return entry_errors, other_lines
+
+
 def _strip_path_prefix(output: str, search_base: str) -> str:
     """Strip search_base prefix from each line to produce relative paths."""
     prefix = search_base.rstrip("/\\") + os.sep
@@ -382,14 +433,52 @@ def _strip_path_prefix(output: str, search_base: str) -> str:
     )
 
 
+_RG_BINARY_NOTICE_RE = re.compile(
+    r"^(?:(.*): )?(?:binary file matches|WARNING: stopped searching binary file after match) "
+    r"\(found .*\)$"
+)
+"""The notice ripgrep prints in place of (or after) the matching lines of a binary file."""
+
+
+def _is_binary_file(path: str) -> bool:
+    """Whether the file looks binary, using the same NUL-byte sniff as `detect_file_type`."""
+    try:
+        with open(path, "rb") as f:
+            return is_binary(f.read(SNIFF_BYTES))
+    except OSError:
+        return False
+
+
 class Grep(CallableTool2[Params]):
     name: str = "Grep"
     description: str = load_desc(Path(__file__).parent / "grep.md")
     params: type[Params] = Params
 
+    def __init__(self, runtime: Runtime) -> None:
+        super().__init__()
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+
     @override
     async def __call__(self, params: Params, *, _retry: bool = False) -> ToolReturnValue:
         try:
+            if params.root is not None:
+                root_dir = resolve_workspace_root(
+                    params.root, self._work_dir, self._additional_dirs
+                )
+                if root_dir is None:
+                    return ToolError(
+                        message=(
+                            f"`{params.root}` is not a workspace root. The workspace roots are "
+                            f"{format_workspace_roots(self._work_dir, self._additional_dirs)}."
+                        ),
+                        brief="Invalid root",
+                    )
+                path = kaos_path_from_user_input(params.path)
+                if not path.is_absolute():
+                    path = (root_dir / path).canonical()
+                params = params.model_copy(update={"path": str(path), "root": None})
+
             builder = ToolResultBuilder()
             message = ""
 
@@ -457,16 +546,31 @@ class Grep(CallableTool2[Params]):
                 timeout_msg = f"Grep timed out after {RG_TIMEOUT}s. Partial results returned."
                 message = f"{message} {timeout_msg}" if message else timeout_msg
 
-            # rg exit codes: 0=matches found, 1=no matches, 2+=error
+            search_path = os.path.abspath(str(kaos_path_from_user_input(params.path)))
+            searched_file = search_path if os.path.isfile(search_path) else None
+            search_base = os.path.dirname(search_path) if searched_file else search_path
+
+            # rg exit codes: 0=matches found, 1=no matches, 2+=error. Errors on single
+            # entries (unreadable directories, files removed during the search) also exit
+            # with 2; those are skipped and reported, unless the searched path itself failed.
+            entry_errors, other_errors = _parse_rg_entry_errors(stderr_str)
             if not timed_out and process.returncode not in (0, 1):
                 # EAGAIN: retry once with single-threaded mode
                 if not _retry and _is_eagain(stderr_str):
                     logger.warning("rg EAGAIN error, retrying with -j 1")
                     return await self.__call__(params, _retry=True)
-                return ToolError(
-                    message=f"Failed to grep. Error: {stderr_str}",
-                    brief="Failed to grep",
+                root_failed = any(os.path.normpath(path) == search_path for path, _ in entry_errors)
+                if other_errors or not entry_errors or root_failed:
+                    return ToolError(
+                        message=f"Failed to grep. Error: {stderr_str}",
+                        brief="Failed to grep",
+                    )
+            if entry_errors:
+                unreadable_msg = unreadable_entries_message(
+                    [_strip_path_prefix(path, search_base) for path, _ in entry_errors],
+                    permission_errors=all(code in _PERMISSION_ERRNOS for _, code in entry_errors),
                 )
+                message = f"{message} {unreadable_msg}" if message else unreadable_msg
 
             # --- Post-processing pipeline ---
 
@@ -480,9 +584,6 @@ class Grep(CallableTool2[Params]):
                 output = "\n".join(lines)
 
             # Step 2: shorten paths to relative (prefix stripping)
-            search_base = os.path.abspath(os.path.expanduser(normalize_user_path(params.path)))
-            if os.path.isfile(search_base):
-                search_base = os.path.dirname(search_base)
             output = _strip_path_prefix(output, search_base)
 
             # Step 3: filter sensitive files from output
@@ -528,7 +629,52 @@ class Grep(CallableTool2[Params]):
                 warning = sensitive_file_warning(filtered_paths)
                 message = f"{message} {warning}" if message else warning
 
-            # Step 4: count_matches summary (before pagination, on full results)
+            # Step 4: binary files are skipped in content mode and marked in
+            # files_with_matches mode, like ripgrep does
+            if not params.binary and params.output_mode != "count_matches":
+                binary_cache: dict[str, bool] = {}
+
+                def _binary(file_path: str) -> bool:
+                    if file_path not in binary_cache:
+                        binary_cache[file_path] = _is_binary_file(
+                            os.path.join(search_base, file_path)
+                        )
+                    return binary_cache[file_path]
+
+                skipped_binaries: set[str] = set()
+                kept_lines = []
+                for line in output.split("\n"):
+                    if params.output_mode == "files_with_matches":
+                        if line and _binary(line):
+                            line = f"{line} (binary file matches)"
+                        kept_lines.append(line)
+                        continue
+                    notice = _RG_BINARY_NOTICE_RE.match(line)
+                    if notice:
+                        file_path = notice.group(1) or searched_file
+                    elif searched_file:
+                        file_path = searched_file
+                    else:
+                        m = _RG_LINE_RE.match(line)
+                        file_path = m.group(1) if m else None
+                    if file_path and (notice or _binary(file_path)):
+                        skipped_binaries.add(file_path)
+                        continue
+                    # Drop "--" separators left dangling by the skipped lines
+                    if line == "--" and (not kept_lines or kept_lines[-1] == "--"):
+                        continue
+                    kept_lines.append(line)
+                while kept_lines and kept_lines[-1] == "--":
+                    kept_lines.pop()
+                output = "\n".join(kept_lines)
+                if skipped_binaries:
+                    binary_msg = (
+                        f"Skipped {len(skipped_binaries)} binary file(s). "
+                        "Set `binary` to true to search them as text."
+                    )
+                    message = f"{message} {binary_msg}" if message else binary_msg
+
+            # Step 5: count_matches summary (before pagination, on full results)
             lines = output.split("\n")
             if lines and lines[-1] == "":
                 lines = lines[:-1]
@@ -549,7 +695,7 @@ class Grep(CallableTool2[Params]):
                 )
                 message = f"{message} {count_summary}" if message else count_summary
 
-            # Step 5: offset + head_limit pagination
+            # Step 6: offset + head_limit pagination
             if params.offset > 0:
                 lines = lines[params.offset :]
 
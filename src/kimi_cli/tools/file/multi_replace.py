@@ -0,0 +1,305 @@
+"""
+A tool for replacing strings in several files as one transaction.
+
+Every edit is applied in memory and checked before anything is written, so that a refactor
+spanning several files never leaves them half-edited: either all files are written, or none
+of them is changed.
+"""
+
+from collections.abc import Callable
+from dataclasses import dataclass
+from pathlib import Path
+from typing import override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolError, ToolReturnValue
+from pydantic import BaseModel, Field
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.turn_summary import FILE_CHANGES_EXTRA
+from kimi_cli.tools.display import DisplayBlock
+from kimi_cli.tools.file.plan_mode import inspect_plan_edit_target
+from kimi_cli.tools.file.replace import Edit, _apply_edits
+from kimi_cli.tools.file.utils import (
+    DRY_RUN_DESCRIPTION,
+    FORCE_DESCRIPTION,
+    TextFormat,
+    decode_text_for_edit,
+    modified_externally_error,
+    request_edit_approval,
+    write_too_large_error,
+)
+from kimi_cli.tools.utils import dry_run_result, load_desc
+from kimi_cli.utils.diff import build_diff_blocks
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
+
+MAX_FILES = 50
+
+
+class FileEdits(BaseModel):
+    path: str = Field(
+        description=(
+            "The path to the file to edit. Absolute paths are required when editing files "
+            "outside the working directory."
+        )
+    )
+    edits: list[Edit] = Field(
+        description="The edits to apply to this file, in order.",
+        min_length=1,
+    )
+
+
+class Params(BaseModel):
+    files: list[FileEdits] = Field(
+        description=(
+            f"The files to edit and their edits, at most {MAX_FILES} files. "
+            "Each file may only be listed once."
+        ),
+        min_length=1,
+        max_length=MAX_FILES,
+    )
+    force: bool = Field(default=False, description=FORCE_DESCRIPTION)
+    dry_run: bool = Field(default=False, description=DRY_RUN_DESCRIPTION)
+
+
+@dataclass(slots=True)
+class _PreparedFile:
+    path: KaosPath
+    original_data: bytes
+    """The raw bytes of the file, written back if the transaction is rolled back."""
+    original_content: str
+    text_format: TextFormat
+    new_content: str
+    replacements: int
+    is_plan_target: bool
+
+
+class _EditFailed(Exception):
+    def __init__(self, error: ToolError):
+        super().__init__(error.message)
+        self.error = error
+
+
+class MultiStrReplaceFile(CallableTool2[Params]):
+    name: str = "MultiStrReplaceFile"
+    params: type[Params] = Params
+
+    def __init__(self, runtime: Runtime, approval: Approval):
+        super().__init__(
+            description=load_desc(
+                Path(__file__).parent / "multi_replace.md",
+                {
+                    "MAX_FILES": MAX_FILES,
+                    "MAX_WRITE_BYTES": runtime.config.tools.max_write_bytes,
+                },
+            )
+        )
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+        self._approval = approval
+        self._runtime = runtime
+        self._file_journal = runtime.file_journal
+        self._file_tracker = runtime.file_tracker
+        self._max_write_bytes = runtime.config.tools.max_write_bytes
+        self._plan_mode_checker: Callable[[], bool] | None = None
+        self._plan_file_path_getter: Callable[[], Path | None] | None = None
+
+    def bind_plan_mode(
+        self, checker: Callable[[], bool], path_getter: Callable[[], Path | None]
+    ) -> None:
+        """Bind plan mode state checker and plan file path getter."""
+        self._plan_mode_checker = checker
+        self._plan_file_path_getter = path_getter
+
+    async def _prepare(self, file: FileEdits, force: bool) -> _PreparedFile:
+        """
+        Read a file and apply its edits in memory.
+
+        Raises:
+            _EditFailed: If the file cannot be edited, or one of its edits does not match.
+        """
+        if not file.path:
+            raise _EditFailed(ToolError(message="File path cannot be empty.", brief="Empty path"))
+        p = kaos_path_from_user_input(file.path)
+        if (
+            not is_within_workspace(p.canonical(), self._work_dir, self._additional_dirs)
+            and not p.is_absolute()
+        ):
+            raise _EditFailed(
+                ToolError(
+                    message=(
+                        f"`{file.path}` is not an absolute path. "
+                        "You must provide an absolute path to edit a file "
+                        "outside the working directory."
+                        + outside_workspace_hint(self._work_dir, self._additional_dirs)
+                    ),
+                    brief="Invalid path",
+                )
+            )
+        p = p.canonical()
+
+        plan_target = inspect_plan_edit_target(
+            p,
+            plan_mode_checker=self._plan_mode_checker,
+            plan_file_path_getter=self._plan_file_path_getter,
+        )
+        if isinstance(plan_target, ToolError):
+            raise _EditFailed(plan_target)
+
+        if not await p.exists():
+            raise _EditFailed(
+                ToolError(message=f"`{file.path}` does not exist.", brief="File not found")
+            )
+        if not await p.is_file():
+            raise _EditFailed(
+                ToolError(message=f"`{file.path}` is not a file.", brief="Invalid path")
+            )
+        if not force and (change := await self._file_tracker.check(p)) is not None:
+            raise _EditFailed(modified_externally_error(file.path, change))
+
+        data = await p.read_bytes()
+        content, text_format = decode_text_for_edit(p, data)
+        new_content, outcomes = _apply_edits(content, file.edits)
+        for i, outcome in enumerate(outcomes, start=1):
+            if outcome.failure is not None:
+                raise _EditFailed(
+                    ToolError(
+                        message=(
+                            f"Edit {i} of `{file.path}` did not match: {outcome.failure}. "
+                            "No file was changed."
+                        ),
+                        brief="Edit did not match",
+                    )
+                )
+
+        return _PreparedFile(
+            path=p,
+            original_data=data,
+            original_content=content,
+            text_format=text_format,
+            new_content=new_content,
+            replacements=sum(outcome.replacements for outcome in outcomes),
+            is_plan_target=plan_target.is_plan_target,
+        )
+
+    async def _rollback(self, written: list[_PreparedFile]) -> list[KaosPath]:
+        """Restore the original content of written files, returning those that failed."""
+        failed: list[KaosPath] = []
+        for file in reversed(written):
+            try:
+                await file.path.write_bytes_atomic(file.original_data)
+                await self._file_tracker.record(file.path)
+            except Exception as e:
+                logger.error(
+                    "Failed to restore {path} after a failed edit: {error}",
+                    path=file.path,
+                    error=e,
+                )
+                failed.append(file.path)
+        return failed
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        # Compute every new content first; nothing is written if any file fails
+        prepared: list[_PreparedFile] = []
+        try:
+            for file in params.files:
+                result = await self._prepare(file, params.force)
+                if any(str(other.path) == str(result.path) for other in prepared):
+                    return ToolError(
+                        message=(
+                            f"`{file.path}` is listed more than once. "
+                            "Put all of its edits in a single entry."
+                        ),
+                        brief="Duplicate file",
+                    )
+                prepared.append(result)
+        except _EditFailed as e:
+            return e.error
+        except Exception as e:
+            logger.warning("MultiStrReplaceFile failed: {error}", error=e)
+            return ToolError(message=f"Failed to edit. Error: {e}", brief="Failed to edit files")
+
+        changed = [file for file in prepared if file.new_content != file.original_content]
+        if not changed:
+            return ToolError(
+                message="The edits did not change any file.",
+                brief="No changes made",
+            )
+        for file in changed:
+            added = len(file.new_content.encode("utf-8")) - len(
+                file.original_content.encode("utf-8")
+            )
+            if added > self._max_write_bytes:
+                return write_too_large_error(str(file.path), added, self._max_write_bytes)
+
+        diff_blocks: list[DisplayBlock] = []
+        for file in changed:
+            diff_blocks.extend(
+                await build_diff_blocks(str(file.path), file.original_content, file.new_content)
+            )
+
+        description = f"Edit {len(changed)} files: " + ", ".join(
+            f"`{file.path}`" for file in changed
+        )
+        total_replacements = sum(file.replacements for file in prepared)
+        message = (
+            f"{len(changed)} file(s) successfully edited with "
+            f"{total_replacements} total replacement(s)."
+        )
+        if params.dry_run or self._runtime.dry_run:
+            return dry_run_result(f"{description}. {message}", diff_blocks)
+
+        # Plan file edits are auto-approved; all other edits need approval, shown together.
+        if any(not file.is_plan_target for file in changed):
+            result = await request_edit_approval(
+                self._approval,
+                self.name,
+                description,
+                [file.path for file in changed],
+                work_dir=self._work_dir,
+                additional_dirs=self._additional_dirs,
+                display=diff_blocks,
+            )
+            if not result:
+                return result.rejection_error()
+
+        written: list[_PreparedFile] = []
+        for file in changed:
+            try:
+                if self._file_journal is not None:
+                    await self._file_journal.record(file.path)
+                # Each file is replaced atomically, so a failed write leaves it untouched
+                await file.path.write_bytes_atomic(
+                    file.text_format.apply(file.new_content).encode("utf-8", errors="replace")
+                )
+                written.append(file)
+                await self._file_tracker.record(file.path)
+            except Exception as e:
+                logger.warning(
+                    "MultiStrReplaceFile failed to write {path}: {error}", path=file.path, error=e
+                )
+                not_restored = await self._rollback(written)
+                message = f"Failed to write `{file.path}`. Error: {e}\n"
+                if not_restored:
+                    message += (
+                        "These files could not be restored and may be partially edited: "
+                        + ", ".join(f"`{path}`" for path in not_restored)
+                    )
+                else:
+                    message += "The files already written were restored; no file was changed."
+                return ToolError(message=message, brief="Failed to edit files")
+
+        return ToolReturnValue(
+            is_error=False,
+            output="",
+            message=message,
+            display=diff_blocks,
+            extras={FILE_CHANGES_EXTRA: {str(file.path): "modified" for file in changed}},
+        )
@@ -0,0 +1,423 @@
+"""
+Tools for reading and editing Jupyter notebooks cell by cell.
+
+Only the parts of the nbformat 4 schema the tools rely on are validated. Edits are applied to
+the raw JSON, so cell outputs, attachments and notebook metadata the tools do not know about
+are written back untouched.
+"""
+
+import json
+import uuid
+from collections.abc import Callable
+from pathlib import Path
+from typing import Any, Literal, override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolError, ToolReturnValue
+from pydantic import BaseModel, Field, ValidationError
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.turn_summary import FILE_CHANGES_EXTRA
+from kimi_cli.tools.display import DisplayBlock
+from kimi_cli.tools.file.plan_mode import inspect_plan_edit_target
+from kimi_cli.tools.file.utils import (
+    DRY_RUN_DESCRIPTION,
+    FORCE_DESCRIPTION,
+    modified_externally_error,
+    request_edit_approval,
+)
+from kimi_cli.tools.utils import ToolResultBuilder, dry_run_result, load_desc
+from kimi_cli.utils.diff import build_diff_blocks
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
+
+MAX_OUTPUT_CHARS = 500
+"""Characters of each cell output kept in the `NotebookRead` summary."""
+
+CellType = Literal["code", "markdown", "raw"]
+
+
+class NotebookCell(BaseModel):
+    cell_type: CellType
+    source: str | list[str]
+    outputs: list[dict[str, Any]] = Field(default_factory=list[dict[str, Any]])
+    execution_count: int | None = None
+
+    @property
+    def source_text(self) -> str:
+        return _join_text(self.source)
+
+
+class Notebook(BaseModel):
+    nbformat: Literal[4]
+    nbformat_minor: int
+    metadata: dict[str, Any]
+    cells: list[NotebookCell]
+
+
+class InvalidNotebookError(ValueError):
+    """Raised when a file is not a valid nbformat 4 notebook."""
+
+
+async def load_notebook(path: KaosPath) -> tuple[dict[str, Any], Notebook]:
+    """
+    Read and validate a notebook.
+
+    Returns:
+        tuple[dict[str, Any], Notebook]: The raw JSON, which edits should be applied to, and
+            its validated view.
+
+    Raises:
+        InvalidNotebookError: If the file is not valid JSON or not an nbformat 4 notebook.
+    """
+    try:
+        raw = json.loads(await path.read_text(errors="replace"))
+    except json.JSONDecodeError as e:
+        raise InvalidNotebookError(f"invalid JSON: {e}") from e
+    try:
+        notebook = Notebook.model_validate(raw)
+    except ValidationError as e:
+        error = e.errors()[0]
+        loc = ".".join(str(part) for part in error["loc"]) or "<root>"
+        raise InvalidNotebookError(f"`{loc}`: {error['msg']}") from e
+    return raw, notebook
+
+
+def dump_notebook(raw: dict[str, Any]) -> str:
+    """Serialize a notebook the way Jupyter does."""
+    return json.dumps(raw, indent=1, ensure_ascii=False) + "\n"
+
+
+def _join_text(text: str | list[str]) -> str:
+    return text if isinstance(text, str) else "".join(text)
+
+
+def _shorten(text: str) -> str:
+    text = text.strip()
+    if len(text) <= MAX_OUTPUT_CHARS:
+        return text
+    return text[:MAX_OUTPUT_CHARS] + "[...truncated]"
+
+
+def _summarize_output(output: dict[str, Any]) -> str:
+    output_type = str(output.get("output_type", "unknown"))
+    match output_type:
+        case "stream":
+            name = output.get("name", "stdout")
+            return f"[stream {name}] {_shorten(_join_text(output.get('text', '')))}"
+        case "execute_result" | "display_data":
+            data: dict[str, Any] = output.get("data") or {}
+            parts: list[str] = []
+            if "text/plain" in data:
+                parts.append(_shorten(_join_text(data["text/plain"])))
+            omitted = [mime for mime in data if mime != "text/plain"]
+            if omitted:
+                parts.append(f"({', '.join(omitted)} not shown)")
+            return f"[{output_type}] {' '.join(parts)}"
+        case "error":
+            return f"[error] {output.get('ename', '')}: {_shorten(str(output.get('evalue', '')))}"
+        case _:
+            return f"[{output_type}]"
+
+
+def _format_cell(index: int, cell: NotebookCell) -> str:
+    attrs = f'index="{index}" type="{cell.cell_type}"'
+    if cell.cell_type == "code" and cell.execution_count is not None:
+        attrs += f' execution_count="{cell.execution_count}"'
+    source = cell.source_text
+    if not source.endswith("\n"):
+        source += "\n"
+    text = f"<cell {attrs}>\n{source}</cell>\n"
+    if cell.outputs:
+        summaries = "\n".join(_summarize_output(output) for output in cell.outputs)
+        text += f'<outputs index="{index}">\n{summaries}\n</outputs>\n'
+    return text
+
+
+def _new_cell(cell_type: CellType, source: str, *, with_id: bool) -> dict[str, Any]:
+    cell: dict[str, Any] = {"cell_type": cell_type}
+    if with_id:
+        # Cell IDs are required since nbformat 4.5.
+        cell["id"] = uuid.uuid4().hex[:8]
+    cell["metadata"] = {}
+    if cell_type == "code":
+        cell["execution_count"] = None
+        cell["outputs"] = []
+    cell["source"] = source.splitlines(keepends=True)
+    return cell
+
+
+def _set_cell_type(cell: dict[str, Any], cell_type: CellType) -> None:
+    cell["cell_type"] = cell_type
+    if cell_type == "code":
+        cell.setdefault("execution_count", None)
+        cell.setdefault("outputs", [])
+    else:
+        cell.pop("execution_count", None)
+        cell.pop("outputs", None)
+
+
+async def _validate_path(
+    path: KaosPath, work_dir: KaosPath, additional_dirs: list[KaosPath]
+) -> ToolError | None:
+    if not is_within_workspace(path.canonical(), work_dir, additional_dirs) and (
+        not path.is_absolute()
+    ):
+        return ToolError(
+            message=(
+                f"`{path}` is not an absolute path. "
+                "You must provide an absolute path to access a notebook "
+                "outside the working directory."
+                + outside_workspace_hint(work_dir, additional_dirs)
+            ),
+            brief="Invalid path",
+        )
+    return None
+
+
+async def _check_notebook_file(path: KaosPath, raw_path: str) -> ToolError | None:
+    if not await path.exists():
+        return ToolError(message=f"`{raw_path}` does not exist.", brief="File not found")
+    if not await path.is_file():
+        return ToolError(message=f"`{raw_path}` is not a file.", brief="Invalid path")
+    return None
+
+
+def _invalid_notebook(raw_path: str, error: InvalidNotebookError) -> ToolError:
+    return ToolError(
+        message=f"`{raw_path}` is not a valid Jupyter notebook: {error}",
+        brief="Invalid notebook",
+    )
+
+
+class ReadParams(BaseModel):
+    path: str = Field(
+        description=(
+            "The path to the `.ipynb` file to read. Absolute paths are required when reading "
+            "notebooks outside the working directory."
+        )
+    )
+
+
+class NotebookRead(CallableTool2[ReadParams]):
+    name: str = "NotebookRead"
+    description: str = load_desc(
+        Path(__file__).parent / "notebook_read.md", {"MAX_OUTPUT_CHARS": MAX_OUTPUT_CHARS}
+    )
+    params: type[ReadParams] = ReadParams
+
+    def __init__(self, runtime: Runtime) -> None:
+        super().__init__()
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+        self._file_tracker = runtime.file_tracker
+
+    @override
+    async def __call__(self, params: ReadParams) -> ToolReturnValue:
+        if not params.path:
+            return ToolError(message="File path cannot be empty.", brief="Empty file path")
+
+        try:
+            p = kaos_path_from_user_input(params.path)
+            if err := await _validate_path(p, self._work_dir, self._additional_dirs):
+                return err
+            p = p.canonical()
+            if err := await _check_notebook_file(p, params.path):
+                return err
+
+            try:
+                _, notebook = await load_notebook(p)
+            except InvalidNotebookError as e:
+                return _invalid_notebook(params.path, e)
+            await self._file_tracker.record(p)
+
+            builder = ToolResultBuilder()
+            for index, cell in enumerate(notebook.cells):
+                builder.write(_format_cell(index, cell))
+            return builder.ok(
+                f"{len(notebook.cells)} cells read from notebook",
+                brief=f"{len(notebook.cells)} cells",
+            )
+        except Exception as e:
+            logger.warning("NotebookRead failed: {path}: {error}", path=params.path, error=e)
+            return ToolError(
+                message=f"Failed to read {params.path}. Error: {e}",
+                brief="Failed to read notebook",
+            )
+
+
+class EditParams(BaseModel):
+    path: str = Field(
+        description=(
+            "The path to the `.ipynb` file to edit. Absolute paths are required when editing "
+            "notebooks outside the working directory."
+        )
+    )
+    cell_index: int = Field(
+        description=(
+            "The 0-based index of the cell to edit. For `insert`, the new cell is inserted at "
+            "this index, so use the number of cells to append at the end."
+        ),
+        ge=0,
+    )
+    new_source: str = Field(
+        default="",
+        description="The new source of the cell. Ignored when `edit_type` is `delete`.",
+    )
+    cell_type: CellType | None = Field(
+        default=None,
+        description=(
+            "The type of the cell. Defaults to the current type when replacing, and to `code` "
+            "when inserting."
+        ),
+    )
+    edit_type: Literal["replace", "insert", "delete"] = Field(
+        default="replace",
+        description=(
+            "`replace` the source of the cell, `insert` a new cell, or `delete` the cell. "
+            "Defaults to `replace`."
+        ),
+    )
+    force: bool = Field(default=False, description=FORCE_DESCRIPTION)
+    dry_run: bool = Field(default=False, description=DRY_RUN_DESCRIPTION)
+
+
+class NotebookEdit(CallableTool2[EditParams]):
+    name: str = "NotebookEdit"
+    description: str = load_desc(Path(__file__).parent / "notebook_edit.md")
+    params: type[EditParams] = EditParams
+
+    def __init__(self, runtime: Runtime, approval: Approval):
+        super().__init__()
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+        self._approval = approval
+        self._runtime = runtime
+        self._file_journal = runtime.file_journal
+        self._file_tracker = runtime.file_tracker
+        self._plan_mode_checker: Callable[[], bool] | None = None
+        self._plan_file_path_getter: Callable[[], Path | None] | None = None
+
+    def bind_plan_mode(
+        self, checker: Callable[[], bool], path_getter: Callable[[], Path | None]
+    ) -> None:
+        """Bind plan mode state checker and plan file path getter."""
+        self._plan_mode_checker = checker
+        self._plan_file_path_getter = path_getter
+
+    @override
+    async def __call__(self, params: EditParams) -> ToolReturnValue:
+        if not params.path:
+            return ToolError(message="File path cannot be empty.", brief="Empty file path")
+
+        try:
+            p = kaos_path_from_user_input(params.path)
+            if err := await _validate_path(p, self._work_dir, self._additional_dirs):
+                return err
+            p = p.canonical()
+
+            plan_target = inspect_plan_edit_target(
+                p,
+                plan_mode_checker=self._plan_mode_checker,
+                plan_file_path_getter=self._plan_file_path_getter,
+            )
+            if isinstance(plan_target, ToolError):
+                return plan_target
+
+            if err := await _check_notebook_file(p, params.path):
+                return err
+            if not params.force and (change := await self._file_tracker.check(p)) is not None:
+                return modified_externally_error(params.path, change)
+
+            try:
+                raw, notebook = await load_notebook(p)
+            except InvalidNotebookError as e:
+                return _invalid_notebook(params.path, e)
+
+            cells: list[dict[str, Any]] = raw["cells"]
+            index = params.cell_index
+            n_cells = len(cells)
+            max_index = n_cells if params.edit_type == "insert" else n_cells - 1
+            if index > max_index:
+                return ToolError(
+                    message=(
+                        f"Cell index {index} is out of range for `{params.edit_type}`. "
+                        f"The notebook has {n_cells} cells."
+                    ),
+                    brief="Cell index out of range",
+                )
+
+            old_source = ""
+            new_source = params.new_source
+            match params.edit_type:
+                case "replace":
+                    cell = cells[index]
+                    old_source = notebook.cells[index].source_text
+                    cell["source"] = new_source.splitlines(keepends=True)
+                    if params.cell_type is not None:
+                        _set_cell_type(cell, params.cell_type)
+                    if cell["cell_type"] == "code":
+                        # The outputs no longer match the new source.
+                        cell["execution_count"] = None
+                        cell["outputs"] = []
+                    description = f"Replace cell {index} of notebook `{p}`"
+                    message = f"Cell {index} replaced."
+                case "insert":
+                    cell_type = params.cell_type or "code"
+                    cells.insert(
+                        index,
+                        _new_cell(cell_type, new_source, with_id=notebook.nbformat_minor >= 5),
+                    )
+                    description = f"Insert a {cell_type} cell at index {index} of notebook `{p}`"
+                    message = f"Inserted a {cell_type} cell at index {index}."
+                case "delete":
+                    old_source = notebook.cells[index].source_text
+                    new_source = ""
+                    del cells[index]
+                    description = f"Delete cell {index} of notebook `{p}`"
+                    message = f"Cell {index} deleted."
+
+            diff_blocks: list[DisplayBlock] = await build_diff_blocks(
+                str(p), old_source, new_source
+            )
+            message += f" The notebook now has {len(cells)} cells."
+            if params.dry_run or self._runtime.dry_run:
+                return dry_run_result(f"{description}. {message}", diff_blocks)
+
+            result = await request_edit_approval(
+                self._approval,
+                self.name,
+                description,
+                [p],
+                work_dir=self._work_dir,
+                additional_dirs=self._additional_dirs,
+                display=diff_blocks,
+            )
+            if not result:
+                return result.rejection_error()
+
+            if self._file_journal is not None:
+                await self._file_journal.record(p)
+
+            await p.write_text(dump_notebook(raw))
+            await self._file_tracker.record(p)
+
+            return ToolReturnValue(
+                is_error=False,
+                output="",
+                message=message,
+                display=diff_blocks,
+                extras={FILE_CHANGES_EXTRA: {str(p): "modified"}},
+            )
+
+        except Exception as e:
+            logger.warning("NotebookEdit failed: {path}: {error}", path=params.path, error=e)
+            return ToolError(
+                message=f"Failed to edit {params.path}. Error: {e}",
+                brief="Failed to edit notebook",
+            )
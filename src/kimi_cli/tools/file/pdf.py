@@ -0,0 +1,227 @@
+"""
+A tool for extracting the text of PDF files page by page.
+
+It needs the optional `pypdf` dependency, installed with the `pdf` extra, and skips itself from
+loading when the dependency is missing.
+"""
+
+import asyncio
+import importlib.util
+from dataclasses import dataclass
+from io import BytesIO
+from pathlib import Path
+from typing import override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
+from pydantic import BaseModel, Field
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools import SkipThisTool
+from kimi_cli.tools.utils import load_desc
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
+
+MAX_BYTES = 100 << 10  # 100KB
+MAX_PDF_MEGABYTES = 100
+NO_TEXT_NOTE = "[No extractable text. The page is likely scanned or only contains images.]"
+
+_PDF_MAGIC = b"%PDF-"
+
+
+def pdf_support_available() -> bool:
+    return importlib.util.find_spec("pypdf") is not None
+
+
+class Params(BaseModel):
+    path: str = Field(
+        description=(
+            "The path to the PDF file to read. Absolute paths are required when reading files "
+            "outside the working directory."
+        )
+    )
+    first_page: int = Field(
+        default=1,
+        description="The 1-based page number to start reading from. Defaults to the first page.",
+        ge=1,
+    )
+    n_pages: int | None = Field(
+        default=None,
+        description=(
+            "The number of pages to read. By default read until the end of the document or until "
+            f"{MAX_BYTES} bytes of text have been read."
+        ),
+        ge=1,
+    )
+
+
+class EncryptedPdfError(Exception):
+    """Raised when a PDF cannot be opened without a password."""
+
+
+@dataclass(frozen=True, slots=True)
+class PdfText:
+    total_pages: int
+    pages: list[tuple[int, str]]
+    """Page numbers and their extracted text, empty for pages without text."""
+    max_bytes_reached: bool
+    last_page_cut: bool = False
+    """Whether the text of the last page alone exceeded `MAX_BYTES` and was cut."""
+
+
+def extract_pdf_text(data: bytes, first_page: int, n_pages: int | None) -> PdfText:
+    """
+    Extract the text of up to `n_pages` pages starting from `first_page`, stopping once
+    `MAX_BYTES` of text have been read. At least one page is always returned when `first_page`
+    exists, cut to `MAX_BYTES` if needed.
+
+    Raises:
+        EncryptedPdfError: If the PDF needs a password.
+        pypdf.errors.PyPdfError: If the PDF cannot be parsed.
+    """
+    from pypdf import PdfReader
+
+    reader = PdfReader(BytesIO(data))
+    if reader.is_encrypted and not reader.decrypt(""):
+        raise EncryptedPdfError()
+
+    total_pages = len(reader.pages)
+    last_page = total_pages if n_pages is None else min(total_pages, first_page + n_pages - 1)
+    pages: list[tuple[int, str]] = []
+    n_bytes = 0
+    for page_no in range(first_page, last_page + 1):
+        text = (reader.pages[page_no - 1].extract_text() or "").strip()
+        size = len(text.encode("utf-8"))
+        if pages and n_bytes + size > MAX_BYTES:
+            return PdfText(total_pages, pages, max_bytes_reached=True)
+        if size > MAX_BYTES:
+            text = text.encode("utf-8")[:MAX_BYTES].decode("utf-8", errors="ignore")
+            pages.append((page_no, text))
+            return PdfText(total_pages, pages, max_bytes_reached=True, last_page_cut=True)
+        pages.append((page_no, text))
+        n_bytes += size
+    return PdfText(total_pages, pages, max_bytes_reached=False)
+
+
+def _format_page(page_no: int, text: str) -> str:
+    return f'<page number="{page_no}">\n{text or NO_TEXT_NOTE}\n</page>\n'
+
+
+class ReadPdf(CallableTool2[Params]):
+    name: str = "ReadPdf"
+    params: type[Params] = Params
+
+    def __init__(self, runtime: Runtime) -> None:
+        if not pdf_support_available():
+            raise SkipThisTool()
+        description = load_desc(
+            Path(__file__).parent / "pdf.md",
+            {"MAX_BYTES": MAX_BYTES, "MAX_PDF_MEGABYTES": MAX_PDF_MEGABYTES},
+        )
+        super().__init__(description=description)
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+
+    async def _validate_path(self, path: KaosPath) -> ToolError | None:
+        """Validate that the path is safe to read."""
+        if (
+            not is_within_workspace(path.canonical(), self._work_dir, self._additional_dirs)
+            and not path.is_absolute()
+        ):
+            return ToolError(
+                message=(
+                    f"`{path}` is not an absolute path. "
+                    "You must provide an absolute path to read a file "
+                    "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
+                ),
+                brief="Invalid path",
+            )
+        return None
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        from pypdf.errors import PyPdfError
+
+        if not params.path:
+            return ToolError(message="File path cannot be empty.", brief="Empty file path")
+
+        try:
+            p = kaos_path_from_user_input(params.path)
+            if err := await self._validate_path(p):
+                return err
+            p = p.canonical()
+
+            if not await p.exists():
+                return ToolError(message=f"`{params.path}` does not exist.", brief="File not found")
+            if not await p.is_file():
+                return ToolError(message=f"`{params.path}` is not a file.", brief="Invalid path")
+            size = (await p.stat()).st_size
+            if size > (MAX_PDF_MEGABYTES << 20):
+                return ToolError(
+                    message=(
+                        f"`{params.path}` is {size} bytes, which exceeds the max "
+                        f"{MAX_PDF_MEGABYTES}MB for PDF files."
+                    ),
+                    brief="File too large",
+                )
+            data = await p.read_bytes()
+            if not data.startswith(_PDF_MAGIC):
+                return ToolError(message=f"`{params.path}` is not a PDF file.", brief="Not a PDF")
+
+            result = await asyncio.to_thread(
+                extract_pdf_text, data, params.first_page, params.n_pages
+            )
+        except EncryptedPdfError:
+            return ToolError(
+                message=f"`{params.path}` is encrypted and cannot be read without a password.",
+                brief="Encrypted PDF",
+            )
+        except PyPdfError as e:
+            return ToolError(
+                message=f"`{params.path}` could not be parsed as a PDF: {e}",
+                brief="Invalid PDF",
+            )
+        except Exception as e:
+            logger.warning("ReadPdf failed: {path}: {error}", path=params.path, error=e)
+            return ToolError(
+                message=f"Failed to read {params.path}. Error: {e}",
+                brief="Failed to read file",
+            )
+
+        if params.first_page > result.total_pages:
+            return ToolError(
+                message=(
+                    f"Page {params.first_page} does not exist. "
+                    f"The PDF has {result.total_pages} pages."
+                ),
+                brief="Page out of range",
+            )
+
+        first, last = result.pages[0][0], result.pages[-1][0]
+        page_range = f"Page {first}" if first == last else f"Pages {first}-{last}"
+        message = f"{page_range} read from PDF. Total pages: {result.total_pages}."
+        if result.last_page_cut:
+            message += f" Page {last} has more than {MAX_BYTES} bytes of text and was cut short."
+            if last < result.total_pages:
+                message += f" Set `first_page` to {last + 1} to continue with the next page."
+        elif result.max_bytes_reached:
+            message += (
+                f" Max {MAX_BYTES} bytes reached; set `first_page` to {last + 1} to continue."
+            )
+        elif last == result.total_pages:
+            message += " End of document reached."
+        if empty := [page_no for page_no, text in result.pages if not text]:
+            message += (
+                f" Pages {empty} have no extractable text; they are likely scanned "
+                "or only contain images."
+            )
+        return ToolOk(
+            output="".join(_format_page(page_no, text) for page_no, text in result.pages),
+            message=message,
+            brief=f"{len(result.pages)} pages",
+        )
@@ -7,10 +7,19 @@ from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
 from pydantic import BaseModel, Field, model_validator
 
 from kimi_cli.soul.agent import Runtime
-from kimi_cli.tools.file.utils import MEDIA_SNIFF_BYTES, detect_file_type
-from kimi_cli.tools.utils import load_desc, truncate_line
+from kimi_cli.tools.file.utils import (
+    MEDIA_SNIFF_BYTES,
+    detect_file_type,
+    detect_text_encoding,
+    resolve_text_encoding,
+)
+from kimi_cli.tools.utils import ArgumentClamps, load_desc, truncate_line
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.path import is_within_workspace, kaos_path_from_user_input
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
 from kimi_cli.utils.sensitive import is_sensitive_file
 
 MAX_LINES = 1000
@@ -44,6 +53,14 @@ class Params(BaseModel):
         default=MAX_LINES,
         ge=1,
     )
+    encoding: str | None = Field(
+        description=(
+            "The text encoding of the file, e.g. `latin-1` or `shift_jis`. "
+            "By default the file is read as UTF-8, unless it is not valid UTF-8 and its "
+            "encoding can be detected."
+        ),
+        default=None,
+    )
 
     @model_validator(mode="after")
     def _validate_line_offset(self) -> "Params":
@@ -92,6 +109,7 @@ class ReadFile(CallableTool2[Params]):
                     f"`{path}` is not an absolute path. "
                     "You must provide an absolute path to read a file "
                     "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
                 ),
                 brief="Invalid path",
             )
@@ -121,12 +139,13 @@ class ReadFile(CallableTool2[Params]):
                     brief="Sensitive file",
                 )
 
-            if not await p.exists():
+            metadata = await p.metadata()
+            if not metadata.exists:
                 return ToolError(
                     message=f"`{params.path}` does not exist.",
                     brief="File not found",
                 )
-            if not await p.is_file():
+            if not metadata.is_file:
                 return ToolError(
                     message=f"`{params.path}` is not a file.",
                     brief="Invalid path",
@@ -155,13 +174,33 @@ class ReadFile(CallableTool2[Params]):
                     brief="File not readable",
                 )
 
+            if params.encoding is not None:
+                encoding = resolve_text_encoding(params.encoding)
+                if encoding is None:
+                    return ToolError(
+                        message=(
+                            f"`{params.encoding}` is not a supported encoding. "
+                            "Use an ASCII-compatible encoding, e.g. `latin-1` or `shift_jis`."
+                        ),
+                        brief="Unsupported encoding",
+                    )
+            else:
+                encoding = await detect_text_encoding(p) or "utf-8"
+
             assert params.n_lines >= 1
             assert params.line_offset != 0
+            clamps = ArgumentClamps()
+            params = params.model_copy(
+                update={"n_lines": clamps.clamp("n_lines", params.n_lines, MAX_LINES)}
+            )
 
             if params.line_offset < 0:
-                return await self._read_tail(p, params)
+                result = await self._read_tail(p, params, encoding)
             else:
-                return await self._read_forward(p, params)
+                result = await self._read_forward(p, params, encoding)
+            if not result.is_error:
+                await self._runtime.file_tracker.record(p)
+            return clamps.annotate(result)
         except Exception as e:
             logger.warning("ReadFile failed: {path}: {error}", path=params.path, error=e)
             return ToolError(
@@ -169,7 +208,7 @@ class ReadFile(CallableTool2[Params]):
                 brief="Failed to read file",
             )
 
-    async def _read_forward(self, p: KaosPath, params: Params) -> ToolReturnValue:
+    async def _read_forward(self, p: KaosPath, params: Params, encoding: str) -> ToolReturnValue:
         """Read file from a positive line_offset, counting total lines."""
         lines: list[str] = []
         n_bytes = 0
@@ -178,7 +217,7 @@ class ReadFile(CallableTool2[Params]):
         max_bytes_reached = False
         collecting = True  # False once we've collected enough lines
         current_line_no = 0
-        async for line in p.read_lines(errors="replace"):
+        async for line in p.read_lines(encoding=encoding, errors="replace"):
             current_line_no += 1
             if not collecting:
                 continue
@@ -220,12 +259,14 @@ class ReadFile(CallableTool2[Params]):
             message += " End of file reached."
         if truncated_line_numbers:
             message += f" Lines {truncated_line_numbers} were truncated."
+        if encoding != "utf-8":
+            message += f" Decoded from {encoding}."
         return ToolOk(
             output="".join(lines_with_no),
             message=message,
         )
 
-    async def _read_tail(self, p: KaosPath, params: Params) -> ToolReturnValue:
+    async def _read_tail(self, p: KaosPath, params: Params, encoding: str) -> ToolReturnValue:
         """Read file from a negative line_offset (tail mode)."""
         tail_count = abs(params.line_offset)
 
@@ -233,7 +274,7 @@ class ReadFile(CallableTool2[Params]):
         # Each entry: (line_no, truncated_line, was_truncated)
         tail_buf: deque[tuple[int, str, bool]] = deque(maxlen=tail_count)
         current_line_no = 0
-        async for line in p.read_lines(errors="replace"):
+        async for line in p.read_lines(encoding=encoding, errors="replace"):
             current_line_no += 1
             truncated = truncate_line(line, MAX_LINE_LENGTH)
             tail_buf.append((current_line_no, truncated, truncated != line))
@@ -294,6 +335,8 @@ class ReadFile(CallableTool2[Params]):
             message += " End of file reached."
         if truncated_line_numbers:
             message += f" Lines {truncated_line_numbers} were truncated."
+        if encoding != "utf-8":
+            message += f" Decoded from {encoding}."
         return ToolOk(
             output="".join(lines_with_no),
             message=message,
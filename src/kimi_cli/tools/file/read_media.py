@@ -1,5 +1,3 @@
-import base64
-from io import BytesIO
 from pathlib import Path
 from typing import override
 
@@ -12,32 +10,19 @@ from kimi_cli.soul.agent import Runtime
 from kimi_cli.tools import SkipThisTool
 from kimi_cli.tools.file.utils import MEDIA_SNIFF_BYTES, FileType, detect_file_type
 from kimi_cli.tools.utils import load_desc
+from kimi_cli.utils.image import get_image_size, to_data_url
 from kimi_cli.utils.logging import logger
 from kimi_cli.utils.media_tags import wrap_media_part
-from kimi_cli.utils.path import is_within_workspace, kaos_path_from_user_input
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
 from kimi_cli.wire.types import ImageURLPart, VideoURLPart
 
 MAX_MEDIA_MEGABYTES = 100
 
 
-def _to_data_url(mime_type: str, data: bytes) -> str:
-    encoded = base64.b64encode(data).decode("ascii")
-    return f"data:{mime_type};base64,{encoded}"
-
-
-def _extract_image_size(data: bytes) -> tuple[int, int] | None:
-    try:
-        from PIL import Image
-    except Exception:
-        return None
-    try:
-        with Image.open(BytesIO(data)) as image:
-            image.load()
-            return image.size
-    except Exception:
-        return None
-
-
 class Params(BaseModel):
     path: str = Field(
         description=(
@@ -84,6 +69,7 @@ class ReadMediaFile(CallableTool2[Params]):
                     f"`{path}` is not an absolute path. "
                     "You must provide an absolute path to read a file "
                     "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
                 ),
                 brief="Invalid path",
             )
@@ -112,10 +98,10 @@ class ReadMediaFile(CallableTool2[Params]):
         match file_type.kind:
             case "image":
                 data = await path.read_bytes()
-                data_url = _to_data_url(file_type.mime_type, data)
+                data_url = to_data_url(file_type.mime_type, data)
                 part = ImageURLPart(image_url=ImageURLPart.ImageURL(url=data_url))
                 wrapped = wrap_media_part(part, tag="image", attrs={"path": media_path})
-                image_size = _extract_image_size(data)
+                image_size = get_image_size(data)
             case "video":
                 data = await path.read_bytes()
                 if (llm := self._runtime.llm) and isinstance(llm.chat_provider, Kimi):
@@ -125,7 +111,7 @@ class ReadMediaFile(CallableTool2[Params]):
                     )
                     wrapped = wrap_media_part(part, tag="video", attrs={"path": media_path})
                 else:
-                    data_url = _to_data_url(file_type.mime_type, data)
+                    data_url = to_data_url(file_type.mime_type, data)
                     part = VideoURLPart(video_url=VideoURLPart.VideoURL(url=data_url))
                     wrapped = wrap_media_part(part, tag="video", attrs={"path": media_path})
                 image_size = None
@@ -1,4 +1,5 @@
-from collections.abc import Callable
+from collections.abc import Callable, Sequence
+from dataclasses import dataclass
 from pathlib import Path
 from typing import override
 
@@ -8,15 +9,25 @@ from pydantic import BaseModel, Field
 
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.turn_summary import FILE_CHANGES_EXTRA
 from kimi_cli.tools.display import DisplayBlock
-from kimi_cli.tools.file import FileActions
 from kimi_cli.tools.file.plan_mode import inspect_plan_edit_target
-from kimi_cli.tools.utils import load_desc
+from kimi_cli.tools.file.utils import (
+    DRY_RUN_DESCRIPTION,
+    FORCE_DESCRIPTION,
+    modified_externally_error,
+    read_text_for_edit,
+    request_edit_approval,
+    write_too_large_error,
+)
+from kimi_cli.tools.utils import dry_run_result, load_desc
 from kimi_cli.utils.diff import build_diff_blocks
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.path import is_within_workspace, kaos_path_from_user_input
-
-_BASE_DESCRIPTION = load_desc(Path(__file__).parent / "replace.md")
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
 
 
 class Edit(BaseModel):
@@ -25,6 +36,60 @@ class Edit(BaseModel):
     replace_all: bool = Field(description="Whether to replace all occurrences.", default=False)
 
 
+@dataclass(slots=True)
+class _EditOutcome:
+    line: int | None
+    """The line of the first match, counted after the edits before it, or None if unmatched."""
+    replacements: int = 0
+    failure: str | None = None
+
+
+def _line_of(content: str, index: int) -> int:
+    return content.count("\n", 0, index) + 1
+
+
+def _apply_edits(content: str, edits: Sequence[Edit]) -> tuple[str, list[_EditOutcome]]:
+    """
+    Apply the edits in order to the content in memory, skipping the ones that do not match.
+
+    An edit whose old string was in the content before an earlier edit changed it is reported
+    as invalidated by that edit, rather than as not found.
+    """
+    versions = [content]
+    outcomes: list[_EditOutcome] = []
+    for edit in edits:
+        count = content.count(edit.old) if edit.old else 0
+        if count == 0:
+            failure = _edit_failure(versions, edit, outcomes)
+            outcomes.append(_EditOutcome(line=None, failure=failure))
+            continue
+        line = _line_of(content, content.index(edit.old))
+        if edit.replace_all:
+            content = content.replace(edit.old, edit.new)
+        else:
+            content = content.replace(edit.old, edit.new, 1)
+            count = 1
+        outcomes.append(_EditOutcome(line=line, replacements=count))
+        versions.append(content)
+    return content, outcomes
+
+
+def _edit_failure(versions: list[str], edit: Edit, outcomes: list[_EditOutcome]) -> str:
+    if not edit.old:
+        return "the old string is empty"
+    applied = [i for i, outcome in enumerate(outcomes, start=1) if outcome.failure is None]
+    # versions[k] is the content before the applied edit applied[k]
+    for k, edit_no in enumerate(applied):
+        before, after = versions[k], versions[k + 1]
+        if edit.old in before and edit.old not in after:
+            line = _line_of(before, before.index(edit.old))
+            return (
+                f"the old string was at line {line} until edit {edit_no} changed it; "
+                f"merge the two edits, or match the text written by edit {edit_no}"
+            )
+    return "the old string was not found in the file"
+
+
 class Params(BaseModel):
     path: str = Field(
         description=(
@@ -38,18 +103,28 @@ class Params(BaseModel):
             "You can provide a single edit or a list of edits here."
         )
     )
+    force: bool = Field(default=False, description=FORCE_DESCRIPTION)
+    dry_run: bool = Field(default=False, description=DRY_RUN_DESCRIPTION)
 
 
 class StrReplaceFile(CallableTool2[Params]):
     name: str = "StrReplaceFile"
-    description: str = _BASE_DESCRIPTION
     params: type[Params] = Params
 
     def __init__(self, runtime: Runtime, approval: Approval):
-        super().__init__()
+        super().__init__(
+            description=load_desc(
+                Path(__file__).parent / "replace.md",
+                {"MAX_WRITE_BYTES": runtime.config.tools.max_write_bytes},
+            )
+        )
         self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
         self._additional_dirs = runtime.additional_dirs
         self._approval = approval
+        self._runtime = runtime
+        self._file_journal = runtime.file_journal
+        self._file_tracker = runtime.file_tracker
+        self._max_write_bytes = runtime.config.tools.max_write_bytes
         self._plan_mode_checker: Callable[[], bool] | None = None
         self._plan_file_path_getter: Callable[[], Path | None] | None = None
 
@@ -73,18 +148,12 @@ class StrReplaceFile(CallableTool2[Params]):
                     f"`{path}` is not an absolute path. "
                     "You must provide an absolute path to edit a file "
                     "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
                 ),
                 brief="Invalid path",
             )
         return None
 
-    def _apply_edit(self, content: str, edit: Edit) -> str:
-        """Apply a single edit to the content."""
-        if edit.replace_all:
-            return content.replace(edit.old, edit.new)
-        else:
-            return content.replace(edit.old, edit.new, 1)
-
     @override
     async def __call__(self, params: Params) -> ToolReturnValue:
         if not params.path:
@@ -128,63 +197,82 @@ class StrReplaceFile(CallableTool2[Params]):
                     brief="Invalid path",
                 )
 
-            # Read the file content
-            content = await p.read_text(errors="replace")
+            if not params.force and (change := await self._file_tracker.check(p)) is not None:
+                return modified_externally_error(params.path, change)
+
+            # Read the file content, with CRLF line endings and any BOM stripped for editing
+            content, text_format = await read_text_for_edit(p)
 
             original_content = content
             edits = [params.edit] if isinstance(params.edit, Edit) else params.edit
 
-            # Apply all edits
-            for edit in edits:
-                content = self._apply_edit(content, edit)
+            # Apply all edits in memory; the file is only written if every one of them matches
+            content, outcomes = _apply_edits(content, edits)
+            failed = [outcome for outcome in outcomes if outcome.failure is not None]
+            if failed and isinstance(params.edit, list):
+                lines = [
+                    f"{len(failed)} of {len(edits)} edits did not match, so no edit was applied "
+                    "and the file was not changed:"
+                ]
+                for i, outcome in enumerate(outcomes, start=1):
+                    if outcome.failure is None:
+                        lines.append(f"- Edit {i}: matched at line {outcome.line}")
+                    else:
+                        lines.append(f"- Edit {i}: did not match: {outcome.failure}")
+                return ToolError(message="\n".join(lines), brief="Edit did not match")
 
             # Check if any changes were made
-            if content == original_content:
+            if failed or content == original_content:
                 return ToolError(
                     message="No replacements were made. The old string was not found in the file.",
                     brief="No replacements made",
                 )
+            # Only the growth counts, so that existing large files can still be edited
+            added = len(content.encode("utf-8")) - len(original_content.encode("utf-8"))
+            if added > self._max_write_bytes:
+                return write_too_large_error(params.path, added, self._max_write_bytes)
 
             diff_blocks: list[DisplayBlock] = await build_diff_blocks(
                 str(p), original_content, content
             )
 
-            action = (
-                FileActions.EDIT
-                if is_within_workspace(p, self._work_dir, self._additional_dirs)
-                else FileActions.EDIT_OUTSIDE
+            total_replacements = sum(outcome.replacements for outcome in outcomes)
+            message = (
+                f"File successfully edited. "
+                f"Applied {len(edits)} edit(s) with {total_replacements} total replacement(s)."
             )
 
+            if params.dry_run or self._runtime.dry_run:
+                return dry_run_result(f"Edit file `{p}`. {message}", diff_blocks)
+
             # Plan file edits are auto-approved; all other edits need approval.
             if not is_plan_file_edit:
-                result = await self._approval.request(
+                result = await request_edit_approval(
+                    self._approval,
                     self.name,
-                    action,
                     f"Edit file `{p}`",
+                    [p],
+                    work_dir=self._work_dir,
+                    additional_dirs=self._additional_dirs,
                     display=diff_blocks,
                 )
                 if not result:
                     return result.rejection_error()
 
-            # Write the modified content back to the file
-            await p.write_text(content, errors="replace")
+            if self._file_journal is not None:
+                await self._file_journal.record(p)
 
-            # Count changes for success message
-            total_replacements = 0
-            for edit in edits:
-                if edit.replace_all:
-                    total_replacements += original_content.count(edit.old)
-                else:
-                    total_replacements += 1 if edit.old in original_content else 0
+            # Write the modified content back in place, which keeps the file's permission
+            # bits, and restore its BOM and line endings
+            await p.write_text(text_format.apply(content), errors="replace")
+            await self._file_tracker.record(p)
 
             return ToolReturnValue(
                 is_error=False,
                 output="",
-                message=(
-                    f"File successfully edited. "
-                    f"Applied {len(edits)} edit(s) with {total_replacements} total replacement(s)."
-                ),
+                message=message,
                 display=diff_blocks,
+                extras={FILE_CHANGES_EXTRA: {str(p): "modified"}},
             )
 
         except Exception as e:
@@ -0,0 +1,261 @@
+"""
+A tool for previewing CSV and TSV files as aligned tables.
+"""
+
+import csv
+from collections.abc import AsyncIterator
+from contextlib import aclosing
+from pathlib import Path
+from typing import override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
+from pydantic import BaseModel, Field
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.file.utils import MEDIA_SNIFF_BYTES, detect_file_type
+from kimi_cli.tools.utils import load_desc
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
+from kimi_cli.utils.sensitive import is_sensitive_file
+
+DEFAULT_ROWS = 50
+MAX_ROWS = 500
+DEFAULT_COLUMNS = 20
+MAX_COLUMNS = 100
+MAX_CELL_CHARS = 40
+SNIFF_LINES = 20
+"""Lines used to detect the delimiter when it is not given."""
+
+_CANDIDATE_DELIMITERS = ",\t;|"
+_DELIMITER_NAMES = {",": "comma", "\t": "tab", ";": "semicolon", "|": "pipe", " ": "space"}
+_TSV_SUFFIXES = {".tsv", ".tab"}
+
+
+class Params(BaseModel):
+    path: str = Field(
+        description=(
+            "The path to the CSV or TSV file to read. Absolute paths are required when reading "
+            "files outside the working directory."
+        )
+    )
+    delimiter: str | None = Field(
+        default=None,
+        description=(
+            'The single-character field delimiter, e.g. "," or "\\t". '
+            "By default it is detected from the file."
+        ),
+        min_length=1,
+        max_length=1,
+    )
+    max_rows: int = Field(
+        default=DEFAULT_ROWS,
+        description=(
+            f"The number of rows to show, counting the header row. Defaults to {DEFAULT_ROWS}, "
+            f"at most {MAX_ROWS}."
+        ),
+        ge=1,
+        le=MAX_ROWS,
+    )
+    max_columns: int = Field(
+        default=DEFAULT_COLUMNS,
+        description=(
+            f"The number of columns to show. Defaults to {DEFAULT_COLUMNS}, at most {MAX_COLUMNS}."
+        ),
+        ge=1,
+        le=MAX_COLUMNS,
+    )
+
+
+def detect_delimiter(path: KaosPath, sample: str) -> str:
+    """Guess the delimiter of a table from its suffix and its first lines."""
+    if Path(path.name).suffix.lower() in _TSV_SUFFIXES:
+        return "\t"
+    try:
+        return csv.Sniffer().sniff(sample, delimiters=_CANDIDATE_DELIMITERS).delimiter
+    except csv.Error:
+        # Ragged rows confuse the sniffer; fall back to the most common candidate in the header
+        first_line = sample.split("\n", 1)[0]
+        counts = {d: first_line.count(d) for d in _CANDIDATE_DELIMITERS}
+        best = max(counts, key=lambda d: counts[d])
+        return best if counts[best] > 0 else ","
+
+
+async def _iter_records(lines: AsyncIterator[str]) -> AsyncIterator[list[str]]:
+    """
+    Group lines into the lines of each record, keeping together the lines of a quoted cell that
+    spans several of them.
+    """
+    record: list[str] = []
+    n_quotes = 0
+    async for line in lines:
+        record.append(line)
+        n_quotes += line.count('"')
+        if n_quotes % 2 == 0:
+            yield record
+            record, n_quotes = [], 0
+    if record:
+        yield record
+
+
+def _format_cell(cell: str) -> tuple[str, bool]:
+    cell = cell.replace("\r\n", "\\n").replace("\n", "\\n").replace("\t", " ")
+    if len(cell) <= MAX_CELL_CHARS:
+        return cell, False
+    return cell[: MAX_CELL_CHARS - 3] + "...", True
+
+
+def format_table(rows: list[list[str]]) -> tuple[str, bool]:
+    """
+    Render rows as an aligned table, numbering each row like `cat -n`.
+
+    Returns:
+        tuple[str, bool]: The table, and whether any cell was truncated.
+    """
+    n_columns = max((len(row) for row in rows), default=0)
+    truncated = False
+    cells: list[list[str]] = []
+    for row in rows:
+        formatted: list[str] = []
+        for cell in row + [""] * (n_columns - len(row)):
+            text, cut = _format_cell(cell)
+            formatted.append(text)
+            truncated = truncated or cut
+        cells.append(formatted)
+    widths = [max(len(row[i]) for row in cells) for i in range(n_columns)]
+
+    lines: list[str] = []
+    for row_no, row in enumerate(cells, start=1):
+        line = " | ".join(cell.ljust(width) for cell, width in zip(row, widths, strict=True))
+        lines.append(f"{row_no:6d}\t{line.rstrip()}\n")
+    return "".join(lines), truncated
+
+
+class ReadTable(CallableTool2[Params]):
+    name: str = "ReadTable"
+    description: str = load_desc(
+        Path(__file__).parent / "table.md",
+        {
+            "DEFAULT_ROWS": DEFAULT_ROWS,
+            "DEFAULT_COLUMNS": DEFAULT_COLUMNS,
+            "MAX_CELL_CHARS": MAX_CELL_CHARS,
+        },
+    )
+    params: type[Params] = Params
+
+    def __init__(self, runtime: Runtime) -> None:
+        super().__init__()
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._additional_dirs = runtime.additional_dirs
+        self._file_tracker = runtime.file_tracker
+
+    async def _validate_path(self, path: KaosPath) -> ToolError | None:
+        """Validate that the path is safe to read."""
+        if (
+            not is_within_workspace(path.canonical(), self._work_dir, self._additional_dirs)
+            and not path.is_absolute()
+        ):
+            return ToolError(
+                message=(
+                    f"`{path}` is not an absolute path. "
+                    "You must provide an absolute path to read a file "
+                    "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
+                ),
+                brief="Invalid path",
+            )
+        return None
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        if not params.path:
+            return ToolError(message="File path cannot be empty.", brief="Empty file path")
+
+        try:
+            p = kaos_path_from_user_input(params.path)
+            if err := await self._validate_path(p):
+                return err
+            p = p.canonical()
+
+            if is_sensitive_file(str(p)):
+                return ToolError(
+                    message=(
+                        f"`{params.path}` appears to contain secrets "
+                        "(matched sensitive file pattern). "
+                        "Reading this file is blocked to protect credentials."
+                    ),
+                    brief="Sensitive file",
+                )
+            if not await p.exists():
+                return ToolError(message=f"`{params.path}` does not exist.", brief="File not found")
+            if not await p.is_file():
+                return ToolError(message=f"`{params.path}` is not a file.", brief="Invalid path")
+            header = await p.read_bytes(MEDIA_SNIFF_BYTES)
+            if detect_file_type(str(p), header=header).kind != "text":
+                return ToolError(
+                    message=f"`{params.path}` is not a text file.",
+                    brief="Unsupported file type",
+                )
+
+            # Read one row past the limit to tell whether there are more, and no further
+            rows: list[list[str]] = []
+            async with aclosing(_iter_records(p.read_lines(errors="replace"))) as records:
+                sample: list[list[str]] = []
+                async for record in records:
+                    sample.append(record)
+                    if len(sample) == SNIFF_LINES:
+                        break
+                delimiter = params.delimiter or detect_delimiter(
+                    p, "".join(line for record in sample for line in record)
+                )
+                for record in sample:
+                    rows.extend(row for row in csv.reader(record, delimiter=delimiter) if row)
+                if len(rows) <= params.max_rows:
+                    async for record in records:
+                        rows.extend(row for row in csv.reader(record, delimiter=delimiter) if row)
+                        if len(rows) > params.max_rows:
+                            break
+            await self._file_tracker.record(p)
+        except csv.Error as e:
+            return ToolError(
+                message=f"`{params.path}` could not be parsed as a table: {e}",
+                brief="Invalid table",
+            )
+        except Exception as e:
+            logger.warning("ReadTable failed: {path}: {error}", path=params.path, error=e)
+            return ToolError(
+                message=f"Failed to read {params.path}. Error: {e}",
+                brief="Failed to read file",
+            )
+
+        if not rows:
+            return ToolOk(output="", message="The table is empty.", brief="Empty table")
+
+        more_rows = len(rows) > params.max_rows
+        rows = rows[: params.max_rows]
+        min_columns = min(len(row) for row in rows)
+        max_columns = max(len(row) for row in rows)
+        output, cells_truncated = format_table([row[: params.max_columns] for row in rows])
+        delimiter_name = _DELIMITER_NAMES.get(delimiter, repr(delimiter))
+        if more_rows:
+            message = (
+                f"Showing the first {len(rows)} rows, which have {max_columns} columns, "
+                f"delimited by {delimiter_name}. The table has more rows."
+            )
+        else:
+            message = f"{len(rows)} rows and {max_columns} columns, delimited by {delimiter_name}."
+        if min_columns != max_columns:
+            message += f" Rows have between {min_columns} and {max_columns} columns."
+        if max_columns > params.max_columns:
+            message += f" Showing the first {params.max_columns} columns."
+        if cells_truncated:
+            message += f" Cells longer than {MAX_CELL_CHARS} characters were truncated."
+        return ToolOk(
+            output=output,
+            message=message,
+            brief=f"{len(rows)}{'+' if more_rows else ''} rows x {max_columns} columns",
+        )
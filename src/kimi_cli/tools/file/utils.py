@@ -1,11 +1,27 @@
 from __future__ import annotations
 
+import asyncio
+import codecs
 import mimetypes
+from collections.abc import Sequence
 from dataclasses import dataclass
 from pathlib import PurePath
 from typing import Literal
 
-MEDIA_SNIFF_BYTES = 512
+import charset_normalizer
+from kaos import SNIFF_BYTES, ensure_text, is_binary
+from kaos.path import KaosPath
+from kosong.tooling import ToolError
+
+from kimi_cli.soul.approval import Approval, ApprovalResult
+from kimi_cli.tools.display import DisplayBlock
+from kimi_cli.tools.file import FileActions
+from kimi_cli.utils.path import is_within_workspace
+
+MEDIA_SNIFF_BYTES = SNIFF_BYTES
+
+VCS_DIRS = (".git", ".svn", ".hg", ".bzr", ".jj", ".sl")
+"""Version control metadata directories that `Glob` and `Grep` never descend into."""
 
 _EXTRA_MIME_TYPES = {
     ".avif": "image/avif",
@@ -246,8 +262,7 @@ def detect_file_type(path: str | PurePath, header: bytes | None = None) -> FileT
             if media_hint and sniffed.kind != media_hint.kind:
                 return FileType(kind="unknown", mime_type="")
             return sniffed
-        # NUL bytes are a strong signal of binary content.
-        if b"\x00" in header:
+        if is_binary(header):
             return FileType(kind="unknown", mime_type="")
 
     if media_hint:
@@ -255,3 +270,187 @@ def detect_file_type(path: str | PurePath, header: bytes | None = None) -> FileT
     if suffix in _NON_TEXT_SUFFIXES:
         return FileType(kind="unknown", mime_type="")
     return FileType(kind="text", mime_type="text/plain")
+
+
+UTF8_BOM = "\ufeff"
+
+
+@dataclass(frozen=True)
+class TextFormat:
+    """How a text file is laid out on disk, so that edits can write it back the same way."""
+
+    bom: bool = False
+    line_ending: Literal["\n", "\r\n"] = "\n"
+
+    @staticmethod
+    def detect(raw: str) -> TextFormat:
+        """Detect the BOM and the dominant line ending of the raw file content."""
+        crlf = raw.count("\r\n")
+        lf = raw.count("\n") - crlf
+        return TextFormat(bom=raw.startswith(UTF8_BOM), line_ending="\r\n" if crlf > lf else "\n")
+
+    @staticmethod
+    def normalize(text: str) -> str:
+        """Strip the BOM and convert CRLF line endings to LF."""
+        return text.removeprefix(UTF8_BOM).replace("\r\n", "\n")
+
+    def apply(self, text: str, *, bom: bool = True) -> str:
+        """Convert normalized text back to this format. Pass `bom=False` when appending."""
+        text = self.normalize(text)
+        if self.line_ending != "\n":
+            text = text.replace("\n", self.line_ending)
+        return UTF8_BOM + text if self.bom and bom else text
+
+
+async def read_text_for_edit(path: KaosPath) -> tuple[str, TextFormat]:
+    """
+    Read a text file that is about to be edited.
+
+    Returns:
+        tuple[str, TextFormat]: The normalized content, which is what edits and diffs work on,
+            and the format to write it back with.
+
+    Raises:
+        BinaryFileError: If the file looks like a binary file.
+    """
+    return decode_text_for_edit(path, await path.read_bytes())
+
+
+def decode_text_for_edit(path: KaosPath, data: bytes) -> tuple[str, TextFormat]:
+    """
+    Decode the content of a text file that is about to be edited, like `read_text_for_edit`.
+
+    Raises:
+        BinaryFileError: If the file looks like a binary file.
+    """
+    ensure_text(path, data)
+    raw = data.decode("utf-8", errors="replace")
+    return TextFormat.normalize(raw), TextFormat.detect(raw)
+
+
+ENCODING_SAMPLE_BYTES = 64 << 10  # 64KB
+"""Number of leading bytes of a file that its encoding is detected from."""
+MIN_DETECTION_BYTES = 16
+"""Detection needs at least this many non-ASCII bytes in the sample to be trusted."""
+
+
+def resolve_text_encoding(encoding: str) -> str | None:
+    """
+    Return the normalized name of an encoding that files can be read with line by line, or None
+    if the encoding is unknown or not ASCII-compatible (e.g. UTF-16), since lines are split on
+    LF bytes before they are decoded.
+    """
+    try:
+        name = codecs.lookup(encoding).name
+        ascii_compatible = "\r\n".encode(name) == b"\r\n"
+    except (LookupError, UnicodeError):
+        return None
+    return name if ascii_compatible else None
+
+
+async def detect_text_encoding(path: KaosPath) -> str | None:
+    """
+    Detect the encoding of a text file that is not UTF-8, e.g. Latin-1 or Shift-JIS.
+
+    Returns None if the leading bytes of the file are valid UTF-8, or if they are too few or too
+    ambiguous to tell the encoding; the file should then be read as UTF-8 with invalid bytes
+    replaced.
+    """
+    sample = await path.read_bytes(ENCODING_SAMPLE_BYTES)
+    try:
+        # Not final, so that a character cut at the end of the sample is not an error
+        codecs.getincrementaldecoder("utf-8")().decode(sample)
+        return None
+    except UnicodeDecodeError:
+        pass
+    if sum(byte >= 0x80 for byte in sample) < MIN_DETECTION_BYTES:
+        return None
+    best = (await asyncio.to_thread(charset_normalizer.from_bytes, sample)).best()
+    if best is None:
+        return None
+    encoding = resolve_text_encoding(best.encoding)
+    return None if encoding == "utf-8" else encoding
+
+
+FORCE_DESCRIPTION = (
+    "Write the file even if it has been modified since you last read or wrote it, e.g. by the "
+    "user. Only set this after you have reviewed the external changes. Defaults to false."
+)
+
+
+DRY_RUN_DESCRIPTION = (
+    "Only check the edit and show its diff and result, without changing any file or asking for "
+    "approval. Use it to preview a large change. Defaults to false."
+)
+
+
+def modified_externally_error(path: str, change: str) -> ToolError:
+    """The error returned when a file changed since the agent last read or wrote it."""
+    return ToolError(
+        message=(
+            f"`{path}` has been modified since you last read or wrote it, e.g. by the user or "
+            f"a shell command. {change}\nRead the file again and merge your changes into it, "
+            "or set `force` to true to overwrite the external changes."
+        ),
+        brief="File modified externally",
+    )
+
+
+def write_too_large_error(path: str, size: int, limit: int) -> ToolError:
+    """The error returned when a single call would write more than `max_write_bytes`."""
+    return ToolError(
+        message=(
+            f"This call would write {size} bytes to `{path}`, more than the limit of {limit} "
+            "bytes per call. Split the work into smaller steps, e.g. write the file in parts "
+            "with `append` mode, or split the content into several files."
+        ),
+        brief="File too large",
+    )
+
+
+MAX_REPORTED_UNREADABLE = 5
+"""How many unreadable entries `unreadable_entries_message` lists by path."""
+
+
+def unreadable_entries_message(paths: Sequence[str], *, permission_errors: bool) -> str:
+    """
+    Summarize the entries a search skipped because they could not be read, listing the first
+    `MAX_REPORTED_UNREADABLE` of them.
+    """
+    reason = "permission errors" if permission_errors else "read errors"
+    noun = "entry" if len(paths) == 1 else "entries"
+    listed = ", ".join(f"`{p}`" for p in paths[:MAX_REPORTED_UNREADABLE])
+    message = f"Skipped {len(paths)} {noun} due to {reason}: {listed}"
+    if len(paths) > MAX_REPORTED_UNREADABLE:
+        message += f" and {len(paths) - MAX_REPORTED_UNREADABLE} more"
+    return message + "."
+
+
+async def request_edit_approval(
+    approval: Approval,
+    sender: str,
+    description: str,
+    paths: Sequence[KaosPath],
+    *,
+    work_dir: KaosPath,
+    additional_dirs: Sequence[KaosPath],
+    display: list[DisplayBlock],
+) -> ApprovalResult:
+    """
+    Request approval for editing or writing `paths`.
+
+    Edits inside the workspace use the `EDIT` action. If any path is outside of it, the request
+    uses the `EDIT_OUTSIDE` action instead, names those paths in the description, and cannot be
+    approved for the whole session, so that the user confirms every edit outside the project.
+    """
+    outside = [p for p in paths if not is_within_workspace(p, work_dir, additional_dirs)]
+    if not outside:
+        return await approval.request(sender, FileActions.EDIT, description, display=display)
+    description += "\nOutside the working directory: " + ", ".join(f"`{p}`" for p in outside)
+    return await approval.request(
+        sender,
+        FileActions.EDIT_OUTSIDE,
+        description,
+        display=display,
+        allow_session_approval=False,
+    )
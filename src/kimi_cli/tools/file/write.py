@@ -2,21 +2,32 @@ from collections.abc import Callable
 from pathlib import Path
 from typing import Literal, override
 
+from kaos import BinaryFileError
 from kaos.path import KaosPath
 from kosong.tooling import CallableTool2, ToolError, ToolReturnValue
 from pydantic import BaseModel, Field
 
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.turn_summary import FILE_CHANGES_EXTRA
 from kimi_cli.tools.display import DisplayBlock
-from kimi_cli.tools.file import FileActions
 from kimi_cli.tools.file.plan_mode import inspect_plan_edit_target
-from kimi_cli.tools.utils import load_desc
+from kimi_cli.tools.file.utils import (
+    DRY_RUN_DESCRIPTION,
+    TextFormat,
+    modified_externally_error,
+    read_text_for_edit,
+    request_edit_approval,
+    write_too_large_error,
+)
+from kimi_cli.tools.utils import dry_run_result, load_desc
 from kimi_cli.utils.diff import build_diff_blocks
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.path import is_within_workspace, kaos_path_from_user_input
-
-_BASE_DESCRIPTION = load_desc(Path(__file__).parent / "write.md")
+from kimi_cli.utils.path import (
+    is_within_workspace,
+    kaos_path_from_user_input,
+    outside_workspace_hint,
+)
 
 
 class Params(BaseModel):
@@ -35,18 +46,35 @@ class Params(BaseModel):
         ),
         default="overwrite",
     )
+    force: bool = Field(
+        default=False,
+        description=(
+            "Overwrite the file even if you have not read it in this session, or it has been "
+            "modified since you last read or wrote it, e.g. by the user. Only set this when "
+            "you are sure the current content can be discarded. Defaults to false."
+        ),
+    )
+    dry_run: bool = Field(default=False, description=DRY_RUN_DESCRIPTION)
 
 
 class WriteFile(CallableTool2[Params]):
     name: str = "WriteFile"
-    description: str = _BASE_DESCRIPTION
     params: type[Params] = Params
 
     def __init__(self, runtime: Runtime, approval: Approval):
-        super().__init__()
+        super().__init__(
+            description=load_desc(
+                Path(__file__).parent / "write.md",
+                {"MAX_WRITE_BYTES": runtime.config.tools.max_write_bytes},
+            )
+        )
         self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
         self._additional_dirs = runtime.additional_dirs
         self._approval = approval
+        self._runtime = runtime
+        self._file_journal = runtime.file_journal
+        self._file_tracker = runtime.file_tracker
+        self._max_write_bytes = runtime.config.tools.max_write_bytes
         self._plan_mode_checker: Callable[[], bool] | None = None
         self._plan_file_path_getter: Callable[[], Path | None] | None = None
 
@@ -70,6 +98,7 @@ class WriteFile(CallableTool2[Params]):
                     f"`{path}` is not an absolute path. "
                     "You must provide an absolute path to write a file "
                     "outside the working directory."
+                    + outside_workspace_hint(self._work_dir, self._additional_dirs)
                 ),
                 brief="Invalid path",
             )
@@ -104,11 +133,17 @@ class WriteFile(CallableTool2[Params]):
             if is_plan_file_write and plan_target.plan_path is not None:
                 plan_target.plan_path.parent.mkdir(parents=True, exist_ok=True)
 
-            if not await p.parent.exists():
+            parent_metadata = await p.parent.metadata()
+            if not parent_metadata.exists:
                 return ToolError(
                     message=f"`{params.path}` parent directory does not exist.",
                     brief="Parent directory not found",
                 )
+            if not parent_metadata.is_dir:
+                return ToolError(
+                    message=f"`{params.path}` parent is not a directory.",
+                    brief="Invalid path",
+                )
 
             # Validate mode parameter
             if params.mode not in ["overwrite", "append"]:
@@ -120,53 +155,99 @@ class WriteFile(CallableTool2[Params]):
                     brief="Invalid write mode",
                 )
 
-            file_existed = await p.exists()
+            target_metadata = await p.metadata()
+            file_existed = target_metadata.exists
+            if (
+                file_existed
+                and params.mode == "overwrite"
+                and not params.force
+                and not is_plan_file_write
+                and not self._file_tracker.has_seen(p)
+            ):
+                return ToolError(
+                    message=(
+                        f"`{params.path}` already exists and has not been read in this session. "
+                        "Read it first so that overwriting it does not lose content you have "
+                        "not seen, or set `force` to true to overwrite it anyway."
+                    ),
+                    brief="File not read",
+                )
+            if (
+                file_existed
+                and not params.force
+                and (change := await self._file_tracker.check(p)) is not None
+            ):
+                return modified_externally_error(params.path, change)
+
             old_text = None
+            # New files are written with LF line endings and no BOM.
+            text_format = TextFormat()
             if file_existed:
-                old_text = await p.read_text(errors="replace")
+                try:
+                    old_text, text_format = await read_text_for_edit(p)
+                except BinaryFileError:
+                    if params.mode == "append":
+                        raise
+                    # Overwriting a binary file: there is no meaningful text to diff against.
 
-            new_text = (
-                params.content if params.mode == "overwrite" else (old_text or "") + params.content
-            )
+            content = TextFormat.normalize(params.content)
+            # Overwriting writes the whole content, appending only adds it to the file
+            if (size := len(content.encode("utf-8"))) > self._max_write_bytes:
+                return write_too_large_error(params.path, size, self._max_write_bytes)
+            new_text = content if params.mode == "overwrite" else (old_text or "") + content
             diff_blocks: list[DisplayBlock] = await build_diff_blocks(
                 str(p),
                 old_text or "",
                 new_text,
             )
 
-            # Plan file writes are auto-approved; other writes need approval
-            if not is_plan_file_write:
-                action = (
-                    FileActions.EDIT
-                    if is_within_workspace(p, self._work_dir, self._additional_dirs)
-                    else FileActions.EDIT_OUTSIDE
+            action = "overwritten" if params.mode == "overwrite" else "appended to"
+            if params.dry_run or self._runtime.dry_run:
+                written = text_format.apply(content, bom=params.mode == "overwrite")
+                file_size = len(written.encode("utf-8"))
+                if params.mode == "append" and file_existed:
+                    file_size += target_metadata.size
+                return dry_run_result(
+                    f"Write file `{p}`. File successfully {action}. "
+                    f"Current size: {file_size} bytes.",
+                    diff_blocks,
                 )
 
-                # Request approval
-                result = await self._approval.request(
+            # Plan file writes are auto-approved; other writes need approval
+            if not is_plan_file_write:
+                result = await request_edit_approval(
+                    self._approval,
                     self.name,
-                    action,
                     f"Write file `{p}`",
+                    [p],
+                    work_dir=self._work_dir,
+                    additional_dirs=self._additional_dirs,
                     display=diff_blocks,
                 )
                 if not result:
                     return result.rejection_error()
 
-            # Write content to file
+            if self._file_journal is not None:
+                await self._file_journal.record(p)
+
+            # Write content to file, keeping the BOM and line endings of an existing file.
+            # An overwrite replaces the file atomically, keeping its permission bits, so that a
+            # crash cannot leave it half-written.
             match params.mode:
                 case "overwrite":
-                    await p.write_text(params.content)
+                    await p.write_bytes_atomic(text_format.apply(content).encode("utf-8"))
                 case "append":
-                    await p.append_text(params.content)
+                    await p.append_text(text_format.apply(content, bom=False))
+            await self._file_tracker.record(p)
 
             # Get file info for success message
             file_size = (await p.stat()).st_size
-            action = "overwritten" if params.mode == "overwrite" else "appended to"
             return ToolReturnValue(
                 is_error=False,
                 output="",
                 message=(f"File successfully {action}. Current size: {file_size} bytes."),
                 display=diff_blocks,
+                extras={FILE_CHANGES_EXTRA: {str(p): "modified" if file_existed else "created"}},
             )
 
         except Exception as e:
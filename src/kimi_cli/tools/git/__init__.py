@@ -0,0 +1,420 @@
+import asyncio
+from dataclasses import dataclass, field
+from pathlib import Path
+from typing import Literal, override
+
+import kaos
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, DisplayBlock, ToolError, ToolReturnValue
+from kosong.utils.typing import JsonType
+from pydantic import BaseModel, Field
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.soul.approval import Approval
+from kimi_cli.tools.display import DiffDisplayBlock
+from kimi_cli.tools.utils import ToolResultBuilder, dry_run_result, load_desc
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.subprocess_env import get_noninteractive_env
+
+GIT_TIMEOUT = 60
+MAX_LOG_ENTRIES = 100
+MAX_DISPLAY_DIFF_CHARS = 20_000
+"""Per-file diff size above which only a summary display block is emitted."""
+
+_FIELD_SEP = "\x1f"
+
+
+class Params(BaseModel):
+    op: Literal["status", "diff", "log", "commit"] = Field(
+        description="The git operation to perform."
+    )
+    staged: bool = Field(
+        default=False,
+        description="For `diff`: show staged changes instead of unstaged ones.",
+    )
+    paths: list[str] = Field(
+        default_factory=list[str],
+        description="For `diff`: only show changes under these paths.",
+    )
+    n: int = Field(
+        default=10,
+        ge=1,
+        le=MAX_LOG_ENTRIES,
+        description="For `log`: the number of commits to show.",
+    )
+    message: str = Field(
+        default="",
+        description="For `commit`: the commit message. Required when `op` is `commit`.",
+    )
+    add_all: bool = Field(
+        default=False,
+        description=(
+            "For `commit`: stage all changes, including untracked files, before committing."
+        ),
+    )
+
+
+@dataclass(slots=True)
+class GitStatus:
+    """Structured summary of `git status --porcelain=v2`."""
+
+    branch: str | None = None
+    upstream: str | None = None
+    ahead: int = 0
+    behind: int = 0
+    staged: list[tuple[str, str]] = field(default_factory=list[tuple[str, str]])
+    unstaged: list[tuple[str, str]] = field(default_factory=list[tuple[str, str]])
+    untracked: list[str] = field(default_factory=list[str])
+    conflicted: list[str] = field(default_factory=list[str])
+
+    @property
+    def is_clean(self) -> bool:
+        return not (self.staged or self.unstaged or self.untracked or self.conflicted)
+
+    def to_json(self) -> dict[str, JsonType]:
+        return {
+            "branch": self.branch,
+            "upstream": self.upstream,
+            "ahead": self.ahead,
+            "behind": self.behind,
+            "staged": [{"status": s, "path": p} for s, p in self.staged],
+            "unstaged": [{"status": s, "path": p} for s, p in self.unstaged],
+            "untracked": list(self.untracked),
+            "conflicted": list(self.conflicted),
+        }
+
+    def format(self) -> str:
+        lines = [f"branch: {self.branch or '(unknown)'}"]
+        if self.upstream:
+            lines.append(f"upstream: {self.upstream} (ahead {self.ahead}, behind {self.behind})")
+        if self.is_clean:
+            lines.append("working tree clean")
+            return "\n".join(lines) + "\n"
+        for title, entries in (("staged", self.staged), ("unstaged", self.unstaged)):
+            if entries:
+                lines.append(f"{title}:")
+                lines.extend(f"  {status} {path}" for status, path in entries)
+        for title, paths in (("untracked", self.untracked), ("conflicted", self.conflicted)):
+            if paths:
+                lines.append(f"{title}:")
+                lines.extend(f"  {path}" for path in paths)
+        return "\n".join(lines) + "\n"
+
+
+def parse_status_porcelain_v2(output: str) -> GitStatus:
+    """Parse the NUL-separated output of `git status --porcelain=v2 --branch -z`."""
+    status = GitStatus()
+    entries = output.split("\0")
+    i = 0
+    while i < len(entries):
+        entry = entries[i]
+        i += 1
+        if not entry:
+            continue
+        if entry.startswith("# "):
+            key, _, value = entry[2:].partition(" ")
+            match key:
+                case "branch.head":
+                    status.branch = None if value == "(detached)" else value
+                case "branch.upstream":
+                    status.upstream = value
+                case "branch.ab":
+                    ahead, _, behind = value.partition(" ")
+                    status.ahead = int(ahead.lstrip("+") or 0)
+                    status.behind = int(behind.lstrip("-") or 0)
+                case _:
+                    pass
+            continue
+        kind = entry[0]
+        if kind == "?":
+            status.untracked.append(entry[2:])
+        elif kind == "u":
+            # u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+            status.conflicted.append(entry.split(" ", 10)[10])
+        elif kind in ("1", "2"):
+            # 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
+            # 2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <X><score> <path>\0<origPath>
+            n_fields = 9 if kind == "1" else 10
+            parts = entry.split(" ", n_fields - 1)
+            xy, path = parts[1], parts[-1]
+            if kind == "2" and i < len(entries):
+                path = f"{entries[i]} -> {path}"
+                i += 1
+            if xy[0] != ".":
+                status.staged.append((xy[0], path))
+            if xy[1] != ".":
+                status.unstaged.append((xy[1], path))
+    return status
+
+
+def split_diff_by_file(diff: str) -> list[tuple[str, str]]:
+    """Split a multi-file unified diff into `(path, file_diff)` pairs."""
+    files: list[tuple[str, str]] = []
+    current: list[str] = []
+
+    def _flush() -> None:
+        if not current:
+            return
+        path = ""
+        for line in current:
+            if line.startswith("+++ ") and line[4:].strip() != "/dev/null":
+                path = line[4:].strip().removeprefix("b/")
+                break
+            if line.startswith("--- ") and line[4:].strip() != "/dev/null":
+                path = line[4:].strip().removeprefix("a/")
+        if not path:
+            # binary or mode-only changes have no ---/+++ header
+            path = current[0].rsplit(" b/", 1)[-1].strip()
+        files.append((path, "".join(current)))
+        current.clear()
+
+    for line in diff.splitlines(keepends=True):
+        if line.startswith("diff --git "):
+            _flush()
+        current.append(line)
+    _flush()
+    return files
+
+
+def build_file_diff_blocks(path: str, file_diff: str) -> list[DisplayBlock]:
+    """Convert the hunks of a single-file unified diff into display blocks."""
+    if len(file_diff) > MAX_DISPLAY_DIFF_CHARS:
+        added = sum(
+            1 for ln in file_diff.splitlines() if ln.startswith("+") and not ln.startswith("+++")
+        )
+        removed = sum(
+            1 for ln in file_diff.splitlines() if ln.startswith("-") and not ln.startswith("---")
+        )
+        return [
+            DiffDisplayBlock(
+                path=path,
+                old_text=f"({removed} lines removed)",
+                new_text=f"({added} lines added)",
+                is_summary=True,
+            )
+        ]
+
+    blocks: list[DisplayBlock] = []
+    old_lines: list[str] = []
+    new_lines: list[str] = []
+    old_start = new_start = 1
+    in_hunk = False
+
+    def _flush() -> None:
+        if in_hunk and (old_lines or new_lines):
+            blocks.append(
+                DiffDisplayBlock(
+                    path=path,
+                    old_text="\n".join(old_lines),
+                    new_text="\n".join(new_lines),
+                    old_start=old_start,
+                    new_start=new_start,
+                )
+            )
+        old_lines.clear()
+        new_lines.clear()
+
+    for line in file_diff.splitlines():
+        if line.startswith("@@"):
+            _flush()
+            in_hunk = True
+            # @@ -a[,b] +c[,d] @@
+            header = line.split("@@")[1].split()
+            old_start = max(int(header[0][1:].split(",")[0]), 1)
+            new_start = max(int(header[1][1:].split(",")[0]), 1)
+            continue
+        if not in_hunk or line.startswith("\\"):
+            continue
+        if line.startswith(" "):
+            old_lines.append(line[1:])
+            new_lines.append(line[1:])
+        elif line.startswith("-"):
+            old_lines.append(line[1:])
+        elif line.startswith("+"):
+            new_lines.append(line[1:])
+    _flush()
+    return blocks
+
+
+async def run_git(work_dir: KaosPath, *args: str) -> tuple[int, str, str]:
+    """
+    Run git in `work_dir` without prompts and return its exit code, stdout and stderr.
+
+    Raises:
+        TimeoutError: When git does not finish within `GIT_TIMEOUT` seconds.
+    """
+    env = get_noninteractive_env()
+    env["GIT_TERMINAL_PROMPT"] = "0"
+    process = await kaos.exec("git", "-C", str(work_dir), *args, env=env)
+    process.stdin.close()
+    try:
+        stdout, stderr = await asyncio.wait_for(
+            asyncio.gather(process.stdout.read(), process.stderr.read()), GIT_TIMEOUT
+        )
+        code = await process.wait()
+    except (asyncio.CancelledError, TimeoutError):
+        await process.kill()
+        raise
+    return (
+        code,
+        stdout.decode("utf-8", errors="replace"),
+        stderr.decode("utf-8", errors="replace"),
+    )
+
+
+async def read_git_status(work_dir: KaosPath) -> GitStatus:
+    """Read the status of the work tree at `work_dir`. Raises RuntimeError if git fails."""
+    code, out, err = await run_git(work_dir, "status", "--porcelain=v2", "--branch", "-z")
+    if code != 0:
+        raise RuntimeError(err.strip() or f"git status exited with code {code}")
+    return parse_status_porcelain_v2(out)
+
+
+class Git(CallableTool2[Params]):
+    name: str = "Git"
+    description: str = load_desc(Path(__file__).parent / "git.md")
+    params: type[Params] = Params
+
+    def __init__(self, runtime: Runtime, approval: Approval):
+        super().__init__()
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+        self._approval = approval
+        self._runtime = runtime
+
+    @override
+    async def __call__(self, params: Params) -> ToolReturnValue:
+        try:
+            code, out, _ = await self._git("rev-parse", "--is-inside-work-tree")
+            if code != 0 or out.strip() != "true":
+                return ToolError(
+                    message=(
+                        f"`{self._work_dir}` is not inside a git work tree. "
+                        "The Git tool only operates on git repositories; "
+                        "run `git init` first if you intend to create one."
+                    ),
+                    brief="Not a git repository",
+                )
+
+            match params.op:
+                case "status":
+                    return await self._status()
+                case "diff":
+                    return await self._diff(params.staged, params.paths)
+                case "log":
+                    return await self._log(params.n)
+                case "commit":
+                    return await self._commit(params.message, params.add_all)
+        except TimeoutError:
+            return ToolError(
+                message=f"git {params.op} timed out after {GIT_TIMEOUT}s.",
+                brief="Timeout",
+            )
+        except Exception as e:
+            logger.warning("Git {op} failed: {error}", op=params.op, error=e)
+            return ToolError(message=f"Failed to run git {params.op}. Error: {e}", brief="Failed")
+
+    async def _git(self, *args: str) -> tuple[int, str, str]:
+        return await run_git(self._work_dir, *args)
+
+    async def _read_status(self) -> GitStatus:
+        return await read_git_status(self._work_dir)
+
+    async def _status(self) -> ToolReturnValue:
+        status = await self._read_status()
+        builder = ToolResultBuilder()
+        builder.write(status.format())
+        builder.extras(status=status.to_json())
+        brief = "Clean" if status.is_clean else f"On {status.branch or 'detached HEAD'}"
+        return builder.ok(brief=brief)
+
+    async def _diff(self, staged: bool, paths: list[str]) -> ToolReturnValue:
+        args = ["diff", "--no-color", "--no-ext-diff"]
+        if staged:
+            args.append("--cached")
+        args.append("--")
+        args.extend(paths)
+        code, out, err = await self._git(*args)
+        builder = ToolResultBuilder()
+        if code != 0:
+            builder.write(err)
+            return builder.error(f"git diff exited with code {code}.", brief="Diff failed")
+        if not out:
+            which = "staged" if staged else "unstaged"
+            return builder.ok(f"No {which} changes", brief="No changes")
+
+        files = split_diff_by_file(out)
+        builder.write(out)
+        for path, file_diff in files:
+            builder.display(*build_file_diff_blocks(path, file_diff))
+        return builder.ok(brief=f"{len(files)} file(s) changed")
+
+    async def _log(self, n: int) -> ToolReturnValue:
+        code, out, err = await self._git(
+            "log",
+            f"-n{n}",
+            "--date=short",
+            f"--pretty=format:%h{_FIELD_SEP}%ad{_FIELD_SEP}%an{_FIELD_SEP}%s",
+        )
+        builder = ToolResultBuilder()
+        if code != 0:
+            builder.write(err)
+            return builder.error(f"git log exited with code {code}.", brief="Log failed")
+
+        commits: list[JsonType] = []
+        for line in out.splitlines():
+            sha, date, author, subject = line.split(_FIELD_SEP, 3)
+            commits.append({"sha": sha, "date": date, "author": author, "subject": subject})
+            builder.write(f"{sha} {date} {author}: {subject}\n")
+        builder.extras(commits=commits)
+        return builder.ok(brief=f"{len(commits)} commit(s)")
+
+    async def _commit(self, message: str, add_all: bool) -> ToolReturnValue:
+        if not message.strip():
+            return ToolError(message="A commit message is required.", brief="Empty message")
+
+        status = await self._read_status()
+        if status.conflicted:
+            return ToolError(
+                message=(
+                    "Cannot commit with unresolved conflicts: " + ", ".join(status.conflicted)
+                ),
+                brief="Unresolved conflicts",
+            )
+        files = [path for _, path in status.staged]
+        if add_all:
+            files.extend(path for _, path in status.unstaged if path not in files)
+            files.extend(status.untracked)
+        if not files:
+            return ToolError(
+                message=(
+                    "Nothing to commit. Stage changes first or set `add_all` to commit "
+                    "all changes in the working tree."
+                ),
+                brief="Nothing to commit",
+            )
+
+        file_list = "\n".join(f"- {path}" for path in files)
+        description = f"Commit {len(files)} file(s) with message:\n{message}\n\nFiles:\n{file_list}"
+        if self._runtime.dry_run:
+            return dry_run_result(description)
+
+        result = await self._approval.request(self.name, "git commit", description)
+        if not result:
+            return result.rejection_error()
+
+        builder = ToolResultBuilder()
+        if add_all:
+            code, _, err = await self._git("add", "-A")
+            if code != 0:
+                builder.write(err)
+                return builder.error(f"git add exited with code {code}.", brief="Add failed")
+
+        code, out, err = await self._git("commit", "-m", message)
+        builder.write(out + err)
+        if code != 0:
+            return builder.error(f"git commit exited with code {code}.", brief="Commit failed")
+
+        _, sha, _ = await self._git("rev-parse", "--short", "HEAD")
+        sha = sha.strip()
+        builder.extras(sha=sha, files=list(files))
+        return builder.ok(f"Committed {sha}", brief=f"Committed {sha}")
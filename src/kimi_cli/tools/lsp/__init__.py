@@ -0,0 +1,152 @@
+from pathlib import Path
+from typing import override
+
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
+from pydantic import BaseModel, Field
+
+from kimi_cli.lsp import Location, LSPClient, LSPManager, parse_locations
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.utils import ToolResultBuilder, load_desc
+from kimi_cli.utils.logging import logger
+from kimi_cli.utils.path import is_within_directory, kaos_path_from_user_input
+
+CONTEXT_LINES = 2
+MAX_REFERENCES = 200
+
+
+class Position(BaseModel):
+    path: str = Field(description="The path to the file containing the symbol.")
+    line: int = Field(description="The 1-based line number of the symbol.", ge=1)
+    column: int = Field(description="The 1-based column number of the symbol.", ge=1)
+
+
+class ReferencesParams(Position):
+    limit: int = Field(
+        description="The maximum number of references to return.",
+        default=50,
+        ge=1,
+        le=MAX_REFERENCES,
+    )
+
+
+class _LSPTool[P: Position](CallableTool2[P]):
+    def _bind(self, runtime: Runtime) -> None:
+        assert runtime.lsp is not None
+        self._lsp: LSPManager = runtime.lsp
+        self._work_dir = runtime.builtin_args.KIMI_WORK_DIR
+
+    async def _prepare(self, params: Position) -> tuple[LSPClient, str] | ToolReturnValue:
+        p = kaos_path_from_user_input(params.path)
+        if not p.is_absolute():
+            p = self._work_dir / p
+        p = p.canonical()
+        if not await p.is_file():
+            return ToolError(message=f"`{params.path}` is not a file.", brief="File not found")
+        if self._lsp.server_for(p) is None:
+            suffix = Path(str(p)).suffix or p.name
+            return ToolOk(
+                output="",
+                message=(
+                    f"LSP not configured for {suffix}. "
+                    "Fall back to Grep to locate the symbol."
+                ),
+                brief=f"LSP not configured for {suffix}",
+            )
+        client = await self._lsp.client_for(p)
+        assert client is not None
+        uri = await client.sync_document(p)
+        return client, uri
+
+    async def _request(
+        self, params: Position, method: str, extra: dict[str, object]
+    ) -> list[Location] | ToolReturnValue:
+        try:
+            prepared = await self._prepare(params)
+            if isinstance(prepared, ToolReturnValue):
+                return prepared
+            client, uri = prepared
+            result = await client.request(
+                method,
+                {
+                    "textDocument": {"uri": uri},
+                    "position": {"line": params.line - 1, "character": params.column - 1},
+                    **extra,
+                },
+            )
+            return parse_locations(result)
+        except Exception as e:
+            logger.warning("{tool} failed: {error}", tool=self.name, error=e)
+            return ToolError(
+                message=f"Language server request failed: {e}",
+                brief="LSP request failed",
+            )
+
+    async def _format(self, locations: list[Location], noun: str) -> ToolReturnValue:
+        builder = ToolResultBuilder()
+        if not locations:
+            return builder.ok(f"No {noun} found", brief=f"No {noun}")
+        # Locations often share a file, which is read once for all of them
+        files: dict[str, list[str]] = {}
+        for loc in locations:
+            path = KaosPath(loc.path)
+            display = (
+                str(path.relative_to(self._work_dir))
+                if is_within_directory(path, self._work_dir)
+                else loc.path
+            )
+            if loc.path not in files:
+                files[loc.path] = await _read_lines(path)
+            builder.write(f"{display}:{loc.line + 1}:{loc.character + 1}\n")
+            builder.write(_context(files[loc.path], loc.line))
+            builder.write("\n")
+        return builder.ok(brief=f"{len(locations)} {noun}")
+
+
+async def _read_lines(path: KaosPath) -> list[str]:
+    try:
+        return (await path.read_text(errors="replace")).splitlines()
+    except Exception:
+        return []
+
+
+def _context(lines: list[str], line: int) -> str:
+    start = max(line - CONTEXT_LINES, 0)
+    end = min(line + CONTEXT_LINES + 1, len(lines))
+    return "".join(f"{'>' if i == line else ' '}{i + 1:6}\t{lines[i]}\n" for i in range(start, end))
+
+
+class GoToDefinition(_LSPTool[Position]):
+    name: str = "GoToDefinition"
+    description: str = load_desc(Path(__file__).parent / "definition.md")
+    params: type[Position] = Position
+
+    def __init__(self, runtime: Runtime) -> None:
+        super().__init__()
+        self._bind(runtime)
+
+    @override
+    async def __call__(self, params: Position) -> ToolReturnValue:
+        locations = await self._request(params, "textDocument/definition", {})
+        if isinstance(locations, ToolReturnValue):
+            return locations
+        return await self._format(locations, "definition(s)")
+
+
+class FindReferences(_LSPTool[ReferencesParams]):
+    name: str = "FindReferences"
+    description: str = load_desc(Path(__file__).parent / "references.md")
+    params: type[ReferencesParams] = ReferencesParams
+
+    def __init__(self, runtime: Runtime) -> None:
+        super().__init__()
+        self._bind(runtime)
+
+    @override
+    async def __call__(self, params: ReferencesParams) -> ToolReturnValue:
+        locations = await self._request(
+            params, "textDocument/references", {"context": {"includeDeclaration": True}}
+        )
+        if isinstance(locations, ToolReturnValue):
+            return locations
+        return await self._format(locations[: params.limit], "reference(s)")
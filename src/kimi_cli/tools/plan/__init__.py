@@ -14,6 +14,7 @@ from pydantic import BaseModel, Field, field_validator
 
 from kimi_cli.soul import get_wire_or_none, wire_send
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
+from kimi_cli.tools import ExemptFromTimeout
 from kimi_cli.tools.utils import ToolRejectedError, load_desc
 from kimi_cli.wire.types import (
     PlanDisplay,
@@ -78,7 +79,7 @@ class Params(BaseModel):
         return v
 
 
-class ExitPlanMode(CallableTool2[Params]):
+class ExitPlanMode(CallableTool2[Params], ExemptFromTimeout):
     name: str = NAME
     description: str = load_desc(Path(__file__).parent / "description.md")
     params: type[Params] = Params
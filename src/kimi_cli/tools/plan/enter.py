@@ -13,6 +13,7 @@ from pydantic import BaseModel
 
 from kimi_cli.soul import get_wire_or_none, wire_send
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
+from kimi_cli.tools import ExemptFromTimeout
 from kimi_cli.tools.utils import load_desc
 from kimi_cli.wire.types import QuestionItem, QuestionNotSupported, QuestionOption, QuestionRequest
 
@@ -27,7 +28,7 @@ class Params(BaseModel):
     pass
 
 
-class EnterPlanMode(CallableTool2[Params]):
+class EnterPlanMode(CallableTool2[Params], ExemptFromTimeout):
     name: str = NAME
     description: str = _DESCRIPTION
     params: type[Params] = Params
@@ -4,16 +4,25 @@ from pathlib import Path
 from typing import Self, override
 
 import kaos
-from kaos import AsyncReadable
+from kaos import AsyncReadable, CappedReadable
 from kosong.tooling import CallableTool2, ToolReturnValue
 from pydantic import BaseModel, Field, model_validator
 
 from kimi_cli.background import TaskView, format_task
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.message import format_size
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
-from kimi_cli.tools.display import BackgroundTaskDisplayBlock, ShellDisplayBlock
-from kimi_cli.tools.utils import ToolResultBuilder, load_desc
+from kimi_cli.soul.turn_summary import SHELL_COMMAND_EXTRA
+from kimi_cli.tools.display import BackgroundTaskDisplayBlock, DisplayBlock, ShellDisplayBlock
+from kimi_cli.tools.shell.analysis import analyze_shell_command
+from kimi_cli.tools.utils import (
+    DEFAULT_MAX_CHARS,
+    ArgumentClamps,
+    ToolResultBuilder,
+    dry_run_result,
+    load_desc,
+)
 from kimi_cli.utils.environment import Environment
 from kimi_cli.utils.logging import logger
 from kimi_cli.utils.shell_quoting import rewrite_windows_null_redirect
@@ -21,6 +30,10 @@ from kimi_cli.utils.subprocess_env import get_noninteractive_env
 
 MAX_FOREGROUND_TIMEOUT = 5 * 60
 MAX_BACKGROUND_TIMEOUT = 24 * 60 * 60
+DEFAULT_TIMEOUT = 60
+MAX_STREAM_BYTES = DEFAULT_MAX_CHARS * 2 // 5
+"""Bytes kept of each of stdout and stderr, half from the start and half from the end, so that
+both fit in the tool result with room for the truncation markers."""
 
 
 class Params(BaseModel):
@@ -30,7 +43,7 @@ class Params(BaseModel):
             "The timeout in seconds for the command to execute. "
             "If the command takes longer than this, it will be killed."
         ),
-        default=60,
+        default=DEFAULT_TIMEOUT,
         ge=1,
         le=MAX_BACKGROUND_TIMEOUT,
     )
@@ -49,11 +62,6 @@ class Params(BaseModel):
     def _validate_background_fields(self) -> Self:
         if self.run_in_background and not self.description.strip():
             raise ValueError("description is required when run_in_background is true")
-        if not self.run_in_background and self.timeout > MAX_FOREGROUND_TIMEOUT:
-            raise ValueError(
-                f"timeout must be <= {MAX_FOREGROUND_TIMEOUT}s for foreground commands; "
-                f"use run_in_background=true for longer timeouts (up to {MAX_BACKGROUND_TIMEOUT}s)"
-            )
         return self
 
 
@@ -65,7 +73,12 @@ class Shell(CallableTool2[Params]):
         super().__init__(
             description=load_desc(
                 Path(__file__).parent / "bash.md",
-                {"SHELL": f"{environment.shell_name} (`{environment.shell_path}`)"},
+                {
+                    "SHELL": f"{environment.shell_name} (`{environment.shell_path}`)",
+                    "DEFAULT_TIMEOUT": DEFAULT_TIMEOUT,
+                    "MAX_FOREGROUND_TIMEOUT": MAX_FOREGROUND_TIMEOUT,
+                    "MAX_BACKGROUND_TIMEOUT": MAX_BACKGROUND_TIMEOUT,
+                },
             )
         )
         self._approval = approval
@@ -77,28 +90,44 @@ class Shell(CallableTool2[Params]):
         """Apply platform-specific defensive rewrites before execution."""
         return rewrite_windows_null_redirect(command, on_windows=self._on_windows)
 
+    def _display_block(self, command: str) -> ShellDisplayBlock:
+        analysis = analyze_shell_command(
+            command, self._runtime.builtin_args.KIMI_WORK_DIR, self._runtime.additional_dirs
+        )
+        return ShellDisplayBlock(language="bash", command=command, analysis=analysis)
+
     @override
     async def __call__(self, params: Params) -> ToolReturnValue:
-        builder = ToolResultBuilder()
-
         if not params.command:
-            return builder.error("Command cannot be empty.", brief="Empty command")
+            return ToolResultBuilder().error("Command cannot be empty.", brief="Empty command")
 
         if params.run_in_background:
             return await self._run_in_background(params)
 
+        # Longer timeouts are only allowed in the background
+        clamps = ArgumentClamps()
+        timeout = clamps.clamp(
+            "timeout",
+            params.timeout,
+            MAX_FOREGROUND_TIMEOUT,
+            hint=" for a foreground command, use run_in_background=true for longer timeouts",
+        )
+        return clamps.annotate(
+            await self._run_in_foreground(params.model_copy(update={"timeout": timeout}))
+        )
+
+    async def _run_in_foreground(self, params: Params) -> ToolReturnValue:
+        builder = ToolResultBuilder()
         command = self._preprocess_command(params.command)
+        display: list[DisplayBlock] = [self._display_block(command)]
+        if self._runtime.dry_run:
+            return dry_run_result(f"Run command `{command}`", display)
 
         result = await self._approval.request(
             self.name,
             "run command",
             f"Run command `{command}`",
-            display=[
-                ShellDisplayBlock(
-                    language="bash",
-                    command=command,
-                )
-            ],
+            display=display,
         )
         if not result:
             return result.rejection_error()
@@ -112,20 +141,29 @@ class Shell(CallableTool2[Params]):
             builder.write(line_str)
 
         try:
-            exitcode = await self._run_shell_command(command, stdout_cb, stderr_cb, params.timeout)
+            exitcode, output_bytes = await self._run_shell_command(
+                command, stdout_cb, stderr_cb, params.timeout
+            )
+            builder.extras(**{SHELL_COMMAND_EXTRA: {"command": command, "exit_code": exitcode}})
+            truncation = (
+                f" Output truncated, total {format_size(output_bytes)}."
+                if output_bytes is not None
+                else ""
+            )
 
             if exitcode == 0:
-                return builder.ok("Command executed successfully.")
+                return builder.ok("Command executed successfully." + truncation)
             else:
                 brief = f"Failed with exit code: {exitcode}"
                 tail = builder.tail()
                 if tail:
                     brief += f"\n{tail}"
                 return builder.error(
-                    f"Command failed with exit code: {exitcode}.",
+                    f"Command failed with exit code: {exitcode}." + truncation,
                     brief=brief,
                 )
         except TimeoutError:
+            builder.extras(**{SHELL_COMMAND_EXTRA: {"command": command, "exit_code": None}})
             return builder.error(
                 f"Command killed by timeout ({params.timeout}s)",
                 brief=f"Killed by timeout ({params.timeout}s)",
@@ -150,17 +188,15 @@ class Shell(CallableTool2[Params]):
             )
 
         command = self._preprocess_command(params.command)
+        display: list[DisplayBlock] = [self._display_block(command)]
+        if self._runtime.dry_run:
+            return dry_run_result(f"Run background command `{command}`", display)
 
         result = await self._approval.request(
             self.name,
             "run background command",
             f"Run background command `{command}`",
-            display=[
-                ShellDisplayBlock(
-                    language="bash",
-                    command=command,
-                )
-            ],
+            display=display,
         )
         if not result:
             return result.rejection_error()
@@ -224,7 +260,15 @@ class Shell(CallableTool2[Params]):
         stdout_cb: Callable[[bytes], None],
         stderr_cb: Callable[[bytes], None],
         timeout: int,
-    ) -> int:
+    ) -> tuple[int, int | None]:
+        """
+        Run the command, passing its output to the callbacks line by line.
+
+        Returns:
+            The exit code, and the total bytes of output if some of it was dropped for
+            exceeding `MAX_STREAM_BYTES`, None otherwise.
+        """
+
         async def _read_stream(stream: AsyncReadable, cb: Callable[[bytes], None]):
             while True:
                 line = await stream.readline()
@@ -243,16 +287,21 @@ class Shell(CallableTool2[Params]):
         # Close stdin immediately so interactive prompts (e.g. git password) get
         # EOF instead of hanging forever waiting for input that will never come.
         process.stdin.close()
+        stdout = CappedReadable(process.stdout, MAX_STREAM_BYTES)
+        stderr = CappedReadable(process.stderr, MAX_STREAM_BYTES)
 
         try:
             await asyncio.wait_for(
                 asyncio.gather(
-                    _read_stream(process.stdout, stdout_cb),
-                    _read_stream(process.stderr, stderr_cb),
+                    _read_stream(stdout, stdout_cb),
+                    _read_stream(stderr, stderr_cb),
                 ),
                 timeout,
             )
-            return await process.wait()
+            exitcode = await process.wait()
+            if stdout.truncated or stderr.truncated:
+                return exitcode, stdout.total_bytes + stderr.total_bytes
+            return exitcode, None
         except asyncio.CancelledError:
             await process.kill()
             raise
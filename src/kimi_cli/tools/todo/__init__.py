@@ -2,7 +2,7 @@ import json
 from pathlib import Path
 from typing import Any, Literal, cast, override
 
-from kosong.tooling import CallableTool2, ToolReturnValue
+from kosong.tooling import CallableTool2, ToolError, ToolReturnValue
 from pydantic import BaseModel, Field
 
 from kimi_cli.session_state import TodoItemState
@@ -45,7 +45,24 @@ class SetTodoList(CallableTool2[Params]):
     # ---- Write mode --------------------------------------------------------
 
     def _write_todos(self, todos: list[Todo]) -> ToolReturnValue:
-        """Persist the todo list and return confirmation."""
+        """Validate status transitions, persist the todo list and return confirmation."""
+        previous = {todo.title: todo.status for todo in load_todos(self._runtime)}
+        skipped = [
+            todo.title
+            for todo in todos
+            if todo.status == "done" and previous.get(todo.title) not in ("in_progress", "done")
+        ]
+        if skipped:
+            titles = ", ".join(f'"{title}"' for title in skipped)
+            return ToolError(
+                message=(
+                    f"Cannot mark {titles} as done: an item must be in_progress before it is "
+                    "done. Todo items move from pending to in_progress to done. Mark the item "
+                    "in_progress, do the work, then mark it done. The todo list was not changed."
+                ),
+                brief="Invalid todo transition",
+            )
+
         self._save_todos(todos)
 
         items = [TodoDisplayItem(title=todo.title, status=todo.status) for todo in todos]
@@ -60,7 +77,7 @@ class SetTodoList(CallableTool2[Params]):
 
     def _read_todos(self) -> ToolReturnValue:
         """Return the current todo list as text output for the model."""
-        todos = self._load_todos()
+        todos = load_todos(self._runtime)
         if not todos:
             return ToolReturnValue(
                 is_error=False,
@@ -90,79 +107,88 @@ class SetTodoList(CallableTool2[Params]):
         else:
             self._save_subagent_todos(items)
 
-    def _load_todos(self) -> list[Todo]:
-        """Load todos from the appropriate state file."""
-        if self._runtime.role == "root":
-            return self._load_root_todos()
-        else:
-            return self._load_subagent_todos()
-
     def _save_root_todos(self, items: list[TodoItemState]) -> None:
         session = self._runtime.session
         session.state.todos = items
         session.save_state()
 
-    def _load_root_todos(self) -> list[Todo]:
-        from kimi_cli.session_state import load_session_state
-
-        session = self._runtime.session
-        fresh = load_session_state(session.dir)
-        session.state.todos = fresh.todos
-        result: list[Todo] = []
-        for t in fresh.todos:
-            try:
-                result.append(Todo(title=t.title, status=t.status))
-            except Exception:
-                logger.warning("Skipping malformed todo item in root state: {t}", t=t)
-        return result
-
     def _save_subagent_todos(self, items: list[TodoItemState]) -> None:
-        state_file = self._subagent_state_file()
+        state_file = _subagent_state_file(self._runtime)
         if state_file is None:
             return
-        data = self._read_subagent_state(state_file)
+        data = _read_subagent_state(state_file)
         data["todos"] = [item.model_dump() for item in items]
         self._write_subagent_state(state_file, data)
 
-    def _load_subagent_todos(self) -> list[Todo]:
-        state_file = self._subagent_state_file()
-        if state_file is None:
-            return []
-        data = self._read_subagent_state(state_file)
-        raw_todos_val = data.get("todos", [])
-        raw_todos = cast(list[Any], raw_todos_val) if isinstance(raw_todos_val, list) else []
-        result: list[Todo] = []
-        for item in raw_todos:
-            try:
-                result.append(Todo(**item))
-            except Exception:
-                logger.warning("Skipping malformed todo item in subagent state: {item}", item=item)
-        return result
-
-    def _subagent_state_file(self) -> Path | None:
-        store = self._runtime.subagent_store
-        agent_id = self._runtime.subagent_id
-        if store is None or agent_id is None:
-            return None
-        return store.instance_dir(agent_id) / "state.json"
-
-    @staticmethod
-    def _read_subagent_state(path: Path) -> dict[str, Any]:
-        if not path.exists():
-            return {}
-        try:
-            data = json.loads(path.read_text(encoding="utf-8"))
-        except (json.JSONDecodeError, OSError, UnicodeDecodeError):
-            logger.warning("Corrupted subagent todo state, using defaults: {path}", path=path)
-            return {}
-        if not isinstance(data, dict):
-            logger.warning("Invalid subagent todo state type, using defaults: {path}", path=path)
-            return {}
-        return cast(dict[str, Any], data)
-
     @staticmethod
     def _write_subagent_state(path: Path, data: dict[str, Any]) -> None:
         from kimi_cli.utils.io import atomic_json_write
 
         path.parent.mkdir(parents=True, exist_ok=True)
         atomic_json_write(data, path)
+
+
+def load_todos(runtime: Runtime) -> list[Todo]:
+    """Load the latest persisted todo list of the agent owning `runtime`."""
+    if runtime.role == "root":
+        return _load_root_todos(runtime)
+    else:
+        return _load_subagent_todos(runtime)
+
+
+def unfinished_todos(todos: list[Todo]) -> list[Todo]:
+    """Return the todos that are still pending or in progress."""
+    return [todo for todo in todos if todo.status != "done"]
+
+
+def _load_root_todos(runtime: Runtime) -> list[Todo]:
+    from kimi_cli.session_state import load_session_state
+
+    session = runtime.session
+    fresh = load_session_state(session.dir)
+    session.state.todos = fresh.todos
+    result: list[Todo] = []
+    for t in fresh.todos:
+        try:
+            result.append(Todo(title=t.title, status=t.status))
+        except Exception:
+            logger.warning("Skipping malformed todo item in root state: {t}", t=t)
+    return result
+
+
+def _load_subagent_todos(runtime: Runtime) -> list[Todo]:
+    state_file = _subagent_state_file(runtime)
+    if state_file is None:
+        return []
+    data = _read_subagent_state(state_file)
+    raw_todos_val = data.get("todos", [])
+    raw_todos = cast(list[Any], raw_todos_val) if isinstance(raw_todos_val, list) else []
+    result: list[Todo] = []
+    for item in raw_todos:
+        try:
+            result.append(Todo(**item))
+        except Exception:
+            logger.warning("Skipping malformed todo item in subagent state: {item}", item=item)
+    return result
+
+
+def _subagent_state_file(runtime: Runtime) -> Path | None:
+    store = runtime.subagent_store
+    agent_id = runtime.subagent_id
+    if store is None or agent_id is None:
+        return None
+    return store.instance_dir(agent_id) / "state.json"
+
+
+def _read_subagent_state(path: Path) -> dict[str, Any]:
+    if not path.exists():
+        return {}
+    try:
+        data = json.loads(path.read_text(encoding="utf-8"))
+    except (json.JSONDecodeError, OSError, UnicodeDecodeError):
+        logger.warning("Corrupted subagent todo state, using defaults: {path}", path=path)
+        return {}
+    if not isinstance(data, dict):
+        logger.warning("Invalid subagent todo state type, using defaults: {path}", path=path)
+        return {}
+    return cast(dict[str, Any], data)
@@ -5,18 +5,29 @@ from jinja2 import Environment, Undefined
 from kosong.tooling import BriefDisplayBlock, DisplayBlock, ToolError, ToolReturnValue
 from kosong.utils.typing import JsonType
 
+from kimi_cli.utils.logging import logger
+
 
 class _KeepPlaceholderUndefined(Undefined):
     def __str__(self) -> str:
         if self._undefined_name is None:
             return ""
+        logger.debug(
+            "Tool description placeholder {name} is not defined, keeping it as is",
+            name=self._undefined_name,
+        )
         return f"${{{self._undefined_name}}}"
 
     __repr__ = __str__
 
 
 def load_desc(path: Path, context: dict[str, object] | None = None) -> str:
-    """Load a tool description from a file, rendered via Jinja2."""
+    """
+    Load a tool description from a file, rendered via Jinja2.
+
+    `${NAME}` placeholders are filled from `context`, so that a description can mention limits
+    and settings that are only known at runtime. Unknown placeholders are left intact.
+    """
     description = path.read_text(encoding="utf-8")
     env = Environment(
         keep_trailing_newline=True,
@@ -199,6 +210,45 @@ class ToolResultBuilder:
         )
 
 
+def dry_run_result(description: str, display: list[DisplayBlock] | None = None) -> ToolReturnValue:
+    """
+    The result of a tool call with side effects when the runtime is in dry-run mode. The call
+    succeeds without doing anything, and tells what it would have done, e.g. with the diff of a
+    file edit or the command to run.
+    """
+    return ToolReturnValue(
+        is_error=False,
+        output="",
+        message=f"Dry run, nothing was executed. The tool call would: {description}",
+        display=display or [BriefDisplayBlock(text="Dry run")],
+    )
+
+
+class ArgumentClamps:
+    """
+    The arguments a tool clamped to its soft limits. The tool proceeds with the clamped values
+    and tells the model in a note line before the message of its result, so that it learns the
+    actual limits.
+    """
+
+    def __init__(self) -> None:
+        self._notes: list[str] = []
+
+    def clamp(self, name: str, value: int, limit: int, *, hint: str = "") -> int:
+        """Return `value`, or `limit` if `value` exceeds it. `hint` is appended to the note."""
+        if value <= limit:
+            return value
+        self._notes.append(f"{name} clamped from {value} to {limit}{hint}")
+        return limit
+
+    def annotate(self, ret: ToolReturnValue) -> ToolReturnValue:
+        """Prepend the note on the clamped arguments, if any, to the message of `ret`."""
+        if not self._notes:
+            return ret
+        note = "note: " + "; ".join(self._notes)
+        return ret.model_copy(update={"message": f"{note}\n{ret.message}" if ret.message else note})
+
+
 class ToolRejectedError(ToolError):
     has_feedback: bool = False
 
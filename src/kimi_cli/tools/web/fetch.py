@@ -11,7 +11,7 @@ from kimi_cli.constant import USER_AGENT
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
 from kimi_cli.tools.utils import ToolResultBuilder, load_desc
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
 
 
@@ -49,6 +49,7 @@ class FetchURL(CallableTool2[Params]):
                 new_client_session(timeout=fetch_timeout) as session,
                 session.get(
                     params.url,
+                    proxy=request_proxy(params.url),
                     headers={
                         "User-Agent": (
                             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 "
@@ -150,6 +151,7 @@ class FetchURL(CallableTool2[Params]):
                 new_client_session() as session,
                 session.post(
                     self._service_config.base_url,
+                    proxy=request_proxy(self._service_config.base_url),
                     headers=headers,
                     json={"url": params.url},
                 ) as response,
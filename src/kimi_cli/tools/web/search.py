@@ -11,7 +11,7 @@ from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.toolset import get_current_tool_call_or_none
 from kimi_cli.tools import SkipThisTool
 from kimi_cli.tools.utils import ToolResultBuilder, load_desc
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
 
 
@@ -74,6 +74,7 @@ class SearchWeb(CallableTool2[Params]):
                 new_client_session(timeout=search_timeout) as session,
                 session.post(
                     self._base_url,
+                    proxy=request_proxy(self._base_url),
                     headers={
                         "User-Agent": USER_AGENT,
                         "Authorization": f"Bearer {api_key}",
@@ -2,6 +2,7 @@ from __future__ import annotations
 
 import asyncio
 import json
+import signal
 import sys
 import time
 from functools import partial
@@ -20,8 +21,10 @@ from rich import print
 from kimi_cli.background.models import is_terminal_status
 from kimi_cli.cli import ExitCode, InputFormat, OutputFormat
 from kimi_cli.soul import (
+    BudgetExceeded,
     LLMNotSet,
     LLMNotSupported,
+    MaxMessageSizeExceeded,
     MaxStepsReached,
     RunCancelled,
     Soul,
@@ -30,7 +33,7 @@ from kimi_cli.soul import (
 from kimi_cli.soul.kimisoul import KimiSoul
 from kimi_cli.ui.print.visualize import visualize
 from kimi_cli.utils.logging import logger, open_original_stderr
-from kimi_cli.utils.signals import install_sigint_handler
+from kimi_cli.utils.signals import ShutdownSignals, install_shutdown_handler
 
 
 class Print:
@@ -62,13 +65,15 @@ class Print:
 
     async def run(self, command: str | None = None) -> int:
         cancel_event = asyncio.Event()
+        # Ctrl-C or SIGTERM cancels the turn, and the caller cleans up as on any other exit
+        shutdown_signals = ShutdownSignals()
 
-        def _handler():
-            logger.debug("SIGINT received.")
+        def _handler(signum: signal.Signals):
+            shutdown_signals.receive(signum)
             cancel_event.set()
 
         loop = asyncio.get_running_loop()
-        remove_sigint = install_sigint_handler(loop, _handler)
+        remove_signal_handler = install_shutdown_handler(loop, _handler)
 
         if command is None and not sys.stdin.isatty() and self.input_format == "text":
             command = sys.stdin.read().strip()
@@ -415,6 +420,10 @@ class Print:
             logger.exception("LLM not supported:")
             print(str(e))
             return ExitCode.FAILURE
+        except MaxMessageSizeExceeded as e:
+            logger.warning("Max message size exceeded: {limit}", limit=e.limit)
+            print(str(e))
+            return ExitCode.FAILURE
         except ChatProviderError as e:
             logger.exception("LLM provider error:")
             print(str(e))
@@ -423,16 +432,24 @@ class Print:
             logger.warning("Max steps reached: {n_steps}", n_steps=e.n_steps)
             print(str(e))
             return ExitCode.FAILURE
+        except BudgetExceeded as e:
+            logger.warning("Turn budget exceeded: {error}", error=e)
+            print(str(e))
+            return ExitCode.BUDGET_EXCEEDED
         except RunCancelled:
-            logger.error("Interrupted by user")
-            print("Interrupted by user")
-            return ExitCode.FAILURE
+            if shutdown_signals.signum is not None and shutdown_signals.signum != signal.SIGINT:
+                logger.error("Stopped by {signal}", signal=shutdown_signals.signum.name)
+                print(f"Stopped by {shutdown_signals.signum.name}")
+            else:
+                logger.error("Interrupted by user")
+                print("Interrupted by user")
+            return shutdown_signals.exit_code or ExitCode.FAILURE
         except BaseException as e:
             logger.exception("Unknown error:")
             print(f"Unknown error: {e}")
             raise
         finally:
-            remove_sigint()
+            remove_signal_handler()
         return ExitCode.FAILURE
 
     _RETRYABLE_STATUS_CODES = {429, 500, 502, 503, 504}
@@ -26,7 +26,16 @@ from kimi_cli import logger
 from kimi_cli.background import list_task_views
 from kimi_cli.llm import model_display_name
 from kimi_cli.notifications import NotificationManager, NotificationWatcher
-from kimi_cli.soul import LLMNotSet, LLMNotSupported, MaxStepsReached, RunCancelled, Soul, run_soul
+from kimi_cli.soul import (
+    BudgetExceeded,
+    LLMNotSet,
+    LLMNotSupported,
+    MaxMessageSizeExceeded,
+    MaxStepsReached,
+    RunCancelled,
+    Soul,
+    run_soul,
+)
 from kimi_cli.soul.kimisoul import FLOW_COMMAND_PREFIX, KimiSoul
 from kimi_cli.ui.shell import update as _update_mod
 from kimi_cli.ui.shell.console import console
@@ -959,6 +968,13 @@ class Shell:
             # actually unsupported input/mode should already be blocked by prompt session
             logger.exception("LLM not supported:")
             console.print(f"[red]{e}[/red]")
+        except MaxMessageSizeExceeded as e:
+            logger.warning("Max message size exceeded: {limit}", limit=e.limit)
+            console.print(
+                f"[yellow]{e}[/yellow]\n"
+                "[dim]Send another message to retry, or raise "
+                "[bold]loop_control.max_message_chars[/bold] if this is expected.[/dim]"
+            )
         except ChatProviderError as e:
             logger.exception("LLM provider error:")
             if isinstance(e, APIStatusError) and e.status_code == 401:
@@ -1002,6 +1018,13 @@ class Shell:
                 f"[yellow]{e}[/yellow]\n"
                 "[dim]Send another message to continue where it left off.[/dim]"
             )
+        except BudgetExceeded as e:
+            logger.warning("Turn budget exceeded: {error}", error=e)
+            console.print(
+                f"[yellow]{e}[/yellow]\n"
+                "[dim]Send another message to continue where it left off, or raise "
+                "loop_control.max_tokens_per_turn / max_cost_per_turn.[/dim]"
+            )
         except RunCancelled:
             logger.info("Cancelled by user")
             console.print("[red]Interrupted by user[/red]")
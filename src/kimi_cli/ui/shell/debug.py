@@ -16,6 +16,7 @@ from kimi_cli.ui.shell.slash import registry
 from kimi_cli.wire.types import (
     AudioURLPart,
     ContentPart,
+    FilePart,
     ImageURLPart,
     TextPart,
     ThinkPart,
@@ -62,6 +63,11 @@ def _format_content_part(part: ContentPart) -> Text | Panel | Group:
             url_display = video.url[:80] + "..." if len(video.url) > 80 else video.url
             return Text(f"[Video] {url_display}", style="blue")
 
+        case FilePart(file=file):
+            url_display = file.url[:80] + "..." if len(file.url) > 80 else file.url
+            name_text = f" {file.name}" if file.name else ""
+            return Text(f"[File{name_text}] {url_display}", style="blue")
+
         case _:
             return Text(f"[Unknown content type: {type(part).__name__}]", style="red")
 
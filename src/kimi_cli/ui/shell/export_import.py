@@ -38,6 +38,7 @@ async def export(app: Shell, args: str):
         token_count=soul.context.token_count,
         args=args,
         default_dir=Path(str(session.work_dir)),
+        compaction_markers=soul.context.compaction_markers,
     )
     if isinstance(result, str):
         console.print(f"[yellow]{result}[/yellow]")
@@ -452,7 +452,7 @@ async def feedback(app: Shell, args: str):
     from kimi_cli.auth.platforms import get_platform_by_id, managed_provider_key
     from kimi_cli.constant import VERSION
     from kimi_cli.ui.shell.oauth import current_model_key
-    from kimi_cli.utils.aiohttp import new_client_session
+    from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 
     ISSUE_URL = "https://github.com/MoonshotAI/kimi-cli/issues"
 
@@ -506,6 +506,7 @@ async def feedback(app: Shell, args: str):
                 new_client_session() as session,
                 session.post(
                     feedback_url,
+                    proxy=request_proxy(feedback_url),
                     json=payload,
                     headers={
                         "Authorization": f"Bearer {api_key}",
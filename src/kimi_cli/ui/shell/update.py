@@ -18,7 +18,7 @@ import aiohttp
 
 from kimi_cli.share import get_share_dir
 from kimi_cli.ui.shell.console import console
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.logging import logger
 
 BASE_URL = "https://cdn.kimi.com/binaries/kimi-cli"
@@ -75,7 +75,7 @@ def _detect_target() -> str | None:
 
 async def _get_latest_version(session: aiohttp.ClientSession) -> str | None:
     try:
-        async with session.get(LATEST_VERSION_URL) as resp:
+        async with session.get(LATEST_VERSION_URL, proxy=request_proxy(LATEST_VERSION_URL)) as resp:
             resp.raise_for_status()
             data = await resp.text()
             return data.strip()
@@ -279,7 +279,7 @@ async def _do_update(*, print: bool, check_only: bool) -> UpdateResult:
             logger.info("Downloading from {download_url}...", download_url=download_url)
             _print("[grey50]Downloading...[/grey50]")
             try:
-                async with session.get(download_url) as resp:
+                async with session.get(download_url, proxy=request_proxy(download_url)) as resp:
                     resp.raise_for_status()
                     with open(tar_path, "wb") as f:
                         async for chunk in resp.content.iter_chunked(1024 * 64):
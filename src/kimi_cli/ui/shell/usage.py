@@ -19,7 +19,7 @@ from kimi_cli.config import LLMModel
 from kimi_cli.soul.kimisoul import KimiSoul
 from kimi_cli.ui.shell.console import console
 from kimi_cli.ui.shell.slash import registry
-from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.aiohttp import new_client_session, request_proxy
 from kimi_cli.utils.datetime import format_duration
 
 if TYPE_CHECKING:
@@ -97,6 +97,7 @@ async def _fetch_usage(url: str, api_key: str) -> Mapping[str, Any]:
         new_client_session() as session,
         session.get(
             url,
+            proxy=request_proxy(url),
             headers={"Authorization": f"Bearer {api_key}"},
             raise_for_status=True,
         ) as resp,
@@ -14,6 +14,7 @@ from rich.padding import Padding
 from rich.panel import Panel
 from rich.text import Text
 
+from kimi_cli.tools.display import ShellRiskIndicator
 from kimi_cli.ui.shell.console import console, render_to_ansi
 from kimi_cli.ui.shell.keyboard import KeyEvent
 from kimi_cli.utils.rich.diff_render import (
@@ -45,6 +46,35 @@ class ApprovalContentBlock(NamedTuple):
     lexer: str = ""
 
 
+_SHELL_INDICATOR_LABELS: dict[ShellRiskIndicator, str] = {
+    "write": "Deletes or moves files",
+    "network": "Accesses the network",
+    "privilege_escalation": "Escalates privileges",
+}
+
+
+def _shell_analysis_summary(block: ShellDisplayBlock) -> str:
+    """The lines pointing out what a shell command runs, empty for a single harmless command."""
+    analysis = block.analysis
+    if analysis is None:
+        return ""
+    lines: list[str] = []
+    if len(analysis.commands) > 1:
+        programs = ", ".join(" ".join(command.programs) for command in analysis.commands)
+        lines.append(f"Runs {len(analysis.commands)} commands: {programs}")
+    for indicator in analysis.indicators:
+        programs = ", ".join(
+            " ".join(command.programs)
+            for command in analysis.commands
+            if indicator in command.indicators
+        )
+        lines.append(f"\u26a0 {_SHELL_INDICATOR_LABELS[indicator]}: {programs}")
+    if analysis.outside_redirects:
+        targets = ", ".join(analysis.outside_redirects)
+        lines.append(f"\u26a0 Redirects output outside the workspace: {targets}")
+    return "\n".join(lines)
+
+
 def _render_feedback_with_cursor(text: str, cursor: int | None) -> Text:
     if cursor is None or cursor >= len(text):
         return Text(text + "\u2588")
@@ -107,7 +137,7 @@ class ApprovalRequestPanel:
                         break
                     diff_blocks.append(b)
                     idx += 1
-                if any(b.is_summary for b in diff_blocks):
+                if any(b.is_summary and not b.hunks for b in diff_blocks):
                     self._has_diff = True
                     self._preview_renderables.extend(render_diff_summary_preview(path, diff_blocks))
                 else:
@@ -136,6 +166,14 @@ class ApprovalRequestPanel:
                     non_diff_budget -= min(line_count, non_diff_budget)
                 else:
                     self._non_diff_truncated = True
+                # The summary is never truncated, so that the warnings stay visible.
+                if summary := _shell_analysis_summary(block):
+                    self._content_blocks.append(
+                        ApprovalContentBlock(
+                            text=summary, lines=summary.count("\n") + 1, style="yellow"
+                        )
+                    )
+                    self._preview_renderables.append(Text(summary, style="yellow"))
                 idx += 1
             elif isinstance(block, BriefDisplayBlock) and block.text:
                 text = block.text.rstrip("\n")
@@ -306,7 +344,7 @@ def show_approval_in_pager(panel: ApprovalRequestPanel) -> None:
                         break
                     diff_blocks.append(b)
                     idx += 1
-                if any(b.is_summary for b in diff_blocks):
+                if any(b.is_summary and not b.hunks for b in diff_blocks):
                     console.print(render_diff_summary_panel(path, diff_blocks))
                     rendered_any = True
                 else:
@@ -316,6 +354,8 @@ def show_approval_in_pager(panel: ApprovalRequestPanel) -> None:
                         rendered_any = True
             elif isinstance(block, ShellDisplayBlock):
                 console.print(KimiSyntax(block.command.rstrip("\n"), block.language))
+                if summary := _shell_analysis_summary(block):
+                    console.print(Text(summary, style="yellow"))
                 rendered_any = True
                 idx += 1
             elif isinstance(block, BriefDisplayBlock) and block.text:
@@ -509,7 +509,7 @@ class _ToolCallBlock:
                             break
                         diff_blocks.append(b)
                         idx += 1
-                    if any(b.is_summary for b in diff_blocks):
+                    if any(b.is_summary and not b.hunks for b in diff_blocks):
                         lines.append(render_diff_summary_panel(path, diff_blocks))
                     else:
                         hunks, added_total, removed_total = collect_diff_hunks(diff_blocks)
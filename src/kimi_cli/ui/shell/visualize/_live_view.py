@@ -22,6 +22,7 @@ from rich.panel import Panel
 from rich.spinner import Spinner
 from rich.text import Text
 
+from kimi_cli.soul.compaction import compaction_divider
 from kimi_cli.ui.shell.console import console
 from kimi_cli.ui.shell.echo import render_user_echo
 from kimi_cli.ui.shell.keyboard import KeyboardListener, KeyEvent
@@ -52,6 +53,7 @@ from kimi_cli.wire.types import (
     BtwEnd,
     CompactionBegin,
     CompactionEnd,
+    CompactionProgress,
     ContentPart,
     MCPLoadingBegin,
     MCPLoadingEnd,
@@ -138,6 +140,9 @@ class _LiveView:
         self._mooning_spinner = Spinner("moon", "")
         self._active_turn_depth = 0
         self._compacting_spinner: Spinner | None = None
+        self._compaction_summary = ""
+        """The summary written so far by the current attempt of the compaction."""
+        self._compaction_attempt = 0
         self._mcp_loading_spinner: Spinner | None = None
         self._btw_spinner: Spinner | None = None
         self._btw_question: str | None = None
@@ -447,9 +452,31 @@ class _LiveView:
                 self._active_turn_depth = max(0, self._active_turn_depth - 1)
             case CompactionBegin():
                 self._compacting_spinner = Spinner("balloon", "Compacting...")
+                self._compaction_summary = ""
+                self._compaction_attempt = 0
                 self.refresh_soon()
-            case CompactionEnd():
+            case CompactionProgress(text=text, think=False, attempt=attempt) if (
+                self._compacting_spinner is not None
+            ):
+                if attempt != self._compaction_attempt:
+                    self._compaction_summary = ""
+                    self._compaction_attempt = attempt
+                self._compaction_summary += text
+                # Show the end of the line being written, so that a long compaction does not
+                # look stalled
+                lines = self._compaction_summary.strip().splitlines()
+                if lines:
+                    tail = lines[-1].strip()
+                    if len(tail) > 60:
+                        tail = "..." + tail[-57:]
+                    self._compacting_spinner.update(text=Text(f"Compacting... {tail}"))
+                    self.refresh_soon()
+            case CompactionEnd(n_summarized=n_summarized):
                 self._compacting_spinner = None
+                if n_summarized is not None:
+                    self.flush_content()
+                    console.print(Text(compaction_divider(n_summarized), style="grey50"))
+                    console.print()
                 self.refresh_soon()
             case MCPLoadingBegin():
                 self._mcp_loading_spinner = Spinner("dots", "Connecting to MCP servers...")
@@ -1,11 +1,8 @@
 from __future__ import annotations
 
-import ssl
-
 import aiohttp
-import certifi
 
-_ssl_context = ssl.create_default_context(cafile=certifi.where())
+from kimi_cli.utils.network import proxy_for_url, ssl_context
 
 _DEFAULT_TIMEOUT = aiohttp.ClientTimeout(
     total=120,
@@ -18,7 +15,20 @@ def new_client_session(
     *,
     timeout: aiohttp.ClientTimeout | None = None,
 ) -> aiohttp.ClientSession:
+    """
+    Create a client session with the `[network]` TLS settings.
+
+    aiohttp resolves proxies per request, so pass `proxy=request_proxy(url)` to each request.
+    """
     return aiohttp.ClientSession(
-        connector=aiohttp.TCPConnector(ssl=_ssl_context),
+        connector=aiohttp.TCPConnector(ssl=ssl_context()),
         timeout=timeout or _DEFAULT_TIMEOUT,
     )
+
+
+def request_proxy(url: str) -> str | None:
+    """The proxy for an aiohttp request to the URL. SOCKS proxies are not supported by aiohttp."""
+    proxy = proxy_for_url(url)
+    if proxy is None or not proxy.lower().startswith(("http://", "https://")):
+        return None
+    return proxy
@@ -62,3 +62,23 @@ def format_elapsed(seconds: float) -> str:
     parts.append(f"{minutes}m")
     parts.append(f"{secs}s")
     return " ".join(parts)
+
+
+_DURATION_UNITS = {"s": 1, "m": 60, "h": 3600, "d": 86400, "w": 7 * 86400}
+
+
+def parse_duration(text: str) -> float:
+    """Parse a duration like `90s`, `30m`, `12h`, `7d` or `2w` into seconds.
+
+    Raises:
+        ValueError: If the duration is malformed.
+    """
+    text = text.strip().lower()
+    unit = _DURATION_UNITS.get(text[-1:])
+    try:
+        value = float(text[:-1]) if unit is not None else None
+    except ValueError:
+        value = None
+    if unit is None or value is None or value < 0:
+        raise ValueError(f"Invalid duration {text!r}, expected e.g. 30m, 12h or 7d.")
+    return value * unit
@@ -6,12 +6,21 @@ from difflib import SequenceMatcher
 
 from kosong.tooling import DisplayBlock
 
-from kimi_cli.tools.display import DiffDisplayBlock
+from kimi_cli.tools.display import DiffDisplayBlock, DiffHunk, DiffWordChange
 
 N_CONTEXT_LINES = 3
 
 _HUGE_FILE_THRESHOLD = 10000
-"""Line count above which diff computation is skipped entirely."""
+"""
+Line count above which a diff is sent as the hunks of a truncated summary block, and above which
+the changed region of a file is not diffed line by line.
+"""
+
+_TRUNCATED_HUNKS_BUDGET = 64 * 1024
+"""Characters of hunk text a truncated block carries at most."""
+
+_WORD_CHANGE_MIN_RATIO = 0.5
+"""Similarity below which a line changed in place gets no word changes, as it is rewritten."""
 
 
 def format_unified_diff(
@@ -20,6 +29,7 @@ def format_unified_diff(
     path: str = "",
     *,
     include_file_header: bool = True,
+    n_context: int = N_CONTEXT_LINES,
 ) -> str:
     """
     Format a unified diff between old_text and new_text.
@@ -29,6 +39,7 @@ def format_unified_diff(
         new_text: The new text.
         path: Optional file path for the diff header.
         include_file_header: Whether to include the ---/+++ file header lines.
+        n_context: The number of context lines around each change.
 
     Returns:
         A unified diff string.
@@ -51,6 +62,7 @@ def format_unified_diff(
             new_lines,
             fromfile=fromfile,
             tofile=tofile,
+            n=n_context,
             lineterm="\n",
         )
     )
@@ -66,10 +78,90 @@ def format_unified_diff(
     return "".join(diff)
 
 
+def word_change(
+    old_line: str, new_line: str, old_num: int, new_num: int
+) -> DiffWordChange | None:
+    """
+    The changed character ranges of a line changed in place, or None if the two lines are too
+    different to be worth highlighting, i.e. the line was rewritten.
+    """
+    matcher = SequenceMatcher(None, old_line, new_line)
+    if matcher.ratio() < _WORD_CHANGE_MIN_RATIO:
+        return None
+    old_ranges: list[tuple[int, int]] = []
+    new_ranges: list[tuple[int, int]] = []
+    for tag, i1, i2, j1, j2 in matcher.get_opcodes():
+        if tag in ("delete", "replace"):
+            old_ranges.append((i1, i2))
+        if tag in ("insert", "replace"):
+            new_ranges.append((j1, j2))
+    return DiffWordChange(
+        old_line=old_num, new_line=new_num, old_ranges=old_ranges, new_ranges=new_ranges
+    )
+
+
+def _diff_hunks(old_lines: list[str], new_lines: list[str], n_context: int) -> list[DiffHunk]:
+    # Only the region between the common prefix and suffix is diffed, so that an edit in one
+    # place of a huge file compares a few lines rather than the whole file.
+    n_common = min(len(old_lines), len(new_lines))
+    prefix = 0
+    while prefix < n_common and old_lines[prefix] == new_lines[prefix]:
+        prefix += 1
+    suffix = 0
+    while suffix < n_common - prefix and old_lines[-1 - suffix] == new_lines[-1 - suffix]:
+        suffix += 1
+    lo = max(prefix - n_context, 0)
+    old_lines = old_lines[lo : len(old_lines) - max(suffix - n_context, 0)]
+    new_lines = new_lines[lo : len(new_lines) - max(suffix - n_context, 0)]
+
+    if max(len(old_lines), len(new_lines)) > _HUGE_FILE_THRESHOLD:
+        return [
+            DiffHunk(
+                old_text="\n".join(old_lines),
+                new_text="\n".join(new_lines),
+                old_start=lo + 1,
+                new_start=lo + 1,
+            )
+        ]
+
+    matcher = SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+    hunks: list[DiffHunk] = []
+    for group in matcher.get_grouped_opcodes(n=n_context):
+        if not group:
+            continue
+        word_changes: list[DiffWordChange] = []
+        for tag, i1, i2, j1, j2 in group:
+            if tag != "replace":
+                continue
+            # Replaced lines are paired in order, as the shell shows them
+            for k in range(min(i2 - i1, j2 - j1)):
+                old_num, new_num = i1 + k, j1 + k
+                change = word_change(
+                    old_lines[old_num], new_lines[new_num], lo + old_num + 1, lo + new_num + 1
+                )
+                if change is not None:
+                    word_changes.append(change)
+        i1 = group[0][1]
+        i2 = group[-1][2]
+        j1 = group[0][3]
+        j2 = group[-1][4]
+        hunks.append(
+            DiffHunk(
+                old_text="\n".join(old_lines[i1:i2]),
+                new_text="\n".join(new_lines[j1:j2]),
+                old_start=lo + i1 + 1,
+                new_start=lo + j1 + 1,
+                word_changes=word_changes,
+            )
+        )
+    return hunks
+
+
 def _build_diff_blocks_sync(
     path: str,
     old_text: str,
     new_text: str,
+    n_context: int = N_CONTEXT_LINES,
 ) -> list[DisplayBlock]:
     """Synchronous diff block builder — CPU-bound, meant to run in a thread."""
     if old_text == new_text:
@@ -77,59 +169,63 @@ def _build_diff_blocks_sync(
 
     old_lines = old_text.splitlines()
     new_lines = new_text.splitlines()
+    hunks = _diff_hunks(old_lines, new_lines, n_context)
 
-    max_lines = max(len(old_lines), len(new_lines))
-
-    # Huge files: skip diff entirely, return a summary block
-    if max_lines > _HUGE_FILE_THRESHOLD:
-        old_desc = f"({len(old_lines)} lines)"
-        if len(old_lines) == len(new_lines):
-            new_desc = f"({len(new_lines)} lines, modified)"
-        else:
-            new_desc = f"({len(new_lines)} lines)"
+    if max(len(old_lines), len(new_lines)) <= _HUGE_FILE_THRESHOLD:
         return [
             DiffDisplayBlock(
                 path=path,
-                old_text=old_desc,
-                new_text=new_desc,
-                old_start=1,
-                new_start=1,
-                is_summary=True,
+                old_text=hunk.old_text,
+                new_text=hunk.new_text,
+                old_start=hunk.old_start,
+                new_start=hunk.new_start,
+                word_changes=hunk.word_changes,
             )
+            for hunk in hunks
         ]
 
-    matcher = SequenceMatcher(None, old_lines, new_lines, autojunk=False)
-
-    blocks: list[DisplayBlock] = []
-    for group in matcher.get_grouped_opcodes(n=N_CONTEXT_LINES):
-        if not group:
-            continue
-        i1 = group[0][1]
-        i2 = group[-1][2]
-        j1 = group[0][3]
-        j2 = group[-1][4]
-        blocks.append(
-            DiffDisplayBlock(
-                path=path,
-                old_text="\n".join(old_lines[i1:i2]),
-                new_text="\n".join(new_lines[j1:j2]),
-                old_start=i1 + 1,
-                new_start=j1 + 1,
-            )
+    # Huge files: send the line counts in place of the texts, so that clients without `hunks`
+    # show a summary, and as many hunks as fit in the budget.
+    old_desc = f"({len(old_lines)} lines)"
+    if len(old_lines) == len(new_lines):
+        new_desc = f"({len(new_lines)} lines, modified)"
+    else:
+        new_desc = f"({len(new_lines)} lines)"
+    kept: list[DiffHunk] = []
+    size = 0
+    for hunk in hunks:
+        size += len(hunk.old_text) + len(hunk.new_text)
+        if size > _TRUNCATED_HUNKS_BUDGET:
+            break
+        kept.append(hunk)
+    return [
+        DiffDisplayBlock(
+            path=path,
+            old_text=old_desc,
+            new_text=new_desc,
+            old_start=1,
+            new_start=1,
+            is_summary=True,
+            truncated=True,
+            hunks=kept,
+            omitted_hunks=len(hunks) - len(kept),
         )
-    return blocks
+    ]
 
 
 async def build_diff_blocks(
     path: str,
     old_text: str,
     new_text: str,
+    *,
+    n_context: int = N_CONTEXT_LINES,
 ) -> list[DisplayBlock]:
-    """Build diff display blocks grouped with small context windows.
+    """Build diff display blocks grouped with `n_context` lines of context.
 
-    Runs the CPU-bound diff computation in a thread to avoid blocking
+    Files over `_HUGE_FILE_THRESHOLD` lines get a single truncated summary block carrying the
+    hunks instead. Runs the CPU-bound diff computation in a thread to avoid blocking
     the event loop.
     """
     if old_text == new_text:
         return []
-    return await asyncio.to_thread(_build_diff_blocks_sync, path, old_text, new_text)
+    return await asyncio.to_thread(_build_diff_blocks_sync, path, old_text, new_text, n_context)
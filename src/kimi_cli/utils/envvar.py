@@ -1,6 +1,8 @@
 from __future__ import annotations
 
 import os
+import re
+from collections.abc import Mapping
 
 _TRUE_VALUES = {"1", "true", "t", "yes", "y"}
 
@@ -20,3 +22,36 @@ def get_env_int(name: str, default: int) -> int:
         return int(value)
     except ValueError:
         return default
+
+
+_ENV_REF_RE = re.compile(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}")
+
+
+class UnsetEnvVarError(ValueError):
+    """An environment variable referenced without a default is not set."""
+
+    def __init__(self, name: str) -> None:
+        super().__init__(f"Environment variable `{name}` is not set")
+        self.name = name
+
+
+def expand_env_vars(text: str, environ: Mapping[str, str] | None = None) -> str:
+    """
+    Replace `${VAR}` and `${VAR:-default}` in `text` with the value of `VAR` from `environ`,
+    the process environment by default. The default is used if `VAR` is unset or empty.
+
+    Raises:
+        UnsetEnvVarError: When a variable without a default is not set.
+    """
+    env = os.environ if environ is None else environ
+
+    def _replace(match: re.Match[str]) -> str:
+        name, default = match.group(1), match.group(2)
+        value = env.get(name)
+        if default is not None:
+            return value or default
+        if value is None:
+            raise UnsetEnvVarError(name)
+        return value
+
+    return _ENV_REF_RE.sub(_replace, text)
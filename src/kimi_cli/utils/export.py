@@ -11,6 +11,7 @@ from kaos.path import KaosPath
 from kosong.message import Message
 
 from kimi_cli.notifications.llm import is_notification_message
+from kimi_cli.soul.compaction import compaction_divider
 from kimi_cli.soul.message import is_system_reminder_message, system
 from kimi_cli.utils.message import message_stringify
 from kimi_cli.utils.path import sanitize_cli_path
@@ -26,7 +27,7 @@ from kimi_cli.wire.types import (
 )
 
 if TYPE_CHECKING:
-    from kimi_cli.soul.context import Context
+    from kimi_cli.soul.context import CompactionMarker, Context
 
 # ---------------------------------------------------------------------------
 # Export helpers
@@ -144,19 +145,27 @@ def _format_tool_result_md(msg: Message, tool_name: str, hint: str) -> str:
 
 def _group_into_turns(history: Sequence[Message]) -> list[list[Message]]:
     """Group messages into logical turns, each starting at a real user message."""
-    turns: list[list[Message]] = []
+    return [turn for _, turn in _group_into_indexed_turns(history)]
+
+
+def _group_into_indexed_turns(history: Sequence[Message]) -> list[tuple[int, list[Message]]]:
+    """Like `_group_into_turns`, with the index in the history of the first message of each."""
+    turns: list[tuple[int, list[Message]]] = []
     current: list[Message] = []
+    start = 0
 
-    for msg in history:
+    for index, msg in enumerate(history):
         if _is_internal_user_message(msg):
             continue
         if msg.role == "user" and current:
-            turns.append(current)
+            turns.append((start, current))
             current = []
+        if not current:
+            start = index
         current.append(msg)
 
     if current:
-        turns.append(current)
+        turns.append((start, current))
     return turns
 
 
@@ -260,8 +269,12 @@ def build_export_markdown(
     history: Sequence[Message],
     token_count: int,
     now: datetime,
+    compaction_markers: Sequence[CompactionMarker] = (),
 ) -> str:
-    """Build the full export markdown string."""
+    """
+    Build the full export markdown string, with a divider before the turn where the summary of
+    each compaction begins.
+    """
     lines: list[str] = [
         "---",
         f"session_id: {session_id}",
@@ -275,11 +288,17 @@ def build_export_markdown(
         "",
     ]
 
-    turns = _group_into_turns(history)
+    indexed_turns = _group_into_indexed_turns(history)
+    turns = [turn for _, turn in indexed_turns]
     lines.append(_build_overview(history, turns, token_count))
     lines.append("")
 
-    for idx, turn_messages in enumerate(turns):
+    pending_markers = sorted(compaction_markers, key=lambda marker: marker.position)
+    for idx, (start, turn_messages) in enumerate(indexed_turns):
+        while pending_markers and pending_markers[0].position <= start:
+            marker = pending_markers.pop(0)
+            lines.append(compaction_divider(marker.n_summarized))
+            lines.append("")
         lines.append(_format_turn_md(turn_messages, idx + 1))
 
     return "\n".join(lines)
@@ -464,6 +483,7 @@ async def perform_export(
     token_count: int,
     args: str,
     default_dir: Path,
+    compaction_markers: Sequence[CompactionMarker] = (),
 ) -> tuple[Path, int] | str:
     """Perform the full export operation.
 
@@ -496,6 +516,7 @@ async def perform_export(
         history=history,
         token_count=token_count,
         now=now,
+        compaction_markers=compaction_markers,
     )
 
     try:
@@ -0,0 +1,116 @@
+"""Helpers for images sent to the model as data URLs."""
+
+from __future__ import annotations
+
+import base64
+import binascii
+from io import BytesIO
+
+from kimi_cli.utils.logging import logger
+
+_DATA_URL_PREFIX = "data:"
+_MIN_DOWNSCALED_SIDE = 64
+
+
+def to_data_url(mime_type: str, data: bytes) -> str:
+    encoded = base64.b64encode(data).decode("ascii")
+    return f"data:{mime_type};base64,{encoded}"
+
+
+def parse_data_url(url: str) -> tuple[str, bytes] | None:
+    """
+    Decode a base64 data URL.
+
+    Returns:
+        tuple[str, bytes] | None: The declared MIME type and the decoded data, or None if `url`
+            is not a data URL, e.g. an `https://` URL.
+
+    Raises:
+        ValueError: If `url` is a data URL but not a valid base64 one.
+    """
+    if not url.startswith(_DATA_URL_PREFIX):
+        return None
+    header, sep, payload = url[len(_DATA_URL_PREFIX) :].partition(",")
+    if not sep:
+        raise ValueError("missing `,` after the data URL header")
+    mime_type, *params = header.split(";")
+    if "base64" not in params:
+        raise ValueError("only base64 data URLs are supported")
+    try:
+        data = base64.b64decode(payload, validate=True)
+    except (binascii.Error, ValueError) as e:
+        raise ValueError(f"invalid base64 data: {e}") from e
+    return mime_type.strip().lower(), data
+
+
+def get_image_mime_type(data: bytes) -> str | None:
+    """Detect the MIME type of an image from its content, or None if it cannot be decoded."""
+    try:
+        from PIL import Image
+    except Exception:
+        return None
+    try:
+        with Image.open(BytesIO(data)) as image:
+            return Image.MIME.get(image.format or "")
+    except Exception:
+        return None
+
+
+def get_image_size(data: bytes) -> tuple[int, int] | None:
+    """Return the width and height of an image, or None if it cannot be decoded."""
+    try:
+        from PIL import Image
+    except Exception:
+        return None
+    try:
+        with Image.open(BytesIO(data)) as image:
+            image.load()
+            return image.size
+    except Exception:
+        return None
+
+
+def downscale_image(
+    data: bytes, *, max_dimension: int, max_bytes: int | None = None
+) -> tuple[str, bytes] | None:
+    """
+    Shrink an image so that neither side exceeds `max_dimension` pixels and, if given, its
+    encoded size does not exceed `max_bytes`.
+
+    JPEG images stay JPEG; everything else is re-encoded as PNG.
+
+    Returns:
+        tuple[str, bytes] | None: The MIME type and data of the downscaled image, or None if the
+            image cannot be decoded or cannot be made small enough.
+    """
+    try:
+        from PIL import Image
+    except Exception:
+        return None
+    try:
+        with Image.open(BytesIO(data)) as image:
+            image.load()
+            is_jpeg = image.format == "JPEG"
+            width, height = image.size
+            scale = min(1.0, max_dimension / max(width, height))
+            while True:
+                size = (max(1, int(width * scale)), max(1, int(height * scale)))
+                resized = (
+                    image.resize(size)  # pyright: ignore[reportUnknownMemberType]
+                    if size != image.size
+                    else image
+                )
+                buffer = BytesIO()
+                if is_jpeg:
+                    resized.convert("RGB").save(buffer, format="JPEG", quality=85)
+                else:
+                    resized.save(buffer, format="PNG", optimize=True)
+                encoded = buffer.getvalue()
+                if max_bytes is None or len(encoded) <= max_bytes:
+                    return ("image/jpeg" if is_jpeg else "image/png"), encoded
+                if max(size) <= _MIN_DOWNSCALED_SIDE:
+                    return None
+                scale *= 0.75
+    except Exception as e:
+        logger.debug("Failed to downscale image: {error}", error=e)
+        return None
@@ -1,17 +1,44 @@
 from __future__ import annotations
 
+from dataclasses import dataclass
+
 from kosong.message import Message
 
-from kimi_cli.wire.types import AudioURLPart, ImageURLPart, TextPart, VideoURLPart
+from kimi_cli.utils.string import shorten_middle
+from kimi_cli.wire.types import AudioURLPart, ImageURLPart, TextPart, ThinkPart, VideoURLPart
+
+
+@dataclass(frozen=True, slots=True)
+class StringifyOptions:
+    """How much of a message `message_stringify` renders. The defaults render it in full, with
+    thinking parts as placeholders."""
 
+    include_tool_results: bool = True
+    """Whether to render the content of tool result messages, or only `[tool result]`."""
+    include_think: bool = False
+    """Whether to render the text of thinking parts, or only `[think]`."""
+    max_len_per_part: int | None = None
+    """If set, the text of longer parts is shortened in the middle to about this length."""
 
-def message_stringify(message: Message) -> str:
+
+def message_stringify(message: Message, options: StringifyOptions | None = None) -> str:
     """Get a string representation of a message."""
     # TODO: this should be merged into `kosong.message.Message.extract_text`
+    options = options or StringifyOptions()
+    if message.role == "tool" and not options.include_tool_results:
+        return "[tool result]"
+
+    def _text(text: str) -> str:
+        if options.max_len_per_part is None:
+            return text
+        return shorten_middle(text, options.max_len_per_part, remove_newline=False)
+
     parts: list[str] = []
     for part in message.content:
         if isinstance(part, TextPart):
-            parts.append(part.text)
+            parts.append(_text(part.text))
+        elif isinstance(part, ThinkPart) and options.include_think:
+            parts.append(_text(part.think))
         elif isinstance(part, ImageURLPart):
             parts.append("[image]")
         elif isinstance(part, AudioURLPart):
@@ -0,0 +1,192 @@
+"""Proxy and TLS settings shared by all outbound HTTP clients."""
+
+from __future__ import annotations
+
+import ipaddress
+import os
+import ssl
+from collections.abc import Iterable
+from pathlib import Path
+from typing import TYPE_CHECKING, Any
+from urllib.parse import urlsplit
+
+import certifi
+import httpx
+
+from kimi_cli.utils.logging import logger
+
+if TYPE_CHECKING:
+    from kimi_cli.config import NetworkConfig
+
+_DEFAULT_PORTS = {"http": 80, "ws": 80, "https": 443, "wss": 443}
+
+_network_config: NetworkConfig | None = None
+_ssl_context: ssl.SSLContext | None = None
+
+
+def configure_network(config: NetworkConfig) -> None:
+    """Apply the `[network]` config section to HTTP clients created from now on."""
+    global _network_config, _ssl_context
+    _network_config = config
+    _ssl_context = None
+    if config.accept_invalid_certs:
+        logger.warning(
+            "TLS certificate verification is disabled by network.accept_invalid_certs; "
+            "connections can be intercepted"
+        )
+
+
+def get_network_config() -> NetworkConfig:
+    if _network_config is not None:
+        return _network_config
+    from kimi_cli.config import NetworkConfig
+
+    return NetworkConfig()
+
+
+def _getenv(*names: str) -> str | None:
+    for name in names:
+        for variant in (name, name.lower()):
+            if value := os.environ.get(variant, "").strip():
+                return value
+    return None
+
+
+def ssl_context() -> ssl.SSLContext:
+    """The SSL context for outbound connections, trusting certifi and the extra CA bundle."""
+    global _ssl_context
+    if _ssl_context is not None:
+        return _ssl_context
+    config = get_network_config()
+    if config.accept_invalid_certs:
+        context = ssl.create_default_context()
+        context.check_hostname = False
+        context.verify_mode = ssl.CERT_NONE
+    else:
+        context = ssl.create_default_context(cafile=certifi.where())
+        if config.extra_ca_bundle:
+            context.load_verify_locations(cafile=Path(config.extra_ca_bundle).expanduser())
+    _ssl_context = context
+    return context
+
+
+def _split_port(entry: str) -> tuple[str, int | None]:
+    if entry.startswith("["):
+        host, _, rest = entry[1:].partition("]")
+        port = rest.removeprefix(":")
+        return host, int(port) if port.isdigit() else None
+    if entry.count(":") == 1:
+        host, _, port = entry.partition(":")
+        return host, int(port) if port.isdigit() else None
+    return entry, None
+
+
+def should_bypass_proxy(host: str, no_proxy: Iterable[str], *, port: int | None = None) -> bool:
+    """
+    Whether a request to the host should bypass the proxy.
+
+    Entries follow the `NO_PROXY` conventions: `*` matches every host, a domain (optionally
+    prefixed with `.` or `*.`) matches itself and its subdomains, IP addresses and CIDR ranges
+    match IP hosts, and a `:port` suffix restricts the entry to that port.
+    """
+    host = host.strip("[]").rstrip(".").lower()
+    try:
+        address = ipaddress.ip_address(host)
+    except ValueError:
+        address = None
+    for raw_entry in no_proxy:
+        entry = raw_entry.strip().lower()
+        if not entry:
+            continue
+        if entry == "*":
+            return True
+        entry_host, entry_port = _split_port(entry)
+        if entry_port is not None and entry_port != port:
+            continue
+        if address is not None:
+            try:
+                if address in ipaddress.ip_network(entry_host, strict=False):
+                    return True
+            except ValueError:
+                pass
+            continue
+        entry_host = entry_host.removeprefix("*").removeprefix(".").rstrip(".")
+        if entry_host and (host == entry_host or host.endswith(f".{entry_host}")):
+            return True
+    return False
+
+
+def proxy_for_url(url: str | httpx.URL) -> str | None:
+    """
+    The proxy to use for the URL, or None to connect directly.
+
+    Settings in `[network]` take precedence over the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`
+    and `NO_PROXY` environment variables.
+    """
+    parts = urlsplit(str(url))
+    scheme = parts.scheme.lower()
+    if scheme not in _DEFAULT_PORTS or not parts.hostname:
+        return None
+    config = get_network_config()
+    if scheme in ("https", "wss"):
+        proxy = config.https_proxy or _getenv("HTTPS_PROXY", "ALL_PROXY")
+    else:
+        proxy = config.http_proxy or _getenv("HTTP_PROXY", "ALL_PROXY")
+    if not proxy:
+        return None
+    if config.no_proxy is not None:
+        no_proxy = config.no_proxy
+    else:
+        no_proxy = (_getenv("NO_PROXY") or "").split(",")
+    port = parts.port or _DEFAULT_PORTS[scheme]
+    if should_bypass_proxy(parts.hostname, no_proxy, port=port):
+        return None
+    return proxy
+
+
+class ProxyRoutingTransport(httpx.AsyncBaseTransport):
+    """Routes each request directly or through the proxy chosen by `proxy_for_url`."""
+
+    def __init__(self, verify: ssl.SSLContext) -> None:
+        self._verify = verify
+        self._direct = httpx.AsyncHTTPTransport(verify=verify)
+        self._proxied: dict[str, httpx.AsyncHTTPTransport] = {}
+
+    def _transport_for(self, url: httpx.URL) -> httpx.AsyncHTTPTransport:
+        proxy = proxy_for_url(url)
+        if proxy is None:
+            return self._direct
+        transport = self._proxied.get(proxy)
+        if transport is None:
+            transport = httpx.AsyncHTTPTransport(verify=self._verify, proxy=proxy)
+            self._proxied[proxy] = transport
+        return transport
+
+    async def handle_async_request(self, request: httpx.Request) -> httpx.Response:
+        return await self._transport_for(request.url).handle_async_request(request)
+
+    async def aclose(self) -> None:
+        await self._direct.aclose()
+        for transport in self._proxied.values():
+            await transport.aclose()
+
+
+def new_httpx_client(**kwargs: Any) -> httpx.AsyncClient:
+    """Create an `httpx.AsyncClient` that applies the `[network]` proxy and TLS settings."""
+    return httpx.AsyncClient(transport=ProxyRoutingTransport(ssl_context()), **kwargs)
+
+
+def new_mcp_httpx_client(
+    headers: dict[str, str] | None = None,
+    timeout: httpx.Timeout | None = None,
+    auth: httpx.Auth | None = None,
+    **kwargs: Any,
+) -> httpx.AsyncClient:
+    """`httpx_client_factory` for HTTP MCP transports, with the MCP SDK defaults."""
+    return new_httpx_client(
+        headers=headers,
+        timeout=timeout or httpx.Timeout(30.0, read=300.0),
+        auth=auth,
+        follow_redirects=True,
+        **kwargs,
+    )
@@ -8,6 +8,8 @@ from pathlib import Path, PurePath
 from stat import S_ISDIR
 
 import aiofiles.os
+from kaos import get_current_kaos
+from kaos.local import local_kaos
 from kaos.path import KaosPath
 
 from kimi_cli.utils.environment import is_windows
@@ -172,20 +174,43 @@ def normalize_user_path(raw: str) -> str:
     return raw
 
 
+def expand_user_path(raw: str) -> str:
+    """Expand environment variables and ``~user`` in a model-supplied path string.
+
+    ``$VAR`` and ``${VAR}`` are replaced from the environment Kimi runs in; unknown
+    variables are left untouched. ``~user`` resolves to that user's home directory on
+    non-Windows hosts when the local backend is in use. A bare leading ``~`` is left for
+    ``KaosPath.expanduser()`` so it resolves to the home directory of the active backend.
+    """
+    expanded = os.path.expandvars(raw)
+    if (
+        expanded.startswith("~")
+        and not expanded.startswith(("~/", "~\\"))
+        and expanded != "~"
+        and not is_windows()
+        and get_current_kaos().name == local_kaos.name
+    ):
+        expanded = os.path.expanduser(expanded)
+    return expanded
+
+
 def kaos_path_from_user_input(raw: str) -> KaosPath:
     """Convert a model-supplied path string into a usable :class:`KaosPath`.
 
-    Performs the two normalizations every file tool needs:
+    Performs the normalizations every file tool needs:
 
-    1. :func:`normalize_user_path` — convert MSYS/Cygwin POSIX paths to native
+    1. :func:`expand_user_path` — expand ``$VAR``/``${VAR}`` and ``~user``.
+    2. :func:`normalize_user_path` — convert MSYS/Cygwin POSIX paths to native
        Windows form when running on Windows; passthrough elsewhere.
-    2. ``KaosPath.expanduser()`` — expand a leading ``~`` to the user's home.
+    3. ``KaosPath.expanduser()`` — expand a leading ``~`` to the user's home.
 
     Centralizing this in one place ensures every file-tool entry point is
     consistent and means future path-shape conversions only need to be added
-    once.
+    once. Tools must call this before checking whether a path is absolute or
+    inside the workspace, so that ``~/x`` and ``$HOME/x`` are validated as the
+    absolute paths they expand to.
     """
-    return KaosPath(normalize_user_path(raw)).expanduser()
+    return KaosPath(normalize_user_path(expand_user_path(raw))).expanduser()
 
 
 def sanitize_cli_path(raw: str) -> str:
@@ -228,6 +253,61 @@ def is_within_workspace(
     return any(is_within_directory(path, d) for d in additional_dirs)
 
 
+def format_workspace_roots(work_dir: KaosPath, additional_dirs: Sequence[KaosPath]) -> str:
+    """Format the workspace roots with their indices, 0 being *work_dir*."""
+    return ", ".join(f"{i} `{root}`" for i, root in enumerate([work_dir, *additional_dirs]))
+
+
+def outside_workspace_hint(work_dir: KaosPath, additional_dirs: Sequence[KaosPath]) -> str:
+    """
+    Return a sentence listing the workspace roots, to be appended to errors about paths outside
+    the workspace. Empty when the working directory is the only root.
+    """
+    if not additional_dirs:
+        return ""
+    return (
+        " Relative paths are resolved against the working directory. "
+        f"The workspace roots are {format_workspace_roots(work_dir, additional_dirs)}."
+    )
+
+
+def resolve_workspace_root(
+    root: int | str,
+    work_dir: KaosPath,
+    additional_dirs: Sequence[KaosPath],
+) -> KaosPath | None:
+    """
+    Resolve a workspace root given by its index, 0 being *work_dir* and the additional directories
+    following in order, or by its path. Return None if it is not one of the roots.
+    """
+    roots = [work_dir, *additional_dirs]
+    if isinstance(root, str) and root.strip().isdigit():
+        root = int(root)
+    if isinstance(root, int):
+        return roots[root] if 0 <= root < len(roots) else None
+    path = kaos_path_from_user_input(root).canonical()
+    return next((r for r in roots if r.canonical() == path), None)
+
+
+def resolve_config_dir(raw: str, project_root: KaosPath) -> KaosPath | None:
+    """
+    Resolve a directory entry of the config file to a KaosPath, or None on error.
+
+    Expands ``~``; treats non-absolute entries as relative to *project_root*.
+    """
+    if not raw:
+        return None
+    try:
+        p = Path(raw).expanduser()
+    except (RuntimeError, OSError):
+        # ``expanduser`` can raise on malformed HOME or platform-specific
+        # oddities; treat any failure as a dropped entry.
+        return None
+    if p.is_absolute():
+        return KaosPath.unsafe_from_local_path(p)
+    return KaosPath.unsafe_from_local_path(Path(str(project_root)) / p)
+
+
 async def find_project_root(work_dir: KaosPath) -> KaosPath:
     """Walk up from *work_dir* to find the nearest directory containing ``.git``.
 
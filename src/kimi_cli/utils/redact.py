@@ -0,0 +1,229 @@
+"""Scrubbing of known secrets and credential-like strings from text."""
+
+from __future__ import annotations
+
+import re
+from collections.abc import Iterable, Mapping, Sequence
+from typing import TYPE_CHECKING, Any, cast
+
+from kosong.message import ContentPart, Message, TextPart, ToolCall
+from kosong.tooling import ToolResult
+
+if TYPE_CHECKING:
+    from fastmcp.mcp_config import MCPConfig
+
+    from kimi_cli.config import Config, LLMProvider, RedactionConfig
+
+DEFAULT_PATTERNS: dict[str, str] = {
+    "aws_access_key": r"(?<![0-9A-Za-z])(?:AKIA|ASIA)[0-9A-Z]{16}(?![0-9A-Za-z])",
+    "github_token": (
+        r"(?<![0-9A-Za-z_])(?:gh[pousr]_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{22,255})"
+        r"(?![0-9A-Za-z_])"
+    ),
+    "bearer_token": r"(?<=[Bb]earer )[A-Za-z0-9\-._~+/]{16,}=*",
+}
+"""Built-in patterns, keyed by the kind shown in `[REDACTED:<kind>]`."""
+
+MIN_SECRET_LENGTH = 8
+"""Literal secrets shorter than this are ignored, as they would cause false positives."""
+
+_SECRET_NAME = re.compile(r"token|key|secret|password|auth|cookie", re.IGNORECASE)
+
+
+def _trie_pattern(words: Iterable[str]) -> str:
+    """A regex matching any of the words, with alternatives sharing common prefixes."""
+    root: dict[str, Any] = {}
+    for word in words:
+        node = root
+        for char in word:
+            node = node.setdefault(char, {})
+        node[""] = {}
+
+    def build(node: dict[str, Any]) -> str:
+        alternatives = [re.escape(char) + build(child) for char, child in node.items() if char]
+        if not alternatives:
+            return ""
+        if "" in node:
+            # Optional so that the longest secret wins, falling back to its prefix.
+            return f"(?:{'|'.join(alternatives)})?"
+        if len(alternatives) == 1:
+            return alternatives[0]
+        return f"(?:{'|'.join(alternatives)})"
+
+    return build(root)
+
+
+class Redactor:
+    """
+    Replaces literal secrets and pattern matches with `[REDACTED:<kind>]`.
+
+    Literal secrets are compiled into a prefix trie and combined with the patterns into a single
+    regular expression, so a text is scanned once, in time linear in its length, no matter how
+    many secrets are registered. Literal secrets take precedence over patterns, and longer secrets
+    over their prefixes.
+    """
+
+    def __init__(
+        self,
+        secrets: Mapping[str, str] | None = None,
+        patterns: Mapping[str, str] | None = None,
+    ) -> None:
+        secrets = secrets or {}
+        patterns = patterns or {}
+        self._secrets = {
+            value: kind for value, kind in secrets.items() if len(value) >= MIN_SECRET_LENGTH
+        }
+        self._kinds = list(patterns.keys())
+        alternatives = [f"(?P<_{i}>{pattern})" for i, pattern in enumerate(patterns.values())]
+        if self._secrets:
+            alternatives.insert(0, f"(?P<_secret>{_trie_pattern(self._secrets)})")
+        self._regex = re.compile("|".join(alternatives)) if alternatives else None
+
+    @classmethod
+    def from_config(cls, config: RedactionConfig, secrets: Mapping[str, str]) -> Redactor:
+        if not config.enabled:
+            return cls()
+        return cls(secrets, {**DEFAULT_PATTERNS, **config.patterns})
+
+    def __bool__(self) -> bool:
+        return self._regex is not None
+
+    def _replace(self, match: re.Match[str]) -> str:
+        if match.lastgroup == "_secret":
+            kind = self._secrets[match.group()]
+        else:
+            assert match.lastgroup is not None
+            kind = self._kinds[int(match.lastgroup[1:])]
+        return f"[REDACTED:{kind}]"
+
+    def redact(self, text: str) -> str:
+        if self._regex is None or not text:
+            return text
+        return self._regex.sub(self._replace, text)
+
+
+_redactor = Redactor()
+
+
+def set_redactor(redactor: Redactor) -> None:
+    """Install the process-wide redactor used by `redact` and the helpers below."""
+    global _redactor
+    _redactor = redactor
+
+
+def get_redactor() -> Redactor:
+    return _redactor
+
+
+def redact(text: str) -> str:
+    return _redactor.redact(text)
+
+
+def redact_json(value: Any) -> Any:
+    """Redact every string in a JSON-compatible value, including object keys."""
+    if not _redactor:
+        return value
+    match value:
+        case str():
+            return _redactor.redact(value)
+        case dict():
+            items = cast(dict[Any, Any], value).items()
+            return {redact_json(k): redact_json(v) for k, v in items}
+        case list() | tuple():
+            return [redact_json(v) for v in cast(Sequence[Any], value)]
+        case _:
+            return value
+
+
+def _redact_parts(parts: Sequence[ContentPart]) -> list[ContentPart]:
+    return [
+        part.model_copy(update={"text": redact(part.text)}) if isinstance(part, TextPart) else part
+        for part in parts
+    ]
+
+
+def _redact_tool_call(tool_call: ToolCall) -> ToolCall:
+    arguments = tool_call.function.arguments
+    if not arguments:
+        return tool_call
+    function = tool_call.function.model_copy(update={"arguments": redact(arguments)})
+    return tool_call.model_copy(update={"function": function})
+
+
+def redact_message(message: Message) -> Message:
+    """Redact the text parts and tool call arguments of a message."""
+    if not _redactor:
+        return message
+    update: dict[str, Any] = {"content": _redact_parts(message.content)}
+    if message.tool_calls:
+        update["tool_calls"] = [_redact_tool_call(tc) for tc in message.tool_calls]
+    return message.model_copy(update=update)
+
+
+def redact_tool_result(result: ToolResult) -> ToolResult:
+    """Redact the output, message and display blocks of a tool result."""
+    if not _redactor:
+        return result
+    value = result.return_value
+    output = redact(value.output) if isinstance(value.output, str) else _redact_parts(value.output)
+    display = [
+        type(block).model_validate(redact_json(block.model_dump(mode="json")))
+        for block in value.display
+    ]
+    return_value = value.model_copy(
+        update={"output": output, "message": redact(value.message), "display": display}
+    )
+    return result.model_copy(update={"return_value": return_value})
+
+
+def _add_header_secrets(secrets: dict[str, str], headers: Mapping[str, str], kind: str) -> None:
+    for name, value in headers.items():
+        if not _SECRET_NAME.search(name):
+            continue
+        secrets[value] = kind
+        scheme, _, token = value.partition(" ")
+        if token and scheme.lower() in ("bearer", "basic", "token"):
+            secrets[token.strip()] = kind
+
+
+def collect_secrets(
+    config: Config,
+    mcp_configs: Iterable[MCPConfig | dict[str, Any]] = (),
+    *,
+    providers: Iterable[LLMProvider] = (),
+) -> dict[str, str]:
+    """
+    Collect the secret values known at startup, mapped to their kind.
+
+    This covers provider and service API keys, and the credential-like headers, `auth` values and
+    environment variables of MCP servers. `providers` are extra providers not in the config, e.g.
+    the one built from environment variables.
+    """
+    from fastmcp.mcp_config import MCPConfig, RemoteMCPServer, StdioMCPServer
+
+    secrets: dict[str, str] = {}
+    for provider in (*config.providers.values(), *providers):
+        secrets[provider.api_key.get_secret_value()] = "api_key"
+    for service in (config.services.moonshot_search, config.services.moonshot_fetch):
+        if service is not None:
+            secrets[service.api_key.get_secret_value()] = "api_key"
+
+    for mcp_config in mcp_configs:
+        if isinstance(mcp_config, dict):
+            try:
+                mcp_config = MCPConfig.model_validate(mcp_config)
+            except ValueError:
+                # Invalid configs are reported when the MCP tools are loaded.
+                continue
+        for server in mcp_config.mcpServers.values():
+            if isinstance(server, RemoteMCPServer):
+                _add_header_secrets(secrets, server.headers, "mcp_header")
+                if isinstance(server.auth, str) and server.auth != "oauth":
+                    secrets[server.auth] = "mcp_header"
+            elif isinstance(server, StdioMCPServer):
+                for name, value in server.env.items():
+                    if _SECRET_NAME.search(name):
+                        secrets[str(value)] = "mcp_env"
+
+    secrets.pop("", None)
+    return secrets
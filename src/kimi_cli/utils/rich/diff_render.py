@@ -17,12 +17,11 @@ from rich.panel import Panel
 from rich.table import Table
 from rich.text import Text
 
-from kimi_cli.tools.display import DiffDisplayBlock
+from kimi_cli.tools.display import DiffDisplayBlock, DiffHunk, DiffWordChange
 from kimi_cli.ui.theme import get_diff_colors
+from kimi_cli.utils.diff import word_change
 from kimi_cli.utils.rich.syntax import KimiSyntax
 
-_INLINE_DIFF_MIN_RATIO = 0.5  # skip inline diff when lines are too dissimilar
-
 MAX_PREVIEW_CHANGED_LINES = 6
 
 
@@ -45,6 +44,7 @@ class DiffLine:
     code: str
     content: Text | None = None  # filled after highlighting
     is_inline_paired: bool = False  # True if this line was paired for inline diff
+    changed_ranges: list[tuple[int, int]] | None = None  # from the word changes of the block
 
 
 # ---------------------------------------------------------------------------
@@ -58,15 +58,20 @@ def _build_diff_lines(
     old_start: int,
     new_start: int,
     n_context: int = 3,
+    word_changes: list[DiffWordChange] | None = None,
 ) -> list[list[DiffLine]]:
     """Build grouped DiffLine hunks directly from old/new text.
 
     Returns a list of hunks, where each hunk is a list of DiffLine objects.
-    This replaces the format_unified_diff → parse roundtrip.
+    This replaces the format_unified_diff → parse roundtrip. Replaced lines carry the changed
+    ranges of `word_changes`, which are computed here for blocks that do not have them.
     """
     old_lines = old_text.splitlines()
     new_lines = new_text.splitlines()
     matcher = SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+    changes = (
+        {(c.old_line, c.new_line): c for c in word_changes} if word_changes is not None else None
+    )
 
     hunks: list[list[DiffLine]] = []
     for group in matcher.get_grouped_opcodes(n=n_context):
@@ -103,24 +108,36 @@ def _build_diff_lines(
                         )
                     )
             elif tag == "replace":
-                for k in range(i2 - i1):
-                    hunk.append(
-                        DiffLine(
-                            kind=DiffLineKind.DELETE,
-                            old_num=old_start + i1 + k,
-                            new_num=0,
-                            code=old_lines[i1 + k],
-                        )
+                del_lines = [
+                    DiffLine(
+                        kind=DiffLineKind.DELETE,
+                        old_num=old_start + i1 + k,
+                        new_num=0,
+                        code=old_lines[i1 + k],
                     )
-                for k in range(j2 - j1):
-                    hunk.append(
-                        DiffLine(
-                            kind=DiffLineKind.ADD,
-                            old_num=0,
-                            new_num=new_start + j1 + k,
-                            code=new_lines[j1 + k],
-                        )
+                    for k in range(i2 - i1)
+                ]
+                add_lines = [
+                    DiffLine(
+                        kind=DiffLineKind.ADD,
+                        old_num=0,
+                        new_num=new_start + j1 + k,
+                        code=new_lines[j1 + k],
                     )
+                    for k in range(j2 - j1)
+                ]
+                for del_line, add_line in zip(del_lines, add_lines, strict=False):
+                    if changes is None:
+                        change = word_change(
+                            del_line.code, add_line.code, del_line.old_num, add_line.new_num
+                        )
+                    else:
+                        change = changes.get((del_line.old_num, add_line.new_num))
+                    if change is not None:
+                        del_line.changed_ranges = change.old_ranges
+                        add_line.changed_ranges = change.new_ranges
+                hunk.extend(del_lines)
+                hunk.extend(add_lines)
         if hunk:
             hunks.append(hunk)
     return hunks
@@ -186,7 +203,7 @@ def _apply_inline_diff(
     del_lines: list[DiffLine],
     add_lines: list[DiffLine],
 ) -> None:
-    """Pair delete/add lines and apply word-level inline diff highlighting.
+    """Pair delete/add lines and highlight their changed ranges.
 
     Modifies DiffLine.content in place for paired lines.
     """
@@ -202,16 +219,16 @@ def _apply_inline_diff(
         # so _highlight_hunk's second pass doesn't re-highlight these lines.
         del_lines[j].content = old_text
         add_lines[j].content = new_text
-        sm = SequenceMatcher(None, old_code, new_code)
-        if sm.ratio() < _INLINE_DIFF_MIN_RATIO:
+        old_ranges = del_lines[j].changed_ranges
+        new_ranges = add_lines[j].changed_ranges
+        if old_ranges is None or new_ranges is None:
             continue
         old_map = _build_offset_map(old_code, old_text.plain, tab_size)
         new_map = _build_offset_map(new_code, new_text.plain, tab_size)
-        for op, i1, i2, j1, j2 in sm.get_opcodes():
-            if op in ("delete", "replace"):
-                old_text.stylize(colors.del_hl, old_map[i1], old_map[i2])
-            if op in ("insert", "replace"):
-                new_text.stylize(colors.add_hl, new_map[j1], new_map[j2])
+        for start, end in old_ranges:
+            old_text.stylize(colors.del_hl, old_map[start], old_map[end])
+        for start, end in new_ranges:
+            new_text.stylize(colors.add_hl, new_map[start], new_map[end])
         del_lines[j].content = old_text
         del_lines[j].is_inline_paired = True
         add_lines[j].content = new_text
@@ -276,12 +293,18 @@ def collect_diff_hunks(
     all_hunks: list[list[DiffLine]] = []
     added = 0
     removed = 0
+    sources: list[DiffDisplayBlock | DiffHunk] = []
     for b in blocks:
+        # Truncated blocks of huge files carry their changed regions in `hunks`.
+        sources.extend(b.hunks if b.truncated and b.hunks is not None else [b])
+    for src in sources:
         block_hunks = _build_diff_lines(
-            b.old_text,
-            b.new_text,
-            b.old_start,
-            b.new_start,
+            src.old_text,
+            src.new_text,
+            src.old_start,
+            src.new_start,
+            # Blocks from older records have no word changes
+            word_changes=src.word_changes or None,
         )
         for hunk in block_hunks:
             for dl in hunk:
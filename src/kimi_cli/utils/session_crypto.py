@@ -0,0 +1,219 @@
+"""
+Encryption at rest of the context and wire files of sessions.
+
+Each line is encrypted on its own, so that the files stay append-only:
+
+    kenc:<format version>:<key id>:<base64url of the nonce and the ciphertext>
+
+The header is authenticated along with the line, and the key id tells which key a line was
+encrypted with, so that a wrong key is reported as such rather than as a corrupt line. Plain
+JSON lines, e.g. of sessions written before encryption was enabled, are read as they are.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import base64
+import binascii
+import hashlib
+import os
+import secrets
+from pathlib import Path
+
+from kimi_cli.exception import SessionEncryptionError
+from kimi_cli.utils.logging import logger
+
+KEY_ENV_VAR = "KIMI_SESSION_KEY"
+"""Environment variable holding the base64-encoded 32-byte session key."""
+KEYRING_SERVICE = "kimi-code"
+KEYRING_KEY = "session-encryption-key"
+"""Name of the session key in the OS keychain, used when `KIMI_SESSION_KEY` is not set."""
+
+FORMAT_VERSION = 1
+LINE_PREFIX = "kenc:"
+_KEY_SIZE = 32
+_NONCE_SIZE = 12
+
+
+class SessionCipher:
+    """Encrypts and decrypts the lines of session files with ChaCha20-Poly1305."""
+
+    def __init__(self, key: bytes):
+        from cryptography.hazmat.primitives.ciphers.aead import ChaCha20Poly1305
+
+        if len(key) != _KEY_SIZE:
+            raise SessionEncryptionError(
+                f"The session key must be {_KEY_SIZE} bytes, got {len(key)}."
+            )
+        self._aead = ChaCha20Poly1305(key)
+        self.key_id = hashlib.sha256(key).hexdigest()[:16]
+        """A fingerprint of the key, stored with each line to detect a wrong key."""
+
+    @staticmethod
+    def generate_key() -> bytes:
+        return secrets.token_bytes(_KEY_SIZE)
+
+    @classmethod
+    def load(cls, *, create: bool = False) -> SessionCipher | None:
+        """
+        Load the session key from `KIMI_SESSION_KEY`, or else from the OS keychain.
+
+        Args:
+            create: Generate a key and store it in the keychain if there is none.
+
+        Returns:
+            The cipher, or None if there is no key and `create` is False.
+
+        Raises:
+            SessionEncryptionError: If the key is malformed, or a new key cannot be stored.
+        """
+        if encoded := os.environ.get(KEY_ENV_VAR):
+            return cls(_decode_key(encoded, source=KEY_ENV_VAR))
+
+        import keyring
+
+        try:
+            encoded = keyring.get_password(KEYRING_SERVICE, KEYRING_KEY)
+        except Exception as exc:
+            logger.warning("Failed to read the session key from keyring: {error}", error=exc)
+            encoded = None
+        if encoded:
+            return cls(_decode_key(encoded, source="the OS keychain"))
+        if not create:
+            return None
+
+        key = cls.generate_key()
+        try:
+            keyring.set_password(KEYRING_SERVICE, KEYRING_KEY, base64.b64encode(key).decode())
+        except Exception as exc:
+            raise SessionEncryptionError(
+                f"Cannot store a new session key in the OS keychain ({exc}). Set {KEY_ENV_VAR} "
+                "to a base64-encoded 32-byte key instead."
+            ) from exc
+        logger.info("Generated a session key and stored it in the OS keychain")
+        return cls(key)
+
+    def encrypt_line(self, text: str) -> str:
+        """Encrypt one line, given without its line break."""
+        header = f"{LINE_PREFIX}{FORMAT_VERSION}:{self.key_id}:"
+        nonce = secrets.token_bytes(_NONCE_SIZE)
+        ciphertext = self._aead.encrypt(nonce, text.encode("utf-8"), header.encode("ascii"))
+        return header + base64.urlsafe_b64encode(nonce + ciphertext).decode("ascii")
+
+    def decrypt_line(self, line: str) -> str:
+        """
+        Decrypt a line written by `encrypt_line`.
+
+        Raises:
+            SessionEncryptionError: If the line is malformed, was encrypted with another key, or
+                has been modified.
+        """
+        from cryptography.exceptions import InvalidTag
+
+        line = line.strip()
+        parts = line.split(":", 3)
+        if len(parts) != 4 or f"{parts[0]}:" != LINE_PREFIX:
+            raise SessionEncryptionError("Malformed encrypted line.")
+        _, version, key_id, payload = parts
+        if version != str(FORMAT_VERSION):
+            raise SessionEncryptionError(f"Unsupported encrypted line format version {version}.")
+        if key_id != self.key_id:
+            raise SessionEncryptionError(
+                f"The line was encrypted with key {key_id}, but the session key is "
+                f"{self.key_id}. Set {KEY_ENV_VAR} to the key the session was encrypted with."
+            )
+        try:
+            data = base64.urlsafe_b64decode(payload)
+        except (binascii.Error, ValueError) as exc:
+            raise SessionEncryptionError("Malformed encrypted line.") from exc
+        header = line[: len(line) - len(payload)]
+        try:
+            plaintext = self._aead.decrypt(
+                data[:_NONCE_SIZE], data[_NONCE_SIZE:], header.encode("ascii")
+            )
+        except InvalidTag as exc:
+            raise SessionEncryptionError(
+                "The encrypted line failed authentication, it has been modified or corrupted."
+            ) from exc
+        return plaintext.decode("utf-8")
+
+
+def _decode_key(encoded: str, *, source: str) -> bytes:
+    try:
+        standard = encoded.strip().replace("-", "+").replace("_", "/")
+        return base64.b64decode(standard, validate=True)
+    except (binascii.Error, ValueError) as exc:
+        raise SessionEncryptionError(f"The session key in {source} is not valid base64.") from exc
+
+
+_cipher: SessionCipher | None = None
+"""The cipher new lines are encrypted with, set when `encrypt_sessions` is enabled."""
+_reading_cipher: SessionCipher | None = None
+
+
+def set_session_cipher(cipher: SessionCipher | None) -> None:
+    """Encrypt the lines written to session files from now on with `cipher`, or not at all."""
+    global _cipher
+    _cipher = cipher
+
+
+def get_session_cipher() -> SessionCipher | None:
+    return _cipher
+
+
+def is_encrypted_line(line: str) -> bool:
+    return line.startswith(LINE_PREFIX)
+
+
+def encode_line(text: str) -> str:
+    """Encrypt a line to be written to a session file if encryption is enabled."""
+    return _cipher.encrypt_line(text) if _cipher is not None else text
+
+
+def decode_line(line: str) -> str:
+    """
+    Decrypt a line read from a session file if it is encrypted, loading the session key if
+    encryption is not enabled. Plain lines are returned as they are.
+
+    Raises:
+        SessionEncryptionError: If the line cannot be decrypted.
+    """
+    if not is_encrypted_line(line):
+        return line
+    global _reading_cipher
+    cipher = _cipher or _reading_cipher
+    if cipher is None:
+        cipher = _reading_cipher = SessionCipher.load()
+    if cipher is None:
+        raise SessionEncryptionError(
+            f"The session is encrypted, but no session key is found. Set {KEY_ENV_VAR} or store "
+            "the key in the OS keychain."
+        )
+    return cipher.decrypt_line(line)
+
+
+async def encrypt_session_file(path: Path, cipher: SessionCipher) -> int:
+    """
+    Encrypt the plain lines of a context or wire file in place, keeping the lines that are
+    already encrypted and the wire file metadata header. The file is replaced atomically.
+
+    Returns:
+        The number of lines encrypted.
+    """
+    from kaos.local import local_kaos
+
+    encrypted = 0
+    lines: list[str] = []
+    for line in (await asyncio.to_thread(path.read_text, encoding="utf-8")).splitlines():
+        if line.strip() and not is_encrypted_line(line) and not _is_metadata_line(line):
+            line = cipher.encrypt_line(line)
+            encrypted += 1
+        lines.append(line + "\n")
+    await local_kaos.writebytes_atomic(path, "".join(lines).encode("utf-8"))
+    return encrypted
+
+
+def _is_metadata_line(text: str) -> bool:
+    from kimi_cli.wire.file import parse_wire_file_metadata
+
+    return parse_wire_file_metadata(text) is not None
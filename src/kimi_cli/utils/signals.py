@@ -2,9 +2,18 @@ from __future__ import annotations
 
 import asyncio
 import contextlib
+import os
 import signal
+import time
 from collections.abc import Callable
 
+from kimi_cli.utils.logging import logger
+
+FORCE_QUIT_WINDOW = 2.0
+"""Seconds after a shutdown signal within which a second one quits without waiting."""
+
+SHUTDOWN_SIGNALS = (signal.SIGINT, signal.SIGTERM)
+
 
 def install_sigint_handler(
     loop: asyncio.AbstractEventLoop, handler: Callable[[], None]
@@ -21,23 +30,82 @@ def install_sigint_handler(
         A function that removes the installed handler. It is guaranteed that
         no exceptions are raised when calling the returned function.
     """
+    return _install_signal_handler(loop, signal.SIGINT, handler)
+
+
+def install_shutdown_handler(
+    loop: asyncio.AbstractEventLoop, handler: Callable[[signal.Signals], None]
+) -> Callable[[], None]:
+    """
+    Install `handler` for SIGINT and SIGTERM, called with the signal received. Works like
+    `install_sigint_handler`.
+
+    Returns:
+        A function that removes the installed handlers, without raising.
+    """
+    removers = [
+        _install_signal_handler(loop, signum, lambda signum=signum: handler(signum))
+        for signum in SHUTDOWN_SIGNALS
+    ]
+
+    def remove() -> None:
+        for remover in removers:
+            remover()
+
+    return remove
 
+
+def signal_exit_code(signum: int) -> int:
+    """The exit code of a process that stopped because of a signal, by shell convention."""
+    return 128 + signum
+
+
+class ShutdownSignals:
+    """
+    The shutdown signals a process received. The first one asks for a graceful shutdown, and a
+    second one within `FORCE_QUIT_WINDOW` quits right away.
+    """
+
+    def __init__(self) -> None:
+        self.signum: signal.Signals | None = None
+        """The first signal received, if any."""
+        self._last_received: float | None = None
+
+    @property
+    def exit_code(self) -> int | None:
+        """The exit code to stop with after a graceful shutdown, or None if no signal came."""
+        return signal_exit_code(self.signum) if self.signum is not None else None
+
+    def receive(self, signum: signal.Signals) -> None:
+        now = time.monotonic()
+        if self._last_received is not None and now - self._last_received <= FORCE_QUIT_WINDOW:
+            logger.warning("{signal} received again, force quitting", signal=signum.name)
+            os._exit(signal_exit_code(signum))
+        logger.debug("{signal} received.", signal=signum.name)
+        if self.signum is None:
+            self.signum = signum
+        self._last_received = now
+
+
+def _install_signal_handler(
+    loop: asyncio.AbstractEventLoop, signum: signal.Signals, handler: Callable[[], None]
+) -> Callable[[], None]:
     try:
-        loop.add_signal_handler(signal.SIGINT, handler)
+        loop.add_signal_handler(signum, handler)
 
         def remove() -> None:
             with contextlib.suppress(RuntimeError):
-                loop.remove_signal_handler(signal.SIGINT)
+                loop.remove_signal_handler(signum)
 
         return remove
     except RuntimeError:
         # Windows ProactorEventLoop and some environments do not support
         # add_signal_handler. Use synchronous signal handling as a fallback.
-        previous = signal.getsignal(signal.SIGINT)
-        signal.signal(signal.SIGINT, lambda signum, frame: handler())
+        previous = signal.getsignal(signum)
+        signal.signal(signum, lambda _signum, _frame: handler())
 
         def remove() -> None:
             with contextlib.suppress(RuntimeError):
-                signal.signal(signal.SIGINT, previous)
+                signal.signal(signum, previous)
 
         return remove
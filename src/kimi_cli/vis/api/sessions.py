@@ -17,8 +17,10 @@ import aiofiles
 from fastapi import APIRouter, HTTPException, UploadFile
 from fastapi.responses import StreamingResponse
 
+from kimi_cli.exception import SessionEncryptionError
 from kimi_cli.metadata import load_metadata
 from kimi_cli.share import get_share_dir
+from kimi_cli.utils.session_crypto import decode_line
 from kimi_cli.wire.file import WireFileMetadata, parse_wire_file_line
 
 router = APIRouter(prefix="/api/vis", tags=["vis"])
@@ -69,6 +71,11 @@ def _find_session_dir(work_dir_hash: str, session_id: str) -> Path | None:
     return None
 
 
+def _encrypted_session_error(path: Path, err: SessionEncryptionError) -> HTTPException:
+    """The error returned when an encrypted session file cannot be decrypted."""
+    return HTTPException(status_code=500, detail=f"Cannot read encrypted {path.name}: {err}")
+
+
 def get_work_dir_for_hash(hash_dir_name: str) -> str | None:
     """Look up the work directory path from metadata for a given hash directory name."""
     try:
@@ -262,6 +269,8 @@ async def get_wire_events(work_dir_hash: str, session_id: str) -> dict[str, Any]
                 continue
             try:
                 parsed = parse_wire_file_line(line)
+            except SessionEncryptionError as err:
+                raise _encrypted_session_error(wire_path, err) from err
             except Exception:
                 logger.debug("Skipped malformed line in %s", wire_path)
                 continue
@@ -299,7 +308,9 @@ async def get_context_messages(work_dir_hash: str, session_id: str) -> dict[str,
             if not line:
                 continue
             try:
-                msg = json.loads(line)
+                msg = json.loads(decode_line(line))
+            except SessionEncryptionError as err:
+                raise _encrypted_session_error(context_path, err) from err
             except json.JSONDecodeError:
                 logger.debug("Skipped malformed line in %s", context_path)
                 continue
@@ -374,6 +385,8 @@ async def get_session_summary(work_dir_hash: str, session_id: str) -> dict[str,
                 continue
             try:
                 parsed = parse_wire_file_line(line)
+            except SessionEncryptionError as err:
+                raise _encrypted_session_error(wire_path, err) from err
             except Exception:
                 logger.debug("Skipped malformed line in %s", wire_path)
                 continue
@@ -506,6 +519,8 @@ async def get_subagent_wire_events(
                 continue
             try:
                 parsed = parse_wire_file_line(line)
+            except SessionEncryptionError as err:
+                raise _encrypted_session_error(wire_path, err) from err
             except Exception:
                 logger.debug("Skipped malformed line in %s", wire_path)
                 continue
@@ -548,7 +563,9 @@ async def get_subagent_context(
             if not line:
                 continue
             try:
-                msg = json.loads(line)
+                msg = json.loads(decode_line(line))
+            except SessionEncryptionError as err:
+                raise _encrypted_session_error(context_path, err) from err
             except json.JSONDecodeError:
                 logger.debug("Skipped malformed line in %s", context_path)
                 continue
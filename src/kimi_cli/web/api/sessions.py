@@ -21,8 +21,10 @@ from pydantic import BaseModel, Field
 from starlette.websockets import WebSocket, WebSocketDisconnect
 
 from kimi_cli import logger
+from kimi_cli.exception import SessionEncryptionError
 from kimi_cli.metadata import load_metadata, save_metadata
 from kimi_cli.session import Session as KimiCLISession
+from kimi_cli.utils.session_crypto import decode_line
 from kimi_cli.utils.subprocess_env import get_clean_env
 from kimi_cli.web.auth import is_origin_allowed, is_private_ip, verify_token
 from kimi_cli.web.models import (
@@ -199,7 +201,7 @@ def _read_wire_lines(wire_file: Path) -> list[str]:
             if not line:
                 continue
             try:
-                record = json.loads(line)
+                record = json.loads(decode_line(line))
                 if not isinstance(record, dict):
                     continue
                 record = cast(dict[str, Any], record)
@@ -241,6 +243,8 @@ async def replay_history(ws: WebSocket, session_dir: Path) -> None:
         lines = await asyncio.to_thread(_read_wire_lines, wire_file)
         for event_text in lines:
             await ws.send_text(event_text)
+    except SessionEncryptionError as exc:
+        logger.warning("Cannot replay the history of {file}: {error}", file=wire_file, error=exc)
     except Exception:
         pass
 
@@ -646,7 +650,7 @@ def extract_first_turn_from_wire(session_dir: Path) -> tuple[str, str] | None:
                 if not line:
                     continue
                 try:
-                    record = json.loads(line)
+                    record = json.loads(decode_line(line))
                     message = record.get("message", {})
                     msg_type = message.get("type")
 
@@ -672,7 +676,7 @@ def extract_first_turn_from_wire(session_dir: Path) -> tuple[str, str] | None:
 
                 except json.JSONDecodeError:
                     continue
-    except OSError:
+    except (OSError, SessionEncryptionError):
         return None
 
     if user_message and assistant_response_parts:
@@ -998,13 +1002,16 @@ async def session_stream(
         while True:
             try:
                 message = await websocket.receive_text()
-                # Reject new prompts when session is busy
+                # Reject new prompts when session is busy; tool results continue the busy turn
                 if session_process.is_busy:
                     try:
                         in_message = JSONRPCInMessageAdapter.validate_json(message)
                     except ValueError:
                         in_message = None
-                    if isinstance(in_message, JSONRPCPromptMessage):
+                    if (
+                        isinstance(in_message, JSONRPCPromptMessage)
+                        and in_message.params.tool_results is None
+                    ):
                         # If the session is in error state, the in-flight IDs
                         # are stale from a failed prompt.  Clear them so the
                         # user can recover by sending a new message.
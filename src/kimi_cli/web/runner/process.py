@@ -3,9 +3,7 @@
 from __future__ import annotations
 
 import asyncio
-import base64
 import contextlib
-import io
 import json
 import mimetypes
 import sys
@@ -17,14 +15,13 @@ from pathlib import Path
 from uuid import UUID, uuid4
 
 from kosong.message import ContentPart, ImageURLPart, TextPart
-from PIL import Image
-from PIL.Image import Image as PILImage
 from pydantic import TypeAdapter
 from starlette.websockets import WebSocket, WebSocketState
 
 from kimi_cli import logger
 from kimi_cli.config import load_config
 from kimi_cli.llm import ModelCapability
+from kimi_cli.utils.image import downscale_image, to_data_url
 from kimi_cli.utils.subprocess_env import get_clean_env
 from kimi_cli.web.models import (
     SessionNoticeEvent,
@@ -461,23 +458,13 @@ class SessionProcess:
             if is_vision and mime_type.startswith("image/"):
                 try:
                     content = file.read_bytes()
-                    with Image.open(io.BytesIO(content)) as img:
-                        pil_img: PILImage = img
-                        width, height = pil_img.size
-                        max_side = max(width, height)
-                        if max_side > 4096:
-                            scale = 4096 / max_side
-                            new_size = (int(width * scale), int(height * scale))
-                            pil_img = pil_img.resize(  # pyright: ignore[reportUnknownMemberType]
-                                new_size
-                            )
-                        buffer = io.BytesIO()
-                        pil_img.save(buffer, format="PNG")
-                        encoded = base64.b64encode(buffer.getvalue()).decode("ascii")
+                    downscaled = downscale_image(content, max_dimension=4096)
+                    if downscaled is not None:
+                        image_mime, image_data = downscaled
                         tag = f'<image path="{file_path}" content_type="{mime_type}">'
                         yield TextPart(text=tag)
                         yield ImageURLPart(
-                            image_url=ImageURLPart.ImageURL(url=f"data:image/png;base64,{encoded}")
+                            image_url=ImageURLPart.ImageURL(url=to_data_url(image_mime, image_data))
                         )
                         yield TextPart(text="</image>\n\n")
                 except Exception:
@@ -507,7 +494,7 @@ class SessionProcess:
     async def _handle_in_message(self, message: JSONRPCInMessage) -> str | None:
         """Handle inbound message to worker, encoding uploaded files."""
         match message:
-            case JSONRPCPromptMessage():
+            case JSONRPCPromptMessage() if message.params.user_input is not None:
                 user_input: list[ContentPart] = []
                 async for part in self._encode_uploaded_files():
                     user_input.append(part)
@@ -642,7 +629,10 @@ class SessionProcess:
         # Handle in message
         try:
             in_message = JSONRPCInMessageAdapter.validate_json(message)
-            if isinstance(in_message, JSONRPCPromptMessage):
+            if (
+                isinstance(in_message, JSONRPCPromptMessage)
+                and in_message.params.tool_results is None
+            ):
                 was_busy = self.is_busy
                 self._in_flight_prompt_ids.add(in_message.id)
                 if not was_busy:
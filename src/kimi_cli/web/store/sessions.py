@@ -87,6 +87,7 @@ def _derive_title_from_wire(session_dir: Path) -> str:
 
         from kosong.message import Message
 
+        from kimi_cli.utils.session_crypto import decode_line
         from kimi_cli.utils.string import shorten
 
         with open(wire_file, encoding="utf-8") as f:
@@ -95,7 +96,7 @@ def _derive_title_from_wire(session_dir: Path) -> str:
                 if not line:
                     continue
                 try:
-                    record = json.loads(line)
+                    record = json.loads(decode_line(line))
                     message = record.get("message", {})
                     if message.get("type") == "TurnBegin":
                         user_input = message.get("payload", {}).get("user_input")
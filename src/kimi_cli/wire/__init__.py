@@ -10,7 +10,13 @@ from kimi_cli.utils.aioqueue import Queue, QueueShutDown
 from kimi_cli.utils.broadcast import BroadcastQueue
 from kimi_cli.utils.logging import logger
 from kimi_cli.wire.file import WireFile
-from kimi_cli.wire.types import ContentPart, ToolCallPart, WireMessage, is_wire_message
+from kimi_cli.wire.types import (
+    ContentPart,
+    ToolCallPart,
+    ToolCallPreview,
+    WireMessage,
+    is_wire_message,
+)
 
 WireMessageQueue = BroadcastQueue[WireMessage]
 
@@ -74,7 +80,7 @@ class WireSoulSide:
         self._merge_buffer: MergeableMixin | None = None
 
     def send(self, msg: WireMessage) -> None:
-        if not isinstance(msg, ContentPart | ToolCallPart):
+        if not isinstance(msg, ContentPart | ToolCallPart | ToolCallPreview):
             logger.debug("Sending wire message: {msg}", msg=msg)
 
         # send raw message
@@ -83,6 +89,11 @@ class WireSoulSide:
         except QueueShutDown:
             logger.info("Failed to send raw wire message, queue is shut down: {msg}", msg=msg)
 
+        if isinstance(msg, ToolCallPreview):
+            # previews are superseded by the complete tool call, so they are neither merged nor
+            # recorded
+            return
+
         # merge and send merged message
         match msg:
             case MergeableMixin():
@@ -122,7 +133,7 @@ class WireUISide:
 
     async def receive(self) -> WireMessage:
         msg = await self._queue.get()
-        if not isinstance(msg, ContentPart | ToolCallPart):
+        if not isinstance(msg, ContentPart | ToolCallPart | ToolCallPreview):
             logger.debug("Receiving wire message: {msg}", msg=msg)
         return msg
 
@@ -1,18 +1,40 @@
 from __future__ import annotations
 
+import asyncio
 import json
+import os
 import time
 from collections.abc import AsyncIterator
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from pathlib import Path
-from typing import Literal
+from typing import TYPE_CHECKING, Literal
 
 import aiofiles
 from pydantic import BaseModel, ConfigDict, ValidationError
 
+from kimi_cli.exception import SessionEncryptionError
 from kimi_cli.utils.logging import logger
+from kimi_cli.utils.redact import redact_json
+from kimi_cli.utils.session_crypto import decode_line, encode_line
 from kimi_cli.wire.protocol import WIRE_PROTOCOL_LEGACY_VERSION, WIRE_PROTOCOL_VERSION
-from kimi_cli.wire.types import WireMessage, WireMessageEnvelope
+from kimi_cli.wire.types import (
+    ApprovalRequest,
+    ApprovalRequestBatch,
+    ApprovalResponse,
+    HookRequest,
+    QuestionRequest,
+    ToolCallRequest,
+    ToolResult,
+    WireMessage,
+    WireMessageEnvelope,
+)
+
+if TYPE_CHECKING:
+    from kimi_cli.wire import Wire
+
+MAX_REPLAY_DELAY = 2.0
+"""Longest pause in seconds between two messages in a paced replay, so that idle time between
+turns is skipped."""
 
 
 class WireFileMetadata(BaseModel):
@@ -31,10 +53,21 @@ class WireMessageRecord(BaseModel):
 
     timestamp: float
     message: WireMessageEnvelope
+    """The envelope of the message, with the same `timestamp` and the `seq` of the record. Both
+    are missing from records written by older versions."""
 
     @classmethod
-    def from_wire_message(cls, msg: WireMessage, *, timestamp: float) -> WireMessageRecord:
-        return cls(timestamp=timestamp, message=WireMessageEnvelope.from_wire_message(msg))
+    def from_wire_message(
+        cls, msg: WireMessage, *, timestamp: float, seq: int | None = None
+    ) -> WireMessageRecord:
+        return cls(
+            timestamp=timestamp,
+            message=WireMessageEnvelope.from_wire_message(msg, seq=seq, timestamp=timestamp),
+        )
+
+    @property
+    def seq(self) -> int | None:
+        return self.message.seq
 
     def to_wire_message(self) -> WireMessage:
         return self.message.to_wire_message()
@@ -49,17 +82,24 @@ def parse_wire_file_metadata(line: str) -> WireFileMetadata | None:
 
 
 def parse_wire_file_line(line: str) -> WireFileMetadata | WireMessageRecord:
-    """Parse a wire file line into metadata or a message record."""
+    """
+    Parse a wire file line into metadata or a message record, decrypting it if it is encrypted.
+
+    Raises:
+        SessionEncryptionError: If the line is encrypted and cannot be decrypted.
+    """
     metadata = parse_wire_file_metadata(line)
     if metadata is not None:
         return metadata
-    return WireMessageRecord.model_validate_json(line)
+    return WireMessageRecord.model_validate_json(decode_line(line))
 
 
 @dataclass(slots=True)
 class WireFile:
     path: Path
     protocol_version: str = WIRE_PROTOCOL_VERSION
+    _last_seq: int | None = field(default=None, init=False, repr=False)
+    """The `seq` of the last record, read from the end of the file on the first append."""
 
     def __post_init__(self) -> None:
         if self.path.exists():
@@ -103,6 +143,8 @@ class WireFile:
                         continue
                     try:
                         parsed = parse_wire_file_line(line)
+                    except SessionEncryptionError:
+                        raise
                     except Exception:
                         logger.exception(
                             "Failed to parse line in wire file {file}:", file=self.path
@@ -111,13 +153,90 @@ class WireFile:
                     if isinstance(parsed, WireFileMetadata):
                         continue
                     yield parsed
+        except SessionEncryptionError as exc:
+            raise SessionEncryptionError(f"Cannot read wire file {self.path}: {exc}") from exc
         except Exception:
             logger.exception("Failed to read wire file {file}:", file=self.path)
 
+    async def replay_into(self, wire: Wire, *, speed: float = 1.0) -> int:
+        """
+        Send the recorded messages into a live `Wire`, so that a connected UI renders them as
+        if the session were running. Pauses between messages follow the recorded timestamps
+        divided by `speed`, and are capped at `MAX_REPLAY_DELAY`.
+
+        Recorded requests are resolved when their recorded response is replayed: approval
+        requests by their `ApprovalResponse`, tool call and question requests by the
+        `ToolResult` of their tool call. Hook responses are not recorded, so hook requests are
+        allowed right away.
+
+        Returns:
+            int: The number of replayed messages.
+        """
+        if speed <= 0:
+            raise ValueError(f"Replay speed must be positive, got {speed}")
+
+        approvals: dict[str, ApprovalRequest] = {}
+        tool_calls: dict[str, ToolCallRequest | QuestionRequest] = {}
+        replayed = 0
+        previous_timestamp: float | None = None
+        async for record in self.iter_records():
+            if previous_timestamp is not None:
+                delay = min((record.timestamp - previous_timestamp) / speed, MAX_REPLAY_DELAY)
+                if delay > 0:
+                    await asyncio.sleep(delay)
+            previous_timestamp = record.timestamp
+
+            try:
+                msg = record.to_wire_message()
+            except Exception:
+                logger.exception(
+                    "Failed to deserialize wire record for replay: {file}", file=self.path
+                )
+                continue
+
+            match msg:
+                case ApprovalRequest():
+                    approvals[msg.id] = msg
+                case ApprovalRequestBatch():
+                    approvals.update((request.id, request) for request in msg.requests)
+                case ToolCallRequest():
+                    tool_calls[msg.id] = msg
+                case QuestionRequest():
+                    tool_calls[msg.tool_call_id] = msg
+                case _:
+                    pass
+            wire.soul_side.send(msg)
+            replayed += 1
+
+            match msg:
+                case HookRequest():
+                    msg.resolve("allow")
+                case ApprovalResponse():
+                    if (approval := approvals.pop(msg.request_id, None)) is not None:
+                        approval.resolve(msg.response, msg.feedback)
+                case ToolResult():
+                    match tool_calls.pop(msg.tool_call_id, None):
+                        case ToolCallRequest() as request:
+                            request.resolve(msg.return_value)
+                        case QuestionRequest() as request:
+                            request.resolve({})
+                        case None:
+                            pass
+                case _:
+                    pass
+            await asyncio.sleep(0)  # yield to the UI loop
+
+        wire.soul_side.flush()
+        return replayed
+
     async def append_message(self, msg: WireMessage, *, timestamp: float | None = None) -> None:
+        if self._last_seq is None:
+            self._last_seq = await asyncio.to_thread(_load_last_seq, self.path)
+        self._last_seq += 1
         record = WireMessageRecord.from_wire_message(
             msg,
             timestamp=time.time() if timestamp is None else timestamp,
+            seq=self._last_seq,
         )
         await self.append_record(record)
 
@@ -126,13 +245,14 @@ class WireFile:
         needs_header = not self.path.exists() or self.path.stat().st_size == 0
         async with aiofiles.open(self.path, mode="a", encoding="utf-8") as f:
             if needs_header:
+                # The header stays plain, so that the version is known without the session key
                 metadata = WireFileMetadata(protocol_version=self.protocol_version)
-                await f.write(_dump_line(metadata))
-            await f.write(_dump_line(record))
+                await f.write(_dump_line(metadata) + "\n")
+            await f.write(encode_line(_dump_line(record)) + "\n")
 
 
 def _dump_line(model: BaseModel) -> str:
-    return json.dumps(model.model_dump(mode="json"), ensure_ascii=False) + "\n"
+    return json.dumps(redact_json(model.model_dump(mode="json")), ensure_ascii=False)
 
 
 def _load_protocol_version(path: Path) -> str | None:
@@ -149,3 +269,78 @@ def _load_protocol_version(path: Path) -> str | None:
     except OSError:
         logger.exception("Failed to read wire file {file}:", file=path)
     return None
+
+
+def _load_last_seq(path: Path) -> int:
+    """
+    The `seq` of the last record. Records are appended in `seq` order, so it is read from the
+    last line, unless that record has no `seq` and the file has to be counted.
+    """
+    try:
+        line = _read_last_line(path)
+    except FileNotFoundError:
+        return 0
+    except OSError:
+        logger.exception("Failed to read wire file {file}:", file=path)
+        return 0
+    if line is None:
+        return 0
+    try:
+        data = json.loads(decode_line(line))
+    except (SessionEncryptionError, ValueError):
+        data = None
+    if isinstance(data, dict) and data.get("type") == "metadata":
+        return 0
+    message = data.get("message") if isinstance(data, dict) else None
+    seq = message.get("seq") if isinstance(message, dict) else None
+    if isinstance(seq, int):
+        return seq
+    return _count_last_seq(path)
+
+
+_TAIL_BLOCK_SIZE = 64 * 1024
+
+
+def _read_last_line(path: Path) -> str | None:
+    """The last non-empty line of a file, read backwards from its end in blocks."""
+    with path.open("rb") as f:
+        position = f.seek(0, os.SEEK_END)
+        data = b""
+        while position > 0:
+            size = min(_TAIL_BLOCK_SIZE, position)
+            position -= size
+            f.seek(position)
+            data = f.read(size) + data
+            stripped = data.rstrip()
+            if b"\n" in stripped:
+                return stripped.rsplit(b"\n", 1)[1].decode("utf-8")
+    stripped = data.strip()
+    return stripped.decode("utf-8") if stripped else None
+
+
+def _count_last_seq(path: Path) -> int:
+    last_seq = 0
+    position = 0
+    try:
+        with path.open(encoding="utf-8") as f:
+            for line in f:
+                try:
+                    data = json.loads(decode_line(line))
+                except SessionEncryptionError:
+                    # Cannot tell its `seq`, so number it by its position like a legacy record
+                    position += 1
+                    last_seq = max(last_seq, position)
+                    continue
+                except ValueError:
+                    continue
+                if not isinstance(data, dict) or data.get("type") == "metadata":
+                    continue
+                position += 1
+                message = data.get("message")
+                seq = message.get("seq") if isinstance(message, dict) else None
+                last_seq = max(last_seq, seq if isinstance(seq, int) else position)
+    except FileNotFoundError:
+        pass
+    except OSError:
+        logger.exception("Failed to read wire file {file}:", file=path)
+    return last_seq
@@ -6,17 +6,22 @@ from kosong.utils.typing import JsonType
 from pydantic import (
     BaseModel,
     ConfigDict,
+    Field,
+    RootModel,
     TypeAdapter,
     field_serializer,
     field_validator,
     model_serializer,
+    model_validator,
 )
 
-from kimi_cli.wire.serde import serialize_wire_message
+from kimi_cli.config import StreamThinking
+from kimi_cli.wire.serde import serialize_wire_message, to_legacy_shape
 from kimi_cli.wire.types import (
     ContentPart,
     Event,
     Request,
+    ToolResult,
     is_event,
     is_request,
 )
@@ -71,6 +76,12 @@ class JSONRPCErrorResponseNullableID(_MessageBase):
     error: JSONRPCErrorObject
 
 
+class JSONRPCBatchResponse(
+    RootModel[list[JSONRPCSuccessResponse | JSONRPCErrorResponse | JSONRPCErrorResponseNullableID]]
+):
+    """The responses to a batch of messages, sent together as one JSON array."""
+
+
 class ClientInfo(BaseModel):
     name: str
     version: str | None = None
@@ -89,6 +100,13 @@ class ClientCapabilities(BaseModel):
     """Whether the client can handle QuestionRequest messages."""
     supports_plan_mode: bool = False
     """Whether the client supports plan mode (EnterPlanMode / ExitPlanMode)."""
+    stream_thinking: StreamThinking | None = None
+    """How much of the model's reasoning to stream to the client. None keeps the configured
+    `stream_thinking` mode."""
+    supports_approval_batch: bool = False
+    """Whether the client can handle ApprovalRequestBatch messages."""
+    approval_batch_window_ms: int = Field(default=150, ge=0)
+    """How long to collect approval requests before sending them as one batch."""
 
 
 class WireHookSubscription(BaseModel):
@@ -111,6 +129,9 @@ class JSONRPCInitializeMessage(_MessageBase):
         external_tools: list[ExternalTool] | None = None
         hooks: list[WireHookSubscription] | None = None
         capabilities: ClientCapabilities | None = None
+        last_seq: int | None = Field(default=None, ge=0)
+        """The `seq` of the last message received before reconnecting. The messages sent
+        after it are sent again."""
 
     method: Literal["initialize"] = "initialize"
     id: str
@@ -119,7 +140,19 @@ class JSONRPCInitializeMessage(_MessageBase):
 
 class JSONRPCPromptMessage(_MessageBase):
     class Params(BaseModel):
-        user_input: str | list[ContentPart]
+        user_input: str | list[ContentPart] | None = None
+        """The input of a new turn. Required unless `tool_results` is given."""
+        tool_results: list[ToolResult] | None = None
+        """Results of external tool calls the active turn is waiting for. Instead of starting a
+        new turn, the prompt resolves their `ToolCallRequest`s at once and the turn continues."""
+        stream_thinking: StreamThinking | None = None
+        """Overrides the session's `stream_thinking` mode for this prompt only."""
+
+        @model_validator(mode="after")
+        def _check_input(self) -> JSONRPCPromptMessage.Params:
+            if (self.user_input is None) == (self.tool_results is None):
+                raise ValueError("Exactly one of `user_input` and `tool_results` must be given")
+            return self
 
     method: Literal["prompt"] = "prompt"
     id: str
@@ -131,9 +164,14 @@ class JSONRPCPromptMessage(_MessageBase):
 
 
 class JSONRPCReplayMessage(_MessageBase):
+    class Params(BaseModel):
+        speed: float | None = Field(default=None, gt=0)
+        """If set, the recorded messages are paced by their timestamps, sped up by this factor.
+        Otherwise they are sent as fast as possible."""
+
     method: Literal["replay"] = "replay"
     id: str
-    params: JsonType | None = None
+    params: Params | None = None
 
 
 class JSONRPCSteerMessage(_MessageBase):
@@ -174,10 +212,18 @@ class JSONRPCCancelMessage(_MessageBase):
 class JSONRPCEventMessage(_MessageBase):
     method: Literal["event"] = "event"
     params: Event
+    seq: int | None = Field(default=None, exclude=True)
+    """Sent as the `seq` of the params, set by the Wire server when the message is sent."""
+    timestamp: float | None = Field(default=None, exclude=True)
+    """Sent as the `timestamp` of the params, set by the Wire server when the message is sent,
+    or to the recorded time of replayed messages."""
+    legacy: bool = Field(default=False, exclude=True)
+    """Whether to send the params in their shape of the legacy protocol version."""
 
     @field_serializer("params")
     def _serialize_params(self, params: Event) -> dict[str, JsonType]:
-        return serialize_wire_message(params)
+        data = serialize_wire_message(params, seq=self.seq, timestamp=self.timestamp)
+        return to_legacy_shape(data) if self.legacy else data
 
     @field_validator("params", mode="before")
     @classmethod
@@ -191,10 +237,18 @@ class JSONRPCRequestMessage(_MessageBase):
     method: Literal["request"] = "request"
     id: str
     params: Request
+    seq: int | None = Field(default=None, exclude=True)
+    """Sent as the `seq` of the params, set by the Wire server when the message is sent."""
+    timestamp: float | None = Field(default=None, exclude=True)
+    """Sent as the `timestamp` of the params, set by the Wire server when the message is sent,
+    or to the recorded time of replayed messages."""
+    legacy: bool = Field(default=False, exclude=True)
+    """Whether to send the params in their shape of the legacy protocol version."""
 
     @field_serializer("params")
     def _serialize_params(self, params: Request) -> dict[str, JsonType]:
-        return serialize_wire_message(params)
+        data = serialize_wire_message(params, seq=self.seq, timestamp=self.timestamp)
+        return to_legacy_shape(data) if self.legacy else data
 
     @field_validator("params", mode="before")
     @classmethod
@@ -204,6 +258,17 @@ class JSONRPCRequestMessage(_MessageBase):
         raise NotImplementedError("Request message deserialization is not implemented.")
 
 
+class JSONRPCShutdownMessage(_MessageBase):
+    """The last message of a Wire server stopped by a signal, sent before it exits."""
+
+    class Params(BaseModel):
+        signal: str
+        """The name of the signal, e.g. `SIGTERM`."""
+
+    method: Literal["shutdown"] = "shutdown"
+    params: Params
+
+
 type JSONRPCInMessage = (
     JSONRPCSuccessResponse
     | JSONRPCErrorResponse
@@ -223,8 +288,9 @@ type JSONRPCOutMessage = (
     | JSONRPCErrorResponseNullableID
     | JSONRPCEventMessage
     | JSONRPCRequestMessage
+    | JSONRPCShutdownMessage
 )
-JSONRPC_OUT_METHODS = {"event", "request"}
+JSONRPC_OUT_METHODS = {"event", "request", "shutdown"}
 
 
 class ErrorCodes:
@@ -259,5 +325,9 @@ class Statuses:
     """The agent run was cancelled by the user."""
     MAX_STEPS_REACHED = "max_steps_reached"
     """The agent run reached the maximum number of steps."""
+    BUDGET_EXCEEDED = "budget_exceeded"
+    """The agent run exceeded the token or cost budget of the turn."""
     STEERED = "steered"
     """A steer message was queued for injection into the active turn."""
+    RESUMED = "resumed"
+    """Pending external tool calls were resolved and the active turn continues."""
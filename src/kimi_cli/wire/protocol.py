@@ -1,2 +1,28 @@
-WIRE_PROTOCOL_VERSION: str = "1.10"
+WIRE_PROTOCOL_VERSION: str = "1.11"
 WIRE_PROTOCOL_LEGACY_VERSION: str = "1.1"
+
+
+def negotiate_protocol_version(client_version: str) -> str | None:
+    """
+    Pick the protocol version to speak with a client that supports up to `client_version`.
+
+    A client that only supports `WIRE_PROTOCOL_LEGACY_VERSION` gets legacy-shaped messages. The
+    versions after it only added to the protocol, so later clients of the same major version get
+    `WIRE_PROTOCOL_VERSION`. Returns None if there is no common version.
+    """
+    client = _parse_version(client_version)
+    current = _parse_version(WIRE_PROTOCOL_VERSION)
+    legacy = _parse_version(WIRE_PROTOCOL_LEGACY_VERSION)
+    assert current is not None and legacy is not None
+    if client is None or client[0] != current[0] or client < legacy:
+        return None
+    if client == legacy:
+        return WIRE_PROTOCOL_LEGACY_VERSION
+    return WIRE_PROTOCOL_VERSION
+
+
+def _parse_version(version: str) -> tuple[int, int] | None:
+    major, dot, minor = version.strip().partition(".")
+    if not dot or not major.isdigit() or not minor.isdigit():
+        return None
+    return int(major), int(minor)
@@ -1,17 +1,19 @@
 from __future__ import annotations
 
-from typing import Any
+from typing import Any, cast
 
 from kosong.utils.typing import JsonType
 
-from kimi_cli.wire.types import WireMessage, WireMessageEnvelope
+from kimi_cli.wire.types import SubagentEvent, WireMessage, WireMessageEnvelope
 
 
-def serialize_wire_message(msg: WireMessage) -> dict[str, JsonType]:
+def serialize_wire_message(
+    msg: WireMessage, *, seq: int | None = None, timestamp: float | None = None
+) -> dict[str, JsonType]:
     """
     Convert a `WireMessage` into a jsonifiable dict.
     """
-    envelope = WireMessageEnvelope.from_wire_message(msg)
+    envelope = WireMessageEnvelope.from_wire_message(msg, seq=seq, timestamp=timestamp)
     return envelope.model_dump(mode="json")
 
 
@@ -24,3 +26,58 @@ def deserialize_wire_message(data: dict[str, JsonType] | Any) -> WireMessage:
     """
     envelope = WireMessageEnvelope.model_validate(data)
     return envelope.to_wire_message()
+
+
+LEGACY_UNSUPPORTED_EVENTS: frozenset[str] = frozenset(
+    {
+        "TurnEnd",
+        "SteerInput",
+        "StepRetry",
+        "CompactionProgress",
+        "HookTriggered",
+        "HookResolved",
+        "ToolCallPreview",
+        "SubagentEnd",
+        "PlanDisplay",
+        "BtwBegin",
+        "BtwEnd",
+    }
+)
+"""Events added after `WIRE_PROTOCOL_LEGACY_VERSION`, which are not sent to legacy clients."""
+
+_LEGACY_UNSUPPORTED_FIELDS: dict[str, tuple[str, ...]] = {
+    "StepBegin": ("timestamp",),
+    "CompactionEnd": ("marker_id", "n_summarized", "before_tokens", "after_tokens"),
+    "StatusUpdate": ("phase", "attempt", "elapsed_ms", "rate_limit", "step_metrics"),
+    "SubagentEvent": ("agent_id", "subagent_type", "depth"),
+    "ApprovalRequest": (
+        "source_kind",
+        "source_id",
+        "agent_id",
+        "subagent_type",
+        "source_description",
+    ),
+}
+
+
+def exists_in_legacy_version(msg: WireMessage) -> bool:
+    """Check whether a message, or the event wrapped in it, exists in the legacy version."""
+    if isinstance(msg, SubagentEvent):
+        return exists_in_legacy_version(msg.event)
+    return type(msg).__name__ not in LEGACY_UNSUPPORTED_EVENTS
+
+
+def to_legacy_shape(data: dict[str, JsonType]) -> dict[str, JsonType]:
+    """
+    Convert a message serialized by `serialize_wire_message` into its legacy shape: without
+    `seq` and `timestamp`, without the payload fields added since, and with `SubagentEvent`'s
+    `parent_tool_call_id` under its old name `task_tool_call_id`.
+    """
+    msg_type = cast(str, data["type"])
+    payload = dict(cast(dict[str, JsonType], data["payload"]))
+    for name in _LEGACY_UNSUPPORTED_FIELDS.get(msg_type, ()):
+        payload.pop(name, None)
+    if msg_type == "SubagentEvent":
+        payload["task_tool_call_id"] = payload.pop("parent_tool_call_id", None)
+        payload["event"] = to_legacy_shape(cast(dict[str, JsonType], payload["event"]))
+    return {"type": msg_type, "payload": payload}
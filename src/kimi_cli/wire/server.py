@@ -3,25 +3,56 @@ from __future__ import annotations
 import asyncio
 import contextlib
 import json
+import signal
+import time
+import uuid
+from collections import deque
+from collections.abc import Awaitable, Callable
 from typing import Any, Literal, cast
 
 import acp  # type: ignore[reportMissingTypeStubs]
 import pydantic
-from kosong.chat_provider import APIStatusError, ChatProviderError
+from kosong.chat_provider import (
+    APIConnectionError,
+    APIEmptyResponseError,
+    APIStatusError,
+    APITimeoutError,
+    ChatProviderError,
+)
 from kosong.tooling import ToolError, ToolResult
 from kosong.utils.typing import JsonType
 
 from kimi_cli.approval_runtime import ApprovalRuntime
 from kimi_cli.constant import USER_AGENT
-from kimi_cli.soul import LLMNotSet, LLMNotSupported, MaxStepsReached, RunCancelled, Soul, run_soul
+from kimi_cli.soul import (
+    BudgetExceeded,
+    LLMNotSet,
+    LLMNotSupported,
+    MaxMessageSizeExceeded,
+    MaxStepsReached,
+    RunCancelled,
+    Soul,
+    run_soul,
+)
 from kimi_cli.soul.kimisoul import KimiSoul
 from kimi_cli.soul.toolset import KimiToolset, WireExternalTool
 from kimi_cli.utils.aioqueue import Queue, QueueShutDown
+from kimi_cli.utils.envvar import get_env_int
 from kimi_cli.utils.logging import logger
-from kimi_cli.utils.signals import install_sigint_handler
+from kimi_cli.utils.signals import ShutdownSignals, install_shutdown_handler
 from kimi_cli.wire import Wire
+from kimi_cli.wire.file import MAX_REPLAY_DELAY
+from kimi_cli.wire.protocol import (
+    WIRE_PROTOCOL_LEGACY_VERSION,
+    WIRE_PROTOCOL_VERSION,
+    negotiate_protocol_version,
+)
+from kimi_cli.wire.serde import exists_in_legacy_version
 from kimi_cli.wire.types import (
+    ApprovalBatchResponse,
+    ApprovalDecision,
     ApprovalRequest,
+    ApprovalRequestBatch,
     ApprovalResponse,
     HookRequest,
     HookResponse,
@@ -38,6 +69,7 @@ from kimi_cli.wire.types import (
 from .jsonrpc import (
     ClientInfo,
     ErrorCodes,
+    JSONRPCBatchResponse,
     JSONRPCCancelMessage,
     JSONRPCErrorObject,
     JSONRPCErrorResponse,
@@ -52,18 +84,27 @@ from .jsonrpc import (
     JSONRPCReplayMessage,
     JSONRPCRequestMessage,
     JSONRPCSetPlanModeMessage,
+    JSONRPCShutdownMessage,
     JSONRPCSteerMessage,
     JSONRPCSuccessResponse,
     Statuses,
 )
 
+type _Response = JSONRPCSuccessResponse | JSONRPCErrorResponse | JSONRPCErrorResponseNullableID
+type _Respond = Callable[[_Response], Awaitable[None]]
+
 # Maximum buffer size for the asyncio StreamReader used for stdio.
 # Passed as the `limit` argument to `acp.stdio_streams`, this caps how much
 # data can be buffered when reading from stdin (e.g., large tool or model
 # outputs sent over JSON-RPC). A 100MB limit is large enough for typical
 # interactive use while still protecting the process from unbounded memory
 # growth or buffer-overrun errors when peers send unexpectedly large payloads.
+# A line longer than the limit is answered with a parse error and skipped.
 STDIO_BUFFER_LIMIT = 100 * 1024 * 1024
+STDIO_BUFFER_LIMIT_ENV = "KIMI_CLI_WIRE_BUFFER_LIMIT"
+
+RESUME_BUFFER_SIZE = 10_000
+"""Maximum number of messages of the current turn kept to be sent again after a reconnect."""
 
 
 def _is_oauth_session(runtime: Any) -> bool:
@@ -79,6 +120,27 @@ def _is_oauth_session(runtime: Any) -> bool:
     return getattr(provider_config, "oauth", None) is not None
 
 
+def _provider_error_data(error: ChatProviderError) -> dict[str, JsonType]:
+    """The `data` of a `CHAT_PROVIDER_ERROR` response: the kind of error, and its details."""
+    match error:
+        case APIStatusError():
+            return {
+                "kind": "status",
+                "status_code": error.status_code,
+                "request_id": error.request_id,
+            }
+        case APITimeoutError():
+            return {"kind": "timeout"}
+        case APIConnectionError():
+            return {"kind": "connection"}
+        case APIEmptyResponseError():
+            return {"kind": "empty_response"}
+        case MaxMessageSizeExceeded():
+            return {"kind": "message_too_large", "limit": error.limit}
+        case _:
+            return {"kind": "other"}
+
+
 class WireServer:
     def __init__(self, soul: Soul):
         self._reader: asyncio.StreamReader | None = None
@@ -86,7 +148,16 @@ class WireServer:
 
         # outward
         self._write_task: asyncio.Task[None] | None = None
-        self._write_queue: Queue[JSONRPCOutMessage] = Queue()
+        self._write_queue: Queue[JSONRPCOutMessage | JSONRPCBatchResponse] = Queue()
+        self._seq: int = 0
+        """The `seq` of the last event or request sent."""
+        self._resume_buffer: deque[JSONRPCEventMessage | JSONRPCRequestMessage] = deque(
+            maxlen=RESUME_BUFFER_SIZE
+        )
+        """Events and requests sent since the current or last turn began, for resuming."""
+        self._resume_floor: int = 0
+        """Messages up to this `seq` are no longer in the resume buffer."""
+        self._replaying: bool = False
 
         # inward
         self._dispatch_tasks: set[asyncio.Task[None]] = set()
@@ -100,7 +171,20 @@ class WireServer:
         """Whether the Wire client supports QuestionRequest."""
         self._client_supports_plan_mode: bool = False
         """Whether the Wire client supports plan mode."""
+        self._client_supports_approval_batch: bool = False
+        """Whether the Wire client supports ApprovalRequestBatch."""
+        self._approval_batch_window: float = 0.15
+        """Seconds to collect approval requests for before sending them as one batch."""
+        self._approval_buffer: list[ApprovalRequest] = []
+        """Approval requests waiting to be sent with the next batch."""
+        self._approval_flush_task: asyncio.Task[None] | None = None
         self._initialized: bool = False
+        self._protocol_version: str = WIRE_PROTOCOL_VERSION
+        """The protocol version negotiated by `initialize`."""
+        self._shutdown_signals = ShutdownSignals()
+        """The signals received, to force-quit on a second one."""
+        self._last_request_at: float = time.monotonic()
+        """When the last request was received or the last turn ended, for the idle timeout."""
         self._root_hub_queue: Queue[Any] | None = None
         self._root_hub_task: asyncio.Task[None] | None = None
 
@@ -113,17 +197,32 @@ class WireServer:
     async def serve(self) -> None:
         logger.info("Starting Wire server on stdio")
 
-        self._reader, self._writer = await acp.stdio_streams(limit=STDIO_BUFFER_LIMIT)
+        limit = get_env_int(STDIO_BUFFER_LIMIT_ENV, STDIO_BUFFER_LIMIT)
+        if limit <= 0:
+            logger.warning(
+                "Ignoring invalid {env}={limit}", env=STDIO_BUFFER_LIMIT_ENV, limit=limit
+            )
+            limit = STDIO_BUFFER_LIMIT
+        self._reader, self._writer = await acp.stdio_streams(limit=limit)
         self._write_task = asyncio.create_task(self._write_loop())
         if isinstance(self._soul, KimiSoul) and self._soul.runtime.root_wire_hub is not None:
             self._root_hub_queue = self._soul.runtime.root_wire_hub.subscribe()
             self._root_hub_task = asyncio.create_task(self._root_hub_loop())
         stop_event = asyncio.Event()
         loop = asyncio.get_running_loop()
-        remove_sigint = install_sigint_handler(loop, stop_event.set)
+        remove_signal_handler = install_shutdown_handler(
+            loop, lambda signum: self._interrupt(stop_event, signum)
+        )
         read_task = asyncio.create_task(self._read_loop())
         stop_task = asyncio.create_task(stop_event.wait())
         tasks: set[asyncio.Task[Any]] = {read_task, stop_task}
+        idle_timeout = (
+            self._soul.runtime.config.session.idle_timeout_secs
+            if isinstance(self._soul, KimiSoul)
+            else None
+        )
+        if idle_timeout is not None:
+            tasks.add(asyncio.create_task(self._wait_until_idle(idle_timeout)))
         pending = tasks
         try:
             done, pending = await asyncio.wait(
@@ -131,7 +230,7 @@ class WireServer:
                 return_when=asyncio.FIRST_COMPLETED,
             )
             if stop_event.is_set():
-                logger.info("Wire server interrupted, shutting down")
+                logger.info("Wire server stopped by a signal, shutting down")
                 if self._cancel_event is not None:
                     self._cancel_event.set()
                 if not read_task.done():
@@ -140,17 +239,46 @@ class WireServer:
                         await read_task
             elif read_task in done:
                 read_task.result()
+            else:
+                logger.info("Wire server idle for {timeout}s, shutting down", timeout=idle_timeout)
         except KeyboardInterrupt:
             logger.info("Wire server interrupted, shutting down")
             if self._cancel_event is not None:
                 self._cancel_event.set()
         finally:
-            remove_sigint()
-            for task in pending:
-                task.cancel()
-                with contextlib.suppress(asyncio.CancelledError):
-                    await task
-            await self._shutdown()
+            # Signals stay handled until the cancelled turn has finished and flushed the wire file
+            try:
+                for task in pending:
+                    task.cancel()
+                    with contextlib.suppress(asyncio.CancelledError):
+                        await task
+                await self._shutdown(signum=self._shutdown_signals.signum)
+            finally:
+                remove_signal_handler()
+
+    @property
+    def exit_code(self) -> int | None:
+        """The exit code for the signal that stopped the server, or None if none did."""
+        return self._shutdown_signals.exit_code
+
+    async def _wait_until_idle(self, timeout: float) -> None:
+        """Return once no request has been received and no turn has run for `timeout` seconds."""
+        while True:
+            if self._is_streaming:
+                await asyncio.sleep(timeout)
+                continue
+            remaining = self._last_request_at + timeout - time.monotonic()
+            if remaining <= 0:
+                return
+            await asyncio.sleep(remaining)
+
+    def _interrupt(self, stop_event: asyncio.Event, signum: signal.Signals) -> None:
+        """
+        Stop the server gracefully on Ctrl-C or SIGTERM, or force-quit on a second signal that
+        comes quickly.
+        """
+        self._shutdown_signals.receive(signum)
+        stop_event.set()
 
     async def _root_hub_loop(self) -> None:
         assert self._root_hub_queue is not None
@@ -165,7 +293,7 @@ class WireServer:
                 if isinstance(msg, ApprovalRequest):
                     await self._request_approval(msg)
                 elif isinstance(msg, ApprovalResponse):
-                    self._pending_requests.pop(msg.request_id, None)
+                    self._discard_pending_approval(msg)
                     await self._send_msg(JSONRPCEventMessage(method="event", params=msg))
                 elif is_event(msg):
                     await self._send_msg(JSONRPCEventMessage(method="event", params=msg))
@@ -194,10 +322,23 @@ class WireServer:
         assert self._reader is not None
 
         while True:
-            raw_line = await self._reader.readline()
+            raw_line = await self._readline()
+            if raw_line is None:
+                logger.error("Skipped a JSON-RPC message longer than the stdio buffer limit")
+                await self._send_msg(
+                    JSONRPCErrorResponseNullableID(
+                        id=None,
+                        error=JSONRPCErrorObject(
+                            code=ErrorCodes.PARSE_ERROR,
+                            message="Message exceeds the stdio buffer limit",
+                        ),
+                    )
+                )
+                continue
             if not raw_line:
                 logger.info("stdin closed, Wire server exiting")
                 break
+            self._last_request_at = time.monotonic()
             line = raw_line.decode("utf-8", errors="replace").strip()
 
             try:
@@ -215,86 +356,170 @@ class WireServer:
                 )
                 continue
 
+            if isinstance(msg_json, list):
+                await self._receive_batch(cast(list[Any], msg_json))
+            else:
+                await self._receive(msg_json, respond=self._send_msg)
+
+    async def _readline(self) -> bytes | None:
+        """
+        Read the next line from stdin, or an empty line at EOF. A line longer than the buffer
+        limit is skipped up to its line break, and None is returned for it.
+        """
+        assert self._reader is not None
+
+        try:
+            return await self._reader.readuntil(b"\n")
+        except asyncio.IncompleteReadError as e:
+            return e.partial
+        except asyncio.LimitOverrunError as e:
+            consumed = e.consumed
+        # Drop the line a buffer at a time, instead of buffering it whole
+        while True:
+            await self._reader.readexactly(consumed)
             try:
-                generic_msg = JSONRPCMessage.model_validate(msg_json)
+                await self._reader.readuntil(b"\n")
+                return None
+            except asyncio.IncompleteReadError:
+                return None
+            except asyncio.LimitOverrunError as e:
+                consumed = e.consumed
+
+    async def _receive(self, msg_json: Any, *, respond: _Respond) -> asyncio.Task[None] | None:
+        """
+        Validate an inbound message and start dispatching it.
+
+        Args:
+            respond: Where to send the response to the message, including validation errors.
+
+        Returns:
+            The dispatching task, or None if the message is invalid.
+        """
+        try:
+            generic_msg = JSONRPCMessage.model_validate(msg_json)
+        except pydantic.ValidationError as e:
+            logger.error("Invalid JSON-RPC message: {error}", error=e)
+            await respond(
+                JSONRPCErrorResponseNullableID(
+                    id=None,
+                    error=JSONRPCErrorObject(
+                        code=ErrorCodes.INVALID_REQUEST,
+                        message="Invalid request",
+                    ),
+                )
+            )
+            return None
+
+        if generic_msg.is_response():
+            # for responses, we skip the method check
+            try:
+                msg = JSONRPCInMessageAdapter.validate_python(msg_json)
             except pydantic.ValidationError as e:
-                logger.error("Invalid JSON-RPC message: {error}", error=e)
-                await self._send_msg(
+                logger.error("Invalid JSON-RPC response: {error}", error=e)
+                await respond(
                     JSONRPCErrorResponseNullableID(
                         id=None,
                         error=JSONRPCErrorObject(
                             code=ErrorCodes.INVALID_REQUEST,
-                            message="Invalid request",
+                            message="Invalid response",
                         ),
                     )
                 )
-                continue
-
-            if generic_msg.is_response():
-                # for responses, we skip the method check
-                try:
-                    msg = JSONRPCInMessageAdapter.validate_python(msg_json)
-                except pydantic.ValidationError as e:
-                    logger.error("Invalid JSON-RPC response: {error}", error=e)
-                    await self._send_msg(
-                        JSONRPCErrorResponseNullableID(
-                            id=None,
-                            error=JSONRPCErrorObject(
-                                code=ErrorCodes.INVALID_REQUEST,
-                                message="Invalid response",
-                            ),
-                        )
-                    )
-                    continue  # ignore invalid json-rpc responses
+                return None  # ignore invalid json-rpc responses
 
-                if not isinstance(msg, (JSONRPCSuccessResponse, JSONRPCErrorResponse)):
-                    logger.error(
-                        "Invalid JSON-RPC response message: {msg}",
-                        msg=msg_json,
-                    )
-                    continue  # ignore invalid response messages
-
-                task = asyncio.create_task(self._dispatch_msg(msg))
-                task.add_done_callback(self._dispatch_tasks.discard)
-                self._dispatch_tasks.add(task)
-                continue
-
-            if not generic_msg.method_is_inbound():
+            if not isinstance(msg, (JSONRPCSuccessResponse, JSONRPCErrorResponse)):
                 logger.error(
-                    "Unexpected JSON-RPC method received: {method}",
-                    method=generic_msg.method,
+                    "Invalid JSON-RPC response message: {msg}",
+                    msg=msg_json,
                 )
-                if generic_msg.id is not None:
-                    resp = JSONRPCErrorResponse(
-                        id=generic_msg.id,
-                        error=JSONRPCErrorObject(
-                            code=ErrorCodes.METHOD_NOT_FOUND,
-                            message=f"Unexpected method received: {generic_msg.method}",
-                        ),
-                    )
-                    await self._send_msg(resp)
-                continue  # ignore unexpected outbound methods
+                return None  # ignore invalid response messages
 
-            try:
-                msg = JSONRPCInMessageAdapter.validate_python(msg_json)
-            except pydantic.ValidationError as e:
-                logger.error("Invalid JSON-RPC inbound message: {error}", error=e)
-                if generic_msg.id is not None:
-                    resp = JSONRPCErrorResponse(
-                        id=generic_msg.id,
-                        error=JSONRPCErrorObject(
-                            code=ErrorCodes.INVALID_PARAMS,
-                            message=f"Invalid parameters for method `{generic_msg.method}`",
-                        ),
-                    )
-                    await self._send_msg(resp)
-                continue  # ignore invalid inbound messages
+            return self._start_dispatch(msg, respond=respond)
 
-            task = asyncio.create_task(self._dispatch_msg(msg))
-            task.add_done_callback(self._dispatch_tasks.discard)
-            self._dispatch_tasks.add(task)
+        if not generic_msg.method_is_inbound():
+            logger.error(
+                "Unexpected JSON-RPC method received: {method}",
+                method=generic_msg.method,
+            )
+            if generic_msg.id is not None:
+                resp = JSONRPCErrorResponse(
+                    id=generic_msg.id,
+                    error=JSONRPCErrorObject(
+                        code=ErrorCodes.METHOD_NOT_FOUND,
+                        message=f"Unexpected method received: {generic_msg.method}",
+                    ),
+                )
+                await respond(resp)
+            return None  # ignore unexpected outbound methods
 
-    async def _shutdown(self) -> None:
+        try:
+            msg = JSONRPCInMessageAdapter.validate_python(msg_json)
+        except pydantic.ValidationError as e:
+            logger.error("Invalid JSON-RPC inbound message: {error}", error=e)
+            if generic_msg.id is not None:
+                resp = JSONRPCErrorResponse(
+                    id=generic_msg.id,
+                    error=JSONRPCErrorObject(
+                        code=ErrorCodes.INVALID_PARAMS,
+                        message=f"Invalid parameters for method `{generic_msg.method}`",
+                    ),
+                )
+                await respond(resp)
+            return None  # ignore invalid inbound messages
+
+        return self._start_dispatch(msg, respond=respond)
+
+    async def _receive_batch(self, batch: list[Any]) -> None:
+        """
+        Start dispatching each message of a JSON-RPC batch, concurrently like single messages.
+        Their responses are sent together as one array, in the order they finished, once all of
+        them are handled; notifications and responses from the client get none, so a batch of
+        only those gets no reply at all.
+        """
+        if not batch:
+            logger.error("Empty JSON-RPC batch received")
+            await self._send_msg(
+                JSONRPCErrorResponseNullableID(
+                    id=None,
+                    error=JSONRPCErrorObject(
+                        code=ErrorCodes.INVALID_REQUEST,
+                        message="Invalid request: empty batch",
+                    ),
+                )
+            )
+            return
+
+        responses: list[_Response] = []
+
+        async def _collect(resp: _Response) -> None:
+            responses.append(resp)
+
+        tasks: list[asyncio.Task[None]] = []
+        for msg_json in batch:
+            task = await self._receive(msg_json, respond=_collect)
+            if task is not None:
+                tasks.append(task)
+
+        async def _respond_when_done() -> None:
+            await asyncio.gather(*tasks, return_exceptions=True)
+            if responses:
+                await self._send_msg(JSONRPCBatchResponse(responses))
+
+        task = asyncio.create_task(_respond_when_done())
+        task.add_done_callback(self._dispatch_tasks.discard)
+        self._dispatch_tasks.add(task)
+
+    def _start_dispatch(self, msg: JSONRPCInMessage, *, respond: _Respond) -> asyncio.Task[None]:
+        task = asyncio.create_task(self._dispatch_msg(msg, respond=respond))
+        task.add_done_callback(self._dispatch_tasks.discard)
+        self._dispatch_tasks.add(task)
+        return task
+
+    async def _shutdown(self, *, signum: signal.Signals | None = None) -> None:
+        if self._approval_flush_task is not None:
+            self._approval_flush_task.cancel()
+            self._approval_flush_task = None
+        self._approval_buffer.clear()
         for request in self._pending_requests.values():
             if request.resolved:
                 continue
@@ -304,6 +529,12 @@ class WireServer:
                         request.resolve("reject")
                         if self._approval_runtime is not None:
                             self._approval_runtime.resolve(request.id, "reject")
+                case ApprovalRequestBatch():
+                    for approval in request.requests:
+                        if approval.source_kind == "foreground_turn" and not approval.resolved:
+                            approval.resolve("reject")
+                            if self._approval_runtime is not None:
+                                self._approval_runtime.resolve(approval.id, "reject")
                 case ToolCallRequest():
                     request.resolve(
                         ToolError(
@@ -321,6 +552,16 @@ class WireServer:
             self._cancel_event.set()
             self._cancel_event = None
 
+        if (
+            signum is not None
+            and self._initialized
+            and self._protocol_version != WIRE_PROTOCOL_LEGACY_VERSION
+        ):
+            # Tell the client why the server goes away, as its last message; legacy clients do
+            # not know the `shutdown` method
+            await self._send_msg(
+                JSONRPCShutdownMessage(params=JSONRPCShutdownMessage.Params(signal=signum.name))
+            )
         self._write_queue.shutdown()
         if self._write_task is not None:
             with contextlib.suppress(asyncio.CancelledError):
@@ -351,7 +592,7 @@ class WireServer:
         self._reader = None
         self._initialized = False
 
-    async def _dispatch_msg(self, msg: JSONRPCInMessage) -> None:
+    async def _dispatch_msg(self, msg: JSONRPCInMessage, *, respond: _Respond) -> None:
         resp: JSONRPCSuccessResponse | JSONRPCErrorResponse | None = None
         try:
             match msg:
@@ -371,12 +612,32 @@ class WireServer:
                     await self._handle_response(msg)
 
             if resp is not None:
-                await self._send_msg(resp)
+                await respond(resp)
         except Exception:
             logger.exception("Unexpected error dispatching JSONRPC message:")
             raise
 
-    async def _send_msg(self, msg: JSONRPCOutMessage) -> None:
+    async def _send_msg(self, msg: JSONRPCOutMessage | JSONRPCBatchResponse) -> None:
+        if (
+            isinstance(msg, JSONRPCEventMessage | JSONRPCRequestMessage)
+            and self._protocol_version == WIRE_PROTOCOL_LEGACY_VERSION
+        ):
+            # Legacy clients get no `seq`, so they cannot resume and need no resume buffer
+            if not exists_in_legacy_version(msg.params):
+                return
+            msg.legacy = True
+        elif isinstance(msg, JSONRPCEventMessage | JSONRPCRequestMessage) and msg.seq is None:
+            self._seq += 1
+            msg.seq = self._seq
+            if msg.timestamp is None:
+                msg.timestamp = time.time()
+            if self._replaying:
+                # History sent by `replay` is in the wire file, not in the resume buffer
+                self._resume_floor = self._seq
+            else:
+                if len(self._resume_buffer) == self._resume_buffer.maxlen:
+                    self._resume_floor = self._resume_buffer[0].seq or self._resume_floor
+                self._resume_buffer.append(msg)
         try:
             await self._write_queue.put(msg)
         except QueueShutDown:
@@ -389,7 +650,8 @@ class WireServer:
     async def _handle_initialize(
         self, msg: JSONRPCInitializeMessage
     ) -> JSONRPCSuccessResponse | JSONRPCErrorResponse:
-        if self._is_streaming:
+        # A client reconnecting during a turn resumes it; other clients wait for it to end
+        if self._is_streaming and msg.params.last_seq is None:
             return JSONRPCErrorResponse(
                 id=msg.id,
                 error=JSONRPCErrorObject(
@@ -398,6 +660,22 @@ class WireServer:
                 ),
             )
 
+        protocol_version = negotiate_protocol_version(msg.params.protocol_version)
+        if protocol_version is None:
+            return JSONRPCErrorResponse(
+                id=msg.id,
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.INVALID_PARAMS,
+                    message=(
+                        f"Unsupported protocol version {msg.params.protocol_version}; "
+                        f"supported versions are {WIRE_PROTOCOL_LEGACY_VERSION} "
+                        f"to {WIRE_PROTOCOL_VERSION}"
+                    ),
+                    data={"supported": [WIRE_PROTOCOL_LEGACY_VERSION, WIRE_PROTOCOL_VERSION]},
+                ),
+            )
+        self._protocol_version = protocol_version
+
         accepted: list[str] = []
         rejected: list[dict[str, str]] = []
         toolset = None
@@ -433,7 +711,6 @@ class WireServer:
         from kimi_cli.hooks.config import HOOK_EVENT_TYPES
         from kimi_cli.hooks.engine import WireHookHandle, WireHookSubscription
         from kimi_cli.soul import wire_send
-        from kimi_cli.wire.protocol import WIRE_PROTOCOL_VERSION
         from kimi_cli.wire.types import HookResolved, HookTriggered
 
         # Hook engine setup — register wire subscriptions and callbacks
@@ -508,7 +785,7 @@ class WireServer:
         )
 
         result: dict[str, JsonType] = {
-            "protocol_version": WIRE_PROTOCOL_VERSION,
+            "protocol_version": protocol_version,
             "server": cast(JsonType, {"name": NAME, "version": VERSION}),
             "slash_commands": cast(JsonType, slash_commands),
         }
@@ -530,12 +807,27 @@ class WireServer:
         if msg.params.capabilities is not None:
             self._client_supports_question = msg.params.capabilities.supports_question
             self._client_supports_plan_mode = msg.params.capabilities.supports_plan_mode
+            self._client_supports_approval_batch = msg.params.capabilities.supports_approval_batch
+            self._approval_batch_window = msg.params.capabilities.approval_batch_window_ms / 1000
+            if msg.params.capabilities.stream_thinking is not None and isinstance(
+                self._soul, KimiSoul
+            ):
+                self._soul.runtime.stream_thinking = msg.params.capabilities.stream_thinking
 
         if toolset is not None:
             self._sync_ask_user_tool_visibility(toolset)
             self._sync_plan_mode_tool_visibility(toolset)
+            result["tools"] = cast(
+                JsonType,
+                [
+                    schema.model_dump(mode="json", exclude_none=True)
+                    for schema in toolset.tool_schemas()
+                ],
+            )
 
         self._initialized = True
+        if msg.params.last_seq is not None:
+            result["resume"] = await self._resume(msg.params.last_seq)
         if self._approval_runtime is not None:
             for request in self._approval_runtime.list_pending():
                 await self._request_approval(
@@ -555,7 +847,7 @@ class WireServer:
 
         result["capabilities"] = cast(
             JsonType,
-            {"supports_question": True},
+            {"supports_question": True, "supports_approval_batch": True},
         )
 
         return JSONRPCSuccessResponse(
@@ -563,6 +855,29 @@ class WireServer:
             result=result,
         )
 
+    async def _resume(self, last_seq: int) -> JsonType:
+        """
+        Send again the messages a reconnecting client missed after `last_seq`, and the requests
+        still waiting for its response. Pending approvals are sent again by `initialize`.
+        """
+        replayed = 0
+        for sent in list(self._resume_buffer):
+            assert sent.seq is not None
+            if isinstance(sent, JSONRPCRequestMessage):
+                if isinstance(sent.params, ApprovalRequest | ApprovalRequestBatch):
+                    continue
+                if self._pending_requests.get(sent.id) is not sent.params:
+                    continue
+            elif sent.seq <= last_seq:
+                continue
+            await self._send_msg(sent)
+            replayed += 1
+        return {
+            "complete": self._resume_floor <= last_seq <= self._seq,
+            "replayed": replayed,
+            "in_progress": self._is_streaming,
+        }
+
     def _sync_ask_user_tool_visibility(self, toolset: KimiToolset) -> None:
         """Hide or unhide the AskUserQuestion tool based on client capabilities."""
         from kimi_cli.tools.ask_user import NAME as ASK_USER_TOOL_NAME
@@ -644,6 +959,10 @@ class WireServer:
     async def _handle_prompt(
         self, msg: JSONRPCPromptMessage
     ) -> JSONRPCSuccessResponse | JSONRPCErrorResponse:
+        if msg.params.tool_results is not None:
+            return self._resolve_external_tools(msg.id, msg.params.tool_results)
+        assert msg.params.user_input is not None
+
         if self._is_streaming:
             # TODO: support queueing multiple inputs
             return JSONRPCErrorResponse(
@@ -657,7 +976,12 @@ class WireServer:
             self._track_session_started(None)
 
         self._cancel_event = asyncio.Event()
+        self._resume_buffer.clear()
+        self._resume_floor = self._seq
         runtime = self._soul.runtime if isinstance(self._soul, KimiSoul) else None
+        session_stream_thinking = runtime.stream_thinking if runtime else None
+        if runtime is not None and msg.params.stream_thinking is not None:
+            runtime.stream_thinking = msg.params.stream_thinking
         try:
             await run_soul(
                 self._soul,
@@ -679,7 +1003,11 @@ class WireServer:
         except LLMNotSupported as e:
             return JSONRPCErrorResponse(
                 id=msg.id,
-                error=JSONRPCErrorObject(code=ErrorCodes.LLM_NOT_SUPPORTED, message=str(e)),
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.LLM_NOT_SUPPORTED,
+                    message=str(e),
+                    data={"capabilities": list(e.capabilities)},
+                ),
             )
         except APIStatusError as e:
             if e.status_code == 401 and _is_oauth_session(runtime):
@@ -691,22 +1019,41 @@ class WireServer:
                             "Authentication failed. Your login session may have expired. "
                             'Please run "/login" to sign in again.'
                         ),
+                        data=_provider_error_data(e),
                     ),
                 )
             return JSONRPCErrorResponse(
                 id=msg.id,
-                error=JSONRPCErrorObject(code=ErrorCodes.CHAT_PROVIDER_ERROR, message=str(e)),
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.CHAT_PROVIDER_ERROR,
+                    message=str(e),
+                    data=_provider_error_data(e),
+                ),
             )
         except ChatProviderError as e:
             return JSONRPCErrorResponse(
                 id=msg.id,
-                error=JSONRPCErrorObject(code=ErrorCodes.CHAT_PROVIDER_ERROR, message=str(e)),
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.CHAT_PROVIDER_ERROR,
+                    message=str(e),
+                    data=_provider_error_data(e),
+                ),
             )
         except MaxStepsReached as e:
             return JSONRPCSuccessResponse(
                 id=msg.id,
                 result={"status": Statuses.MAX_STEPS_REACHED, "steps": e.n_steps},
             )
+        except BudgetExceeded as e:
+            return JSONRPCSuccessResponse(
+                id=msg.id,
+                result={
+                    "status": Statuses.BUDGET_EXCEEDED,
+                    "budget": e.kind,
+                    "tokens": e.tokens,
+                    "cost": e.cost,
+                },
+            )
         except RunCancelled:
             return JSONRPCSuccessResponse(
                 id=msg.id,
@@ -735,6 +1082,15 @@ class WireServer:
                             request.resolve("reject")
                             if self._approval_runtime is not None:
                                 self._approval_runtime.resolve(request.id, "reject")
+                    case ApprovalRequestBatch():
+                        for approval in request.requests:
+                            if approval.source_kind == "foreground_turn" and not approval.resolved:
+                                approval.resolve("reject")
+                                if self._approval_runtime is not None:
+                                    self._approval_runtime.resolve(approval.id, "reject")
+                        # Background approvals in the batch stay pending
+                        if request.resolved:
+                            self._pending_requests.pop(msg_id, None)
                     case ToolCallRequest():
                         self._pending_requests.pop(msg_id, None)
                         request.resolve(
@@ -751,7 +1107,56 @@ class WireServer:
                         request.resolve("allow")
                     case _:
                         pass
+            if runtime is not None and session_stream_thinking is not None:
+                runtime.stream_thinking = session_stream_thinking
             self._cancel_event = None
+            self._last_request_at = time.monotonic()
+
+    def _resolve_external_tools(
+        self, msg_id: str, tool_results: list[ToolResult]
+    ) -> JSONRPCSuccessResponse | JSONRPCErrorResponse:
+        """Resolve pending `ToolCallRequest`s of the active turn with results sent by `prompt`."""
+        if not self._is_streaming:
+            return JSONRPCErrorResponse(
+                id=msg_id,
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.INVALID_STATE, message="No agent turn is in progress"
+                ),
+            )
+
+        requests: dict[str, ToolCallRequest] = {}
+        unknown_ids: list[str] = []
+        for tool_result in tool_results:
+            request = self._pending_requests.get(tool_result.tool_call_id)
+            if (
+                not isinstance(request, ToolCallRequest)
+                or request.resolved
+                or request.id in requests
+            ):
+                unknown_ids.append(tool_result.tool_call_id)
+                continue
+            requests[request.id] = request
+        if unknown_ids:
+            # Resolve all or nothing, so that the client can correct the batch and send it again
+            return JSONRPCErrorResponse(
+                id=msg_id,
+                error=JSONRPCErrorObject(
+                    code=ErrorCodes.INVALID_PARAMS,
+                    message=(
+                        "No external tool call is waiting for a result with ID: "
+                        + ", ".join(unknown_ids)
+                    ),
+                ),
+            )
+
+        for tool_result in tool_results:
+            request = requests[tool_result.tool_call_id]
+            self._pending_requests.pop(request.id, None)
+            request.resolve(tool_result.return_value)
+        return JSONRPCSuccessResponse(
+            id=msg_id,
+            result={"status": Statuses.RESUMED, "resolved": list(requests)},
+        )
 
     async def _handle_steer(
         self, msg: JSONRPCSteerMessage
@@ -808,6 +1213,7 @@ class WireServer:
         wire_file = self._soul.wire_file if isinstance(self._soul, KimiSoul) else None
 
         self._cancel_event = asyncio.Event()
+        self._replaying = True
         events = 0
         requests = 0
         try:
@@ -817,7 +1223,15 @@ class WireServer:
                     result={"status": Statuses.FINISHED, "events": 0, "requests": 0},
                 )
 
+            speed = msg.params.speed if msg.params is not None else None
+            previous_timestamp: float | None = None
             async for record in wire_file.iter_records():
+                if speed is not None and previous_timestamp is not None:
+                    delay = min((record.timestamp - previous_timestamp) / speed, MAX_REPLAY_DELAY)
+                    if delay > 0:
+                        with contextlib.suppress(TimeoutError):
+                            await asyncio.wait_for(self._cancel_event.wait(), delay)
+                previous_timestamp = record.timestamp
                 if self._cancel_event.is_set():
                     return JSONRPCSuccessResponse(
                         id=msg.id,
@@ -838,10 +1252,16 @@ class WireServer:
                     continue
 
                 if is_request(wire_msg):
-                    await self._send_msg(JSONRPCRequestMessage(id=wire_msg.id, params=wire_msg))
+                    await self._send_msg(
+                        JSONRPCRequestMessage(
+                            id=wire_msg.id, params=wire_msg, timestamp=record.timestamp
+                        )
+                    )
                     requests += 1
                 elif is_event(wire_msg):
-                    await self._send_msg(JSONRPCEventMessage(params=wire_msg))
+                    await self._send_msg(
+                        JSONRPCEventMessage(params=wire_msg, timestamp=record.timestamp)
+                    )
                     events += 1
                 else:
                     # Not reachable for valid WireMessage, but keep a guard for corrupted data.
@@ -876,6 +1296,7 @@ class WireServer:
                 ),
             )
         finally:
+            self._replaying = False
             self._cancel_event = None
 
     async def _handle_cancel(
@@ -935,6 +1356,8 @@ class WireServer:
                     self._approval_runtime.resolve(
                         request.id, result.response, feedback=result.feedback
                     )
+            case ApprovalRequestBatch():
+                await self._handle_approval_batch_response(request, msg)
             case ToolCallRequest():
                 if isinstance(msg, JSONRPCErrorResponse):
                     error = msg.error.message
@@ -1035,6 +1458,13 @@ class WireServer:
     async def _request_approval(self, request: ApprovalRequest) -> None:
         msg_id = request.id  # just use the approval request id as message id
         self._pending_requests[msg_id] = request
+        if self._client_supports_approval_batch:
+            # Sent together with the other requests made within the batch window, e.g. by
+            # the other tool calls of the same step
+            self._approval_buffer.append(request)
+            if self._approval_flush_task is None:
+                self._approval_flush_task = asyncio.create_task(self._flush_approval_buffer())
+            return
         await self._send_msg(JSONRPCRequestMessage(id=msg_id, params=request))
         # Do NOT await request.wait() here.  The approval future is awaited by
         # the tool that created the request (inside the soul task).  Blocking the
@@ -1042,6 +1472,101 @@ class WireServer:
         # concurrent subagent — from reaching stdout, causing a cascade deadlock
         # when the approval response is lost (e.g. no WebSocket connected).
 
+    async def _flush_approval_buffer(self) -> None:
+        await asyncio.sleep(self._approval_batch_window)
+        self._approval_flush_task = None
+        # Requests resolved or cleaned up while waiting are not sent
+        requests = [
+            request
+            for request in self._approval_buffer
+            if not request.resolved and self._pending_requests.get(request.id) is request
+        ]
+        self._approval_buffer.clear()
+        await self._send_approval_requests(requests)
+
+    async def _send_approval_requests(self, requests: list[ApprovalRequest]) -> None:
+        """Send pending approval requests, as one batch if there are several."""
+        if not requests:
+            return
+        if len(requests) == 1:
+            request = requests[0]
+            await self._send_msg(JSONRPCRequestMessage(id=request.id, params=request))
+            return
+        batch = ApprovalRequestBatch(id=str(uuid.uuid4()), requests=requests)
+        for request in requests:
+            self._pending_requests.pop(request.id, None)
+        self._pending_requests[batch.id] = batch
+        await self._send_msg(JSONRPCRequestMessage(id=batch.id, params=batch))
+
+    async def _handle_approval_batch_response(
+        self,
+        batch: ApprovalRequestBatch,
+        msg: JSONRPCSuccessResponse | JSONRPCErrorResponse,
+    ) -> None:
+        decisions: dict[str, ApprovalDecision]
+        if isinstance(msg, JSONRPCErrorResponse):
+            decisions = {
+                request.id: ApprovalDecision(response="reject") for request in batch.requests
+            }
+        else:
+            try:
+                result = ApprovalBatchResponse.model_validate(msg.result)
+            except pydantic.ValidationError as e:
+                logger.error(
+                    "Invalid approval batch response for request id={id}: {error}",
+                    id=msg.id,
+                    error=e,
+                )
+                decisions = {
+                    request.id: ApprovalDecision(response="reject") for request in batch.requests
+                }
+            else:
+                if result.batch_id != batch.id:
+                    logger.warning(
+                        "Approval batch response id mismatch: request={request_id}, "
+                        "response={response_id}",
+                        request_id=batch.id,
+                        response_id=result.batch_id,
+                    )
+                decisions = result.decisions
+
+        unknown_ids = decisions.keys() - {request.id for request in batch.requests}
+        if unknown_ids:
+            logger.warning(
+                "Approval batch response has decisions for unknown requests: {ids}",
+                ids=sorted(unknown_ids),
+            )
+        unanswered: list[ApprovalRequest] = []
+        for request in batch.requests:
+            if request.resolved:
+                continue
+            decision = decisions.get(request.id)
+            if decision is None:
+                unanswered.append(request)
+                continue
+            request.resolve(decision.response, decision.feedback)
+            if self._approval_runtime is not None:
+                self._approval_runtime.resolve(
+                    request.id, decision.response, feedback=decision.feedback
+                )
+
+        # A partial response only resolves the answered requests; the others are asked again
+        for request in unanswered:
+            self._pending_requests[request.id] = request
+        await self._send_approval_requests(unanswered)
+
+    def _discard_pending_approval(self, response: ApprovalResponse) -> None:
+        """Forget an approval request that has been resolved, e.g. by another client."""
+        self._pending_requests.pop(response.request_id, None)
+        for msg_id, pending in list(self._pending_requests.items()):
+            if not isinstance(pending, ApprovalRequestBatch):
+                continue
+            for request in pending.requests:
+                if request.id == response.request_id:
+                    request.resolve(response.response, response.feedback)
+            if pending.resolved:
+                del self._pending_requests[msg_id]
+
     async def _request_external_tool(self, request: ToolCallRequest) -> None:
         msg_id = request.id
         self._pending_requests[msg_id] = request
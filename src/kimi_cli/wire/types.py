@@ -1,32 +1,48 @@
 from __future__ import annotations
 
 import asyncio
-from typing import Any, Literal, TypeGuard, cast
+from typing import Any, Literal, TypeGuard, cast, override
 
 from kosong.chat_provider import TokenUsage
 from kosong.message import (
     AudioURLPart,
     ContentPart,
+    FilePart,
     ImageURLPart,
+    MergeableMixin,
     TextPart,
     ThinkPart,
     ToolCall,
     ToolCallPart,
+    ToolCallPreview,
     VideoURLPart,
 )
 from kosong.tooling import (
     BriefDisplayBlock,
     DisplayBlock,
+    Tool,
     ToolResult,
     ToolReturnValue,
     UnknownDisplayBlock,
 )
 from kosong.utils.typing import JsonType
-from pydantic import BaseModel, Field, field_serializer, field_validator, model_validator
+from pydantic import (
+    BaseModel,
+    Field,
+    SerializerFunctionWrapHandler,
+    field_serializer,
+    field_validator,
+    model_serializer,
+    model_validator,
+)
 
 from kimi_cli.tools.display import (
     BackgroundTaskDisplayBlock,
     DiffDisplayBlock,
+    DiffHunk,
+    DiffWordChange,
+    ShellCommandAnalysis,
+    ShellCommandInfo,
     ShellDisplayBlock,
     TodoDisplayBlock,
     TodoDisplayItem,
@@ -60,7 +76,10 @@ class TurnEnd(BaseModel):
     If the turn is interrupted, this event may be omitted.
     """
 
-    pass
+    todos_remaining: int | None = None
+    """Number of todo items left pending or in progress by a turn that completed normally."""
+    todos: list[TodoDisplayItem] | None = None
+    """The todo items left pending or in progress, when `todos_remaining` is set."""
 
 
 class StepBegin(BaseModel):
@@ -71,6 +90,8 @@ class StepBegin(BaseModel):
 
     n: int
     """The step number."""
+    timestamp: float | None = None
+    """When the step began, in Unix seconds. Missing from events recorded by older versions."""
 
 
 class StepInterrupted(BaseModel):
@@ -107,13 +128,45 @@ class CompactionBegin(BaseModel):
     pass
 
 
+class CompactionProgress(BaseModel, MergeableMixin):
+    """
+    A piece of the summary being written by a compaction, streamed as it is generated.
+    This event may only be sent between a `CompactionBegin` and its `CompactionEnd`.
+    """
+
+    text: str
+    """The text appended to the summary, or to the thinking before it if `think` is set."""
+    think: bool = False
+    """Whether the text is thinking of the compaction model rather than the summary."""
+    attempt: int = 1
+    """The attempt of the compaction, 1-based. A retry supersedes the text of earlier attempts."""
+
+    @override
+    def merge_in_place(self, other: Any) -> bool:
+        if not isinstance(other, CompactionProgress):
+            return False
+        if (other.think, other.attempt) != (self.think, self.attempt):
+            return False
+        self.text += other.text
+        return True
+
+
 class CompactionEnd(BaseModel):
     """
     Indicates that a compaction just ended.
     This event must be sent directly after a `CompactionBegin` event.
+
+    The fields are None in records written before they were added.
     """
 
-    pass
+    marker_id: str | None = None
+    """The ID of the compaction marker written to the context where the summary begins."""
+    n_summarized: int | None = None
+    """The number of messages replaced by the summary."""
+    before_tokens: int | None = None
+    """The token count of the context before the compaction."""
+    after_tokens: int | None = None
+    """The estimated token count of the context after the compaction."""
 
 
 class HookTriggered(BaseModel):
@@ -173,6 +226,52 @@ class MCPStatusSnapshot(BaseModel):
     servers: tuple[MCPServerSnapshot, ...] = ()
 
 
+type ToolSource = Literal["builtin", "external", "mcp"]
+"""Where a tool comes from: built into the agent, registered by the Wire client, or provided by
+an MCP server."""
+
+
+class ToolSchema(Tool):
+    """A tool offered to the model, with where it comes from."""
+
+    source: ToolSource
+    mcp_server: str | None = None
+    """The MCP server providing the tool, set only for `mcp` tools."""
+
+
+type StepPhase = Literal["generating", "running_tools", "retrying"]
+"""What the current step is doing: waiting for the LLM, waiting for tool results, or waiting
+to retry a failed LLM call."""
+
+
+class RateLimitStatus(BaseModel):
+    """The state of the rate limiter shared by the requests to the current model."""
+
+    waiting: bool
+    """Whether the current step is waiting to send its request."""
+    queued: int
+    """The number of requests waiting to be sent, including the current step's if `waiting`."""
+    in_flight: int
+    """The number of requests sent and not finished yet."""
+    concurrency_limit: int | None
+    """The current limit of requests in flight, lowered after a 429. None means no limit."""
+    cooldown_remaining_ms: int
+    """The milliseconds left before requests are sent again after a 429, 0 if not cooling down."""
+
+
+class StepMetrics(BaseModel):
+    """The timing of the LLM response of a step, measured from sending the request."""
+
+    time_to_first_part_ms: int
+    """The milliseconds until the first streamed part was received."""
+    duration_ms: int
+    """The milliseconds until the whole response was received."""
+    part_count: int
+    """The number of streamed parts received."""
+    output_tokens_per_second: float | None
+    """The approximate output speed, None without usage or for a non-streaming response."""
+
+
 class StatusUpdate(BaseModel):
     """
     An update on the current status of the soul.
@@ -187,12 +286,28 @@ class StatusUpdate(BaseModel):
     """The maximum number of tokens the context can hold."""
     token_usage: TokenUsage | None = None
     """The token usage statistics of the current step."""
+    session_usage: TokenUsage | None = None
+    """The accumulated token usage of the session, including compaction and subagents."""
+    session_compaction_usage: TokenUsage | None = None
+    """The part of `session_usage` spent on context compaction."""
     message_id: str | None = None
     """The message ID of the current step."""
     plan_mode: bool | None = None
     """Whether plan mode (read-only) is active. None means no change."""
     mcp_status: MCPStatusSnapshot | None = None
     """The current MCP startup snapshot. None means no change."""
+    phase: StepPhase | None = None
+    """The phase the current step just entered, or is still in if `elapsed_ms` is set."""
+    attempt: int | None = None
+    """The number of the next attempt, 1-based, when `phase` is `retrying`."""
+    elapsed_ms: int | None = None
+    """Set when `phase` has lasted longer than `loop_control.slow_phase_threshold_s`: the
+    milliseconds spent in it so far."""
+    rate_limit: RateLimitStatus | None = None
+    """Set when the current step has to wait for the rate limiter, and again with `waiting`
+    false once its request is answered. None means no change."""
+    step_metrics: StepMetrics | None = None
+    """The timing of the LLM response of the step, set in the update at the end of each step."""
 
 
 class Notification(BaseModel):
@@ -239,6 +354,27 @@ class BtwEnd(BaseModel):
     """Error message if the side question failed."""
 
 
+class SubagentEnd(BaseModel):
+    """
+    Indicates that a foreground subagent has finished running.
+    """
+
+    type Status = Literal["completed", "failed", "cancelled"]
+
+    parent_tool_call_id: str | None = None
+    """The ID of the parent Agent tool call associated with this subagent."""
+    agent_id: str | None = None
+    """The subagent instance ID."""
+    subagent_type: str | None = None
+    """The built-in subagent type used by this instance."""
+    status: Status
+    """How the subagent run ended."""
+    final_message: str | None = None
+    """The final response of the subagent. None if it did not produce one."""
+    usage: TokenUsage | None = None
+    """The token usage of the subagent run, including its own subagents."""
+
+
 class SubagentEvent(BaseModel):
     """
     An event from a subagent.
@@ -250,6 +386,12 @@ class SubagentEvent(BaseModel):
     """The subagent instance ID."""
     subagent_type: str | None = None
     """The built-in subagent type used by this instance."""
+    depth: int = Field(default=1, ge=1)
+    """
+    The number of subagent levels between the receiver and the subagent that produced `event`.
+    Events nested deeper than `loop_control.max_subagent_event_depth` are flattened into the
+    innermost allowed level instead of being wrapped again.
+    """
     event: Event
     """The event from the subagent."""
     # TODO: maybe restrict the event types? to exclude approval request, etc.
@@ -367,6 +509,44 @@ class ApprovalRequest(BaseModel):
         return self._future is not None and self._future.done()
 
 
+class ApprovalDecision(BaseModel):
+    """The decision on one approval request of an `ApprovalRequestBatch`."""
+
+    response: ApprovalResponse.Kind
+    """The response to the approval request."""
+    feedback: str = ""
+    """Optional user feedback when rejecting (e.g. instructions for the model)."""
+
+
+class ApprovalBatchResponse(BaseModel):
+    """
+    Client response to an `ApprovalRequestBatch`.
+    """
+
+    batch_id: str
+    """The ID of the `ApprovalRequestBatch` being responded to."""
+    decisions: dict[str, ApprovalDecision]
+    """Maps the IDs of the answered approval requests to their decisions. Requests that are not
+    answered stay pending and are sent again."""
+
+
+class ApprovalRequestBatch(BaseModel):
+    """
+    Several approval requests sent to the client at once, e.g. when the model edits several
+    files in one step. Only sent to Wire clients that declare `supports_approval_batch`.
+    """
+
+    id: str
+    """The unique batch ID."""
+    requests: list[ApprovalRequest]
+    """The approval requests in the batch, in the order they were made."""
+
+    @property
+    def resolved(self) -> bool:
+        """Whether every request in the batch is resolved."""
+        return all(request.resolved for request in self.requests)
+
+
 class QuestionOption(BaseModel):
     """A single option for a question."""
 
@@ -523,6 +703,7 @@ type Event = (
     | HookTriggered
     | HookResolved
     | CompactionBegin
+    | CompactionProgress
     | CompactionEnd
     | MCPLoadingBegin
     | MCPLoadingEnd
@@ -531,9 +712,11 @@ type Event = (
     | ContentPart
     | ToolCall
     | ToolCallPart
+    | ToolCallPreview
     | ToolResult
     | ApprovalResponse
     | SubagentEvent
+    | SubagentEnd
     | PlanDisplay
     | BtwBegin
     | BtwEnd
@@ -597,7 +780,9 @@ class HookRequest(BaseModel):
         return self._future is not None and self._future.done()
 
 
-type Request = ApprovalRequest | ToolCallRequest | QuestionRequest | HookRequest
+type Request = (
+    ApprovalRequest | ApprovalRequestBatch | ToolCallRequest | QuestionRequest | HookRequest
+)
 """Any request. Request is a message that expects a response."""
 
 type WireMessage = Event | Request
@@ -634,9 +819,27 @@ _NAME_TO_WIRE_MESSAGE_TYPE["ApprovalRequestResolved"] = ApprovalResponse
 class WireMessageEnvelope(BaseModel):
     type: str
     payload: dict[str, JsonType]
+    seq: int | None = None
+    """Increasing number of the message, assigned by the Wire server to the `event` and
+    `request` messages it sends, and by `WireFile` to the records it persists. Clients use it to
+    resume after reconnecting."""
+    timestamp: float | None = None
+    """When the message was sent, in Unix seconds. Missing from records written by older
+    versions."""
+
+    @model_serializer(mode="wrap")
+    def _serialize(self, handler: SerializerFunctionWrapHandler) -> dict[str, Any]:
+        data = handler(self)
+        if self.seq is None:
+            data.pop("seq", None)
+        if self.timestamp is None:
+            data.pop("timestamp", None)
+        return data
 
     @classmethod
-    def from_wire_message(cls, msg: WireMessage) -> WireMessageEnvelope:
+    def from_wire_message(
+        cls, msg: WireMessage, *, seq: int | None = None, timestamp: float | None = None
+    ) -> WireMessageEnvelope:
         typename: str | None = None
         for name, typ in _NAME_TO_WIRE_MESSAGE_TYPE.items():
             if issubclass(type(msg), typ):
@@ -646,6 +849,8 @@ class WireMessageEnvelope(BaseModel):
         return cls(
             type=typename,
             payload=msg.model_dump(mode="json"),
+            seq=seq,
+            timestamp=timestamp,
         )
 
     def to_wire_message(self) -> WireMessage:
@@ -670,23 +875,34 @@ __all__ = [
     "StepInterrupted",
     "StepRetry",
     "CompactionBegin",
+    "CompactionProgress",
     "CompactionEnd",
     "MCPLoadingBegin",
     "MCPLoadingEnd",
     "StatusUpdate",
+    "StepPhase",
+    "RateLimitStatus",
+    "StepMetrics",
     "MCPServerSnapshot",
     "MCPStatusSnapshot",
+    "ToolSource",
+    "ToolSchema",
     "Notification",
     "ContentPart",
     "ToolCall",
     "ToolCallPart",
+    "ToolCallPreview",
     "ToolResult",
     "ApprovalResponse",
     "SubagentEvent",
+    "SubagentEnd",
     "PlanDisplay",
     "BtwBegin",
     "BtwEnd",
     "ApprovalRequest",
+    "ApprovalDecision",
+    "ApprovalBatchResponse",
+    "ApprovalRequestBatch",
     "ToolCallRequest",
     "QuestionOption",
     "QuestionItem",
@@ -703,6 +919,7 @@ __all__ = [
     "ImageURLPart",
     "AudioURLPart",
     "VideoURLPart",
+    "FilePart",
     # `ToolResult`-related
     "ToolReturnValue",
     # `DisplayBlock` types
@@ -710,8 +927,12 @@ __all__ = [
     "UnknownDisplayBlock",
     "BriefDisplayBlock",
     "DiffDisplayBlock",
+    "DiffHunk",
+    "DiffWordChange",
     "TodoDisplayBlock",
     "TodoDisplayItem",
     "ShellDisplayBlock",
+    "ShellCommandAnalysis",
+    "ShellCommandInfo",
     "BackgroundTaskDisplayBlock",
 ]
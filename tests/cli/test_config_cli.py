@@ -0,0 +1,103 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+from typer.testing import CliRunner
+
+from kimi_cli.cli import cli
+
+_CONFIG = """\
+# Personal settings
+default_model = "kimi"
+
+[models.kimi]
+provider = "moonshot"  # the hosted API
+model = "kimi-k2"
+max_context_size = 262144
+
+[providers.moonshot]
+type = "kimi"
+base_url = "https://api.moonshot.ai/v1"
+api_key = "sk-secret"
+
+[loop_control]
+# keep retries low on flaky networks
+max_retries_per_step = 2
+"""
+
+
+@pytest.fixture
+def config_file(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path))
+    path = tmp_path / "config.toml"
+    path.write_text(_CONFIG, encoding="utf-8")
+    return path
+
+
+def _invoke(*args: str):
+    return CliRunner().invoke(cli, ["config", *args])
+
+
+def test_config_set_then_get(config_file: Path) -> None:
+    result = _invoke("set", "loop_control.max_steps_per_turn", "50")
+    assert result.exit_code == 0, result.output
+    result = _invoke("get", "loop_control.max_steps_per_turn")
+    assert result.exit_code == 0, result.output
+    assert result.output.strip() == "50"
+
+    assert _invoke("set", "models.kimi.pricing", "{ input = 1.0, output = 4.0 }").exit_code == 0
+    result = _invoke("get", "models.kimi.pricing.output")
+    assert result.output.strip() == "4.0"
+
+    assert _invoke("set", "default_editor", "code --wait").exit_code == 0
+    assert _invoke("get", "default_editor").output.strip() == "code --wait"
+
+    assert _invoke("unset", "loop_control.max_steps_per_turn").exit_code == 0
+    assert _invoke("get", "loop_control.max_steps_per_turn").output.strip() == "1000"
+
+
+def test_config_set_preserves_comments(config_file: Path) -> None:
+    result = _invoke("set", "loop_control.max_retries_per_step", "5")
+    assert result.exit_code == 0, result.output
+
+    text = config_file.read_text(encoding="utf-8")
+    assert text == _CONFIG.replace("max_retries_per_step = 2", "max_retries_per_step = 5")
+
+
+@pytest.mark.parametrize(
+    ("key", "value", "error"),
+    [
+        ("loop_control.max_step", "5", "Unknown config key: loop_control.max_step"),
+        ("loop_control.max_retries_per_step", "many", "expected int, got 'many'"),
+        ("loop_control.max_retries_per_step", "0", "expected int, got '0'"),
+        ("default_model", "missing", "Default model missing not found in models"),
+    ],
+)
+def test_config_set_rejects_invalid_values(
+    config_file: Path, key: str, value: str, error: str
+) -> None:
+    result = _invoke("set", key, value)
+
+    assert result.exit_code == 1
+    assert error in result.output
+    assert config_file.read_text(encoding="utf-8") == _CONFIG
+
+
+def test_config_list_masks_secrets(config_file: Path) -> None:
+    result = _invoke("list", "--json")
+    assert result.exit_code == 0, result.output
+    values = json.loads(result.output)
+
+    assert values["providers.moonshot.api_key"] == {"value": "********", "set": True}
+    assert values["loop_control.max_retries_per_step"] == {"value": 2, "set": True}
+    assert values["loop_control.max_steps_per_turn"] == {"value": 1000, "set": False}
+
+    result = _invoke("list")
+    assert "* providers.moonshot.api_key = \"********\"" in result.output
+    assert "  loop_control.max_steps_per_turn = 1000" in result.output
+    assert "sk-secret" not in result.output
+
+    assert _invoke("get", "providers.moonshot.api_key").output.strip() == "sk-secret"
+    assert "sk-secret" not in _invoke("get", "providers.moonshot").output
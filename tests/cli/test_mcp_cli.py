@@ -0,0 +1,91 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+from typer.testing import CliRunner
+
+from kimi_cli.cli import cli
+
+
+def test_mcp_list_json(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path))
+    config = {
+        "mcpServers": {
+            "fs": {"command": "npx", "args": ["-y", "fs-mcp"]},
+            "linear": {
+                "url": "https://mcp.linear.app/mcp",
+                "transport": "streamable-http",
+                "auth": "oauth",
+            },
+            "docs": {"url": "https://docs.example.test/sse", "transport": "sse"},
+        }
+    }
+    (tmp_path / "mcp.json").write_text(json.dumps(config), encoding="utf-8")
+
+    result = CliRunner().invoke(cli, ["mcp", "list", "--json"])
+    assert result.exit_code == 0, result.output
+    assert json.loads(result.output) == [
+        {
+            "name": "fs",
+            "transport": "stdio",
+            "command": "npx",
+            "args": ["-y", "fs-mcp"],
+            "auth_required": False,
+            "status": "ready",
+        },
+        {
+            "name": "linear",
+            "transport": "http",
+            "url": "https://mcp.linear.app/mcp",
+            "auth_required": True,
+            "status": "authorization_required",
+        },
+        {
+            "name": "docs",
+            "transport": "sse",
+            "url": "https://docs.example.test/sse",
+            "auth_required": False,
+            "status": "ready",
+        },
+    ]
+
+    result = CliRunner().invoke(cli, ["mcp", "list"])
+    assert result.exit_code == 0, result.output
+    assert "  fs (stdio): npx -y fs-mcp" in result.output
+    assert "authorization required - run: kimi mcp auth linear" in result.output
+
+
+def test_mcp_test_expands_env_vars(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path))
+    monkeypatch.delenv("FS_MCP_TOKEN", raising=False)
+    config = {
+        "mcpServers": {
+            "fs": {"command": "npx", "args": ["-y", "fs-mcp"], "env": {"TOKEN": "${FS_MCP_TOKEN}"}}
+        }
+    }
+    (tmp_path / "mcp.json").write_text(json.dumps(config), encoding="utf-8")
+
+    result = CliRunner().invoke(cli, ["mcp", "test", "fs"])
+
+    assert result.exit_code == 1
+    assert "Environment variable `FS_MCP_TOKEN` is not set" in result.output
+
+
+def test_prepare_mcp_server_config_expands_stdio_env(monkeypatch: pytest.MonkeyPatch) -> None:
+    from kimi_cli.mcp_oauth import prepare_mcp_server_config
+
+    monkeypatch.setenv("FS_MCP_TOKEN", "secret")
+    monkeypatch.delenv("FS_MCP_ROOT", raising=False)
+    server = {
+        "command": "npx",
+        "args": ["-y", "fs-mcp", "--root=${FS_MCP_ROOT:-/srv}"],
+        "env": {"TOKEN": "${FS_MCP_TOKEN}"},
+    }
+
+    prepared = prepare_mcp_server_config(server)
+
+    assert prepared["args"] == ["-y", "fs-mcp", "--root=/srv"]
+    assert prepared["env"] == {"TOKEN": "secret"}
+    assert server["env"] == {"TOKEN": "${FS_MCP_TOKEN}"}
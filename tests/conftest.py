@@ -35,13 +35,21 @@ from kimi_cli.tools.background import (
     TaskOutput,
     TaskStop,
 )
+from kimi_cli.tools.context import FindInContext
 from kimi_cli.tools.dmail import SendDMail
+from kimi_cli.tools.environment import GetEnvironment
 from kimi_cli.tools.file.glob import Glob
 from kimi_cli.tools.file.grep_local import Grep
+from kimi_cli.tools.file.multi_replace import MultiStrReplaceFile
+from kimi_cli.tools.file.notebook import NotebookEdit, NotebookRead
+from kimi_cli.tools.file.pdf import ReadPdf
 from kimi_cli.tools.file.read import ReadFile
 from kimi_cli.tools.file.read_media import ReadMediaFile
 from kimi_cli.tools.file.replace import StrReplaceFile
+from kimi_cli.tools.file.table import ReadTable
 from kimi_cli.tools.file.write import WriteFile
+from kimi_cli.tools.git import Git
+from kimi_cli.tools.lsp import FindReferences, GoToDefinition
 from kimi_cli.tools.shell import Shell
 from kimi_cli.tools.think import Think
 from kimi_cli.tools.todo import SetTodoList
@@ -237,9 +245,9 @@ def agent_tool(runtime: Runtime) -> AgentTool:
 
 
 @pytest.fixture
-def send_dmail_tool(denwa_renji: DenwaRenji) -> SendDMail:
+def send_dmail_tool(denwa_renji: DenwaRenji, runtime: Runtime) -> SendDMail:
     """Create a SendDMail tool instance."""
-    return SendDMail(denwa_renji)
+    return SendDMail(denwa_renji, runtime)
 
 
 @pytest.fixture
@@ -248,6 +256,12 @@ def think_tool() -> Think:
     return Think()
 
 
+@pytest.fixture
+def find_in_context_tool() -> FindInContext:
+    """Create a FindInContext tool instance."""
+    return FindInContext()
+
+
 @pytest.fixture
 def set_todo_list_tool(runtime: Runtime) -> SetTodoList:
     """Create a SetTodoList tool instance."""
@@ -298,9 +312,9 @@ def glob_tool(runtime: Runtime) -> Glob:
 
 
 @pytest.fixture
-def grep_tool() -> Grep:
+def grep_tool(runtime: Runtime) -> Grep:
     """Create a Grep tool instance."""
-    return Grep()
+    return Grep(runtime)
 
 
 @pytest.fixture
@@ -317,6 +331,66 @@ def str_replace_file_tool(runtime: Runtime, approval: Approval) -> Generator[Str
         yield StrReplaceFile(runtime, approval)
 
 
+@pytest.fixture
+def multi_str_replace_file_tool(
+    runtime: Runtime, approval: Approval
+) -> Generator[MultiStrReplaceFile]:
+    """Create a MultiStrReplaceFile tool instance."""
+    with tool_call_context("MultiStrReplaceFile"):
+        yield MultiStrReplaceFile(runtime, approval)
+
+
+@pytest.fixture
+def notebook_read_tool(runtime: Runtime) -> NotebookRead:
+    """Create a NotebookRead tool instance."""
+    return NotebookRead(runtime)
+
+
+@pytest.fixture
+def read_pdf_tool(runtime: Runtime) -> ReadPdf:
+    """Create a ReadPdf tool instance, skipping the test without the `pdf` extra."""
+    pytest.importorskip("pypdf")
+    return ReadPdf(runtime)
+
+
+@pytest.fixture
+def read_table_tool(runtime: Runtime) -> ReadTable:
+    """Create a ReadTable tool instance."""
+    return ReadTable(runtime)
+
+
+@pytest.fixture
+def notebook_edit_tool(runtime: Runtime, approval: Approval) -> Generator[NotebookEdit]:
+    """Create a NotebookEdit tool instance."""
+    with tool_call_context("NotebookEdit"):
+        yield NotebookEdit(runtime, approval)
+
+
+@pytest.fixture
+def git_tool(runtime: Runtime, approval: Approval) -> Generator[Git]:
+    """Create a Git tool instance."""
+    with tool_call_context("Git"):
+        yield Git(runtime, approval)
+
+
+@pytest.fixture
+def get_environment_tool(runtime: Runtime) -> GetEnvironment:
+    """Create a GetEnvironment tool instance."""
+    return GetEnvironment(runtime)
+
+
+@pytest.fixture
+def go_to_definition_tool(runtime: Runtime) -> GoToDefinition:
+    """Create a GoToDefinition tool instance."""
+    return GoToDefinition(runtime)
+
+
+@pytest.fixture
+def find_references_tool(runtime: Runtime) -> FindReferences:
+    """Create a FindReferences tool instance."""
+    return FindReferences(runtime)
+
+
 @pytest.fixture
 def search_web_tool(config: Config, runtime: Runtime) -> SearchWeb:
     """Create a SearchWeb tool instance."""
@@ -0,0 +1,105 @@
+"""Tests for KimiCLIBuilder, and the embedding example built on it."""
+
+from __future__ import annotations
+
+import importlib.util
+from pathlib import Path
+
+import pytest
+from kaos.path import KaosPath
+from kosong.tooling import CallableTool2, ToolOk, ToolReturnValue
+from pydantic import BaseModel
+
+from kimi_cli.builder import KimiCLIBuilder
+from kimi_cli.exception import KimiCLIBuilderError
+from kimi_cli.session import Session
+
+EXAMPLE = Path(__file__).parents[2] / "examples" / "embed-kimi-cli" / "main.py"
+
+
+class _Params(BaseModel):
+    pass
+
+
+class _Noop(CallableTool2[_Params]):
+    name: str = "Noop"
+    description: str = "Do nothing."
+    params: type[_Params] = _Params
+
+    async def __call__(self, params: _Params) -> ToolReturnValue:
+        return ToolOk(output="")
+
+
+def test_validate_accepts_a_default_builder() -> None:
+    assert KimiCLIBuilder().validate() == []
+
+
+async def test_validate_reports_all_problems_at_once(
+    session: Session, temp_work_dir: KaosPath, tmp_path: Path
+) -> None:
+    builder = (
+        KimiCLIBuilder()
+        .session(session)
+        .work_dir(temp_work_dir)
+        .config(tmp_path / "missing.toml")
+        .agent_file(tmp_path / "missing.yaml")
+        .skills_dir(tmp_path / "missing-skills")
+        .loop_limits(max_steps_per_turn=0, max_retries_per_step=0, ralph_failure_limit=0)
+        .add_tool(_Noop())
+        .add_tool(_Noop())
+    )
+
+    assert builder.validate() == [
+        "set either a session or a work_dir, not both",
+        f"config file not found: {tmp_path / 'missing.toml'}",
+        f"agent file not found: {tmp_path / 'missing.yaml'}",
+        f"skills directory not found: {tmp_path / 'missing-skills'}",
+        "max_steps_per_turn must be at least 1",
+        "max_retries_per_step must be at least 1",
+        "ralph_failure_limit must be at least 1",
+        "tool Noop is added more than once",
+    ]
+    with pytest.raises(KimiCLIBuilderError) as exc_info:
+        await builder.build()
+    assert exc_info.value.problems == builder.validate()
+    assert str(exc_info.value).startswith("Invalid setup:\n- set either a session")
+
+
+def test_loop_limits_keep_the_limits_left_unset() -> None:
+    builder = KimiCLIBuilder().loop_limits(max_steps_per_turn=0).loop_limits(max_ralph_iterations=3)
+
+    assert builder.validate() == ["max_steps_per_turn must be at least 1"]
+
+
+async def test_failed_build_deletes_the_session_it_started(
+    temp_work_dir: KaosPath, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path / "share"))
+    started: list[Session] = []
+
+    async def failing_create(session: Session, **kwargs: object) -> None:
+        started.append(session)
+        assert (session.work_dir_meta.sessions_dir / session.id).is_dir()
+        raise RuntimeError("no model")
+
+    monkeypatch.setattr("kimi_cli.app.KimiCLI.create", failing_create)
+
+    with pytest.raises(RuntimeError, match="no model"):
+        await KimiCLIBuilder().work_dir(temp_work_dir).build()
+
+    (session,) = started
+    assert not (session.work_dir_meta.sessions_dir / session.id).exists()
+
+
+async def test_embedding_example_runs_a_prompt_with_a_custom_tool(
+    temp_work_dir: KaosPath, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path / "share"))
+    spec = importlib.util.spec_from_file_location("embed_kimi_cli_example", EXAMPLE)
+    assert spec is not None and spec.loader is not None
+    example = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(example)
+
+    answer = await example.main(Path(str(temp_work_dir)))
+
+    assert answer == "The Greet tool says: Hello, Kimi!"
@@ -0,0 +1,122 @@
+"""Tests for the markers of where the summary of a compaction begins."""
+
+from __future__ import annotations
+
+import json
+from datetime import datetime
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.message import Message
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import CompactionMarker, Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.utils.export import build_export_markdown
+from kimi_cli.wire.types import CompactionEnd
+
+
+async def test_forced_compaction_marks_where_the_summary_begins(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    provider = ScriptBuilder().text("We fixed the login bug.").build()
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    context = Context(file_backend=tmp_path / "history.jsonl")
+    soul = KimiSoul(agent, context=context)
+    for i in range(3):
+        await context.append_message(Message(role="user", content=f"question {i}"))
+        await context.append_message(Message(role="assistant", content=f"answer {i}"))
+    await context.update_token_count(5000)
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", sent.append)
+
+    await soul.compact_context(manual=True)
+
+    # The first four messages are summarized, the last two are preserved after the summary.
+    [marker] = context.compaction_markers
+    assert (marker.n_summarized, marker.before_tokens) == (4, 5000)
+    assert marker.after_tokens == context.token_count
+    assert context.history[marker.position].extract_text().startswith(
+        "<system>Previous context has been compacted."
+    )
+    assert [m.extract_text() for m in context.history[marker.position + 1 :]] == [
+        "question 2",
+        "answer 2",
+    ]
+    assert (
+        CompactionEnd(
+            marker_id=marker.id,
+            n_summarized=4,
+            before_tokens=5000,
+            after_tokens=marker.after_tokens,
+        )
+        in sent
+    )
+
+    restored = Context(file_backend=context.file_backend)
+    await restored.restore()
+    assert restored.compaction_markers == context.compaction_markers
+
+    exported = build_export_markdown(
+        session_id="session",
+        work_dir="/work",
+        history=context.history,
+        token_count=context.token_count,
+        now=datetime.now(),
+        compaction_markers=context.compaction_markers,
+    )
+    divider = "---- context compacted (4 messages → summary) ----"
+    assert exported.index(divider) < exported.index("## Turn 1")
+    assert exported.count(divider) == 1
+
+
+async def test_markers_are_restored_in_place_and_dropped_on_revert(tmp_path: Path) -> None:
+    path = tmp_path / "history.jsonl"
+    records = [
+        {"role": "_system_prompt", "content": "system"},
+        {"role": "_checkpoint", "id": 0},
+        {"role": "_compaction", "id": "old", "n_summarized": 1},  # invalid, skipped
+        {
+            "role": "_compaction",
+            "id": "m1",
+            "n_summarized": 12,
+            "before_tokens": 9000,
+            "after_tokens": 300,
+        },
+        {"role": "user", "content": "summary"},
+        {"role": "_checkpoint", "id": 1},
+        {"role": "user", "content": "next question"},
+    ]
+    path.write_text("".join(json.dumps(record) + "\n" for record in records), encoding="utf-8")
+
+    context = Context(file_backend=path)
+    await context.restore()
+    assert context.compaction_markers == [
+        CompactionMarker(id="m1", n_summarized=12, before_tokens=9000, after_tokens=300)
+    ]
+
+    await context.revert_to(1)
+    assert len(context.compaction_markers) == 1
+    await context.revert_to(0)
+    assert context.compaction_markers == []
+
+
+async def test_context_without_markers_still_loads(tmp_path: Path) -> None:
+    path = tmp_path / "history.jsonl"
+    records = [{"role": "user", "content": "hi"}, {"role": "_usage", "token_count": 10}]
+    path.write_text("".join(json.dumps(record) + "\n" for record in records), encoding="utf-8")
+
+    context = Context(file_backend=path)
+    assert await context.restore()
+    assert context.compaction_markers == []
+    assert [m.extract_text() for m in context.history] == ["hi"]
@@ -28,14 +28,29 @@ def test_default_config_dump():
             "default_editor": "",
             "theme": "dark",
             "show_thinking_stream": True,
+            "stream_thinking": None,
             "models": {},
             "providers": {},
             "loop_control": {
                 "max_steps_per_turn": 1000,
                 "max_retries_per_step": 3,
                 "max_ralph_iterations": 0,
+                "ralph_success_command": None,
+                "ralph_failure_limit": None,
                 "reserved_context_size": 50000,
                 "compaction_trigger_ratio": 0.85,
+                "compaction_max_output_tokens": 16000,
+                "max_message_chars": 200000,
+                "max_tokens_per_turn": None,
+                "max_cost_per_turn": None,
+                "max_subagent_event_depth": 3,
+                "slow_phase_threshold_s": 120,
+            },
+            "generation": {
+                "max_output_tokens": None,
+                "stop": [],
+                "temperature": None,
+                "top_p": None,
             },
             "background": {
                 "max_running_tasks": 4,
@@ -54,11 +69,48 @@ def test_default_config_dump():
                 "claim_stale_after_ms": 15000,
             },
             "services": {"moonshot_search": None, "moonshot_fetch": None},
-            "mcp": {"client": {"tool_call_timeout_ms": 60000}},
+            "tools": {
+                "enabled": None,
+                "disabled": [],
+                "max_write_bytes": 4194304,
+                "timeout_s": 600,
+                "timeouts": {},
+            },
+            "mcp": {
+                "client": {
+                    "tool_call_timeout_ms": 60000,
+                    "prefix_tool_names": False,
+                    "max_concurrent_connections": 4,
+                }
+            },
+            "lsp": {"servers": {}},
+            "network": {
+                "http_proxy": None,
+                "https_proxy": None,
+                "no_proxy": None,
+                "extra_ca_bundle": None,
+                "accept_invalid_certs": False,
+            },
+            "redaction": {"enabled": True, "patterns": {}, "redact_wire": False},
+            "file_journal": {
+                "enabled": False,
+                "max_file_size": 1048576,
+                "max_total_size": 52428800,
+            },
+            "session": {"idle_timeout_secs": None},
+            "image_input": {
+                "max_image_size": 5242880,
+                "max_message_size": 20971520,
+                "max_dimension": 4096,
+            },
+            "on_unsupported_media": "error",
+            "vision_model": None,
             "hooks": [],
             "merge_all_available_skills": True,
             "extra_skill_dirs": [],
             "telemetry": True,
+            "encrypt_sessions": False,
+            "verify_model": "off",
             "skip_afk_prompt_injection": False,
         }
     )
@@ -104,6 +156,16 @@ def test_load_config_reserved_context_size():
     assert config.loop_control.reserved_context_size == 30000
 
 
+def test_load_config_generation():
+    config = load_config_from_string(
+        '[generation]\nmax_output_tokens = 4096\nstop = ["<END>"]\ntemperature = 0.2\n'
+    )
+    assert config.generation.max_output_tokens == 4096
+    assert config.generation.stop == ["<END>"]
+    assert config.generation.temperature == 0.2
+    assert config.generation.top_p is None
+
+
 def test_load_config_max_steps_per_turn():
     config = load_config_from_string("[loop_control]\nmax_steps_per_turn = 42\n")
     assert config.loop_control.max_steps_per_turn == 42
@@ -114,6 +176,14 @@ def test_load_config_legacy_skip_yolo_prompt_injection_ignored():
     assert config.skip_afk_prompt_injection is False
 
 
+def test_load_config_lsp_servers():
+    config = load_config_from_string(
+        '[lsp.servers.python]\ncommand = ["pyright-langserver", "--stdio"]\n'
+    )
+    assert config.lsp.servers["python"].command == ["pyright-langserver", "--stdio"]
+    assert config.lsp.servers["python"].extensions == []
+
+
 def test_load_config_max_steps_per_run():
     config = load_config_from_string('{"loop_control": {"max_steps_per_run": 7}}')
     assert config.loop_control.max_steps_per_turn == 7
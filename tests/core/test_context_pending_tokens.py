@@ -215,6 +215,63 @@ async def test_revert_to_no_pending_when_usage_is_last(tmp_path: Path) -> None:
     assert ctx.token_count_with_pending == 3000  # no pending
 
 
+@pytest.mark.asyncio
+async def test_revert_to_across_compaction_boundary(tmp_path: Path) -> None:
+    """Reverting after a compaction restores the count of the compacted history, not a stale one."""
+    path = tmp_path / "ctx.jsonl"
+    path.touch()
+    ctx = Context(file_backend=path)
+
+    def needs_compaction() -> bool:
+        return should_auto_compact(
+            ctx.token_count_with_pending, 200_000, trigger_ratio=0.85, reserved_context_size=50_000
+        )
+
+    # A long history that needs compaction.
+    await ctx.checkpoint(add_user_message=False)
+    await ctx.append_message(_msg("user", "long task"))
+    await ctx.update_token_count(180_000)
+    await ctx.checkpoint(add_user_message=False)
+    assert needs_compaction()
+
+    # Compaction, the way KimiSoul does it.
+    await ctx.clear()
+    await ctx.write_system_prompt("system")
+    await ctx.checkpoint(add_user_message=False)
+    summary = _msg("user", "summary of the long task")
+    await ctx.append_message(summary)
+    compacted_tokens = estimate_text_tokens([summary])
+    await ctx.update_token_count(compacted_tokens)
+
+    # More steps after the compaction.
+    await ctx.checkpoint(add_user_message=False)
+    await ctx.append_message(_msg("assistant", "reading files"))
+    await ctx.append_message(_msg("tool", "z" * 40_000))
+    await ctx.update_token_count(170_000)
+    await ctx.checkpoint(add_user_message=False)
+    assert needs_compaction()
+
+    # Reverting past the big tool result brings the count back to the compacted history.
+    await ctx.revert_to(1)
+    assert ctx.history == [summary]
+    assert ctx.token_count == compacted_tokens
+    assert ctx.token_count_with_pending == compacted_tokens
+    assert not needs_compaction()
+
+    # Restoring the same file agrees with the in-memory state.
+    restored = Context(file_backend=path)
+    await restored.restore()
+    assert restored.token_count == ctx.token_count
+    assert restored.token_count_with_pending == ctx.token_count_with_pending
+
+    # The first checkpoint after the compaction precedes the summary and any usage.
+    await ctx.revert_to(0)
+    assert ctx.history == []
+    assert ctx.token_count == 0
+    assert ctx.token_count_with_pending == 0
+    assert ctx.system_prompt == "system"
+
+
 # --- Restore rebuilds pending for messages after last _usage ---
 
 
@@ -0,0 +1,139 @@
+"""Tests for repairing unpaired or misplaced tool messages in the context history."""
+
+from __future__ import annotations
+
+import json
+from collections.abc import Sequence
+from pathlib import Path
+from typing import Any
+
+import pytest
+from kosong.chat_provider.kimi import _convert_message  # pyright: ignore[reportPrivateUsage]
+from kosong.message import Message, ToolCall
+
+from kimi_cli.soul.context import TOOL_RESULT_LOST_MESSAGE, Context, repair_tool_messages
+from kimi_cli.soul.message import system
+from kimi_cli.wire.types import TextPart
+
+
+def _call(call_id: str) -> ToolCall:
+    return ToolCall(
+        id=call_id,
+        function=ToolCall.FunctionBody(name="Shell", arguments='{"command": "ls"}'),
+    )
+
+
+def _user(text: str) -> Message:
+    return Message(role="user", content=[TextPart(text=text)])
+
+
+def _assistant(*call_ids: str) -> Message:
+    return Message(
+        role="assistant",
+        content=[TextPart(text="Running.")],
+        tool_calls=[_call(call_id) for call_id in call_ids],
+    )
+
+
+def _tool(call_id: str) -> Message:
+    return Message(role="tool", content=[TextPart(text=f"result {call_id}")], tool_call_id=call_id)
+
+
+def _assert_provider_accepts(history: Sequence[Message]) -> None:
+    """Every tool call is answered right after its assistant message, and nothing else."""
+    converted: list[Any] = [_convert_message(message) for message in history]
+    i = 0
+    while i < len(converted):
+        message = converted[i]
+        assert message["role"] != "tool", f"orphan tool message at {i}"
+        i += 1
+        call_ids = [call["id"] for call in message.get("tool_calls") or []]
+        for call_id in call_ids:
+            assert converted[i]["role"] == "tool"
+            assert converted[i]["tool_call_id"] == call_id
+            i += 1
+
+
+def test_repair_keeps_valid_history() -> None:
+    history = [_user("hi"), _assistant("a", "b"), _tool("a"), _tool("b"), _user("next")]
+
+    repaired, repairs = repair_tool_messages(history)
+
+    assert repairs == []
+    assert repaired == history
+
+
+def test_repair_synthesizes_missing_tool_result() -> None:
+    history = [_user("hi"), _assistant("a", "b"), _tool("a"), _user("next")]
+
+    repaired, repairs = repair_tool_messages(history)
+
+    assert repairs == ["synthesized missing tool result for 'b'"]
+    assert repaired[:3] == history[:3]
+    assert repaired[3] == Message(
+        role="tool", content=[system(TOOL_RESULT_LOST_MESSAGE)], tool_call_id="b"
+    )
+    assert repaired[4] == history[3]
+    _assert_provider_accepts(repaired)
+
+
+def test_repair_drops_orphan_tool_result() -> None:
+    history = [_user("hi"), _tool("ghost"), _assistant("a"), _tool("a"), _tool("a")]
+
+    repaired, repairs = repair_tool_messages(history)
+
+    assert repairs == ["dropped orphan tool result 'ghost'", "dropped orphan tool result 'a'"]
+    assert repaired == [history[0], history[2], history[3]]
+    _assert_provider_accepts(repaired)
+
+
+def test_repair_moves_interleaved_tool_results() -> None:
+    history = [_user("hi"), _assistant("a", "b"), _tool("b"), _user("steer"), _tool("a")]
+
+    repaired, repairs = repair_tool_messages(history)
+
+    assert repairs == [
+        "moved tool result 'a' after its tool call",
+        "moved tool result 'b' after its tool call",
+    ]
+    assert repaired == [history[0], history[1], history[4], history[2], history[3]]
+    _assert_provider_accepts(repaired)
+
+
+def test_repair_does_not_match_results_across_steps() -> None:
+    # Tool call IDs such as `Shell:0` are reused across steps.
+    history = [_user("hi"), _assistant("Shell:0"), _assistant("Shell:0"), _tool("Shell:0")]
+
+    repaired, repairs = repair_tool_messages(history)
+
+    assert repairs == ["synthesized missing tool result for 'Shell:0'"]
+    assert [message.role for message in repaired] == [
+        "user",
+        "assistant",
+        "tool",
+        "assistant",
+        "tool",
+    ]
+    assert repaired[4] == history[3]
+    _assert_provider_accepts(repaired)
+
+
+@pytest.mark.asyncio
+async def test_restore_repairs_history(tmp_path: Path) -> None:
+    path = tmp_path / "context.jsonl"
+    history = [_user("hi"), _assistant("a"), _user("interrupted"), _tool("orphan")]
+    path.write_text(
+        "".join(message.model_dump_json(exclude_none=True) + "\n" for message in history),
+        encoding="utf-8",
+    )
+    ctx = Context(file_backend=path, session_id="session-1")
+
+    await ctx.restore()
+
+    assert [message.role for message in ctx.history] == ["user", "assistant", "tool", "user"]
+    assert ctx.history[2].tool_call_id == "a"
+    _assert_provider_accepts(ctx.history)
+    # The file is left untouched.
+    lines = [json.loads(line) for line in path.read_text(encoding="utf-8").splitlines()]
+    assert [line["role"] for line in lines] == ["user", "assistant", "user", "tool"]
+    assert ctx.validate_and_repair() == 0
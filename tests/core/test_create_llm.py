@@ -1,13 +1,27 @@
 from __future__ import annotations
 
+from pathlib import Path
+from unittest.mock import AsyncMock
+
 from inline_snapshot import snapshot
+from kosong import generate
+from kosong.chat_provider.cache import CachingChatProvider
 from kosong.chat_provider.echo import EchoChatProvider
 from kosong.chat_provider.kimi import Kimi
 from kosong.contrib.chat_provider.openai_responses import OpenAIResponses
+from kosong.message import Message
 from pydantic import SecretStr
 
-from kimi_cli.config import LLMModel, LLMProvider
-from kimi_cli.llm import augment_provider_with_env_vars, create_llm
+import kimi_cli.soul.agent as agent_module
+from kimi_cli.auth.oauth import OAuthManager
+from kimi_cli.config import LLMModel, LLMProvider, RateLimitConfig
+from kimi_cli.llm import (
+    LLMCassette,
+    augment_provider_with_env_vars,
+    create_llm,
+    get_rate_limiter,
+)
+from kimi_cli.soul.agent import Runtime
 
 
 def test_augment_provider_with_env_vars_kimi(monkeypatch):
@@ -310,6 +324,37 @@ def test_create_llm_vertexai_custom_headers():
     assert http_options.headers.get("X-Custom") == "value"
 
 
+def test_create_llm_anthropic_and_google_genai_use_network_settings():
+    from kosong.contrib.chat_provider.anthropic import Anthropic
+    from kosong.contrib.chat_provider.google_genai import GoogleGenAI
+
+    from kimi_cli.utils.network import ProxyRoutingTransport
+
+    anthropic_llm = create_llm(
+        LLMProvider(
+            type="anthropic", base_url="https://api.anthropic.com", api_key=SecretStr("test-key")
+        ),
+        LLMModel(provider="anthropic", model="claude-sonnet-4-20250514", max_context_size=200000),
+    )
+    assert anthropic_llm is not None
+    assert isinstance(anthropic_llm.chat_provider, Anthropic)
+    assert isinstance(anthropic_llm.chat_provider._client._client._transport, ProxyRoutingTransport)
+
+    gemini_llm = create_llm(
+        LLMProvider(
+            type="gemini",
+            base_url="https://generativelanguage.googleapis.com",
+            api_key=SecretStr("test-key"),
+        ),
+        LLMModel(provider="gemini", model="gemini-2.5-pro", max_context_size=1000000),
+    )
+    assert gemini_llm is not None
+    assert isinstance(gemini_llm.chat_provider, GoogleGenAI)
+    http_options = gemini_llm.chat_provider._client._api_client._http_options
+    assert http_options.httpx_async_client is not None
+    assert isinstance(http_options.httpx_async_client._transport, ProxyRoutingTransport)
+
+
 def test_create_llm_custom_headers_isolated_between_instances():
     """Mutating headers on one instance must not affect another created from the same provider."""
     from kosong.contrib.chat_provider.openai_legacy import OpenAILegacy
@@ -542,3 +587,70 @@ def test_create_llm_kimi_thinking_keep_injected_on_explicit_thinking_true(monkey
     assert llm.chat_provider.model_parameters.get("extra_body") == snapshot(
         {"thinking": {"type": "enabled", "keep": "all"}}
     )
+
+
+def test_get_rate_limiter_is_shared_per_provider_and_model():
+    provider = LLMProvider(type="_echo", base_url="", api_key=SecretStr(""))
+    config = RateLimitConfig(max_concurrent_requests=2, requests_per_minute=30)
+    main = create_llm(provider, LLMModel(provider="limited", model="a", max_context_size=1))
+    subagent = create_llm(provider, LLMModel(provider="limited", model="a", max_context_size=1))
+    other = create_llm(provider, LLMModel(provider="limited", model="b", max_context_size=1))
+    assert main is not None and subagent is not None and other is not None
+
+    limiter = get_rate_limiter(main, config)
+    assert get_rate_limiter(subagent, RateLimitConfig()) is limiter
+    assert get_rate_limiter(other, config) is not limiter
+    assert limiter.state().concurrency_limit == 2
+
+
+async def test_create_llm_records_and_replays_a_cassette(tmp_path: Path, monkeypatch):
+    history = [Message(role="user", content="text: Hello from the cassette")]
+    model = LLMModel(provider="kimi", model="kimi-base", max_context_size=4096)
+
+    echo = LLMProvider(type="_echo", base_url="", api_key=SecretStr(""))
+    recorder = create_llm(echo, model, cassette=LLMCassette(tmp_path, "record"))
+    assert recorder is not None
+    assert isinstance(recorder.chat_provider, CachingChatProvider)
+    recorded = await generate(recorder.chat_provider, "", [], history)
+
+    def _no_network(*args, **kwargs):
+        raise AssertionError("the provider must not be created when replaying")
+
+    monkeypatch.setattr(Kimi, "__init__", _no_network)
+    kimi = LLMProvider(type="kimi", base_url="https://api.test/v1", api_key=SecretStr("key"))
+    replayer = create_llm(kimi, model, cassette=LLMCassette(tmp_path, "replay"))
+    assert replayer is not None
+    assert replayer.model_name == "kimi-base"
+    replayed = await generate(replayer.chat_provider, "", [], history)
+
+    assert replayed.message == recorded.message
+    assert replayed.message.extract_text() == "Hello from the cassette"
+
+
+async def test_cassette_replays_a_session_recorded_by_another_session(
+    tmp_path: Path, monkeypatch, config, session, environment
+):
+    monkeypatch.setattr(agent_module, "list_directory", AsyncMock(return_value=""))
+    monkeypatch.setattr(agent_module, "load_agents_md", AsyncMock(return_value=None))
+    monkeypatch.setattr(agent_module.Environment, "detect", AsyncMock(return_value=environment))
+    monkeypatch.setattr(agent_module, "resolve_skills_roots", AsyncMock(return_value=[]))
+    monkeypatch.setattr(agent_module, "discover_skills_from_roots", AsyncMock(return_value=[]))
+    history = [Message(role="user", content="text: What time is it?")]
+    model = LLMModel(provider="kimi", model="kimi-base", max_context_size=4096)
+    cassette_dir = tmp_path / "cassette"
+
+    echo = LLMProvider(type="_echo", base_url="", api_key=SecretStr(""))
+    recorder = create_llm(echo, model, cassette=LLMCassette(cassette_dir, "record"))
+    assert recorder is not None
+    first = await Runtime.create(config, OAuthManager(config), recorder, session, yolo=False)
+    system_prompt = f"Now: {first.builtin_args.KIMI_NOW}"
+    recorded = await generate(recorder.chat_provider, system_prompt, [], history)
+
+    replayer = create_llm(echo, model, cassette=LLMCassette(cassette_dir, "replay"))
+    assert replayer is not None
+    second = await Runtime.create(config, OAuthManager(config), replayer, session, yolo=False)
+    assert second.builtin_args.KIMI_NOW == first.builtin_args.KIMI_NOW
+    system_prompt = f"Now: {second.builtin_args.KIMI_NOW}"
+    replayed = await generate(replayer.chat_provider, system_prompt, [], history)
+
+    assert replayed.message == recorded.message
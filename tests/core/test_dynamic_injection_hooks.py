@@ -9,7 +9,7 @@ from unittest.mock import AsyncMock, MagicMock, patch
 from kosong.tooling.empty import EmptyToolset
 
 from kimi_cli.soul.agent import Agent, Runtime
-from kimi_cli.soul.context import Context
+from kimi_cli.soul.context import CompactionMarker, Context
 from kimi_cli.soul.dynamic_injection import DynamicInjection, DynamicInjectionProvider
 from kimi_cli.soul.kimisoul import KimiSoul
 
@@ -78,6 +78,9 @@ def _make_compactable_soul() -> Any:
     ctx.write_system_prompt = AsyncMock()
     ctx.append_message = AsyncMock()
     ctx.update_token_count = AsyncMock()
+    ctx.write_compaction_marker = AsyncMock(
+        return_value=CompactionMarker(id="marker", n_summarized=0, before_tokens=0, after_tokens=0)
+    )
     soul._context = ctx
 
     soul._hook_engine = MagicMock()
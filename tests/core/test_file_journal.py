@@ -0,0 +1,196 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+from kaos.path import KaosPath
+from kosong.chat_provider.echo import ScriptBuilder
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.config import FileJournalConfig
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.file_journal import FileJournal
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.tools.dmail import SendDMail
+from kimi_cli.tools.file import WriteFile
+
+
+def _journal(tmp_path: Path, work_dir: KaosPath, **config: int) -> FileJournal:
+    return FileJournal(tmp_path / "file_journal", work_dir, FileJournalConfig(**config))
+
+
+async def test_restore_brings_files_back_to_checkpoint(
+    tmp_path: Path, temp_work_dir: KaosPath
+) -> None:
+    journal = _journal(tmp_path, temp_work_dir)
+    existing = temp_work_dir / "existing.txt"
+    created = temp_work_dir / "created.txt"
+    await existing.write_text("original")
+
+    journal.begin_checkpoint(0)
+    journal.begin_checkpoint(1)
+    await journal.record(existing)
+    await existing.write_text("first edit")
+    await journal.record(existing)  # only the first change in a checkpoint is recorded
+    await existing.write_text("second edit")
+    journal.begin_checkpoint(2)
+    await journal.record(existing)
+    await existing.write_text("third edit")
+    await journal.record(created)
+    await created.write_text("new file")
+
+    assert journal.changed_paths(1) == [str(existing), str(created)]
+    assert journal.changed_paths(2) == [str(existing), str(created)]
+
+    assert await journal.restore(1) == [str(existing), str(created)]
+    assert await existing.read_text() == "original"
+    assert not await created.exists()
+    assert journal.changed_paths(0) == []
+
+
+async def test_restore_keeps_earlier_checkpoints(tmp_path: Path, temp_work_dir: KaosPath) -> None:
+    journal = _journal(tmp_path, temp_work_dir)
+    file = temp_work_dir / "file.txt"
+    await file.write_text("v0")
+
+    journal.begin_checkpoint(0)
+    await journal.record(file)
+    await file.write_text("v1")
+    journal.begin_checkpoint(1)
+    await journal.record(file)
+    await file.write_text("v2")
+
+    assert await journal.restore(1) == [str(file)]
+    assert await file.read_text() == "v1"
+    assert journal.changed_paths(0) == [str(file)]
+
+
+async def test_new_checkpoint_prunes_discarded_journals(
+    tmp_path: Path, temp_work_dir: KaosPath
+) -> None:
+    journal = _journal(tmp_path, temp_work_dir)
+    file = temp_work_dir / "file.txt"
+    await file.write_text("v0")
+
+    journal.begin_checkpoint(0)
+    journal.begin_checkpoint(1)
+    await journal.record(file)
+    # e.g. after the context is cleared or compacted
+    journal.begin_checkpoint(0)
+
+    assert journal.changed_paths(0) == []
+    assert not (tmp_path / "file_journal" / "1").exists()
+
+
+async def test_record_ignores_files_outside_work_dir(
+    tmp_path: Path, temp_work_dir: KaosPath
+) -> None:
+    journal = _journal(tmp_path, temp_work_dir)
+    outside = KaosPath.unsafe_from_local_path(tmp_path / "outside.txt")
+    await outside.write_text("outside")
+
+    journal.begin_checkpoint(0)
+    await journal.record(outside)
+
+    assert journal.changed_paths(0) == []
+
+
+async def test_files_over_the_cap_are_not_restored(tmp_path: Path, temp_work_dir: KaosPath) -> None:
+    journal = _journal(tmp_path, temp_work_dir, max_file_size=4)
+    small = temp_work_dir / "small.txt"
+    large = temp_work_dir / "large.txt"
+    await small.write_text("tiny")
+    await large.write_text("too large")
+
+    journal.begin_checkpoint(0)
+    await journal.record(small)
+    await journal.record(large)
+    await small.write_text("changed")
+    await large.write_text("changed")
+
+    assert journal.changed_paths(0) == [str(small), str(large)]
+    assert await journal.restore(0) == [str(small)]
+    assert await small.read_text() == "tiny"
+    assert await large.read_text() == "changed"
+
+
+async def test_record_appends_without_reading_the_index(
+    tmp_path: Path, temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    journal = _journal(tmp_path, temp_work_dir)
+    first = temp_work_dir / "first.txt"
+    second = temp_work_dir / "second.txt"
+    created = temp_work_dir / "created.txt"
+    await first.write_text("first")
+    await second.write_text("second")
+
+    journal.begin_checkpoint(0)
+    monkeypatch.setattr(journal, "_read_index", lambda _dir: pytest.fail("index was read"))
+    for path in (first, created, second):
+        await journal.record(path)
+    monkeypatch.undo()
+
+    index = (tmp_path / "file_journal" / "0" / "index.jsonl").read_text(encoding="utf-8")
+    assert [json.loads(line) for line in index.splitlines()] == [
+        {"path": str(first), "state": "saved", "blob": "0"},
+        {"path": str(created), "state": "absent"},
+        {"path": str(second), "state": "saved", "blob": "2"},
+    ]
+    await second.write_text("changed")
+    assert await journal.restore(0) == [str(first), str(created), str(second)]
+    assert await second.read_text() == "second"
+
+
+async def test_dmail_restores_files_written_after_checkpoint(
+    runtime: Runtime,
+    temp_work_dir: KaosPath,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    target = temp_work_dir / "notes.txt"
+    await target.write_text("original")
+    await runtime.file_tracker.record(target)
+    runtime.file_journal = _journal(tmp_path, temp_work_dir)
+
+    # Checkpoint 0 is taken for the user message, then one per step.
+    provider = (
+        ScriptBuilder()
+        .tool_call("WriteFile", {"path": str(target), "content": "rewritten"}, id="write-1")
+        .turn()
+        .tool_call(
+            "SendDMail",
+            {"message": "Do not touch notes.txt.", "checkpoint_id": 1, "restore_files": True},
+            id="dmail-1",
+        )
+        .turn()
+        .text("Done.")
+        .build()
+    )
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    toolset = KimiToolset()
+    toolset.add(WriteFile(runtime, runtime.approval))
+    toolset.add(SendDMail(runtime.denwa_renji, runtime))
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+
+    await soul.run("Rewrite the notes.")
+
+    assert await target.read_text() == "original"
+    texts = [message.extract_text() for message in soul.context.history]
+    assert not any("rewritten" in text for text in texts)
+    assert any("restored to their content at this checkpoint" in text for text in texts)
+    assert texts[-1] == "Done."
+
+    [notice] = runtime.notifications.claim_for_sink("wire")
+    assert notice.event.type == "files_restored"
+    assert notice.event.payload == {"checkpoint_id": 1, "paths": [str(target)]}
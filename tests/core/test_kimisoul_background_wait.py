@@ -19,6 +19,7 @@ When background agents are still running after ``run_soul()`` completes a turn,
 from __future__ import annotations
 
 import asyncio
+import signal
 from pathlib import Path
 from unittest.mock import AsyncMock, MagicMock, patch
 
@@ -375,13 +376,13 @@ async def test_print_skips_wait_when_keep_alive_on_exit_enabled(tmp_path: Path)
 
 
 # ---------------------------------------------------------------------------
-# Cancellation → FAILURE, not SUCCESS
+# Cancellation → the signal's exit code, not SUCCESS
 # ---------------------------------------------------------------------------
 
 
 @pytest.mark.asyncio
-async def test_print_background_wait_cancel_returns_failure(tmp_path: Path) -> None:
-    """Ctrl+C during background wait should exit and return FAILURE."""
+async def test_print_background_wait_cancel_returns_signal_exit_code(tmp_path: Path) -> None:
+    """Ctrl+C during background wait should exit and return 128 + SIGINT."""
     state = _FakeState(active=True, pending=False)
     manager, notifications = _wire_manager(state)
 
@@ -392,7 +393,7 @@ async def test_print_background_wait_cancel_returns_failure(tmp_path: Path) -> N
 
     with (
         patch("kimi_cli.ui.print.run_soul", side_effect=fake_run_soul),
-        patch("kimi_cli.ui.print.install_sigint_handler") as mock_sigint,
+        patch("kimi_cli.ui.print.install_shutdown_handler") as mock_sigint,
     ):
         cancel_handler = None
 
@@ -407,12 +408,12 @@ async def test_print_background_wait_cancel_returns_failure(tmp_path: Path) -> N
             task = asyncio.create_task(p.run(command="test"))
             await asyncio.sleep(0.05)
             if cancel_handler:
-                cancel_handler()
+                cancel_handler(signal.SIGINT)
             return await asyncio.wait_for(task, timeout=5.0)
 
         code = await run_with_cancel()
 
-    assert code == ExitCode.FAILURE
+    assert code == 130
 
 
 # ---------------------------------------------------------------------------
@@ -0,0 +1,68 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.config import LLMModel
+from kimi_cli.llm import LLM
+from kimi_cli.soul import MaxMessageSizeExceeded
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.types import StepInterrupted, TextPart
+
+
+async def test_runaway_message_stops_turn(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    provider = ScriptBuilder().text("a" * 800).text("b" * 800).text("c" * 800).build()
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    runtime.config.loop_control.max_message_chars = 1000
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    sent: list[object] = []
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    monkeypatch.setattr(kimisoul_module, "wire_send", sent.append)
+
+    with pytest.raises(MaxMessageSizeExceeded, match="exceeded 1,000 characters"):
+        await soul.run("Repeat yourself.")
+
+    assert [message.role for message in soul.context.history] == ["user"]
+    assert [msg for msg in sent if isinstance(msg, TextPart)] == [TextPart(text="a" * 800)]
+    assert StepInterrupted() in sent
+
+
+def test_model_max_output_tokens_overrides_generation_default(runtime: Runtime) -> None:
+    runtime.config.generation.max_output_tokens = 4096
+    assert runtime.generate_options is not None
+    assert runtime.generate_options.max_output_tokens == 4096
+
+    assert runtime.llm is not None
+    runtime.llm.model_config = LLMModel(
+        provider="kimi", model="kimi-k2", max_context_size=100_000, max_output_tokens=1234
+    )
+    assert runtime.generate_options.max_output_tokens == 1234
+    assert runtime.generate_options.parallel_tool_calls is None
+
+
+def test_model_parallel_tool_calls(runtime: Runtime) -> None:
+    assert runtime.llm is not None
+    runtime.llm.model_config = LLMModel(
+        provider="kimi", model="kimi-k2", max_context_size=100_000, parallel_tool_calls=False
+    )
+    assert runtime.generate_options is not None
+    assert runtime.generate_options.parallel_tool_calls is False
@@ -1,24 +1,33 @@
 from __future__ import annotations
 
 import asyncio
+import platform
 from collections.abc import AsyncIterator, Sequence
 from pathlib import Path
 from typing import Self, TypeVar
 
 import pytest
 from inline_snapshot import Snapshot, snapshot
-from kosong.chat_provider import StreamedMessagePart, ThinkingEffort, TokenUsage
+from kosong.chat_provider import (
+    GenerateOptions,
+    StreamedMessagePart,
+    ThinkingEffort,
+    TokenUsage,
+    ToolChoice,
+)
 from kosong.message import ContentPart, ImageURLPart, Message, TextPart, ToolCall
 from kosong.tooling import CallableTool2, Tool, ToolResult, ToolReturnValue, Toolset
 from kosong.tooling.simple import SimpleToolset
 from pydantic import BaseModel
 
+import kimi_cli.soul.ralph as ralph_module
 from kimi_cli.llm import LLM, ModelCapability
 from kimi_cli.soul import run_soul
 from kimi_cli.soul.agent import Agent, Runtime
 from kimi_cli.soul.approval import Approval
 from kimi_cli.soul.context import Context
 from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.ralph import SuccessCheck
 from kimi_cli.tools.utils import ToolRejectedError
 from kimi_cli.utils.aioqueue import QueueShutDown
 from kimi_cli.wire import Wire
@@ -385,3 +394,114 @@ async def test_ralph_loop_disabled_skips_loop_prompt(runtime: Runtime, tmp_path:
             ]
         ),
     )
+
+
+class ToolChoiceRecordingChatProvider(SequenceChatProvider):
+    def __init__(self, sequences: Sequence[Sequence[StreamedMessagePart]]) -> None:
+        super().__init__(sequences)
+        self.tool_choices: list[ToolChoice] = []
+
+    async def generate(
+        self,
+        system_prompt: str,
+        tools: Sequence[Tool],
+        history: Sequence[Message],
+        options: GenerateOptions | None = None,
+    ) -> SequenceStreamedMessage:
+        self.tool_choices.append(options.tool_choice if options is not None else "auto")
+        return await super().generate(system_prompt, tools, history)
+
+
+@pytest.mark.asyncio
+async def test_ralph_loop_forbids_tools_when_asking_again_for_a_choice(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    runtime.config.loop_control.max_ralph_iterations = -1
+    provider = ToolChoiceRecordingChatProvider(
+        [
+            [TextPart(text="first")],
+            [TextPart(text="still working")],
+            [TextPart(text="<choice>STOP</choice>")],
+        ]
+    )
+    llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    soul, context = _make_soul(runtime, llm, SimpleToolset(), tmp_path)
+
+    await _run_and_collect_turns(soul, "do it")
+
+    assert provider.tool_choices == ["auto", "auto", "none"]
+    assert context.history[-1] == Message(
+        role="assistant", content=[TextPart(text="<choice>STOP</choice>")]
+    )
+
+
+def _user_texts(context: Context) -> list[str]:
+    return [message.extract_text("") for message in context.history if message.role == "user"]
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="Success command tests run on Unix.")
+@pytest.mark.asyncio
+async def test_ralph_loop_stops_when_success_command_passes(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    script = tmp_path / "check.sh"
+    script.write_text(
+        'n=$(cat "$0.count" 2>/dev/null || echo 0)\n'
+        "n=$((n + 1))\n"
+        'echo "$n" > "$0.count"\n'
+        'echo "run $n"\n'
+        '[ "$n" -ge 3 ]\n'
+    )
+    runtime.config.loop_control.max_ralph_iterations = -1
+    runtime.config.loop_control.ralph_success_command = f"sh {script}"
+
+    llm = _make_llm([[TextPart(text="working")]], set())
+    soul, context = _make_soul(runtime, llm, SimpleToolset(), tmp_path)
+
+    await _run_and_collect_turns(soul, "make the tests pass")
+
+    prompts = _user_texts(context)
+    assert len(prompts) == 3
+    assert prompts[0] == "make the tests pass"
+    assert f"until `sh {script}` succeeds" in prompts[1]
+    assert "<choice>" not in prompts[1]
+    assert f"<system>The success command `sh {script}` failed with exit code 1." in prompts[1]
+    assert prompts[1].endswith("Its output was:\nrun 1</system>")
+    assert prompts[2].endswith("Its output was:\nrun 2</system>")
+    assert (tmp_path / "check.sh.count").read_text().strip() == "3"
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="Success command tests run on Unix.")
+@pytest.mark.asyncio
+async def test_ralph_loop_stops_when_success_command_output_does_not_change(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    runtime.config.loop_control.max_ralph_iterations = -1
+    runtime.config.loop_control.ralph_success_command = "echo '2 tests failed'; exit 1"
+    runtime.config.loop_control.ralph_failure_limit = 3
+
+    llm = _make_llm([[TextPart(text="working")]], set())
+    soul, context = _make_soul(runtime, llm, SimpleToolset(), tmp_path)
+
+    await _run_and_collect_turns(soul, "make the tests pass")
+
+    prompts = _user_texts(context)
+    assert len(prompts) == 3
+    for prompt in prompts[1:]:
+        assert prompt.endswith("Its output was:\n2 tests failed</system>")
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="Success command tests run on Unix.")
+@pytest.mark.asyncio
+async def test_success_command_timeout_counts_as_failure(
+    runtime: Runtime, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(ralph_module, "SUCCESS_COMMAND_TIMEOUT", 0.2)
+    check = SuccessCheck("sleep 5", runtime.environment.shell_path, failure_limit=2)
+
+    first = await check.run()
+    second = await check.run()
+
+    assert not first.passed and not first.stalled
+    assert first.note == "The success command `sleep 5` timed out after 0.2s."
+    assert not second.passed and second.stalled
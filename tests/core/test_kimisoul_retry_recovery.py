@@ -215,6 +215,44 @@ class PartialThenErrorStreamedMessage:
         return None
 
 
+class PartialContinuationProvider:
+    name = "partial-continuation"
+
+    def __init__(self, *, supports_partial: bool) -> None:
+        self.histories: list[list[Message]] = []
+        self._supports_partial = supports_partial
+
+    @property
+    def model_name(self) -> str:
+        return "partial-continuation"
+
+    @property
+    def thinking_effort(self) -> ThinkingEffort | None:
+        return None
+
+    async def generate(
+        self,
+        system_prompt: str,
+        tools: Sequence[Tool],
+        history: Sequence[Message],
+    ) -> StaticStreamedMessage | PartialThenErrorStreamedMessage:
+        self.histories.append(list(history))
+        if len(self.histories) == 1:
+            return PartialThenErrorStreamedMessage(
+                [TextPart(text="Hello, "), TextPart(text="wor")],
+                APIStatusError(503, "Status 503"),
+            )
+        if history[-1].partial:
+            return StaticStreamedMessage([TextPart(text="ld!")])
+        return StaticStreamedMessage([TextPart(text="Hello, world!")])
+
+    def supports_partial_continuation(self) -> bool:
+        return self._supports_partial
+
+    def with_thinking(self, effort: ThinkingEffort) -> Self:
+        return self
+
+
 class NonRetryableConnectionProvider:
     name = "non-retryable-connection"
 
@@ -424,6 +462,49 @@ async def test_step_retry_event_after_partial_stream(runtime: Runtime, tmp_path:
     assert context.history[-1].extract_text(" ").strip() == "done"
 
 
+@pytest.mark.asyncio
+async def test_step_retry_continues_partial_message(runtime: Runtime, tmp_path: Path) -> None:
+    runtime.config.loop_control.max_retries_per_step = 2
+    provider = PartialContinuationProvider(supports_partial=True)
+    llm = LLM(
+        chat_provider=provider,
+        max_context_size=100_000,
+        capabilities=set(),
+    )
+    soul, context = _make_soul(runtime, llm, tmp_path)
+
+    await run_soul(soul, "trigger continuation", _drain_ui_messages, asyncio.Event())
+
+    assert len(provider.histories) == 2
+    assert len(provider.histories[1]) == len(provider.histories[0]) + 1
+    assert provider.histories[1][-1] == Message(
+        role="assistant", content=[TextPart(text="Hello, wor")], partial=True
+    )
+    assert context.history[-1].role == "assistant"
+    assert context.history[-1].content == [TextPart(text="Hello, world!")]
+    assert not context.history[-1].partial
+
+
+@pytest.mark.asyncio
+async def test_step_retry_regenerates_without_partial_support(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    runtime.config.loop_control.max_retries_per_step = 2
+    provider = PartialContinuationProvider(supports_partial=False)
+    llm = LLM(
+        chat_provider=provider,
+        max_context_size=100_000,
+        capabilities=set(),
+    )
+    soul, context = _make_soul(runtime, llm, tmp_path)
+
+    await run_soul(soul, "trigger regeneration", _drain_ui_messages, asyncio.Event())
+
+    assert len(provider.histories) == 2
+    assert provider.histories[1] == provider.histories[0]
+    assert context.history[-1].content == [TextPart(text="Hello, world!")]
+
+
 @pytest.mark.asyncio
 async def test_step_non_retryable_provider_keeps_tenacity_connection_retries(
     runtime: Runtime, tmp_path: Path
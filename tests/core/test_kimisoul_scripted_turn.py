@@ -0,0 +1,130 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kaos.path import KaosPath
+from kosong.chat_provider.echo import ScriptBuilder
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.observer import SoulObserver, TimelineObserver
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.tools.file.read import ReadFile
+from kimi_cli.wire.types import StatusUpdate, TextPart
+
+
+def _make_soul(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch, builder: ScriptBuilder
+) -> KimiSoul:
+    runtime.llm = LLM(chat_provider=builder.build(), max_context_size=100_000, capabilities=set())
+    toolset = KimiToolset()
+    toolset.add(ReadFile(runtime))
+    agent = Agent(
+        name="Scripted Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: None)
+    return soul
+
+
+@pytest.mark.asyncio
+async def test_scripted_turn_reads_file_then_answers(
+    runtime: Runtime,
+    temp_work_dir: KaosPath,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    (temp_work_dir / "notes.txt").unsafe_to_local_path().write_text("scripted content\n")
+    builder = (
+        ScriptBuilder()
+        .text("Let me read it.")
+        .tool_call("ReadFile", {"path": "notes.txt"}, id="read-1")
+        .usage(input_other=20, output=5)
+        .turn()
+        .text("The file says: scripted content")
+        .usage(input_other=40, output=8)
+    )
+    soul = _make_soul(runtime, tmp_path, monkeypatch, builder)
+    observer = TimelineObserver()
+    soul.add_observer(observer)
+
+    await soul.run("What is in notes.txt?")
+
+    history = soul.context.history
+    assert [message.role for message in history] == ["user", "assistant", "tool", "assistant"]
+    assert history[0].extract_text() == "What is in notes.txt?"
+    assert history[1].tool_calls is not None
+    assert [call.id for call in history[1].tool_calls] == ["read-1"]
+    assert history[2].tool_call_id == "read-1"
+    assert "scripted content" in history[2].extract_text()
+    assert history[3].content == [TextPart(text="The file says: scripted content")]
+
+    assert observer.kinds() == [
+        "turn_begin",
+        "step_begin",
+        "tool_call",
+        "tool_result",
+        "step_end",
+        "step_begin",
+        "step_end",
+        "turn_end",
+    ]
+    assert observer.of_kind("turn_begin") == ["What is in notes.txt?"]
+    assert observer.of_kind("step_begin") == [1, 2]
+    assert [call.id for call in observer.of_kind("tool_call")] == ["read-1"]
+    results = observer.of_kind("tool_result")
+    assert [result.tool_call_id for result in results] == ["read-1"]
+    assert not results[0].return_value.is_error
+    assert [(n, step.message.content) for n, step, _ in observer.of_kind("step_end")] == [
+        (1, [TextPart(text="Let me read it.")]),
+        (2, [TextPart(text="The file says: scripted content")]),
+    ]
+
+
+@pytest.mark.asyncio
+async def test_failing_observer_does_not_stop_the_turn(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    class FailingObserver(SoulObserver):
+        def on_step_begin(self, n: int) -> None:
+            raise RuntimeError("observer failed")
+
+    soul = _make_soul(runtime, tmp_path, monkeypatch, ScriptBuilder().text("Done."))
+    observer = TimelineObserver()
+    soul.add_observer(FailingObserver())
+    soul.add_observer(observer)
+
+    await soul.run("Hi")
+
+    assert soul.context.history[-1].content == [TextPart(text="Done.")]
+    assert observer.kinds() == ["turn_begin", "step_begin", "step_end", "turn_end"]
+
+
+@pytest.mark.asyncio
+async def test_step_status_update_carries_streaming_metrics(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    builder = ScriptBuilder().text("Done.").usage(input_other=10, output=4)
+    soul = _make_soul(runtime, tmp_path, monkeypatch, builder)
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", sent.append)
+
+    await soul.run("Hi")
+
+    metrics = [m.step_metrics for m in sent if isinstance(m, StatusUpdate) and m.step_metrics]
+    assert len(metrics) == 1
+    assert metrics[0].part_count == 1
+    assert metrics[0].duration_ms >= metrics[0].time_to_first_part_ms >= 0
@@ -13,7 +13,9 @@ from kimi_cli.skill.flow import Flow, FlowEdge, FlowNode
 from kimi_cli.soul.agent import Agent, Runtime
 from kimi_cli.soul.context import Context
 from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.slash import help as help_slash
 from kimi_cli.utils.slashcmd import SlashCommand
+from kimi_cli.wire.types import TextPart
 
 
 def _make_flow() -> Flow:
@@ -57,6 +59,54 @@ def test_flow_skill_registers_skill_and_flow_commands(runtime: Runtime, tmp_path
     assert "flow:flow-skill" in command_names
 
 
+def test_help_lists_builtins_skills_and_flows(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    skill_dir_kp = KaosPath.unsafe_from_local_path(tmp_path)
+    runtime.skills = {
+        "review": Skill(
+            name="review",
+            description="Review the diff",
+            type="standard",
+            dir=skill_dir_kp,
+            skill_md_file=skill_dir_kp / "SKILL.md",
+            scope="user",
+        ),
+        "release": Skill(
+            name="release",
+            description="Release flow",
+            type="flow",
+            dir=skill_dir_kp,
+            skill_md_file=skill_dir_kp / "SKILL.md",
+            flow=_make_flow(),
+            scope="user",
+        ),
+    }
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+    sent: list[TextPart] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", lambda msg: sent.append(msg))
+
+    help_slash(soul, "")
+
+    assert len(sent) == 1
+    builtins, skills, flows = sent[0].text.split("\n\n")
+    assert builtins.startswith("Slash commands:\n")
+    assert "  /clear (reset): Clear the context" in builtins.splitlines()
+    assert "  /help (h, ?): List available slash commands and skills" in builtins.splitlines()
+    assert skills.splitlines() == [
+        "Skills:",
+        "  /skill:release: Release flow",
+        "  /skill:review: Review the diff",
+    ]
+    assert flows.splitlines() == ["Flows:", "  /flow:release: Release flow"]
+
+
 @pytest.mark.asyncio
 async def test_skill_slash_run_does_not_auto_generate_session_title(
     runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
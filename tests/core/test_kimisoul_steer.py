@@ -233,7 +233,7 @@ async def test_agent_loop_injects_steer_between_completed_steps(
     outcome = await soul._agent_loop()
 
     assert outcome.stop_reason == "no_tool_calls"
-    assert [msg for msg in sent if isinstance(msg, StepBegin)] == [StepBegin(n=1), StepBegin(n=2)]
+    assert [msg.n for msg in sent if isinstance(msg, StepBegin)] == [1, 2]
     assert [msg for msg in sent if isinstance(msg, SteerInput)] == [
         SteerInput(user_input="follow-up steer")
     ]
@@ -302,7 +302,7 @@ async def test_agent_loop_continues_after_tool_rejected_when_steer_is_injected(
     outcome = await soul._agent_loop()
 
     assert outcome.stop_reason == "no_tool_calls"
-    assert [msg for msg in sent if isinstance(msg, StepBegin)] == [StepBegin(n=1), StepBegin(n=2)]
+    assert [msg.n for msg in sent if isinstance(msg, StepBegin)] == [1, 2]
     assert [msg for msg in sent if isinstance(msg, SteerInput)] == [
         SteerInput(user_input="switch to a read-only approach")
     ]
@@ -463,7 +463,7 @@ async def test_run_soul_emits_steer_input_and_continues_same_turn(
     assert [msg for msg in seen if isinstance(msg, SteerInput)] == [
         SteerInput(user_input="follow-up steer")
     ]
-    assert [msg for msg in seen if isinstance(msg, StepBegin)] == [StepBegin(n=1), StepBegin(n=2)]
+    assert [msg.n for msg in seen if isinstance(msg, StepBegin)] == [1, 2]
     assert isinstance(seen[-1], TurnEnd)
 
 
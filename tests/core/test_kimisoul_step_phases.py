@@ -0,0 +1,81 @@
+from __future__ import annotations
+
+import asyncio
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.tooling import CallableTool2, ToolOk, ToolReturnValue
+from pydantic import BaseModel
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.wire.types import StatusUpdate, StepBegin
+
+
+class SlowParams(BaseModel):
+    seconds: float
+
+
+class SlowTool(CallableTool2[SlowParams]):
+    name: str = "Slow"
+    description: str = "Sleep for a while."
+    params: type[SlowParams] = SlowParams
+
+    async def __call__(self, params: SlowParams) -> ToolReturnValue:
+        await asyncio.sleep(params.seconds)
+        return ToolOk(output="awake")
+
+
+@pytest.mark.asyncio
+async def test_step_reports_phases_and_slow_tools(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    provider = (
+        ScriptBuilder()
+        .tool_call("Slow", {"seconds": 0.6}, id="slow-1")
+        .turn()
+        .text("Done.")
+        .build()
+    )
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    runtime.config.loop_control.slow_phase_threshold_s = 0.2
+    toolset = KimiToolset()
+    toolset.add(SlowTool())
+    agent = Agent(
+        name="Phases Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    sent: list[object] = []
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run("Take a nap.")
+
+    phases = [
+        (msg.phase, msg.elapsed_ms is not None)
+        for msg in sent
+        if isinstance(msg, StatusUpdate) and msg.phase is not None
+    ]
+    assert phases == [
+        ("generating", False),
+        ("running_tools", False),
+        ("running_tools", True),
+        ("generating", False),
+    ]
+    slow = next(msg for msg in sent if isinstance(msg, StatusUpdate) and msg.elapsed_ms)
+    assert slow.elapsed_ms is not None and slow.elapsed_ms >= 200
+    assert all(msg.timestamp is not None for msg in sent if isinstance(msg, StepBegin))
@@ -0,0 +1,117 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.config import StreamThinking
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.wire.types import TextPart, ThinkPart
+
+
+async def _run_turn(
+    runtime: Runtime,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+    mode: StreamThinking,
+) -> tuple[KimiSoul, list[object]]:
+    provider = (
+        ScriptBuilder()
+        .think("First, look at ")
+        .think("the parser. ")
+        .think("Then the tokenizer.")
+        .text("The parser ")
+        .text("is fine.")
+        .build()
+    )
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    runtime.stream_thinking = mode
+    agent = Agent(
+        name="Scripted Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=KimiToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    sent: list[object] = []
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run("Why are commas dropped?")
+    return soul, sent
+
+
+def _context_thinking(soul: KimiSoul) -> str:
+    assistant = soul.context.history[-1]
+    return "".join(part.think for part in assistant.content if isinstance(part, ThinkPart))
+
+
+@pytest.mark.asyncio
+async def test_full_forwards_every_think_part(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul, sent = await _run_turn(runtime, tmp_path, monkeypatch, "full")
+
+    assert [msg for msg in sent if isinstance(msg, ThinkPart)] == [
+        ThinkPart(think="First, look at "),
+        ThinkPart(think="the parser. "),
+        ThinkPart(think="Then the tokenizer."),
+    ]
+    assert _context_thinking(soul) == "First, look at the parser. Then the tokenizer."
+
+
+@pytest.mark.asyncio
+async def test_summary_forwards_one_truncated_part_per_step(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(kimisoul_module, "THINKING_SUMMARY_CHARS", 20)
+
+    soul, sent = await _run_turn(runtime, tmp_path, monkeypatch, "summary")
+
+    parts = [msg for msg in sent if isinstance(msg, ThinkPart | TextPart)]
+    assert parts == [
+        ThinkPart(think="First, look at the p"),
+        TextPart(text="The parser "),
+        TextPart(text="is fine."),
+    ]
+    assert _context_thinking(soul) == "First, look at the parser. Then the tokenizer."
+
+
+@pytest.mark.asyncio
+async def test_summary_batches_short_thinking_before_the_text(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    _, sent = await _run_turn(runtime, tmp_path, monkeypatch, "summary")
+
+    parts = [msg for msg in sent if isinstance(msg, ThinkPart | TextPart)]
+    assert parts == [
+        ThinkPart(think="First, look at the parser. Then the tokenizer."),
+        TextPart(text="The parser "),
+        TextPart(text="is fine."),
+    ]
+
+
+@pytest.mark.asyncio
+async def test_off_forwards_no_thinking_but_keeps_it_in_context(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul, sent = await _run_turn(runtime, tmp_path, monkeypatch, "off")
+
+    assert not [msg for msg in sent if isinstance(msg, ThinkPart)]
+    assert [msg for msg in sent if isinstance(msg, TextPart)] == [
+        TextPart(text="The parser "),
+        TextPart(text="is fine."),
+    ]
+    assert _context_thinking(soul) == "First, look at the parser. Then the tokenizer."
@@ -55,7 +55,7 @@ async def test_run_emits_turn_end_when_step_interrupts(
         await soul.run("hello")
 
     assert [msg for msg in sent if isinstance(msg, TurnBegin)] == [TurnBegin(user_input="hello")]
-    assert [msg for msg in sent if isinstance(msg, StepBegin)] == [StepBegin(n=1)]
+    assert [msg.n for msg in sent if isinstance(msg, StepBegin)] == [1]
     assert [msg for msg in sent if isinstance(msg, StepInterrupted)] == [StepInterrupted()]
     assert [msg for msg in sent if isinstance(msg, TurnEnd)] == [TurnEnd()]
     assert isinstance(sent[-1], TurnEnd)
@@ -85,7 +85,7 @@ async def test_run_emits_turn_end_on_cancelled_error(
         await soul.run("hello")
 
     assert [msg for msg in sent if isinstance(msg, TurnBegin)] == [TurnBegin(user_input="hello")]
-    assert [msg for msg in sent if isinstance(msg, StepBegin)] == [StepBegin(n=1)]
+    assert [msg.n for msg in sent if isinstance(msg, StepBegin)] == [1]
     assert [msg for msg in sent if isinstance(msg, StepInterrupted)] == []
     assert [msg for msg in sent if isinstance(msg, TurnEnd)] == [TurnEnd()]
     assert isinstance(sent[-1], TurnEnd)
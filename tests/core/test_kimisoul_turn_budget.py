@@ -0,0 +1,106 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.config import LLMModel, ModelPricing
+from kimi_cli.llm import LLM
+from kimi_cli.soul import BudgetExceeded
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.message import is_system_reminder_message
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.tools.think import Think
+
+
+def _make_soul(runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> KimiSoul:
+    builder = ScriptBuilder()
+    for i in range(3):
+        builder.tool_call("Think", {"thought": f"step {i}"}, id=f"think-{i}")
+        builder.usage(input_other=600, output=100)
+        builder.turn()
+    provider = builder.text("Done.").build()
+    runtime.llm = LLM(
+        chat_provider=provider,
+        max_context_size=100_000,
+        capabilities=set(),
+        model_config=LLMModel(
+            provider="kimi",
+            model="kimi-k2",
+            max_context_size=100_000,
+            pricing=ModelPricing(input=1000, output=2000),
+        ),
+    )
+    toolset = KimiToolset()
+    toolset.add(Think())
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+    return soul
+
+
+async def test_turn_stops_when_token_budget_is_exceeded(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    runtime.config.loop_control.max_tokens_per_turn = 1000
+    soul = _make_soul(runtime, tmp_path, monkeypatch)
+
+    with pytest.raises(BudgetExceeded, match="1,400 tokens used, limit 1,000") as exc_info:
+        await soul.run("Think hard.")
+
+    assert exc_info.value.kind == "tokens"
+    assert exc_info.value.tokens == 1400
+    history = soul.context.history
+    assert [message.role for message in history] == [
+        "user",
+        "assistant",
+        "tool",
+        "assistant",
+        "tool",
+        "user",
+    ]
+    assert is_system_reminder_message(history[-1])
+    assert "Turn budget exceeded" in history[-1].extract_text()
+
+
+async def test_turn_stops_when_cost_budget_is_exceeded(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    # Each step costs 600 * $1000/M + 100 * $2000/M = $0.80.
+    runtime.config.loop_control.max_cost_per_turn = 2.0
+    soul = _make_soul(runtime, tmp_path, monkeypatch)
+
+    with pytest.raises(BudgetExceeded, match=r"\$2\.4000 spent, limit \$2\.0000") as exc_info:
+        await soul.run("Think hard.")
+
+    assert exc_info.value.kind == "cost"
+    assert exc_info.value.cost == pytest.approx(2.4)
+    assert [message.role for message in soul.context.history].count("assistant") == 3
+    assert is_system_reminder_message(soul.context.history[-1])
+
+
+async def test_turn_within_budget_finishes(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    runtime.config.loop_control.max_tokens_per_turn = 10_000
+    runtime.config.loop_control.max_cost_per_turn = 10.0
+    soul = _make_soul(runtime, tmp_path, monkeypatch)
+
+    await soul.run("Think hard.")
+
+    assert soul.context.history[-1].extract_text() == "Done."
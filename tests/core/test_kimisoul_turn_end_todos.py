@@ -0,0 +1,122 @@
+"""Tests for reporting unfinished todo items at the end of a turn."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.message import Message
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.session_state import TodoItemState
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import FlowRunner, KimiSoul, TurnOutcome
+from kimi_cli.wire.types import TodoDisplayItem, TurnEnd
+
+
+def _make_soul(runtime: Runtime, tmp_path: Path) -> KimiSoul:
+    agent = Agent(
+        name="Turn End Todos Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+
+def _save_todos(runtime: Runtime) -> None:
+    runtime.session.state.todos = [
+        TodoItemState(title="Write the parser", status="done"),
+        TodoItemState(title="Write the tests", status="in_progress"),
+        TodoItemState(title="Update the docs", status="pending"),
+    ]
+    runtime.session.save_state()
+
+
+def _stub_turn(
+    soul: KimiSoul, monkeypatch: pytest.MonkeyPatch, outcome: TurnOutcome
+) -> list[Message]:
+    prompts: list[Message] = []
+
+    async def fake_turn(user_message: Message) -> TurnOutcome:
+        prompts.append(user_message)
+        return outcome
+
+    monkeypatch.setattr(soul, "_turn", fake_turn)
+    return prompts
+
+
+@pytest.mark.asyncio
+async def test_turn_end_reports_unfinished_todos(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = _make_soul(runtime, tmp_path)
+    _save_todos(runtime)
+    _stub_turn(soul, monkeypatch, TurnOutcome("no_tool_calls", None, 1))
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run("hello")
+
+    assert [msg for msg in sent if isinstance(msg, TurnEnd)] == [
+        TurnEnd(
+            todos_remaining=2,
+            todos=[
+                TodoDisplayItem(title="Write the tests", status="in_progress"),
+                TodoDisplayItem(title="Update the docs", status="pending"),
+            ],
+        )
+    ]
+
+
+@pytest.mark.asyncio
+async def test_turn_end_ignores_todos_of_unfinished_turns(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = _make_soul(runtime, tmp_path)
+    _save_todos(runtime)
+    _stub_turn(soul, monkeypatch, TurnOutcome("tool_rejected", None, 1))
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run("hello")
+
+    assert [msg for msg in sent if isinstance(msg, TurnEnd)] == [TurnEnd()]
+
+
+@pytest.mark.asyncio
+async def test_turn_end_without_todos_is_empty(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = _make_soul(runtime, tmp_path)
+    _stub_turn(soul, monkeypatch, TurnOutcome("no_tool_calls", None, 1))
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run("hello")
+
+    assert [msg for msg in sent if isinstance(msg, TurnEnd)] == [TurnEnd()]
+
+
+@pytest.mark.asyncio
+async def test_ralph_iteration_is_reminded_of_unfinished_todos(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = _make_soul(runtime, tmp_path)
+    _save_todos(runtime)
+    stop = Message(role="assistant", content="<choice>STOP</choice>")
+    prompts = _stub_turn(soul, monkeypatch, TurnOutcome("no_tool_calls", stop, 1))
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: None)
+
+    runner = FlowRunner.ralph_loop(Message(role="user", content="Build the parser"), 1)
+    await runner.run(soul, "")
+
+    [first, second] = [prompt.extract_text("\n") for prompt in prompts]
+    assert "unfinished" not in first
+    assert (
+        "The last iteration ended with these todo items unfinished:\n"
+        "- [in_progress] Write the tests\n"
+        "- [pending] Update the docs\n"
+    ) in second
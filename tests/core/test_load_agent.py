@@ -10,7 +10,7 @@ from typing import Any
 import pytest
 from inline_snapshot import snapshot
 
-from kimi_cli.config import Config
+from kimi_cli.config import Config, ToolsConfig
 from kimi_cli.exception import InvalidToolError, SystemPromptTemplateError
 from kimi_cli.session import Session
 from kimi_cli.soul.agent import BuiltinSystemPromptArgs, Runtime, _load_system_prompt, load_agent
@@ -150,6 +150,44 @@ def test_load_tools_valid(runtime: Runtime):
     assert len(toolset.tools) == snapshot(2)
 
 
+def test_load_tools_skips_disabled_tools(runtime: Runtime):
+    """Disabled tools are never registered, so calls to them fail with tool not found."""
+    tool_paths = ["kimi_cli.tools.think:Think", "kimi_cli.tools.shell:Shell"]
+    toolset = KimiToolset(ToolsConfig(disabled=["Shell"]))
+    toolset.load_tools(
+        tool_paths,
+        {
+            Runtime: runtime,
+            Config: runtime.config,
+            BuiltinSystemPromptArgs: runtime.builtin_args,
+            Session: runtime.session,
+            DenwaRenji: runtime.denwa_renji,
+            Approval: runtime.approval,
+            Environment: runtime.environment,
+        },
+    )
+    assert [tool.name for tool in toolset.tools] == ["Think"]
+    assert toolset.find("Shell") is None
+
+
+def test_load_tools_only_loads_enabled_tools(runtime: Runtime):
+    tool_paths = ["kimi_cli.tools.think:Think", "kimi_cli.tools.shell:Shell"]
+    toolset = KimiToolset(ToolsConfig(enabled=["Shell"]))
+    toolset.load_tools(
+        tool_paths,
+        {
+            Runtime: runtime,
+            Config: runtime.config,
+            BuiltinSystemPromptArgs: runtime.builtin_args,
+            Session: runtime.session,
+            DenwaRenji: runtime.denwa_renji,
+            Approval: runtime.approval,
+            Environment: runtime.environment,
+        },
+    )
+    assert [tool.name for tool in toolset.tools] == ["Shell"]
+
+
 def test_load_tools_invalid(runtime: Runtime):
     """Test loading with invalid tool paths."""
     tool_paths = ["kimi_cli.tools.nonexistent:Tool", "kimi_cli.tools.think:Think"]
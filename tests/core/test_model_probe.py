@@ -0,0 +1,147 @@
+from __future__ import annotations
+
+import copy
+from collections.abc import AsyncIterator, Sequence
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider import (
+    APIConnectionError,
+    APIStatusError,
+    StreamedMessagePart,
+    ThinkingEffort,
+)
+from kosong.message import ImageURLPart, Message, TextPart, ThinkPart
+
+from kimi_cli.llm import LLM, ModelCapability
+from kimi_cli.model_probe import PROBE_CACHE_FILE_NAME, PROBE_CACHE_TTL_S, verify_model
+
+
+class _ProbedStream:
+    def __init__(self, parts: list[StreamedMessagePart]):
+        self._parts = iter(parts)
+
+    def __aiter__(self) -> AsyncIterator[StreamedMessagePart]:
+        return self
+
+    async def __anext__(self) -> StreamedMessagePart:
+        try:
+            return next(self._parts)
+        except StopIteration:
+            raise StopAsyncIteration from None
+
+    @property
+    def id(self) -> str | None:
+        return "probe"
+
+    @property
+    def usage(self):
+        return None
+
+
+class _ProbedProvider:
+    """A model that always thinks and cannot see images."""
+
+    name = "probed"
+
+    def __init__(self, *, always_thinks: bool = True, sees_images: bool = False) -> None:
+        self._always_thinks = always_thinks
+        self._sees_images = sees_images
+        self._effort: ThinkingEffort | None = None
+        self.calls: list[Sequence[Message]] = []
+
+    @property
+    def model_name(self) -> str:
+        return "probed-model"
+
+    @property
+    def thinking_effort(self) -> ThinkingEffort | None:
+        return self._effort
+
+    async def generate(self, system_prompt, tools, history, *, options=None) -> _ProbedStream:
+        self.calls.append(history)
+        if not self._sees_images and any(
+            isinstance(part, ImageURLPart) for message in history for part in message.content
+        ):
+            raise APIStatusError(400, "image input is not supported")
+        parts: list[StreamedMessagePart] = []
+        if self._always_thinks or self._effort not in (None, "off"):
+            parts.append(ThinkPart(think="The user wants OK."))
+        parts.append(TextPart(text="OK"))
+        return _ProbedStream(parts)
+
+    def with_thinking(self, effort: ThinkingEffort) -> _ProbedProvider:
+        new = copy.copy(self)
+        new._effort = effort
+        return new
+
+
+def _llm(provider: _ProbedProvider, capabilities: set[ModelCapability]) -> LLM:
+    return LLM(chat_provider=provider, max_context_size=100_000, capabilities=capabilities)
+
+
+@pytest.fixture(autouse=True)
+def share_dir(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path))
+    return tmp_path
+
+
+async def test_probe_reports_declared_and_omitted_capabilities(share_dir: Path) -> None:
+    provider = _ProbedProvider()
+
+    warnings = await verify_model(_llm(provider, {"image_in"}))
+
+    assert len(warnings) == 2
+    assert '"always_thinking"' in warnings[0]
+    assert warnings[1].startswith('config declares "image_in" but the provider rejected')
+    assert "image input is not supported" in warnings[1]
+    assert (share_dir / PROBE_CACHE_FILE_NAME).exists()
+
+
+async def test_probe_passes_a_matching_config() -> None:
+    provider = _ProbedProvider(always_thinks=False, sees_images=True)
+
+    assert await verify_model(_llm(provider, {"thinking", "image_in"})) == []
+    # The plain request, the thinking request and the image request.
+    assert len(provider.calls) == 3
+
+
+async def test_probe_result_is_cached_per_model_for_a_day() -> None:
+    provider = _ProbedProvider()
+    llm = _llm(provider, {"image_in"})
+
+    warnings = await verify_model(llm, now=1000.0)
+    n_calls = len(provider.calls)
+    assert await verify_model(llm, now=1000.0 + PROBE_CACHE_TTL_S - 1) == warnings
+    assert len(provider.calls) == n_calls
+
+    # Expired, or declared differently: probed again.
+    await verify_model(llm, now=1000.0 + PROBE_CACHE_TTL_S)
+    assert len(provider.calls) > n_calls
+    n_calls = len(provider.calls)
+    fixed = _llm(provider, {"thinking", "always_thinking"})
+    assert await verify_model(fixed, now=1000.0 + PROBE_CACHE_TTL_S) == []
+    assert len(provider.calls) > n_calls
+
+
+async def test_probe_result_is_cached_per_thinking_effort() -> None:
+    provider = _ProbedProvider(always_thinks=False)
+    capabilities: set[ModelCapability] = {"thinking"}
+
+    assert await verify_model(_llm(provider, capabilities), now=1000.0) == []
+    n_calls = len(provider.calls)
+
+    thinking = _llm(provider.with_thinking("high"), capabilities)
+    assert await verify_model(thinking, now=1000.0) == []
+    assert len(provider.calls) > n_calls
+
+
+async def test_failed_probe_is_reported_and_not_cached(share_dir: Path) -> None:
+    class _Offline(_ProbedProvider):
+        async def generate(self, system_prompt, tools, history, *, options=None):
+            raise APIConnectionError("connection refused")
+
+    warnings = await verify_model(_llm(_Offline(), set()))
+
+    assert warnings == ["the model did not answer a minimal request: connection refused"]
+    assert not (share_dir / PROBE_CACHE_FILE_NAME).exists()
@@ -7,7 +7,12 @@ from pathlib import Path
 from typing import Self
 
 import pytest
-from kosong.chat_provider import StreamedMessagePart, ThinkingEffort, TokenUsage
+from kosong.chat_provider import (
+    GenerateOptions,
+    StreamedMessagePart,
+    ThinkingEffort,
+    TokenUsage,
+)
 from kosong.message import Message, TextPart
 from kosong.tooling.empty import EmptyToolset
 
@@ -65,6 +70,8 @@ class _SequenceProvider:
         system_prompt: str,
         tools: Sequence[object],
         history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
     ) -> _SequenceStream:
         return _SequenceStream(self._parts)
 
@@ -0,0 +1,129 @@
+"""Tests for validating images in user input before a turn starts."""
+
+from __future__ import annotations
+
+import base64
+from io import BytesIO
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+from PIL import Image
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.config import ImageInputConfig
+from kimi_cli.llm import LLM, ModelCapability
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.message import InvalidUserInput, prepare_user_input
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.utils.image import get_image_size, parse_data_url
+from kimi_cli.wire.types import ContentPart, ImageURLPart, StepBegin, TextPart, TurnBegin, TurnEnd
+
+
+def _png(width: int = 8, height: int = 8) -> bytes:
+    buffer = BytesIO()
+    Image.new("RGB", (width, height), color=(200, 40, 40)).save(buffer, format="PNG")
+    return buffer.getvalue()
+
+
+def _image(url: str) -> ImageURLPart:
+    return ImageURLPart(image_url=ImageURLPart.ImageURL(url=url, id="img-1"))
+
+
+def _data_url(data: bytes, mime_type: str = "image/png") -> str:
+    return f"data:{mime_type};base64,{base64.b64encode(data).decode('ascii')}"
+
+
+def _decode(part: ContentPart) -> tuple[str, bytes]:
+    assert isinstance(part, ImageURLPart)
+    parsed = parse_data_url(part.image_url.url)
+    assert parsed is not None
+    return parsed
+
+
+def test_text_and_remote_images_are_unchanged():
+    config = ImageInputConfig()
+    parts: list[ContentPart] = [TextPart(text="look"), _image("https://example.com/cat.png")]
+
+    assert prepare_user_input("hello", config) == "hello"
+    assert prepare_user_input(parts, config) == parts
+
+
+def test_data_url_mime_type_is_normalized():
+    data = _png()
+    parts: list[ContentPart] = [_image(_data_url(data, "image/jpeg"))]
+
+    [part] = prepare_user_input(parts, ImageInputConfig())
+
+    assert _decode(part) == ("image/png", data)
+    assert isinstance(part, ImageURLPart) and part.image_url.id == "img-1"
+
+
+@pytest.mark.parametrize(
+    ("url", "error"),
+    [
+        ("data:image/png;base64,not base64!", "not a valid data URL"),
+        ("data:image/png,raw", "not a valid data URL"),
+        (_data_url(b"not an image"), "could not be decoded"),
+    ],
+)
+def test_malformed_images_are_rejected(url: str, error: str):
+    with pytest.raises(InvalidUserInput, match=error):
+        prepare_user_input([_image(url)], ImageInputConfig())
+
+
+def test_oversized_images_are_downscaled():
+    config = ImageInputConfig(max_dimension=32)
+    parts: list[ContentPart] = [_image(_data_url(_png(128, 64)))]
+
+    [part] = prepare_user_input(parts, config)
+
+    mime_type, data = _decode(part)
+    assert mime_type == "image/png"
+    assert get_image_size(data) == (32, 16)
+
+
+def test_images_that_cannot_be_shrunk_enough_are_rejected():
+    config = ImageInputConfig(max_image_size=16)
+
+    with pytest.raises(InvalidUserInput, match="could not be downscaled below"):
+        prepare_user_input([_image(_data_url(_png()))], config)
+
+
+def test_message_size_limit():
+    data = _png()
+    config = ImageInputConfig(max_message_size=len(data) + 1)
+    parts: list[ContentPart] = [_image(_data_url(data)), _image(_data_url(data))]
+
+    with pytest.raises(InvalidUserInput, match="image_input.max_message_size"):
+        prepare_user_input(parts, config)
+
+
+def _soul(runtime: Runtime, tmp_path: Path, capabilities: set[ModelCapability]) -> KimiSoul:
+    provider = ScriptBuilder().text("I can't see it.").build()
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=capabilities)
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=KimiToolset(),
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+
+async def test_run_reports_invalid_image_without_calling_the_model(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+):
+    soul = _soul(runtime, tmp_path, {"image_in"})
+    sent: list[object] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda msg: sent.append(msg))
+
+    await soul.run([TextPart(text="look"), _image(_data_url(b"not an image"))])
+
+    assert isinstance(sent[0], TurnBegin)
+    assert isinstance(sent[1], TextPart) and "could not be decoded" in sent[1].text
+    assert isinstance(sent[2], TurnEnd)
+    assert not any(isinstance(msg, StepBegin) for msg in sent)
+    assert soul.context.history == []
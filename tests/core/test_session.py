@@ -9,7 +9,7 @@ import pytest
 from kaos.path import KaosPath
 from kosong.message import Message
 
-from kimi_cli.session import Session
+from kimi_cli.session import Session, generate_title
 from kimi_cli.wire.file import WireFileMetadata, WireMessageRecord
 from kimi_cli.wire.protocol import WIRE_PROTOCOL_VERSION
 from kimi_cli.wire.types import TextPart, TurnBegin
@@ -431,6 +431,7 @@ async def _simulate_post_run(last_session: Session, exit_code: int) -> None:
         work_dir_meta.last_session_id = last_session.id
 
     save_metadata(metadata)
+    await last_session.record_recent()
 
 
 async def test_post_run_empty_session_success(isolated_share_dir: Path, work_dir: KaosPath):
@@ -551,6 +552,99 @@ async def test_post_run_empty_session_does_not_clear_other_last_id(
     )
 
 
+async def _create_used_session(work_dir: KaosPath, text: str) -> Session:
+    session = await Session.create(work_dir)
+    _write_context_message(session.context_file, text)
+    _write_wire_turn(session.dir, text)
+    return session
+
+
+def test_generate_title_truncates_first_message():
+    message = "Why does the parser drop trailing commas in nested lists when the input has CRLF?"
+
+    title = generate_title(message)
+
+    assert title == "Why does the parser drop trailing commas in nested lists…"
+    assert len(title) <= 60
+    assert generate_title("fix  the\nbuild") == "fix the build"
+    assert generate_title("   ") == "Untitled"
+
+
+async def test_record_recent_keeps_most_recent_first(isolated_share_dir: Path, work_dir: KaosPath):
+    first = await _create_used_session(work_dir, "first task")
+    second = await _create_used_session(work_dir, "second task")
+
+    await first.record_recent()
+    await second.record_recent()
+    await first.record_recent()
+
+    recent = await Session.list_recent(work_dir)
+    assert [entry.id for entry in recent] == [first.id, second.id]
+    assert recent[0].title == "first task"
+    assert recent[0].message_count == 1
+    assert recent[0].created_at <= recent[0].last_used_at
+
+
+async def test_recent_sessions_are_bounded(
+    isolated_share_dir: Path, work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setattr("kimi_cli.session.MAX_RECENT_SESSIONS", 3)
+    sessions = [await _create_used_session(work_dir, f"task {i}") for i in range(5)]
+    for session in sessions:
+        await session.record_recent()
+
+    recent = await Session.list_recent(work_dir)
+    assert [entry.title for entry in recent] == ["task 4", "task 3", "task 2"]
+    assert [entry.id for entry in await Session.list_recent(work_dir, 2)] == [
+        sessions[4].id,
+        sessions[3].id,
+    ]
+
+
+async def test_list_recent_backfills_sessions_missing_from_the_index(
+    isolated_share_dir: Path, work_dir: KaosPath
+):
+    unindexed = await _create_used_session(work_dir, "from before the index")
+    recorded = await _create_used_session(work_dir, "recorded")
+    await recorded.record_recent()
+
+    assert [entry.id for entry in await Session.list_recent(work_dir)] == [recorded.id]
+    recent = await Session.list_recent(work_dir, backfill=True)
+    assert [(entry.id, entry.title, entry.message_count) for entry in recent] == [
+        (recorded.id, "recorded", 1),
+        (unindexed.id, "from before the index", 1),
+    ]
+    assert await Session.list_recent(work_dir, 1, backfill=True) == recent[:1]
+
+
+async def test_post_run_prunes_deleted_and_empty_sessions(
+    isolated_share_dir: Path, work_dir: KaosPath
+):
+    deleted = await _create_used_session(work_dir, "deleted later")
+    kept = await _create_used_session(work_dir, "kept")
+    await deleted.record_recent()
+    await kept.record_recent()
+    await deleted.delete()
+
+    empty = await Session.create(work_dir)
+    _write_context_records(empty.context_file, {"role": "_system_prompt", "content": "p"})
+    await _simulate_post_run(empty, EXIT_SUCCESS)
+
+    recent = await Session.list_recent(work_dir)
+    assert [entry.id for entry in recent] == [kept.id]
+
+
+async def test_post_run_records_custom_title(isolated_share_dir: Path, work_dir: KaosPath):
+    session = await _create_used_session(work_dir, "first message")
+    session.state.custom_title = "Parser fixes"
+
+    await _simulate_post_run(session, EXIT_FAILURE)
+
+    [entry] = await Session.list_recent(work_dir)
+    assert entry.id == session.id
+    assert entry.title == "Parser fixes"
+
+
 # ---------------------------------------------------------------------------
 # Reload.source_session tests
 # ---------------------------------------------------------------------------
@@ -52,6 +52,16 @@ class TestSessionTimeRange:
         assert first == 5000.0
         assert last == 5000.0
 
+    def test_ordered_by_seq(self, tmp_path: Path):
+        wire = tmp_path / "wire.jsonl"
+        with wire.open("w") as f:
+            for seq, ts in [(2, 2000.0), (3, 3000.0), (1, 1000.0)]:
+                message = {"type": "StepBegin", "payload": {"n": seq}, "seq": seq}
+                f.write(json.dumps({"timestamp": ts, "message": message}) + "\n")
+        first, last = _session_time_range(tmp_path)
+        assert first == 1000.0
+        assert last == 3000.0
+
     def test_no_wire_file(self, tmp_path: Path):
         first, last = _session_time_range(tmp_path)
         assert first is None
@@ -0,0 +1,143 @@
+from __future__ import annotations
+
+import json
+import time
+from pathlib import Path
+
+import pytest
+from typer.testing import CliRunner
+
+from kimi_cli.cli import cli
+from kimi_cli.session_state import STATE_FILE_NAME, SessionState, UsageState
+from kimi_cli.session_stats import (
+    STATS_FILE_NAME,
+    SessionStats,
+    ToolStats,
+    aggregate_session_stats,
+    load_session_stats,
+    save_session_stats,
+)
+from kimi_cli.soul.stats import StatsRecorder
+
+DAY = 24 * 60 * 60
+
+
+def _make_session(
+    session_dir: Path, stats: SessionStats, *, output_tokens: int = 0, cost: float = 0.0
+) -> Path:
+    session_dir.mkdir(parents=True)
+    (session_dir / "context.jsonl").write_text("", encoding="utf-8")
+    save_session_stats(stats, session_dir)
+    state = SessionState(usage=UsageState(input_other=100, output=output_tokens, cost=cost))
+    (session_dir / STATE_FILE_NAME).write_text(state.model_dump_json(), encoding="utf-8")
+    return session_dir
+
+
+@pytest.fixture
+def sessions(tmp_path: Path) -> list[Path]:
+    now = time.time()
+    recent = SessionStats(
+        updated_at=now - 60,
+        turns=2,
+        steps=5,
+        retries=1,
+        approvals_approved=3,
+        approvals_rejected=1,
+        tools={
+            "Shell": ToolStats(calls=3, errors=1, duration_s=4.5),
+            "ReadFile": ToolStats(calls=2, duration_s=0.5),
+        },
+    )
+    old = SessionStats(
+        updated_at=now - 10 * DAY,
+        turns=1,
+        steps=3,
+        approvals_approved=1,
+        tools={"Shell": ToolStats(calls=1, duration_s=1.5)},
+    )
+    return [
+        _make_session(tmp_path / "wd" / "recent", recent, output_tokens=50, cost=0.25),
+        _make_session(tmp_path / "wd" / "old", old, output_tokens=20, cost=0.5),
+    ]
+
+
+def test_aggregate_adds_up_sessions(sessions: list[Path]) -> None:
+    report = aggregate_session_stats(sessions)
+
+    assert report.sessions == 2
+    assert report.stats.turns == 3
+    assert report.stats.steps == 8
+    assert report.stats.retries == 1
+    assert (report.stats.approvals_approved, report.stats.approvals_rejected) == (4, 1)
+    assert report.stats.tools == {
+        "Shell": ToolStats(calls=4, errors=1, duration_s=6.0),
+        "ReadFile": ToolStats(calls=2, duration_s=0.5),
+    }
+    assert report.usage.input_other == 200
+    assert report.usage.output == 70
+    assert report.usage.cost == pytest.approx(0.75)
+
+
+def test_aggregate_since_skips_inactive_sessions(sessions: list[Path]) -> None:
+    report = aggregate_session_stats(sessions, since=time.time() - 7 * DAY)
+
+    assert report.sessions == 1
+    assert report.stats.turns == 2
+    assert report.stats.tools["Shell"] == ToolStats(calls=3, errors=1, duration_s=4.5)
+    assert report.usage.output == 50
+
+
+def test_corrupt_or_missing_stats_start_over(tmp_path: Path) -> None:
+    assert load_session_stats(tmp_path) == SessionStats()
+
+    (tmp_path / STATS_FILE_NAME).write_text("{not json", encoding="utf-8")
+    assert load_session_stats(tmp_path) == SessionStats()
+
+    # A session without stats is still counted, dated by its context file.
+    (tmp_path / "context.jsonl").write_text("", encoding="utf-8")
+    report = aggregate_session_stats([tmp_path], since=time.time() - DAY)
+    assert report.sessions == 1
+    assert report.stats.steps == 0
+
+
+def test_recorder_persists_each_update(tmp_path: Path) -> None:
+    recorder = StatsRecorder(
+        stats=load_session_stats(tmp_path),
+        on_change=lambda stats: save_session_stats(stats, tmp_path),
+    )
+
+    recorder.record_turn()
+    recorder.record_step()
+    recorder.record_tool_call("Shell", 1.25, error=False)
+    recorder.record_tool_call("Shell", 0.75, error=True)
+    recorder.record_approval(True)
+    recorder.record_approval(False)
+
+    saved = load_session_stats(tmp_path)
+    assert saved.updated_at is not None
+    assert (saved.turns, saved.steps) == (1, 1)
+    assert (saved.approvals_approved, saved.approvals_rejected) == (1, 1)
+    assert saved.tools == {"Shell": ToolStats(calls=2, errors=1, duration_s=2.0)}
+
+
+def test_stats_command(
+    sessions: list[Path], tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    share_dir = tmp_path / "share"
+    (share_dir / "sessions").mkdir(parents=True)
+    (tmp_path / "wd").rename(share_dir / "sessions" / "wd")
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(share_dir))
+
+    result = CliRunner().invoke(cli, ["stats", "--since", "7d", "--json"])
+    assert result.exit_code == 0, result.output
+    data = json.loads(result.output)
+    assert data["sessions"] == 1
+    assert data["stats"]["tools"]["Shell"]["calls"] == 3
+
+    result = CliRunner().invoke(cli, ["stats"])
+    assert result.exit_code == 0, result.output
+    assert "Sessions:  2" in result.output
+    assert "Shell" in result.output.splitlines()[-2]
+
+    result = CliRunner().invoke(cli, ["stats", "--since", "soon"])
+    assert result.exit_code != 0
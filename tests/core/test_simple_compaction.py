@@ -1,10 +1,15 @@
 from __future__ import annotations
 
 from inline_snapshot import snapshot
-from kosong.chat_provider import TokenUsage
+from collections.abc import Sequence
+
+from kosong.chat_provider import GenerateOptions, StreamedMessagePart, TokenUsage
+from kosong.chat_provider.echo import ScriptedEchoChatProvider, ScriptedEchoStreamedMessage
 from kosong.message import AudioURLPart, ImageURLPart, Message, VideoURLPart
+from kosong.tooling import Tool
 
 import kimi_cli.prompts as prompts
+from kimi_cli.llm import LLM
 from kimi_cli.soul.compaction import CompactionResult, SimpleCompaction, should_auto_compact
 from kimi_cli.wire.types import TextPart, ThinkPart
 
@@ -77,6 +82,61 @@ def test_prepare_builds_compact_message_and_preserves_tail():
     )
 
 
+class _RecordingProvider(ScriptedEchoChatProvider):
+    def __init__(self) -> None:
+        super().__init__(["text: compacted summary"])
+        self.options: list[GenerateOptions | None] = []
+
+    async def generate(
+        self,
+        system_prompt: str,
+        tools: Sequence[Tool],
+        history: Sequence[Message],
+        *,
+        options: GenerateOptions | None = None,
+    ) -> ScriptedEchoStreamedMessage:
+        self.options.append(options)
+        return await super().generate(system_prompt, tools, history, options=options)
+
+
+async def test_compact_bounds_summary_output_tokens():
+    messages = [
+        Message(role="user", content=[TextPart(text="Old question")]),
+        Message(role="assistant", content=[TextPart(text="Old answer")]),
+        Message(role="user", content=[TextPart(text="Latest question")]),
+        Message(role="assistant", content=[TextPart(text="Latest answer")]),
+    ]
+    provider = _RecordingProvider()
+    llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+
+    result = await SimpleCompaction(max_output_tokens=1234).compact(messages, llm)
+
+    assert provider.options == [GenerateOptions(max_output_tokens=1234, tool_choice="none")]
+    assert "compacted summary" in result.messages[0].extract_text()
+
+
+async def test_compact_streams_the_summary_while_it_is_written():
+    messages = [
+        Message(role="user", content=[TextPart(text="Old question")]),
+        Message(role="assistant", content=[TextPart(text="Old answer")]),
+        Message(role="user", content=[TextPart(text="Latest question")]),
+        Message(role="assistant", content=[TextPart(text="Latest answer")]),
+    ]
+    provider = ScriptedEchoChatProvider(["think: Summarize.\ntext: The user\ntext:  asked."])
+    llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    parts: list[StreamedMessagePart] = []
+
+    result = await SimpleCompaction().compact(messages, llm, on_message_part=parts.append)
+
+    assert parts == [
+        ThinkPart(think="Summarize."),
+        TextPart(text="The user"),
+        TextPart(text=" asked."),
+    ]
+    # The thinking is streamed but not kept in the summary
+    assert result.messages[0].content[1:] == [TextPart(text="The user asked.")]
+
+
 # --- CompactionResult.estimated_token_count tests ---
 
 
@@ -254,6 +314,24 @@ def test_prepare_only_keeps_text_parts_in_compaction():
     assert any("I can see all the media files." in t for t in texts)
 
 
+def test_prepare_truncates_oversized_messages_to_compact():
+    messages = [
+        Message(role="user", content=[TextPart(text="Read the log")]),
+        Message(role="tool", content=[TextPart(text="x" * 1000)], tool_call_id="call-1"),
+        Message(role="user", content=[TextPart(text="Latest question")]),
+        Message(role="assistant", content=[TextPart(text="Latest answer")]),
+    ]
+
+    result = SimpleCompaction(max_preserved_messages=2, max_message_tokens=10).prepare(messages)
+
+    assert result.compact_message is not None
+    texts = [p.text for p in result.compact_message.content if isinstance(p, TextPart)]
+    assert texts[1] == "Read the log"
+    assert texts[3] == "x" * 39 + "…"
+    # The messages themselves are left as they are.
+    assert messages[1].extract_text() == "x" * 1000
+
+
 def test_prepare_preserves_media_parts_in_recent_messages():
     """Media parts in preserved (recent) messages should remain untouched."""
     messages = [
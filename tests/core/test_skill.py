@@ -15,6 +15,8 @@ from kimi_cli.skill import (
     find_project_skills_dirs,
     find_user_skills_dirs,
     get_builtin_skills_dir,
+    parse_skill_args,
+    render_skill_args,
     resolve_skills_roots,
 )
 
@@ -130,6 +132,54 @@ A --> END([END])
     assert skills[0].flow.begin_id == "BEGIN"
 
 
+@pytest.mark.asyncio
+async def test_discover_skills_flow_block_selects_second_block(tmp_path):
+    root = tmp_path / "skills"
+    root.mkdir()
+
+    illustration = """```mermaid
+flowchart TD
+BEGIN([BEGIN]) --> X[Illustration]
+X --> END([END])
+```
+"""
+    real_flow = """```mermaid{tag}
+flowchart TD
+START([BEGIN]) --> A[Real step]
+A --> END([END])
+```
+"""
+    _write_skill(
+        root / "by-index",
+        "---\nname: by-index\ndescription: Flow skill\ntype: flow\nflow_block: 2\n---\n"
+        + illustration
+        + real_flow.format(tag=""),
+    )
+    _write_skill(
+        root / "by-tag",
+        "---\nname: by-tag\ndescription: Flow skill\ntype: flow\n---\n"
+        + illustration
+        + real_flow.format(tag=" flow"),
+    )
+    _write_skill(
+        root / "first-match",
+        "---\nname: first-match\ndescription: Flow skill\ntype: flow\n---\n"
+        + illustration
+        + real_flow.format(tag=""),
+    )
+
+    skills = await discover_skills(KaosPath.unsafe_from_local_path(root), scope="user")
+    by_name = {skill.name: skill for skill in skills}
+
+    for name in ("by-index", "by-tag"):
+        flow = by_name[name].flow
+        assert flow is not None
+        assert flow.begin_id == "START"
+    first = by_name["first-match"].flow
+    assert first is not None
+    assert first.begin_id == "BEGIN"
+
+
 @pytest.mark.asyncio
 async def test_discover_skills_flow_parse_failure_falls_back(tmp_path):
     root = tmp_path / "skills"
@@ -1839,3 +1889,22 @@ async def test_discover_subdir_skill_malformed_frontmatter_opener_not_used_as_de
     assert len(skills) == 1
     assert skills[0].description != "---"
     assert skills[0].description == "# Heading"
+
+
+def test_parse_skill_args():
+    assert parse_skill_args('title="fix login" draft version=2 =x') == {
+        "title": "fix login",
+        "version": "2",
+    }
+    assert parse_skill_args('unbalanced "quote key=v') == {"key": "v"}
+
+
+def test_render_skill_args_substitutes_placeholders():
+    text = "Release {{arg:version}} ({{ arg:missing }}).\n\nNotes: {{args}}\nDone."
+    assert render_skill_args(text, ' version=1.2.0 notes="bug fixes" ') == snapshot(
+        'Release 1.2.0 ().\n\nNotes: version=1.2.0 notes="bug fixes"\nDone.'
+    )
+
+
+def test_render_skill_args_without_placeholders():
+    assert render_skill_args("Just follow the rules.", "extra request") is None
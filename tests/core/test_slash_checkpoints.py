@@ -0,0 +1,89 @@
+"""Tests for the /checkpoints and /revert slash commands."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.message import Message
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.slash import checkpoints as checkpoints_slash
+from kimi_cli.soul.slash import revert as revert_slash
+from kimi_cli.wire.types import StatusUpdate, TextPart
+
+
+async def _soul(runtime: Runtime, tmp_path: Path) -> KimiSoul:
+    context = Context(file_backend=tmp_path / "history.jsonl")
+    await context.checkpoint(add_user_message=True)
+    await context.append_message(Message(role="user", content="fix the login bug"))
+    await context.checkpoint(add_user_message=True)
+    await context.append_message(Message(role="assistant", content="The session expired."))
+    await context.checkpoint(add_user_message=True)
+    await context.append_message(Message(role="user", content="now add a test"))
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=context)
+
+
+async def test_checkpoints_lists_a_preview_of_each(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = await _soul(runtime, tmp_path)
+    sent: list[object] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", sent.append)
+
+    checkpoints_slash(soul, "")
+
+    assert isinstance(sent[0], TextPart)
+    assert sent[0].text.splitlines()[:4] == [
+        "Checkpoints:",
+        "  0: user: fix the login bug",
+        "  1: assistant: The session expired.",
+        "  2: user: now add a test",
+    ]
+
+
+async def test_revert_drops_the_checkpoint_and_checkpoints_again(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = await _soul(runtime, tmp_path)
+    sent: list[object] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", sent.append)
+
+    await revert_slash(soul, "1")
+
+    # Checkpoint 1 is made again, without a message since the agent has no SendDMail tool.
+    assert soul.context.n_checkpoints == 2
+    assert [message.extract_text() for message in soul.context.history] == [
+        "<system>CHECKPOINT 0</system>",
+        "fix the login bug",
+    ]
+    assert isinstance(sent[0], TextPart)
+    assert sent[0].text.startswith("Reverted the context to checkpoint 1.")
+    assert isinstance(sent[1], StatusUpdate)
+
+
+@pytest.mark.parametrize("args", ["3", "-1", "latest", ""])
+async def test_revert_rejects_unknown_checkpoints(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch, args: str
+) -> None:
+    soul = await _soul(runtime, tmp_path)
+    sent: list[object] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", sent.append)
+    n_messages = len(soul.context.history)
+
+    await revert_slash(soul, args)
+
+    assert soul.context.n_checkpoints == 3
+    assert len(soul.context.history) == n_messages
+    assert len(sent) == 1
+    assert isinstance(sent[0], TextPart)
+    assert "/checkpoints" in sent[0].text
@@ -0,0 +1,177 @@
+"""Tests for session usage accounting and the /cost slash command."""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import Any
+from unittest.mock import AsyncMock
+
+import pytest
+from kosong.chat_provider import TokenUsage
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.soul.agent as agent_module
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.auth.oauth import OAuthManager
+from kimi_cli.config import LLMModel, ModelPricing
+from kimi_cli.llm import LLM
+from kimi_cli.session_state import load_session_state
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.slash import cost as cost_slash
+from kimi_cli.soul.usage import UsageTracker, estimate_cost
+from kimi_cli.wire.types import StatusUpdate, TextPart
+
+PRICING = ModelPricing(input=2.0, output=8.0, cache_read=0.5)
+
+
+def test_estimate_cost_uses_cache_prices() -> None:
+    usage = TokenUsage(
+        input_other=1_000_000,
+        output=500_000,
+        input_cache_read=2_000_000,
+        input_cache_creation=1_000_000,
+    )
+
+    # Cache creation falls back to the input price.
+    assert estimate_cost(usage, PRICING) == pytest.approx(2.0 + 4.0 + 1.0 + 2.0)
+
+
+def test_usage_tracker_separates_turn_and_session() -> None:
+    tracker = UsageTracker()
+    tracker.record(TokenUsage(input_other=100, output=10), PRICING)
+    tracker.begin_turn()
+    tracker.record(TokenUsage(input_other=200, output=20, input_cache_read=50), None)
+
+    assert (tracker.turn.input, tracker.turn.output) == (250, 20)
+    assert (tracker.turn.cost, tracker.turn.unpriced_calls) == (0.0, 1)
+    assert (tracker.session.input, tracker.session.output) == (350, 30)
+    assert tracker.session.cost == pytest.approx((100 * 2.0 + 10 * 8.0) / 1_000_000)
+    assert tracker.session.unpriced_calls == 1
+
+
+def test_usage_tracker_attributes_compaction() -> None:
+    changes: list[int] = []
+    tracker = UsageTracker(on_change=lambda t: changes.append(t.session.output))
+    tracker.record(TokenUsage(input_other=100, output=10), PRICING)
+    tracker.record(TokenUsage(input_other=50, output=5), PRICING, compaction=True)
+
+    assert tracker.session.to_token_usage() == TokenUsage(input_other=150, output=15)
+    assert tracker.compaction.to_token_usage() == TokenUsage(input_other=50, output=5)
+    assert changes == [10, 15]
+
+
+def _make_soul(
+    runtime: Runtime, provider: Any, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> KimiSoul:
+    runtime.llm = LLM(
+        chat_provider=provider,
+        max_context_size=1_000_000,
+        capabilities=set(),
+        model_config=LLMModel(
+            provider="scripted", model="scripted", max_context_size=1_000_000, pricing=PRICING
+        ),
+    )
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    async def _noop_checkpoint() -> None:
+        return None
+
+    monkeypatch.setattr(soul, "_checkpoint", _noop_checkpoint)
+    monkeypatch.setattr(soul._denwa_renji, "set_n_checkpoints", lambda _n: None)
+    return soul
+
+
+async def test_cost_reports_turn_and_session_usage(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    provider = (
+        ScriptBuilder()
+        .text("first")
+        .usage(input_other=300_000, output=100_000, input_cache_read=200_000)
+        .turn()
+        .text("second")
+        .usage(input_other=100_000, output=50_000)
+        .build()
+    )
+    soul = _make_soul(runtime, provider, tmp_path, monkeypatch)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+    await soul.run("one")
+    await soul.run("two")
+
+    sent: list[TextPart] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", lambda msg: sent.append(msg))
+    cost_slash(soul, "")
+
+    assert [part.text for part in sent] == [
+        "Current turn:\n"
+        "  Input tokens: 100,000 (0 cached)\n"
+        "  Output tokens: 50,000\n"
+        "  Estimated cost: $0.6000\n"
+        "\n"
+        "Session:\n"
+        "  Input tokens: 600,000 (200,000 cached)\n"
+        "  Output tokens: 150,000\n"
+        "  Estimated cost: $2.1000"
+    ]
+
+
+async def test_status_update_reports_session_usage(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    provider = (
+        ScriptBuilder()
+        .text("first")
+        .usage(input_other=30, output=10, input_cache_read=20)
+        .turn()
+        .text("second")
+        .usage(input_other=10, output=5)
+        .build()
+    )
+    soul = _make_soul(runtime, provider, tmp_path, monkeypatch)
+    sent: list[Any] = []
+    monkeypatch.setattr(kimisoul_module, "wire_send", sent.append)
+    await soul.run("one")
+    await soul.run("two")
+
+    updates = [msg for msg in sent if isinstance(msg, StatusUpdate) and msg.token_usage]
+    assert [update.session_usage for update in updates] == [
+        TokenUsage(input_other=30, output=10, input_cache_read=20),
+        TokenUsage(input_other=40, output=15, input_cache_read=20),
+    ]
+    assert updates[-1].session_compaction_usage == TokenUsage(input_other=0, output=0)
+
+
+async def test_runtime_create_resumes_session_usage(
+    config, session, environment, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(agent_module, "list_directory", AsyncMock(return_value=""))
+    monkeypatch.setattr(agent_module, "load_agents_md", AsyncMock(return_value=None))
+    monkeypatch.setattr(agent_module.Environment, "detect", AsyncMock(return_value=environment))
+    monkeypatch.setattr(agent_module, "resolve_skills_roots", AsyncMock(return_value=[]))
+    monkeypatch.setattr(agent_module, "discover_skills_from_roots", AsyncMock(return_value=[]))
+    monkeypatch.setattr(agent_module, "index_skills", lambda _skills: {})
+    monkeypatch.setattr(agent_module, "format_skills_for_prompt", lambda _skills: None)
+
+    runtime = await Runtime.create(
+        config, OAuthManager(config), llm=None, session=session, yolo=False
+    )
+    runtime.usage.record(TokenUsage(input_other=100, output=10), PRICING)
+    runtime.usage.record(TokenUsage(input_other=40, output=4), PRICING, compaction=True)
+
+    session.state = load_session_state(session.dir)
+    resumed = await Runtime.create(
+        config, OAuthManager(config), llm=None, session=session, yolo=False
+    )
+    assert resumed.usage.session.to_token_usage() == TokenUsage(input_other=140, output=14)
+    assert resumed.usage.compaction.to_token_usage() == TokenUsage(input_other=40, output=4)
+    assert resumed.usage.session.cost == pytest.approx(runtime.usage.session.cost)
+    assert resumed.usage.turn.input == 0
@@ -0,0 +1,83 @@
+"""Tests for the /save and /load slash commands."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.message import Message
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.slash import load as load_slash
+from kimi_cli.soul.slash import save as save_slash
+from kimi_cli.wire.types import StatusUpdate, TextPart
+
+
+async def _soul(runtime: Runtime, tmp_path: Path) -> KimiSoul:
+    context = Context(file_backend=tmp_path / "history.jsonl")
+    await context.write_system_prompt("Test system prompt.")
+    await context.checkpoint(add_user_message=False)
+    await context.append_message(Message(role="user", content="fix the login bug"))
+    await context.update_token_count(120)
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=context)
+
+
+async def test_save_then_load_restores_the_context(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = await _soul(runtime, tmp_path)
+    sent: list[object] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", sent.append)
+
+    await save_slash(soul, "good-baseline")
+    await soul.context.append_message(Message(role="user", content="now rewrite everything"))
+    await soul.context.update_token_count(900)
+    await load_slash(soul, "good-baseline")
+
+    context = soul.context
+    assert [message.extract_text() for message in context.history] == ["fix the login bug"]
+    assert context.token_count == 120
+    assert context.system_prompt == "Test system prompt."
+    # The saved checkpoint 0, then the one made after loading.
+    assert context.n_checkpoints == 2
+    assert isinstance(sent[-2], TextPart)
+    assert sent[-2].text.startswith("Loaded snapshot good-baseline into the context.")
+    assert isinstance(sent[-1], StatusUpdate)
+
+    # The replaced context ends at a checkpoint made before loading.
+    replaced = Context(file_backend=tmp_path / "history_1.jsonl")
+    assert await replaced.restore()
+    assert [message.extract_text() for message in replaced.history] == [
+        "fix the login bug",
+        "now rewrite everything",
+    ]
+    assert replaced.n_checkpoints == 2
+
+    sent.clear()
+    await save_slash(soul, "")
+    assert isinstance(sent[0], TextPart)
+    assert sent[0].text.splitlines()[1].startswith("  good-baseline (saved ")
+
+
+@pytest.mark.parametrize("args", ["../escape", ".hidden", "two words", "missing", ""])
+async def test_load_rejects_bad_or_unknown_names(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch, args: str
+) -> None:
+    soul = await _soul(runtime, tmp_path)
+    sent: list[object] = []
+    monkeypatch.setattr("kimi_cli.soul.slash.wire_send", sent.append)
+
+    await load_slash(soul, args)
+
+    assert [message.extract_text() for message in soul.context.history] == ["fix the login bug"]
+    assert len(sent) == 1
+    assert isinstance(sent[0], TextPart)
@@ -8,6 +8,7 @@ from kimi_cli.llm import ModelCapability
 from kimi_cli.soul.message import check_message, system, tool_result_to_message
 from kimi_cli.wire.types import (
     AudioURLPart,
+    FilePart,
     ImageURLPart,
     TextPart,
     ThinkPart,
@@ -288,6 +289,15 @@ def test_check_message_with_video_no_video_capability():
     assert missing_capabilities == {"video_in"}
 
 
+def test_check_message_with_file_no_file_capability():
+    """Test check_message with FilePart when model lacks file_in capability."""
+    file_part = FilePart(file=FilePart.File(url="https://example.com/report.pdf"))
+    message = Message(role="user", content=[TextPart(text="Summarize"), file_part])
+
+    assert check_message(message, {"image_in", "video_in"}) == {"file_in"}
+    assert check_message(message, {"file_in"}) == set()
+
+
 def test_check_message_with_think_and_think_capability():
     """Test check_message with ThinkPart when model has thinking capability."""
     think_part = ThinkPart(think="This is a thinking process")
@@ -74,10 +74,14 @@ from typer.testing import CliRunner
 
 lazy_modules = [
     "kimi_cli.cli.info",
+    "kimi_cli.cli.config",
     "kimi_cli.cli.export",
     "kimi_cli.cli.mcp",
+    "kimi_cli.cli.session",
+    "kimi_cli.cli.stats",
     "kimi_cli.cli.vis",
     "kimi_cli.cli.web",
+    "kimi_cli.cli.wire",
 ]
 for name in lazy_modules:
     sys.modules.pop(name, None)
@@ -88,7 +92,7 @@ assert all(name not in sys.modules for name in lazy_modules)
 
 result = CliRunner().invoke(cli, ["--help"])
 assert result.exit_code == 0, result.output
-for name in ("info", "export", "mcp", "vis", "web"):
+for name in ("info", "config", "export", "mcp", "session", "stats", "vis", "web", "wire"):
     assert name in result.output
 assert all(name not in sys.modules for name in lazy_modules)
 print("ok")
@@ -0,0 +1,163 @@
+"""
+Tests for how foreground subagent events reach the parent wire.
+
+The integration test runs a real parent soul and subagent soul on a scripted provider, so that
+the client sees exactly what a Wire client would: the subagent's steps wrapped in
+`SubagentEvent`, followed by a `SubagentEnd` before the Agent tool result.
+"""
+
+from __future__ import annotations
+
+import asyncio
+from pathlib import Path
+
+from kosong.chat_provider import TokenUsage
+from kosong.chat_provider.echo import ScriptBuilder
+
+from kimi_cli.llm import LLM
+from kimi_cli.soul import run_soul
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.subagents import AgentTypeDefinition, ToolPolicy
+from kimi_cli.subagents.runner import limit_subagent_event_depth
+from kimi_cli.tools.agent import Agent as AgentTool
+from kimi_cli.tools.think import Think
+from kimi_cli.utils.aioqueue import QueueShutDown
+from kimi_cli.wire import Wire
+from kimi_cli.wire.types import (
+    StepBegin,
+    SubagentEnd,
+    SubagentEvent,
+    ToolCall,
+    ToolResult,
+    WireMessage,
+)
+
+SUMMARY = " ".join(["The parser drops trailing commas because the tokenizer skips them."] * 4)
+
+
+async def _run_and_collect(soul: KimiSoul, user_input: str) -> list[WireMessage]:
+    messages: list[WireMessage] = []
+
+    async def _ui_loop_fn(wire: Wire) -> None:
+        wire_ui = wire.ui_side(merge=True)
+        while True:
+            try:
+                messages.append(await wire_ui.receive())
+            except QueueShutDown:
+                return
+
+    await run_soul(soul, user_input, _ui_loop_fn, asyncio.Event())
+    return messages
+
+
+async def test_parent_wire_sees_subagent_tool_calls_and_end(
+    runtime: Runtime, tmp_path: Path, monkeypatch
+) -> None:
+    # The parent and the subagent share the LLM, so the script interleaves their steps.
+    provider = (
+        ScriptBuilder()
+        .tool_call(
+            "Agent",
+            {"description": "investigate parser", "prompt": "Why are commas dropped?"},
+            id="agent-1",
+        )
+        .turn()
+        .tool_call("Think", {"thought": "Check the tokenizer."}, id="think-1")
+        .usage(input_other=100, output=10)
+        .turn()
+        .text(SUMMARY)
+        .usage(input_other=200, output=50, input_cache_read=30)
+        .turn()
+        .text("Found it.")
+        .build()
+    )
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    runtime.labor_market.add_builtin_type(
+        AgentTypeDefinition(
+            name="coder",
+            description="Good at general software engineering tasks.",
+            agent_file=tmp_path / "coder.yaml",
+            tool_policy=ToolPolicy(mode="inherit"),
+        )
+    )
+
+    async def fake_load_agent(agent_file, runtime, *, mcp_configs, start_mcp_loading=True):
+        toolset = KimiToolset()
+        toolset.add(Think())
+        return Agent(
+            name=agent_file.stem,
+            system_prompt="Subagent system prompt",
+            toolset=toolset,
+            runtime=runtime,
+        )
+
+    monkeypatch.setattr("kimi_cli.subagents.builder.load_agent", fake_load_agent)
+
+    toolset = KimiToolset()
+    toolset.add(AgentTool(runtime))
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+    messages = await _run_and_collect(soul, "Find the comma bug.")
+
+    subagent_events = [msg for msg in messages if isinstance(msg, SubagentEvent)]
+    assert subagent_events
+    assert all(event.parent_tool_call_id == "agent-1" for event in subagent_events)
+    assert all(event.subagent_type == "coder" for event in subagent_events)
+    assert all(event.depth == 1 for event in subagent_events)
+    sub_tool_calls = [event.event for event in subagent_events if isinstance(event.event, ToolCall)]
+    assert [(call.id, call.function.name) for call in sub_tool_calls] == [("think-1", "Think")]
+
+    [end] = [msg for msg in messages if isinstance(msg, SubagentEnd)]
+    assert end.parent_tool_call_id == "agent-1"
+    assert end.agent_id == subagent_events[0].agent_id
+    assert end.status == "completed"
+    assert end.final_message == SUMMARY
+    assert end.usage == TokenUsage(input_other=300, output=60, input_cache_read=30)
+
+    [agent_result] = [
+        msg for msg in messages if isinstance(msg, ToolResult) and msg.tool_call_id == "agent-1"
+    ]
+    assert messages.index(end) < messages.index(agent_result)
+    assert not agent_result.return_value.is_error
+
+
+def _chain(depth: int) -> SubagentEvent:
+    event: SubagentEvent | StepBegin = StepBegin(n=1)
+    for level in range(depth, 0, -1):
+        event = SubagentEvent(
+            parent_tool_call_id=f"call-{level}", agent_id=f"a{level}", event=event
+        )
+    assert isinstance(event, SubagentEvent)
+    return event
+
+
+def test_limit_subagent_event_depth_flattens_deep_chains():
+    event = limit_subagent_event_depth(_chain(5), 3)
+
+    assert event.agent_id == "a1"
+    assert isinstance(event.event, SubagentEvent)
+    assert event.event.agent_id == "a2"
+    innermost = event.event.event
+    assert isinstance(innermost, SubagentEvent)
+    assert innermost.agent_id == "a3"
+    assert innermost.depth == 3
+    assert innermost.event == StepBegin(n=1)
+
+
+def test_limit_subagent_event_depth_keeps_shallow_chains():
+    chain = _chain(2)
+
+    assert limit_subagent_event_depth(chain, 3) == chain
+    flattened = limit_subagent_event_depth(chain, 1)
+    assert flattened.agent_id == "a1"
+    assert flattened.depth == 2
+    assert flattened.event == StepBegin(n=1)
@@ -5,19 +5,33 @@ from __future__ import annotations
 import asyncio
 import contextlib
 import json
+import os
+import platform
+import sys
+from pathlib import Path
+from types import SimpleNamespace
 
+import mcp
 import pytest
 from kosong.tooling import CallableTool2, ToolError, ToolOk, ToolReturnValue
 from kosong.tooling.error import ToolNotFoundError as KosongToolNotFoundError
 from pydantic import BaseModel
 
+import kimi_cli.soul.toolset as toolset_module
+from kimi_cli.config import ToolsConfig
+from kimi_cli.exception import MCPRuntimeError
+from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.toolset import (
     _REMINDER_TEXT_1,
     _REMINDER_TEXT_3,
     KimiToolset,
+    MCPServerInfo,
     _build_repeat_reminder,
+    set_tool_tracebacks,
+    tool_timeout_paused,
 )
-from kimi_cli.wire.types import ToolCall, ToolResult
+from kimi_cli.tools import ExemptFromTimeout
+from kimi_cli.wire.types import TextPart, ToolCall, ToolResult
 
 
 class DummyParams(BaseModel):
@@ -42,6 +56,35 @@ class DummyToolB(CallableTool2[DummyParams]):
         return ToolOk(output="b")
 
 
+class SlowParams(BaseModel):
+    seconds: float = 0.2
+    paused: bool = False
+
+
+class SlowTool(CallableTool2[SlowParams]):
+    name: str = "Slow"
+    description: str = "Sleeps"
+    params: type[SlowParams] = SlowParams
+
+    async def __call__(self, params: SlowParams) -> ToolReturnValue:
+        with tool_timeout_paused() if params.paused else contextlib.nullcontext():
+            await asyncio.sleep(params.seconds)
+        return ToolOk(output="done")
+
+
+class ExemptSlowTool(SlowTool, ExemptFromTimeout):
+    name: str = "ExemptSlow"
+
+
+class FailingTool(CallableTool2[DummyParams]):
+    name: str = "Failing"
+    description: str = "Raises"
+    params: type[DummyParams] = DummyParams
+
+    async def __call__(self, params: DummyParams) -> ToolReturnValue:
+        raise ValueError("boom")
+
+
 def _make_toolset() -> KimiToolset:
     ts = KimiToolset()
     ts.add(DummyToolA())
@@ -174,6 +217,442 @@ async def test_nonexistent_tool_returns_not_found():
     assert isinstance(result.return_value, KosongToolNotFoundError)
 
 
+# --- [tools] config ---
+
+
+def test_tools_config_disabled_overrides_enabled():
+    config = ToolsConfig(enabled=["ToolA", "ToolB"], disabled=["ToolB"])
+
+    assert config.is_enabled("ToolA")
+    assert not config.is_enabled("ToolB")
+    assert not config.is_enabled("ToolC")
+    assert ToolsConfig().is_enabled("ToolC")
+
+
+def test_disabled_external_tool_is_rejected():
+    ts = KimiToolset(ToolsConfig(disabled=["open_in_ide"]))
+
+    ok, reason = ts.register_external_tool("open_in_ide", "Open a file", {"type": "object"})
+
+    assert not ok
+    assert reason == "tool is disabled in the config"
+    assert ts.find("open_in_ide") is None
+
+
+class _FakeMCPClient:
+    def __init__(self, tools: list[mcp.Tool], server: str = ""):
+        self._tools = tools
+        self._server = server
+
+    async def __aenter__(self) -> _FakeMCPClient:
+        return self
+
+    async def __aexit__(self, *exc_info: object) -> None:
+        return None
+
+    async def list_tools(self) -> list[mcp.Tool]:
+        return self._tools
+
+    async def call_tool(self, name: str, arguments: dict[str, object], **kwargs: object):
+        text = mcp.types.TextContent(type="text", text=f"{self._server}:{name}")
+        return SimpleNamespace(content=[text], is_error=False)
+
+
+def _add_mcp_server(ts: KimiToolset, server: str, *tool_names: str) -> None:
+    ts.mcp_servers[server] = MCPServerInfo(
+        status="pending",
+        client=_FakeMCPClient(  # pyright: ignore[reportArgumentType]
+            [mcp.Tool(name=name, inputSchema={"type": "object"}) for name in tool_names], server
+        ),
+        tools=[],
+    )
+
+
+async def _call(ts: KimiToolset, name: str) -> ToolReturnValue:
+    result = ts.handle(
+        ToolCall(id=f"tc-{name}", function=ToolCall.FunctionBody(name=name, arguments="{}"))
+    )
+    if not isinstance(result, ToolResult):
+        result = await result
+    return result.return_value
+
+
+async def test_disabled_mcp_tools_are_not_registered(runtime: Runtime):
+    ts = KimiToolset(ToolsConfig(disabled=["delete_repo"]))
+    client = _FakeMCPClient(
+        [
+            mcp.Tool(name="list_repos", inputSchema={"type": "object"}),
+            mcp.Tool(name="delete_repo", inputSchema={"type": "object"}),
+        ]
+    )
+    ts.mcp_servers["github"] = MCPServerInfo(
+        status="pending",
+        client=client,  # pyright: ignore[reportArgumentType]
+        tools=[],
+    )
+
+    await ts.load_mcp_tools([], runtime, in_background=False)
+
+    assert _tool_names(ts) == {"list_repos"}
+    assert [tool.name for tool in ts.mcp_servers["github"].tools] == ["list_repos"]
+    result = ts.handle(
+        ToolCall(id="tc-3", function=ToolCall.FunctionBody(name="delete_repo", arguments="{}"))
+    )
+    assert isinstance(result, ToolResult)
+    assert isinstance(result.return_value, KosongToolNotFoundError)
+
+
+class _ClosingMCPClient:
+    def __init__(self, *, hang: bool = False):
+        self._hang = hang
+        self.closed = False
+
+    async def close(self) -> None:
+        if self._hang:
+            await asyncio.Event().wait()
+        self.closed = True
+
+
+async def test_cleanup_closes_mcp_clients_concurrently_with_a_timeout(
+    monkeypatch: pytest.MonkeyPatch,
+):
+    monkeypatch.setattr(toolset_module, "MCP_CLOSE_TIMEOUT_S", 0.1)
+    ts = KimiToolset()
+    hanging = _ClosingMCPClient(hang=True)
+    clients = [hanging, _ClosingMCPClient(), _ClosingMCPClient()]
+    for i, client in enumerate(clients):
+        ts.mcp_servers[f"server{i}"] = MCPServerInfo(
+            status="connected",
+            client=client,  # pyright: ignore[reportArgumentType]
+            tools=[],
+        )
+
+    await asyncio.wait_for(ts.cleanup(), 1.0)
+
+    assert [client.closed for client in clients] == [False, True, True]
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="Checks the process with os.kill.")
+async def test_cleanup_stops_stdio_mcp_server_processes(runtime: Runtime, tmp_path: Path):
+    from fastmcp.mcp_config import MCPConfig
+
+    pid_file = tmp_path / "server.pid"
+    script = tmp_path / "server.py"
+    script.write_text(
+        "import os, pathlib, sys\n"
+        "from fastmcp import FastMCP\n"
+        "pathlib.Path(sys.argv[1]).write_text(str(os.getpid()))\n"
+        "server = FastMCP('pid')\n"
+        "@server.tool\n"
+        "def ping() -> str:\n"
+        "    return 'pong'\n"
+        "server.run()\n"
+    )
+    config = MCPConfig.model_validate(
+        {"mcpServers": {"pid": {"command": sys.executable, "args": [str(script), str(pid_file)]}}}
+    )
+    ts = KimiToolset()
+    await ts.load_mcp_tools([config], runtime, in_background=False)
+    assert ts.mcp_servers["pid"].status == "connected"
+    pid = int(pid_file.read_text())
+
+    await ts.cleanup()
+
+    for _ in range(50):
+        try:
+            os.kill(pid, 0)
+        except ProcessLookupError:
+            break
+        await asyncio.sleep(0.1)
+    else:
+        pytest.fail(f"MCP server process {pid} is still running after cleanup")
+
+
+async def test_tool_schemas_mark_the_source_of_each_tool(runtime: Runtime):
+    ts = _make_toolset()
+    ts.register_external_tool("open_in_ide", "Open a file", {"type": "object"})
+    ts.mcp_servers["github"] = MCPServerInfo(
+        status="pending",
+        client=_FakeMCPClient(  # pyright: ignore[reportArgumentType]
+            [mcp.Tool(name="list_repos", inputSchema={"type": "object"})]
+        ),
+        tools=[],
+    )
+    await ts.load_mcp_tools([], runtime, in_background=False)
+    ts.hide("ToolB")
+
+    schemas = {schema.name: schema for schema in ts.tool_schemas()}
+
+    assert {name: (s.source, s.mcp_server) for name, s in schemas.items()} == {
+        "ToolA": ("builtin", None),
+        "open_in_ide": ("external", None),
+        "list_repos": ("mcp", "github"),
+    }
+    assert schemas["ToolA"].parameters == ts.tools[0].parameters
+    assert schemas["open_in_ide"].model_dump(mode="json", exclude_none=True) == {
+        "name": "open_in_ide",
+        "description": "Open a file",
+        "parameters": {"type": "object"},
+        "source": "external",
+    }
+
+
+async def test_mcp_tools_with_taken_names_are_prefixed_with_their_server(runtime: Runtime):
+    ts = _make_toolset()
+    _add_mcp_server(ts, "docs", "search", "ToolA")
+    _add_mcp_server(ts, "web.search", "search")
+
+    await ts.load_mcp_tools([], runtime, in_background=False)
+
+    assert [tool.name for tool in ts.mcp_servers["docs"].tools] == ["search", "docs__ToolA"]
+    assert [tool.name for tool in ts.mcp_servers["web.search"].tools] == ["web_search__search"]
+    assert (await _call(ts, "ToolA")).output == "a"
+    assert (await _call(ts, "docs__ToolA")).output == [TextPart(text="docs:ToolA")]
+    assert (await _call(ts, "search")).output == [TextPart(text="docs:search")]
+    assert (await _call(ts, "web_search__search")).output == [TextPart(text="web.search:search")]
+    tool = ts.find("web_search__search")
+    assert tool is not None
+    assert "named `web_search__search` here" in tool.base.description
+
+
+async def test_mcp_tools_are_all_prefixed_when_configured(runtime: Runtime):
+    runtime.config.mcp.client.prefix_tool_names = True
+    ts = KimiToolset()
+    _add_mcp_server(ts, "docs", "search")
+
+    await ts.load_mcp_tools([], runtime, in_background=False)
+
+    assert _tool_names(ts) == {"docs__search"}
+    assert (await _call(ts, "docs__search")).output == [TextPart(text="docs:search")]
+
+
+async def test_prefixed_mcp_tool_names_are_cut_to_64_characters(runtime: Runtime):
+    runtime.config.mcp.client.prefix_tool_names = True
+    ts = KimiToolset()
+    _add_mcp_server(ts, "s" * 40, "read_" + "x" * 30, "write_" + "x" * 30)
+
+    await ts.load_mcp_tools([], runtime, in_background=False)
+
+    names = sorted(_tool_names(ts))
+    assert [len(name) for name in names] == [64, 64]
+    assert names[0].startswith("s" * 40 + "__read_")
+    assert names[1].startswith("s" * 40 + "__write_")
+    assert (await _call(ts, names[0])).output == [TextPart(text=f"{'s' * 40}:read_{'x' * 30}")]
+
+
+async def test_mcp_tools_can_be_disabled_by_their_prefixed_name(runtime: Runtime):
+    ts = KimiToolset(ToolsConfig(disabled=["docs__search"]))
+    _add_mcp_server(ts, "web", "search")
+    _add_mcp_server(ts, "docs", "search", "fetch")
+
+    await ts.load_mcp_tools([], runtime, in_background=False)
+
+    assert _tool_names(ts) == {"search", "fetch"}
+    assert [tool.name for tool in ts.mcp_servers["docs"].tools] == ["fetch"]
+
+
+class _SlowMCPClient(_FakeMCPClient):
+    def __init__(self, tools: list[mcp.Tool], release: asyncio.Event):
+        super().__init__(tools)
+        self._release = release
+
+    async def list_tools(self) -> list[mcp.Tool]:
+        await self._release.wait()
+        return await super().list_tools()
+
+
+async def _load_with_slow_server(runtime: Runtime, release: asyncio.Event) -> KimiToolset:
+    ts = KimiToolset()
+    ts.mcp_servers["slow"] = MCPServerInfo(
+        status="pending",
+        client=_SlowMCPClient(  # pyright: ignore[reportArgumentType]
+            [mcp.Tool(name="crawl", inputSchema={"type": "object"})], release
+        ),
+        tools=[],
+    )
+    _add_mcp_server(ts, "fast", "search")
+    await ts.load_mcp_tools([], runtime)
+    for _ in range(5):
+        await asyncio.sleep(0)
+    return ts
+
+
+async def test_slow_mcp_server_does_not_hold_up_the_others(runtime: Runtime):
+    release = asyncio.Event()
+    ts = await _load_with_slow_server(runtime, release)
+
+    assert ts.mcp_servers["fast"].status == "connected"
+    assert ts.mcp_servers["slow"].status == "connecting"
+    assert _tool_names(ts) == {"search"}
+    assert ts.has_pending_mcp_tools()
+
+    release.set()
+    await ts.wait_for_mcp_tools()
+    assert _tool_names(ts) == {"search", "crawl"}
+
+
+async def test_mcp_connections_are_limited(runtime: Runtime):
+    runtime.config.mcp.client.max_concurrent_connections = 1
+    release = asyncio.Event()
+    ts = await _load_with_slow_server(runtime, release)
+
+    assert ts.mcp_servers["fast"].status == "pending"
+    assert _tool_names(ts) == set()
+
+    release.set()
+    await ts.wait_for_mcp_tools()
+    assert _tool_names(ts) == {"search", "crawl"}
+
+
+async def test_stdio_mcp_server_with_unset_env_var_fails(
+    runtime: Runtime, monkeypatch: pytest.MonkeyPatch
+):
+    from fastmcp.mcp_config import MCPConfig
+
+    monkeypatch.delenv("KIMI_TEST_UNSET_TOKEN", raising=False)
+    config = MCPConfig.model_validate(
+        {
+            "mcpServers": {
+                "github": {
+                    "command": "github-mcp",
+                    "args": ["--host", "${GITHUB_HOST:-github.com}"],
+                    "env": {"GITHUB_TOKEN": "${KIMI_TEST_UNSET_TOKEN}"},
+                }
+            }
+        }
+    )
+    ts = KimiToolset()
+
+    with pytest.raises(MCPRuntimeError, match="KIMI_TEST_UNSET_TOKEN"):
+        await ts.load_mcp_tools([config], runtime, in_background=False)
+    assert ts.mcp_servers["github"].status == "failed"
+
+
+class _FlakyMCPClient(_FakeMCPClient):
+    def __init__(self, tools: list[mcp.Tool], timeouts: int):
+        super().__init__(tools)
+        self.timeouts = timeouts
+        self.calls = 0
+
+    async def call_tool(self, name: str, arguments: dict[str, object], **kwargs: object):
+        self.calls += 1
+        if self.calls <= self.timeouts:
+            raise RuntimeError("Timed out while waiting for response")
+        return await super().call_tool(name, arguments, **kwargs)
+
+
+async def _load_flaky_server(
+    runtime: Runtime, timeouts: int, max_retries: int, tools_config: ToolsConfig | None = None
+) -> tuple[KimiToolset, _FlakyMCPClient]:
+    ts = KimiToolset(tools_config)
+    client = _FlakyMCPClient([mcp.Tool(name="crawl", inputSchema={"type": "object"})], timeouts)
+    ts.mcp_servers["flaky"] = MCPServerInfo(
+        status="pending",
+        client=client,  # pyright: ignore[reportArgumentType]
+        tools=[],
+        max_retries=max_retries,
+    )
+    await ts.load_mcp_tools([], runtime, in_background=False)
+    return ts, client
+
+
+async def test_mcp_tool_call_is_retried_on_timeout(
+    runtime: Runtime, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setattr(toolset_module, "_MCP_RETRY_DELAY_S", 0.0)
+    ts, client = await _load_flaky_server(runtime, timeouts=2, max_retries=2)
+
+    assert (await _call(ts, "crawl")).output == [TextPart(text=":crawl")]
+    assert client.calls == 3
+
+
+async def test_mcp_tool_call_timeout_reports_retries(
+    runtime: Runtime, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setattr(toolset_module, "_MCP_RETRY_DELAY_S", 0.0)
+    ts, client = await _load_flaky_server(runtime, timeouts=5, max_retries=2)
+
+    result = await _call(ts, "crawl")
+    assert isinstance(result, ToolError)
+    assert result.message.startswith("Timeout while calling MCP tool `crawl` after 2 of 2 retries.")
+    assert client.calls == 3
+
+    # Without retries, a timeout is returned right away
+    ts, client = await _load_flaky_server(runtime, timeouts=5, max_retries=0)
+    result = await _call(ts, "crawl")
+    assert isinstance(result, ToolError)
+    assert result.message.startswith("Timeout while calling MCP tool `crawl`. ")
+    assert client.calls == 1
+
+
+async def test_mcp_tool_call_is_not_retried_past_the_tool_timeout(runtime: Runtime):
+    runtime.config.mcp.client.tool_call_timeout_ms = 1000
+    ts, client = await _load_flaky_server(
+        runtime, timeouts=5, max_retries=3, tools_config=ToolsConfig(timeouts={"crawl": 1.5})
+    )
+
+    result = await _call(ts, "crawl")
+    assert isinstance(result, ToolError)
+    assert "after 0 of 3 retries" in result.message
+    assert client.calls == 1
+
+
+async def _call_slow(ts: KimiToolset, name: str = "Slow", **arguments: object) -> ToolReturnValue:
+    body = ToolCall.FunctionBody(name=name, arguments=json.dumps(arguments))
+    result = ts.handle(ToolCall(id="tc-slow", function=body))
+    assert isinstance(result, asyncio.Task)
+    return (await result).return_value
+
+
+async def test_tool_call_past_its_timeout_is_stopped():
+    ts = KimiToolset(ToolsConfig(timeout_s=0.05))
+    ts.add(SlowTool())
+
+    ret = await _call_slow(ts)
+
+    assert isinstance(ret, ToolError)
+    assert ret.brief == "Timeout"
+    assert "0.05 seconds" in ret.message
+    assert (await _call_slow(ts, seconds=0)).output == "done"
+
+
+async def test_tool_timeout_can_be_overridden_or_opted_out_of():
+    ts = KimiToolset(ToolsConfig(timeout_s=0.05, timeouts={"Slow": 0}))
+    ts.add(SlowTool())
+    ts.add(ExemptSlowTool())
+
+    assert (await _call_slow(ts)).output == "done"
+    assert (await _call_slow(ts, "ExemptSlow")).output == "done"
+
+    ts = KimiToolset(ToolsConfig(timeouts={"ExemptSlow": 0.05}))
+    ts.add(ExemptSlowTool())
+    assert isinstance(await _call_slow(ts, "ExemptSlow"), ToolError)
+
+
+async def test_tool_timeout_is_paused_while_waiting_for_approval():
+    ts = KimiToolset(ToolsConfig(timeout_s=0.1))
+    ts.add(SlowTool())
+
+    assert (await _call_slow(ts, seconds=0.2, paused=True)).output == "done"
+
+
+async def test_tool_exception_includes_traceback_only_in_debug(monkeypatch: pytest.MonkeyPatch):
+    ts = _make_toolset()
+    ts.add(FailingTool())
+
+    ret = await _call_slow(ts, "Failing", value="x")
+    assert isinstance(ret, ToolError)
+    assert ret.message == "Error running tool: boom"
+
+    monkeypatch.setattr(toolset_module, "_tool_tracebacks", False)
+    set_tool_tracebacks(True)
+    ret = await _call_slow(ts, "Failing", value="x")
+    assert isinstance(ret, ToolError)
+    assert ret.message.startswith("Error running tool: boom\n\nTraceback (most recent call last):")
+    assert "in __call__" in ret.message
+    assert ret.message.endswith("ValueError: boom")
+
+
 # --- hide/unhide cycle ---
 
 
@@ -0,0 +1,119 @@
+"""Tests for the JSON summary written after each turn with `--summary-file`."""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+from kaos.path import KaosPath
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.tooling import ToolResult, ToolReturnValue
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.llm import LLM
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.soul.turn_summary import (
+    FILE_CHANGES_EXTRA,
+    SHELL_COMMAND_EXTRA,
+    TurnSummaryWriter,
+)
+from kimi_cli.soul.usage import UsageTotals
+from kimi_cli.tools.file.write import WriteFile
+
+
+def _make_soul(runtime: Runtime, tmp_path: Path, provider) -> KimiSoul:
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    toolset = KimiToolset()
+    toolset.add(WriteFile(runtime, runtime.approval))
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=toolset,
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+
+def _result(tool_call_id: str, extras: dict) -> ToolResult:
+    return ToolResult(
+        tool_call_id=tool_call_id,
+        return_value=ToolReturnValue(
+            is_error=False, output="", message="", display=[], extras=extras
+        ),
+    )
+
+
+@pytest.mark.asyncio
+async def test_summary_records_written_files(
+    runtime: Runtime,
+    temp_work_dir: KaosPath,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    target = temp_work_dir / "hello.txt"
+    summary_path = tmp_path / "summary.json"
+    runtime.turn_summary = TurnSummaryWriter(summary_path)
+    provider = (
+        ScriptBuilder()
+        .tool_call("WriteFile", {"path": str(target), "content": "hello"}, id="write-1")
+        .usage(input_other=20, output=5)
+        .turn()
+        .text("Done.")
+        .usage(input_other=40, output=8)
+        .turn()
+        .text("Nothing to do.")
+        .build()
+    )
+    soul = _make_soul(runtime, tmp_path, provider)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+
+    await soul.run("Write hello.txt.")
+
+    [summary] = json.loads(summary_path.read_text())["turns"]
+    assert summary["session_id"] == runtime.session.id
+    assert summary["turn"] == 1
+    assert summary["stop_reason"] == "no_tool_calls"
+    assert summary["step_count"] == 2
+    assert summary["usage"]["input_other"] == 60
+    assert summary["usage"]["output"] == 13
+    assert summary["final_message"] == "Done."
+    assert summary["files"] == {"created": [str(target)], "modified": [], "deleted": []}
+    assert summary["shell_commands"] == []
+
+    await soul.run("Anything else?")
+
+    turns = json.loads(summary_path.read_text())["turns"]
+    assert [turn["turn"] for turn in turns] == [1, 2]
+    assert turns[1]["step_count"] == 1
+    assert turns[1]["final_message"] == "Nothing to do."
+    assert turns[1]["files"]["created"] == []
+
+
+@pytest.mark.asyncio
+async def test_changes_to_the_same_file_are_combined(tmp_path: Path) -> None:
+    writer = TurnSummaryWriter(tmp_path / "summary.json")
+    writer.begin_turn()
+    writer.record(
+        [
+            _result("1", {FILE_CHANGES_EXTRA: {"/a": "created", "/b": "deleted", "/c": "created"}}),
+            _result("2", {FILE_CHANGES_EXTRA: {"/a": "modified", "/b": "created"}}),
+            _result("3", {FILE_CHANGES_EXTRA: {"/c": "deleted"}}),
+            _result("4", {SHELL_COMMAND_EXTRA: {"command": "false", "exit_code": 1}}),
+        ]
+    )
+
+    await writer.end_turn(
+        session_id="s",
+        stop_reason="no_tool_calls",
+        step_count=1,
+        usage=UsageTotals(),
+        final_message=None,
+    )
+
+    [summary] = json.loads((tmp_path / "summary.json").read_text())["turns"]
+    assert summary["files"] == {"created": ["/a"], "modified": ["/b"], "deleted": []}
+    assert summary["shell_commands"] == [{"command": "false", "exit_code": 1}]
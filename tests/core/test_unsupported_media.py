@@ -0,0 +1,175 @@
+"""Tests for downgrading media the model cannot see, as set by `on_unsupported_media`."""
+
+from __future__ import annotations
+
+import base64
+from io import BytesIO
+from pathlib import Path
+
+import pytest
+from kosong.chat_provider.echo import ScriptBuilder
+from kosong.message import Message
+from PIL import Image
+
+import kimi_cli.soul.kimisoul as kimisoul_module
+from kimi_cli.llm import LLM
+from kimi_cli.soul import LLMNotSupported
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.media import downgrade_media
+from kimi_cli.soul.toolset import KimiToolset
+from kimi_cli.wire.types import FilePart, ImageURLPart, TextPart, VideoURLPart
+
+
+def _png() -> bytes:
+    buffer = BytesIO()
+    Image.new("RGB", (8, 8), color=(200, 40, 40)).save(buffer, format="PNG")
+    return buffer.getvalue()
+
+
+def _image(url: str, id: str | None = None) -> ImageURLPart:
+    return ImageURLPart(image_url=ImageURLPart.ImageURL(url=url, id=id))
+
+
+def _png_part() -> ImageURLPart:
+    return _image(f"data:image/png;base64,{base64.b64encode(_png()).decode('ascii')}", "cat.png")
+
+
+def _soul(runtime: Runtime, tmp_path: Path) -> KimiSoul:
+    provider = ScriptBuilder().text("Got it.").build()
+    runtime.llm = LLM(chat_provider=provider, max_context_size=100_000, capabilities=set())
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=KimiToolset(),
+        runtime=runtime,
+    )
+    return KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+
+
+def _user_texts(soul: KimiSoul) -> list[str]:
+    return [message.extract_text() for message in soul.context.history if message.role == "user"]
+
+
+@pytest.mark.asyncio
+async def test_error_mode_rejects_unsupported_media(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    soul = _soul(runtime, tmp_path)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+
+    with pytest.raises(LLMNotSupported):
+        await soul.run([TextPart(text="look"), _png_part()])
+
+    assert _user_texts(soul) == []
+    assert runtime.notifications.claim_for_sink("wire") == []
+
+
+@pytest.mark.asyncio
+async def test_strip_mode_replaces_media_with_a_placeholder(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    runtime.config.on_unsupported_media = "strip"
+    soul = _soul(runtime, tmp_path)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+
+    await soul.run([TextPart(text="look"), _png_part()])
+
+    assert (
+        "look[Image `cat.png` (image/png, 0.1 KB) omitted: "
+        "the current model does not support image input.]"
+    ) in _user_texts(soul)
+    assert soul.context.history[-1].extract_text() == "Got it."
+
+    [notice] = runtime.notifications.claim_for_sink("wire")
+    assert notice.event.type == "media_downgraded"
+    assert notice.event.severity == "warning"
+    assert notice.event.payload["mode"] == "strip"
+    assert notice.event.payload["media"] == [
+        {
+            "kind": "image",
+            "name": "cat.png",
+            "mime_type": "image/png",
+            "size": len(_png()),
+            "described": False,
+        }
+    ]
+
+
+@pytest.mark.asyncio
+async def test_describe_mode_substitutes_a_caption_from_the_vision_model(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    runtime.config.on_unsupported_media = "describe"
+    runtime.config.vision_model = "vision"
+    vision_llm = LLM(
+        chat_provider=ScriptBuilder().text("A cat sitting on a mat.").build(),
+        max_context_size=100_000,
+        capabilities={"image_in"},
+    )
+    aliases: list[str | None] = []
+
+    def fake_clone(_llm, _config, model_alias, **_kwargs) -> LLM:
+        aliases.append(model_alias)
+        return vision_llm
+
+    monkeypatch.setattr(kimisoul_module, "clone_llm_with_model_alias", fake_clone)
+    soul = _soul(runtime, tmp_path)
+    monkeypatch.setattr(kimisoul_module, "wire_send", lambda _msg: None)
+
+    await soul.run([TextPart(text="look"), _png_part()])
+
+    assert aliases == ["vision"]
+    assert (
+        "look[Image `cat.png` (image/png, 0.1 KB), described by another model because the "
+        "current model does not support image input:\nA cat sitting on a mat.]"
+    ) in _user_texts(soul)
+    [notice] = runtime.notifications.claim_for_sink("wire")
+    assert notice.event.payload["mode"] == "describe"
+    assert notice.event.payload["media"][0]["described"] is True
+
+
+@pytest.mark.asyncio
+async def test_describe_mode_without_vision_model_falls_back_to_placeholder() -> None:
+    message = Message(
+        role="user",
+        content=[
+            _image("https://example.com/shots/screen%201.png"),
+            VideoURLPart(video_url=VideoURLPart.VideoURL(url="https://example.com/demo.mp4")),
+        ],
+    )
+
+    downgraded_message, downgraded = await downgrade_media(message, {"video_in"}, vision_llm=None)
+
+    assert downgraded_message.content[0] == TextPart(
+        text="[Image `screen 1.png` omitted: the current model does not support image input.]"
+    )
+    assert isinstance(downgraded_message.content[1], VideoURLPart)
+    assert [(media.kind, media.name, media.size) for media in downgraded] == [
+        ("image", "screen 1.png", None)
+    ]
+
+
+@pytest.mark.asyncio
+async def test_strip_mode_replaces_files_with_a_placeholder() -> None:
+    pdf = base64.b64encode(b"%PDF-1.4\n").decode("ascii")
+    message = Message(
+        role="user",
+        content=[
+            FilePart(file=FilePart.File(url=f"data:application/pdf;base64,{pdf}", name="a.pdf")),
+            FilePart(file=FilePart.File(url="file-abc123")),
+        ],
+    )
+
+    downgraded_message, downgraded = await downgrade_media(message, set(), vision_llm=None)
+
+    assert downgraded_message.extract_text("\n") == (
+        "[File `a.pdf` (application/pdf, 0.0 KB) omitted: the current model does not support "
+        "file input.]\n"
+        "[File `file-abc123` omitted: the current model does not support file input.]"
+    )
+    assert [(media.kind, media.name, media.mime_type, media.size) for media in downgraded] == [
+        ("file", "a.pdf", "application/pdf", 9),
+        ("file", "file-abc123", None, None),
+    ]
@@ -0,0 +1,211 @@
+"""Tests for sending several pending approvals to the Wire client as one batch."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.approval_runtime import ApprovalSource
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import (
+    ClientCapabilities,
+    JSONRPCErrorObject,
+    JSONRPCErrorResponse,
+    JSONRPCRequestMessage,
+    JSONRPCSuccessResponse,
+)
+from kimi_cli.wire.server import WireServer
+from kimi_cli.wire.types import ApprovalRequest, ApprovalRequestBatch, ApprovalResponse
+
+
+def _make_server(
+    runtime: Runtime, tmp_path: Path, *, supports_approval_batch: bool
+) -> tuple[WireServer, list[JSONRPCRequestMessage]]:
+    agent = Agent(
+        name="Approval Batch Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+    server = WireServer(soul)
+    server._client_supports_approval_batch = supports_approval_batch
+    server._approval_batch_window = 0
+    sent: list[JSONRPCRequestMessage] = []
+
+    async def fake_send_msg(msg) -> None:
+        assert isinstance(msg, JSONRPCRequestMessage)
+        sent.append(msg)
+
+    server._send_msg = fake_send_msg  # type: ignore[method-assign]
+    return server, sent
+
+
+def _create_request(runtime: Runtime, request_id: str) -> ApprovalRequest:
+    assert runtime.approval_runtime is not None
+    runtime.approval_runtime.create_request(
+        request_id=request_id,
+        tool_call_id=f"call-{request_id}",
+        sender="WriteFile",
+        action="edit file",
+        description=f"write {request_id}.py",
+        display=[],
+        source=ApprovalSource(kind="foreground_turn", id="turn-1"),
+    )
+    return ApprovalRequest(
+        id=request_id,
+        tool_call_id=f"call-{request_id}",
+        sender="WriteFile",
+        action="edit file",
+        description=f"write {request_id}.py",
+        source_kind="foreground_turn",
+        source_id="turn-1",
+    )
+
+
+async def _request_all(server: WireServer, requests: list[ApprovalRequest]) -> None:
+    for request in requests:
+        await server._request_approval(request)
+    if server._approval_flush_task is not None:
+        await server._approval_flush_task
+
+
+def _record(runtime: Runtime, request_id: str):
+    assert runtime.approval_runtime is not None
+    record = runtime.approval_runtime.get_request(request_id)
+    assert record is not None
+    return record
+
+
+def test_client_capabilities_default_to_individual_requests() -> None:
+    caps = ClientCapabilities()
+
+    assert caps.supports_approval_batch is False
+    assert caps.approval_batch_window_ms == 150
+
+
+@pytest.mark.asyncio
+async def test_batch_resolves_mixed_decisions(runtime: Runtime, tmp_path: Path) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=True)
+    requests = [_create_request(runtime, "a"), _create_request(runtime, "b")]
+
+    await _request_all(server, requests)
+
+    [msg] = sent
+    batch = msg.params
+    assert isinstance(batch, ApprovalRequestBatch)
+    assert msg.id == batch.id
+    assert [request.id for request in batch.requests] == ["a", "b"]
+    assert server._pending_requests == {batch.id: batch}
+
+    await server._handle_response(
+        JSONRPCSuccessResponse(
+            id=batch.id,
+            result={
+                "batch_id": batch.id,
+                "decisions": {
+                    "a": {"response": "approve"},
+                    "b": {"response": "reject", "feedback": "Keep b.py as is."},
+                },
+            },
+        )
+    )
+
+    assert _record(runtime, "a").response == "approve"
+    assert _record(runtime, "b").response == "reject"
+    assert _record(runtime, "b").feedback == "Keep b.py as is."
+    assert server._pending_requests == {}
+    assert len(sent) == 1
+
+
+@pytest.mark.asyncio
+async def test_partial_batch_response_asks_again_for_the_rest(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=True)
+    requests = [_create_request(runtime, name) for name in ("a", "b", "c")]
+    await _request_all(server, requests)
+    batch = sent[0].params
+    assert isinstance(batch, ApprovalRequestBatch)
+
+    await server._handle_response(
+        JSONRPCSuccessResponse(
+            id=batch.id,
+            result={"batch_id": batch.id, "decisions": {"b": {"response": "approve"}}},
+        )
+    )
+
+    assert _record(runtime, "b").status == "resolved"
+    assert _record(runtime, "a").status == "pending"
+    assert _record(runtime, "c").status == "pending"
+    resent = sent[1].params
+    assert isinstance(resent, ApprovalRequestBatch)
+    assert [request.id for request in resent.requests] == ["a", "c"]
+    assert server._pending_requests == {resent.id: resent}
+
+
+@pytest.mark.asyncio
+async def test_batch_error_response_rejects_all(runtime: Runtime, tmp_path: Path) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=True)
+    await _request_all(server, [_create_request(runtime, "a"), _create_request(runtime, "b")])
+    batch = sent[0].params
+    assert isinstance(batch, ApprovalRequestBatch)
+
+    await server._handle_response(
+        JSONRPCErrorResponse(id=batch.id, error=JSONRPCErrorObject(code=-1, message="closed"))
+    )
+
+    assert _record(runtime, "a").response == "reject"
+    assert _record(runtime, "b").response == "reject"
+    assert server._pending_requests == {}
+
+
+@pytest.mark.asyncio
+async def test_request_resolved_elsewhere_is_dropped_from_batch(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=True)
+    await _request_all(server, [_create_request(runtime, "a"), _create_request(runtime, "b")])
+    batch = sent[0].params
+    assert isinstance(batch, ApprovalRequestBatch)
+
+    server._discard_pending_approval(ApprovalResponse(request_id="a", response="approve"))
+    assert server._pending_requests == {batch.id: batch}
+    server._discard_pending_approval(ApprovalResponse(request_id="b", response="reject"))
+    assert server._pending_requests == {}
+
+
+@pytest.mark.asyncio
+async def test_single_request_is_not_batched(runtime: Runtime, tmp_path: Path) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=True)
+
+    await _request_all(server, [_create_request(runtime, "a")])
+
+    [msg] = sent
+    assert isinstance(msg.params, ApprovalRequest)
+    assert msg.id == "a"
+
+
+@pytest.mark.asyncio
+async def test_legacy_client_gets_individual_requests(runtime: Runtime, tmp_path: Path) -> None:
+    server, sent = _make_server(runtime, tmp_path, supports_approval_batch=False)
+    requests = [_create_request(runtime, "a"), _create_request(runtime, "b")]
+
+    await _request_all(server, requests)
+
+    assert [(msg.id, type(msg.params)) for msg in sent] == [
+        ("a", ApprovalRequest),
+        ("b", ApprovalRequest),
+    ]
+    assert server._approval_flush_task is None
+
+    await server._handle_response(
+        JSONRPCSuccessResponse(id="b", result={"request_id": "b", "response": "approve"})
+    )
+
+    assert _record(runtime, "b").response == "approve"
+    assert _record(runtime, "a").status == "pending"
@@ -0,0 +1,99 @@
+"""Tests for JSON-RPC batches sent to the Wire server."""
+
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+from typing import Any
+
+import pytest
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import ErrorCodes
+from kimi_cli.wire.server import WireServer
+
+
+def _make_server(runtime: Runtime, tmp_path: Path) -> WireServer:
+    agent = Agent(
+        name="Batch Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+
+
+async def _read_lines(server: WireServer, *lines: Any) -> list[Any]:
+    """Feed `lines` to the server as stdin, and return what it wrote back as JSON."""
+    reader = asyncio.StreamReader()
+    for line in lines:
+        reader.feed_data(json.dumps(line).encode("utf-8") + b"\n")
+    reader.feed_eof()
+    server._reader = reader
+    await server._read_loop()
+    await asyncio.gather(*server._dispatch_tasks)
+
+    sent: list[Any] = []
+    while not server._write_queue.empty():
+        sent.append(json.loads(server._write_queue.get_nowait().model_dump_json()))
+    return sent
+
+
+@pytest.mark.asyncio
+async def test_batch_gets_one_array_of_responses(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    sent = await _read_lines(
+        server,
+        [
+            {"jsonrpc": "2.0", "method": "cancel", "id": "cancel-1"},
+            {"jsonrpc": "2.0", "method": "cancel"},
+            {"jsonrpc": "2.0", "method": "event", "id": "event-1", "params": {}},
+            42,
+        ],
+    )
+
+    [responses] = sent
+    assert isinstance(responses, list)
+    errors = {resp["id"]: resp["error"]["code"] for resp in responses}
+    assert errors == {
+        "cancel-1": ErrorCodes.INVALID_STATE,
+        "event-1": ErrorCodes.METHOD_NOT_FOUND,
+        None: ErrorCodes.INVALID_REQUEST,
+    }
+
+
+@pytest.mark.asyncio
+async def test_batch_of_notifications_gets_no_response(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    sent = await _read_lines(
+        server,
+        [{"jsonrpc": "2.0", "method": "cancel"}],
+        {"jsonrpc": "2.0", "method": "cancel", "id": "cancel-2"},
+    )
+
+    assert [resp["id"] for resp in sent] == ["cancel-2"]
+
+
+@pytest.mark.asyncio
+async def test_empty_batch_is_an_invalid_request(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    sent = await _read_lines(server, [])
+
+    assert sent == [
+        {
+            "jsonrpc": "2.0",
+            "id": None,
+            "error": {
+                "code": ErrorCodes.INVALID_REQUEST,
+                "message": "Invalid request: empty batch",
+                "data": None,
+            },
+        }
+    ]
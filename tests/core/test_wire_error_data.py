@@ -0,0 +1,86 @@
+"""Tests for the structured `data` of prompt error responses."""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import Any
+
+import pytest
+from kosong.chat_provider import APIConnectionError, APIStatusError, APITimeoutError
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.wire.server as server_module
+from kimi_cli.soul import LLMNotSupported, MaxMessageSizeExceeded
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import ErrorCodes, JSONRPCErrorResponse, JSONRPCPromptMessage
+from kimi_cli.wire.server import WireServer
+
+
+def _make_server(runtime: Runtime, tmp_path: Path) -> WireServer:
+    agent = Agent(
+        name="Error Data Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+
+
+async def _prompt_raising(
+    server: WireServer, error: Exception, monkeypatch: pytest.MonkeyPatch
+) -> JSONRPCErrorResponse:
+    async def _raise(*_args: Any, **_kwargs: Any) -> None:
+        raise error
+
+    monkeypatch.setattr(server_module, "run_soul", _raise)
+    response = await server._handle_prompt(
+        JSONRPCPromptMessage(id="prompt-1", params=JSONRPCPromptMessage.Params(user_input="hi"))
+    )
+    assert isinstance(response, JSONRPCErrorResponse)
+    return response
+
+
+@pytest.mark.asyncio
+async def test_llm_not_supported_lists_missing_capabilities(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    assert runtime.llm is not None
+
+    response = await _prompt_raising(
+        server, LLMNotSupported(runtime.llm, ["image_in", "video_in"]), monkeypatch
+    )
+
+    assert response.error.code == ErrorCodes.LLM_NOT_SUPPORTED
+    assert response.error.data == {"capabilities": ["image_in", "video_in"]}
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize(
+    ("error", "data"),
+    [
+        (
+            APIStatusError(429, "Too many requests", request_id="req-1"),
+            {"kind": "status", "status_code": 429, "request_id": "req-1"},
+        ),
+        (APITimeoutError("Timed out"), {"kind": "timeout"}),
+        (APIConnectionError("Connection refused"), {"kind": "connection"}),
+        (MaxMessageSizeExceeded(1000), {"kind": "message_too_large", "limit": 1000}),
+    ],
+)
+async def test_provider_errors_carry_their_kind(
+    runtime: Runtime,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+    error: Exception,
+    data: dict[str, Any],
+) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    response = await _prompt_raising(server, error, monkeypatch)
+
+    assert response.error.code == ErrorCodes.CHAT_PROVIDER_ERROR
+    assert response.error.message == str(error)
+    assert response.error.data == data
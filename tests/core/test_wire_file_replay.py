@@ -0,0 +1,89 @@
+"""Tests for replaying a recorded wire file into a live `Wire`."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+from kosong.tooling import ToolOk
+
+import kimi_cli.wire.file as file_module
+from kimi_cli.wire import Wire, WireUISide
+from kimi_cli.wire.file import MAX_REPLAY_DELAY, WireFile
+from kimi_cli.wire.types import (
+    ApprovalRequest,
+    ApprovalResponse,
+    TextPart,
+    ToolCallRequest,
+    ToolResult,
+    WireMessage,
+)
+
+
+async def _receive_all(ui_side: WireUISide, n: int) -> list[WireMessage]:
+    return [await ui_side.receive() for _ in range(n)]
+
+
+@pytest.mark.asyncio
+async def test_replay_is_paced_by_recorded_timestamps(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    for text, timestamp in [("a", 100.0), ("b", 100.5), ("c", 5000.0)]:
+        await wire_file.append_message(TextPart(text=text), timestamp=timestamp)
+    delays: list[float] = []
+
+    async def fake_sleep(delay: float) -> None:
+        if delay > 0:
+            delays.append(delay)
+
+    monkeypatch.setattr(file_module.asyncio, "sleep", fake_sleep)
+    wire = Wire()
+    ui_side = wire.ui_side(merge=False)
+
+    replayed = await wire_file.replay_into(wire, speed=2)
+
+    assert replayed == 3
+    assert delays == [0.25, MAX_REPLAY_DELAY]
+    assert await _receive_all(ui_side, 3) == [
+        TextPart(text="a"),
+        TextPart(text="b"),
+        TextPart(text="c"),
+    ]
+
+
+@pytest.mark.asyncio
+async def test_requests_resolve_from_recorded_responses(tmp_path: Path) -> None:
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    for msg in [
+        ApprovalRequest(
+            id="approval-1",
+            tool_call_id="tc-1",
+            sender="Shell",
+            action="run command",
+            description="ls",
+        ),
+        ApprovalResponse(request_id="approval-1", response="approve_for_session"),
+        ToolCallRequest(id="tc-2", name="ExternalTool", arguments="{}"),
+        ToolResult(tool_call_id="tc-2", return_value=ToolOk(output="done")),
+    ]:
+        await wire_file.append_message(msg, timestamp=100.0)
+    wire = Wire()
+    ui_side = wire.ui_side(merge=False)
+
+    assert await wire_file.replay_into(wire, speed=100) == 4
+
+    approval, _, tool_call, _ = await _receive_all(ui_side, 4)
+    assert isinstance(approval, ApprovalRequest)
+    assert await approval.wait() == "approve_for_session"
+    assert isinstance(tool_call, ToolCallRequest)
+    result = await tool_call.wait()
+    assert result.output == "done"
+
+
+@pytest.mark.asyncio
+async def test_replay_rejects_non_positive_speed(tmp_path: Path) -> None:
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+
+    with pytest.raises(ValueError, match="must be positive"):
+        await wire_file.replay_into(Wire(), speed=0)
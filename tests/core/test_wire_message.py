@@ -6,13 +6,21 @@ from inline_snapshot import snapshot
 from pydantic import BaseModel
 
 from kimi_cli.wire.file import WireMessageRecord
+from kimi_cli.wire.jsonrpc import JSONRPCInMessageAdapter, JSONRPCPromptMessage
 from kimi_cli.wire.serde import deserialize_wire_message, serialize_wire_message
 from kimi_cli.wire.types import (
+    ApprovalBatchResponse,
+    ApprovalDecision,
     ApprovalRequest,
+    ApprovalRequestBatch,
     ApprovalResponse,
     BriefDisplayBlock,
     CompactionBegin,
     CompactionEnd,
+    CompactionProgress,
+    DiffDisplayBlock,
+    DiffHunk,
+    DiffWordChange,
     ImageURLPart,
     MCPLoadingBegin,
     MCPLoadingEnd,
@@ -29,10 +37,13 @@ from kimi_cli.wire.types import (
     StepBegin,
     StepInterrupted,
     StepRetry,
+    SubagentEnd,
     SubagentEvent,
     TextPart,
+    TokenUsage,
     ToolCall,
     ToolCallPart,
+    ToolCallPreview,
     ToolCallRequest,
     ToolResult,
     ToolReturnValue,
@@ -75,8 +86,34 @@ async def test_wire_message_serde():
     )
     _test_serde(msg)
 
+    msg = TurnBegin(
+        user_input=[
+            TextPart(text="What is this?"),
+            ImageURLPart(
+                image_url=ImageURLPart.ImageURL(url="data:image/png;base64,iVBORw0KGgo=", id="1")
+            ),
+        ]
+    )
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "TurnBegin",
+            "payload": {
+                "user_input": [
+                    {"type": "text", "text": "What is this?"},
+                    {
+                        "type": "image_url",
+                        "image_url": {"url": "data:image/png;base64,iVBORw0KGgo=", "id": "1"},
+                    },
+                ]
+            },
+        }
+    )
+    _test_serde(msg)
+
     msg = TurnEnd()
-    assert serialize_wire_message(msg) == snapshot({"type": "TurnEnd", "payload": {}})
+    assert serialize_wire_message(msg) == snapshot(
+        {"type": "TurnEnd", "payload": {"todos_remaining": None, "todos": None}}
+    )
     _test_serde(msg)
 
     msg = SteerInput(user_input="Follow up")
@@ -107,8 +144,10 @@ async def test_wire_message_serde():
     )
     _test_serde(msg)
 
-    msg = StepBegin(n=1)
-    assert serialize_wire_message(msg) == snapshot({"type": "StepBegin", "payload": {"n": 1}})
+    msg = StepBegin(n=1, timestamp=1700000000.5)
+    assert serialize_wire_message(msg) == snapshot(
+        {"type": "StepBegin", "payload": {"n": 1, "timestamp": 1700000000.5}}
+    )
     _test_serde(msg)
 
     msg = StepInterrupted()
@@ -142,8 +181,48 @@ async def test_wire_message_serde():
     assert serialize_wire_message(msg) == snapshot({"type": "CompactionBegin", "payload": {}})
     _test_serde(msg)
 
+    msg = CompactionProgress(text="The user asked to fix", attempt=2)
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "CompactionProgress",
+            "payload": {"text": "The user asked to fix", "think": False, "attempt": 2},
+        }
+    )
+    _test_serde(msg)
+    assert msg.merge_in_place(CompactionProgress(text=" the login bug.", attempt=2))
+    assert msg.text == "The user asked to fix the login bug."
+    assert not msg.merge_in_place(CompactionProgress(text="The user", attempt=3))
+    assert not msg.merge_in_place(CompactionProgress(text="Hmm", think=True, attempt=2))
+
     msg = CompactionEnd()
-    assert serialize_wire_message(msg) == snapshot({"type": "CompactionEnd", "payload": {}})
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "CompactionEnd",
+            "payload": {
+                "marker_id": None,
+                "n_summarized": None,
+                "before_tokens": None,
+                "after_tokens": None,
+            },
+        }
+    )
+    _test_serde(msg)
+    assert deserialize_wire_message({"type": "CompactionEnd", "payload": {}}) == msg
+
+    msg = CompactionEnd(
+        marker_id="3f2a9c1e", n_summarized=38, before_tokens=180000, after_tokens=4200
+    )
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "CompactionEnd",
+            "payload": {
+                "marker_id": "3f2a9c1e",
+                "n_summarized": 38,
+                "before_tokens": 180000,
+                "after_tokens": 4200,
+            },
+        }
+    )
     _test_serde(msg)
 
     msg = MCPLoadingBegin()
@@ -172,6 +251,8 @@ async def test_wire_message_serde():
                 "context_tokens": None,
                 "max_context_tokens": None,
                 "token_usage": None,
+                "session_usage": None,
+                "session_compaction_usage": None,
                 "message_id": None,
                 "plan_mode": None,
                 "mcp_status": {
@@ -187,6 +268,35 @@ async def test_wire_message_serde():
                         }
                     ],
                 },
+                "phase": None,
+                "attempt": None,
+                "elapsed_ms": None,
+                "rate_limit": None,
+                "step_metrics": None,
+            },
+        }
+    )
+    _test_serde(msg)
+
+    msg = StatusUpdate(phase="running_tools", elapsed_ms=120500)
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "StatusUpdate",
+            "payload": {
+                "context_usage": None,
+                "context_tokens": None,
+                "max_context_tokens": None,
+                "token_usage": None,
+                "session_usage": None,
+                "session_compaction_usage": None,
+                "message_id": None,
+                "plan_mode": None,
+                "mcp_status": None,
+                "phase": "running_tools",
+                "attempt": None,
+                "elapsed_ms": 120500,
+                "rate_limit": None,
+                "step_metrics": None,
             },
         }
     )
@@ -279,6 +389,15 @@ async def test_wire_message_serde():
     )
     _test_serde(msg)
 
+    msg = ToolCallPreview(id="call_123", name="bash", arguments={"command": "ls -"})
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "ToolCallPreview",
+            "payload": {"id": "call_123", "name": "bash", "arguments": {"command": "ls -"}},
+        }
+    )
+    _test_serde(msg)
+
     msg = ToolResult(
         tool_call_id="call_123",
         return_value=ToolReturnValue(
@@ -330,12 +449,109 @@ async def test_wire_message_serde():
                 "parent_tool_call_id": "call_parent_789",
                 "agent_id": "a1234567",
                 "subagent_type": "coder",
-                "event": {"type": "StepBegin", "payload": {"n": 2}},
+                "depth": 1,
+                "event": {"type": "StepBegin", "payload": {"n": 2, "timestamp": None}},
             },
         }
     )
     _test_serde(msg)
 
+    msg = SubagentEvent(
+        parent_tool_call_id="call_parent_789",
+        agent_id="a1234567",
+        subagent_type="coder",
+        event=SubagentEvent(
+            parent_tool_call_id="call_child_123",
+            agent_id="a7654321",
+            subagent_type="explore",
+            depth=3,
+            event=ToolCall(
+                id="call_grandchild_456",
+                function=ToolCall.FunctionBody(name="ReadFile", arguments='{"path": "a.py"}'),
+            ),
+        ),
+    )
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "SubagentEvent",
+            "payload": {
+                "parent_tool_call_id": "call_parent_789",
+                "agent_id": "a1234567",
+                "subagent_type": "coder",
+                "depth": 1,
+                "event": {
+                    "type": "SubagentEvent",
+                    "payload": {
+                        "parent_tool_call_id": "call_child_123",
+                        "agent_id": "a7654321",
+                        "subagent_type": "explore",
+                        "depth": 3,
+                        "event": {
+                            "type": "ToolCall",
+                            "payload": {
+                                "type": "function",
+                                "id": "call_grandchild_456",
+                                "function": {
+                                    "name": "ReadFile",
+                                    "arguments": '{"path": "a.py"}',
+                                },
+                                "extras": None,
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    )
+    _test_serde(msg)
+
+    msg = SubagentEnd(
+        parent_tool_call_id="call_parent_789",
+        agent_id="a1234567",
+        subagent_type="coder",
+        status="completed",
+        final_message="All tests pass.",
+        usage=TokenUsage(input_other=100, output=20, input_cache_read=50),
+    )
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "SubagentEnd",
+            "payload": {
+                "parent_tool_call_id": "call_parent_789",
+                "agent_id": "a1234567",
+                "subagent_type": "coder",
+                "status": "completed",
+                "final_message": "All tests pass.",
+                "usage": {
+                    "input_other": 100,
+                    "output": 20,
+                    "input_cache_read": 50,
+                    "input_cache_creation": 0,
+                },
+            },
+        }
+    )
+    _test_serde(msg)
+
+    msg = SubagentEnd(status="failed")
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "SubagentEnd",
+            "payload": {
+                "parent_tool_call_id": None,
+                "agent_id": None,
+                "subagent_type": None,
+                "status": "failed",
+                "final_message": None,
+                "usage": None,
+            },
+        }
+    )
+    _test_serde(msg)
+
+    with pytest.raises(ValueError):
+        SubagentEvent(depth=0, event=StepBegin(n=1))
+
     legacy_msg = deserialize_wire_message(
         {
             "type": "SubagentEvent",
@@ -439,6 +655,87 @@ async def test_wire_message_serde():
     _test_serde(msg)
 
 
+def test_truncated_diff_display_block_serde():
+    msg = ToolResult(
+        tool_call_id="call_123",
+        return_value=ToolReturnValue(
+            is_error=False,
+            output="",
+            message="File edited",
+            display=[
+                DiffDisplayBlock(
+                    path="big.txt",
+                    old_text="(20000 lines)",
+                    new_text="(20000 lines, modified)",
+                    is_summary=True,
+                    truncated=True,
+                    hunks=[
+                        DiffHunk(
+                            old_text="a = 1",
+                            new_text="a = 2",
+                            old_start=42,
+                            new_start=42,
+                            word_changes=[
+                                DiffWordChange(
+                                    old_line=42,
+                                    new_line=42,
+                                    old_ranges=[(4, 5)],
+                                    new_ranges=[(4, 5)],
+                                )
+                            ],
+                        )
+                    ],
+                    omitted_hunks=2,
+                )
+            ],
+        ),
+    )
+    assert serialize_wire_message(msg) == snapshot(
+        {
+            "type": "ToolResult",
+            "payload": {
+                "tool_call_id": "call_123",
+                "return_value": {
+                    "is_error": False,
+                    "output": "",
+                    "message": "File edited",
+                    "display": [
+                        {
+                            "type": "diff",
+                            "path": "big.txt",
+                            "old_text": "(20000 lines)",
+                            "new_text": "(20000 lines, modified)",
+                            "old_start": 1,
+                            "new_start": 1,
+                            "is_summary": True,
+                            "truncated": True,
+                            "hunks": [
+                                {
+                                    "old_text": "a = 1",
+                                    "new_text": "a = 2",
+                                    "old_start": 42,
+                                    "new_start": 42,
+                                    "word_changes": [
+                                        {
+                                            "old_line": 42,
+                                            "new_line": 42,
+                                            "old_ranges": [[4, 5]],
+                                            "new_ranges": [[4, 5]],
+                                        }
+                                    ],
+                                }
+                            ],
+                            "omitted_hunks": 2,
+                        }
+                    ],
+                    "extras": None,
+                },
+            },
+        }
+    )
+    _test_serde(msg)
+
+
 async def test_approval_request_deserialize_without_display():
     msg = deserialize_wire_message(
         {
@@ -457,6 +754,42 @@ async def test_approval_request_deserialize_without_display():
     assert msg.display == []
 
 
+def test_approval_request_batch_serde():
+    msg = ApprovalRequestBatch(
+        id="batch_1",
+        requests=[
+            ApprovalRequest(
+                id="request_1",
+                tool_call_id="call_1",
+                sender="WriteFile",
+                action="edit file",
+                description="Write file `a.py`",
+            ),
+            ApprovalRequest(
+                id="request_2",
+                tool_call_id="call_2",
+                sender="WriteFile",
+                action="edit file",
+                description="Write file `b.py`",
+            ),
+        ],
+    )
+    assert serialize_wire_message(msg)["type"] == "ApprovalRequestBatch"
+    _test_serde(msg)
+    assert is_request(msg)
+    assert not msg.resolved
+
+    msg.requests[0].resolve("approve")
+    assert not msg.resolved
+    msg.requests[1].resolve("reject")
+    assert msg.resolved
+
+    response = ApprovalBatchResponse.model_validate(
+        {"batch_id": "batch_1", "decisions": {"request_1": {"response": "approve"}}}
+    )
+    assert response.decisions == {"request_1": ApprovalDecision(response="approve")}
+
+
 def test_wire_message_record_roundtrip():
     envelope = WireMessageEnvelope.from_wire_message(TurnBegin(user_input=[TextPart(text="hi")]))
     record = WireMessageRecord(timestamp=123.456, message=envelope)
@@ -476,6 +809,75 @@ def test_wire_message_record_roundtrip():
     assert parsed.to_wire_message() == TurnBegin(user_input=[TextPart(text="hi")])
 
 
+def test_wire_message_record_with_seq_and_timestamp():
+    record = WireMessageRecord.from_wire_message(StepBegin(n=1), timestamp=123.456, seq=7)
+
+    assert record.model_dump(mode="json") == snapshot(
+        {
+            "timestamp": 123.456,
+            "message": {"type": "StepBegin", "payload": {"n": 1}, "seq": 7, "timestamp": 123.456},
+        }
+    )
+    parsed = WireMessageRecord.model_validate_json(record.model_dump_json())
+    assert parsed.seq == 7
+    assert parsed.message.timestamp == 123.456
+
+    legacy = WireMessageRecord.model_validate(
+        {"timestamp": 1.0, "message": {"type": "StepBegin", "payload": {"n": 1}}}
+    )
+    assert legacy.seq is None
+    assert legacy.message.timestamp is None
+
+
+def test_prompt_message_with_content_parts():
+    msg = JSONRPCInMessageAdapter.validate_python(
+        {
+            "jsonrpc": "2.0",
+            "method": "prompt",
+            "id": "1",
+            "params": {
+                "user_input": [
+                    {"type": "text", "text": "What is this?"},
+                    {
+                        "type": "image_url",
+                        "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="},
+                    },
+                ]
+            },
+        }
+    )
+
+    assert isinstance(msg, JSONRPCPromptMessage)
+    assert msg.params.user_input == [
+        TextPart(text="What is this?"),
+        ImageURLPart(image_url=ImageURLPart.ImageURL(url="data:image/png;base64,iVBORw0KGgo=")),
+    ]
+
+
+def test_prompt_message_with_stream_thinking():
+    msg = JSONRPCInMessageAdapter.validate_python(
+        {
+            "jsonrpc": "2.0",
+            "method": "prompt",
+            "id": "1",
+            "params": {"user_input": "Hello", "stream_thinking": "summary"},
+        }
+    )
+
+    assert isinstance(msg, JSONRPCPromptMessage)
+    assert msg.params.stream_thinking == "summary"
+
+    with pytest.raises(ValueError):
+        JSONRPCInMessageAdapter.validate_python(
+            {
+                "jsonrpc": "2.0",
+                "method": "prompt",
+                "id": "1",
+                "params": {"user_input": "Hello", "stream_thinking": "verbose"},
+            }
+        )
+
+
 def test_bad_wire_message_serde():
     with pytest.raises(ValueError):
         deserialize_wire_message(None)
@@ -581,6 +983,36 @@ async def test_type_inspection():
     assert is_request(msg)
 
 
+async def test_tool_call_preview_is_not_merged_or_recorded(tmp_path: Path):
+    from kimi_cli.wire import Wire
+    from kimi_cli.wire.file import WireFile
+
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    wire = Wire(file_backend=wire_file)
+    raw_side = wire.ui_side(merge=False)
+    merged_side = wire.ui_side(merge=True)
+    preview = ToolCallPreview(id="call_1", name="bash", arguments={"command": "ls"})
+    soul_side = wire.soul_side
+    soul_side.send(
+        ToolCall(id="call_1", function=ToolCall.FunctionBody(name="bash", arguments='{"com'))
+    )
+    soul_side.send(ToolCallPart(arguments_part='mand": "ls'))
+    soul_side.send(preview)
+    soul_side.send(ToolCallPart(arguments_part=' -la"}'))
+    wire.shutdown()
+    await wire.join()
+
+    raw = [await raw_side.receive() for _ in range(4)]
+    assert raw[2] == preview
+    merged = await merged_side.receive()
+    assert merged == ToolCall(
+        id="call_1",
+        function=ToolCall.FunctionBody(name="bash", arguments='{"command": "ls -la"}'),
+    )
+    records = [record.to_wire_message() async for record in wire_file.iter_records()]
+    assert records == [merged]
+
+
 async def test_question_request_resolve():
     """Test basic resolve → wait flow for QuestionRequest."""
     request = QuestionRequest(
@@ -639,6 +1071,8 @@ def test_wire_message_type_alias():
         QuestionItem,
         QuestionResponse,
         HookResponse,
+        ApprovalDecision,
+        ApprovalBatchResponse,
     }
 
     wire_message_types = {
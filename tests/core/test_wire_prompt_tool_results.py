@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+import asyncio
+from pathlib import Path
+
+import pydantic
+import pytest
+from kosong.tooling import ToolOk, ToolResult
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import (
+    ErrorCodes,
+    JSONRPCErrorResponse,
+    JSONRPCInMessageAdapter,
+    JSONRPCPromptMessage,
+    JSONRPCSuccessResponse,
+    Statuses,
+)
+from kimi_cli.wire.server import WireServer
+from kimi_cli.wire.types import ToolCallRequest
+
+
+def _make_server(runtime: Runtime, tmp_path: Path) -> WireServer:
+    agent = Agent(
+        name="Tool Results Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+
+
+def _prompt(*tool_results: ToolResult) -> JSONRPCPromptMessage:
+    return JSONRPCPromptMessage(
+        id="prompt-2", params=JSONRPCPromptMessage.Params(tool_results=list(tool_results))
+    )
+
+
+def _result(tool_call_id: str, output: str) -> ToolResult:
+    return ToolResult(tool_call_id=tool_call_id, return_value=ToolOk(output=output))
+
+
+@pytest.mark.asyncio
+async def test_prompt_resolves_pending_external_tool_calls_at_once(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    server._cancel_event = asyncio.Event()  # a turn is in progress
+    first = ToolCallRequest(id="tc-1", name="open", arguments="{}")
+    second = ToolCallRequest(id="tc-2", name="open", arguments="{}")
+    await server._request_external_tool(first)
+    await server._request_external_tool(second)
+
+    response = await server._handle_prompt(
+        _prompt(_result("tc-2", "second"), _result("tc-1", "first"))
+    )
+
+    assert isinstance(response, JSONRPCSuccessResponse)
+    assert response.result == {"status": Statuses.RESUMED, "resolved": ["tc-2", "tc-1"]}
+    assert (await first.wait()).output == "first"
+    assert (await second.wait()).output == "second"
+    assert server._pending_requests == {}
+
+    # Sending tool results does not end the turn, so new input is still rejected
+    response = await server._handle_prompt(
+        JSONRPCPromptMessage(id="prompt-3", params=JSONRPCPromptMessage.Params(user_input="hi"))
+    )
+    assert isinstance(response, JSONRPCErrorResponse)
+    assert response.error.code == ErrorCodes.INVALID_STATE
+
+
+@pytest.mark.asyncio
+async def test_prompt_with_unknown_tool_call_resolves_nothing(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    server._cancel_event = asyncio.Event()
+    request = ToolCallRequest(id="tc-1", name="open", arguments="{}")
+    await server._request_external_tool(request)
+
+    response = await server._handle_prompt(
+        _prompt(_result("tc-1", "first"), _result("tc-9", "unknown"), _result("tc-1", "again"))
+    )
+
+    assert isinstance(response, JSONRPCErrorResponse)
+    assert response.error.code == ErrorCodes.INVALID_PARAMS
+    assert "tc-9, tc-1" in response.error.message
+    assert not request.resolved
+    assert server._pending_requests == {"tc-1": request}
+
+
+@pytest.mark.asyncio
+async def test_prompt_with_tool_results_requires_a_turn_in_progress(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    response = await server._handle_prompt(_prompt(_result("tc-1", "first")))
+
+    assert isinstance(response, JSONRPCErrorResponse)
+    assert response.error.code == ErrorCodes.INVALID_STATE
+
+
+def test_prompt_params_take_either_user_input_or_tool_results() -> None:
+    msg = JSONRPCInMessageAdapter.validate_python(
+        {
+            "jsonrpc": "2.0",
+            "method": "prompt",
+            "id": "1",
+            "params": {
+                "tool_results": [
+                    {
+                        "tool_call_id": "tc-1",
+                        "return_value": {
+                            "is_error": False,
+                            "output": "ok",
+                            "message": "",
+                            "display": [],
+                        },
+                    }
+                ]
+            },
+        }
+    )
+    assert isinstance(msg, JSONRPCPromptMessage)
+    assert msg.params.user_input is None
+    assert msg.params.tool_results is not None
+    assert [(r.tool_call_id, r.return_value.output) for r in msg.params.tool_results] == [
+        ("tc-1", "ok")
+    ]
+
+    with pytest.raises(pydantic.ValidationError):
+        JSONRPCPromptMessage.Params()
+    with pytest.raises(pydantic.ValidationError):
+        JSONRPCPromptMessage.Params(user_input="hi", tool_results=[])
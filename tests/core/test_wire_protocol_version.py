@@ -0,0 +1,133 @@
+"""Tests for negotiating the Wire protocol version in `initialize`."""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import Any
+
+import pytest
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import (
+    ErrorCodes,
+    JSONRPCErrorResponse,
+    JSONRPCEventMessage,
+    JSONRPCInitializeMessage,
+    JSONRPCSuccessResponse,
+)
+from kimi_cli.wire.protocol import (
+    WIRE_PROTOCOL_LEGACY_VERSION,
+    WIRE_PROTOCOL_VERSION,
+    negotiate_protocol_version,
+)
+from kimi_cli.wire.server import WireServer
+from kimi_cli.wire.types import StepBegin, SubagentEvent, TextPart, TurnEnd
+
+
+def _make_server(runtime: Runtime, tmp_path: Path) -> WireServer:
+    agent = Agent(
+        name="Protocol Version Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+
+
+def _initialize(protocol_version: str) -> JSONRPCInitializeMessage:
+    return JSONRPCInitializeMessage(
+        id="init", params=JSONRPCInitializeMessage.Params(protocol_version=protocol_version)
+    )
+
+
+def _drain_params(server: WireServer) -> list[Any]:
+    sent: list[Any] = []
+    while not server._write_queue.empty():
+        sent.append(server._write_queue.get_nowait().model_dump(mode="json")["params"])
+    return sent
+
+
+@pytest.mark.parametrize(
+    ("client_version", "expected"),
+    [
+        ("1.11", WIRE_PROTOCOL_VERSION),
+        ("1.42", WIRE_PROTOCOL_VERSION),
+        ("1.7", WIRE_PROTOCOL_VERSION),
+        ("1.1", WIRE_PROTOCOL_LEGACY_VERSION),
+        ("1.0", None),
+        ("2.0", None),
+        ("latest", None),
+    ],
+)
+def test_negotiate_protocol_version(client_version: str, expected: str | None) -> None:
+    assert negotiate_protocol_version(client_version) == expected
+
+
+@pytest.mark.asyncio
+async def test_current_client_gets_current_messages(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    response = await server._handle_initialize(_initialize("1.11"))
+    await server._send_msg(JSONRPCEventMessage(params=TurnEnd()))
+
+    assert isinstance(response, JSONRPCSuccessResponse)
+    assert isinstance(response.result, dict)
+    assert response.result["protocol_version"] == WIRE_PROTOCOL_VERSION
+    [turn_end] = _drain_params(server)
+    assert turn_end["type"] == "TurnEnd"
+    assert turn_end["seq"] == 1
+
+
+@pytest.mark.asyncio
+async def test_legacy_client_gets_legacy_shaped_messages(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    response = await server._handle_initialize(_initialize("1.1"))
+    assert isinstance(response, JSONRPCSuccessResponse)
+    assert isinstance(response.result, dict)
+    assert response.result["protocol_version"] == WIRE_PROTOCOL_LEGACY_VERSION
+
+    await server._send_msg(JSONRPCEventMessage(params=StepBegin(n=1, timestamp=1.5)))
+    await server._send_msg(JSONRPCEventMessage(params=TurnEnd()))
+    await server._send_msg(
+        JSONRPCEventMessage(
+            params=SubagentEvent(
+                parent_tool_call_id="tc-1",
+                agent_id="agent-1",
+                subagent_type="coder",
+                event=TextPart(text="hi"),
+            )
+        )
+    )
+    await server._send_msg(
+        JSONRPCEventMessage(params=SubagentEvent(parent_tool_call_id="tc-1", event=TurnEnd()))
+    )
+
+    assert _drain_params(server) == [
+        {"type": "StepBegin", "payload": {"n": 1}},
+        {
+            "type": "SubagentEvent",
+            "payload": {
+                "task_tool_call_id": "tc-1",
+                "event": {"type": "ContentPart", "payload": {"type": "text", "text": "hi"}},
+            },
+        },
+    ]
+    assert not server._resume_buffer
+
+
+@pytest.mark.asyncio
+async def test_unsupported_version_is_rejected(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    response = await server._handle_initialize(_initialize("2.0"))
+
+    assert isinstance(response, JSONRPCErrorResponse)
+    assert response.error.code == ErrorCodes.INVALID_PARAMS
+    assert response.error.data == {
+        "supported": [WIRE_PROTOCOL_LEGACY_VERSION, WIRE_PROTOCOL_VERSION]
+    }
+    assert not server._initialized
@@ -0,0 +1,167 @@
+"""Tests for resuming a turn after the Wire client reconnects."""
+
+from __future__ import annotations
+
+import asyncio
+from pathlib import Path
+
+import pytest
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.wire.server as server_module
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import (
+    ErrorCodes,
+    JSONRPCErrorResponse,
+    JSONRPCEventMessage,
+    JSONRPCInitializeMessage,
+    JSONRPCOutMessage,
+    JSONRPCReplayMessage,
+    JSONRPCSuccessResponse,
+)
+from kimi_cli.wire.server import WireServer
+from kimi_cli.wire.types import TextPart, ToolCallRequest
+
+
+def _make_server(runtime: Runtime, tmp_path: Path) -> WireServer:
+    agent = Agent(
+        name="Resume Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    return WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+
+
+def _drain(server: WireServer) -> list[JSONRPCOutMessage]:
+    sent: list[JSONRPCOutMessage] = []
+    while not server._write_queue.empty():
+        sent.append(server._write_queue.get_nowait())  # type: ignore[arg-type]
+    return sent
+
+
+async def _send_text(server: WireServer, text: str) -> None:
+    await server._send_msg(JSONRPCEventMessage(params=TextPart(text=text)))
+
+
+def _initialize(last_seq: int | None) -> JSONRPCInitializeMessage:
+    return JSONRPCInitializeMessage(
+        id="init",
+        params=JSONRPCInitializeMessage.Params(protocol_version="1.11", last_seq=last_seq),
+    )
+
+
+@pytest.mark.asyncio
+async def test_events_and_requests_carry_increasing_seq(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+
+    await _send_text(server, "a")
+    await server._request_external_tool(ToolCallRequest(id="tc-1", name="open", arguments="{}"))
+
+    sent = _drain(server)
+    assert [msg.model_dump(mode="json")["params"]["seq"] for msg in sent] == [1, 2]
+    assert "seq" not in sent[0].model_dump(mode="json")
+    for msg in sent:
+        assert isinstance(msg.model_dump(mode="json")["params"]["timestamp"], float)
+
+
+@pytest.mark.asyncio
+async def test_resume_sends_missed_events_and_pending_requests(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    server._cancel_event = asyncio.Event()  # a turn is in progress
+    await _send_text(server, "a")
+    await server._request_external_tool(ToolCallRequest(id="tc-1", name="open", arguments="{}"))
+    await _send_text(server, "b")
+    await _send_text(server, "c")
+    _drain(server)
+
+    resp = await server._handle_initialize(_initialize(last_seq=3))
+
+    assert isinstance(resp, JSONRPCSuccessResponse)
+    assert isinstance(resp.result, dict)
+    assert resp.result["resume"] == {"complete": True, "replayed": 2, "in_progress": True}
+    resent = [msg.model_dump(mode="json")["params"] for msg in _drain(server)]
+    assert [(params["type"], params["seq"]) for params in resent] == [
+        ("ToolCallRequest", 2),
+        ("TextPart", 4),
+    ]
+
+
+@pytest.mark.asyncio
+async def test_resume_skips_answered_requests(runtime: Runtime, tmp_path: Path) -> None:
+    server = _make_server(runtime, tmp_path)
+    server._cancel_event = asyncio.Event()
+    await server._request_external_tool(ToolCallRequest(id="tc-1", name="open", arguments="{}"))
+    server._pending_requests.clear()
+    _drain(server)
+
+    resp = await server._handle_initialize(_initialize(last_seq=0))
+
+    assert isinstance(resp, JSONRPCSuccessResponse)
+    assert isinstance(resp.result, dict)
+    assert resp.result["resume"] == {"complete": True, "replayed": 0, "in_progress": True}
+    assert _drain(server) == []
+
+
+@pytest.mark.asyncio
+async def test_resume_is_incomplete_when_cursor_is_no_longer_buffered(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(server_module, "RESUME_BUFFER_SIZE", 2)
+    server = _make_server(runtime, tmp_path)
+    for text in ("a", "b", "c"):
+        await _send_text(server, text)
+    _drain(server)
+
+    resp = await server._handle_initialize(_initialize(last_seq=0))
+
+    assert isinstance(resp, JSONRPCSuccessResponse)
+    assert isinstance(resp.result, dict)
+    assert resp.result["resume"] == {"complete": False, "replayed": 2, "in_progress": False}
+
+
+@pytest.mark.asyncio
+async def test_initialize_without_cursor_is_rejected_during_turn(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    server._cancel_event = asyncio.Event()
+
+    resp = await server._handle_initialize(_initialize(last_seq=None))
+
+    assert isinstance(resp, JSONRPCErrorResponse)
+    assert resp.error.code == ErrorCodes.INVALID_STATE
+
+
+@pytest.mark.asyncio
+async def test_replay_is_paced_by_recorded_timestamps(
+    runtime: Runtime, tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    server = _make_server(runtime, tmp_path)
+    for text, timestamp in [("a", 100.0), ("b", 100.5), ("c", 5000.0)]:
+        await runtime.session.wire_file.append_message(TextPart(text=text), timestamp=timestamp)
+    delays: list[float] = []
+
+    async def fake_wait_for(awaitable, timeout: float) -> None:
+        awaitable.close()
+        delays.append(timeout)
+        raise TimeoutError
+
+    monkeypatch.setattr(server_module.asyncio, "wait_for", fake_wait_for)
+
+    resp = await server._handle_replay(
+        JSONRPCReplayMessage(id="replay", params=JSONRPCReplayMessage.Params(speed=2))
+    )
+
+    assert isinstance(resp, JSONRPCSuccessResponse)
+    assert delays == [0.25, server_module.MAX_REPLAY_DELAY]
+    replayed = [msg.model_dump(mode="json")["params"] for msg in _drain(server)]
+    assert [(params["payload"]["text"], params["timestamp"]) for params in replayed] == [
+        ("a", 100.0),
+        ("b", 100.5),
+        ("c", 5000.0),
+    ]
@@ -1,6 +1,8 @@
 from __future__ import annotations
 
 import asyncio
+import signal
+import time
 from pathlib import Path
 
 import pytest
@@ -8,6 +10,8 @@ from kosong.message import ContentPart
 from kosong.tooling.empty import EmptyToolset
 
 import kimi_cli.telemetry as telemetry_mod
+import kimi_cli.utils.signals as signals_module
+import kimi_cli.wire.server as server_module
 from kimi_cli.approval_runtime import ApprovalSource
 from kimi_cli.soul.agent import Agent, Runtime
 from kimi_cli.soul.context import Context
@@ -20,10 +24,12 @@ from kimi_cli.wire.jsonrpc import (
     JSONRPCErrorResponse,
     JSONRPCEventMessage,
     JSONRPCPromptMessage,
+    JSONRPCShutdownMessage,
     JSONRPCSteerMessage,
     JSONRPCSuccessResponse,
     Statuses,
 )
+from kimi_cli.wire.protocol import WIRE_PROTOCOL_LEGACY_VERSION
 from kimi_cli.wire.server import WireServer
 from kimi_cli.wire.types import ApprovalRequest, ApprovalResponse, TextPart
 
@@ -117,6 +123,120 @@ async def test_handle_steer_queues_input_when_streaming(
     assert queued == [[TextPart(text="follow-up")]]
 
 
+@pytest.mark.asyncio
+async def test_interrupt_stops_gracefully_then_force_quits(
+    runtime: Runtime,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    server = WireServer(_make_soul(runtime, tmp_path))
+    exit_codes: list[int] = []
+    monkeypatch.setattr(signals_module.os, "_exit", exit_codes.append)
+    stop_event = asyncio.Event()
+
+    server._interrupt(stop_event, signal.SIGTERM)
+
+    assert stop_event.is_set()
+    assert exit_codes == []
+    assert server.exit_code == 143
+
+    server._interrupt(stop_event, signal.SIGINT)
+
+    assert exit_codes == [130]
+
+
+@pytest.mark.asyncio
+async def test_late_second_interrupt_does_not_force_quit(
+    runtime: Runtime,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    server = WireServer(_make_soul(runtime, tmp_path))
+    exit_codes: list[int] = []
+    monkeypatch.setattr(signals_module.os, "_exit", exit_codes.append)
+    server._interrupt(asyncio.Event(), signal.SIGINT)
+    server._shutdown_signals._last_received = (
+        time.monotonic() - signals_module.FORCE_QUIT_WINDOW - 1
+    )
+
+    server._interrupt(asyncio.Event(), signal.SIGINT)
+
+    assert exit_codes == []
+
+
+@pytest.mark.asyncio
+async def test_shutdown_by_signal_tells_the_client(runtime: Runtime, tmp_path: Path) -> None:
+    server = WireServer(_make_soul(runtime, tmp_path))
+    server._initialized = True
+
+    await server._shutdown(signum=signal.SIGTERM)
+
+    sent = []
+    while not server._write_queue.empty():
+        sent.append(server._write_queue.get_nowait())
+    assert sent == [JSONRPCShutdownMessage(params=JSONRPCShutdownMessage.Params(signal="SIGTERM"))]
+    assert sent[0].model_dump(mode="json") == {
+        "jsonrpc": "2.0",
+        "method": "shutdown",
+        "params": {"signal": "SIGTERM"},
+    }
+
+
+@pytest.mark.asyncio
+async def test_shutdown_by_signal_is_not_sent_to_legacy_clients(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    server = WireServer(_make_soul(runtime, tmp_path))
+    server._initialized = True
+    server._protocol_version = WIRE_PROTOCOL_LEGACY_VERSION
+
+    await server._shutdown(signum=signal.SIGTERM)
+
+    assert server._write_queue.empty()
+
+
+class _NullWriter:
+    def write(self, data: bytes) -> None:
+        pass
+
+    async def drain(self) -> None:
+        pass
+
+
+@pytest.mark.asyncio
+async def test_serve_shuts_down_when_idle(
+    runtime: Runtime,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    runtime.config.session.idle_timeout_secs = 0.05
+    server = WireServer(_make_soul(runtime, tmp_path))
+
+    async def _stdio_streams(limit: int) -> tuple[asyncio.StreamReader, _NullWriter]:
+        return asyncio.StreamReader(limit=limit), _NullWriter()
+
+    monkeypatch.setattr(server_module.acp, "stdio_streams", _stdio_streams)
+
+    await asyncio.wait_for(server.serve(), timeout=5)
+
+    assert server.exit_code is None
+
+
+@pytest.mark.asyncio
+async def test_idle_timeout_waits_for_the_running_turn(runtime: Runtime, tmp_path: Path) -> None:
+    server = WireServer(_make_soul(runtime, tmp_path))
+    server._cancel_event = asyncio.Event()
+    idle = asyncio.create_task(server._wait_until_idle(0.05))
+
+    await asyncio.sleep(0.2)
+    assert not idle.done()
+
+    # The timer restarts when the turn ends
+    server._cancel_event = None
+    server._last_request_at = time.monotonic()
+    await asyncio.wait_for(idle, timeout=5)
+
+
 @pytest.mark.asyncio
 async def test_shutdown_rejects_foreground_approval_in_runtime(
     runtime: Runtime,
@@ -0,0 +1,51 @@
+"""Tests for lines longer than the Wire server's stdio buffer limit."""
+
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+
+import pytest
+from kosong.tooling.empty import EmptyToolset
+
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.wire.jsonrpc import ErrorCodes
+from kimi_cli.wire.server import WireServer
+
+
+@pytest.mark.asyncio
+async def test_oversized_line_is_a_parse_error_and_skipped(
+    runtime: Runtime, tmp_path: Path
+) -> None:
+    agent = Agent(
+        name="Stdio Limit Test Agent",
+        system_prompt="Test prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    server = WireServer(KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl")))
+    reader = asyncio.StreamReader(limit=1024)
+    server._reader = reader
+
+    read_task = asyncio.create_task(server._read_loop())
+    # The oversized line arrives in several pieces, none of which holds its line break
+    oversized = json.dumps({"jsonrpc": "2.0", "method": "cancel", "id": "x" * 10_000}).encode()
+    for start in range(0, len(oversized), 3000):
+        reader.feed_data(oversized[start : start + 3000])
+        await asyncio.sleep(0)
+    reader.feed_data(b"\n")
+    reader.feed_data(b'{"jsonrpc": "2.0", "method": "cancel", "id": "cancel-1"}\n')
+    reader.feed_eof()
+    await read_task
+    await asyncio.gather(*server._dispatch_tasks)
+
+    sent = []
+    while not server._write_queue.empty():
+        sent.append(json.loads(server._write_queue.get_nowait().model_dump_json()))
+    assert [(msg["id"], msg["error"]["code"]) for msg in sent] == [
+        (None, ErrorCodes.PARSE_ERROR),
+        ("cancel-1", ErrorCodes.INVALID_STATE),
+    ]
@@ -169,7 +169,7 @@ def _run_wire_mode(
             "jsonrpc": "2.0",
             "id": "init",
             "method": "initialize",
-            "params": {"protocol_version": "1.1"},
+            "params": {"protocol_version": "1.11"},
         },
     )
     init_resp, _ = _collect_until_response(process, "init")
@@ -271,7 +271,7 @@ def test_scripted_echo_media_e2e(temp_work_dir: KaosPath, tmp_path: Path, mode:
                 "jsonrpc": "2.0",
                 "id": "init",
                 "method": "initialize",
-                "params": {"protocol_version": "1.1"},
+                "params": {"protocol_version": "1.11"},
             },
         )
         init_resp, _ = _collect_until_response(process, "init")
@@ -0,0 +1,103 @@
+"""E2E tests for stopping a running turn with a signal."""
+
+from __future__ import annotations
+
+import json
+import os
+import signal
+import subprocess
+import sys
+import time
+from collections.abc import Callable
+from pathlib import Path
+
+import pytest
+
+from tests_e2e.wire_helpers import (
+    build_shell_tool_call,
+    make_env,
+    make_home_dir,
+    make_work_dir,
+    repo_root,
+    share_dir,
+    write_scripted_config,
+)
+
+pytestmark = pytest.mark.skipif(sys.platform == "win32", reason="POSIX signals only")
+
+
+def _is_running(pid: int) -> bool:
+    try:
+        os.kill(pid, 0)
+    except ProcessLookupError:
+        return False
+    # A zombie has exited, it is just not reaped yet
+    stat = Path(f"/proc/{pid}/stat")
+    return not (stat.exists() and stat.read_text().rsplit(")", 1)[-1].split()[0] == "Z")
+
+
+def _wait_for(condition: Callable[[], object], timeout: float) -> bool:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        if condition():
+            return True
+        time.sleep(0.1)
+    return bool(condition())
+
+
+@pytest.mark.parametrize("signum", [signal.SIGINT, signal.SIGTERM])
+def test_signal_stops_print_mode_gracefully(tmp_path: Path, signum: signal.Signals) -> None:
+    home_dir = make_home_dir(tmp_path)
+    work_dir = make_work_dir(tmp_path)
+    pid_file = work_dir / "shell.pid"
+    # The shell replaces itself with `sleep`, so the recorded PID is the long-running child
+    scripts = [
+        build_shell_tool_call("call-1", f"echo $$ > {pid_file}; exec sleep 60"),
+        "text: unreachable",
+    ]
+    config_path = write_scripted_config(tmp_path, scripts)
+    cmd = [
+        sys.executable,
+        "-m",
+        "kimi_cli.cli",
+        "--print",
+        "--yolo",
+        "--output-format",
+        "stream-json",
+        "--config-file",
+        str(config_path),
+        "--work-dir",
+        str(work_dir),
+        "--prompt",
+        "Run the long command.",
+    ]
+    process = subprocess.Popen(
+        cmd,
+        cwd=repo_root(),
+        stdin=subprocess.DEVNULL,
+        stdout=subprocess.PIPE,
+        stderr=subprocess.STDOUT,
+        text=True,
+        env=make_env(home_dir),
+    )
+    try:
+        assert _wait_for(lambda: pid_file.exists() and pid_file.read_text().strip(), 30)
+        child_pid = int(pid_file.read_text())
+        assert _is_running(child_pid)
+
+        process.send_signal(signum)
+        return_code = process.wait(timeout=30)
+    finally:
+        if process.poll() is None:
+            process.kill()
+            process.wait()
+
+    assert return_code == 128 + signum
+    assert _wait_for(lambda: not _is_running(child_pid), 5)
+
+    context_files = list((share_dir(home_dir) / "sessions").glob("*/*/context.jsonl"))
+    assert len(context_files) == 1
+    records = [json.loads(line) for line in context_files[0].read_text().splitlines() if line]
+    messages = [record for record in records if not record["role"].startswith("_")]
+    # The interrupted step is dropped, the prompt that started the turn is kept
+    assert [message["role"] for message in messages] == ["user"]
@@ -888,6 +888,7 @@ class TestCompactionTracking:
 
     def _make_soul(self, *, before_tokens: int, estimated_after: int) -> Any:
         """Construct a minimal KimiSoul stub bypassing __init__."""
+        from kimi_cli.soul.context import CompactionMarker
         from kimi_cli.soul.kimisoul import KimiSoul
 
         soul = object.__new__(KimiSoul)
@@ -906,6 +907,11 @@ class TestCompactionTracking:
         ctx.write_system_prompt = AsyncMock()
         ctx.append_message = AsyncMock()
         ctx.update_token_count = AsyncMock()
+        ctx.write_compaction_marker = AsyncMock(
+            return_value=CompactionMarker(
+                id="marker", n_summarized=0, before_tokens=before_tokens, after_tokens=0
+            )
+        )
         soul._context = ctx
 
         soul._hook_engine = MagicMock()
@@ -2,24 +2,33 @@
 
 from __future__ import annotations
 
+import json
 import platform
 import tempfile
 from collections.abc import Generator
 from pathlib import Path
+from unittest.mock import AsyncMock
 
 import pytest
 from kaos.path import KaosPath
 
+import kimi_cli.soul.agent as agent_module
+from kimi_cli.auth.oauth import OAuthManager
+from kimi_cli.config import load_config_from_string
+from kimi_cli.session import Session
 from kimi_cli.soul.agent import Runtime
 from kimi_cli.soul.approval import Approval
 from kimi_cli.tools.file.glob import Glob
 from kimi_cli.tools.file.glob import Params as GlobParams
+from kimi_cli.tools.file.grep_local import Grep
+from kimi_cli.tools.file.grep_local import Params as GrepParams
 from kimi_cli.tools.file.read import Params as ReadParams
 from kimi_cli.tools.file.read import ReadFile
 from kimi_cli.tools.file.replace import Edit, StrReplaceFile
 from kimi_cli.tools.file.replace import Params as ReplaceParams
 from kimi_cli.tools.file.write import Params as WriteParams
 from kimi_cli.tools.file.write import WriteFile
+from kimi_cli.utils.environment import Environment
 from tests.conftest import tool_call_context
 
 
@@ -160,6 +169,93 @@ async def test_replace_in_additional_dir(
         assert await target.read_text() == "new_value = 1\n"
 
 
+# ── Workspace roots tests ───────────────────────────────────────────────────
+
+
+async def test_paths_relative_to_additional_dir_resolve_against_work_dir(
+    runtime_with_additional_dir: Runtime, approval: Approval, additional_dir: KaosPath
+):
+    """A path relative to an additional dir is not found there; its absolute path works."""
+    target = additional_dir / "notes.txt"
+    await target.write_text("old note\n")
+    read_tool = ReadFile(runtime_with_additional_dir)
+
+    result = await read_tool(ReadParams(path="notes.txt"))
+    assert result.is_error
+    result = await read_tool(ReadParams(path=str(target)))
+    assert not result.is_error
+    assert "old note" in result.output
+
+    with tool_call_context("StrReplaceFile"):
+        replace_tool = StrReplaceFile(runtime_with_additional_dir, approval)
+        edit = Edit(old="old note", new="new note")
+
+        result = await replace_tool(ReplaceParams(path="notes.txt", edit=edit))
+        assert result.is_error
+        assert await target.read_text() == "old note\n"
+
+        result = await replace_tool(ReplaceParams(path=str(target), edit=edit))
+        assert not result.is_error
+        assert await target.read_text() == "new note\n"
+
+
+async def test_relative_path_outside_workspace_lists_roots(
+    runtime_with_additional_dir: Runtime, additional_dir: KaosPath
+):
+    """The error for a relative path outside the workspace should name every root."""
+    work_dir = runtime_with_additional_dir.builtin_args.KIMI_WORK_DIR
+    read_tool = ReadFile(runtime_with_additional_dir)
+
+    result = await read_tool(ReadParams(path=str(Path("..") / "outside.txt")))
+
+    assert result.is_error
+    assert result.message.endswith(
+        "Relative paths are resolved against the working directory. "
+        f"The workspace roots are 0 `{work_dir}`, 1 `{additional_dir}`."
+    )
+
+
+async def test_glob_with_root(runtime_with_additional_dir: Runtime, additional_dir: KaosPath):
+    """Glob should search a root given by index or path, with a directory relative to it."""
+    glob_tool = Glob(runtime_with_additional_dir)
+    await (additional_dir / "src").mkdir()
+    await (additional_dir / "src" / "lib.py").write_text("pass")
+
+    for root in (1, str(additional_dir)):
+        result = await glob_tool(GlobParams(pattern="src/*.py", root=root))
+        assert not result.is_error
+        assert "lib.py" in result.output
+
+    result = await glob_tool(GlobParams(pattern="*.py", directory="src", root=1))
+    assert not result.is_error
+    assert "lib.py" in result.output
+
+
+async def test_glob_with_unknown_root_rejected(runtime_with_additional_dir: Runtime):
+    """A root that is not one of the workspace roots should be rejected."""
+    glob_tool = Glob(runtime_with_additional_dir)
+
+    for root in (2, "/not/a/root"):
+        result = await glob_tool(GlobParams(pattern="*.py", root=root))
+        assert result.is_error
+        assert result.message.startswith(f"`{root}` is not a workspace root.")
+
+
+async def test_grep_with_root(runtime_with_additional_dir: Runtime, additional_dir: KaosPath):
+    """Grep should search a root given by index, with a path relative to it."""
+    grep_tool = Grep(runtime_with_additional_dir)
+    await (additional_dir / "src").mkdir()
+    await (additional_dir / "src" / "lib.py").write_text("MARKER = 1\n")
+
+    result = await grep_tool(GrepParams(pattern="MARKER", path="src", root=1))
+    assert not result.is_error
+    assert "lib.py" in result.output
+
+    result = await grep_tool(GrepParams(pattern="MARKER", root=3))
+    assert result.is_error
+    assert result.brief == "Invalid root"
+
+
 # ── Dynamic mutation tests ──────────────────────────────────────────────────
 
 
@@ -288,3 +384,33 @@ async def test_subagent_shares_skills_dirs(runtime: Runtime):
     runtime.skills_dirs.append(KaosPath("/fake/skills"))
     subagent = runtime.copy_for_subagent(agent_id="a-sub", subagent_type="coder")
     assert subagent.skills_dirs is runtime.skills_dirs
+
+
+# ── Config tests ────────────────────────────────────────────────────────────
+
+
+async def test_additional_dirs_config_reaches_the_runtime(
+    session: Session,
+    environment: Environment,
+    additional_dir: KaosPath,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+):
+    """Directories of the `additional_dirs` config key are added to the runtime, not saved."""
+    monkeypatch.setattr(agent_module, "list_directory", AsyncMock(return_value=""))
+    monkeypatch.setattr(agent_module, "load_agents_md", AsyncMock(return_value=None))
+    monkeypatch.setattr(agent_module.Environment, "detect", AsyncMock(return_value=environment))
+    monkeypatch.setattr(agent_module, "resolve_skills_roots", AsyncMock(return_value=[]))
+    monkeypatch.setattr(agent_module, "discover_skills_from_roots", AsyncMock(return_value=[]))
+    monkeypatch.setattr(agent_module, "index_skills", lambda _skills: {})
+    monkeypatch.setattr(agent_module, "format_skills_for_prompt", lambda _skills: None)
+    dirs = [str(additional_dir), str(tmp_path / "missing")]
+    config = load_config_from_string(f"additional_dirs = {json.dumps(dirs)}\n")
+
+    runtime = await Runtime.create(
+        config, OAuthManager(config), llm=None, session=session, yolo=False
+    )
+
+    assert runtime.additional_dirs == [additional_dir.canonical()]
+    assert str(additional_dir.canonical()) in runtime.builtin_args.KIMI_ADDITIONAL_DIRS_INFO
+    assert session.state.additional_dirs == []
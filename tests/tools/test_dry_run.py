@@ -0,0 +1,197 @@
+"""Tests for dry-run mode, where side-effecting tools report what they would do."""
+
+from __future__ import annotations
+
+import platform
+from pathlib import Path
+from typing import Any, cast
+
+import mcp.types
+import pytest
+from kaos.path import KaosPath
+from kosong.tooling.empty import EmptyToolset
+
+import kimi_cli.soul.slash as soul_slash
+from kimi_cli.soul.agent import Agent, Runtime
+from kimi_cli.soul.approval import Approval
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.kimisoul import KimiSoul
+from kimi_cli.soul.toolset import MCPTool
+from kimi_cli.tools.file.read import Params as ReadParams
+from kimi_cli.tools.file.read import ReadFile
+from kimi_cli.tools.file.replace import Edit, StrReplaceFile
+from kimi_cli.tools.file.replace import Params as ReplaceParams
+from kimi_cli.tools.file.write import Params as WriteParams
+from kimi_cli.tools.file.write import WriteFile
+from kimi_cli.tools.shell import Params as ShellParams
+from kimi_cli.tools.shell import Shell
+from kimi_cli.wire.types import DiffDisplayBlock, ShellDisplayBlock
+
+
+async def test_write_file_is_not_written(
+    write_file_tool: WriteFile, runtime: Runtime, temp_work_dir: KaosPath
+):
+    runtime.dry_run = True
+    file_path = temp_work_dir / "new_file.txt"
+
+    result = await write_file_tool(WriteParams(path=str(file_path), content="Hello"))
+
+    assert not result.is_error
+    assert result.message == (
+        "Dry run, nothing was executed. The tool call would: "
+        f"Write file `{file_path}`. File successfully overwritten. Current size: 5 bytes."
+    )
+    diff_block = next(block for block in result.display if isinstance(block, DiffDisplayBlock))
+    assert diff_block.new_text == "Hello"
+    assert not await file_path.exists()
+
+
+async def test_str_replace_file_is_not_edited(
+    str_replace_file_tool: StrReplaceFile, runtime: Runtime, temp_work_dir: KaosPath
+):
+    runtime.dry_run = True
+    file_path = temp_work_dir / "a.py"
+    await file_path.write_text("alpha\n")
+
+    result = await str_replace_file_tool(
+        ReplaceParams(path=str(file_path), edit=Edit(old="alpha", new="beta"))
+    )
+
+    assert not result.is_error
+    assert any(isinstance(block, DiffDisplayBlock) for block in result.display)
+    assert await file_path.read_text() == "alpha\n"
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="Bash tests run only on non-Windows.")
+async def test_shell_command_is_not_run(
+    shell_tool: Shell, runtime: Runtime, temp_work_dir: KaosPath
+):
+    runtime.dry_run = True
+    marker = temp_work_dir / "marker"
+
+    result = await shell_tool(ShellParams(command=f"touch {marker}"))
+
+    assert not result.is_error
+    assert f"Run command `touch {marker}`" in result.message
+    assert isinstance(result.display[0], ShellDisplayBlock)
+    assert result.display[0].analysis is not None
+    assert [command.programs for command in result.display[0].analysis.commands] == [["touch"]]
+    assert not await marker.exists()
+
+
+async def test_read_only_tools_still_run(
+    read_file_tool: ReadFile, runtime: Runtime, temp_work_dir: KaosPath
+):
+    runtime.dry_run = True
+    file_path = temp_work_dir / "a.txt"
+    await file_path.write_text("alpha\n")
+
+    result = await read_file_tool(ReadParams(path=str(file_path)))
+
+    assert not result.is_error
+    assert "alpha" in result.output
+
+
+async def test_mcp_tool_is_not_called(runtime: Runtime):
+    runtime.dry_run = True
+    tool = MCPTool(
+        "files",
+        mcp.types.Tool(name="delete", description="Delete a file.", inputSchema={"type": "object"}),
+        cast(Any, None),  # any use of the client fails
+        runtime=runtime,
+    )
+
+    result = await tool(path="a.txt")
+
+    assert not result.is_error
+    assert result.message == (
+        "Dry run, nothing was executed. The tool call would: "
+        'Call MCP tool `delete` of MCP server `files` with arguments {"path": "a.txt"}'
+    )
+
+
+def test_subagent_runtime_inherits_dry_run(runtime: Runtime):
+    runtime.dry_run = True
+
+    subagent = runtime.copy_for_subagent(agent_id="a-sub", subagent_type="coder")
+
+    assert subagent.dry_run
+
+
+async def test_dry_run_parameter_matches_the_real_edit(
+    str_replace_file_tool: StrReplaceFile,
+    approval: Approval,
+    temp_work_dir: KaosPath,
+    monkeypatch: pytest.MonkeyPatch,
+):
+    file_path = temp_work_dir / "a.py"
+    await file_path.write_text("alpha\nbeta\nalpha\n")
+    params = ReplaceParams(
+        path=str(file_path), edit=Edit(old="alpha", new="gamma", replace_all=True)
+    )
+
+    async def _no_approval(*_args, **_kwargs):
+        raise AssertionError("a dry run must not ask for approval")
+
+    monkeypatch.setattr(approval, "request", _no_approval)
+    preview = await str_replace_file_tool(params.model_copy(update={"dry_run": True}))
+    assert await file_path.read_text() == "alpha\nbeta\nalpha\n"
+    monkeypatch.undo()
+
+    result = await str_replace_file_tool(params)
+
+    assert await file_path.read_text() == "gamma\nbeta\ngamma\n"
+    assert not preview.is_error
+    assert preview.message == (
+        f"Dry run, nothing was executed. The tool call would: Edit file `{file_path}`. "
+        f"{result.message}"
+    )
+    assert preview.display == result.display
+    assert preview.extras is None
+
+
+async def test_dry_run_parameter_predicts_the_size_of_an_append(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    file_path = temp_work_dir / "log.txt"
+    await file_path.write_text("one\r\n")
+    params = WriteParams(path=str(file_path), content="two\n", mode="append")
+
+    preview = await write_file_tool(params.model_copy(update={"dry_run": True}))
+    assert await file_path.read_text() == "one\r\n"
+    result = await write_file_tool(params)
+
+    assert preview.message == (
+        f"Dry run, nothing was executed. The tool call would: Write file `{file_path}`. "
+        f"{result.message}"
+    )
+    assert result.message.endswith("Current size: 10 bytes.")
+
+
+async def test_dryrun_slash_command_toggles_dry_run_mode(
+    write_file_tool: WriteFile,
+    runtime: Runtime,
+    temp_work_dir: KaosPath,
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+):
+    agent = Agent(
+        name="Test Agent",
+        system_prompt="Test system prompt.",
+        toolset=EmptyToolset(),
+        runtime=runtime,
+    )
+    soul = KimiSoul(agent, context=Context(file_backend=tmp_path / "history.jsonl"))
+    monkeypatch.setattr(soul_slash, "wire_send", lambda _msg: None)
+    file_path = temp_work_dir / "new_file.txt"
+
+    soul_slash.dryrun(soul, "")
+    result = await write_file_tool(WriteParams(path=str(file_path), content="Hello"))
+    assert result.message.startswith("Dry run, nothing was executed.")
+    assert not await file_path.exists()
+
+    soul_slash.dryrun(soul, "")
+    result = await write_file_tool(WriteParams(path=str(file_path), content="Hello"))
+    assert not runtime.dry_run
+    assert not result.message.startswith("Dry run")
+    assert await file_path.read_text() == "Hello"
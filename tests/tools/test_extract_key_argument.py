@@ -24,6 +24,26 @@ class TestExtractKeyArgument:
         result = extract_key_argument('{"pattern": "hello"}', "Grep")
         assert result == "hello"
 
+    def test_find_in_context(self):
+        result = extract_key_argument('{"pattern": "TimeoutError"}', "FindInContext")
+        assert result == "TimeoutError"
+
+    def test_git(self):
+        assert extract_key_argument('{"op": "status"}', "Git") == "status"
+        result = extract_key_argument('{"op": "diff", "paths": ["a.py", "b.py"]}', "Git")
+        assert result == "diff a.py b.py"
+
+    def test_notebook_edit(self):
+        result = extract_key_argument('{"path": "nb.ipynb", "cell_index": 2}', "NotebookEdit")
+        assert result == "nb.ipynb#2"
+
+    def test_multi_str_replace_file(self):
+        result = extract_key_argument(
+            '{"files": [{"path": "a.py", "edits": []}, {"path": "b.py", "edits": []}]}',
+            "MultiStrReplaceFile",
+        )
+        assert result == "a.py (+1 more)"
+
     def test_invalid_json(self):
         result = extract_key_argument("invalid", "Shell")
         assert result is None
@@ -0,0 +1,107 @@
+"""File tools refuse to write over changes made outside the session since the last read."""
+
+from __future__ import annotations
+
+import pytest
+from kaos.path import KaosPath
+
+from kimi_cli.soul.file_tracker import MAX_SNAPSHOT_BYTES, FileTracker
+from kimi_cli.tools.file import ReadFile, StrReplaceFile, WriteFile
+from kimi_cli.tools.file import read as read_module
+from kimi_cli.tools.file import replace as replace_module
+from kimi_cli.tools.file import write as write_module
+
+
+async def test_str_replace_refuses_external_edit(
+    read_file_tool: ReadFile, str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    file = temp_work_dir / "notes.txt"
+    await file.write_text("alpha\nbeta\n")
+    assert not (await read_file_tool(read_module.Params(path=str(file)))).is_error
+
+    # The user edits the file in their editor
+    await file.write_text("alpha\nbeta\nadded by the user\n")
+
+    edit = replace_module.Edit(old="alpha", new="gamma")
+    result = await str_replace_file_tool(replace_module.Params(path=str(file), edit=edit))
+
+    assert result.is_error
+    assert "has been modified since you last read or wrote it" in result.message
+    assert "+added by the user" in result.message
+    assert await file.read_text() == "alpha\nbeta\nadded by the user\n"
+
+    result = await str_replace_file_tool(
+        replace_module.Params(path=str(file), edit=edit, force=True)
+    )
+
+    assert not result.is_error
+    assert await file.read_text() == "gamma\nbeta\nadded by the user\n"
+
+
+async def test_edit_after_rereading_is_allowed(
+    read_file_tool: ReadFile, str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    file = temp_work_dir / "notes.txt"
+    await file.write_text("alpha\n")
+    await read_file_tool(read_module.Params(path=str(file)))
+    await file.write_text("alpha\nbeta\n")
+    await read_file_tool(read_module.Params(path=str(file)))
+
+    result = await str_replace_file_tool(
+        replace_module.Params(path=str(file), edit=replace_module.Edit(old="beta", new="gamma"))
+    )
+
+    assert not result.is_error
+    assert await file.read_text() == "alpha\ngamma\n"
+
+
+async def test_write_file_refuses_external_edit_after_own_write(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    file = temp_work_dir / "notes.txt"
+    await write_file_tool(write_module.Params(path=str(file), content="first\n"))
+    await file.write_text("changed by the user\n")
+
+    result = await write_file_tool(write_module.Params(path=str(file), content="second\n"))
+
+    assert result.is_error
+    assert await file.read_text() == "changed by the user\n"
+
+
+async def test_tracker_is_bounded_and_clearable(temp_work_dir: KaosPath):
+    tracker = FileTracker(max_files=2)
+    files = [temp_work_dir / f"{i}.txt" for i in range(3)]
+    for file in files:
+        await file.write_text("v1\n")
+        await tracker.record(file)
+    for file in files:
+        await file.write_text("v2, longer\n")
+
+    # The least recently recorded file was evicted
+    assert await tracker.check(files[0]) is None
+    assert await tracker.check(files[1]) is not None
+    assert await tracker.check(files[2]) is not None
+
+    tracker.clear()
+    assert await tracker.check(files[2]) is None
+
+
+async def test_large_files_are_tracked_without_reading_them(
+    temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+):
+    file = temp_work_dir / "huge.log"
+    await file.write_text("x" * (MAX_SNAPSHOT_BYTES + 1))
+
+    async def fail_read_bytes(self: KaosPath, n: int | None = None) -> bytes:
+        raise AssertionError("large files must not be read into memory")
+
+    monkeypatch.setattr(KaosPath, "read_bytes", fail_read_bytes)
+    tracker = FileTracker()
+    await tracker.record(file)
+    assert await tracker.check(file) is None
+
+    await file.write_text("y" * (MAX_SNAPSHOT_BYTES + 2))
+
+    message = await tracker.check(file)
+    assert message is not None
+    assert "no diff is available" in message
@@ -0,0 +1,134 @@
+"""Tests for the FindInContext tool."""
+
+from __future__ import annotations
+
+import re
+from pathlib import Path
+
+import pytest
+from kosong.message import Message, Role, ToolCall
+
+import kimi_cli.tools.context as context_module
+from kimi_cli.soul.context import Context
+from kimi_cli.soul.message import dmail_message
+from kimi_cli.tools.context import FindInContext, Params
+from kimi_cli.wire.types import TextPart
+
+TEST_OUTPUT = (
+    "collected 3 items\n\n..F\nFAILED tests/test_io.py::test_read - TimeoutError\n\n1 failed"
+)
+
+
+def _text(role: Role, text: str) -> Message:
+    return Message(role=role, content=[TextPart(text=text)])
+
+
+async def _make_context(tmp_path: Path) -> Context:
+    context = Context(file_backend=tmp_path / "history.jsonl")
+    await context.checkpoint(add_user_message=False)
+    await context.append_message(
+        [
+            _text("user", "Please fix the flaky test in tests/test_io.py"),
+            Message(
+                role="assistant",
+                content=[TextPart(text="Let me run it first.")],
+                tool_calls=[
+                    ToolCall(
+                        id="tc-1",
+                        function=ToolCall.FunctionBody(
+                            name="Shell", arguments='{"command": "pytest tests/test_io.py"}'
+                        ),
+                    )
+                ],
+            ),
+            Message(role="tool", content=[TextPart(text=TEST_OUTPUT)], tool_call_id="tc-1"),
+        ]
+    )
+    await context.checkpoint(add_user_message=False)
+    await context.append_message(
+        [
+            dmail_message("tests/test_io.py is already fixed, do not touch it again."),
+            _text("user", "Thanks. Why is test_io.py so slow?"),
+        ]
+    )
+    return context
+
+
+def _tool(context: Context) -> FindInContext:
+    tool = FindInContext()
+    tool.bind(context)
+    return tool
+
+
+@pytest.mark.asyncio
+async def test_finds_matches_newest_first_without_dmail(tmp_path: Path) -> None:
+    tool = _tool(await _make_context(tmp_path))
+
+    result = await tool(Params(pattern=r"test_io\.py"))
+
+    assert not result.is_error
+    assert result.message == "Found 4 match(es), newest first."
+    assert result.output == "\n\n".join(
+        [
+            "[message 4, checkpoint 1, user] line 1:\nThanks. Why is test_io.py so slow?",
+            "[message 2, checkpoint 0, result of tc-1] line 4:\n"
+            "\n..F\nFAILED tests/test_io.py::test_read - TimeoutError\n\n1 failed",
+            "[message 1, checkpoint 0, call to Shell (tc-1)] line 1:\n"
+            '{"command": "pytest tests/test_io.py"}',
+            "[message 0, checkpoint 0, user] line 1:\n"
+            "Please fix the flaky test in tests/test_io.py",
+        ]
+    )
+
+
+@pytest.mark.asyncio
+async def test_role_and_limit_narrow_the_search(tmp_path: Path) -> None:
+    tool = _tool(await _make_context(tmp_path))
+
+    result = await tool(Params(pattern="test", role="user", limit=1))
+
+    assert result.output == (
+        "[message 4, checkpoint 1, user] line 1:\nThanks. Why is test_io.py so slow?"
+    )
+
+    result = await tool(Params(pattern="TimeoutError", role="assistant"))
+    assert result.message == "No matches found."
+
+
+@pytest.mark.asyncio
+async def test_checkpoints_survive_restore(tmp_path: Path) -> None:
+    await _make_context(tmp_path)
+    restored = Context(file_backend=tmp_path / "history.jsonl")
+    assert await restored.restore()
+
+    [first, second] = list(restored.search(re.compile("FAILED|slow")))
+
+    assert (first.index, first.checkpoint_id, first.role) == (4, 1, "user")
+    assert (second.index, second.checkpoint_id, second.tool_call_id) == (2, 0, "tc-1")
+
+
+@pytest.mark.asyncio
+async def test_output_is_capped_by_the_byte_budget(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(context_module, "MAX_OUTPUT_BYTES", 100)
+    tool = _tool(await _make_context(tmp_path))
+
+    result = await tool(Params(pattern=r"test_io\.py"))
+
+    assert result.output.startswith("[message 4, checkpoint 1, user]")
+    assert "[message 2" not in result.output
+    assert result.message == (
+        "Found 1 match(es), newest first. "
+        "More matches were left out to fit in the output; narrow the pattern."
+    )
+
+
+@pytest.mark.asyncio
+async def test_invalid_pattern_is_an_error(tmp_path: Path) -> None:
+    tool = _tool(await _make_context(tmp_path))
+
+    result = await tool(Params(pattern="test_io("))
+
+    assert result.is_error
+    assert result.message.startswith("Invalid regular expression `test_io(`")
@@ -0,0 +1,78 @@
+"""Tests for the GetEnvironment tool."""
+
+from __future__ import annotations
+
+import platform
+import subprocess
+
+import pytest
+from inline_snapshot import snapshot
+from kaos.path import KaosPath
+
+from kimi_cli.skill import Skill
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.environment import GetEnvironment, Params
+
+pytestmark = pytest.mark.skipif(
+    platform.system() == "Windows", reason="GetEnvironment tool tests run only on non-Windows."
+)
+
+
+def _git(work_dir: KaosPath, *args: str) -> None:
+    subprocess.run(["git", "-C", str(work_dir), *args], check=True, capture_output=True)
+
+
+async def test_outside_a_git_repository(
+    get_environment_tool: GetEnvironment, temp_work_dir: KaosPath
+):
+    result = await get_environment_tool(Params())
+
+    assert not result.is_error
+    assert result.output == f"""\
+working directory: {temp_work_dir}
+os: Unix 1.0 (aarch64)
+shell: bash (/bin/bash)
+git: not a git repository
+skills: none
+"""
+    assert result.extras == {
+        "environment": {
+            "work_dir": str(temp_work_dir),
+            "additional_dirs": [],
+            "os_kind": "Unix",
+            "os_arch": "aarch64",
+            "os_version": "1.0",
+            "shell_name": "bash",
+            "shell_path": "/bin/bash",
+            "git": None,
+            "skills": [],
+        }
+    }
+
+
+async def test_git_summary_and_skills(runtime: Runtime, temp_work_dir: KaosPath):
+    _git(temp_work_dir, "init", "-q", "-b", "main")
+    (temp_work_dir / "a.txt").unsafe_to_local_path().write_text("one\n")
+    (temp_work_dir / "b.txt").unsafe_to_local_path().write_text("two\n")
+    _git(temp_work_dir, "add", "a.txt")
+    runtime.skills = {
+        "review": Skill(
+            name="review",
+            description="Review a change.\nLonger explanation.",
+            dir=temp_work_dir,
+            skill_md_file=temp_work_dir / "review.md",
+            scope="project",
+        )
+    }
+
+    result = await GetEnvironment(runtime)(Params())
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert result.output.splitlines()[-3:] == snapshot(
+        [
+            "git: on branch main; 1 staged, 1 untracked",
+            "skills:",
+            "  review: Review a change.",
+        ]
+    )
@@ -0,0 +1,199 @@
+"""Tests for the Git tool."""
+
+from __future__ import annotations
+
+import platform
+import subprocess
+
+import pytest
+from inline_snapshot import snapshot
+from kaos.path import KaosPath
+
+from kimi_cli.tools.display import DiffDisplayBlock
+from kimi_cli.tools.git import (
+    MAX_DISPLAY_DIFF_CHARS,
+    Git,
+    Params,
+    build_file_diff_blocks,
+    parse_status_porcelain_v2,
+    split_diff_by_file,
+)
+
+pytestmark = pytest.mark.skipif(
+    platform.system() == "Windows", reason="Git tool tests run only on non-Windows."
+)
+
+
+def _git(work_dir: KaosPath, *args: str) -> str:
+    return subprocess.run(
+        ["git", "-C", str(work_dir), *args],
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout
+
+
+@pytest.fixture
+def repo(temp_work_dir: KaosPath) -> KaosPath:
+    _git(temp_work_dir, "init", "-q", "-b", "main")
+    _git(temp_work_dir, "config", "user.name", "Test")
+    _git(temp_work_dir, "config", "user.email", "test@example.com")
+    _git(temp_work_dir, "config", "commit.gpgsign", "false")
+    (temp_work_dir / "a.txt").unsafe_to_local_path().write_text("one\ntwo\nthree\n")
+    _git(temp_work_dir, "add", "a.txt")
+    _git(temp_work_dir, "commit", "-q", "-m", "initial commit")
+    return temp_work_dir
+
+
+def test_parse_status_porcelain_v2():
+    output = "\0".join(
+        [
+            "# branch.oid 1234567890abcdef",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 M. N... 100644 100644 100644 aaaa bbbb staged.py",
+            "1 .M N... 100644 100644 100644 aaaa aaaa unstaged file.py",
+            "2 R. N... 100644 100644 100644 aaaa aaaa R100 new.py",
+            "old.py",
+            "u UU N... 100644 100644 100644 100644 aaaa bbbb cccc conflict.py",
+            "? untracked.py",
+            "",
+        ]
+    )
+    status = parse_status_porcelain_v2(output)
+    assert status.to_json() == snapshot(
+        {
+            "branch": "main",
+            "upstream": "origin/main",
+            "ahead": 2,
+            "behind": 1,
+            "staged": [
+                {"status": "M", "path": "staged.py"},
+                {"status": "R", "path": "old.py -> new.py"},
+            ],
+            "unstaged": [{"status": "M", "path": "unstaged file.py"}],
+            "untracked": ["untracked.py"],
+            "conflicted": ["conflict.py"],
+        }
+    )
+
+
+def test_build_file_diff_blocks():
+    diff = """\
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+"""
+    files = split_diff_by_file(diff)
+    assert [path for path, _ in files] == ["a.txt"]
+    assert build_file_diff_blocks(*files[0]) == [
+        DiffDisplayBlock(path="a.txt", old_text="one\ntwo\nthree", new_text="one\nTWO\nthree")
+    ]
+
+
+def test_build_file_diff_blocks_caps_size():
+    body = "".join(f"+line {i}\n" for i in range(MAX_DISPLAY_DIFF_CHARS // 5))
+    diff = f"diff --git a/big.txt b/big.txt\n--- /dev/null\n+++ b/big.txt\n@@ -0,0 +1 @@\n{body}"
+    (path, file_diff), *_ = split_diff_by_file(diff)
+    blocks = build_file_diff_blocks(path, file_diff)
+    assert len(blocks) == 1
+    assert isinstance(blocks[0], DiffDisplayBlock)
+    assert blocks[0].is_summary
+
+
+async def test_outside_work_tree(git_tool: Git):
+    result = await git_tool(Params(op="status"))
+    assert result.is_error
+    assert "not inside a git work tree" in result.message
+
+
+@pytest.mark.usefixtures("repo")
+async def test_status_clean(git_tool: Git):
+    result = await git_tool(Params(op="status"))
+    assert not result.is_error
+    assert result.output == snapshot("branch: main\nworking tree clean\n")
+
+
+async def test_status_and_diff(git_tool: Git, repo: KaosPath):
+    (repo / "a.txt").unsafe_to_local_path().write_text("one\nTWO\nthree\n")
+    (repo / "b.txt").unsafe_to_local_path().write_text("new\n")
+
+    result = await git_tool(Params(op="status"))
+    assert not result.is_error
+    assert result.output == snapshot("""\
+branch: main
+unstaged:
+  M a.txt
+untracked:
+  b.txt
+""")
+
+    result = await git_tool(Params(op="diff"))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "-two\n+TWO\n" in result.output
+    assert [block for block in result.display if isinstance(block, DiffDisplayBlock)] == [
+        DiffDisplayBlock(path="a.txt", old_text="one\ntwo\nthree", new_text="one\nTWO\nthree")
+    ]
+
+    result = await git_tool(Params(op="diff", staged=True))
+    assert not result.is_error
+    assert result.message == snapshot("No staged changes.")
+
+
+async def test_diff_path_filter(git_tool: Git, repo: KaosPath):
+    (repo / "c.txt").unsafe_to_local_path().write_text("c\n")
+    _git(repo, "add", "c.txt")
+    _git(repo, "commit", "-q", "-m", "add c")
+    (repo / "a.txt").unsafe_to_local_path().write_text("changed\n")
+    (repo / "c.txt").unsafe_to_local_path().write_text("changed\n")
+
+    result = await git_tool(Params(op="diff", paths=["c.txt"]))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "c.txt" in result.output
+    assert "a.txt" not in result.output
+
+
+async def test_log(git_tool: Git, repo: KaosPath):
+    (repo / "a.txt").unsafe_to_local_path().write_text("changed\n")
+    _git(repo, "commit", "-q", "-am", "second commit")
+
+    result = await git_tool(Params(op="log", n=1))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    lines = result.output.splitlines()
+    assert len(lines) == 1
+    assert lines[0].endswith("Test: second commit")
+
+
+async def test_commit_add_all(git_tool: Git, repo: KaosPath):
+    (repo / "a.txt").unsafe_to_local_path().write_text("changed\n")
+    (repo / "b.txt").unsafe_to_local_path().write_text("new\n")
+
+    result = await git_tool(Params(op="commit", message="update files", add_all=True))
+    assert not result.is_error
+    assert result.message.startswith("Committed ")
+    assert _git(repo, "status", "--porcelain") == ""
+    assert _git(repo, "log", "-1", "--pretty=%s").strip() == "update files"
+
+
+@pytest.mark.usefixtures("repo")
+async def test_commit_nothing_staged(git_tool: Git):
+    result = await git_tool(Params(op="commit", message="noop"))
+    assert result.is_error
+    assert result.brief == snapshot("Nothing to commit")
+
+
+@pytest.mark.usefixtures("repo")
+async def test_commit_requires_message(git_tool: Git):
+    result = await git_tool(Params(op="commit"))
+    assert result.is_error
+    assert result.brief == snapshot("Empty message")
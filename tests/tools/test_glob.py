@@ -2,11 +2,15 @@
 
 from __future__ import annotations
 
+import os
 import platform
+import subprocess
 from pathlib import Path
 
 import pytest
+from kaos import local as kaos_local
 from kaos.path import KaosPath
+from kosong.tooling import BriefDisplayBlock
 
 from kimi_cli.tools.file.glob import MAX_MATCHES, Glob, Params
 
@@ -223,8 +227,11 @@ async def test_glob_max_matches_limit(glob_tool: Glob, temp_work_dir: KaosPath):
     # Should only return MAX_MATCHES results
     output_lines = [line for line in result.output.split("\n") if line.strip()]
     assert len(output_lines) == MAX_MATCHES
-    # Should contain warning message
-    assert f"Only the first {MAX_MATCHES} matches are returned" in result.message
+    # Should tell how many matches were left out
+    total = MAX_MATCHES + 50
+    assert f"Found {total} matches" in result.message
+    assert f"showing {MAX_MATCHES} of {total} matches" in result.message
+    assert result.display == [BriefDisplayBlock(text=f"showing {MAX_MATCHES} of {total} matches")]
 
 
 async def test_glob_enhanced_double_star_validation(glob_tool: Glob, temp_work_dir: KaosPath):
@@ -260,8 +267,9 @@ async def test_glob_exactly_max_matches(glob_tool: Glob, temp_work_dir: KaosPath
     output_lines = [line for line in result.output.split("\n") if line.strip()]
     assert len(output_lines) == MAX_MATCHES
     # Should NOT contain warning message since we have exactly MAX_MATCHES
-    assert "Only the first" not in result.message
+    assert "showing" not in result.message
     assert f"Found {MAX_MATCHES} matches" in result.message
+    assert result.display == [BriefDisplayBlock(text=f"{MAX_MATCHES} matches")]
 
 
 async def test_glob_character_class(glob_tool: Glob, temp_work_dir: KaosPath):
@@ -321,6 +329,32 @@ async def test_glob_pattern_edge_cases(glob_tool: Glob, test_files: KaosPath):
     assert "starts with '**' which is not allowed" in result.message
 
 
+@pytest.mark.skipif(
+    platform.system() == "Windows" or os.geteuid() == 0,
+    reason="POSIX permission bits, which root ignores",
+)
+async def test_glob_skips_unreadable_directories(glob_tool: Glob, temp_work_dir: KaosPath):
+    """An unreadable subdirectory is skipped and reported instead of failing the search."""
+    await (temp_work_dir / "ok").mkdir()
+    await (temp_work_dir / "ok" / "a.py").write_text("a")
+    await (temp_work_dir / "locked").mkdir()
+    await (temp_work_dir / "locked" / "b.py").write_text("b")
+    locked = (temp_work_dir / "locked").unsafe_to_local_path()
+    locked.chmod(0)
+    try:
+        result = await glob_tool(Params(pattern="*/*.py", directory=str(temp_work_dir)))
+        root_result = await glob_tool(Params(pattern="*.py", directory=str(locked)))
+    finally:
+        locked.chmod(0o755)
+
+    assert not result.is_error
+    assert result.output == "ok/a.py"
+    assert "Skipped 1 entry due to permission errors: `locked`." in result.message
+    # The searched directory itself being unreadable is still an error
+    assert root_result.is_error
+    assert root_result.brief == "Directory unreadable"
+
+
 async def test_glob_hidden_files(glob_tool: Glob, temp_work_dir: KaosPath):
     """Hidden files (dotfiles) should be matched by glob patterns."""
     # Create hidden files and visible files
@@ -362,3 +396,78 @@ async def test_glob_hidden_directory_contents(glob_tool: Glob, temp_work_dir: Ka
     assert isinstance(result.output, str)
     output = result.output.replace("\\", "/")
     assert ".github/workflows/ci.yml" in output
+
+
+async def test_glob_skips_gitignored_paths(glob_tool: Glob, temp_work_dir: KaosPath):
+    """Inside a git repository, gitignored paths and `.git` should be skipped by default."""
+    subprocess.run(["git", "init", "-q", str(temp_work_dir)], check=True)
+    await (temp_work_dir / ".gitignore").write_text("node_modules/\n*.log\n")
+    await (temp_work_dir / "node_modules" / "react").mkdir(parents=True)
+    await (temp_work_dir / "node_modules" / "react" / "index.js").write_text("react")
+    await (temp_work_dir / "src").mkdir()
+    await (temp_work_dir / "src" / "app.js").write_text("app")
+    await (temp_work_dir / "build.log").write_text("log")
+
+    result = await glob_tool(Params(pattern="*", directory=str(temp_work_dir)))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert set(result.output.splitlines()) == {".gitignore", "src"}
+
+    result = await glob_tool(Params(pattern="*/*/*.js", directory=str(temp_work_dir)))
+    assert not result.is_error
+    assert result.output == ""
+    assert "No matches found" in result.message
+
+
+async def test_glob_does_not_walk_ignored_directories(
+    glob_tool: Glob, temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+):
+    """Gitignored and VCS directories should be pruned, not listed and filtered afterwards."""
+    subprocess.run(["git", "init", "-q", str(temp_work_dir)], check=True)
+    await (temp_work_dir / ".gitignore").write_text("node_modules/\n")
+    await (temp_work_dir / "node_modules" / "react").mkdir(parents=True)
+    await (temp_work_dir / "node_modules" / "react" / "index.js").write_text("react")
+    await (temp_work_dir / "src").mkdir()
+    await (temp_work_dir / "src" / "app.js").write_text("app")
+
+    listed: list[str] = []
+    scandir = os.scandir
+
+    def recording_scandir(path):
+        listed.append(Path(path).name)
+        return scandir(path)
+
+    monkeypatch.setattr(kaos_local.os, "scandir", recording_scandir)
+    result = await glob_tool(Params(pattern="*/**/*.js", directory=str(temp_work_dir)))
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert result.output.replace("\\", "/").splitlines() == ["src/app.js"]
+    assert "src" in listed
+    assert not {"node_modules", "react", ".git"} & set(listed)
+
+
+async def test_glob_include_ignored(glob_tool: Glob, temp_work_dir: KaosPath):
+    """`include_ignored` should restore gitignored paths, but never `.git`."""
+    subprocess.run(["git", "init", "-q", str(temp_work_dir)], check=True)
+    await (temp_work_dir / ".gitignore").write_text("*.log\n")
+    await (temp_work_dir / "build.log").write_text("log")
+
+    result = await glob_tool(
+        Params(pattern="*", directory=str(temp_work_dir), include_ignored=True)
+    )
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert set(result.output.splitlines()) == {".gitignore", "build.log"}
+
+
+async def test_glob_outside_git_repo_skips_only_vcs_dirs(glob_tool: Glob, temp_work_dir: KaosPath):
+    """Outside a git repository, `.gitignore` has no effect."""
+    await (temp_work_dir / ".gitignore").write_text("*.log\n")
+    await (temp_work_dir / "build.log").write_text("log")
+    await (temp_work_dir / ".hg").mkdir()
+
+    result = await glob_tool(Params(pattern="*", directory=str(temp_work_dir)))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert set(result.output.splitlines()) == {".gitignore", "build.log"}
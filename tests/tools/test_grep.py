@@ -2,13 +2,21 @@
 
 from __future__ import annotations
 
+import os
+import platform
 import tempfile
 from pathlib import Path
 
 import pytest
 from inline_snapshot import snapshot
 
-from kimi_cli.tools.file.grep_local import Grep, Params, _build_rg_args, _strip_path_prefix
+from kimi_cli.tools.file.grep_local import (
+    Grep,
+    Params,
+    _build_rg_args,
+    _parse_rg_entry_errors,
+    _strip_path_prefix,
+)
 from kimi_cli.tools.utils import DEFAULT_MAX_CHARS
 
 
@@ -538,6 +546,43 @@ async def test_grep_vcs_exclusion(grep_tool: Grep):
         assert ".git" not in result.output
 
 
+@pytest.mark.skipif(
+    platform.system() == "Windows" or os.geteuid() == 0,
+    reason="POSIX permission bits, which root ignores",
+)
+async def test_grep_skips_unreadable_directories(grep_tool: Grep):
+    """An unreadable subdirectory is skipped and reported instead of failing the search."""
+    with tempfile.TemporaryDirectory() as temp_dir:
+        (Path(temp_dir) / "readable.txt").write_text("marker\n")
+        locked = Path(temp_dir) / "locked"
+        locked.mkdir()
+        (locked / "secret.txt").write_text("marker\n")
+        locked.chmod(0)
+        try:
+            result = await grep_tool(Params(pattern="marker", path=temp_dir))
+            root_result = await grep_tool(Params(pattern="marker", path=str(locked)))
+        finally:
+            locked.chmod(0o755)
+
+    assert not result.is_error
+    assert result.output.strip() == "readable.txt"
+    assert "Skipped 1 entry due to permission errors: `locked`." in result.message
+    # The searched path itself being unreadable is still an error
+    assert root_result.is_error
+
+
+def test_parse_rg_entry_errors():
+    stderr = (
+        "rg: /tmp/a/locked: Permission denied (os error 13)\n"
+        "rg: /tmp/a/gone.txt: No such file or directory (os error 2)\n"
+        "rg: regex parse error:\n"
+    )
+    assert _parse_rg_entry_errors(stderr) == (
+        [("/tmp/a/locked", 13), ("/tmp/a/gone.txt", 2)],
+        ["rg: regex parse error:"],
+    )
+
+
 async def test_grep_mtime_sorting(grep_tool: Grep):
     """files_with_matches returns most recently modified files first."""
     import os as _os
@@ -952,3 +997,69 @@ async def test_grep_allows_env_example(grep_tool: Grep):
         )
         assert not result.is_error
         assert ".env.example" in result.output
+
+
+# === Tests for binary files ===
+
+
+@pytest.fixture
+def mixed_text_and_binary_files():
+    """A directory holding a text file and a binary file that both contain the pattern."""
+    with tempfile.TemporaryDirectory() as temp_dir:
+        (Path(temp_dir) / "notes.txt").write_text("needle in text\n")
+        (Path(temp_dir) / "blob.bin").write_bytes(b"\x00\x01\x02header\nneedle in binary\n")
+        yield temp_dir
+
+
+async def test_grep_content_skips_binary_files(grep_tool: Grep, mixed_text_and_binary_files):
+    result = await grep_tool(
+        Params(pattern="needle", path=mixed_text_and_binary_files, output_mode="content")
+    )
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "needle in text" in result.output
+    assert "blob.bin" not in result.output
+    assert "\x00" not in result.output
+    assert "Skipped 1 binary file(s)" in result.message
+
+
+async def test_grep_files_with_matches_marks_binary_files(
+    grep_tool: Grep, mixed_text_and_binary_files
+):
+    result = await grep_tool(
+        Params(pattern="needle", path=mixed_text_and_binary_files, output_mode="files_with_matches")
+    )
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert sorted(result.output.split("\n")) == ["blob.bin (binary file matches)", "notes.txt"]
+
+
+async def test_grep_binary_searches_binary_files_as_text(
+    grep_tool: Grep, mixed_text_and_binary_files
+):
+    result = await grep_tool(
+        Params(
+            pattern="needle",
+            path=mixed_text_and_binary_files,
+            output_mode="content",
+            binary=True,
+        )
+    )
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "needle in text" in result.output
+    assert "needle in binary" in result.output
+    assert "binary file" not in result.message
+
+
+def test_build_rg_args_binary():
+    args = _build_rg_args("/usr/bin/rg", Params(pattern="x", binary=True))
+    assert "--text" in args
+    assert "--binary" not in args
+
+    args_default = _build_rg_args("/usr/bin/rg", Params(pattern="x"))
+    assert "--binary" in args_default
+    assert "--text" not in args_default
@@ -0,0 +1,171 @@
+"""Tests for the LSP-backed GoToDefinition and FindReferences tools."""
+
+from __future__ import annotations
+
+import platform
+import sys
+from collections.abc import AsyncGenerator
+from pathlib import Path
+from typing import Any
+
+import pytest
+from inline_snapshot import snapshot
+from kaos.path import KaosPath
+
+from kimi_cli.config import LSPConfig, LSPServerConfig
+from kimi_cli.lsp import LSPManager, parse_locations
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools.lsp import FindReferences, GoToDefinition, Position, ReferencesParams
+
+pytestmark = pytest.mark.skipif(
+    platform.system() == "Windows", reason="LSP tool tests run only on non-Windows."
+)
+
+_MOCK_SERVER = r'''
+import json
+import sys
+
+def read():
+    headers = {}
+    while True:
+        line = sys.stdin.buffer.readline()
+        if not line:
+            sys.exit(0)
+        line = line.decode().strip()
+        if not line:
+            break
+        name, _, value = line.partition(":")
+        headers[name.strip().lower()] = value.strip()
+    return json.loads(sys.stdin.buffer.read(int(headers["content-length"])))
+
+def send(message):
+    body = json.dumps(message).encode()
+    sys.stdout.buffer.write(b"Content-Length: %d\r\n\r\n" % len(body) + body)
+    sys.stdout.buffer.flush()
+
+def loc(uri, line, character):
+    pos = {"line": line, "character": character}
+    return {"uri": uri, "range": {"start": pos, "end": pos}}
+
+while True:
+    msg = read()
+    method = msg.get("method")
+    if method == "exit":
+        sys.exit(0)
+    if "id" not in msg:
+        continue
+    if method == "initialize":
+        result = {"capabilities": {"definitionProvider": True, "referencesProvider": True}}
+        send({"jsonrpc": "2.0", "id": 99, "method": "window/workDoneProgress/create"})
+    elif method == "textDocument/definition":
+        result = [loc(msg["params"]["textDocument"]["uri"], 0, 4)]
+    elif method == "textDocument/references":
+        uri = msg["params"]["textDocument"]["uri"]
+        result = [loc(uri, 0, 4), loc(uri, 4, 0), loc(uri, 5, 6)]
+    else:
+        result = None
+    send({"jsonrpc": "2.0", "id": msg["id"], "result": result})
+'''
+
+_SOURCE = """\
+def greet(name):
+    return f"Hello, {name}"
+
+
+greet("a")
+print(greet("b"))
+"""
+
+
+@pytest.fixture
+async def lsp_runtime(
+    runtime: Runtime, temp_work_dir: KaosPath, tmp_path: Path
+) -> AsyncGenerator[Runtime]:
+    server = tmp_path / "mock_lsp.py"
+    server.write_text(_MOCK_SERVER)
+    (temp_work_dir / "main.py").unsafe_to_local_path().write_text(_SOURCE)
+    runtime.lsp = LSPManager(
+        LSPConfig(servers={"python": LSPServerConfig(command=[sys.executable, str(server)])}),
+        temp_work_dir,
+    )
+    try:
+        yield runtime
+    finally:
+        await runtime.lsp.shutdown()
+
+
+def test_parse_locations_accepts_links():
+    result = [
+        {
+            "targetUri": "file:///tmp/a%20b.py",
+            "targetRange": {"start": {"line": 3, "character": 0}},
+            "targetSelectionRange": {"start": {"line": 3, "character": 4}},
+        }
+    ]
+    (location,) = parse_locations(result)
+    assert (location.path, location.line, location.character) == ("/tmp/a b.py", 3, 4)
+    assert parse_locations(None) == []
+
+
+async def test_go_to_definition(lsp_runtime: Runtime):
+    tool = GoToDefinition(lsp_runtime)
+    result = await tool(Position(path="main.py", line=5, column=1))
+    assert not result.is_error
+    assert result.output == snapshot("""\
+main.py:1:5
+>     1\tdef greet(name):
+      2\t    return f"Hello, {name}"
+      3\t
+
+""")
+
+
+async def test_find_references_limit(lsp_runtime: Runtime):
+    tool = FindReferences(lsp_runtime)
+    result = await tool(ReferencesParams(path="main.py", line=1, column=5, limit=2))
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert [line for line in result.output.splitlines() if line.startswith("main.py:")] == [
+        "main.py:1:5",
+        "main.py:5:1",
+    ]
+
+
+async def test_find_references_reads_each_file_once(
+    lsp_runtime: Runtime, monkeypatch: pytest.MonkeyPatch
+):
+    read_text = KaosPath.read_text
+    reads: list[str] = []
+
+    async def counting_read_text(self: KaosPath, **kwargs: Any) -> str:
+        reads.append(self.name)
+        return await read_text(self, **kwargs)
+
+    monkeypatch.setattr(KaosPath, "read_text", counting_read_text)
+    tool = FindReferences(lsp_runtime)
+    result = await tool(ReferencesParams(path="main.py", line=1, column=5))
+    assert not result.is_error
+    assert result.brief == "3 reference(s)"
+    # Once to open the document in the server, once for the context of all three references
+    assert reads == ["main.py", "main.py"]
+
+
+async def test_server_started_once(lsp_runtime: Runtime):
+    tool = FindReferences(lsp_runtime)
+    assert lsp_runtime.lsp is not None
+    await tool(ReferencesParams(path="main.py", line=1, column=5))
+    client = await lsp_runtime.lsp.client_for(lsp_runtime.builtin_args.KIMI_WORK_DIR / "main.py")
+    await tool(ReferencesParams(path="main.py", line=1, column=5))
+    assert client is await lsp_runtime.lsp.client_for(
+        lsp_runtime.builtin_args.KIMI_WORK_DIR / "main.py"
+    )
+
+
+async def test_not_configured(runtime: Runtime, temp_work_dir: KaosPath):
+    (temp_work_dir / "lib.rs").unsafe_to_local_path().write_text("fn main() {}\n")
+    tool = GoToDefinition(runtime)
+    result = await tool(Position(path="lib.rs", line=1, column=4))
+    assert not result.is_error
+    assert result.message == snapshot(
+        "LSP not configured for .rs. Fall back to Grep to locate the symbol."
+    )
@@ -0,0 +1,145 @@
+"""Tests for the MultiStrReplaceFile tool."""
+
+from __future__ import annotations
+
+import pytest
+from kaos.path import KaosPath
+
+from kimi_cli.tools.file.multi_replace import FileEdits, MultiStrReplaceFile, Params
+from kimi_cli.tools.file.replace import Edit
+from kimi_cli.wire.types import DiffDisplayBlock
+
+
+async def test_edits_all_files(
+    multi_str_replace_file_tool: MultiStrReplaceFile, temp_work_dir: KaosPath
+):
+    a = temp_work_dir / "a.py"
+    b = temp_work_dir / "b.py"
+    await a.write_text("def load():\n    pass\n")
+    await b.write_text("from a import load\n\nload()\nload()\n")
+
+    result = await multi_str_replace_file_tool(
+        Params(
+            files=[
+                FileEdits(path=str(a), edits=[Edit(old="def load", new="def load_config")]),
+                FileEdits(
+                    path=str(b),
+                    edits=[
+                        Edit(old="import load", new="import load_config"),
+                        Edit(old="load()", new="load_config()", replace_all=True),
+                    ],
+                ),
+            ]
+        )
+    )
+
+    assert not result.is_error
+    assert result.message == "2 file(s) successfully edited with 4 total replacement(s)."
+    assert await a.read_text() == "def load_config():\n    pass\n"
+    assert await b.read_text() == "from a import load_config\n\nload_config()\nload_config()\n"
+    diff_paths = {block.path for block in result.display if isinstance(block, DiffDisplayBlock)}
+    assert diff_paths == {str(a), str(b)}
+
+
+async def test_unmatched_edit_changes_no_file(
+    multi_str_replace_file_tool: MultiStrReplaceFile, temp_work_dir: KaosPath
+):
+    a = temp_work_dir / "a.py"
+    b = temp_work_dir / "b.py"
+    await a.write_text("alpha\n")
+    await b.write_text("beta\n")
+
+    result = await multi_str_replace_file_tool(
+        Params(
+            files=[
+                FileEdits(path=str(a), edits=[Edit(old="alpha", new="ALPHA")]),
+                FileEdits(
+                    path=str(b),
+                    edits=[Edit(old="beta", new="BETA"), Edit(old="beta", new="again")],
+                ),
+            ]
+        )
+    )
+
+    assert result.is_error
+    assert result.message.startswith(
+        f"Edit 2 of `{b}` did not match: the old string was at line 1 until edit 1 changed it"
+    )
+    assert await a.read_text() == "alpha\n"
+    assert await b.read_text() == "beta\n"
+
+
+async def test_missing_file_changes_no_file(
+    multi_str_replace_file_tool: MultiStrReplaceFile, temp_work_dir: KaosPath
+):
+    a = temp_work_dir / "a.py"
+    await a.write_text("alpha\n")
+    missing = temp_work_dir / "missing.py"
+
+    result = await multi_str_replace_file_tool(
+        Params(
+            files=[
+                FileEdits(path=str(a), edits=[Edit(old="alpha", new="ALPHA")]),
+                FileEdits(path=str(missing), edits=[Edit(old="x", new="y")]),
+            ]
+        )
+    )
+
+    assert result.is_error
+    assert result.brief == "File not found"
+    assert await a.read_text() == "alpha\n"
+
+
+async def test_rejects_duplicate_files(
+    multi_str_replace_file_tool: MultiStrReplaceFile, temp_work_dir: KaosPath
+):
+    a = temp_work_dir / "a.py"
+    await a.write_text("alpha beta\n")
+
+    result = await multi_str_replace_file_tool(
+        Params(
+            files=[
+                FileEdits(path=str(a), edits=[Edit(old="alpha", new="ALPHA")]),
+                FileEdits(path="a.py", edits=[Edit(old="beta", new="BETA")]),
+            ]
+        )
+    )
+
+    assert result.is_error
+    assert result.brief == "Duplicate file"
+    assert await a.read_text() == "alpha beta\n"
+
+
+async def test_rolls_back_when_a_write_fails(
+    multi_str_replace_file_tool: MultiStrReplaceFile,
+    temp_work_dir: KaosPath,
+    monkeypatch: pytest.MonkeyPatch,
+):
+    a = temp_work_dir / "a.py"
+    b = temp_work_dir / "b.py"
+    await a.write_bytes(b"alpha\r\n")
+    await b.write_text("beta\n")
+
+    original_write_bytes_atomic = KaosPath.write_bytes_atomic
+
+    async def failing_write_bytes_atomic(self: KaosPath, data: bytes):
+        if self.name == "b.py":
+            raise OSError("disk full")
+        return await original_write_bytes_atomic(self, data)
+
+    monkeypatch.setattr(KaosPath, "write_bytes_atomic", failing_write_bytes_atomic)
+
+    result = await multi_str_replace_file_tool(
+        Params(
+            files=[
+                FileEdits(path=str(a), edits=[Edit(old="alpha", new="ALPHA")]),
+                FileEdits(path=str(b), edits=[Edit(old="beta", new="BETA")]),
+            ]
+        )
+    )
+
+    assert result.is_error
+    assert "disk full" in result.message
+    assert "no file was changed" in result.message
+    assert await a.read_bytes() == b"alpha\r\n"
+    assert await b.read_text() == "beta\n"
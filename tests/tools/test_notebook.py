@@ -0,0 +1,190 @@
+"""Tests for the notebook tools."""
+
+from __future__ import annotations
+
+import json
+from typing import Any
+
+from kaos.path import KaosPath
+
+from kimi_cli.tools.file.notebook import EditParams, NotebookEdit, NotebookRead, ReadParams
+
+
+def _notebook() -> dict[str, Any]:
+    return {
+        "cells": [
+            {
+                "cell_type": "markdown",
+                "id": "intro",
+                "metadata": {},
+                "source": ["# Analysis\n", "Load the data."],
+            },
+            {
+                "cell_type": "code",
+                "execution_count": 3,
+                "id": "load",
+                "metadata": {"tags": ["setup"]},
+                "outputs": [
+                    {"name": "stdout", "output_type": "stream", "text": ["loaded 42 rows\n"]},
+                    {
+                        "data": {"image/png": "iVBORw0KGgo=", "text/plain": ["<Figure>"]},
+                        "metadata": {},
+                        "output_type": "display_data",
+                    },
+                ],
+                "source": ["import pandas as pd\n", "df = pd.read_csv('data.csv')"],
+            },
+            {
+                "cell_type": "code",
+                "execution_count": 4,
+                "id": "fail",
+                "metadata": {},
+                "outputs": [
+                    {
+                        "ename": "NameError",
+                        "evalue": "name 'x' is not defined",
+                        "output_type": "error",
+                        "traceback": [],
+                    }
+                ],
+                "source": "print(x)",
+            },
+        ],
+        "metadata": {"kernelspec": {"name": "python3", "display_name": "Python 3"}},
+        "nbformat": 4,
+        "nbformat_minor": 5,
+    }
+
+
+async def _write_notebook(path: KaosPath, notebook: dict[str, Any] | None = None) -> None:
+    await path.write_text(json.dumps(notebook or _notebook(), indent=1) + "\n")
+
+
+async def _read_notebook(path: KaosPath) -> dict[str, Any]:
+    return json.loads(await path.read_text())
+
+
+async def test_read_lists_cells_with_output_summaries(
+    notebook_read_tool: NotebookRead, temp_work_dir: KaosPath
+):
+    path = temp_work_dir / "analysis.ipynb"
+    await _write_notebook(path)
+
+    result = await notebook_read_tool(ReadParams(path=str(path)))
+
+    assert not result.is_error
+    assert result.output == (
+        '<cell index="0" type="markdown">\n'
+        "# Analysis\n"
+        "Load the data.\n"
+        "</cell>\n"
+        '<cell index="1" type="code" execution_count="3">\n'
+        "import pandas as pd\n"
+        "df = pd.read_csv('data.csv')\n"
+        "</cell>\n"
+        '<outputs index="1">\n'
+        "[stream stdout] loaded 42 rows\n"
+        "[display_data] <Figure> (image/png not shown)\n"
+        "</outputs>\n"
+        '<cell index="2" type="code" execution_count="4">\n'
+        "print(x)\n"
+        "</cell>\n"
+        '<outputs index="2">\n'
+        "[error] NameError: name 'x' is not defined\n"
+        "</outputs>\n"
+    )
+    assert result.message == "3 cells read from notebook."
+
+
+async def test_replace_cell_keeps_other_cells_and_metadata(
+    notebook_edit_tool: NotebookEdit, temp_work_dir: KaosPath
+):
+    path = temp_work_dir / "analysis.ipynb"
+    await _write_notebook(path)
+
+    result = await notebook_edit_tool(
+        EditParams(path=str(path), cell_index=1, new_source="import polars as pl\ndf = None")
+    )
+
+    assert not result.is_error
+    assert result.message == "Cell 1 replaced. The notebook now has 3 cells."
+    notebook = await _read_notebook(path)
+    expected = _notebook()
+    expected["cells"][1].update(
+        source=["import polars as pl\n", "df = None"], outputs=[], execution_count=None
+    )
+    assert notebook == expected
+
+
+async def test_replace_can_change_cell_type(
+    notebook_edit_tool: NotebookEdit, temp_work_dir: KaosPath
+):
+    path = temp_work_dir / "analysis.ipynb"
+    await _write_notebook(path)
+
+    result = await notebook_edit_tool(
+        EditParams(path=str(path), cell_index=2, new_source="Explain x.", cell_type="markdown")
+    )
+
+    assert not result.is_error
+    cell = (await _read_notebook(path))["cells"][2]
+    assert cell == {"cell_type": "markdown", "id": "fail", "metadata": {}, "source": ["Explain x."]}
+
+
+async def test_insert_and_delete_cells(notebook_edit_tool: NotebookEdit, temp_work_dir: KaosPath):
+    path = temp_work_dir / "analysis.ipynb"
+    await _write_notebook(path)
+
+    result = await notebook_edit_tool(
+        EditParams(path=str(path), cell_index=3, new_source="df.describe()", edit_type="insert")
+    )
+    assert not result.is_error
+    assert result.message == "Inserted a code cell at index 3. The notebook now has 4 cells."
+    cells = (await _read_notebook(path))["cells"]
+    assert cells[3]["cell_type"] == "code"
+    assert cells[3]["source"] == ["df.describe()"]
+    assert cells[3]["outputs"] == []
+    assert cells[3]["execution_count"] is None
+    assert cells[3]["id"]
+
+    result = await notebook_edit_tool(EditParams(path=str(path), cell_index=0, edit_type="delete"))
+    assert not result.is_error
+    assert result.message == "Cell 0 deleted. The notebook now has 3 cells."
+    cells = (await _read_notebook(path))["cells"]
+    assert [cell["id"] for cell in cells[:2]] == ["load", "fail"]
+
+
+async def test_edit_rejects_out_of_range_index(
+    notebook_edit_tool: NotebookEdit, temp_work_dir: KaosPath
+):
+    path = temp_work_dir / "analysis.ipynb"
+    await _write_notebook(path)
+
+    result = await notebook_edit_tool(EditParams(path=str(path), cell_index=3, new_source="x"))
+
+    assert result.is_error
+    assert result.message == (
+        "Cell index 3 is out of range for `replace`. The notebook has 3 cells."
+    )
+    assert await _read_notebook(path) == _notebook()
+
+
+async def test_malformed_notebooks_are_rejected(
+    notebook_read_tool: NotebookRead, notebook_edit_tool: NotebookEdit, temp_work_dir: KaosPath
+):
+    not_json = temp_work_dir / "broken.ipynb"
+    await not_json.write_text("{not json")
+    result = await notebook_read_tool(ReadParams(path=str(not_json)))
+    assert result.is_error
+    assert result.message.startswith(f"`{not_json}` is not a valid Jupyter notebook: invalid JSON")
+
+    notebook = _notebook()
+    notebook["cells"][1]["cell_type"] = "python"
+    bad_cell = temp_work_dir / "bad_cell.ipynb"
+    await _write_notebook(bad_cell, notebook)
+    result = await notebook_edit_tool(EditParams(path=str(bad_cell), cell_index=0, new_source="x"))
+    assert result.is_error
+    assert result.message.startswith(
+        f"`{bad_cell}` is not a valid Jupyter notebook: `cells.1.cell_type`:"
+    )
+    assert await _read_notebook(bad_cell) == notebook
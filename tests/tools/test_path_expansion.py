@@ -0,0 +1,80 @@
+"""Every file tool expands `~` and environment variables in its path parameter the same way."""
+
+from __future__ import annotations
+
+import pytest
+from kaos.path import KaosPath
+
+from kimi_cli.tools.file import Glob, Grep, ReadFile, StrReplaceFile, WriteFile
+from kimi_cli.tools.file import glob as glob_module
+from kimi_cli.tools.file import grep_local as grep_module
+from kimi_cli.tools.file import read as read_module
+from kimi_cli.tools.file import replace as replace_module
+from kimi_cli.tools.file import write as write_module
+
+PREFIXES = ["~", "$KIMI_TEST_DIR", "${KIMI_TEST_DIR}"]
+
+
+@pytest.fixture(autouse=True)
+def _home_is_work_dir(temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("HOME", str(temp_work_dir))
+    monkeypatch.setenv("USERPROFILE", str(temp_work_dir))
+    monkeypatch.setenv("KIMI_TEST_DIR", str(temp_work_dir))
+
+
+@pytest.mark.parametrize("prefix", PREFIXES)
+async def test_read_file(read_file_tool: ReadFile, temp_work_dir: KaosPath, prefix: str):
+    await (temp_work_dir / "notes.txt").write_text("hello\n")
+
+    result = await read_file_tool(read_module.Params(path=f"{prefix}/notes.txt"))
+
+    assert not result.is_error
+    assert "hello" in result.output
+
+
+@pytest.mark.parametrize("prefix", PREFIXES)
+async def test_write_file(write_file_tool: WriteFile, temp_work_dir: KaosPath, prefix: str):
+    result = await write_file_tool(
+        write_module.Params(path=f"{prefix}/notes.txt", content="hello\n")
+    )
+
+    assert not result.is_error
+    assert await (temp_work_dir / "notes.txt").read_text() == "hello\n"
+
+
+@pytest.mark.parametrize("prefix", PREFIXES)
+async def test_str_replace_file(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath, prefix: str
+):
+    await (temp_work_dir / "notes.txt").write_text("hello\n")
+
+    result = await str_replace_file_tool(
+        replace_module.Params(
+            path=f"{prefix}/notes.txt", edit=replace_module.Edit(old="hello", new="bye")
+        )
+    )
+
+    assert not result.is_error
+    assert await (temp_work_dir / "notes.txt").read_text() == "bye\n"
+
+
+@pytest.mark.parametrize("prefix", PREFIXES)
+async def test_glob(glob_tool: Glob, temp_work_dir: KaosPath, prefix: str):
+    await (temp_work_dir / "notes.txt").write_text("hello\n")
+
+    result = await glob_tool(glob_module.Params(pattern="*.txt", directory=prefix))
+
+    assert not result.is_error
+    assert "notes.txt" in result.output
+
+
+@pytest.mark.parametrize("prefix", PREFIXES)
+async def test_grep(grep_tool: Grep, temp_work_dir: KaosPath, prefix: str):
+    await (temp_work_dir / "notes.txt").write_text("hello\n")
+
+    result = await grep_tool(
+        grep_module.Params(pattern="hello", path=f"{prefix}/notes.txt", output_mode="content")
+    )
+
+    assert not result.is_error
+    assert "hello" in result.output
@@ -2,10 +2,14 @@
 
 from __future__ import annotations
 
+import os
+import sys
 from pathlib import Path
 
 import pytest
 from inline_snapshot import snapshot
+from kaos import StatResult, StrOrKaosPath, reset_current_kaos, set_current_kaos
+from kaos.local import LocalKaos
 from kaos.path import KaosPath
 
 from kimi_cli.tools.file.read import (
@@ -111,6 +115,47 @@ async def test_read_directory_instead_of_file(read_file_tool: ReadFile, temp_wor
     assert result.brief == snapshot("Invalid path")
 
 
+class StatCountingKaos(LocalKaos):
+    def __init__(self) -> None:
+        super().__init__()
+        self.stats: list[str] = []
+
+    async def stat(self, path: StrOrKaosPath, *, follow_symlinks: bool = True) -> StatResult:
+        self.stats.append(str(path))
+        return await super().stat(path, follow_symlinks=follow_symlinks)
+
+
+async def test_precondition_checks_stat_the_path_once(
+    read_file_tool: ReadFile, temp_work_dir: KaosPath
+):
+    """Telling a missing path or a directory from a file takes a single stat of the path."""
+    nonexistent_file = temp_work_dir / "nonexistent.txt"
+    counting = StatCountingKaos()
+    token = set_current_kaos(counting)
+    try:
+        missing = await read_file_tool(Params(path=str(nonexistent_file)))
+        directory = await read_file_tool(Params(path=str(temp_work_dir)))
+    finally:
+        reset_current_kaos(token)
+
+    assert missing.brief == "File not found"
+    assert directory.brief == "Invalid path"
+    assert counting.stats.count(str(nonexistent_file)) == 1
+    assert counting.stats.count(str(temp_work_dir)) == 1
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="POSIX symlinks")
+async def test_read_dangling_symlink(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """A symlink to a missing file does not exist as far as reading goes."""
+    link = temp_work_dir / "dangling.txt"
+    os.symlink("missing.txt", link.unsafe_to_local_path())
+
+    result = await read_file_tool(Params(path=str(link)))
+
+    assert result.is_error
+    assert result.message == f"`{link}` does not exist."
+
+
 async def test_read_with_relative_path(
     read_file_tool: ReadFile, temp_work_dir: KaosPath, sample_file: KaosPath
 ):
@@ -230,6 +275,20 @@ async def test_read_unicode_file(read_file_tool: ReadFile, temp_work_dir: KaosPa
     )
 
 
+async def test_read_crlf_and_invalid_utf8_file(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """CRLF line breaks are normalized and invalid UTF-8 only affects its own line."""
+    file_path = temp_work_dir / "windows.txt"
+    await file_path.write_bytes(b"first\r\nbad \xff byte\r\nlast\r\n")
+
+    result = await read_file_tool(Params(path=str(file_path)))
+
+    assert not result.is_error
+    assert result.output == "     1\tfirst\n     2\tbad \ufffd byte\n     3\tlast\n"
+    assert result.message == snapshot(
+        "3 lines read from file starting from line 1. Total lines in file: 3. End of file reached."
+    )
+
+
 async def test_read_edge_cases(read_file_tool: ReadFile, sample_file: KaosPath):
     """Test edge cases for line offset reading."""
     # Test reading from line 1 (should be same as default)
@@ -354,8 +413,10 @@ async def test_max_lines_boundary(read_file_tool: ReadFile, temp_work_dir: KaosP
 
     assert not result.is_error
     assert isinstance(result.output, str)
-    # Should read MAX_LINES lines, not the full file
-    assert f"Max {MAX_LINES} lines reached" in result.message
+    # Should read MAX_LINES lines, not the full file, and tell the model about the limit
+    assert result.message.startswith(
+        f"note: n_lines clamped from {MAX_LINES + 5} to {MAX_LINES}\n{MAX_LINES} lines read"
+    )
     # Count actual lines in output (accounting for line numbers)
     output_lines = [line for line in result.output.split("\n") if line.strip()]
     assert len(output_lines) == MAX_LINES
@@ -600,3 +661,61 @@ async def test_read_tail_line_truncation(read_file_tool: ReadFile, temp_work_dir
     line_4 = [x for x in output_lines if x.strip().startswith("4")][0]
     actual_content = line_4.split("\t", 1)[1]
     assert actual_content.endswith("...")
+
+
+SHIFT_JIS_TEXT = (
+    "設定ファイルの説明です。\n"
+    "このファイルはシフトJISで保存されています。\n"
+    "日本語の文字化けが起きないことを確認します。\n"
+)
+
+
+async def test_read_detects_shift_jis(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """A file that is not UTF-8 is decoded with its detected encoding."""
+    sjis_file = temp_work_dir / "readme_ja.txt"
+    await sjis_file.write_bytes(SHIFT_JIS_TEXT.encode("shift_jis"))
+
+    result = await read_file_tool(Params(path=str(sjis_file)))
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "このファイルはシフトJISで保存されています。" in result.output
+    assert "�" not in result.output
+    assert "Decoded from " in result.message
+
+
+async def test_read_with_explicit_encoding(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """The `encoding` parameter overrides detection."""
+    latin1_file = temp_work_dir / "notes.txt"
+    await latin1_file.write_bytes("café\nnaïve\n".encode("latin-1"))
+
+    result = await read_file_tool(Params(path=str(latin1_file), encoding="latin-1"))
+
+    assert not result.is_error
+    assert result.output == "     1\tcafé\n     2\tnaïve\n"
+    assert result.message.endswith(" Decoded from iso8859-1.")
+
+
+async def test_read_utf8_is_not_redetected(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """Valid UTF-8 is read as before, without an encoding note."""
+    utf8_file = temp_work_dir / "readme_ja.txt"
+    await utf8_file.write_text(SHIFT_JIS_TEXT)
+
+    result = await read_file_tool(Params(path=str(utf8_file)))
+
+    assert not result.is_error
+    assert isinstance(result.output, str)
+    assert "このファイルはシフトJISで保存されています。" in result.output
+    assert "Decoded from" not in result.message
+
+
+async def test_read_rejects_unsupported_encoding(read_file_tool: ReadFile, temp_work_dir: KaosPath):
+    """Unknown encodings, and encodings that lines cannot be split in, are rejected."""
+    text_file = temp_work_dir / "a.txt"
+    await text_file.write_text("hello\n")
+
+    for encoding in ("no-such-encoding", "utf-16"):
+        result = await read_file_tool(Params(path=str(text_file), encoding=encoding))
+
+        assert result.is_error
+        assert result.brief == "Unsupported encoding"
@@ -0,0 +1,133 @@
+"""Tests for the ReadPdf tool, which needs the optional `pdf` extra."""
+
+from __future__ import annotations
+
+import pytest
+from kaos.path import KaosPath
+
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.tools import SkipThisTool
+from kimi_cli.tools.file import pdf as pdf_module
+from kimi_cli.tools.file.pdf import NO_TEXT_NOTE, Params, ReadPdf
+
+
+def _pdf(pages: list[str | None]) -> bytes:
+    """Build a minimal PDF with one line of Helvetica text per page, or a blank page for None."""
+    n_pages = len(pages)
+    font_id = 3 + 2 * n_pages
+    objects: list[bytes] = [
+        b"<< /Type /Catalog /Pages 2 0 R >>",
+        b"<< /Type /Pages /Kids ["
+        + b" ".join(f"{3 + 2 * i} 0 R".encode() for i in range(n_pages))
+        + f"] /Count {n_pages} >>".encode(),
+    ]
+    for i, text in enumerate(pages):
+        objects.append(
+            f"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 300 300] /Contents {4 + 2 * i} 0 R "
+            f"/Resources << /Font << /F1 {font_id} 0 R >> >> >>".encode()
+        )
+        content = f"BT /F1 12 Tf 20 150 Td ({text}) Tj ET".encode() if text else b""
+        objects.append(
+            f"<< /Length {len(content)} >>\nstream\n".encode() + content + b"\nendstream"
+        )
+    objects.append(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>")
+
+    out = bytearray(b"%PDF-1.4\n")
+    offsets: list[int] = []
+    for obj_id, body in enumerate(objects, start=1):
+        offsets.append(len(out))
+        out += f"{obj_id} 0 obj\n".encode() + body + b"\nendobj\n"
+    xref = len(out)
+    out += f"xref\n0 {len(objects) + 1}\n0000000000 65535 f \n".encode()
+    for offset in offsets:
+        out += f"{offset:010d} 00000 n \n".encode()
+    out += f"trailer\n<< /Size {len(objects) + 1} /Root 1 0 R >>\n".encode()
+    out += f"startxref\n{xref}\n%%EOF\n".encode()
+    return bytes(out)
+
+
+async def test_read_pages(read_pdf_tool: ReadPdf, temp_work_dir: KaosPath):
+    file = temp_work_dir / "report.pdf"
+    await file.write_bytes(_pdf(["First page", None, "Third page"]))
+
+    result = await read_pdf_tool(Params(path=str(file)))
+
+    assert not result.is_error
+    assert result.output == (
+        '<page number="1">\nFirst page\n</page>\n'
+        f'<page number="2">\n{NO_TEXT_NOTE}\n</page>\n'
+        '<page number="3">\nThird page\n</page>\n'
+    )
+    assert result.message == (
+        "Pages 1-3 read from PDF. Total pages: 3. End of document reached. "
+        "Pages [2] have no extractable text; they are likely scanned or only contain images."
+    )
+
+
+async def test_read_page_range(read_pdf_tool: ReadPdf, temp_work_dir: KaosPath):
+    file = temp_work_dir / "report.pdf"
+    await file.write_bytes(_pdf(["One", "Two", "Three"]))
+
+    result = await read_pdf_tool(Params(path=str(file), first_page=2, n_pages=1))
+
+    assert result.output == '<page number="2">\nTwo\n</page>\n'
+    assert result.message == "Page 2 read from PDF. Total pages: 3."
+
+    result = await read_pdf_tool(Params(path=str(file), first_page=4))
+
+    assert result.is_error
+    assert result.message == "Page 4 does not exist. The PDF has 3 pages."
+
+
+async def test_text_is_capped(
+    read_pdf_tool: ReadPdf, temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setattr(pdf_module, "MAX_BYTES", 12)
+    file = temp_work_dir / "report.pdf"
+    await file.write_bytes(_pdf(["Page one", "Page two"]))
+
+    result = await read_pdf_tool(Params(path=str(file)))
+
+    assert result.output == '<page number="1">\nPage one\n</page>\n'
+    assert "Max 12 bytes reached; set `first_page` to 2 to continue." in result.message
+
+
+async def test_oversized_page_is_cut(
+    read_pdf_tool: ReadPdf, temp_work_dir: KaosPath, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setattr(pdf_module, "MAX_BYTES", 4)
+    file = temp_work_dir / "report.pdf"
+    await file.write_bytes(_pdf(["Page one", "Page two"]))
+
+    result = await read_pdf_tool(Params(path=str(file)))
+
+    assert result.output == '<page number="1">\nPage\n</page>\n'
+    assert result.message == (
+        "Page 1 read from PDF. Total pages: 2. Page 1 has more than 4 bytes of text and was cut "
+        "short. Set `first_page` to 2 to continue with the next page."
+    )
+
+    result = await read_pdf_tool(Params(path=str(file), first_page=2))
+
+    assert result.output == '<page number="2">\nPage\n</page>\n'
+    assert result.message == (
+        "Page 2 read from PDF. Total pages: 2. Page 2 has more than 4 bytes of text and was cut "
+        "short."
+    )
+
+
+async def test_not_a_pdf(read_pdf_tool: ReadPdf, temp_work_dir: KaosPath):
+    file = temp_work_dir / "report.pdf"
+    await file.write_text("just text")
+
+    result = await read_pdf_tool(Params(path=str(file)))
+
+    assert result.is_error
+    assert result.message == f"`{file}` is not a PDF file."
+
+
+def test_skipped_without_pdf_extra(runtime: Runtime, monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.setattr(pdf_module, "pdf_support_available", lambda: False)
+
+    with pytest.raises(SkipThisTool):
+        ReadPdf(runtime)
@@ -0,0 +1,100 @@
+"""Tests for the ReadTable tool."""
+
+from __future__ import annotations
+
+from inline_snapshot import snapshot
+from kaos.path import KaosPath
+
+from kimi_cli.tools.file.table import Params, ReadTable
+
+
+async def test_read_csv_as_aligned_table(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    file = temp_work_dir / "people.csv"
+    await file.write_text('name,age,city\nAlice,30,"New York, NY"\nBob,4,Paris\n')
+
+    result = await read_table_tool(Params(path=str(file)))
+
+    assert not result.is_error
+    assert result.output == snapshot(
+        """\
+     1	name  | age | city
+     2	Alice | 30  | New York, NY
+     3	Bob   | 4   | Paris
+"""
+    )
+    assert result.message == "3 rows and 3 columns, delimited by comma."
+
+
+async def test_detect_tsv_and_semicolon(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    tsv = temp_work_dir / "data.tsv"
+    await tsv.write_text("a\tb\n1\t2\n")
+    semicolon = temp_work_dir / "data.csv"
+    await semicolon.write_text("a;b;c\n1;2;3\n4;5;6\n")
+
+    tsv_result = await read_table_tool(Params(path=str(tsv)))
+    semicolon_result = await read_table_tool(Params(path=str(semicolon)))
+
+    assert tsv_result.message == "2 rows and 2 columns, delimited by tab."
+    assert semicolon_result.message == "3 rows and 3 columns, delimited by semicolon."
+
+
+async def test_explicit_delimiter(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    file = temp_work_dir / "data.txt"
+    await file.write_text("a|b\n1|2\n")
+
+    result = await read_table_tool(Params(path=str(file), delimiter="|"))
+
+    assert result.output == snapshot(
+        """\
+     1	a | b
+     2	1 | 2
+"""
+    )
+
+
+async def test_truncation_is_reported(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    file = temp_work_dir / "wide.csv"
+    rows = [",".join(f"r{r}c{c}" for c in range(6)) for r in range(10)]
+    rows[1] = "x" * 60 + ",short"
+    await file.write_text("\n".join(rows) + "\n")
+
+    result = await read_table_tool(Params(path=str(file), max_rows=3, max_columns=2))
+
+    assert not result.is_error
+    assert result.output == snapshot(
+        """\
+     1	r0c0                                     | r0c1
+     2	xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx... | short
+     3	r2c0                                     | r2c1
+"""
+    )
+    assert result.message == snapshot(
+        "Showing the first 3 rows, which have 6 columns, delimited by comma. "
+        "The table has more rows. Rows have between 2 and 6 columns. "
+        "Showing the first 2 columns. Cells longer than 40 characters were truncated."
+    )
+
+
+async def test_quoted_cells_spanning_lines(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    file = temp_work_dir / "notes.csv"
+    await file.write_text('id,note\n1,"first line\nsecond ""quoted"" line"\n2,plain\n')
+
+    result = await read_table_tool(Params(path=str(file), max_rows=2))
+
+    assert result.output == snapshot(
+        """\
+     1	id | note
+     2	1  | first line\\nsecond "quoted" line
+"""
+    )
+    assert result.message == snapshot(
+        "Showing the first 2 rows, which have 2 columns, delimited by comma. "
+        "The table has more rows."
+    )
+
+
+async def test_missing_file(read_table_tool: ReadTable, temp_work_dir: KaosPath):
+    result = await read_table_tool(Params(path=str(temp_work_dir / "missing.csv")))
+
+    assert result.is_error
+    assert "does not exist" in result.message
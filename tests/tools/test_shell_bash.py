@@ -188,6 +188,23 @@ async def test_output_truncation_on_failure(shell_tool: Shell):
     assert "Command failed with exit code:" in result.message
 
 
+async def test_huge_output_keeps_head_and_tail(shell_tool: Shell):
+    """Test that huge output is capped while streaming, keeping its start and end."""
+    result = await shell_tool(
+        Params(command="echo first; yes middle | head -c 5000000; echo; echo last; exit 3")
+    )
+
+    assert result.is_error
+    assert isinstance(result.output, str)
+    assert result.output.startswith("first\n")
+    assert result.output.endswith("last\n")
+    assert "bytes truncated]" in result.output
+    assert len(result.output) < DEFAULT_MAX_CHARS
+    assert result.message == snapshot(
+        "Command failed with exit code: 3. Output truncated, total 4.8 MB."
+    )
+
+
 async def test_timeout_parameter_validation_bounds(shell_tool: Shell):
     """Test timeout parameter validation (bounds checking)."""
     # Test timeout < 1 (should fail validation)
@@ -203,9 +220,9 @@ async def test_timeout_parameter_validation_bounds(shell_tool: Shell):
     with pytest.raises(ValueError, match="timeout"):
         Params(command="echo test", timeout=MAX_BACKGROUND_TIMEOUT + 1)
 
-    # Test foreground timeout > MAX_FOREGROUND_TIMEOUT (should fail validation)
-    with pytest.raises(ValueError, match="foreground"):
-        Params(command="echo test", timeout=MAX_FOREGROUND_TIMEOUT + 1)
+    # Foreground timeouts > MAX_FOREGROUND_TIMEOUT are clamped when the command runs
+    params = Params(command="echo test", timeout=MAX_FOREGROUND_TIMEOUT + 1)
+    assert params.timeout == MAX_FOREGROUND_TIMEOUT + 1
 
     # Background commands can use longer timeouts
     params = Params(
@@ -217,6 +234,20 @@ async def test_timeout_parameter_validation_bounds(shell_tool: Shell):
     assert params.timeout == MAX_FOREGROUND_TIMEOUT + 1
 
 
+async def test_foreground_timeout_is_clamped_with_a_note(shell_tool: Shell):
+    from kimi_cli.tools.shell import MAX_FOREGROUND_TIMEOUT
+
+    result = await shell_tool(Params(command="echo hi", timeout=MAX_FOREGROUND_TIMEOUT + 100))
+
+    assert not result.is_error
+    assert result.output == "hi\n"
+    assert result.message == (
+        f"note: timeout clamped from {MAX_FOREGROUND_TIMEOUT + 100} to {MAX_FOREGROUND_TIMEOUT} "
+        "for a foreground command, use run_in_background=true for longer timeouts\n"
+        "Command executed successfully."
+    )
+
+
 async def test_shell_works_in_plan_mode(shell_tool: Shell, runtime):
     """Shell should still work in plan mode — plan mode constraints are enforced by
     the dynamic injection prompt, not by hard-blocking the tool."""
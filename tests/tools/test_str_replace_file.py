@@ -2,12 +2,18 @@
 
 from __future__ import annotations
 
+import platform
+import stat
 from pathlib import Path
 
+import pytest
 from kaos.path import KaosPath
 
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.soul.approval import Approval
 from kimi_cli.tools.file.replace import Edit, Params, StrReplaceFile
 from kimi_cli.wire.types import DiffDisplayBlock
+from tests.conftest import tool_call_context
 
 
 async def test_replace_single_occurrence(
@@ -246,3 +252,155 @@ async def test_replace_empty_strings(
     assert not result.is_error
     assert "successfully edited" in result.message
     assert await file_path.read_text() == "Hello !"
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="POSIX permission bits")
+async def test_replace_keeps_executable_bit(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    """Editing a script keeps its permission bits."""
+    file_path = temp_work_dir / "run.sh"
+    await file_path.write_text("#!/bin/sh\necho hello\n")
+    local_path = file_path.unsafe_to_local_path()
+    local_path.chmod(0o755)
+
+    result = await str_replace_file_tool(
+        Params(path=str(file_path), edit=Edit(old="hello", new="bye"))
+    )
+
+    assert not result.is_error
+    assert await file_path.read_text() == "#!/bin/sh\necho bye\n"
+    assert stat.S_IMODE(local_path.stat().st_mode) == 0o755
+
+
+async def test_replace_keeps_crlf_line_endings(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    """Replacement text with bare LF is written with the file's CRLF line endings."""
+    file_path = temp_work_dir / "windows.txt"
+    await file_path.write_bytes(b"line 1\r\nline 2\r\nline 3\r\n")
+
+    result = await str_replace_file_tool(
+        Params(path=str(file_path), edit=Edit(old="line 2\nline 3", new="line two\nline three"))
+    )
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"line 1\r\nline two\r\nline three\r\n"
+    assert isinstance(result.display[0], DiffDisplayBlock)
+    assert "\r" not in result.display[0].old_text
+    assert "\r" not in result.display[0].new_text
+
+
+async def test_replace_keeps_bom(str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath):
+    """A UTF-8 BOM at the start of the file is kept, and is not part of the edited text."""
+    file_path = temp_work_dir / "bom.txt"
+    await file_path.write_bytes(b"\xef\xbb\xbfHello world")
+
+    result = await str_replace_file_tool(
+        Params(path=str(file_path), edit=Edit(old="Hello", new="Goodbye"))
+    )
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"\xef\xbb\xbfGoodbye world"
+
+
+async def test_replace_over_size_limit_is_rejected(
+    runtime: Runtime, approval: Approval, temp_work_dir: KaosPath
+):
+    """Edits growing the file by more than `max_write_bytes` are not written."""
+    runtime.config.tools.max_write_bytes = 100
+    file_path = temp_work_dir / "big.txt"
+    await file_path.write_text("x" * 500 + "\nmarker\n")
+    with tool_call_context("StrReplaceFile"):
+        tool = StrReplaceFile(runtime, approval)
+
+        result = await tool(
+            Params(path=str(file_path), edit=Edit(old="marker", new="marker" + "a" * 101))
+        )
+        assert result.is_error
+        assert result.brief == "File too large"
+        assert await file_path.read_text() == "x" * 500 + "\nmarker\n"
+
+        # The file is already over the limit, but the edit only adds 100 bytes to it
+        result = await tool(
+            Params(path=str(file_path), edit=Edit(old="marker", new="marker" + "a" * 100))
+        )
+        assert not result.is_error
+
+
+async def test_replace_mid_batch_failure_leaves_file_untouched(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    """A list of edits is all or nothing, and the error says which edits matched."""
+    file_path = temp_work_dir / "test.txt"
+    original_content = "def load():\n    pass\n\ndef save():\n    pass\n"
+    await file_path.write_text(original_content)
+
+    result = await str_replace_file_tool(
+        Params(
+            path=str(file_path),
+            edit=[
+                Edit(old="def load():", new="def load_all():"),
+                Edit(old="def save():", new="def save_all():"),
+                Edit(old="def delete():", new="def delete_all():"),
+            ],
+        )
+    )
+
+    assert result.is_error
+    assert result.brief == "Edit did not match"
+    assert result.message.splitlines() == [
+        "1 of 3 edits did not match, so no edit was applied and the file was not changed:",
+        "- Edit 1: matched at line 1",
+        "- Edit 2: matched at line 4",
+        "- Edit 3: did not match: the old string was not found in the file",
+    ]
+    assert await file_path.read_text() == original_content
+
+
+async def test_replace_reports_edit_invalidated_by_earlier_edit(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    """An edit whose old string an earlier edit rewrote is reported as such."""
+    file_path = temp_work_dir / "test.txt"
+    original_content = "name = 'kimi'\nprint(name)\n"
+    await file_path.write_text(original_content)
+
+    result = await str_replace_file_tool(
+        Params(
+            path=str(file_path),
+            edit=[
+                Edit(old="name", new="agent_name", replace_all=True),
+                Edit(old="print(name)", new="print(name.upper())"),
+            ],
+        )
+    )
+
+    assert result.is_error
+    assert result.message.splitlines()[1:] == [
+        "- Edit 1: matched at line 1",
+        "- Edit 2: did not match: the old string was at line 2 until edit 1 changed it; "
+        "merge the two edits, or match the text written by edit 1",
+    ]
+    assert await file_path.read_text() == original_content
+
+
+async def test_replace_multiple_edits_show_one_combined_diff(
+    str_replace_file_tool: StrReplaceFile, temp_work_dir: KaosPath
+):
+    file_path = temp_work_dir / "test.txt"
+    await file_path.write_text("alpha\nbeta\n")
+
+    result = await str_replace_file_tool(
+        Params(
+            path=str(file_path),
+            edit=[Edit(old="alpha", new="one"), Edit(old="one\nbeta", new="one\ntwo")],
+        )
+    )
+
+    assert not result.is_error
+    assert "Applied 2 edit(s) with 2 total replacement(s)." in result.message
+    [block] = result.display
+    assert isinstance(block, DiffDisplayBlock)
+    assert (block.old_text, block.new_text) == ("alpha\nbeta", "one\ntwo")
+    assert await file_path.read_text() == "one\ntwo\n"
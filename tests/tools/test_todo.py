@@ -25,6 +25,9 @@ class TestSetTodoListOutputNotEmpty:
     async def test_write_mode_returns_nonempty_output(self, set_todo_list_tool: SetTodoList):
         """When todos are provided, the tool must return a non-empty output
         so the model gets meaningful feedback (not just 'Todo list updated')."""
+        await set_todo_list_tool(
+            Params(todos=[Todo(title="Read requirements", status="in_progress")])
+        )
         params = Params(
             todos=[
                 Todo(title="Analyze code", status="pending"),
@@ -45,6 +48,7 @@ class TestSetTodoListOutputNotEmpty:
         """When no todos are provided (None), the tool should return the current
         todo list from persistent storage, including status."""
         # First write some todos
+        await set_todo_list_tool(Params(todos=[Todo(title="Task B", status="in_progress")]))
         write_params = Params(
             todos=[
                 Todo(title="Task A", status="pending"),
@@ -93,6 +97,7 @@ class TestSetTodoListOutputNotEmpty:
         """Write mode should persist todos to disk via SessionState."""
         from kimi_cli.session_state import load_session_state
 
+        await set_todo_list_tool(Params(todos=[Todo(title="Another task", status="in_progress")]))
         params = Params(
             todos=[
                 Todo(title="Disk task", status="in_progress"),
@@ -126,6 +131,48 @@ class TestSetTodoListOutputNotEmpty:
         assert result.display == []
 
 
+class TestSetTodoListStatusOrder:
+    """Todo items must move from pending to in_progress to done."""
+
+    async def test_done_without_in_progress_is_rejected(self, set_todo_list_tool: SetTodoList):
+        await set_todo_list_tool(Params(todos=[Todo(title="Task A", status="pending")]))
+
+        result = await set_todo_list_tool(
+            Params(
+                todos=[
+                    Todo(title="Task A", status="done"),
+                    Todo(title="Task B", status="done"),
+                ]
+            )
+        )
+
+        assert result.is_error
+        assert result.brief == "Invalid todo transition"
+        assert result.message.startswith('Cannot mark "Task A", "Task B" as done')
+        assert "pending to in_progress to done" in result.message
+        # The rejected update must not be persisted
+        result = await set_todo_list_tool(Params(todos=None))
+        assert result.output == "Current todo list:\n- [pending] Task A"
+
+    async def test_in_progress_then_done_is_accepted(self, set_todo_list_tool: SetTodoList):
+        await set_todo_list_tool(Params(todos=[Todo(title="Task A", status="pending")]))
+        await set_todo_list_tool(Params(todos=[Todo(title="Task A", status="in_progress")]))
+
+        result = await set_todo_list_tool(Params(todos=[Todo(title="Task A", status="done")]))
+        assert not result.is_error
+
+        # Items already done may stay done in later updates
+        result = await set_todo_list_tool(
+            Params(
+                todos=[
+                    Todo(title="Task A", status="done"),
+                    Todo(title="Task B", status="pending"),
+                ]
+            )
+        )
+        assert not result.is_error
+
+
 class TestSetTodoListSubagent:
     """Test SetTodoList behavior in subagent context."""
 
@@ -12,6 +12,7 @@ from kimi_cli.tools.background import TaskList, TaskOutput, TaskStop
 from kimi_cli.tools.dmail import SendDMail
 from kimi_cli.tools.file.glob import Glob
 from kimi_cli.tools.file.grep_local import Grep
+from kimi_cli.tools.file.multi_replace import MultiStrReplaceFile
 from kimi_cli.tools.file.read import ReadFile
 from kimi_cli.tools.file.read_media import ReadMediaFile
 from kimi_cli.tools.file.replace import StrReplaceFile
@@ -19,6 +20,7 @@ from kimi_cli.tools.file.write import WriteFile
 from kimi_cli.tools.shell import Shell
 from kimi_cli.tools.think import Think
 from kimi_cli.tools.todo import SetTodoList
+from kimi_cli.tools.utils import load_desc
 from kimi_cli.tools.web.fetch import FetchURL
 from kimi_cli.tools.web.search import SearchWeb
 
@@ -90,7 +92,7 @@ Typical scenarios you may want to send a D-Mail:
 
 After a D-Mail is sent, the system will revert the current context to the specified checkpoint, after which, you will no longer see any messages which you can now see after that checkpoint. The message in the D-Mail will be appended to the end of the context. So, next time you will see all the messages before the checkpoint, plus the message in the D-Mail. You must make it very clear in the message, tell your past self what you have done/changed, what you have learned and any other information that may be useful, so that your past self can continue the task without confusion and will not repeat the steps you have already done.
 
-You must understand that, unlike D-Mail in Steins;Gate, the D-Mail you send here will not revert the filesystem or any external state. That means, you are basically folding the recent messages in your context into a single message, which can significantly reduce the waste of context window.
+You must understand that, unlike D-Mail in Steins;Gate, the D-Mail you send here will not revert the filesystem or any external state by default. That means, you are basically folding the recent messages in your context into a single message, which can significantly reduce the waste of context window. If the changes you made to files after the checkpoint should be thrown away, set `restore_files` to `true`: once the user confirms, the files changed by `WriteFile` and `StrReplaceFile` are restored to their content at the checkpoint, and you will be told which files were restored. Other changes, such as those made by shell commands, are never undone.
 
 When sending a D-Mail, DO NOT explain to the user. The user do not care about this. Just explain to your past self.
 """
@@ -120,6 +122,8 @@ Todo list is a simple yet powerful tool to help you get things done. You typical
 
 This is the only todo list tool available to you. That said, each time you want to update the todo list, you need to provide the whole list. Make sure to maintain the todo items and their statuses properly.
 
+Each item moves from `pending` to `in_progress` to `done`. Mark an item `in_progress` when you start working on it, and `done` once it is finished. An item that was never `in_progress` cannot be marked `done`; such updates are rejected and the todo list is left unchanged.
+
 Once you finished a subtask/milestone, remember to update the todo list to reflect the progress. Also, you can give yourself a self-encouragement to keep you motivated.
 
 Abusing this tool to track too small steps will just waste your time and make your context messy. For example, here are some cases you should not use this tool:
@@ -149,7 +153,7 @@ If `run_in_background=true`, the command will be started as a background task an
 
 **Guidelines for safety and security:**
 - Each shell tool call will be executed in a fresh shell environment. The shell variables, current working directory changes, and the shell history is not preserved between calls.
-- The tool call will return after the command is finished. You shall not use this tool to execute an interactive command or a command that may run forever. For possibly long-running commands, you shall set `timeout` argument to a reasonable value.
+- The tool call will return after the command is finished. You shall not use this tool to execute an interactive command or a command that may run forever. For possibly long-running commands, you shall set `timeout` argument to a reasonable value. The timeout defaults to 60 seconds and can be at most 300 seconds, or 86400 seconds with `run_in_background=true`.
 - Avoid using `..` to access files or directories outside of the working directory.
 - Avoid modifying files outside of the working directory unless explicitly instructed to do so.
 - Never run commands that require superuser privileges unless explicitly instructed to do so.
@@ -242,6 +246,8 @@ Read text content from a file.
 - This tool is a tool that you typically want to use in parallel. Always read multiple files in one response when possible.
 - This tool can only read text files. To read images or videos, use other appropriate tools. To list directories, use the Glob tool or `ls` command via the Shell tool. To read other file types, use appropriate commands via the Shell tool.
 - If the file doesn't exist or path is invalid, an error will be returned.
+- `~`, `~user`, `$VAR` and `${VAR}` in `path` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
+- Files that are not UTF-8 (e.g. Latin-1 or Shift-JIS) are decoded with their detected encoding, which is noted in the message. If the content still looks garbled, set `encoding` explicitly.
 - If you want to search for a certain content/pattern, prefer Grep tool over ReadFile.
 - Content will be returned with a line number before each line like `cat -n` format.
 - Use `line_offset` and `n_lines` parameters when you only need to read a part of the file.
@@ -292,6 +298,13 @@ Find files and directories using glob patterns. This tool supports standard glob
 - Locate configuration files (e.g., `*.config.*`, `*.json`)
 - Find test files (e.g., `test_*.py`, `*_test.go`)
 
+**Ignored paths:**
+- VCS directories such as `.git` are never returned.
+- Inside a git repository, paths excluded by `.gitignore` (e.g. `node_modules`, `target`) are skipped, just like `Grep`. Set `include_ignored` to `true` if you really need to look into them.
+
+**Paths:**
+- `~`, `~user`, `$VAR` and `${VAR}` in `directory` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
+
 **Example patterns:**
 - `*.py` - All Python files in current directory
 - `src/**/*.js` - All JavaScript files in src directory recursively
@@ -324,6 +337,13 @@ On Windows, the `directory` parameter accepts both Windows native paths (`C:\\Us
 - Locate configuration files (e.g., `*.config.*`, `*.json`)
 - Find test files (e.g., `test_*.py`, `*_test.go`)
 
+**Ignored paths:**
+- VCS directories such as `.git` are never returned.
+- Inside a git repository, paths excluded by `.gitignore` (e.g. `node_modules`, `target`) are skipped, just like `Grep`. Set `include_ignored` to `true` if you really need to look into them.
+
+**Paths:**
+- `~`, `~user`, `$VAR` and `${VAR}` in `directory` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
+
 **Example patterns:**
 - `*.py` - All Python files in current directory
 - `src/**/*.js` - All JavaScript files in src directory recursively
@@ -345,8 +365,10 @@ A powerful search tool based-on ripgrep.
 
 **Tips:**
 - ALWAYS use Grep tool instead of running `grep` or `rg` command with Shell tool.
+- `~`, `~user`, `$VAR` and `${VAR}` in `path` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
 - Use the ripgrep pattern syntax, not grep syntax. E.g. you need to escape braces like `\\\\{` to search for `{`.
 - Hidden files (dotfiles like `.gitlab-ci.yml`, `.eslintrc.json`) are always searched. To also search files excluded by `.gitignore` (e.g. `node_modules`, build outputs), set `include_ignored` to `true`. Sensitive files (such as `.env`) are still skipped for safety, even when `include_ignored` is `true`.
+- Binary files (a NUL byte near the start) are skipped in `content` mode and marked `(binary file matches)` in `files_with_matches` mode. Set `binary` to `true` only when you really need to search them as text.
 """
     )
 
@@ -359,6 +381,10 @@ Write content to a file.
 
 **Tips:**
 - When `mode` is not specified, it defaults to `overwrite`. Always write with caution.
+- Read an existing file before overwriting it; overwriting a file you have not read in this session fails unless `force` is true. Appending and creating new files are not affected.
+- Existing files keep their permissions, UTF-8 BOM, and line endings; always use `\\n` line endings in your content, they are converted to CRLF if the file uses CRLF.
+- `~`, `~user`, `$VAR` and `${VAR}` in `path` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
+- A single call can write at most 4194304 bytes.
 - When the content to write is too long (e.g. > 100 lines), use this tool multiple times instead of a single call. Use `overwrite` mode at the first time, then use `append` mode after the first write.
 """
     )
@@ -372,7 +398,10 @@ Replace specific strings within a specified file.
 
 **Tips:**
 - Only use this tool on text files.
+- `~`, `~user`, `$VAR` and `${VAR}` in `path` are expanded before the path is checked, so `~/notes.txt` counts as an absolute path.
 - Multi-line strings are supported.
+- Existing files keep their permissions, UTF-8 BOM, and line endings; always use `\\n` line endings in your content, they are converted to CRLF if the file uses CRLF.
+- The edits in a single call can grow the file by at most 4194304 bytes.
 - Can specify a single edit or a list of edits in one call.
 - You should prefer this tool over WriteFile tool and Shell `sed` command.
 """
@@ -391,3 +420,24 @@ def test_fetch_url_description(fetch_url_tool: FetchURL):
     assert fetch_url_tool.base.description == snapshot(
         "Fetch a web page from a URL and extract main text content from it.\n"
     )
+
+
+def test_file_edit_descriptions_render_configured_write_limit(runtime, approval):
+    """The write limit in edit tool descriptions follows the config."""
+    runtime.config.tools.max_write_bytes = 1024
+
+    for tool in (
+        WriteFile(runtime, approval),
+        StrReplaceFile(runtime, approval),
+        MultiStrReplaceFile(runtime, approval),
+    ):
+        assert "at most 1024 bytes" in tool.base.description
+        assert "${MAX_WRITE_BYTES}" not in tool.base.description
+
+
+def test_load_desc_keeps_unknown_placeholders(tmp_path):
+    """Placeholders without a value in the context are left for the reader to see."""
+    desc_file = tmp_path / "desc.md"
+    desc_file.write_text("Read at most ${MAX_LINES} lines from ${WORK_DIR}.\n")
+
+    assert load_desc(desc_file, {"MAX_LINES": 10}) == "Read at most 10 lines from ${WORK_DIR}.\n"
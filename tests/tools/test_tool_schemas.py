@@ -6,13 +6,21 @@ from inline_snapshot import snapshot
 
 from kimi_cli.tools.agent import Agent as AgentTool
 from kimi_cli.tools.background import TaskList, TaskOutput, TaskStop
+from kimi_cli.tools.context import FindInContext
 from kimi_cli.tools.dmail import SendDMail
+from kimi_cli.tools.environment import GetEnvironment
 from kimi_cli.tools.file.glob import Glob
 from kimi_cli.tools.file.grep_local import Grep
+from kimi_cli.tools.file.multi_replace import MultiStrReplaceFile
+from kimi_cli.tools.file.notebook import NotebookEdit, NotebookRead
+from kimi_cli.tools.file.pdf import ReadPdf
 from kimi_cli.tools.file.read import ReadFile
 from kimi_cli.tools.file.read_media import ReadMediaFile
 from kimi_cli.tools.file.replace import StrReplaceFile
+from kimi_cli.tools.file.table import ReadTable
 from kimi_cli.tools.file.write import WriteFile
+from kimi_cli.tools.git import Git
+from kimi_cli.tools.lsp import FindReferences, GoToDefinition
 from kimi_cli.tools.shell import Shell
 from kimi_cli.tools.think import Think
 from kimi_cli.tools.todo import SetTodoList
@@ -79,6 +87,11 @@ def test_send_dmail_params_schema(send_dmail_tool: SendDMail):
                     "minimum": 0,
                     "type": "integer",
                 },
+                "restore_files": {
+                    "default": False,
+                    "description": "Also undo the changes made by `WriteFile` and `StrReplaceFile` to files in the working directory since the checkpoint. The user is asked to confirm first. Only available when the file journal is enabled.",
+                    "type": "boolean",
+                },
             },
             "required": ["message", "checkpoint_id"],
             "type": "object",
@@ -102,6 +115,37 @@ def test_think_params_schema(think_tool: Think):
     )
 
 
+def test_find_in_context_params_schema(find_in_context_tool: FindInContext):
+    """Test the schema of FindInContext tool parameters."""
+    assert find_in_context_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "pattern": {
+                    "description": "The regular expression to search for.",
+                    "type": "string",
+                },
+                "role": {
+                    "anyOf": [
+                        {"enum": ["user", "assistant", "tool"], "type": "string"},
+                        {"type": "null"},
+                    ],
+                    "default": None,
+                    "description": "Only search messages of this role. Defaults to all roles.",
+                },
+                "limit": {
+                    "default": 20,
+                    "description": "Maximum number of matches to return.",
+                    "maximum": 100,
+                    "minimum": 1,
+                    "type": "integer",
+                },
+            },
+            "required": ["pattern"],
+            "type": "object",
+        }
+    )
+
+
 def test_set_todo_list_params_schema(set_todo_list_tool: SetTodoList):
     """Test the schema of SetTodoList tool parameters."""
     assert set_todo_list_tool.base.parameters == snapshot(
@@ -261,6 +305,11 @@ def test_read_file_params_schema(read_file_tool: ReadFile):
                     "minimum": 1,
                     "type": "integer",
                 },
+                "encoding": {
+                    "anyOf": [{"type": "string"}, {"type": "null"}],
+                    "default": None,
+                    "description": "The text encoding of the file, e.g. `latin-1` or `shift_jis`. By default the file is read as UTF-8, unless it is not valid UTF-8 and its encoding can be detected.",
+                },
             },
             "required": ["path"],
             "type": "object",
@@ -298,11 +347,21 @@ def test_glob_params_schema(glob_tool: Glob):
                     "default": None,
                     "description": "Absolute path to the directory to search in (defaults to working directory).",
                 },
+                "root": {
+                    "anyOf": [{"type": "integer"}, {"type": "string"}, {"type": "null"}],
+                    "default": None,
+                    "description": "The workspace root to search in, given by its index (0 is the working directory, the additional directories follow in the order they are listed) or by its path. When set, `directory` may be relative to this root. Defaults to the working directory.",
+                },
                 "include_dirs": {
                     "default": True,
                     "description": "Whether to include directories in results.",
                     "type": "boolean",
                 },
+                "include_ignored": {
+                    "default": False,
+                    "description": "Include paths that are ignored by `.gitignore`, `.git/info/exclude`, and the global git excludes file. Useful for looking into gitignored artifacts such as build outputs or `node_modules`. VCS directories like `.git` are always skipped. Defaults to false.",
+                    "type": "boolean",
+                },
             },
             "required": ["pattern"],
             "type": "object",
@@ -324,6 +383,11 @@ def test_grep_params_schema(grep_tool: Grep):
                     "description": "File or directory to search in. Defaults to current working directory. If specified, it must be an absolute path.",
                     "type": "string",
                 },
+                "root": {
+                    "anyOf": [{"type": "integer"}, {"type": "string"}, {"type": "null"}],
+                    "default": None,
+                    "description": "The workspace root to search in, given by its index (0 is the working directory, the additional directories follow in the order they are listed) or by its path. When set, `path` may be relative to this root. Defaults to the working directory.",
+                },
                 "glob": {
                     "anyOf": [{"type": "string"}, {"type": "null"}],
                     "default": None,
@@ -385,6 +449,11 @@ def test_grep_params_schema(grep_tool: Grep):
                     "description": "Include files that are ignored by `.gitignore`, `.ignore`, and other ignore rules. Useful for searching gitignored artifacts such as build outputs (e.g. `dist/`, `build/`) or `node_modules`. Sensitive files (like `.env`) remain filtered by the sensitive-file protection layer. Defaults to false.",
                     "type": "boolean",
                 },
+                "binary": {
+                    "default": False,
+                    "description": "Search binary files as if they were text (the `--text` option). By default, files with a NUL byte near the start are treated as binary: they are skipped in `content` mode and marked `(binary file matches)` in `files_with_matches` mode. Defaults to false.",
+                    "type": "boolean",
+                },
             },
             "required": ["pattern"],
             "type": "object",
@@ -411,6 +480,16 @@ def test_write_file_params_schema(write_file_tool: WriteFile):
                     "enum": ["overwrite", "append"],
                     "type": "string",
                 },
+                "force": {
+                    "default": False,
+                    "description": "Overwrite the file even if you have not read it in this session, or it has been modified since you last read or wrote it, e.g. by the user. Only set this when you are sure the current content can be discarded. Defaults to false.",
+                    "type": "boolean",
+                },
+                "dry_run": {
+                    "default": False,
+                    "description": "Only check the edit and show its diff and result, without changing any file or asking for approval. Use it to preview a large change. Defaults to false.",
+                    "type": "boolean",
+                },
             },
             "required": ["path", "content"],
             "type": "object",
@@ -473,6 +552,16 @@ def test_str_replace_file_params_schema(str_replace_file_tool: StrReplaceFile):
                     ],
                     "description": "The edit(s) to apply to the file. You can provide a single edit or a list of edits here.",
                 },
+                "force": {
+                    "default": False,
+                    "description": "Write the file even if it has been modified since you last read or wrote it, e.g. by the user. Only set this after you have reviewed the external changes. Defaults to false.",
+                    "type": "boolean",
+                },
+                "dry_run": {
+                    "default": False,
+                    "description": "Only check the edit and show its diff and result, without changing any file or asking for approval. Use it to preview a large change. Defaults to false.",
+                    "type": "boolean",
+                },
             },
             "required": ["path", "edit"],
             "type": "object",
@@ -480,6 +569,304 @@ def test_str_replace_file_params_schema(str_replace_file_tool: StrReplaceFile):
     )
 
 
+def test_multi_str_replace_file_params_schema(multi_str_replace_file_tool: MultiStrReplaceFile):
+    """Test the schema of MultiStrReplaceFile tool parameters."""
+    assert multi_str_replace_file_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "files": {
+                    "description": "The files to edit and their edits, at most 50 files. Each file may only be listed once.",
+                    "items": {
+                        "properties": {
+                            "path": {
+                                "description": "The path to the file to edit. Absolute paths are required when editing files outside the working directory.",
+                                "type": "string",
+                            },
+                            "edits": {
+                                "description": "The edits to apply to this file, in order.",
+                                "items": {
+                                    "properties": {
+                                        "old": {
+                                            "description": "The old string to replace. Can be multi-line.",
+                                            "type": "string",
+                                        },
+                                        "new": {
+                                            "description": "The new string to replace with. Can be multi-line.",
+                                            "type": "string",
+                                        },
+                                        "replace_all": {
+                                            "default": False,
+                                            "description": "Whether to replace all occurrences.",
+                                            "type": "boolean",
+                                        },
+                                    },
+                                    "required": ["old", "new"],
+                                    "type": "object",
+                                },
+                                "minItems": 1,
+                                "type": "array",
+                            },
+                        },
+                        "required": ["path", "edits"],
+                        "type": "object",
+                    },
+                    "maxItems": 50,
+                    "minItems": 1,
+                    "type": "array",
+                },
+                "force": {
+                    "default": False,
+                    "description": "Write the file even if it has been modified since you last read or wrote it, e.g. by the user. Only set this after you have reviewed the external changes. Defaults to false.",
+                    "type": "boolean",
+                },
+                "dry_run": {
+                    "default": False,
+                    "description": "Only check the edit and show its diff and result, without changing any file or asking for approval. Use it to preview a large change. Defaults to false.",
+                    "type": "boolean",
+                },
+            },
+            "required": ["files"],
+            "type": "object",
+        }
+    )
+
+
+def test_notebook_read_params_schema(notebook_read_tool: NotebookRead):
+    """Test the schema of NotebookRead tool parameters."""
+    assert notebook_read_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the `.ipynb` file to read. Absolute paths are required when reading notebooks outside the working directory.",
+                    "type": "string",
+                },
+            },
+            "required": ["path"],
+            "type": "object",
+        }
+    )
+
+
+def test_notebook_edit_params_schema(notebook_edit_tool: NotebookEdit):
+    """Test the schema of NotebookEdit tool parameters."""
+    assert notebook_edit_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the `.ipynb` file to edit. Absolute paths are required when editing notebooks outside the working directory.",
+                    "type": "string",
+                },
+                "cell_index": {
+                    "description": "The 0-based index of the cell to edit. For `insert`, the new cell is inserted at this index, so use the number of cells to append at the end.",
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "new_source": {
+                    "default": "",
+                    "description": "The new source of the cell. Ignored when `edit_type` is `delete`.",
+                    "type": "string",
+                },
+                "cell_type": {
+                    "anyOf": [
+                        {"enum": ["code", "markdown", "raw"], "type": "string"},
+                        {"type": "null"},
+                    ],
+                    "default": None,
+                    "description": "The type of the cell. Defaults to the current type when replacing, and to `code` when inserting.",
+                },
+                "edit_type": {
+                    "default": "replace",
+                    "description": "`replace` the source of the cell, `insert` a new cell, or `delete` the cell. Defaults to `replace`.",
+                    "enum": ["replace", "insert", "delete"],
+                    "type": "string",
+                },
+                "force": {
+                    "default": False,
+                    "description": "Write the file even if it has been modified since you last read or wrote it, e.g. by the user. Only set this after you have reviewed the external changes. Defaults to false.",
+                    "type": "boolean",
+                },
+                "dry_run": {
+                    "default": False,
+                    "description": "Only check the edit and show its diff and result, without changing any file or asking for approval. Use it to preview a large change. Defaults to false.",
+                    "type": "boolean",
+                },
+            },
+            "required": ["path", "cell_index"],
+            "type": "object",
+        }
+    )
+
+
+def test_read_table_params_schema(read_table_tool: ReadTable):
+    """Test the schema of ReadTable tool parameters."""
+    assert read_table_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the CSV or TSV file to read. Absolute paths are required when reading files outside the working directory.",
+                    "type": "string",
+                },
+                "delimiter": {
+                    "anyOf": [{"maxLength": 1, "minLength": 1, "type": "string"}, {"type": "null"}],
+                    "default": None,
+                    "description": 'The single-character field delimiter, e.g. "," or "\\t". By default it is detected from the file.',
+                },
+                "max_rows": {
+                    "default": 50,
+                    "description": "The number of rows to show, counting the header row. Defaults to 50, at most 500.",
+                    "maximum": 500,
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "max_columns": {
+                    "default": 20,
+                    "description": "The number of columns to show. Defaults to 20, at most 100.",
+                    "maximum": 100,
+                    "minimum": 1,
+                    "type": "integer",
+                },
+            },
+            "required": ["path"],
+            "type": "object",
+        }
+    )
+
+
+def test_read_pdf_params_schema(read_pdf_tool: ReadPdf):
+    """Test the schema of ReadPdf tool parameters."""
+    assert read_pdf_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the PDF file to read. Absolute paths are required when reading files outside the working directory.",
+                    "type": "string",
+                },
+                "first_page": {
+                    "default": 1,
+                    "description": "The 1-based page number to start reading from. Defaults to the first page.",
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "n_pages": {
+                    "anyOf": [{"minimum": 1, "type": "integer"}, {"type": "null"}],
+                    "default": None,
+                    "description": "The number of pages to read. By default read until the end of the document or until 102400 bytes of text have been read.",
+                },
+            },
+            "required": ["path"],
+            "type": "object",
+        }
+    )
+
+
+def test_get_environment_params_schema(get_environment_tool: GetEnvironment):
+    """Test the schema of GetEnvironment tool parameters."""
+    assert get_environment_tool.base.parameters == snapshot({"properties": {}, "type": "object"})
+
+
+def test_git_params_schema(git_tool: Git):
+    """Test the schema of Git tool parameters."""
+    assert git_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "op": {
+                    "description": "The git operation to perform.",
+                    "enum": ["status", "diff", "log", "commit"],
+                    "type": "string",
+                },
+                "staged": {
+                    "default": False,
+                    "description": "For `diff`: show staged changes instead of unstaged ones.",
+                    "type": "boolean",
+                },
+                "paths": {
+                    "description": "For `diff`: only show changes under these paths.",
+                    "items": {"type": "string"},
+                    "type": "array",
+                },
+                "n": {
+                    "default": 10,
+                    "description": "For `log`: the number of commits to show.",
+                    "maximum": 100,
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "message": {
+                    "default": "",
+                    "description": "For `commit`: the commit message. Required when `op` is `commit`.",
+                    "type": "string",
+                },
+                "add_all": {
+                    "default": False,
+                    "description": "For `commit`: stage all changes, including untracked files, before committing.",
+                    "type": "boolean",
+                },
+            },
+            "required": ["op"],
+            "type": "object",
+        }
+    )
+
+
+def test_go_to_definition_params_schema(go_to_definition_tool: GoToDefinition):
+    """Test the schema of GoToDefinition tool parameters."""
+    assert go_to_definition_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the file containing the symbol.",
+                    "type": "string",
+                },
+                "line": {
+                    "description": "The 1-based line number of the symbol.",
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "column": {
+                    "description": "The 1-based column number of the symbol.",
+                    "minimum": 1,
+                    "type": "integer",
+                },
+            },
+            "required": ["path", "line", "column"],
+            "type": "object",
+        }
+    )
+
+
+def test_find_references_params_schema(find_references_tool: FindReferences):
+    """Test the schema of FindReferences tool parameters."""
+    assert find_references_tool.base.parameters == snapshot(
+        {
+            "properties": {
+                "path": {
+                    "description": "The path to the file containing the symbol.",
+                    "type": "string",
+                },
+                "line": {
+                    "description": "The 1-based line number of the symbol.",
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "column": {
+                    "description": "The 1-based column number of the symbol.",
+                    "minimum": 1,
+                    "type": "integer",
+                },
+                "limit": {
+                    "default": 50,
+                    "description": "The maximum number of references to return.",
+                    "maximum": 200,
+                    "minimum": 1,
+                    "type": "integer",
+                },
+            },
+            "required": ["path", "line", "column"],
+            "type": "object",
+        }
+    )
+
+
 def test_search_web_params_schema(search_web_tool: SearchWeb):
     """Test the schema of MoonshotSearch tool parameters."""
     assert search_web_tool.base.parameters == snapshot(
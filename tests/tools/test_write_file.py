@@ -2,14 +2,25 @@
 
 from __future__ import annotations
 
+import asyncio
+import os
+import platform
+import stat
 from pathlib import Path
 
 import pytest
+from kaos import reset_current_kaos, set_current_kaos
+from kaos.local import LocalKaos
 from kaos.path import KaosPath
 from pydantic import ValidationError
 
+from kimi_cli.soul.agent import Runtime
+from kimi_cli.soul.approval import Approval
+from kimi_cli.tools.file import FileActions, ReadFile
+from kimi_cli.tools.file.read import Params as ReadParams
 from kimi_cli.tools.file.write import Params, WriteFile
 from kimi_cli.wire.types import DiffDisplayBlock
+from tests.conftest import tool_call_context
 
 
 async def test_write_new_file(write_file_tool: WriteFile, temp_work_dir: KaosPath):
@@ -30,11 +41,14 @@ async def test_write_new_file(write_file_tool: WriteFile, temp_work_dir: KaosPat
     assert await file_path.read_text() == content
 
 
-async def test_overwrite_existing_file(write_file_tool: WriteFile, temp_work_dir: KaosPath):
-    """Test overwriting an existing file."""
+async def test_overwrite_existing_file(
+    write_file_tool: WriteFile, read_file_tool: ReadFile, temp_work_dir: KaosPath
+):
+    """Test overwriting an existing file after reading it."""
     file_path = temp_work_dir / "existing.txt"
     original_content = "Original content"
     await file_path.write_text(original_content)
+    assert not (await read_file_tool(ReadParams(path=str(file_path)))).is_error
 
     new_content = "New content"
     result = await write_file_tool(Params(path=str(file_path), content=new_content))
@@ -44,6 +58,25 @@ async def test_overwrite_existing_file(write_file_tool: WriteFile, temp_work_dir
     assert await file_path.read_text() == new_content
 
 
+async def test_overwrite_binary_file(write_file_tool: WriteFile, temp_work_dir: KaosPath):
+    """Test overwriting a binary file, which cannot be read as text for the diff."""
+    file_path = temp_work_dir / "data.bin"
+    await file_path.write_bytes(b"\x00\x01\x02")
+
+    result = await write_file_tool(Params(path=str(file_path), content="text", force=True))
+
+    assert not result.is_error
+    diff_block = next(block for block in result.display if block.type == "diff")
+    assert isinstance(diff_block, DiffDisplayBlock)
+    assert diff_block.old_text == ""
+    assert await file_path.read_text() == "text"
+
+    await file_path.write_bytes(b"\x00\x01\x02")
+    result = await write_file_tool(Params(path=str(file_path), content="x", mode="append"))
+    assert result.is_error
+    assert "binary file" in result.message
+
+
 async def test_append_to_file(write_file_tool: WriteFile, temp_work_dir: KaosPath):
     """Test appending to an existing file."""
     file_path = temp_work_dir / "append_test.txt"
@@ -115,6 +148,27 @@ async def test_write_outside_work_directory(write_file_tool: WriteFile, outside_
     assert outside_file.read_text() == "content"
 
 
+async def test_write_outside_work_directory_is_approved_every_time(
+    runtime: Runtime, outside_file: Path
+):
+    """Approving an outside write for the session must not cover later outside writes."""
+    approval = Approval(yolo=False)
+    with tool_call_context("WriteFile"):
+        tool = WriteFile(runtime, approval)
+        for content in ("first", "second"):
+            task = asyncio.create_task(tool(Params(path=str(outside_file), content=content)))
+            while not approval.runtime.list_pending() and not task.done():
+                await asyncio.sleep(0)
+            [request] = approval.runtime.list_pending()
+            assert request.action == FileActions.EDIT_OUTSIDE
+            assert "Outside the working directory:" in request.description
+            assert outside_file.name in request.description
+            approval.runtime.resolve(request.id, "approve_for_session")
+            assert not (await task).is_error
+
+    assert outside_file.read_text() == "second"
+
+
 async def test_write_outside_work_directory_with_prefix(
     write_file_tool: WriteFile, temp_work_dir: KaosPath
 ):
@@ -171,3 +225,149 @@ async def test_write_large_content(write_file_tool: WriteFile, temp_work_dir: Ka
     assert not result.is_error
     assert await file_path.exists()
     assert await file_path.read_text() == content
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="POSIX permission bits")
+async def test_overwrite_keeps_executable_bit(write_file_tool: WriteFile, temp_work_dir: KaosPath):
+    """Overwriting a script keeps its permission bits."""
+    file_path = temp_work_dir / "run.sh"
+    await file_path.write_text("#!/bin/sh\necho hello\n")
+    local_path = file_path.unsafe_to_local_path()
+    local_path.chmod(0o755)
+
+    result = await write_file_tool(
+        Params(path=str(file_path), content="#!/bin/sh\necho bye\n", force=True)
+    )
+
+    assert not result.is_error
+    assert stat.S_IMODE(local_path.stat().st_mode) == 0o755
+
+
+async def test_failed_overwrite_keeps_the_original_file(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    """A write that fails before the new content is renamed into place leaves no trace."""
+
+    class FailingReplaceKaos(LocalKaos):
+        async def _replace(self, src: Path, dst: Path) -> None:
+            raise OSError("disk full")
+
+    file_path = temp_work_dir / "config.txt"
+    await file_path.write_text("original\n")
+    token = set_current_kaos(FailingReplaceKaos())
+    try:
+        result = await write_file_tool(
+            Params(path=str(file_path), content="replaced\n", force=True)
+        )
+    finally:
+        reset_current_kaos(token)
+
+    assert result.is_error
+    assert "disk full" in result.message
+    assert await file_path.read_text() == "original\n"
+    assert os.listdir(temp_work_dir.unsafe_to_local_path()) == ["config.txt"]
+
+
+async def test_overwrite_keeps_crlf_line_endings(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    """Content with bare LF is written with the existing file's CRLF line endings."""
+    file_path = temp_work_dir / "windows.txt"
+    await file_path.write_bytes(b"old 1\r\nold 2\r\n")
+
+    result = await write_file_tool(
+        Params(path=str(file_path), content="new 1\nnew 2\n", force=True)
+    )
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"new 1\r\nnew 2\r\n"
+    assert isinstance(result.display[0], DiffDisplayBlock)
+    assert "\r" not in result.display[0].old_text
+    assert "\r" not in result.display[0].new_text
+
+
+async def test_append_keeps_crlf_line_endings_and_bom(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    """Appended content follows the file's line endings without repeating the BOM."""
+    file_path = temp_work_dir / "windows.txt"
+    await file_path.write_bytes(b"\xef\xbb\xbfline 1\r\n")
+
+    result = await write_file_tool(
+        Params(path=str(file_path), content="line 2\nline 3\n", mode="append")
+    )
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"\xef\xbb\xbfline 1\r\nline 2\r\nline 3\r\n"
+
+
+async def test_overwrite_keeps_bom(write_file_tool: WriteFile, temp_work_dir: KaosPath):
+    """A UTF-8 BOM is kept when the file is overwritten."""
+    file_path = temp_work_dir / "bom.txt"
+    await file_path.write_bytes(b"\xef\xbb\xbfold")
+
+    result = await write_file_tool(Params(path=str(file_path), content="new", force=True))
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"\xef\xbb\xbfnew"
+
+
+async def test_new_file_uses_lf(write_file_tool: WriteFile, temp_work_dir: KaosPath):
+    """New files are written with LF line endings."""
+    file_path = temp_work_dir / "new.txt"
+
+    result = await write_file_tool(Params(path=str(file_path), content="line 1\r\nline 2\r\n"))
+
+    assert not result.is_error
+    assert await file_path.read_bytes() == b"line 1\nline 2\n"
+
+
+async def test_write_over_size_limit_is_rejected(
+    runtime: Runtime, approval: Approval, temp_work_dir: KaosPath
+):
+    """Content just over `max_write_bytes` is not written."""
+    runtime.config.tools.max_write_bytes = 100
+    file_path = temp_work_dir / "big.txt"
+    with tool_call_context("WriteFile"):
+        tool = WriteFile(runtime, approval)
+
+        result = await tool(Params(path=str(file_path), content="a" * 101))
+        assert result.is_error
+        assert result.brief == "File too large"
+        assert not await file_path.exists()
+
+        result = await tool(Params(path=str(file_path), content="a" * 100))
+        assert not result.is_error
+        assert await file_path.read_text() == "a" * 100
+
+
+async def test_overwrite_unread_file_is_rejected(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    """An existing file that was not read in this session is not overwritten without `force`."""
+    file_path = temp_work_dir / "unread.txt"
+    await file_path.write_text("keep me\n")
+
+    result = await write_file_tool(Params(path=str(file_path), content="replaced\n"))
+
+    assert result.is_error
+    assert result.brief == "File not read"
+    assert await file_path.read_text() == "keep me\n"
+
+    result = await write_file_tool(Params(path=str(file_path), content="replaced\n", force=True))
+
+    assert not result.is_error
+    assert await file_path.read_text() == "replaced\n"
+
+
+async def test_append_to_unread_file_is_allowed(
+    write_file_tool: WriteFile, temp_work_dir: KaosPath
+):
+    """Appending does not lose content, so the file does not have to be read first."""
+    file_path = temp_work_dir / "log.txt"
+    await file_path.write_text("line 1\n")
+
+    result = await write_file_tool(Params(path=str(file_path), content="line 2\n", mode="append"))
+
+    assert not result.is_error
+    assert await file_path.read_text() == "line 1\nline 2\n"
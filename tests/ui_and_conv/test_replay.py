@@ -6,6 +6,7 @@ import pytest
 from kosong.message import Message
 
 import kimi_cli.ui.shell.replay as replay_module
+import kimi_cli.wire.file as wire_file_module
 from kimi_cli.soul.message import system_reminder
 from kimi_cli.ui.shell.replay import (
     _build_replay_turns_from_history,
@@ -145,6 +146,39 @@ async def test_build_replay_turns_from_wire_keeps_steer_as_user_turn(tmp_path: P
     assert turns[1].n_steps == 2
 
 
+@pytest.mark.asyncio
+async def test_wire_file_numbers_records_after_existing_ones(tmp_path: Path) -> None:
+    path = tmp_path / "wire.jsonl"
+    path.write_text(
+        '{"type": "metadata", "protocol_version": "1.10"}\n'
+        '{"timestamp": 1.0, "message": {"type": "StepBegin", "payload": {"n": 1}}}\n'
+        '{"timestamp": 2.0, "message": {"type": "StepBegin", "payload": {"n": 2}}}\n'
+    )
+    wire_file = WireFile(path)
+
+    await wire_file.append_message(StepBegin(n=3), timestamp=3.0)
+    await WireFile(path).append_message(StepBegin(n=4), timestamp=4.0)
+
+    records = [record async for record in wire_file.iter_records()]
+    assert [record.seq for record in records] == [None, None, 3, 4]
+    assert [record.message.timestamp for record in records] == [None, None, 3.0, 4.0]
+
+
+@pytest.mark.asyncio
+async def test_wire_file_reads_the_last_seq_from_a_long_last_line(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(wire_file_module, "_TAIL_BLOCK_SIZE", 16)
+    path = tmp_path / "wire.jsonl"
+    await WireFile(path).append_message(StepBegin(n=1), timestamp=1.0)
+    await WireFile(path).append_message(TextPart(text="x" * 100), timestamp=2.0)
+
+    await WireFile(path).append_message(StepBegin(n=2), timestamp=3.0)
+
+    records = [record async for record in WireFile(path).iter_records()]
+    assert [record.seq for record in records] == [1, 2, 3]
+
+
 @pytest.mark.asyncio
 async def test_replay_recent_history_falls_back_to_history_when_wire_misses_steer(
     tmp_path: Path,
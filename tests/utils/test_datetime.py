@@ -1,6 +1,8 @@
 """Tests for datetime utility functions, including format_elapsed."""
 
-from kimi_cli.utils.datetime import format_elapsed
+import pytest
+
+from kimi_cli.utils.datetime import format_elapsed, parse_duration
 
 
 class TestFormatElapsed:
@@ -31,3 +33,18 @@ class TestFormatElapsed:
     def test_hours(self):
         assert format_elapsed(3600.0) == "1h 0m 0s"
         assert format_elapsed(3661.0) == "1h 1m 1s"
+
+
+class TestParseDuration:
+    def test_units(self):
+        assert parse_duration("90s") == 90
+        assert parse_duration("30m") == 1800
+        assert parse_duration("12h") == 12 * 3600
+        assert parse_duration("7d") == 7 * 86400
+        assert parse_duration("2W") == 14 * 86400
+        assert parse_duration("1.5h") == 5400
+
+    def test_invalid(self):
+        for text in ("", "7", "d", "-1d", "7y", "xd"):
+            with pytest.raises(ValueError):
+                parse_duration(text)
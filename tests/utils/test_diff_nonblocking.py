@@ -8,11 +8,13 @@ from unittest.mock import AsyncMock, patch
 from kosong.tooling import DisplayBlock
 
 from kimi_cli.utils.diff import (
+    N_CONTEXT_LINES,
     _HUGE_FILE_THRESHOLD,
+    _TRUNCATED_HUNKS_BUDGET,
     _build_diff_blocks_sync,
     build_diff_blocks,
 )
-from kimi_cli.wire.types import DiffDisplayBlock
+from kimi_cli.wire.types import DiffDisplayBlock, DiffHunk, DiffWordChange
 
 
 def _make_lines(n: int, prefix: str = "Line") -> str:
@@ -45,7 +47,7 @@ async def test_build_diff_blocks_delegates_to_thread() -> None:
         result = await build_diff_blocks("/tmp/test.txt", old, new)
 
     mock_asyncio.to_thread.assert_awaited_once_with(
-        _build_diff_blocks_sync, "/tmp/test.txt", old, new
+        _build_diff_blocks_sync, "/tmp/test.txt", old, new, N_CONTEXT_LINES
     )
     assert result is sentinel
 
@@ -135,6 +137,65 @@ async def test_unchanged_huge_file_returns_empty() -> None:
     assert blocks == [], "Unchanged content must produce no diff blocks"
 
 
+async def test_huge_file_edit_sends_only_the_hunks() -> None:
+    """An edit in one place of a huge file is sent as the hunk around it, with the line
+    counts in place of the texts, keeping the payload far under the budget."""
+    n = 20000
+    old = _make_lines(n)
+    new = old.replace("Line 12345\n", "Line 12345 edited\n")
+
+    [block] = _as_diff(await build_diff_blocks("/tmp/huge_edit.txt", old, new))
+
+    assert (block.is_summary, block.truncated) == (True, True)
+    assert (block.old_text, block.new_text) == (f"({n} lines)", f"({n} lines, modified)")
+    assert block.hunks == [
+        DiffHunk(
+            old_text="\n".join(f"Line {i}" for i in range(12342, 12349)),
+            new_text="\n".join(
+                "Line 12345 edited" if i == 12345 else f"Line {i}" for i in range(12342, 12349)
+            ),
+            old_start=12343,
+            new_start=12343,
+            word_changes=[
+                DiffWordChange(old_line=12346, new_line=12346, old_ranges=[], new_ranges=[(10, 17)])
+            ],
+        )
+    ]
+    assert len(block.model_dump_json()) < _TRUNCATED_HUNKS_BUDGET
+
+
+async def test_huge_file_hunks_stop_at_the_budget() -> None:
+    """Hunks past the budget are left out; a rewritten huge file carries none."""
+    n = _HUGE_FILE_THRESHOLD + 100
+    old = _make_lines(n)
+    lines = old.split("\n")
+    for i in range(0, n // 2, 10):
+        lines[i] = f"Edited {i}"
+    new = "\n".join(lines)
+
+    [block] = _as_diff(await build_diff_blocks("/tmp/huge_edits.txt", old, new))
+    assert block.truncated is True
+    assert block.hunks is not None
+    assert 0 < len(block.hunks) < n // 20
+    assert len(block.hunks) + block.omitted_hunks == len(range(0, n // 2, 10))
+    assert sum(len(h.old_text) + len(h.new_text) for h in block.hunks) <= _TRUNCATED_HUNKS_BUDGET
+
+    [block] = _as_diff(
+        await build_diff_blocks("/tmp/huge_rewrite.txt", old, _make_lines(n, prefix="New"))
+    )
+    assert (block.truncated, block.hunks, block.omitted_hunks) == (True, [], 1)
+
+
+async def test_context_lines_are_configurable() -> None:
+    old = _make_lines(20)
+    new = old.replace("Line 10\n", "Line ten\n")
+
+    [block] = _as_diff(await build_diff_blocks("/tmp/context.txt", old, new, n_context=1))
+
+    assert (block.old_start, block.old_text) == (10, "Line 9\nLine 10\nLine 11")
+    assert block.new_text == "Line 9\nLine ten\nLine 11"
+
+
 # ---------------------------------------------------------------------------
 # Summary block rendering
 # ---------------------------------------------------------------------------
@@ -186,3 +247,23 @@ def test_summary_preview_returns_renderables() -> None:
     # Second line should contain the description
     text = str(renderables[1])
     assert "too large" in text.lower() or "inline diff" in text.lower()
+
+
+def test_truncated_block_renders_its_hunks() -> None:
+    """A truncated block with hunks is rendered as a diff of the hunks, not as a summary."""
+    from kimi_cli.utils.rich.diff_render import DiffLineKind, collect_diff_hunks
+
+    block = DiffDisplayBlock(
+        path="huge.py",
+        old_text="(20000 lines)",
+        new_text="(20000 lines, modified)",
+        is_summary=True,
+        truncated=True,
+        hunks=[DiffHunk(old_text="a\nb", new_text="a\nc", old_start=500, new_start=500)],
+    )
+    hunks, added, removed = collect_diff_hunks([block])
+    assert (added, removed) == (1, 1)
+    assert [(dl.kind, dl.old_num, dl.code) for dl in hunks[0]][:2] == [
+        (DiffLineKind.CONTEXT, 500, "a"),
+        (DiffLineKind.DELETE, 501, "b"),
+    ]
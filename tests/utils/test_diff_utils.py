@@ -8,7 +8,7 @@ from kimi_cli.utils.diff import (
 from kimi_cli.utils.diff import (
     format_unified_diff,
 )
-from kimi_cli.wire.types import DiffDisplayBlock
+from kimi_cli.wire.types import DiffDisplayBlock, DiffWordChange
 
 
 def test_build_diff_blocks_simple_change() -> None:
@@ -49,6 +49,10 @@ Line four
 Line five modified
 Line six added\
 """,
+                word_changes=[
+                    DiffWordChange(old_line=1, new_line=1, old_ranges=[], new_ranges=[(8, 12)]),
+                    DiffWordChange(old_line=5, new_line=5, old_ranges=[], new_ranges=[(9, 18)]),
+                ],
             ),
         ]
     )
@@ -221,6 +225,10 @@ Line nine updated
 Line ten
 Line eleven\
 """,
+                word_changes=[
+                    DiffWordChange(old_line=2, new_line=2, old_ranges=[], new_ranges=[(8, 16)]),
+                    DiffWordChange(old_line=9, new_line=10, old_ranges=[], new_ranges=[(9, 17)]),
+                ],
             ),
         ]
     )
@@ -284,6 +292,9 @@ Line 3
 Line 4
 Line 5\
 """,
+                word_changes=[
+                    DiffWordChange(old_line=2, new_line=2, old_ranges=[], new_ranges=[(6, 14)])
+                ],
             ),
             DiffDisplayBlock(
                 path="/tmp/context.txt",
@@ -305,6 +316,9 @@ Line 16\
 """,
                 old_start=11,
                 new_start=11,
+                word_changes=[
+                    DiffWordChange(old_line=14, new_line=14, old_ranges=[], new_ranges=[(7, 15)])
+                ],
             ),
         ]
     )
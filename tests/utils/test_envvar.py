@@ -0,0 +1,30 @@
+"""Tests for environment variable utilities."""
+
+from __future__ import annotations
+
+import pytest
+
+from kimi_cli.utils.envvar import UnsetEnvVarError, expand_env_vars
+
+
+def test_expand_env_vars():
+    env = {"GITHUB_TOKEN": "ghp_123", "EMPTY": ""}
+
+    assert expand_env_vars("Bearer ${GITHUB_TOKEN}", env) == "Bearer ghp_123"
+    assert expand_env_vars("${HOST:-localhost}:${PORT:-}", env) == "localhost:"
+    assert expand_env_vars("${EMPTY:-fallback}", env) == "fallback"
+    assert expand_env_vars("${EMPTY}", env) == ""
+    # Only the braced form is expanded
+    assert expand_env_vars("$GITHUB_TOKEN ${not a var}", env) == "$GITHUB_TOKEN ${not a var}"
+
+
+def test_expand_env_vars_reads_the_process_environment(monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.setenv("KIMI_TEST_TOKEN", "secret")
+
+    assert expand_env_vars("${KIMI_TEST_TOKEN}") == "secret"
+
+
+def test_expand_env_vars_names_the_unset_variable():
+    with pytest.raises(UnsetEnvVarError, match="`GITHUB_TOKEN` is not set") as exc_info:
+        expand_env_vars("--token=${GITHUB_TOKEN}", {})
+    assert exc_info.value.name == "GITHUB_TOKEN"
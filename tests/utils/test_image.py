@@ -0,0 +1,23 @@
+from __future__ import annotations
+
+import pytest
+
+from kimi_cli.utils.image import parse_data_url, to_data_url
+
+
+def test_parse_data_url_roundtrip():
+    assert parse_data_url(to_data_url("image/png", b"\x89PNG")) == ("image/png", b"\x89PNG")
+    assert parse_data_url("data:Image/PNG;name=a.png;base64,AA==") == ("image/png", b"\x00")
+
+
+def test_parse_data_url_ignores_remote_urls():
+    assert parse_data_url("https://example.com/cat.png") is None
+
+
+@pytest.mark.parametrize(
+    "url",
+    ["data:image/png;base64", "data:image/png,AA==", "data:image/png;base64,%%%"],
+)
+def test_parse_data_url_rejects_malformed_urls(url: str):
+    with pytest.raises(ValueError):
+        parse_data_url(url)
@@ -4,8 +4,8 @@ from __future__ import annotations
 
 from kosong.message import Message
 
-from kimi_cli.utils.message import message_stringify
-from kimi_cli.wire.types import ImageURLPart, TextPart
+from kimi_cli.utils.message import StringifyOptions, message_stringify
+from kimi_cli.wire.types import ImageURLPart, TextPart, ThinkPart
 
 
 def test_extract_text_from_string_content():
@@ -98,6 +98,27 @@ def test_stringify_empty_parts():
     assert result == ""
 
 
+def test_stringify_default_options_keep_tool_results_and_hide_think():
+    """Test that the default options render tool results and a placeholder for thinking."""
+    assistant = Message(role="assistant", content=[ThinkPart(think="Hmm"), TextPart(text="Hi")])
+    tool = Message(role="tool", content=[TextPart(text="output")], tool_call_id="call-1")
+
+    assert message_stringify(assistant) == "[think]Hi"
+    assert message_stringify(tool) == "output"
+
+
+def test_stringify_with_options():
+    """Test rendering thinking, hiding tool results and shortening long parts."""
+    assistant = Message(
+        role="assistant", content=[ThinkPart(think="Let me think"), TextPart(text="a" * 20)]
+    )
+    tool = Message(role="tool", content=[TextPart(text="output")], tool_call_id="call-1")
+    options = StringifyOptions(include_tool_results=False, include_think=True, max_len_per_part=10)
+
+    assert message_stringify(assistant, options) == "Let m...thinkaaaaa...aaaaa"
+    assert message_stringify(tool, options) == "[tool result]"
+
+
 def test_extract_text_from_empty_string():
     """Test extracting text from empty string content."""
     message = Message(role="user", content="")
@@ -0,0 +1,155 @@
+from __future__ import annotations
+
+import asyncio
+import datetime
+import ipaddress
+import ssl
+from collections.abc import AsyncIterator
+from pathlib import Path
+
+import httpx
+import pytest
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.x509.oid import NameOID
+
+import kimi_cli.utils.network as network
+from kimi_cli.config import NetworkConfig
+from kimi_cli.utils.aiohttp import new_client_session
+from kimi_cli.utils.network import (
+    configure_network,
+    new_httpx_client,
+    proxy_for_url,
+    should_bypass_proxy,
+)
+
+_PROXY_ENV_VARS = ("HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY")
+
+
+@pytest.fixture(autouse=True)
+def clean_network(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(network, "_network_config", None)
+    monkeypatch.setattr(network, "_ssl_context", None)
+    for name in _PROXY_ENV_VARS:
+        monkeypatch.delenv(name, raising=False)
+        monkeypatch.delenv(name.lower(), raising=False)
+
+
+@pytest.mark.parametrize(
+    ("host", "port", "no_proxy", "expected"),
+    [
+        ("api.example.com", 443, ["example.com"], True),
+        ("example.com", 443, [".example.com"], True),
+        ("api.example.com", 443, ["*.example.com"], True),
+        ("notexample.com", 443, ["example.com"], False),
+        ("example.com.", 443, ["EXAMPLE.com"], True),
+        ("anything.io", 443, ["*"], True),
+        ("internal", 8080, ["internal:8080"], True),
+        ("internal", 443, ["internal:8080"], False),
+        ("10.1.2.3", 80, ["10.0.0.0/8"], True),
+        ("192.168.1.1", 80, ["10.0.0.0/8", "192.168.1.1"], True),
+        ("[::1]", 80, ["::1"], True),
+        ("::1", 8080, ["[::1]:8080"], True),
+        ("10.1.2.3", 80, ["example.com", ""], False),
+    ],
+)
+def test_should_bypass_proxy(host: str, port: int, no_proxy: list[str], expected: bool) -> None:
+    assert should_bypass_proxy(host, no_proxy, port=port) is expected
+
+
+def test_proxy_for_url_falls_back_to_environment(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("HTTPS_PROXY", "http://env-proxy:3128")
+    monkeypatch.setenv("no_proxy", "localhost,.internal")
+
+    assert proxy_for_url("https://api.moonshot.ai/v1") == "http://env-proxy:3128"
+    assert proxy_for_url("https://git.internal/repo") is None
+    assert proxy_for_url("http://api.moonshot.ai/v1") is None
+
+    configure_network(
+        NetworkConfig(http_proxy="http://proxy:8080", https_proxy="http://proxy:8443", no_proxy=[])
+    )
+
+    assert proxy_for_url("https://git.internal/repo") == "http://proxy:8443"
+    assert proxy_for_url("http://api.moonshot.ai/v1") == "http://proxy:8080"
+
+
+def _write_self_signed_cert(directory: Path) -> tuple[Path, Path]:
+    key = ec.generate_private_key(ec.SECP256R1())
+    name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, "localhost")])
+    now = datetime.datetime.now(datetime.UTC)
+    cert = (
+        x509.CertificateBuilder()
+        .subject_name(name)
+        .issuer_name(name)
+        .public_key(key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(now - datetime.timedelta(days=1))
+        .not_valid_after(now + datetime.timedelta(days=1))
+        .add_extension(
+            x509.SubjectAlternativeName(
+                [x509.DNSName("localhost"), x509.IPAddress(ipaddress.ip_address("127.0.0.1"))]
+            ),
+            critical=False,
+        )
+        .add_extension(x509.BasicConstraints(ca=True, path_length=None), critical=True)
+        .sign(key, hashes.SHA256())
+    )
+    cert_file = directory / "cert.pem"
+    key_file = directory / "key.pem"
+    cert_file.write_bytes(cert.public_bytes(serialization.Encoding.PEM))
+    key_file.write_bytes(
+        key.private_bytes(
+            serialization.Encoding.PEM,
+            serialization.PrivateFormat.PKCS8,
+            serialization.NoEncryption(),
+        )
+    )
+    return cert_file, key_file
+
+
+@pytest.fixture
+async def tls_server(tmp_path: Path) -> AsyncIterator[tuple[str, Path]]:
+    cert_file, key_file = _write_self_signed_cert(tmp_path)
+    context = ssl.create_default_context(ssl.Purpose.CLIENT_AUTH)
+    context.load_cert_chain(cert_file, key_file)
+
+    async def handle(reader: asyncio.StreamReader, writer: asyncio.StreamWriter) -> None:
+        await reader.readuntil(b"\r\n\r\n")
+        writer.write(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+        await writer.drain()
+        writer.close()
+
+    server = await asyncio.start_server(handle, "127.0.0.1", 0, ssl=context)
+    port = server.sockets[0].getsockname()[1]
+    async with server:
+        yield f"https://127.0.0.1:{port}/", cert_file
+
+
+async def test_untrusted_certificate_is_rejected(tls_server: tuple[str, Path]) -> None:
+    url, _ = tls_server
+
+    async with new_httpx_client() as client:
+        with pytest.raises(httpx.ConnectError):
+            await client.get(url)
+
+
+async def test_extra_ca_bundle_is_trusted(tls_server: tuple[str, Path]) -> None:
+    url, cert_file = tls_server
+    configure_network(NetworkConfig(extra_ca_bundle=str(cert_file)))
+
+    async with new_httpx_client() as client:
+        response = await client.get(url)
+        assert response.text == "ok"
+
+    async with new_client_session() as session, session.get(url) as response:
+        assert await response.text() == "ok"
+
+
+async def test_accept_invalid_certs_skips_verification(tls_server: tuple[str, Path]) -> None:
+    url, _ = tls_server
+    configure_network(NetworkConfig(accept_invalid_certs=True))
+
+    async with new_httpx_client() as client:
+        response = await client.get(url)
+        assert response.text == "ok"
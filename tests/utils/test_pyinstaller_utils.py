@@ -146,12 +146,16 @@ def test_pyinstaller_hiddenimports():
     assert sorted(hiddenimports) == snapshot(
         [
             "kimi_cli._build_info",
+            "kimi_cli.cli.config",
             "kimi_cli.cli.export",
             "kimi_cli.cli.info",
             "kimi_cli.cli.mcp",
             "kimi_cli.cli.plugin",
+            "kimi_cli.cli.session",
+            "kimi_cli.cli.stats",
             "kimi_cli.cli.vis",
             "kimi_cli.cli.web",
+            "kimi_cli.cli.wire",
             "kimi_cli.tools",
             "kimi_cli.tools.agent",
             "kimi_cli.tools.ask_user",
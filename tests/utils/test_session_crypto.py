@@ -0,0 +1,191 @@
+from __future__ import annotations
+
+import base64
+import json
+from pathlib import Path
+
+import pytest
+from kosong.message import Message
+
+import kimi_cli.utils.session_crypto as session_crypto
+from kimi_cli.exception import SessionEncryptionError
+from kimi_cli.session import Session
+from kimi_cli.soul.context import Context
+from kimi_cli.utils.session_crypto import (
+    KEY_ENV_VAR,
+    SessionCipher,
+    decode_line,
+    encrypt_session_file,
+    set_session_cipher,
+)
+from kimi_cli.web.api.sessions import _read_wire_lines, extract_first_turn_from_wire
+from kimi_cli.web.store.sessions import _derive_title_from_wire
+from kimi_cli.wire.file import WireFile
+from kimi_cli.wire.types import TextPart, TurnBegin, TurnEnd
+
+
+@pytest.fixture(autouse=True)
+def reset_cipher(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(session_crypto, "_cipher", None)
+    monkeypatch.setattr(session_crypto, "_reading_cipher", None)
+    monkeypatch.delenv(KEY_ENV_VAR, raising=False)
+
+
+@pytest.fixture
+def cipher(monkeypatch: pytest.MonkeyPatch) -> SessionCipher:
+    key = SessionCipher.generate_key()
+    monkeypatch.setenv(KEY_ENV_VAR, base64.b64encode(key).decode())
+    return SessionCipher(key)
+
+
+def test_line_round_trip(cipher: SessionCipher) -> None:
+    text = json.dumps({"role": "user", "content": "héllo"})
+
+    line = cipher.encrypt_line(text)
+
+    assert line.startswith(f"kenc:1:{cipher.key_id}:")
+    assert "héllo" not in line
+    assert cipher.decrypt_line(line) == text
+    assert decode_line(line + "\n") == text
+
+
+def test_tampered_line_is_detected(cipher: SessionCipher) -> None:
+    line = cipher.encrypt_line('{"role": "user", "content": "hello"}')
+    header, payload = line.rsplit(":", 1)
+    data = bytearray(base64.urlsafe_b64decode(payload))
+    data[-1] ^= 1
+    tampered = f"{header}:{base64.urlsafe_b64encode(bytes(data)).decode()}"
+
+    with pytest.raises(SessionEncryptionError, match="failed authentication"):
+        cipher.decrypt_line(tampered)
+
+
+def test_wrong_key_is_reported(cipher: SessionCipher) -> None:
+    other = SessionCipher(SessionCipher.generate_key())
+    line = other.encrypt_line("{}")
+
+    with pytest.raises(SessionEncryptionError, match=f"encrypted with key {other.key_id}"):
+        decode_line(line)
+
+
+def test_missing_key_is_reported(monkeypatch: pytest.MonkeyPatch) -> None:
+    line = SessionCipher(SessionCipher.generate_key()).encrypt_line("{}")
+    monkeypatch.setattr(SessionCipher, "load", classmethod(lambda cls, create=False: None))
+
+    with pytest.raises(SessionEncryptionError, match="no session key is found"):
+        decode_line(line)
+
+
+@pytest.mark.asyncio
+async def test_context_round_trip(tmp_path: Path, cipher: SessionCipher) -> None:
+    path = tmp_path / "context.jsonl"
+    set_session_cipher(cipher)
+    ctx = Context(file_backend=path)
+    await ctx.write_system_prompt("Secret prompt")
+    await ctx.append_message(Message(role="user", content=[TextPart(text="secret question")]))
+
+    raw = path.read_text(encoding="utf-8")
+    assert "secret" not in raw.lower()
+    assert all(line.startswith("kenc:") for line in raw.splitlines())
+
+    set_session_cipher(None)
+    restored = Context(file_backend=path)
+    assert await restored.restore() is True
+    assert restored.system_prompt == "Secret prompt"
+    assert [m.extract_text() for m in restored.history] == ["secret question"]
+
+
+@pytest.mark.asyncio
+async def test_legacy_context_loads_and_mixes_with_encrypted_lines(
+    tmp_path: Path, cipher: SessionCipher
+) -> None:
+    path = tmp_path / "context.jsonl"
+    legacy = Message(role="user", content=[TextPart(text="old")])
+    path.write_text(legacy.model_dump_json(exclude_none=True) + "\n", encoding="utf-8")
+
+    set_session_cipher(cipher)
+    ctx = Context(file_backend=path)
+    assert await ctx.restore() is True
+    await ctx.append_message(Message(role="assistant", content=[TextPart(text="new")]))
+
+    lines = path.read_text(encoding="utf-8").splitlines()
+    assert not lines[0].startswith("kenc:")
+    assert lines[1].startswith("kenc:")
+    restored = Context(file_backend=path)
+    await restored.restore()
+    assert [m.extract_text() for m in restored.history] == ["old", "new"]
+
+
+@pytest.mark.asyncio
+async def test_wire_file_round_trip(tmp_path: Path, cipher: SessionCipher) -> None:
+    set_session_cipher(cipher)
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    await wire_file.append_message(TextPart(text="secret"))
+
+    lines = wire_file.path.read_text(encoding="utf-8").splitlines()
+    assert not lines[0].startswith("kenc:")
+    assert lines[1].startswith("kenc:")
+    records = [r async for r in WireFile(path=wire_file.path).iter_records()]
+    assert [r.to_wire_message() for r in records] == [TextPart(text="secret")]
+
+
+@pytest.mark.asyncio
+async def test_encrypt_session_file_in_place(tmp_path: Path, cipher: SessionCipher) -> None:
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    await wire_file.append_message(TextPart(text="a"))
+    await wire_file.append_message(TextPart(text="b"))
+
+    assert await encrypt_session_file(wire_file.path, cipher) == 2
+    assert await encrypt_session_file(wire_file.path, cipher) == 0
+
+    lines = wire_file.path.read_text(encoding="utf-8").splitlines()
+    assert [line.startswith("kenc:") for line in lines] == [False, True, True]
+    records = [r async for r in WireFile(path=wire_file.path).iter_records()]
+    assert [r.to_wire_message() for r in records] == [TextPart(text="a"), TextPart(text="b")]
+
+
+@pytest.mark.asyncio
+async def test_session_summary_reads_encrypted_context(
+    session: Session, cipher: SessionCipher
+) -> None:
+    set_session_cipher(cipher)
+    ctx = Context(file_backend=session.context_file)
+    await ctx.write_system_prompt("Secret prompt")
+    await ctx.append_message(Message(role="user", content=[TextPart(text="secret question")]))
+    set_session_cipher(None)
+
+    assert not session.is_empty()
+    assert session.message_count() == 1
+
+
+@pytest.mark.asyncio
+async def test_web_readers_decrypt_wire_file(tmp_path: Path, cipher: SessionCipher) -> None:
+    set_session_cipher(cipher)
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    await wire_file.append_message(TurnBegin(user_input="secret question"))
+    await wire_file.append_message(TextPart(text="secret answer"))
+    await wire_file.append_message(TurnEnd())
+    set_session_cipher(None)
+
+    assert _derive_title_from_wire(tmp_path) == "secret question"
+    assert extract_first_turn_from_wire(tmp_path) == ("secret question", "secret answer")
+    events = [json.loads(line) for line in _read_wire_lines(wire_file.path)]
+    assert [event["params"]["type"] for event in events] == ["TurnBegin", "ContentPart", "TurnEnd"]
+
+
+@pytest.mark.asyncio
+async def test_wire_file_appends_after_undecryptable_records(
+    tmp_path: Path, cipher: SessionCipher, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    set_session_cipher(cipher)
+    wire_file = WireFile(path=tmp_path / "wire.jsonl")
+    await wire_file.append_message(TextPart(text="a"))
+    await wire_file.append_message(TextPart(text="b"))
+    set_session_cipher(None)
+    monkeypatch.delenv(KEY_ENV_VAR)
+    monkeypatch.setattr(SessionCipher, "load", classmethod(lambda cls, create=False: None))
+
+    await WireFile(path=wire_file.path).append_message(TextPart(text="c"))
+
+    last_line = wire_file.path.read_text(encoding="utf-8").splitlines()[-1]
+    assert json.loads(last_line)["message"]["seq"] == 3
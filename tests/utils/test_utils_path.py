@@ -3,11 +3,18 @@
 from __future__ import annotations
 
 import asyncio
+import os
+import platform
 from pathlib import Path
 
 import pytest
 
-from kimi_cli.utils.path import next_available_rotation, sanitize_cli_path
+from kimi_cli.utils.path import (
+    expand_user_path,
+    kaos_path_from_user_input,
+    next_available_rotation,
+    sanitize_cli_path,
+)
 
 
 async def test_next_available_rotation_empty_dir(tmp_path):
@@ -253,3 +260,33 @@ async def test_next_available_rotation_concurrent_calls(tmp_path):
 )
 def test_sanitize_cli_path(raw: str, expected: str):
     assert sanitize_cli_path(raw) == expected
+
+
+def test_expand_user_path_env_vars(monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.setenv("KIMI_TEST_DIR", "/srv/project")
+    monkeypatch.delenv("KIMI_TEST_UNSET", raising=False)
+
+    assert expand_user_path("$KIMI_TEST_DIR/src") == "/srv/project/src"
+    assert expand_user_path("${KIMI_TEST_DIR}/src") == "/srv/project/src"
+    assert expand_user_path("$KIMI_TEST_UNSET/src") == "$KIMI_TEST_UNSET/src"
+
+
+def test_expand_user_path_leaves_bare_tilde_to_kaos():
+    assert expand_user_path("~") == "~"
+    assert expand_user_path("~/notes.txt") == "~/notes.txt"
+
+
+@pytest.mark.skipif(platform.system() == "Windows", reason="~user is not expanded on Windows")
+def test_expand_user_path_other_user():
+    import pwd
+
+    user = pwd.getpwuid(os.getuid())
+    assert expand_user_path(f"~{user.pw_name}/notes.txt") == f"{user.pw_dir}/notes.txt"
+    assert expand_user_path("~no-such-user-kimi/notes.txt") == "~no-such-user-kimi/notes.txt"
+
+
+def test_kaos_path_from_user_input_is_absolute_after_expansion(monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.setenv("KIMI_TEST_DIR", str(Path.home()))
+
+    assert kaos_path_from_user_input("~/notes.txt").is_absolute()
+    assert kaos_path_from_user_input("$KIMI_TEST_DIR/notes.txt").is_absolute()
@@ -1,10 +1,14 @@
 from __future__ import annotations
 
+import base64
+import json
 from pathlib import Path
 
 from fastapi.testclient import TestClient
 
+import kimi_cli.utils.session_crypto as session_crypto
 from kimi_cli.metadata import Metadata, WorkDirMeta, save_metadata
+from kimi_cli.utils.session_crypto import KEY_ENV_VAR, SessionCipher
 from kimi_cli.vis.api import system as vis_system_api
 from kimi_cli.vis.app import create_app
 
@@ -53,3 +57,37 @@ def test_vis_capabilities_report_open_in_support(monkeypatch) -> None:
 
     assert response.status_code == 200
     assert response.json() == {"open_in_supported": False}
+
+
+def test_vis_reads_encrypted_session_context(monkeypatch, tmp_path: Path) -> None:
+    monkeypatch.setenv("KIMI_SHARE_DIR", str(tmp_path))
+    monkeypatch.setattr(session_crypto, "_cipher", None)
+    monkeypatch.setattr(session_crypto, "_reading_cipher", None)
+    key = SessionCipher.generate_key()
+    monkeypatch.setenv(KEY_ENV_VAR, base64.b64encode(key).decode())
+    cipher = SessionCipher(key)
+
+    work_dir = tmp_path / "project"
+    work_dir.mkdir()
+    metadata = Metadata(work_dirs=[WorkDirMeta(path=str(work_dir))])
+    save_metadata(metadata)
+    session_dir = metadata.work_dirs[0].sessions_dir / "session123"
+    session_dir.mkdir(parents=True)
+    message = {"role": "user", "content": "hello"}
+    (session_dir / "context.jsonl").write_text(
+        cipher.encrypt_line(json.dumps(message)) + "\n", encoding="utf-8"
+    )
+    hash_dir = session_dir.parent.name
+
+    with TestClient(create_app()) as client:
+        response = client.get(f"/api/vis/sessions/{hash_dir}/session123/context")
+        assert response.status_code == 200
+        assert response.json() == {"total": 1, "messages": [{**message, "index": 0}]}
+
+        monkeypatch.setattr(session_crypto, "_reading_cipher", None)
+        monkeypatch.delenv(KEY_ENV_VAR)
+        monkeypatch.setattr(SessionCipher, "load", classmethod(lambda cls, create=False: None))
+        response = client.get(f"/api/vis/sessions/{hash_dir}/session123/context")
+
+    assert response.status_code == 500
+    assert "no session key is found" in response.json()["detail"]
@@ -27,7 +27,8 @@ def _find_event(messages: list[dict[str, Any]], event_type: str) -> dict[str, An
             continue
         params = msg.get("params")
         if isinstance(params, dict) and params.get("type") == event_type:
-            return params
+            # `seq` depends on the events sent before, e.g. during initialize
+            return {key: value for key, value in params.items() if key not in ("seq", "timestamp")}
     raise AssertionError(f"Missing event {event_type}")
 
 
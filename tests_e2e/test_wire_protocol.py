@@ -36,14 +36,19 @@ def test_initialize_handshake(tmp_path) -> None:
     try:
         resp = send_initialize(wire)
         result = _as_dict(resp.get("result"))
-        assert result.get("protocol_version") == "1.10"
+        assert result.get("protocol_version") == "1.11"
         assert "slash_commands" in result
         assert normalize_response(resp) == snapshot(
             {
                 "result": {
-                    "protocol_version": "1.10",
+                    "protocol_version": "1.11",
                     "server": {"name": "Kimi Code CLI", "version": "<VERSION>"},
                     "slash_commands": [
+                        {
+                            "name": "help",
+                            "description": "List available slash commands and skills",
+                            "aliases": ["h", "?"],
+                        },
                         {
                             "name": "init",
                             "description": "Analyze the codebase and generate an `AGENTS.md` file",
@@ -55,6 +60,11 @@ def test_initialize_handshake(tmp_path) -> None:
                             "aliases": [],
                         },
                         {"name": "clear", "description": "Clear the context", "aliases": ["reset"]},
+                        {
+                            "name": "cost",
+                            "description": "Show token usage and estimated cost of the current turn and the session",
+                            "aliases": [],
+                        },
                         {
                             "name": "yolo",
                             "description": "Toggle YOLO mode (auto-approve all actions)",
@@ -65,6 +75,11 @@ def test_initialize_handshake(tmp_path) -> None:
                             "description": "Toggle afk mode (auto-dismiss AskUserQuestion, auto-approve tool calls)",
                             "aliases": [],
                         },
+                        {
+                            "name": "dryrun",
+                            "description": "Toggle dry-run mode (file edits and commands only report what they would do)",
+                            "aliases": [],
+                        },
                         {
                             "name": "plan",
                             "description": "Toggle plan mode. Usage: /plan [on|off|view|clear]",
@@ -151,9 +166,14 @@ def test_initialize_external_tool_conflict(tmp_path) -> None:
         assert normalize_response(resp) == snapshot(
             {
                 "result": {
-                    "protocol_version": "1.10",
+                    "protocol_version": "1.11",
                     "server": {"name": "Kimi Code CLI", "version": "<VERSION>"},
                     "slash_commands": [
+                        {
+                            "name": "help",
+                            "description": "List available slash commands and skills",
+                            "aliases": ["h", "?"],
+                        },
                         {
                             "name": "init",
                             "description": "Analyze the codebase and generate an `AGENTS.md` file",
@@ -165,6 +185,11 @@ def test_initialize_external_tool_conflict(tmp_path) -> None:
                             "aliases": [],
                         },
                         {"name": "clear", "description": "Clear the context", "aliases": ["reset"]},
+                        {
+                            "name": "cost",
+                            "description": "Show token usage and estimated cost of the current turn and the session",
+                            "aliases": [],
+                        },
                         {
                             "name": "yolo",
                             "description": "Toggle YOLO mode (auto-approve all actions)",
@@ -175,6 +200,11 @@ def test_initialize_external_tool_conflict(tmp_path) -> None:
                             "description": "Toggle afk mode (auto-dismiss AskUserQuestion, auto-approve tool calls)",
                             "aliases": [],
                         },
+                        {
+                            "name": "dryrun",
+                            "description": "Toggle dry-run mode (file edits and commands only report what they would do)",
+                            "aliases": [],
+                        },
                         {
                             "name": "plan",
                             "description": "Toggle plan mode. Usage: /plan [on|off|view|clear]",
@@ -294,7 +294,7 @@ def send_initialize(
     external_tools: list[dict[str, Any]] | None = None,
     capabilities: dict[str, Any] | None = None,
 ) -> dict[str, Any]:
-    params: dict[str, Any] = {"protocol_version": "1.1"}
+    params: dict[str, Any] = {"protocol_version": "1.11"}
     if external_tools:
         params["external_tools"] = external_tools
     if capabilities is not None:
@@ -472,10 +472,26 @@ def summarize_messages(
         if method not in {"event", "request"}:
             continue
         params = msg.get("params", {})
+        msg_type = params.get("type")
+        payload = params.get("payload")
+        if isinstance(payload, dict):
+            if msg_type == "StatusUpdate" and payload.get("phase") is not None:
+                # Step phases and their timing depend on scheduling, not on the script
+                continue
+            if msg_type == "StepBegin":
+                payload = {k: v for k, v in payload.items() if k != "timestamp"}
+            if msg_type == "TurnEnd":
+                payload = {k: v for k, v in payload.items() if v is not None}
+            if msg_type == "StatusUpdate":
+                payload = {
+                    k: v
+                    for k, v in payload.items()
+                    if k not in {"phase", "attempt", "elapsed_ms"}
+                }
         entry = {
             "method": method,
-            "type": params.get("type"),
-            "payload": normalize_value(params.get("payload"), replacements=replacements),
+            "type": msg_type,
+            "payload": normalize_value(payload, replacements=replacements),
         }
         summary.append(entry)
     return _normalize_message_order(summary)
@@ -154,7 +154,7 @@ const NEWLINE_REGEX = /\r?\n/;
 // Match <image path="..."> or <video path="..."> tags (path attribute only, no content_type required)
 const MEDIA_TAG_PATH_REGEX = /<(?:image|video)\s+[^>]*path="([^"]*\/([0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12})\/uploads\/([^"]+))"/g;
 const BROWSER_URL_PROTOCOLS = new Set(["http:", "https:", "data:", "blob:"]);
-const WIRE_PROTOCOL_VERSION = "1.10";
+const WIRE_PROTOCOL_VERSION = "1.11";
 
 type StepRetryPayload = StepRetryEvent["payload"];
 
@@ -411,6 +411,8 @@ export function useSessionStream(
 
   // Track compaction indicator message so we can remove it on CompactionEnd
   const compactionMessageIdRef = useRef<string | null>(null);
+  // The summary streamed by the current compaction attempt, shown in the indicator
+  const compactionSummaryRef = useRef({ attempt: 0, text: "" });
 
   // Track MCP loading indicator message so we can remove it on MCPLoadingEnd
   const mcpLoadingMessageIdRef = useRef<string | null>(null);
@@ -2046,6 +2048,7 @@ export function useSessionStream(
         case "CompactionBegin": {
           const compactionMsgId = getNextMessageId("assistant");
           compactionMessageIdRef.current = compactionMsgId;
+          compactionSummaryRef.current = { attempt: 0, text: "" };
           setMessages((prev) => [
             ...prev,
             {
@@ -2059,6 +2062,25 @@ export function useSessionStream(
           break;
         }
 
+        case "CompactionProgress": {
+          const compactMsgId = compactionMessageIdRef.current;
+          if (!compactMsgId || event.payload.think) {
+            break;
+          }
+          const attempt = event.payload.attempt ?? 1;
+          const summary = compactionSummaryRef.current;
+          if (summary.attempt !== attempt) {
+            summary.attempt = attempt;
+            summary.text = "";
+          }
+          summary.text += event.payload.text;
+          const content = `Compacting conversation history…\n\n${summary.text}`;
+          setMessages((prev) =>
+            prev.map((msg) => (msg.id === compactMsgId ? { ...msg, content } : msg)),
+          );
+          break;
+        }
+
         case "CompactionEnd": {
           const compactMsgId = compactionMessageIdRef.current;
           compactionMessageIdRef.current = null;
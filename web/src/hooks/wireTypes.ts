@@ -141,6 +141,8 @@ export type StatusUpdateEvent = {
   payload: {
     context_usage: number | null;
     token_usage?: TokenUsage | null;
+    session_usage?: TokenUsage | null;
+    session_compaction_usage?: TokenUsage | null;
     message_id?: string;
     plan_mode?: boolean | null;
   };
@@ -161,9 +163,23 @@ export type CompactionBeginEvent = {
   payload?: Record<string, never>;
 };
 
+export type CompactionProgressEvent = {
+  type: "CompactionProgress";
+  payload: {
+    text: string;
+    think?: boolean;
+    attempt?: number;
+  };
+};
+
 export type CompactionEndEvent = {
   type: "CompactionEnd";
-  payload?: Record<string, never>;
+  payload?: {
+    marker_id?: string | null;
+    n_summarized?: number | null;
+    before_tokens?: number | null;
+    after_tokens?: number | null;
+  };
 };
 
 export type MCPLoadingBeginEvent = {
@@ -244,10 +260,28 @@ export type SubagentEventWire = {
     parent_tool_call_id?: string | null;
     agent_id?: string | null;
     subagent_type?: string | null;
+    /** Subagent levels between the receiver and the producer of `event`. Added in Wire 1.11 */
+    depth?: number;
     event: { type: string; payload: unknown };
   };
 };
 
+/**
+ * Sent when a foreground subagent finishes, with its final message and token usage.
+ * Added in Wire 1.11.
+ */
+export type SubagentEndEvent = {
+  type: "SubagentEnd";
+  payload: {
+    parent_tool_call_id?: string | null;
+    agent_id?: string | null;
+    subagent_type?: string | null;
+    status: "completed" | "failed" | "cancelled";
+    final_message?: string | null;
+    usage?: TokenUsage | null;
+  };
+};
+
 export type SteerInputEvent = {
   type: "SteerInput";
   payload: {
@@ -276,6 +310,7 @@ export type WireEvent =
   | StatusUpdateEvent
   | SessionNoticeEvent
   | CompactionBeginEvent
+  | CompactionProgressEvent
   | CompactionEndEvent
   | MCPLoadingBeginEvent
   | MCPLoadingEndEvent
@@ -283,6 +318,7 @@ export type WireEvent =
   | ApprovalRequestResolvedEvent
   | QuestionRequestEvent
   | SubagentEventWire
+  | SubagentEndEvent
   | SteerInputEvent
   | PlanDisplayEvent;
 